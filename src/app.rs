@@ -0,0 +1,71 @@
+use crate::{
+    components::AppSettings,
+    pages::{
+        about::About, compare::Compare, examples::Examples, home::Home, not_found::NotFound,
+        open::Open, settings::Settings,
+    },
+    utils::i18n::{Message, locale, tr},
+};
+use leptos::prelude::*;
+use leptos_meta::*;
+use leptos_router::{components::*, path};
+
+/// An app router which renders the homepage and handles 404's
+#[component]
+pub fn App() -> impl IntoView {
+    provide_meta_context();
+    let settings = AppSettings::provide();
+    let theme = move || settings.with(|settings| settings.theme.name());
+
+    view! {
+        <Html attr:lang=locale().tag() attr:dir="ltr" attr:data-theme=theme />
+        <Meta charset="UTF-8" />
+        <Meta name="viewport" content="width=device-width, initial-scale=1.0" />
+        <Title text="HLS Manifest Viewer" />
+        <Link rel="icon" href="/hls-manifest-viewer/quick-m3u8-logo.ico" attr::type="image/ico" />
+        <Link
+            rel="mask-icon"
+            href="/hls-manifest-viewer/quick-m3u8-logo.ico"
+            attr::color="#000000"
+        />
+
+        <Router>
+            <nav>
+                <a class="button" href="/hls-manifest-viewer">
+                    {tr(Message::NavHome)}
+                </a>
+                <a class="button" href="/hls-manifest-viewer/compare">
+                    {tr(Message::NavCompare)}
+                </a>
+                <a class="button" href="/hls-manifest-viewer/examples">
+                    {tr(Message::NavExamples)}
+                </a>
+                <a class="button" href="/hls-manifest-viewer/about">
+                    {tr(Message::NavAbout)}
+                </a>
+                <a class="button" href="/hls-manifest-viewer/settings">
+                    {tr(Message::NavSettings)}
+                </a>
+                <a
+                    class="button"
+                    href="https://github.com/theRealRobG/hls-manifest-viewer"
+                    target="_blank"
+                >
+                    <img src="/hls-manifest-viewer/github-mark-white.svg" />
+                </a>
+            </nav>
+            <main>
+                <Routes fallback=NotFound>
+                    <Route path=path!("/hls-manifest-viewer") view=Home />
+                    <Route path=path!("/hls-manifest-viewer/compare") view=Compare />
+                    <Route path=path!("/hls-manifest-viewer/examples") view=Examples />
+                    <Route path=path!("/hls-manifest-viewer/about") view=About />
+                    <Route path=path!("/hls-manifest-viewer/settings") view=Settings />
+                    // The playlist URL to open may be given in the path, as well as in the query.
+                    <Route path=path!("/hls-manifest-viewer/open") view=Open />
+                    <Route path=path!("/hls-manifest-viewer/open/*target") view=Open />
+                </Routes>
+            </main>
+        </Router>
+    }
+}
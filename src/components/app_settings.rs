@@ -0,0 +1,40 @@
+use crate::utils::settings::{Settings, load_settings, save_settings};
+use leptos::prelude::*;
+
+/// The settings of the app, which the app provides as context so that a change made to them (on
+/// the settings page, or in the settings shown on the home page) applies straight away to the
+/// views that depend on them. Changes are saved as they are made.
+#[derive(Clone, Copy)]
+pub struct AppSettings(RwSignal<Settings>);
+
+impl AppSettings {
+    pub fn provide() -> Self {
+        let settings = Self(RwSignal::new(load_settings()));
+        provide_context(settings);
+        settings
+    }
+
+    /// The settings provided by the app, or else those saved, for a component shown outside of it.
+    pub fn current() -> Self {
+        use_context().unwrap_or_else(|| Self(RwSignal::new(load_settings())))
+    }
+
+    /// The settings as they are now, such as for a request that is about to be made (which is not
+    /// made again when the settings change).
+    pub fn get_untracked(&self) -> Settings {
+        self.0.get_untracked()
+    }
+
+    pub fn with<T>(&self, read: impl FnOnce(&Settings) -> T) -> T {
+        self.0.with(read)
+    }
+
+    pub fn with_untracked<T>(&self, read: impl FnOnce(&Settings) -> T) -> T {
+        self.0.with_untracked(read)
+    }
+
+    pub fn update(&self, change: impl FnOnce(&mut Settings)) {
+        self.0.update(change);
+        self.0.with_untracked(save_settings);
+    }
+}
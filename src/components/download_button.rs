@@ -0,0 +1,24 @@
+use crate::utils::{
+    download::download_bytes,
+    i18n::{Message, tr, tr_with},
+};
+use leptos::prelude::*;
+
+// Takes a closure to get the bytes so to only clone when the button is clicked.
+#[component]
+pub fn DownloadButton<F>(file_name: String, mime_type: &'static str, contents: F) -> impl IntoView
+where
+    F: Fn() -> Vec<u8> + Send + Sync + 'static,
+{
+    let title = tr_with(Message::DownloadTitle, &[("file_name", &file_name)]);
+    view! {
+        <button
+            class="button download-button"
+            type="button"
+            title=title
+            on:click=move |_| download_bytes(&file_name, mime_type, &contents())
+        >
+            {tr(Message::Download)}
+        </button>
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{
+    components::{AppSettings, LiveSnapshot},
+    utils::{
+        embed::{
+            EmbedCommand, error_event, loaded_event, origin_of, parse_command, ready_event,
+            validation_event,
+        },
+        href::{
+            PLAYLIST_URL_QUERY_NAME, SEGMENT_QUERY_NAME, SUPPLEMENTAL_VIEW_QUERY_NAME,
+            replace_query_value,
+        },
+        network::{FetchError, FetchTextResponse},
+        query_codec::percent_encode,
+        validation::validate,
+    },
+};
+use leptos::{ev, prelude::*};
+use leptos_router::{
+    NavigateOptions,
+    hooks::{use_navigate, use_url},
+};
+use wasm_bindgen::JsValue;
+use web_sys::{MessageEvent, Window, js_sys::JSON};
+
+/// Lets the page that the viewer is embedded in (in an iframe) drive it with `postMessage`, and
+/// posts back what the viewer loaded and found, as set out in `utils::embed`. Commands are taken
+/// from the parent window alone, and events are posted to the origin of the last command (or,
+/// before there is one, to that of the page that embedded the viewer). Outside of an iframe this
+/// does nothing.
+#[component]
+pub fn EmbedBridge(
+    playlist_url: Memo<Option<String>>,
+    playlist_result: LocalResource<Result<FetchTextResponse, FetchError>>,
+    live_snapshot: LiveSnapshot,
+) -> impl IntoView {
+    let Some(parent) = embedding_window() else {
+        return;
+    };
+    let target_origin = StoredValue::new(origin_of(&document().referrer()));
+    let post = {
+        let parent = parent.clone();
+        move |event: String| {
+            let Some(origin) = target_origin.get_value() else {
+                return;
+            };
+            let message = JSON::parse(&event).unwrap_or_else(|_| JsValue::from_str(&event));
+            if let Err(e) = parent.post_message(&message, &origin) {
+                log::error!("posting to the embedding page failed due to {e:?}");
+            }
+        }
+    };
+    let navigate = use_navigate();
+    let url = use_url();
+    let handle = window_event_listener(ev::message, {
+        let post = post.clone();
+        move |event: MessageEvent| {
+            let from_parent = event
+                .source()
+                .is_some_and(|source| JsValue::from(source) == JsValue::from(parent.clone()));
+            if !from_parent {
+                return;
+            }
+            target_origin.set_value(Some(event.origin()));
+            let data = event.data();
+            let message = data
+                .as_string()
+                .unwrap_or_else(|| JSON::stringify(&data).map(String::from).unwrap_or_default());
+            let command = match parse_command(&message) {
+                Ok(command) => command,
+                Err(e) => {
+                    post(error_event(&e));
+                    return;
+                }
+            };
+            match command {
+                EmbedCommand::Load { url } => {
+                    let href = format!("?{PLAYLIST_URL_QUERY_NAME}={}", percent_encode(&url));
+                    navigate(&href, NavigateOptions::default());
+                }
+                EmbedCommand::SetHeaders { headers } => {
+                    AppSettings::current().update(|settings| settings.request_headers = headers);
+                }
+                // The segment is opened from the playlist, as for a link asking for it, so any
+                // segment already open is closed first.
+                EmbedCommand::OpenSegment { segment } => {
+                    let search = url.with_untracked(|url| url.search().to_string());
+                    let search = replace_query_value(&search, SUPPLEMENTAL_VIEW_QUERY_NAME, None);
+                    let href = replace_query_value(
+                        search.trim_start_matches('?'),
+                        SEGMENT_QUERY_NAME,
+                        Some(&segment.to_string()),
+                    );
+                    let options = NavigateOptions {
+                        replace: true,
+                        ..Default::default()
+                    };
+                    navigate(&href, options);
+                }
+            }
+        }
+    });
+    on_cleanup(move || handle.remove());
+    post(ready_event());
+    let post_loaded = move |result: Result<FetchTextResponse, FetchError>| {
+        // A local file has no playlist URL, and is given by its name.
+        let url = playlist_url.get_untracked();
+        match result {
+            Ok(response) => {
+                let url = url.unwrap_or_else(|| response.url.clone());
+                // Nothing is loaded until a playlist is entered.
+                if url.is_empty() {
+                    return;
+                }
+                let status = response.headers.status;
+                post(loaded_event(&url, Ok((&response.url, status))));
+                post(validation_event(&url, &validate(&response.response_text)));
+            }
+            Err(e) => post(loaded_event(&url.unwrap_or_default(), Err(&e.to_string()))),
+        }
+    };
+    Effect::new({
+        let post_loaded = post_loaded.clone();
+        move |_| {
+            if let Some(result) = playlist_result.get() {
+                post_loaded(result);
+            }
+        }
+    });
+    // A live playlist that is rechecked is posted as loaded again, as it was reloaded in place.
+    Effect::new(move |_| {
+        if let Some(result) = live_snapshot.rechecked() {
+            post_loaded(result);
+        }
+    });
+}
+
+// The window of the page that the viewer is embedded in, if it is.
+fn embedding_window() -> Option<Window> {
+    let window = window();
+    window
+        .parent()
+        .ok()
+        .flatten()
+        .filter(|parent| *parent != window)
+}
@@ -0,0 +1,222 @@
+use crate::utils::{
+    fetch_timing::FetchTiming,
+    href::PLAYLIST_URL_QUERY_NAME,
+    i18n::{Message, tr, tr_with},
+    network::{FetchTextResponse, ResponseHeaders},
+    query_codec::percent_encode,
+    response::probe_is_playlist,
+};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::{NavigateOptions, hooks::use_navigate};
+use url::Url;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{DragEvent, File, HtmlInputElement, js_sys::Uint8Array};
+
+const HOME_PATH: &str = "/hls-manifest-viewer";
+
+/// A playlist opened from a local file (or pasted in), which Home shows in place of fetching the
+/// playlist in the query. The query then holds the base URL that was given for the file, if any, so
+/// that relative URIs resolve against it; without one they are not linked.
+#[derive(Clone, Copy, Default)]
+pub struct LocalPlaylist(RwSignal<Option<LocalPlaylistFile>>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalPlaylistFile {
+    pub name: String,
+    pub text: String,
+    pub base_url: Option<String>,
+}
+
+impl LocalPlaylist {
+    pub fn get(&self) -> Option<LocalPlaylistFile> {
+        self.0.get()
+    }
+
+    pub fn get_untracked(&self) -> Option<LocalPlaylistFile> {
+        self.0.get_untracked()
+    }
+
+    /// Shows the file, navigating to its base URL (or to no playlist at all).
+    pub fn show(&self, file: LocalPlaylistFile, navigate: impl Fn(&str, NavigateOptions)) {
+        let href = match &file.base_url {
+            Some(base_url) => format!(
+                "{HOME_PATH}?{PLAYLIST_URL_QUERY_NAME}={}",
+                percent_encode(base_url)
+            ),
+            None => String::from(HOME_PATH),
+        };
+        self.0.set(Some(file));
+        navigate(&href, NavigateOptions::default());
+    }
+
+    /// Closes the file once the query moves on to a playlist other than its base URL (e.g. when a
+    /// URL is entered in the form).
+    pub fn retain_for(&self, playlist_url: Option<&str>) {
+        let moved_on = self.0.with_untracked(|file| {
+            file.as_ref()
+                .is_some_and(|file| file.base_url.as_deref() != playlist_url)
+        });
+        if moved_on {
+            self.0.set(None);
+        }
+    }
+}
+
+impl LocalPlaylistFile {
+    /// The file as though it had been fetched, so that it goes through the same viewer.
+    pub fn response(self) -> FetchTextResponse {
+        FetchTextResponse {
+            response_text: self.text,
+            url: self.base_url.unwrap_or_default(),
+            headers: ResponseHeaders {
+                url: self.name,
+                status: 200,
+                status_text: String::from("(local file)"),
+                headers: Vec::new(),
+            },
+            timing: FetchTiming::default(),
+        }
+    }
+}
+
+/// A media segment opened from a local file, which Home shows on its own in place of any playlist.
+#[derive(Clone, Copy, Default)]
+pub struct LocalSegment(RwSignal<Option<LocalSegmentFile>>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalSegmentFile {
+    pub name: String,
+    pub data: Vec<u8>,
+    pub content_type: Option<String>,
+}
+
+impl LocalSegment {
+    pub fn get(&self) -> Option<LocalSegmentFile> {
+        self.0.get()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.0.with(Option::is_some)
+    }
+
+    pub fn clear(&self) {
+        if self.0.with_untracked(Option::is_some) {
+            self.0.set(None);
+        }
+    }
+}
+
+/// Opens a playlist or a media segment saved on this device, either picked or dropped onto the
+/// input. Segments are shown on their own, so that any file can be inspected without a playlist.
+#[component]
+pub fn LocalFileInput() -> Option<impl IntoView> {
+    let local_playlist = use_context::<LocalPlaylist>()?;
+    let local_segment = use_context::<LocalSegment>()?;
+    let navigate = use_navigate();
+    let base_url = RwSignal::new(String::new());
+    let dragging = RwSignal::new(false);
+    let open = move |file: File| {
+        let navigate = navigate.clone();
+        let base_url = base_url.get_untracked();
+        spawn_local(async move {
+            let data = match JsFuture::from(file.array_buffer()).await {
+                Ok(buffer) => Uint8Array::new(&buffer).to_vec(),
+                Err(e) => {
+                    log::error!("failed to read {} due to {e:?}", file.name());
+                    return;
+                }
+            };
+            let name = file.name();
+            let lowercase_name = name.to_lowercase();
+            if lowercase_name.ends_with(".m3u8")
+                || lowercase_name.ends_with(".m3u")
+                || probe_is_playlist(&data)
+            {
+                local_segment.clear();
+                let file = LocalPlaylistFile {
+                    name,
+                    text: String::from_utf8_lossy(&data).into_owned(),
+                    base_url: Url::parse(base_url.trim()).ok().map(String::from),
+                };
+                local_playlist.show(file, navigate);
+            } else {
+                local_playlist.0.set(None);
+                let content_type = Some(file.type_()).filter(|t| !t.is_empty());
+                local_segment.0.set(Some(LocalSegmentFile {
+                    name,
+                    data,
+                    content_type,
+                }));
+                navigate(HOME_PATH, NavigateOptions::default());
+            }
+        });
+    };
+    let on_drop = {
+        let open = open.clone();
+        move |ev: DragEvent| {
+            ev.prevent_default();
+            dragging.set(false);
+            if let Some(file) = ev
+                .data_transfer()
+                .and_then(|data| data.files())
+                .and_then(|files| files.get(0))
+            {
+                open(file);
+            }
+        }
+    };
+    let close = move |_| {
+        local_playlist.0.set(None);
+        local_segment.clear();
+    };
+    Some(view! {
+        <div
+            class="local-file"
+            class:dragging=move || dragging.get()
+            on:dragover=move |ev: DragEvent| {
+                ev.prevent_default();
+                dragging.set(true);
+            }
+            on:dragleave=move |_| dragging.set(false)
+            on:drop=on_drop
+        >
+            <label>
+                {tr(Message::OpenLocalFile)}
+                <input
+                    type="file"
+                    accept=".m3u8,.m3u,application/vnd.apple.mpegurl,audio/mpegurl,.mp4,.m4s,.m4v,.m4a,.cmfv,.cmfa,.vtt,.webvtt,.ts"
+                    on:change=move |ev| {
+                        let input = event_target::<HtmlInputElement>(&ev);
+                        if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                            open(file);
+                        }
+                        input.set_value("");
+                    }
+                />
+            </label>
+            <input
+                type="url"
+                placeholder=tr(Message::BaseUrlPlaceholder)
+                title=tr(Message::BaseUrlTitle)
+                prop:value=move || base_url.get()
+                on:input=move |ev| base_url.set(event_target_value(&ev))
+            />
+            {move || {
+                local_playlist
+                    .0
+                    .with(|file| file.as_ref().map(|file| file.name.clone()))
+                    .or_else(|| {
+                        local_segment.0.with(|file| file.as_ref().map(|file| file.name.clone()))
+                    })
+                    .map(|name| {
+                        view! {
+                            <span>{tr_with(Message::ShowingLocalFile, &[("name", &name)])}</span>
+                            <button class="button" on:click=close>
+                                {tr(Message::CloseLocalFile)}
+                            </button>
+                        }
+                    })
+            }}
+        </div>
+    })
+}
@@ -1,7 +1,26 @@
+mod app_settings;
 mod copy_button;
+mod download_button;
+mod embed_bridge;
+mod local_file;
+mod offline_cache_settings;
+mod proxy_settings;
+mod request_settings;
+mod session_export;
 mod url_input_form;
 mod viewer;
 
+pub use app_settings::AppSettings;
 pub use copy_button::CopyButton;
+pub use download_button::DownloadButton;
+pub use embed_bridge::EmbedBridge;
+pub use local_file::{LocalFileInput, LocalPlaylist, LocalPlaylistFile, LocalSegment};
+pub use offline_cache_settings::OfflineCacheSettings;
+pub use proxy_settings::ProxySettings;
+pub use request_settings::RequestSettings;
+pub use session_export::SessionExport;
 pub use url_input_form::UrlInputForm;
-pub use viewer::{Viewer, ViewerLoading};
+pub use viewer::{
+    Automation, BoxTreeExpansion, Breadcrumbs, DriftHistory, LastSegment, LiveSnapshot,
+    LocalSegmentViewer, PlaylistCache, PlaylistDiff, SubtitleHistory, Viewer, ViewerLoading,
+};
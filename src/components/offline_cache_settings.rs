@@ -0,0 +1,94 @@
+use crate::{
+    components::AppSettings,
+    utils::{
+        i18n::{Message, tr, tr_with},
+        offline_cache::{OfflineCacheMode, clear_offline_cache, offline_cache_count},
+    },
+};
+use leptos::{prelude::*, task::spawn_local};
+
+/// Configures whether fetched responses are kept offline, and whether requests are answered from
+/// them. Responses kept offline outlast the session, and so are only let go of when the cache is
+/// cleared from here.
+#[component]
+pub fn OfflineCacheSettings(
+    /// Whether the settings start expanded rather than behind their summary.
+    #[prop(optional)]
+    open: bool,
+) -> impl IntoView {
+    let settings = AppSettings::current();
+    let cleared = RwSignal::new(0_u32);
+    let count = LocalResource::new(move || {
+        cleared.track();
+        offline_cache_count()
+    });
+    let summary = move || {
+        tr(match settings.with(|settings| settings.offline_cache) {
+            OfflineCacheMode::Off => Message::OfflineCacheOff,
+            OfflineCacheMode::Keep => Message::OfflineCacheKeeping,
+            OfflineCacheMode::Serve => Message::OfflineCacheServing,
+        })
+    };
+    let count_text = move || {
+        count.get().map(|count| match count {
+            Ok(1) => String::from(tr(Message::OneResponseKept)),
+            Ok(count) => tr_with(Message::ResponsesKept, &[("count", &count.to_string())]),
+            Err(_) => String::from(tr(Message::OfflineCacheUnavailable)),
+        })
+    };
+    let clear = move |_| {
+        spawn_local(async move {
+            if let Err(e) = clear_offline_cache().await {
+                log::error!("clearing the offline cache failed due to {e:?}");
+            }
+            cleared.update(|cleared| *cleared += 1);
+        });
+    };
+    view! {
+        <details class="offline-cache-settings" open=open>
+            <summary>{summary}</summary>
+            <label>
+                {tr(Message::OfflineCache)}
+                <select on:change=move |ev| {
+                    if let Some(mode) = OfflineCacheMode::from_name(&event_target_value(&ev)) {
+                        settings.update(|settings| settings.offline_cache = mode);
+                    }
+                }>
+                    {OfflineCacheMode::ALL
+                        .into_iter()
+                        .map(|mode| {
+                            view! {
+                                <option
+                                    value=mode.name()
+                                    selected=move || {
+                                        settings.with(|settings| settings.offline_cache == mode)
+                                    }
+                                >
+                                    {mode_label(mode)}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </label>
+            <div>
+                <button
+                    class="button"
+                    title=tr(Message::ClearOfflineCacheTitle)
+                    on:click=clear
+                >
+                    {tr(Message::ClearOfflineCache)}
+                </button>
+                {count_text}
+            </div>
+        </details>
+    }
+}
+
+fn mode_label(mode: OfflineCacheMode) -> &'static str {
+    tr(match mode {
+        OfflineCacheMode::Off => Message::OfflineCacheModeOff,
+        OfflineCacheMode::Keep => Message::OfflineCacheModeKeep,
+        OfflineCacheMode::Serve => Message::OfflineCacheModeServe,
+    })
+}
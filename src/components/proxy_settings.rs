@@ -0,0 +1,75 @@
+use crate::{
+    components::AppSettings,
+    utils::{
+        i18n::{Message, tr, tr_with},
+        proxy::{PROXY_URL_PLACEHOLDER, parse_hosts},
+    },
+};
+use leptos::prelude::*;
+
+/// Configures the CORS proxy that requests are sent through. Changes are saved straight away and
+/// apply to the requests made from then on.
+#[component]
+pub fn ProxySettings(
+    /// Whether the settings start expanded rather than behind their summary.
+    #[prop(optional)]
+    open: bool,
+) -> impl IntoView {
+    let settings = AppSettings::current();
+    let summary = move || {
+        settings.with(|settings| {
+            let config = &settings.proxy;
+            if config.enabled && !config.prefix.trim().is_empty() {
+                tr_with(Message::CorsProxy, &[("prefix", &config.prefix)])
+            } else {
+                String::from(tr(Message::CorsProxyOff))
+            }
+        })
+    };
+    let prefix_title = tr_with(
+        Message::ProxyPrefixTitle,
+        &[("placeholder", PROXY_URL_PLACEHOLDER)],
+    );
+    view! {
+        <details class="proxy-settings" open=open>
+            <summary>{summary}</summary>
+            <label>
+                <input
+                    type="checkbox"
+                    prop:checked=move || settings.with(|settings| settings.proxy.enabled)
+                    on:change=move |ev| {
+                        let enabled = event_target_checked(&ev);
+                        settings.update(|settings| settings.proxy.enabled = enabled);
+                    }
+                />
+                {tr(Message::UseCorsProxy)}
+            </label>
+            <label>
+                {tr(Message::ProxyPrefix)}
+                <input
+                    type="text"
+                    placeholder="https://myproxy/?url="
+                    title=prefix_title
+                    prop:value=move || settings.with(|settings| settings.proxy.prefix.clone())
+                    on:change=move |ev| {
+                        let prefix = event_target_value(&ev).trim().to_string();
+                        settings.update(|settings| settings.proxy.prefix = prefix);
+                    }
+                />
+            </label>
+            <label>
+                {tr(Message::ProxyBypassHosts)}
+                <input
+                    type="text"
+                    placeholder="example.com, .cdn.example.com"
+                    title=tr(Message::ProxyBypassHostsTitle)
+                    prop:value=move || settings.with(|settings| settings.proxy.bypass_hosts.join(", "))
+                    on:change=move |ev| {
+                        let hosts = parse_hosts(&event_target_value(&ev));
+                        settings.update(|settings| settings.proxy.bypass_hosts = hosts);
+                    }
+                />
+            </label>
+        </details>
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    components::AppSettings,
+    utils::{
+        i18n::{Message, tr, tr_with},
+        settings::{format_headers, parse_headers},
+    },
+};
+use leptos::prelude::*;
+
+/// Configures how long requests may take before they are aborted, and any headers added to them.
+/// Changes are saved straight away and apply to the requests made from then on.
+#[component]
+pub fn RequestSettings(
+    /// Whether the settings start expanded rather than behind their summary.
+    #[prop(optional)]
+    open: bool,
+) -> impl IntoView {
+    let settings = AppSettings::current();
+    let summary = move || {
+        settings.with(|settings| {
+            let timeout = match settings.fetch_timeout_seconds {
+                0 => String::from(tr(Message::RequestTimeoutNone)),
+                seconds => tr_with(
+                    Message::RequestTimeout,
+                    &[("seconds", &seconds.to_string())],
+                ),
+            };
+            match settings.request_headers.len() {
+                0 => timeout,
+                1 => tr_with(Message::OneExtraHeader, &[("timeout", &timeout)]),
+                count => tr_with(
+                    Message::ExtraHeaders,
+                    &[("timeout", &timeout), ("count", &count.to_string())],
+                ),
+            }
+        })
+    };
+    view! {
+        <details class="request-settings" open=open>
+            <summary>{summary}</summary>
+            <label>
+                {tr(Message::RequestTimeoutBefore)}
+                <input
+                    type="number"
+                    min="0"
+                    title=tr(Message::RequestTimeoutTitle)
+                    prop:value=move || {
+                        settings.with(|settings| settings.fetch_timeout_seconds.to_string())
+                    }
+                    on:change=move |ev| {
+                        let Ok(seconds) = event_target_value(&ev).trim().parse::<u32>() else {
+                            return;
+                        };
+                        settings.update(|settings| settings.fetch_timeout_seconds = seconds);
+                    }
+                />
+                {tr(Message::RequestTimeoutAfter)}
+            </label>
+            <label class="request-headers">
+                {tr(Message::ExtraHeadersLabel)}
+                <textarea
+                    rows="3"
+                    placeholder=tr(Message::ExtraHeadersPlaceholder)
+                    title=tr(Message::ExtraHeadersTitle)
+                    prop:value=move || {
+                        settings.with(|settings| format_headers(&settings.request_headers))
+                    }
+                    on:change=move |ev| {
+                        let headers = parse_headers(&event_target_value(&ev));
+                        settings.update(|settings| settings.request_headers = headers);
+                    }
+                ></textarea>
+            </label>
+        </details>
+    }
+}
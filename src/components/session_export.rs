@@ -0,0 +1,115 @@
+use crate::utils::{
+    download::download_text,
+    har::{session_bundle, session_har},
+    i18n::{Message, tr, tr_with},
+    session_bundle::{parse_session_bundle, replay, stop_replay},
+};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::{NavigateOptions, hooks::use_navigate};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{File, HtmlInputElement};
+
+const HAR_FILE_NAME: &str = "hls-manifest-viewer.har";
+const SESSION_FILE_NAME: &str = "hls-manifest-viewer-session.har";
+
+/// Exports the requests made so far in the session, so that they can be attached to a ticket or
+/// opened in other tools that read HAR files. With the responses included, the export is a session
+/// bundle, which can be imported again later to look into the session offline.
+#[component]
+pub fn SessionExport() -> impl IntoView {
+    let navigate = use_navigate();
+    // The name of the bundle being replayed and how many requests it holds.
+    let replaying = RwSignal::new(None::<(String, usize)>);
+    let import = move |file: File| {
+        let navigate = navigate.clone();
+        spawn_local(async move {
+            let text = match JsFuture::from(file.text()).await {
+                Ok(text) => text.as_string().unwrap_or_default(),
+                Err(e) => {
+                    log::error!("failed to read {} due to {e:?}", file.name());
+                    return;
+                }
+            };
+            let bundle = match parse_session_bundle(&text) {
+                Ok(bundle) => bundle,
+                Err(e) => {
+                    log::error!("failed to import {} as a session: {e}", file.name());
+                    return;
+                }
+            };
+            let viewer_url = bundle.viewer_url.clone();
+            replaying.set(Some((file.name(), bundle.request_count())));
+            replay(bundle);
+            if let Some(viewer_url) = viewer_url {
+                navigate(&viewer_url, NavigateOptions::default());
+            }
+        });
+    };
+    let export_session = move |_| {
+        let location = window().location();
+        let viewer_url = [location.pathname(), location.search(), location.hash()]
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect::<String>();
+        download_text(
+            SESSION_FILE_NAME,
+            "application/json",
+            &session_bundle(&viewer_url),
+        );
+    };
+    view! {
+        <div class="session-export">
+            <button
+                class="button"
+                title=tr(Message::ExportHarTitle)
+                on:click=move |_| download_text(HAR_FILE_NAME, "application/json", &session_har())
+            >
+                {tr(Message::ExportHar)}
+            </button>
+            <button
+                class="button"
+                title=tr(Message::ExportSessionTitle)
+                on:click=export_session
+            >
+                {tr(Message::ExportSession)}
+            </button>
+            <label>
+                {tr(Message::ImportSession)}
+                <input
+                    type="file"
+                    accept=".har,.json,application/json"
+                    on:change=move |ev| {
+                        let input = event_target::<HtmlInputElement>(&ev);
+                        if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                            import(file);
+                        }
+                        input.set_value("");
+                    }
+                />
+            </label>
+            {move || {
+                replaying
+                    .get()
+                    .map(|(name, request_count)| {
+                        view! {
+                            <span>
+                                {tr_with(
+                                    Message::ReplayingSession,
+                                    &[("name", &name), ("count", &request_count.to_string())],
+                                )}
+                            </span>
+                            <button
+                                class="button"
+                                on:click=move |_| {
+                                    stop_replay();
+                                    replaying.set(None);
+                                }
+                            >
+                                {tr(Message::StopReplaying)}
+                            </button>
+                        }
+                    })
+            }}
+        </div>
+    }
+}
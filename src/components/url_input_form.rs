@@ -1,6 +1,19 @@
-use crate::utils::href::PLAYLIST_URL_QUERY_NAME;
-use leptos::prelude::*;
-use leptos_router::{components::Form, hooks::use_query_map};
+use crate::{
+    components::{LocalPlaylist, LocalPlaylistFile, LocalSegment},
+    utils::{
+        href::{FORWARD_QUERY_QUERY_NAME, PLAYLIST_URL_QUERY_NAME},
+        i18n::{Message, tr},
+        query_codec::percent_encode,
+        url_history::{load_url_history, save_url_history, with_visited_url},
+    },
+};
+use leptos::{either::Either, prelude::*};
+use leptos_router::{
+    components::Form,
+    hooks::{use_navigate, use_query_map},
+};
+
+const URL_HISTORY_LIST_ID: &str = "url-history";
 
 #[component]
 pub fn UrlInputForm() -> impl IntoView {
@@ -11,22 +24,178 @@ pub fn UrlInputForm() -> impl IntoView {
             .get(PLAYLIST_URL_QUERY_NAME)
             .unwrap_or_default()
     };
+    let forward_query = move || {
+        query
+            .read()
+            .get(FORWARD_QUERY_QUERY_NAME)
+            .unwrap_or_default()
+    };
+    // Every playlist that is viewed is remembered, so that it can be picked again later.
+    let history = RwSignal::new(load_url_history());
+    Effect::watch(
+        playlist_url,
+        move |playlist_url, _, _| {
+            if playlist_url.is_empty() {
+                return;
+            }
+            history.update(|history| {
+                *history = with_visited_url(std::mem::take(history), playlist_url);
+            });
+            history.with_untracked(|history| save_url_history(history));
+        },
+        true,
+    );
+    // The playlist can instead be pasted in (e.g. from logs or a proxy capture), when Home is able
+    // to show it without a URL.
+    let local_playlist = use_context::<LocalPlaylist>();
+    let pasting = RwSignal::new(false);
+    let mode_toggle = move || {
+        local_playlist.map(|_| {
+            view! {
+                <button
+                    class="button"
+                    type="button"
+                    on:click=move |_| pasting.update(|pasting| *pasting = !*pasting)
+                >
+                    {move || {
+                        if pasting.get() {
+                            tr(Message::EnterUrl)
+                        } else {
+                            tr(Message::PastePlaylist)
+                        }
+                    }}
+                </button>
+            }
+        })
+    };
+
+    view! {
+        {move || match local_playlist.filter(|_| pasting.get()) {
+            Some(local_playlist) => Either::Left(view! { <PastedPlaylistForm local_playlist /> }),
+            None => {
+                Either::Right(
+                    view! {
+                        <Form attr:class="url-input-form" method="GET" action="">
+                            <div class="url-input-form-inner-container">
+                                <input
+                                    class="url-input"
+                                    type="url"
+                                    name=PLAYLIST_URL_QUERY_NAME
+                                    value=playlist_url
+                                    list=URL_HISTORY_LIST_ID
+                                    placeholder="https://example.com/mvp.m3u8"
+                                    pattern="https?://.*"
+                                    aria-label=tr(Message::PlaylistUrlLabel)
+                                    title=tr(Message::PlaylistUrlTitle)
+                                />
+                                <input
+                                    class="url-input forward-query-input"
+                                    type="text"
+                                    name=FORWARD_QUERY_QUERY_NAME
+                                    value=forward_query
+                                    placeholder=tr(Message::ForwardQueryPlaceholder)
+                                    aria-label=tr(Message::ForwardQueryLabel)
+                                    title=tr(Message::ForwardQueryTitle)
+                                />
+                                <input class="button" type="submit" />
+                            </div>
+                        </Form>
+                    },
+                )
+            }
+        }}
+        <datalist id=URL_HISTORY_LIST_ID>
+            {move || {
+                history.get().into_iter().map(|url| view! { <option value=url /> }).collect_view()
+            }}
+        </datalist>
+        <div class="url-input-mode">{mode_toggle}</div>
+        <UrlHistory history />
+    }
+}
 
+#[component]
+fn PastedPlaylistForm(local_playlist: LocalPlaylist) -> impl IntoView {
+    let navigate = use_navigate();
+    let local_segment = use_context::<LocalSegment>();
+    let text = RwSignal::new(String::new());
+    let show = move |_| {
+        let text = text.get_untracked();
+        if text.trim().is_empty() {
+            return;
+        }
+        let file = LocalPlaylistFile {
+            name: String::from(tr(Message::PastedPlaylistName)),
+            text,
+            base_url: None,
+        };
+        if let Some(local_segment) = local_segment {
+            local_segment.clear();
+        }
+        local_playlist.show(file, navigate.clone());
+    };
     view! {
-        <Form attr:class="url-input-form" method="GET" action="">
+        <div class="url-input-form">
             <div class="url-input-form-inner-container">
-                <input
-                    class="url-input"
-                    type="url"
-                    name=PLAYLIST_URL_QUERY_NAME
-                    value=playlist_url
-                    placeholder="https://example.com/mvp.m3u8"
-                    pattern="https?://.*"
-                    aria-label="playlist url"
-                    title="url with http or https scheme (e.g. https://example.com/mvp.m3u8)"
+                <textarea
+                    class="url-input playlist-text-input"
+                    rows="8"
+                    placeholder="#EXTM3U"
+                    aria-label=tr(Message::PlaylistTextLabel)
+                    prop:value=move || text.get()
+                    on:input=move |ev| text.set(event_target_value(&ev))
                 />
-                <input class="button" type="submit" />
+                <button class="button" type="button" on:click=show>
+                    {tr(Message::ShowPastedPlaylist)}
+                </button>
             </div>
-        </Form>
+        </div>
+    }
+}
+
+/// The recently viewed playlists, each of which can be opened again or forgotten.
+#[component]
+fn UrlHistory(history: RwSignal<Vec<String>>) -> impl IntoView {
+    let forget = move |url: &str| {
+        history.update(|history| history.retain(|visited| visited != url));
+        history.with_untracked(|history| save_url_history(history));
+    };
+    let clear = move |_| {
+        history.set(Vec::new());
+        save_url_history(&[]);
+    };
+    let entries = move || {
+        history
+            .get()
+            .into_iter()
+            .map(|url| {
+                let href = format!("?{PLAYLIST_URL_QUERY_NAME}={}", percent_encode(&url));
+                let forgotten = url.clone();
+                view! {
+                    <li>
+                        <a href=href>{url}</a>
+                        <button
+                            class="button"
+                            type="button"
+                            title=tr(Message::RemoveRecentPlaylistTitle)
+                            on:click=move |_| forget(&forgotten)
+                        >
+                            {tr(Message::RemoveRecentPlaylist)}
+                        </button>
+                    </li>
+                }
+            })
+            .collect_view()
+    };
+    view! {
+        <Show when=move || !history.with(Vec::is_empty)>
+            <details class="url-history">
+                <summary>{tr(Message::RecentPlaylists)}</summary>
+                <ul>{entries}</ul>
+                <button class="button" type="button" on:click=clear>
+                    {tr(Message::ClearRecentPlaylists)}
+                </button>
+            </details>
+        </Show>
     }
 }
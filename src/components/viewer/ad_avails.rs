@@ -0,0 +1,78 @@
+use super::{AD_AVAILS_CLASS, playlist::line_anchor_id};
+use crate::utils::{
+    ad_avails::{AdAvail, ad_avail_totals, ad_avails},
+    i18n::{Message, tr, tr_with},
+    live_timeline::format_time_of_day,
+};
+use leptos::prelude::*;
+
+/// Every ad avail in the playlist, whether signalled with SCTE-35, filled by an interstitial, or
+/// both, with how much ad time they add up to and how far along each break is: signalled by its
+/// SCTE35-OUT, filled by an interstitial, and returned to the content by its SCTE35-IN.
+#[component]
+pub fn AdAvails(playlist: String) -> Option<impl IntoView> {
+    let avails = ad_avails(&playlist);
+    if avails.is_empty() {
+        return None;
+    }
+    let totals = ad_avail_totals(&avails);
+    let count = avails.len().to_string();
+    let summary = match totals.average {
+        Some(average) => tr_with(
+            Message::AdAvailsSummary,
+            &[
+                ("count", &count),
+                ("duration", &format!("{:.3}", totals.duration)),
+                ("average", &format!("{average:.3}")),
+            ],
+        ),
+        None => tr_with(Message::AdAvailsCount, &[("count", &count)]),
+    };
+    Some(view! {
+        <details class=AD_AVAILS_CLASS>
+            <summary>{summary}</summary>
+            <table>
+                <tr>
+                    <th>{tr(Message::AdId)}</th>
+                    <th>{tr(Message::AdAvailStart)}</th>
+                    <th>{tr(Message::AdAvailDuration)}</th>
+                    <th>{tr(Message::AdAvailStatus)}</th>
+                </tr>
+                {avails.into_iter().map(avail_row).collect_view()}
+            </table>
+        </details>
+    })
+}
+
+fn avail_row(avail: AdAvail) -> impl IntoView {
+    let status = [
+        (tr(Message::AdAvailSignalled), avail.signalled_line),
+        (tr(Message::AdAvailFilled), avail.interstitial_line),
+        (tr(Message::AdAvailReturned), avail.returned_line),
+    ]
+    .into_iter()
+    .filter_map(|(status, line)| Some((status, line?)))
+    .enumerate()
+    .map(|(index, (status, line))| {
+        view! {
+            {(index > 0).then_some(", ")}
+            <a href=format!("#{}", line_anchor_id(line))>{status}</a>
+        }
+    })
+    .collect_view();
+    view! {
+        <tr>
+            <td>
+                <a href=format!("#{}", line_anchor_id(avail.line()))>{avail.id.clone()}</a>
+            </td>
+            <td>{avail.start_date.map(format_time_of_day).unwrap_or_else(|| String::from("-"))}</td>
+            <td>
+                {avail
+                    .duration
+                    .map(|duration| format!("{duration:.3}s"))
+                    .unwrap_or_else(|| String::from("-"))}
+            </td>
+            <td>{status}</td>
+        </tr>
+    }
+}
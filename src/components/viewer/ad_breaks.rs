@@ -0,0 +1,140 @@
+use super::{AD_BREAKS_CLASS, VALIDATION_WARNING_CLASS, playlist::line_anchor_id};
+use crate::utils::{
+    ad_breaks::{AdBreak, BreakPairing, ad_breaks},
+    i18n::{Message, tr, tr_with},
+};
+use leptos::prelude::*;
+
+/// The ad breaks signalled with SCTE-35 in the playlist, with each SCTE35-OUT paired with its
+/// SCTE35-IN, and the duration of each break as signalled and as it falls on the segments compared
+/// against its PLANNED-DURATION. Breaks that are unpaired, overlap, or run longer or shorter than
+/// planned are called out.
+#[component]
+pub fn AdBreaks(playlist: String) -> Option<impl IntoView> {
+    let breaks = ad_breaks(&playlist);
+    if breaks.is_empty() {
+        return None;
+    }
+    let unpaired = breaks
+        .iter()
+        .filter(|ad_break| ad_break.pairing == BreakPairing::Unpaired)
+        .count();
+    let overlapping = breaks
+        .iter()
+        .filter(|ad_break| !ad_break.overlaps.is_empty())
+        .count();
+    let summary = tr_with(
+        Message::AdBreaksSummary,
+        &[
+            ("count", &breaks.len().to_string()),
+            ("unpaired", &unpaired.to_string()),
+            ("overlapping", &overlapping.to_string()),
+        ],
+    );
+    Some(view! {
+        <details class=AD_BREAKS_CLASS open={unpaired + overlapping > 0}>
+            <summary>{summary}</summary>
+            <table>
+                <tr>
+                    <th>{tr(Message::AdId)}</th>
+                    <th>"SCTE35-OUT"</th>
+                    <th>"SCTE35-IN"</th>
+                    <th>{tr(Message::AdBreakEventId)}</th>
+                    <th>{tr(Message::AdBreakPlanned)}</th>
+                    <th>{tr(Message::AdBreakSignalled)}</th>
+                    <th>{tr(Message::AdBreakActual)}</th>
+                    <th>{tr(Message::AdBreakProblems)}</th>
+                </tr>
+                {breaks.into_iter().map(break_row).collect_view()}
+            </table>
+        </details>
+    })
+}
+
+fn break_row(ad_break: AdBreak) -> impl IntoView {
+    let problems = break_problems(&ad_break);
+    let AdBreak {
+        id,
+        out_line,
+        in_line,
+        pairing,
+        event_id,
+        planned_duration,
+        signalled_duration,
+        actual_duration,
+        ..
+    } = ad_break;
+    let in_line = in_line.map(|line| {
+        let by = match pairing {
+            BreakPairing::EventId => Some(format!(" {}", tr(Message::PairedByEventId))),
+            BreakPairing::SameId | BreakPairing::Unpaired => None,
+        };
+        view! {
+            <a href=format!("#{}", line_anchor_id(line))>{line_label(line)}</a>
+            {by}
+        }
+    });
+    view! {
+        <tr>
+            <td>{id}</td>
+            <td>
+                {out_line
+                    .map(|line| {
+                        view! {
+                            <a href=format!("#{}", line_anchor_id(line))>{line_label(line)}</a>
+                        }
+                    })}
+            </td>
+            <td>{in_line}</td>
+            <td>{event_id.map(|id| format!("{id} (0x{id:08X})"))}</td>
+            <td>{seconds(planned_duration)}</td>
+            <td>{seconds(signalled_duration)}</td>
+            <td>{seconds(actual_duration)}</td>
+            <td class=VALIDATION_WARNING_CLASS>{problems.join(" ")}</td>
+        </tr>
+    }
+}
+
+fn break_problems(ad_break: &AdBreak) -> Vec<String> {
+    let mut problems = Vec::new();
+    match (ad_break.pairing, ad_break.out_line) {
+        (BreakPairing::Unpaired, Some(_)) => {
+            problems.push(String::from(tr(Message::AdBreakWithoutIn)));
+        }
+        (BreakPairing::Unpaired, None) => {
+            problems.push(String::from(tr(Message::AdBreakWithoutOut)));
+        }
+        _ => (),
+    }
+    if let Some(difference) = ad_break.planned_duration_mismatch() {
+        let message = if difference > 0.0 {
+            Message::AdBreakLonger
+        } else {
+            Message::AdBreakShorter
+        };
+        problems.push(tr_with(
+            message,
+            &[("difference", &format!("{:.3}", difference.abs()))],
+        ));
+    }
+    if !ad_break.overlaps.is_empty() {
+        let ids = ad_break
+            .overlaps
+            .iter()
+            .map(|id| format!("\"{id}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        problems.push(tr_with(Message::AdBreakOverlaps, &[("ids", &ids)]));
+    }
+    problems
+}
+
+fn line_label(line: usize) -> String {
+    tr_with(Message::FindingLine, &[("line", &line.to_string())])
+}
+
+fn seconds(seconds: Option<f64>) -> String {
+    seconds
+        .map(|seconds| format!("{seconds:.3}s"))
+        .unwrap_or_else(|| String::from("-"))
+}
@@ -1,33 +1,93 @@
-use super::{SPACER_BOTTOM, SUPPLEMENTAL_VIEW_CLASS, UNDERLINED, URI_CLASS};
-use crate::{components::viewer::error::ViewerError, utils::href::media_playlist_href};
+use super::{
+    ASSET_PREVIEW_CLASS, SPACER_BOTTOM, SUPPLEMENTAL_VIEW_CLASS, UNDERLINED, URI_CLASS,
+    VALIDATION_WARNING_CLASS, network::NetworkLog,
+};
+use crate::{
+    components::{AppSettings, DownloadButton, viewer::error::ViewerError},
+    utils::{
+        href::media_playlist_href,
+        i18n::{Message, tr, tr_with},
+        network::fetch_text,
+        playlist_lines::{LineKind, parse},
+        playlist_timeline::timeline,
+    },
+};
 use leptos::{either::Either, prelude::*};
 use serde::Deserialize;
 use std::collections::HashMap;
+use url::Url;
 
+const JSON_MIME_TYPE: &str = "application/json";
+/// How far (in seconds) the assets may run from the break that they fill before it is pointed out.
+const BREAK_DURATION_TOLERANCE: f64 = 0.5;
+
+/// The assets of an interstitial, where `file_name` is what the fetched JSON is downloaded as. Each
+/// asset can be previewed in place, and their durations are summed against `break_duration`, the
+/// length of the break that they fill (when it is known from the playlist).
 #[component]
-pub fn AssetListView(json: String) -> impl IntoView {
+pub fn AssetListView(
+    json: String,
+    file_name: String,
+    /// The URL of the asset list, which the URIs of the assets are relative to.
+    asset_list_url: String,
+    break_duration: Option<f64>,
+) -> impl IntoView {
+    let download = view! {
+        <DownloadButton
+            file_name
+            mime_type=JSON_MIME_TYPE
+            contents={
+                let json = json.clone();
+                move || json.clone().into_bytes()
+            }
+        />
+    };
     match decode(&json) {
-        Ok(asset_list) => Either::Left(view! {
+        Ok(asset_list) => Either::Left({
+            let total_duration = asset_list
+                .assets
+                .iter()
+                .map(|asset| asset.duration)
+                .sum::<f64>();
+            view! {
             <div class=SUPPLEMENTAL_VIEW_CLASS>
+                {download}
                 <p class=UNDERLINED>"ASSETS"</p>
                 <table class=SPACER_BOTTOM>
                     <tr>
                         <th>"URI"</th>
                         <th>"DURATION"</th>
+                        <th></th>
                     </tr>
                     {asset_list
                         .assets
                         .iter()
-                        .map(|asset| {
-                            view! {
-                                <tr>
-                                    <td>{uri_link(asset.uri.clone())}</td>
-                                    <td>{asset.duration}</td>
-                                </tr>
-                            }
-                        })
+                        .map(|asset| asset_row(asset, &asset_list_url))
                         .collect_view()}
+                    <tr>
+                        <td>{tr(Message::AssetListTotal)}</td>
+                        <td>{format!("{total_duration:.3}")}</td>
+                        <td></td>
+                    </tr>
                 </table>
+                {break_duration
+                    .map(|break_duration| {
+                        let difference = total_duration - break_duration;
+                        let class = if difference.abs() > BREAK_DURATION_TOLERANCE {
+                            VALIDATION_WARNING_CLASS
+                        } else {
+                            ""
+                        };
+                        let message = tr_with(
+                            Message::AssetListBreakDuration,
+                            &[
+                                ("duration", &format!("{total_duration:.3}")),
+                                ("break_duration", &format!("{break_duration:.3}")),
+                                ("difference", &format!("{difference:+.3}")),
+                            ],
+                        );
+                        view! { <p class=class>{message}</p> }
+                    })}
                 {if let Some(skip_control) = asset_list.skip_control {
                     Either::Left(
                         view! {
@@ -54,11 +114,13 @@ pub fn AssetListView(json: String) -> impl IntoView {
                 <p class=UNDERLINED>"JSON"</p>
                 <code>{json}</code>
             </div>
+            }
         }),
         Err(error) => Either::Right(view! {
             <div class=SUPPLEMENTAL_VIEW_CLASS>
+                {download}
                 <ViewerError
-                    error="Error deserializing JSON".to_string()
+                    error=tr(Message::AssetListJsonError).to_string()
                     extra_info=Some(format!("{error}"))
                 />
             </div>
@@ -66,6 +128,130 @@ pub fn AssetListView(json: String) -> impl IntoView {
     }
 }
 
+// The asset with a button that previews its playlist in a row beneath it.
+fn asset_row(asset: &AssetDescription, asset_list_url: &str) -> impl IntoView {
+    let url = Url::parse(asset_list_url)
+        .and_then(|base| base.join(&asset.uri))
+        .map(String::from)
+        .ok();
+    let previewing = RwSignal::new(false);
+    view! {
+        <tr>
+            <td>{uri_link(asset.uri.clone())}</td>
+            <td>{asset.duration}</td>
+            <td>
+                {url
+                    .is_some()
+                    .then(|| {
+                        view! {
+                            <button
+                                class="button"
+                                type="button"
+                                aria-expanded=move || previewing.get().to_string()
+                                on:click=move |_| previewing.update(|shown| *shown = !*shown)
+                            >
+                                {move || {
+                                    if previewing.get() {
+                                        tr(Message::HideAssetPreview)
+                                    } else {
+                                        tr(Message::PreviewAsset)
+                                    }
+                                }}
+                            </button>
+                        }
+                    })}
+            </td>
+        </tr>
+        {url
+            .map(|url| {
+                view! {
+                    <Show when=move || previewing.get()>
+                        <tr>
+                            <td colspan="3">
+                                <AssetPreview url=url.clone() duration=asset.duration />
+                            </td>
+                        </tr>
+                    </Show>
+                }
+            })}
+    }
+}
+
+/// The playlist of an asset, fetched when it is first previewed, with how long it plays for.
+#[component]
+fn AssetPreview(url: String, duration: f64) -> impl IntoView {
+    let network_log = use_context::<NetworkLog>();
+    let settings = AppSettings::current();
+    let response = LocalResource::new(move || {
+        let url = url.clone();
+        let settings = settings.get_untracked();
+        async move {
+            let response = fetch_text(url, &settings).await;
+            if let (Some(log), Ok(response)) = (network_log, &response) {
+                log.record(
+                    tr(Message::NetworkAsset),
+                    response.headers.clone(),
+                    response.timing,
+                );
+            }
+            response
+        }
+    });
+    view! {
+        <div class=ASSET_PREVIEW_CLASS>
+            <Suspense fallback=|| tr(Message::LoadingFallback)>
+                {move || {
+                    response
+                        .get()
+                        .map(|response| match response {
+                            Ok(response) => {
+                                Either::Left(
+                                    view! {
+                                        <p>{asset_summary(&response.response_text, duration)}</p>
+                                        <pre>{response.response_text}</pre>
+                                    },
+                                )
+                            }
+                            Err(e) => {
+                                Either::Right(
+                                    view! {
+                                        <ViewerError error=e.error() extra_info=e.extra_info() />
+                                    },
+                                )
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+// An asset is usually a Multivariant Playlist, whose variants are not fetched, but a Media Playlist
+// can be measured against the DURATION that the asset list gives it.
+fn asset_summary(playlist: &str, duration: f64) -> String {
+    let timeline = timeline(playlist);
+    if timeline.segments.is_empty() {
+        let variants = parse(playlist)
+            .into_iter()
+            .filter(
+                |line| matches!(&line.kind, LineKind::Tag(tag) if tag.name == "EXT-X-STREAM-INF"),
+            )
+            .count();
+        return tr_with(
+            Message::AssetMultivariantSummary,
+            &[("variants", &variants.to_string())],
+        );
+    }
+    tr_with(
+        Message::AssetMediaSummary,
+        &[
+            ("segments", &timeline.segments.len().to_string()),
+            ("duration", &format!("{:.3}", timeline.duration())),
+            ("asset_duration", &duration.to_string()),
+        ],
+    )
+}
+
 fn uri_link(uri: String) -> impl IntoView {
     if let Some(href) = media_playlist_href(&uri, &HashMap::new()) {
         Either::Left(view! {
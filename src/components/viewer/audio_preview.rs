@@ -0,0 +1,98 @@
+use super::{AUDIO_PREVIEW_CLASS, SUPPLEMENTAL_VIEW_CLASS, VALIDATION_WARNING_CLASS};
+use crate::{
+    components::DownloadButton,
+    utils::{
+        audio::PackedAudioFormat,
+        i18n::{Message, tr, tr_with},
+        network::SharedBytes,
+        playback::{DecodedAudio, play_audio},
+    },
+};
+use leptos::{prelude::*, task::spawn_local};
+use web_sys::AudioContext;
+
+/// Shows a packed audio segment, which can be played after being decoded with Web Audio, along with
+/// what the decoding found (including whether the segment is silent).
+#[component]
+pub fn PackedAudioViewer(
+    data: SharedBytes,
+    format: PackedAudioFormat,
+    #[prop(optional_no_strip)] download_name: Option<String>,
+) -> impl IntoView {
+    let size = data.len();
+    let decoded = RwSignal::new(None::<DecodedAudio>);
+    let error = RwSignal::new(None::<String>);
+    let context = StoredValue::new_local(None::<AudioContext>);
+    let stop = move || {
+        if let Some(context) = context.try_update_value(Option::take).flatten() {
+            let _ = context.close();
+        }
+    };
+    on_cleanup(stop);
+    let download = download_name.map(|file_name| {
+        let data = data.clone();
+        view! {
+            <DownloadButton file_name mime_type=format.mime_type() contents=move || data.to_vec() />
+        }
+    });
+    let play = move |_| {
+        stop();
+        let data = data.clone();
+        spawn_local(async move {
+            match play_audio(&data).await {
+                Ok((playing, audio)) => {
+                    context.set_value(Some(playing));
+                    decoded.set(Some(audio));
+                    error.set(None);
+                }
+                Err(e) => error.set(Some(e)),
+            }
+        });
+    };
+    view! {
+        <div class=SUPPLEMENTAL_VIEW_CLASS>
+            <div class=AUDIO_PREVIEW_CLASS>
+                <p>
+                    {tr_with(
+                        Message::PackedAudioSegment,
+                        &[("format", format.name()), ("size", &size.to_string())],
+                    )}
+                </p>
+                <button
+                    class="button"
+                    type="button"
+                    title=tr(Message::PlayAudioTitle)
+                    on:click=play
+                >
+                    {tr(Message::Play)}
+                </button>
+                <button class="button" type="button" on:click=move |_| stop()>
+                    {tr(Message::Stop)}
+                </button>
+                {download}
+                {move || {
+                    decoded.get().map(|decoded| view! { <p>{decoded_summary(&decoded)}</p> })
+                }}
+                {move || {
+                    error.get().map(|error| view! { <p class=VALIDATION_WARNING_CLASS>{error}</p> })
+                }}
+            </div>
+        </div>
+    }
+}
+
+fn decoded_summary(decoded: &DecodedAudio) -> String {
+    let level = match decoded.peak_dbfs {
+        Some(peak) => tr_with(Message::AudioPeak, &[("peak", &format!("{peak:.1}"))]),
+        None => String::from(tr(Message::AudioSilent)),
+    };
+    tr_with(
+        Message::AudioDecoded,
+        &[
+            ("duration", &format!("{:.3}", decoded.duration)),
+            ("channels", &decoded.channels.to_string()),
+            ("sample_rate", &decoded.sample_rate.to_string()),
+            ("level", &level),
+        ],
+    )
+}
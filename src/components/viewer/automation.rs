@@ -0,0 +1,77 @@
+use crate::utils::query_codec::{PlaylistView, SegmentSelector, is_flag_set};
+use leptos::{html::ElementType, prelude::*};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, ScrollIntoViewOptions, ScrollLogicalPosition};
+
+/// What the link that the viewer was opened with asks to be done once the playlist has loaded, so
+/// that a diagnosis can be bookmarked, or a screenshot of it scripted: `validate=1` opens the
+/// validation report, `view=summary` opens the table of variants and `segment=msn:1234` opens a
+/// segment. The page provides this as context, from the query of the URL (which, when the viewer is
+/// embedded, the embedding page may also set).
+#[derive(Clone, Copy)]
+pub struct Automation {
+    validate: Memo<bool>,
+    view: Memo<Option<PlaylistView>>,
+    segment: Memo<Option<SegmentSelector>>,
+}
+
+impl Automation {
+    pub fn new(
+        validate: Memo<Option<String>>,
+        view: Memo<Option<String>>,
+        segment: Memo<Option<String>>,
+    ) -> Self {
+        Self {
+            validate: Memo::new(move |_| validate.get().is_some_and(|value| is_flag_set(&value))),
+            view: Memo::new(move |_| {
+                view.get()
+                    .and_then(|value| parsed(&value, PlaylistView::parse))
+            }),
+            segment: Memo::new(move |_| {
+                segment
+                    .get()
+                    .and_then(|value| parsed(&value, SegmentSelector::parse))
+            }),
+        }
+    }
+
+    pub fn validate(&self) -> bool {
+        self.validate.get_untracked()
+    }
+
+    pub fn view(&self) -> Option<PlaylistView> {
+        self.view.get_untracked()
+    }
+
+    /// Tracked, unlike the others, so that a segment asked for once the playlist is shown is opened
+    /// as well.
+    pub fn segment(&self) -> Option<SegmentSelector> {
+        self.segment.get()
+    }
+}
+
+fn parsed<T>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    let parsed = parse(value);
+    if parsed.is_none() {
+        log::warn!("ignoring \"{value}\" in the query, which is not understood");
+    }
+    parsed
+}
+
+/// Scrolls the element to the top of the window once it has been rendered, for a view that the
+/// link asked for.
+pub fn bring_into_view<E>(node_ref: NodeRef<E>)
+where
+    E: ElementType,
+    E::Output: JsCast + Clone + 'static,
+{
+    node_ref.on_load(|element| {
+        request_animation_frame(move || {
+            let options = ScrollIntoViewOptions::new();
+            options.set_block(ScrollLogicalPosition::Start);
+            element
+                .unchecked_ref::<Element>()
+                .scroll_into_view_with_scroll_into_view_options(&options);
+        });
+    });
+}
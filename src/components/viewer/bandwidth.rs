@@ -0,0 +1,388 @@
+use super::{BANDWIDTH_CHECK_CLASS, BITRATE_GRAPH_CLASS, VALIDATION_WARNING_CLASS};
+use crate::{
+    components::AppSettings,
+    utils::{
+        bandwidth::{
+            BandwidthMeasurement, bandwidth_outliers, deviation_percent, measure, sample_segments,
+            segment_bitrate,
+        },
+        byterange::RequestRange,
+        href::resolve_playlist_relative_url,
+        i18n::{Message, tr, tr_with},
+        network::{FetchError, fetch_array_buffer, fetch_content_length},
+        playlist_lines::variable_definitions,
+        query_codec::VariantContext,
+        settings::Settings,
+    },
+};
+use leptos::{either::Either, prelude::*};
+use std::collections::HashMap;
+
+const DEFAULT_SAMPLE_COUNT: usize = 5;
+const MAX_SAMPLE_COUNT: usize = 50;
+// Only the headers of each segment are requested for the graph, so many more can be graphed than
+// measured, though a long (e.g. event) playlist is still sampled.
+const MAX_GRAPHED_SEGMENTS: usize = 300;
+
+#[component]
+pub fn BandwidthCheck(
+    playlist: String,
+    variant: VariantContext,
+    imported_definitions: HashMap<String, String>,
+) -> impl IntoView {
+    if variant.bandwidth.is_none() && variant.average_bandwidth.is_none() {
+        return Either::Left(());
+    }
+    let (sample_count, set_sample_count) = signal(DEFAULT_SAMPLE_COUNT);
+    // The measurement only runs once requested, as it downloads every sampled segment.
+    let (requested_count, set_requested_count) = signal(None::<usize>);
+    let definitions = variable_definitions(&playlist, &imported_definitions);
+    let bitrate_graph = view! {
+        <BitrateGraph
+            playlist=playlist.clone()
+            definitions=definitions.clone()
+            bandwidth=variant.bandwidth
+        />
+    };
+    let settings = AppSettings::current();
+    let measurement = LocalResource::new(move || {
+        // The segment URLs are resolved up front because resolving relies on the router context,
+        // which is not available once the future has been suspended.
+        let requests = requested_count
+            .get()
+            .map(|count| segment_requests(&playlist, count, &definitions));
+        let settings = settings.get_untracked();
+        async move {
+            match requests {
+                Some(Ok(requests)) => Some(measure_segments(requests, &settings).await),
+                Some(Err(e)) => Some(Err(e)),
+                None => None,
+            }
+        }
+    });
+    Either::Right(view! {
+        <div class=BANDWIDTH_CHECK_CLASS>
+            <button class="button" on:click=move |_| set_requested_count.set(Some(sample_count.get()))>
+                {tr(Message::MeasureBandwidth)}
+            </button>
+            " "
+            {tr(Message::BandwidthSampleOf)}
+            " "
+            <input
+                type="number"
+                min="1"
+                max=MAX_SAMPLE_COUNT
+                prop:value=move || sample_count.get()
+                on:input=move |ev| {
+                    if let Ok(count) = event_target_value(&ev).parse::<usize>() {
+                        set_sample_count.set(count.clamp(1, MAX_SAMPLE_COUNT));
+                    }
+                }
+            />
+            " "
+            {tr(Message::BandwidthSampleSegments)}
+            <Suspense fallback=|| view! { <p>{tr(Message::DownloadingSegments)}</p> }>
+                {move || {
+                    measurement
+                        .get()
+                        .flatten()
+                        .map(|result| match result {
+                            Ok(measured) => {
+                                Either::Left(
+                                    view! { <MeasurementTable measured variant=variant.clone() /> },
+                                )
+                            }
+                            Err(e) => {
+                                Either::Right(
+                                    view! { <p class=VALIDATION_WARNING_CLASS>{e.to_string()}</p> },
+                                )
+                            }
+                        })
+                }}
+            </Suspense>
+            {bitrate_graph}
+        </div>
+    })
+}
+
+/// The bit rate of each segment across the playlist, from the size of the segment (given by its
+/// byte range, or else by the Content-Length of a HEAD request) over its EXTINF duration, so that
+/// the segments that exceed BANDWIDTH stand out.
+#[component]
+fn BitrateGraph(
+    playlist: String,
+    definitions: HashMap<String, String>,
+    bandwidth: Option<u64>,
+) -> impl IntoView {
+    let (requested, set_requested) = signal(false);
+    let settings = AppSettings::current();
+    let graphed = LocalResource::new(move || {
+        let requests = requested
+            .get()
+            .then(|| segment_requests(&playlist, MAX_GRAPHED_SEGMENTS, &definitions));
+        let settings = settings.get_untracked();
+        async move {
+            match requests {
+                Some(Ok(requests)) => Some(Ok(segment_bitrates(requests, &settings).await)),
+                Some(Err(e)) => Some(Err(e)),
+                None => None,
+            }
+        }
+    });
+    view! {
+        <div class=BITRATE_GRAPH_CLASS>
+            <button class="button" on:click=move |_| set_requested.set(true)>
+                {tr(Message::GraphSegmentBitrates)}
+            </button>
+            <Suspense fallback=|| view! { <p>{tr(Message::RequestingSegmentSizes)}</p> }>
+                {move || {
+                    graphed
+                        .get()
+                        .flatten()
+                        .map(|result| match result {
+                            Ok(segments) => {
+                                Either::Left(view! { <BitrateBars segments bandwidth /> })
+                            }
+                            Err(e) => {
+                                Either::Right(
+                                    view! { <p class=VALIDATION_WARNING_CLASS>{e.to_string()}</p> },
+                                )
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn BitrateBars(segments: Vec<GraphedSegment>, bandwidth: Option<u64>) -> impl IntoView {
+    let bitrates = segments
+        .iter()
+        .map(|segment| segment.bitrate.as_ref().ok().copied().flatten())
+        .collect::<Vec<_>>();
+    let outliers = bandwidth
+        .map(|bandwidth| bandwidth_outliers(&bitrates, bandwidth))
+        .unwrap_or_default();
+    let scale = bitrates
+        .iter()
+        .flatten()
+        .chain(&bandwidth)
+        .max()
+        .copied()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let percent = move |bitrate: u64| format!("{}%", bitrate as f64 / scale * 100.0);
+    let width = 100.0 / segments.len().max(1) as f64;
+    let unknown = segments
+        .iter()
+        .filter_map(|segment| match &segment.bitrate {
+            Ok(Some(_)) => None,
+            Ok(None) => Some(tr_with(
+                Message::SegmentWithoutContentLength,
+                &[("url", &segment.url)],
+            )),
+            Err(e) => Some(format!("{}: {}", segment.url, e.error())),
+        })
+        .collect::<Vec<_>>();
+    let bars = segments
+        .iter()
+        .zip(&bitrates)
+        .enumerate()
+        .filter_map(|(index, (segment, bitrate))| {
+            let bitrate = (*bitrate)?;
+            let title = tr_with(
+                Message::SegmentBitrateTitle,
+                &[
+                    ("url", &segment.url),
+                    ("duration", &format!("{:.3}", segment.duration)),
+                    ("bitrate", &bitrate.to_string()),
+                ],
+            );
+            Some(view! {
+                <div
+                    class="bitrate-bar"
+                    class:exceeds=outliers.contains(&index)
+                    title=title
+                    style:left=format!("{}%", index as f64 * width)
+                    style:width=format!("{width}%")
+                    style:height=percent(bitrate)
+                />
+            })
+        })
+        .collect_view();
+    let bandwidth_line = bandwidth.map(|bandwidth| {
+        view! {
+            <div
+                class="bitrate-bandwidth"
+                title=format!("BANDWIDTH={bandwidth}")
+                style:bottom=percent(bandwidth)
+            />
+        }
+    });
+    let summary = bandwidth.map(|bandwidth| {
+        let class = if outliers.is_empty() {
+            ""
+        } else {
+            VALIDATION_WARNING_CLASS
+        };
+        view! {
+            <p class=class>
+                {tr_with(
+                    Message::SegmentsExceedBandwidth,
+                    &[
+                        ("exceeding", &outliers.len().to_string()),
+                        ("count", &segments.len().to_string()),
+                        ("bandwidth", &bandwidth.to_string()),
+                    ],
+                )}
+            </p>
+        }
+    });
+    let unknown = (!unknown.is_empty()).then(|| {
+        view! {
+            <details>
+                <summary>
+                    {tr_with(Message::SegmentsNotSized, &[("count", &unknown.len().to_string())])}
+                </summary>
+                <ul>{unknown.into_iter().map(|line| view! { <li>{line}</li> }).collect_view()}</ul>
+            </details>
+        }
+    });
+    view! {
+        <div class="bitrate-bars">{bars} {bandwidth_line}</div>
+        {summary}
+        {unknown}
+    }
+}
+
+#[component]
+fn MeasurementTable(measured: BandwidthMeasurement, variant: VariantContext) -> impl IntoView {
+    // BANDWIDTH must be at least the peak segment bit rate, whereas AVERAGE-BANDWIDTH is expected
+    // to be close to (though not necessarily above) the measured average.
+    let rows = [
+        (
+            tr(Message::BandwidthPeak),
+            "BANDWIDTH",
+            measured.peak,
+            variant.bandwidth,
+            true,
+        ),
+        (
+            tr(Message::BandwidthAverage),
+            "AVERAGE-BANDWIDTH",
+            measured.average,
+            variant.average_bandwidth,
+            false,
+        ),
+    ];
+    view! {
+        <table>
+            <tr>
+                <th>{tr(Message::BandwidthMeasured)}</th>
+                <th>{tr(Message::BitsPerSecond)}</th>
+                <th>{tr(Message::BandwidthAdvertised)}</th>
+                <th>{tr(Message::BandwidthDeviation)}</th>
+            </tr>
+            {rows
+                .into_iter()
+                .map(|(label, attribute, measured, advertised, is_upper_bound)| {
+                    let deviation = advertised.and_then(|a| deviation_percent(measured, a));
+                    let exceeds = is_upper_bound && deviation.is_some_and(|d| d > 0.0);
+                    view! {
+                        <tr>
+                            <td>{label}</td>
+                            <td>{measured}</td>
+                            <td>
+                                {match advertised {
+                                    Some(advertised) => format!("{attribute}={advertised}"),
+                                    None => {
+                                        tr_with(
+                                            Message::AttributeNotSet,
+                                            &[("attribute", attribute)],
+                                        )
+                                    }
+                                }}
+                            </td>
+                            <td class=if exceeds { VALIDATION_WARNING_CLASS } else { "" }>
+                                {deviation.map(|d| format!("{d:+.1}%")).unwrap_or_default()}
+                            </td>
+                        </tr>
+                    }
+                })
+                .collect_view()}
+        </table>
+        <p>{tr(Message::BandwidthMeasuredNote)}</p>
+    }
+}
+
+struct SegmentRequest {
+    url: String,
+    byterange: Option<RequestRange>,
+    duration: f64,
+}
+
+fn segment_requests(
+    playlist: &str,
+    count: usize,
+    definitions: &HashMap<String, String>,
+) -> Result<Vec<SegmentRequest>, FetchError> {
+    sample_segments(playlist, count)
+        .into_iter()
+        .map(|sample| {
+            let Some(url) = resolve_playlist_relative_url(&sample.uri, definitions) else {
+                return Err(FetchError::Other {
+                    message: tr_with(Message::UnresolvedSegmentUri, &[("uri", &sample.uri)]),
+                });
+            };
+            Ok(SegmentRequest {
+                url,
+                byterange: sample
+                    .byterange
+                    .map(|(length, offset)| RequestRange::from_length_with_offset(length, offset)),
+                duration: sample.duration,
+            })
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+struct GraphedSegment {
+    url: String,
+    duration: f64,
+    /// The bit rate, which is `None` when the server gave no Content-Length for the segment.
+    bitrate: Result<Option<u64>, FetchError>,
+}
+
+async fn segment_bitrates(
+    requests: Vec<SegmentRequest>,
+    settings: &Settings,
+) -> Vec<GraphedSegment> {
+    let mut segments = Vec::new();
+    for request in requests {
+        let size = match request.byterange {
+            Some(byterange) => Ok(Some(byterange.end - byterange.start + 1)),
+            None => fetch_content_length(request.url.clone(), settings).await,
+        };
+        segments.push(GraphedSegment {
+            bitrate: size.map(|size| size.and_then(|size| segment_bitrate(request.duration, size))),
+            url: request.url,
+            duration: request.duration,
+        });
+    }
+    segments
+}
+
+async fn measure_segments(
+    requests: Vec<SegmentRequest>,
+    settings: &Settings,
+) -> Result<BandwidthMeasurement, FetchError> {
+    let mut measured_segments = Vec::new();
+    for request in requests {
+        let response = fetch_array_buffer(request.url, request.byterange, settings).await?;
+        measured_segments.push((request.duration, response.response_body.len() as u64));
+    }
+    measure(&measured_segments).ok_or_else(|| FetchError::Other {
+        message: String::from(tr(Message::NoSegmentsToMeasure)),
+    })
+}
@@ -0,0 +1,77 @@
+use super::BOX_TREE_CONTROLS_CLASS;
+use crate::utils::i18n::{Message, tr};
+use leptos::prelude::*;
+use std::collections::HashSet;
+
+/// The paths of the boxes that are collapsed in the list of boxes. Opening another segment
+/// re-creates the viewer, so this is provided as context by the page, which clears it whenever the
+/// playlist changes; the segments of one rendition then share how their boxes are expanded.
+#[derive(Clone, Copy, Default)]
+pub struct BoxTreeExpansion(RwSignal<HashSet<String>>);
+
+impl BoxTreeExpansion {
+    pub fn clear(&self) {
+        self.0.set(HashSet::new());
+    }
+
+    pub(super) fn is_collapsed(&self, path: &str) -> bool {
+        self.0.with(|collapsed| collapsed.contains(path))
+    }
+
+    pub(super) fn toggle(&self, path: &str) {
+        self.0.update(|collapsed| {
+            if !collapsed.remove(path) {
+                collapsed.insert(path.to_string());
+            }
+        });
+    }
+}
+
+/// Expands or collapses every box in the list at once, or expands it down to a chosen depth.
+#[component]
+pub fn BoxTreeControls(
+    expansion: BoxTreeExpansion,
+    /// The path and depth of each box that has children.
+    containers: Vec<(String, usize)>,
+) -> Option<impl IntoView> {
+    let max_depth = containers.iter().map(|(_, depth)| *depth).max()?;
+    let containers = StoredValue::new(containers);
+    // Expanding to a depth shows that many levels of boxes, collapsing the containers at the
+    // deepest level shown.
+    let expand_to = move |depth: usize| {
+        let collapsed = containers.with_value(|containers| {
+            containers
+                .iter()
+                .filter(|(_, container_depth)| *container_depth + 1 >= depth)
+                .map(|(path, _)| path.clone())
+                .collect()
+        });
+        expansion.0.set(collapsed);
+    };
+    let depth = RwSignal::new(1);
+    Some(view! {
+        <div class=BOX_TREE_CONTROLS_CLASS>
+            <button class="button" type="button" on:click=move |_| expansion.clear()>
+                {tr(Message::ExpandAll)}
+            </button>
+            <button class="button" type="button" on:click=move |_| expand_to(1)>
+                {tr(Message::CollapseAll)}
+            </button>
+            <button class="button" type="button" on:click=move |_| expand_to(depth.get())>
+                {tr(Message::ExpandToDepth)}
+            </button>
+            <input
+                type="number"
+                min="1"
+                max=max_depth + 1
+                aria-label=tr(Message::Depth)
+                prop:value=move || depth.get()
+                on:input=move |ev| {
+                    if let Ok(value) = event_target_value(&ev).parse::<usize>() {
+                        depth.set(value.clamp(1, max_depth + 1));
+                    }
+                }
+            />
+        </div>
+    })
+}
@@ -0,0 +1,118 @@
+use super::playlist::line_anchor_id;
+use crate::{
+    components::LocalPlaylist,
+    utils::{
+        href::{
+            PLAYLIST_URL_QUERY_NAME, SUPPLEMENTAL_VIEW_QUERY_NAME, VARIANT_QUERY_NAME,
+            parent_playlist, playlist_name, query_value_from_leptos_url, replace_query_value,
+        },
+        i18n::{Message, tr, tr_with},
+        query_codec::{SupplementalViewQueryContext, percent_decode},
+    },
+};
+use leptos::prelude::*;
+use leptos_router::hooks::use_url;
+
+/// The levels of the stream that lead to what is being viewed, built from the query, where each
+/// level above the current one links back up to it. Going back up keeps the rest of the query (the
+/// imported definitions among it), so the playlist is shown just as it was before.
+#[component]
+pub fn Breadcrumbs() -> impl IntoView {
+    let url = use_url();
+    let local_playlist = use_context::<LocalPlaylist>();
+    move || {
+        let url = url.read();
+        let playlist_url = query_value_from_leptos_url(&url, PLAYLIST_URL_QUERY_NAME)
+            .map(|value| percent_decode(&value).to_string());
+        // A playlist opened from a file is named by the file rather than by its base URL.
+        let name = match local_playlist.and_then(|local| local.get()) {
+            Some(file) => file.name,
+            None => playlist_name(playlist_url.as_deref()?),
+        };
+        let supplemental_label = query_value_from_leptos_url(&url, SUPPLEMENTAL_VIEW_QUERY_NAME)
+            .and_then(|value| {
+                SupplementalViewQueryContext::try_from(percent_decode(&value).as_ref()).ok()
+            })
+            .map(|context| context.label());
+        // The way back up goes to the line of the Multivariant Playlist that links down to here,
+        // which is highlighted as the target of the link.
+        let parent = parent_playlist(url.search());
+        // A playlist reached from a Multivariant Playlist (or through an EXT-X-STREAM-INF, which
+        // carries the attributes of its variant) is a Media Playlist, otherwise there is no telling
+        // what level it is from the query alone.
+        let playlist_crumb = if parent.is_some()
+            || query_value_from_leptos_url(&url, VARIANT_QUERY_NAME).is_some()
+        {
+            Message::MediaPlaylistCrumb
+        } else {
+            Message::PlaylistCrumb
+        };
+        let playlist_crumb = tr_with(playlist_crumb, &[("name", &name)]);
+        let playlist_crumb = if supplemental_label.is_some() {
+            let href = replace_query_value(url.search(), SUPPLEMENTAL_VIEW_QUERY_NAME, None);
+            view! {
+                <a href=href title=playlist_url>
+                    {playlist_crumb}
+                </a>
+            }
+            .into_any()
+        } else {
+            view! {
+                <span aria-current="page" title=playlist_url>
+                    {playlist_crumb}
+                </span>
+            }
+            .into_any()
+        };
+        let parent_href = parent
+            .as_ref()
+            .map(|parent| format!("{}#{}", parent.href, line_anchor_id(parent.line_number)));
+        let parent_crumb = parent.zip(parent_href.clone()).map(|(parent, href)| {
+            let name = playlist_name(&parent.playlist_url);
+            view! {
+                <li>
+                    <a
+                        href=href
+                        title=tr_with(
+                            Message::ParentPlaylistTitle,
+                            &[
+                                ("url", &parent.playlist_url),
+                                ("line", &parent.line_number.to_string()),
+                            ],
+                        )
+                    >
+                        {tr_with(Message::MultivariantPlaylistCrumb, &[("name", &name)])}
+                    </a>
+                </li>
+            }
+        });
+        let back_link = parent_href.map(|href| {
+            view! {
+                <a
+                    class="button"
+                    href=href
+                    title=tr(Message::BackToMultivariantTitle)
+                >
+                    {tr(Message::BackToMultivariant)}
+                </a>
+            }
+        });
+        let supplemental_crumb = supplemental_label.map(|label| {
+            view! {
+                <li>
+                    <span aria-current="page">{label}</span>
+                </li>
+            }
+        });
+        Some(view! {
+            <nav class="breadcrumbs" aria-label="breadcrumbs">
+                <ol>
+                    {parent_crumb}
+                    <li>{playlist_crumb}</li>
+                    {supplemental_crumb}
+                </ol>
+                {back_link}
+            </nav>
+        })
+    }
+}
@@ -0,0 +1,130 @@
+use super::COLLAPSED_SEGMENTS_CLASS;
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    segment_runs::{SegmentRun, collapsible_runs, format_duration},
+};
+use leptos::prelude::*;
+use std::ops::Range;
+
+/// The runs of plain segments that are collapsed in the playlist, until they are expanded.
+#[derive(Clone, Copy)]
+pub struct CollapsedRuns {
+    runs: Memo<Vec<SegmentRun>>,
+    /// The index of the run that each line belongs to, if any.
+    line_runs: Memo<Vec<Option<usize>>>,
+    /// The Media Sequence Numbers of the runs that have been expanded, by which a run is kept
+    /// expanded while segments are added to it and removed from it as the playlist is reloaded.
+    expanded: RwSignal<Vec<Range<u64>>>,
+}
+
+impl CollapsedRuns {
+    /// Runs are left expanded when they include the highlighted segment or any of the `diagnosed`
+    /// lines (given as 0-based indexes), so that those are never hidden.
+    pub fn new(
+        playlist: Signal<String>,
+        highlighted_segment: Option<u64>,
+        diagnosed: Memo<Vec<usize>>,
+    ) -> Self {
+        let runs = Memo::new(move |_| {
+            let runs = playlist.with(|playlist| collapsible_runs(playlist));
+            diagnosed.with(|diagnosed| {
+                runs.into_iter()
+                    .filter(|run| {
+                        !highlighted_segment
+                            .is_some_and(|segment| run.media_sequences.contains(&segment))
+                            && !diagnosed
+                                .iter()
+                                .any(|line| (run.start..run.start + run.line_count).contains(line))
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+        let line_runs = Memo::new(move |_| {
+            runs.with(|runs| {
+                let line_count = runs.last().map_or(0, |run| run.start + run.line_count);
+                let mut line_runs = vec![None; line_count];
+                for (index, run) in runs.iter().enumerate() {
+                    for line_run in line_runs.iter_mut().skip(run.start).take(run.line_count) {
+                        *line_run = Some(index);
+                    }
+                }
+                line_runs
+            })
+        });
+        Self {
+            runs,
+            line_runs,
+            expanded: RwSignal::new(Vec::new()),
+        }
+    }
+
+    /// Whether the line at the 0-based `index` is collapsed.
+    pub fn is_collapsed(&self, index: usize) -> bool {
+        self.media_sequences_of(index)
+            .is_some_and(|media_sequences| !self.is_expanded(&media_sequences))
+    }
+
+    /// Expands the run that the line at the 0-based `index` belongs to, if any.
+    pub fn reveal(&self, index: usize) {
+        if let Some(media_sequences) = self.media_sequences_of(index) {
+            self.expand(media_sequences);
+        }
+    }
+
+    // The Media Sequence Numbers of the run that the line at the 0-based `index` belongs to.
+    fn media_sequences_of(&self, index: usize) -> Option<Range<u64>> {
+        let run = self
+            .line_runs
+            .with(|line_runs| line_runs.get(index).copied().flatten())?;
+        self.runs
+            .with(|runs| runs.get(run).map(|run| run.media_sequences.clone()))
+    }
+
+    fn is_expanded(&self, media_sequences: &Range<u64>) -> bool {
+        self.expanded.with(|expanded| {
+            expanded
+                .iter()
+                .any(|run| run.start < media_sequences.end && media_sequences.start < run.end)
+        })
+    }
+
+    fn expand(&self, media_sequences: Range<u64>) {
+        self.expanded
+            .update(|expanded| expanded.push(media_sequences));
+    }
+
+    /// The run that starts at the 0-based line `index`, if any.
+    fn starting_at(&self, index: usize) -> Option<SegmentRun> {
+        self.runs
+            .with(|runs| runs.iter().find(|run| run.start == index).cloned())
+    }
+}
+
+/// Stands in for the collapsed run that starts at the 0-based line `index`, if any, which expands
+/// the run when clicked.
+#[component]
+pub fn CollapsedSegments(runs: CollapsedRuns, #[prop(into)] index: Signal<usize>) -> impl IntoView {
+    move || {
+        let run = runs
+            .starting_at(index.get())
+            .filter(|run| !runs.is_expanded(&run.media_sequences))?;
+        let label = tr_with(
+            Message::CollapsedSegments,
+            &[
+                ("count", &run.segments().to_string()),
+                ("duration", &format_duration(run.duration)),
+            ],
+        );
+        let expand = move |_| runs.expand(run.media_sequences.clone());
+        Some(view! {
+            <button
+                class=COLLAPSED_SEGMENTS_CLASS
+                type="button"
+                title=tr(Message::CollapsedSegmentsTitle)
+                on:click=expand
+            >
+                {label}
+            </button>
+        })
+    }
+}
@@ -1,7 +1,10 @@
 use super::{SUPPLEMENTAL_VIEW_CLASS, UNDERLINED};
 use crate::{
     components::viewer::{SPACER_BOTTOM, error::ViewerError},
-    utils::href::{asset_list_href, media_playlist_href},
+    utils::{
+        href::{asset_list_href, media_playlist_href},
+        i18n::{Message, tr},
+    },
 };
 use leptos::{either::Either, prelude::*};
 use serde_json::Value;
@@ -50,8 +53,7 @@ pub fn DaterangeScheduleView(json: String) -> impl IntoView {
                 } else {
                     Either::Right(
                         view! {
-                            <ViewerError error="Could not deserialize DATERANGES from JSON"
-                                .to_string() />
+                            <ViewerError error=tr(Message::DaterangesJsonError).to_string() />
                             <p class=UNDERLINED></p>
                         },
                     )
@@ -0,0 +1,143 @@
+use super::{DECRYPTION_CLASS, VALIDATION_ERROR_CLASS, network::NetworkLog};
+use crate::{
+    components::AppSettings,
+    utils::{
+        aes::decrypt_aes_128_cbc,
+        encryption::key_bytes,
+        hex::encode_hex,
+        i18n::{Message, tr, tr_with},
+        network::{FetchArrayBufferResonse, RequestOptions, fetch_array_buffer},
+    },
+};
+use leptos::{prelude::*, task::spawn_local};
+use std::sync::Arc;
+
+/// What a segment encrypted with an AES-128 key is decrypted with.
+#[derive(Debug, Clone)]
+pub struct Aes128Decryption {
+    /// The URI of the EXT-X-KEY, resolved against the playlist.
+    pub key_url: Option<String>,
+    pub iv: Option<[u8; 16]>,
+    /// Where the IV comes from.
+    pub iv_source: String,
+}
+
+/// A segment encrypted with AES-128, which is passed to `content` once it is decrypted with a key
+/// that is pasted in or fetched from the URI of its EXT-X-KEY. Fetching the key is left to the
+/// user, as key servers often expect credentials and the key is then on show.
+#[component]
+pub fn EncryptedSegment<F>(
+    response: Arc<FetchArrayBufferResonse>,
+    decryption: Aes128Decryption,
+    content: F,
+) -> impl IntoView
+where
+    F: Fn(Arc<FetchArrayBufferResonse>) -> AnyView + Send + 'static,
+{
+    let network_log = use_context::<NetworkLog>();
+    let settings = AppSettings::current();
+    let Aes128Decryption {
+        key_url,
+        iv,
+        iv_source,
+    } = decryption;
+    let key = RwSignal::new(String::new());
+    let error = RwSignal::new(None::<String>);
+    let decrypted = RwSignal::new(None::<Arc<FetchArrayBufferResonse>>);
+    let encrypted = StoredValue::new(response);
+    let decrypt = move || {
+        let Some(key) = key_bytes(&key.get_untracked()) else {
+            error.set(Some(String::from(tr(Message::DecryptionKeyInvalid))));
+            return;
+        };
+        let Some(iv) = iv else {
+            return;
+        };
+        let decryption = encrypted.with_value(|response| {
+            decrypt_aes_128_cbc(&key, &iv, &response.response_body).map(|data| {
+                // The decrypted segment takes the place of the encrypted one, which is left as it
+                // was for the segment cache.
+                FetchArrayBufferResonse {
+                    response_body: data,
+                    content_type: response.content_type.clone(),
+                    url: response.url.clone(),
+                    headers: response.headers.clone(),
+                    timing: response.timing,
+                    body_truncated: response.body_truncated,
+                }
+            })
+        });
+        match decryption {
+            Ok(response) => {
+                error.set(None);
+                decrypted.set(Some(Arc::new(response)));
+            }
+            Err(e) => error.set(Some(tr_with(
+                Message::DecryptionFailed,
+                &[("error", &e.to_string())],
+            ))),
+        }
+    };
+    let fetch_key = key_url.map(|url| {
+        let fetch = move |_| {
+            let url = url.clone();
+            let settings = settings.get_untracked();
+            spawn_local(async move {
+                // The key is fetched directly, rather than handed to a third-party proxy.
+                let options = RequestOptions::from(&settings).direct();
+                match fetch_array_buffer(url, None, options).await {
+                    Ok(response) => {
+                        if let Some(log) = network_log {
+                            log.record(tr(Message::NetworkKey), response.headers, response.timing);
+                        }
+                        match response.response_body.len() {
+                            16 => {
+                                key.set(encode_hex(&response.response_body));
+                                error.set(None);
+                            }
+                            length => error.set(Some(tr_with(
+                                Message::FetchedKeyLength,
+                                &[("length", &length.to_string())],
+                            ))),
+                        }
+                    }
+                    Err(e) => error.set(Some(e.error())),
+                }
+            });
+        };
+        view! {
+            <button class="button" type="button" on:click=fetch>
+                {tr(Message::FetchKey)}
+            </button>
+        }
+    });
+    let iv_text = match iv {
+        Some(iv) => tr_with(
+            Message::DecryptionIv,
+            &[("iv", &encode_hex(&iv)), ("source", &iv_source)],
+        ),
+        None => tr_with(Message::DecryptionIvMissing, &[("source", &iv_source)]),
+    };
+    view! {
+        <div class=DECRYPTION_CLASS>
+            <p>{tr(Message::SegmentEncrypted)}</p>
+            <p>{iv_text}</p>
+            <label>
+                {tr(Message::DecryptionKey)}
+                <input
+                    type="text"
+                    spellcheck="false"
+                    placeholder=tr(Message::DecryptionKeyPlaceholder)
+                    prop:value=move || key.get()
+                    on:input=move |ev| key.set(event_target_value(&ev))
+                />
+            </label>
+            {fetch_key}
+            <button class="button" type="button" disabled=iv.is_none() on:click=move |_| decrypt()>
+                {tr(Message::Decrypt)}
+            </button>
+            {move || error.get().map(|error| view! { <p class=VALIDATION_ERROR_CLASS>{error}</p> })}
+        </div>
+        {move || decrypted.get().map(&content)}
+    }
+}
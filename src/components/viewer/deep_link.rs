@@ -0,0 +1,115 @@
+use super::{
+    DEEP_LINK_CLASS, PLAYLIST_LINE_CLASS,
+    playlist::{line_anchor_id, line_number_from_anchor_id},
+};
+use crate::{
+    components::LocalPlaylist,
+    utils::{
+        href::COMPRESSED_QUERY_QUERY_NAME,
+        i18n::{Message, tr},
+        query_codec::compress_query,
+        settings::FeatureToggle,
+        storage::{load_flag, save_flag},
+    },
+};
+use leptos::{html::Div, prelude::*};
+use leptos_router::hooks::use_location;
+use leptos_use::{UseClipboardReturn, use_clipboard};
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+
+// Whether copied links have their query compressed, which is remembered as it is a preference for
+// where the links are shared to.
+const COMPRESSED_LINK_KEY: &str = FeatureToggle::CompressedLinks.key();
+
+/// Copies a link to exactly what is being viewed. The query already carries the playlist URL, the
+/// definitions and the supplemental (and pinned) views, so the link only adds the line that is at
+/// the top of the window, which the viewer scrolls to when the link is opened. The query can be
+/// compressed for when it is too long to share comfortably.
+#[component]
+pub fn CopyLinkButton() -> impl IntoView {
+    let UseClipboardReturn {
+        is_supported,
+        text: _,
+        copied,
+        copy,
+    } = use_clipboard();
+    // A playlist opened from a file or pasted in is only in this browser.
+    let local_playlist = use_context::<LocalPlaylist>();
+    let is_local = move || local_playlist.is_some_and(|local| local.get().is_some());
+    let location = use_location();
+    let (pathname, search) = (location.pathname, location.search);
+    let container = NodeRef::<Div>::new();
+    let compressed = RwSignal::new(load_flag(COMPRESSED_LINK_KEY));
+    Effect::watch(
+        move || compressed.get(),
+        move |compressed, _, _| save_flag(COMPRESSED_LINK_KEY, *compressed),
+        false,
+    );
+    let link = move || {
+        let origin = window().location().origin().unwrap_or_default();
+        let hash = container
+            .get_untracked()
+            .and_then(|container| line_at_top(&container))
+            .map(|line_number| format!("#{}", line_anchor_id(line_number)))
+            .unwrap_or_default();
+        let search = search.get_untracked();
+        let query = if search.is_empty() {
+            String::new()
+        } else if compressed.get_untracked() {
+            format!("?{COMPRESSED_QUERY_QUERY_NAME}={}", compress_query(&search))
+        } else {
+            format!("?{search}")
+        };
+        format!("{origin}{}{query}{hash}", pathname.get_untracked())
+    };
+    view! {
+        <div node_ref=container class=DEEP_LINK_CLASS>
+            <Show when=move || is_supported.get() && !is_local()>
+                <button
+                    class="button"
+                    type="button"
+                    title=tr(Message::CopyLinkTitle)
+                    on:click={
+                        let copy = copy.clone();
+                        move |_| copy(&link())
+                    }
+                >
+                    {move || {
+                        if copied.get() {
+                            tr(Message::CopyLinkCopied)
+                        } else {
+                            tr(Message::CopyLink)
+                        }
+                    }}
+                </button>
+                <label title=tr(Message::CompressLinkTitle)>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || compressed.get()
+                        on:change=move |ev| compressed.set(event_target_checked(&ev))
+                    />
+                    {tr(Message::CompressLink)}
+                </label>
+            </Show>
+        </div>
+    }
+}
+
+/// The 1-based number of the playlist line at the top of the window, looking past anything pinned
+/// over it (such as the segment context header), if the playlist has been scrolled to. `container`
+/// is any element in line with the playlist.
+pub fn line_at_top(container: &Element) -> Option<usize> {
+    let left = container.get_bounding_client_rect().left() as f32 + 1.0;
+    document()
+        .elements_from_point(left, 1.0)
+        .iter()
+        .filter_map(|element| element.dyn_into::<Element>().ok())
+        .find_map(|element| {
+            element
+                .closest(&format!(".{PLAYLIST_LINE_CLASS}"))
+                .ok()
+                .flatten()
+        })
+        .and_then(|line| line_number_from_anchor_id(&line.id()))
+}
@@ -0,0 +1,31 @@
+use super::ERROR_TOAST_CLASS;
+use crate::utils::i18n::{Message, tr};
+use leptos::prelude::*;
+
+/// A banner for an error that does not take the place of what is shown below it, with the details
+/// of the error on hover, and a way to retry the request that failed or to dismiss the banner.
+#[component]
+pub fn ErrorToast(
+    error: String,
+    #[prop(optional_no_strip)] extra_info: Option<String>,
+    /// What is shown in place of the resource that failed, if anything.
+    #[prop(optional_no_strip)]
+    fallback_note: Option<String>,
+    on_retry: impl Fn() + 'static,
+) -> impl IntoView {
+    let dismissed = RwSignal::new(false);
+    view! {
+        <div class=ERROR_TOAST_CLASS class:hidden=move || dismissed.get() role="alert">
+            <div>
+                <p title=extra_info>{error}</p>
+                {fallback_note.map(|note| view! { <p class="error-toast-note">{note}</p> })}
+            </div>
+            <button class="button" type="button" on:click=move |_| on_retry()>
+                {tr(Message::Retry)}
+            </button>
+            <button class="button" type="button" on:click=move |_| dismissed.set(true)>
+                {tr(Message::Dismiss)}
+            </button>
+        </div>
+    }
+}
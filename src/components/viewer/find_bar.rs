@@ -0,0 +1,120 @@
+use super::{FIND_BAR_CLASS, line_window::LineWindow};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    playlist_lines::search,
+};
+use leptos::{ev::KeyboardEvent, prelude::*};
+
+/// A search of the playlist, with the lines that match it and the match that has been stepped to.
+#[derive(Clone, Copy)]
+pub struct PlaylistFind {
+    query: RwSignal<String>,
+    lines: Memo<Vec<usize>>,
+    current: RwSignal<usize>,
+}
+
+impl PlaylistFind {
+    pub fn new(playlist: Signal<String>) -> Self {
+        let query = RwSignal::new(String::new());
+        Self {
+            query,
+            lines: Memo::new(move |_| playlist.with(|playlist| search(playlist, &query.read()))),
+            current: RwSignal::new(0),
+        }
+    }
+
+    /// Whether the 1-based `line_number` matches. Reading this subscribes to the matches.
+    pub fn is_match(&self, line_number: usize) -> bool {
+        self.lines
+            .with(|lines| lines.binary_search(&line_number).is_ok())
+    }
+
+    /// Whether the 1-based `line_number` is the match that has been stepped to.
+    pub fn is_current(&self, line_number: usize) -> bool {
+        self.current_line() == Some(line_number)
+    }
+
+    fn current_line(&self) -> Option<usize> {
+        let current = self.current.get();
+        self.lines.with(|lines| lines.get(current).copied())
+    }
+
+    fn step(&self, forward: bool) {
+        let count = self.lines.with_untracked(Vec::len);
+        if count == 0 {
+            return;
+        }
+        self.current.update(|current| {
+            *current = if forward {
+                (*current + 1) % count
+            } else {
+                (*current + count - 1) % count
+            }
+        });
+    }
+}
+
+/// Searches the playlist for the text entered, which steps through the matching lines.
+#[component]
+pub fn FindBar(find: PlaylistFind, line_window: LineWindow) -> impl IntoView {
+    let PlaylistFind { query, .. } = find;
+    // Each new search starts again from its first match.
+    Effect::watch(
+        move || query.get(),
+        move |_, _, _| find.current.set(0),
+        false,
+    );
+    // A reload of the playlist that moves the match along does not scroll to it again.
+    Effect::new(move |_| {
+        query.track();
+        let current = find.current.get();
+        if let Some(line_number) = find
+            .lines
+            .with_untracked(|lines| lines.get(current).copied())
+        {
+            line_window.scroll_to(line_number);
+        }
+    });
+    let on_keydown = move |ev: KeyboardEvent| {
+        if ev.key() == "Enter" {
+            ev.prevent_default();
+            find.step(!ev.shift_key());
+        }
+    };
+    let summary = move || {
+        let count = find.lines.with(Vec::len);
+        if query.with(|query| query.trim().is_empty()) {
+            String::new()
+        } else if count == 0 {
+            String::from(tr(Message::FindNoMatches))
+        } else {
+            tr_with(
+                Message::FindMatch,
+                &[
+                    ("current", &(find.current.get() + 1).to_string()),
+                    ("count", &count.to_string()),
+                ],
+            )
+        }
+    };
+    view! {
+        <div class=FIND_BAR_CLASS>
+            <input
+                type="search"
+                placeholder=tr(Message::FindPlaceholder)
+                aria-label=tr(Message::FindLabel)
+                title=tr(Message::FindTitle)
+                prop:value=move || query.get()
+                on:input=move |ev| query.set(event_target_value(&ev))
+                on:keydown=on_keydown
+            />
+            <span>{summary}</span>
+            <button class="button" type="button" on:click=move |_| find.step(false)>
+                {tr(Message::Previous)}
+            </button>
+            <button class="button" type="button" on:click=move |_| find.step(true)>
+                {tr(Message::Next)}
+            </button>
+        </div>
+    }
+}
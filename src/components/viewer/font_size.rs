@@ -0,0 +1,90 @@
+use super::FONT_SIZE_CONTROL_CLASS;
+use crate::utils::{
+    i18n::{Message, tr},
+    storage::{load_item, save_item},
+};
+use leptos::prelude::*;
+
+// The size of the text of the viewer, as a percentage of its usual size, which is remembered as it
+// depends on the reader (and their screen) rather than on what is being viewed.
+const FONT_SIZE_KEY: &str = "viewer_font_size";
+const FONT_SIZES: [u32; 8] = [75, 90, 100, 110, 125, 150, 175, 200];
+const DEFAULT_FONT_SIZE: u32 = 100;
+
+/// The size of the text of the playlist and box views, which is shared (through context) by every
+/// control for it within a viewer.
+#[derive(Clone, Copy)]
+pub struct FontSize(RwSignal<u32>);
+
+impl FontSize {
+    pub fn load() -> Self {
+        let percent = load_item(FONT_SIZE_KEY)
+            .and_then(|value| value.parse().ok())
+            .filter(|percent| FONT_SIZES.contains(percent))
+            .unwrap_or(DEFAULT_FONT_SIZE);
+        let percent = RwSignal::new(percent);
+        Effect::watch(
+            move || percent.get(),
+            move |percent, _, _| save_item(FONT_SIZE_KEY, &percent.to_string()),
+            false,
+        );
+        Self(percent)
+    }
+
+    /// The CSS custom property that the text sizes of the viewer are scaled by.
+    pub fn style(&self) -> String {
+        format!("--viewer-font-scale: {}", f64::from(self.0.get()) / 100.0)
+    }
+
+    fn step(&self, by: isize) {
+        self.0.update(|percent| {
+            let index = FONT_SIZES
+                .iter()
+                .position(|size| size == percent)
+                .unwrap_or_default();
+            let index = index.saturating_add_signed(by).min(FONT_SIZES.len() - 1);
+            *percent = FONT_SIZES[index];
+        });
+    }
+}
+
+/// Buttons to make the text of the viewer smaller or larger, or to reset it, which are left out
+/// where there is no viewer to apply them to.
+#[component]
+pub fn FontSizeControl() -> Option<impl IntoView> {
+    let font_size = use_context::<FontSize>()?;
+    let FontSize(percent) = font_size;
+    Some(view! {
+        <div class=FONT_SIZE_CONTROL_CLASS role="group" aria-label=tr(Message::TextSize)>
+            <button
+                class="button"
+                type="button"
+                title=tr(Message::SmallerTextTitle)
+                aria-label=tr(Message::SmallerText)
+                disabled=move || percent.get() == FONT_SIZES[0]
+                on:click=move |_| font_size.step(-1)
+            >
+                "A−"
+            </button>
+            <button
+                class="button"
+                type="button"
+                title=tr(Message::ResetTextSizeTitle)
+                aria-label=tr(Message::ResetTextSize)
+                on:click=move |_| percent.set(DEFAULT_FONT_SIZE)
+            >
+                {move || format!("{}%", percent.get())}
+            </button>
+            <button
+                class="button"
+                type="button"
+                title=tr(Message::LargerTextTitle)
+                aria-label=tr(Message::LargerText)
+                disabled=move || percent.get() == FONT_SIZES[FONT_SIZES.len() - 1]
+                on:click=move |_| font_size.step(1)
+            >
+                "A+"
+            </button>
+        </div>
+    })
+}
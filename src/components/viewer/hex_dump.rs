@@ -0,0 +1,140 @@
+use super::HEX_DUMP_CLASS;
+use crate::utils::{
+    hex::{HEX_DUMP_LINE_LENGTH, encode_hex, hex_dump_lines, printable_char},
+    i18n::{Message, tr, tr_with},
+    network::SharedBytes,
+};
+use leptos::prelude::*;
+use std::ops::Range;
+
+/// The number of bytes shown at a time, as a segment can be far too large to show in full.
+const PAGE_LENGTH: usize = HEX_DUMP_LINE_LENGTH * 64;
+/// The number of colours that regions cycle through, as styled by `hex-region-{n}`.
+const REGION_COLORS: usize = 6;
+
+/// The bytes of a segment by offset, coloured by the box that each belongs to. Clicking a byte
+/// selects its box, and selecting a box (from the list of boxes) brings its bytes into view.
+#[component]
+pub fn HexDump(
+    data: SharedBytes,
+    /// The bytes that belong to each box (and not to its children), in the order of the boxes.
+    regions: Vec<Range<usize>>,
+    /// The index of the selected box.
+    highlighted: ReadSignal<usize>,
+    on_select: impl Fn(usize) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let shown = RwSignal::new(false);
+    let page = RwSignal::new(0);
+    let page_count = data.len().div_ceil(PAGE_LENGTH).max(1);
+    let length = data.len();
+    let data = StoredValue::new(data);
+    let regions = StoredValue::new(regions);
+    Effect::new(move || {
+        let Some(region) = regions.with_value(|regions| regions.get(highlighted.get()).cloned())
+        else {
+            return;
+        };
+        let first_page = region.start / PAGE_LENGTH;
+        let last_page = region.end.saturating_sub(1).max(region.start) / PAGE_LENGTH;
+        if !(first_page..=last_page).contains(&page.get_untracked()) {
+            page.set(first_page);
+        }
+    });
+    let lines = move || {
+        let start = page.get() * PAGE_LENGTH;
+        let selected = highlighted.get();
+        let lines = data.with_value(|data| {
+            regions.with_value(|regions| hex_dump_lines(data, start..start + PAGE_LENGTH, regions))
+        });
+        lines
+            .into_iter()
+            .map(|line| {
+                let bytes = line
+                    .bytes
+                    .iter()
+                    .map(|(byte, region)| {
+                        let region = *region;
+                        let class = region
+                            .map(|region| format!("hex-region-{}", region % REGION_COLORS))
+                            .unwrap_or_default();
+                        view! {
+                            <span
+                                class=class
+                                class:highlighted=region == Some(selected)
+                                on:click=move |_| {
+                                    if let Some(region) = region {
+                                        on_select(region);
+                                    }
+                                }
+                            >
+                                {encode_hex(&[*byte])}
+                            </span>
+                            " "
+                        }
+                    })
+                    .collect_view();
+                let text = line
+                    .bytes
+                    .iter()
+                    .map(|(byte, _)| printable_char(*byte))
+                    .collect::<String>();
+                view! {
+                    <div>
+                        <span class="hex-offset">{format!("{:08x}", line.offset)}</span>
+                        "  "
+                        <span class="hex-bytes">{bytes}</span>
+                        " "
+                        <span class="hex-text">{text}</span>
+                    </div>
+                }
+            })
+            .collect_view()
+    };
+    view! {
+        <div class=HEX_DUMP_CLASS>
+            <button
+                class="button"
+                type="button"
+                aria-pressed=move || shown.get().to_string()
+                on:click=move |_| shown.update(|shown| *shown = !*shown)
+            >
+                {move || tr(if shown.get() { Message::HideHexDump } else { Message::ShowHexDump })}
+            </button>
+            <Show when=move || shown.get()>
+                <div class="hex-pages">
+                    <button
+                        class="button"
+                        type="button"
+                        disabled=move || page.get() == 0
+                        on:click=move |_| page.update(|page| *page = page.saturating_sub(1))
+                    >
+                        {tr(Message::Previous)}
+                    </button>
+                    {move || {
+                        let start = page.get() * PAGE_LENGTH;
+                        let end = (start + PAGE_LENGTH).min(length);
+                        tr_with(
+                            Message::HexDumpBytes,
+                            &[
+                                ("start", &start.to_string()),
+                                ("end", &end.saturating_sub(1).to_string()),
+                                ("length", &length.to_string()),
+                            ],
+                        )
+                    }}
+                    <button
+                        class="button"
+                        type="button"
+                        disabled=move || page.get() + 1 >= page_count
+                        on:click=move |_| {
+                            page.update(|page| *page = (*page + 1).min(page_count - 1))
+                        }
+                    >
+                        {tr(Message::Next)}
+                    </button>
+                </div>
+                <pre>{lines}</pre>
+            </Show>
+        </div>
+    }
+}
@@ -1,14 +1,15 @@
 use super::{IMAGE_VIEW_CLASS, SUPPLEMENTAL_VIEW_CLASS};
+use crate::utils::network::SharedBytes;
 use base64::prelude::*;
 use leptos::prelude::*;
 
 #[component]
-pub fn ImageViewer(contents: Vec<u8>, content_type: String) -> impl IntoView {
+pub fn ImageViewer(contents: SharedBytes, content_type: String) -> impl IntoView {
     view! {
         <div class=SUPPLEMENTAL_VIEW_CLASS>
             <img
                 class=IMAGE_VIEW_CLASS
-                src=format!("data:{content_type};base64,{}", BASE64_STANDARD.encode(&contents))
+                src=format!("data:{content_type};base64,{}", BASE64_STANDARD.encode(&*contents))
             />
         </div>
     }
@@ -0,0 +1,133 @@
+use super::{INTERSTITIALS_CLASS, playlist::line_anchor_id};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    interstitials::{Interstitial, PlayoutTotals, TimelineOccupies, interstitials, playout_totals},
+    live_timeline::format_time_of_day,
+};
+use leptos::prelude::*;
+
+/// The interstitials scheduled in the playlist, in the order that they play, with where primary
+/// playback resumes after each of them, how long they play for once any X-PLAYOUT-LIMIT is applied
+/// (and in total so far), and how their X-TIMELINE-OCCUPIES places them on the timeline.
+#[component]
+pub fn InterstitialPlayout(playlist: String) -> Option<impl IntoView> {
+    let interstitials = interstitials(&playlist);
+    if interstitials.is_empty() {
+        return None;
+    }
+    let totals = playout_totals(&interstitials);
+    let summary = match totals.last().and_then(|totals| totals.playout) {
+        Some(playout) => tr_with(
+            Message::InterstitialsPlaying,
+            &[
+                ("count", &interstitials.len().to_string()),
+                ("playout", &format!("{playout:.3}")),
+            ],
+        ),
+        None => tr_with(
+            Message::InterstitialsCount,
+            &[("count", &interstitials.len().to_string())],
+        ),
+    };
+    Some(view! {
+        <details class=INTERSTITIALS_CLASS>
+            <summary>{summary}</summary>
+            <table>
+                <tr>
+                    <th>{tr(Message::AdId)}</th>
+                    <th>{tr(Message::InterstitialScheduled)}</th>
+                    <th>{tr(Message::InterstitialPlaysFor)}</th>
+                    <th>{tr(Message::InterstitialResumes)}</th>
+                    <th>{tr(Message::InterstitialTotalPlayed)}</th>
+                    <th title=tr(Message::PrimaryDelayTitle)>{tr(Message::PrimaryDelay)}</th>
+                    <th>"X-TIMELINE-OCCUPIES"</th>
+                </tr>
+                {interstitials
+                    .into_iter()
+                    .zip(totals)
+                    .map(|(interstitial, totals)| interstitial_row(interstitial, totals))
+                    .collect_view()}
+            </table>
+        </details>
+    })
+}
+
+fn interstitial_row(interstitial: Interstitial, totals: PlayoutTotals) -> impl IntoView {
+    let scheduled = if interstitial.cue.pre {
+        String::from(tr(Message::PreRoll))
+    } else if interstitial.cue.post {
+        String::from(tr(Message::PostRoll))
+    } else {
+        interstitial
+            .start_date
+            .map(format_time_of_day)
+            .unwrap_or_else(|| String::from("-"))
+    };
+    let plays_for = match (interstitial.playout(), interstitial.playout_limit) {
+        (Some(playout), Some(limit)) => tr_with(
+            Message::PlayoutLimited,
+            &[
+                ("playout", &format!("{playout:.3}")),
+                ("limit", &format!("{limit:.3}")),
+            ],
+        ),
+        (playout, _) => seconds(playout),
+    };
+    let resumes = resumes(&interstitial);
+    let occupies = match interstitial.timeline_occupies {
+        TimelineOccupies::Point => "POINT",
+        TimelineOccupies::Range => "RANGE",
+    };
+    view! {
+        <tr>
+            <td>
+                <a href=format!("#{}", line_anchor_id(interstitial.line))>
+                    {interstitial.id.clone()}
+                </a>
+            </td>
+            <td>{scheduled}</td>
+            <td>{plays_for}</td>
+            <td>{resumes}</td>
+            <td>{seconds(totals.playout)}</td>
+            <td>{seconds(totals.delay)}</td>
+            <td>{format!("{occupies}: {}", interstitial.timeline_effect())}</td>
+        </tr>
+    }
+}
+
+// Where primary playback resumes, noting when no X-RESUME-OFFSET was given so that the time the
+// interstitial plays for was taken as the offset.
+fn resumes(interstitial: &Interstitial) -> String {
+    if interstitial.cue.post {
+        return String::from("-");
+    }
+    let Some(offset) = interstitial.effective_resume_offset() else {
+        return String::from(tr(Message::ResumeUnknown));
+    };
+    let offset = format!("{offset:.3}");
+    let default = if interstitial.resume_offset.is_none() {
+        tr(Message::ResumeByDefault)
+    } else {
+        ""
+    };
+    match interstitial.resume_date() {
+        Some(date) => tr_with(
+            Message::ResumeAt,
+            &[
+                ("time", &format_time_of_day(date)),
+                ("offset", &offset),
+                ("default", default),
+            ],
+        ),
+        None => tr_with(
+            Message::ResumeIntoPrimary,
+            &[("offset", &offset), ("default", default)],
+        ),
+    }
+}
+
+fn seconds(seconds: Option<f64>) -> String {
+    seconds
+        .map(|seconds| format!("{seconds:.3}s"))
+        .unwrap_or_else(|| String::from("-"))
+}
@@ -1,30 +1,366 @@
 use crate::{
-    components::viewer::ISOBMFF_VIEW_CLASS,
-    utils::mp4_atom_properties::{
-        AtomProperties, AtomPropertyValue, BasicPropertyValue, TablePropertyValue, get_properties,
+    components::{
+        DownloadButton,
+        viewer::{
+            ISOBMFF_VIEW_CLASS, STRUCTURE_ONLY_CLASS, VALIDATION_WARNING_CLASS,
+            box_tree::{BoxTreeControls, BoxTreeExpansion},
+            font_size::FontSizeControl,
+            hex_dump::HexDump,
+            key_ids::KeyIdCrossReference,
+            media_preview::MediaPreview,
+            report::SelectedBox,
+            sample_encryption::{SampleEncryption, SampleEncryptionBox},
+            segment_download::DownloadProgressBar,
+            thumbnail::Thumbnail,
+            timeline_drift::TimelineDrift,
+        },
+    },
+    utils::{
+        box_json::{BoxEntry, box_tree_json},
+        box_tree::{box_paths, has_children, hidden_boxes},
+        codecs::{SampleEntryCodec, compare_codecs, media_source_type, sample_entry_codecs},
+        csv::CSV_MIME_TYPE,
+        download::download_text,
+        encryption::{
+            KeyIdReference, SegmentKey, encryption_problem, key_id_references, media_protection,
+            sample_encryption_role, sample_encryption_scheme,
+        },
+        frame_rate::{fragment_durations, frame_rate_problem, track_timings, video_frame_rates},
+        hex::{HEX_PAGE_LENGTH, hex_rows},
+        i18n::{Message, tr, tr_with},
+        keyformat::{key_ids, key_name},
+        keyframe::{first_keyframe, video_sample_description},
+        mp4_atom_properties::{
+            AtomProperties, AtomPropertyValue, BasicPropertyValue, TablePropertyValue,
+            get_properties,
+        },
+        network::{DownloadProgress, SharedBytes},
+        playlist_json::JSON_MIME_TYPE,
+        query_codec::VariantContext,
+        resolution::{Resolution, resolution_problem, video_track_resolutions},
+        sample_entries::sample_entries,
+        timeline_drift::{DriftSample, SegmentDateTime, media_time},
     },
 };
 use leptos::{
     either::{Either, EitherOf3},
+    html::Pre,
     prelude::*,
 };
-use mp4_atom::{Buf, FourCC, Header, ReadFrom};
-use std::{borrow::Cow, io::Cursor};
-use web_sys::MouseEvent;
+use mp4_atom::{Atom, Buf, FourCC, Header, ReadFrom, Tkhd};
+use std::{borrow::Cow, io::Cursor, ops::Range};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent, ScrollIntoViewOptions, ScrollLogicalPosition};
 
 const ATOMS_CLASS: &str = "mp4-atoms";
 const PROPERTIES_CLASS: &str = "mp4-properties";
 const INNER_TABLE_CLASS: &str = "mp4-inner-table";
+const VARIANT_CHECKS_CLASS: &str = "variant-checks";
+const VARIANT_CHECK_PASSED_CLASS: &str = "variant-check-passed";
+const HEX_PAGES_CLASS: &str = "hex-property-pages";
+const TABLE_PAGES_CLASS: &str = "table-property-pages";
+const MP4_MIME_TYPE: &str = "video/mp4";
+/// The number of rows of a table property shown at a time, as the entries of a trun or the sizes
+/// of an stsz can run into tens of thousands.
+const TABLE_PAGE_LENGTH: usize = 100;
 
 #[component]
-pub fn IsobmffViewer(data: Vec<u8>) -> mp4_atom::Result<impl IntoView> {
+pub fn IsobmffViewer(
+    data: SharedBytes,
+    #[prop(optional_no_strip)] variant: Option<VariantContext>,
+    /// The initialization segment that applies to `data`, when `data` is a media segment.
+    #[prop(optional_no_strip)]
+    init_data: Option<SharedBytes>,
+    /// The EXT-X-KEY tags that apply to `data` in the playlist it was opened from.
+    #[prop(optional_no_strip)]
+    keys: Option<Vec<SegmentKey>>,
+    /// The EXT-X-PROGRAM-DATE-TIME of `data`, when `data` is a media segment.
+    #[prop(optional_no_strip)]
+    program_date_time: Option<SegmentDateTime>,
+    /// Whether only the boxes before the media data are fetched, which is offered when set.
+    #[prop(optional_no_strip)]
+    structure_only: Option<RwSignal<bool>>,
+    /// Whether `data` stops at the header of the first mdat.
+    #[prop(optional)]
+    truncated: bool,
+    /// The name to save `data` as, which is offered when set (unless `data` was truncated).
+    #[prop(optional_no_strip)]
+    download_name: Option<String>,
+    /// How much of the segment has downloaded, when `data` is only the boxes that have arrived so
+    /// far.
+    #[prop(optional_no_strip)]
+    progress: Option<Signal<Option<DownloadProgress>>>,
+) -> mp4_atom::Result<impl IntoView> {
     let (highlighted, set_highlighted) = signal(0);
-    let mut reader = Cursor::new(data);
+    let json_name = match download_name
+        .as_deref()
+        .and_then(|name| name.rsplit_once('.'))
+    {
+        Some((stem, _)) => format!("{stem}.json"),
+        None => String::from("boxes.json"),
+    };
+    let download = download_name.filter(|_| !truncated).map(|file_name| {
+        let data = data.clone();
+        view! {
+            <DownloadButton file_name mime_type=MP4_MIME_TYPE contents=move || data.to_vec() />
+        }
+    });
+    let init_atoms = match &init_data {
+        Some(init_data) => Some(parse_atoms(init_data.to_vec())?),
+        None => None,
+    };
+    // The first keyframe of a video segment is decoded as a thumbnail, configured from the sample
+    // entry of its initialization segment.
+    let thumbnail =
+        init_data
+            .as_deref()
+            .zip(init_atoms.as_deref())
+            .and_then(|(init_data, init_atoms)| {
+                let sample_description = video_sample_description(init_data)?;
+                let codecs = sample_entry_codecs(&sample_entries(atom_tuples(init_atoms)));
+                let codec = sample_description.codec_string(&codecs)?;
+                let keyframe = first_keyframe(&data)?.data.to_vec();
+                Some(view! { <Thumbnail codec sample_description keyframe /> })
+            });
+    // A media segment (or part) can be played along with its initialization segment, as long as its
+    // media data was fetched and its samples are not encrypted (which would need the key through
+    // EME rather than fail to decode).
+    let protected_entries = init_atoms
+        .as_deref()
+        .map(|init_atoms| media_protection(atom_tuples(init_atoms)).protected_entries)
+        .unwrap_or_default();
+    let media_preview = if protected_entries.is_empty() {
+        init_data
+            .zip(init_atoms.as_deref())
+            .filter(|_| !truncated)
+            .and_then(|(init_data, init_atoms)| {
+                let entries = sample_entries(atom_tuples(init_atoms));
+                let mime_type = media_source_type(&sample_entry_codecs(&entries))?;
+                let data = data.clone();
+                Some(Either::Left(
+                    view! { <MediaPreview mime_type init_data data /> },
+                ))
+            })
+    } else {
+        let note = tr_with(
+            Message::SamplesEncrypted,
+            &[("schemes", &protected_entries.join(", "))],
+        );
+        Some(Either::Right(
+            view! { <p class=VALIDATION_WARNING_CLASS>{note}</p> },
+        ))
+    };
+    // The boxes are read from a copy of their own, while the hex dump shares the bytes.
+    let parsed_atoms = parse_atoms(data.to_vec())?;
+    // Without the page to remember which boxes are collapsed, they are only remembered while this
+    // view is open.
+    let expansion = use_context::<BoxTreeExpansion>().unwrap_or_default();
+    let paths = box_paths(
+        parsed_atoms
+            .iter()
+            .map(|atom| (atom.kind_name.as_str(), atom.depth)),
+    );
+    let depths = parsed_atoms
+        .iter()
+        .map(|atom| atom.depth)
+        .collect::<Vec<_>>();
+    let containers = paths
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| has_children(&depths, *index))
+        .map(|(index, path)| (path.clone(), depths[index]))
+        .collect::<Vec<_>>();
+    let hidden = Memo::new({
+        let (paths, depths) = (paths.clone(), depths.clone());
+        move |_| {
+            let collapsed = paths
+                .iter()
+                .map(|path| expansion.is_collapsed(path))
+                .collect::<Vec<_>>();
+            hidden_boxes(&depths, &collapsed)
+        }
+    });
     let mut atoms = Vec::new();
     let mut properties = Vec::new();
-    let mut index = 0usize;
+    for (index, (atom, path)) in parsed_atoms.iter().zip(paths).enumerate() {
+        let collapsible = has_children(&depths, index);
+        let path = StoredValue::new(path);
+        let atoms_view = view! {
+            <Show when=move || !hidden.with(|hidden| hidden[index])>
+                <AtomName
+                    atom=atom.kind
+                    depth=atom.depth
+                    collapsible
+                    collapsed=move || path.with_value(|path| expansion.is_collapsed(path))
+                    on_toggle=move || path.with_value(|path| expansion.toggle(path))
+                    highlighted=move || highlighted.get() == index
+                    on_select=move || set_highlighted.set(index)
+                />
+            </Show>
+        };
+        atoms.push(atoms_view);
+
+        let atom_properties = atom.properties.clone();
+        let properties_view = view! {
+            <Show when=move || highlighted.get() == index>
+                <AtomInfo properties=atom_properties.clone() />
+            </Show>
+        };
+        properties.push(properties_view);
+    }
+    // The selected box is shared with the viewer for its report, until this view is closed.
+    if let Some(selected_box) = use_context::<SelectedBox>() {
+        let atom_properties = parsed_atoms
+            .iter()
+            .map(|atom| atom.properties.clone())
+            .collect::<Vec<_>>();
+        Effect::new(move || selected_box.set(atom_properties.get(highlighted.get()).cloned()));
+        on_cleanup(move || selected_box.set(None));
+    }
+    // The JSON is only put together when it is asked for, as the tables of a long segment are
+    // large.
+    let box_entries = StoredValue::new(
+        parsed_atoms
+            .iter()
+            .map(|atom| {
+                (
+                    atom.kind_name.clone(),
+                    atom.depth,
+                    atom.bytes.clone(),
+                    atom.properties.clone(),
+                )
+            })
+            .collect::<Vec<_>>(),
+    );
+    let export_json = move |_| {
+        let json = box_entries.with_value(|entries| {
+            let boxes = entries
+                .iter()
+                .map(|(kind, depth, bytes, properties)| BoxEntry {
+                    kind,
+                    depth: *depth,
+                    offset: bytes.start,
+                    size: bytes.len(),
+                    properties,
+                })
+                .collect::<Vec<_>>();
+            box_tree_json(&boxes)
+        });
+        download_text(&json_name, JSON_MIME_TYPE, &json);
+    };
+    let hex_dump = view! {
+        <HexDump
+            data
+            regions=parsed_atoms.iter().map(|atom| atom.own_bytes.clone()).collect()
+            highlighted
+            on_select=move |index| set_highlighted.set(index)
+        />
+    };
+    let mut checks = variant
+        .map(|variant| variant_checks(variant, &parsed_atoms, init_atoms.as_deref()))
+        .unwrap_or_default();
+    let method = keys
+        .iter()
+        .flatten()
+        .find_map(|key| sample_encryption_scheme(&key.method).map(|_| key.method.clone()));
+    let encryption_boxes = sample_encryption_boxes(init_atoms.as_deref(), &parsed_atoms);
+    let sample_encryption = (!encryption_boxes.is_empty()).then(|| {
+        view! {
+            <SampleEncryption
+                method
+                boxes=encryption_boxes
+                on_select=move |index| set_highlighted.set(index)
+            />
+        }
+    });
+    let mut key_id_references = Vec::new();
+    if let Some(keys) = keys {
+        checks.push(encryption_check(&keys, &parsed_atoms));
+        key_id_references = key_id_cross_reference(&keys, &parsed_atoms, init_atoms.as_deref());
+    }
+    let variant_checks = if checks.is_empty() {
+        None
+    } else {
+        Some(view! { <VariantChecks checks /> })
+    };
+    let key_ids = (!key_id_references.is_empty())
+        .then(|| view! { <KeyIdCrossReference references=key_id_references /> });
+    let timeline_drift = program_date_time.and_then(|program_date_time| {
+        let tracks = track_timings(atom_tuples(init_atoms.as_deref().unwrap_or(&parsed_atoms)));
+        let media_time = media_time(atom_tuples(&parsed_atoms), &tracks)?;
+        let sample = DriftSample {
+            program_date_time,
+            media_time,
+        };
+        Some(view! { <TimelineDrift sample /> })
+    });
+    let structure_only = structure_only.map(|structure_only| {
+        view! {
+            <label class=STRUCTURE_ONLY_CLASS>
+                <input
+                    type="checkbox"
+                    prop:checked=move || structure_only.get()
+                    on:change=move |ev| structure_only.set(event_target_checked(&ev))
+                />
+                {tr(Message::ParseStructureOnly)}
+                {truncated.then(|| format!(" {}", tr(Message::MediaDataNotDownloaded)))}
+            </label>
+        }
+    });
+    Ok(view! {
+        <div class=ISOBMFF_VIEW_CLASS>
+            <div class=ATOMS_CLASS>
+                <BoxTreeControls expansion containers />
+                <div
+                    role="listbox"
+                    aria-label=tr(Message::Boxes)
+                    on:keydown=move_focus_between_atoms
+                >
+                    {atoms}
+                </div>
+            </div>
+            <div class=PROPERTIES_CLASS>
+                <FontSizeControl />
+                {download}
+                <button
+                    class="button"
+                    type="button"
+                    title=tr(Message::ExportBoxesJsonTitle)
+                    on:click=export_json
+                >
+                    {tr(Message::ExportJson)}
+                </button>
+                {thumbnail}
+                {media_preview}
+                {progress.map(|progress| view! { <DownloadProgressBar progress /> })}
+                {structure_only} {variant_checks} {key_ids} {sample_encryption} {timeline_drift}
+                {properties} {hex_dump}
+            </div>
+        </div>
+    })
+}
+
+struct ParsedAtom {
+    kind: FourCC,
+    kind_name: String,
+    depth: usize,
+    properties: AtomProperties,
+    /// The presentation width and height of a `tkhd` box, by its track ID. The properties of the
+    /// box only present these 16.16 fixed-point values for display, so they are kept from the
+    /// typed box for the comparison against RESOLUTION.
+    track_dimensions: Option<(u32, Resolution)>,
+    /// The bytes that belong to this box and not to any child box.
+    own_bytes: Range<usize>,
+    /// All of the bytes of this box, including its header and any child boxes.
+    bytes: Range<usize>,
+}
+
+fn parse_atoms(data: Vec<u8>) -> mp4_atom::Result<Vec<ParsedAtom>> {
+    let mut reader = Cursor::new(data);
+    let mut parsed_atoms = Vec::new();
     let mut container_box_end_positions = Vec::new();
     loop {
+        let start = reader.position() as usize;
         let header = Header::read_from(&mut reader)?;
         // Handle popping out of depths when we have reached the end of container boxes. Multiple
         // boxes may end at the same depth and so we need to check more than just one.
@@ -60,71 +396,316 @@ pub fn IsobmffViewer(data: Vec<u8>) -> mp4_atom::Result<impl IntoView> {
         // info) because a new container box should still appear at the same depth as its sibling
         // boxes.
         let depth = container_box_end_positions.len();
+        let body_start = reader.position() as usize;
         // We then get the property information for this box.
         let info = get_properties(&header, &mut reader)?;
+        let track_dimensions = if header.kind == Tkhd::KIND {
+            let mut body = Cursor::new(&reader.get_ref()[body_start..reader.position() as usize]);
+            Tkhd::decode_atom(&header, &mut body).ok().map(|tkhd| {
+                let dimensions = Resolution {
+                    width: u32::from(tkhd.width.integer()),
+                    height: u32::from(tkhd.height.integer()),
+                };
+                (tkhd.track_id, dimensions)
+            })
+        } else {
+            None
+        };
         // If the new info is a container box then we will receive a new "depth until" that
         // indicates at what reader position this box will end at. Above we handle tracking how deep
         // we are into any given box and at what size the box ends.
         if let Some(new_depth_until) = info.new_depth_until {
             container_box_end_positions.push(new_depth_until);
         }
-
-        let atoms_view = view! {
-            <AtomName
-                atom=header.kind
-                depth
-                highlighted=move || highlighted.get() == index
-                on_click=move |_| set_highlighted.set(index)
-            />
-        };
-        atoms.push(atoms_view);
-
-        let properties_view = view! {
-            <Show when=move || highlighted.get() == index>
-                <AtomInfo properties=info.properties.clone() />
-            </Show>
-        };
-        properties.push(properties_view);
+        parsed_atoms.push(ParsedAtom {
+            kind: header.kind,
+            kind_name: header.kind.to_string(),
+            depth,
+            properties: info.properties,
+            track_dimensions,
+            // The reader is now past the box, or for a container box, past the fields that precede
+            // its child boxes.
+            own_bytes: start..reader.position() as usize,
+            bytes: start..info.new_depth_until.unwrap_or(reader.position()) as usize,
+        });
 
         if !reader.has_remaining() {
             break;
         }
-        index += 1;
     }
-    Ok(view! {
-        <div class=ISOBMFF_VIEW_CLASS>
-            <div class=ATOMS_CLASS>{atoms}</div>
-            <div class=PROPERTIES_CLASS>{properties}</div>
+    Ok(parsed_atoms)
+}
+
+/// The codecs of the sample entries of an initialization segment.
+pub fn init_segment_codecs(init_data: Vec<u8>) -> mp4_atom::Result<Vec<SampleEntryCodec>> {
+    let atoms = parse_atoms(init_data)?;
+    Ok(sample_entry_codecs(&sample_entries(atom_tuples(&atoms))))
+}
+
+fn atom_tuples(atoms: &[ParsedAtom]) -> impl Iterator<Item = (&str, usize, &AtomProperties)> {
+    atoms
+        .iter()
+        .map(|atom| (atom.kind_name.as_str(), atom.depth, &atom.properties))
+}
+
+fn track_dimensions(atoms: &[ParsedAtom]) -> Vec<(u32, Resolution)> {
+    atoms
+        .iter()
+        .filter_map(|atom| atom.track_dimensions)
+        .collect()
+}
+
+struct VariantCheck {
+    passed: String,
+    problem: Option<String>,
+}
+
+fn variant_checks(
+    variant: VariantContext,
+    atoms: &[ParsedAtom],
+    init_atoms: Option<&[ParsedAtom]>,
+) -> Vec<VariantCheck> {
+    let mut checks = Vec::new();
+    // Sample entries are only present in the initialization segment, so the codecs and dimensions
+    // can only be checked when viewing one.
+    let entries = sample_entries(atom_tuples(atoms));
+    if !entries.is_empty() {
+        if let Some(advertised) = &variant.codecs {
+            let codecs = sample_entry_codecs(&entries);
+            checks.extend(
+                compare_codecs(advertised, &codecs)
+                    .into_iter()
+                    .map(|comparison| VariantCheck {
+                        passed: tr_with(
+                            Message::CodecsMatch,
+                            &[("actual", &comparison.actual), ("advertised", advertised)],
+                        ),
+                        problem: comparison.problem,
+                    }),
+            );
+        }
+        if let Some(advertised) = variant.resolution {
+            checks.extend(
+                video_track_resolutions(&entries, &track_dimensions(atoms))
+                    .iter()
+                    .map(|actual| VariantCheck {
+                        passed: tr_with(
+                            Message::ResolutionMatches,
+                            &[("advertised", &advertised.to_string())],
+                        ),
+                        problem: resolution_problem(advertised, actual),
+                    }),
+            );
+        }
+    }
+    if let Some(advertised) = variant.frame_rate {
+        let tracks = track_timings(atom_tuples(init_atoms.unwrap_or(atoms)));
+        let fragments = fragment_durations(atom_tuples(atoms), &tracks);
+        checks.extend(
+            video_frame_rates(&tracks, &fragments)
+                .into_iter()
+                .map(|measured| VariantCheck {
+                    passed: tr_with(
+                        Message::FrameRateMatches,
+                        &[
+                            ("measured", &format!("{:.3}", measured.frames_per_second)),
+                            ("advertised", &advertised.to_string()),
+                        ],
+                    ),
+                    problem: frame_rate_problem(advertised, measured),
+                }),
+        );
+    }
+    checks
+}
+
+fn encryption_check(keys: &[SegmentKey], atoms: &[ParsedAtom]) -> VariantCheck {
+    let mut methods = keys
+        .iter()
+        .map(|key| key.method.as_str())
+        .collect::<Vec<&str>>();
+    methods.dedup();
+    let methods = if methods.is_empty() {
+        String::from("NONE")
+    } else {
+        methods.join(", ")
+    };
+    VariantCheck {
+        passed: tr_with(Message::EncryptionConsistent, &[("methods", &methods)]),
+        problem: encryption_problem(keys, &media_protection(atom_tuples(atoms))),
+    }
+}
+
+// When viewing a media segment, the key IDs are in the `tenc` and `pssh` boxes of its
+// initialization segment.
+fn key_id_cross_reference(
+    keys: &[SegmentKey],
+    atoms: &[ParsedAtom],
+    init_atoms: Option<&[ParsedAtom]>,
+) -> Vec<KeyIdReference> {
+    let media =
+        media_protection(atom_tuples(init_atoms.unwrap_or_default()).chain(atom_tuples(atoms)));
+    let playlist_key_ids = keys
+        .iter()
+        .map(|key| (key_name(key), key_ids(key)))
+        .collect::<Vec<_>>();
+    key_id_references(&playlist_key_ids, &media)
+}
+
+// The boxes of the initialization segment come first, as that is where the protected sample entries
+// are that the encryption boxes of a media segment apply to.
+fn sample_encryption_boxes(
+    init_atoms: Option<&[ParsedAtom]>,
+    atoms: &[ParsedAtom],
+) -> Vec<SampleEncryptionBox> {
+    let init_boxes = init_atoms
+        .unwrap_or_default()
+        .iter()
+        .map(|atom| (atom, None));
+    let boxes = atoms
+        .iter()
+        .enumerate()
+        .map(|(index, atom)| (atom, Some(index)));
+    init_boxes
+        .chain(boxes)
+        .filter_map(|(atom, index)| {
+            Some(SampleEncryptionBox {
+                kind: atom.kind_name.clone(),
+                role: sample_encryption_role(&atom.kind_name)?,
+                index,
+            })
+        })
+        .collect()
+}
+
+#[component]
+fn VariantChecks(checks: Vec<VariantCheck>) -> impl IntoView {
+    view! {
+        <div class=VARIANT_CHECKS_CLASS>
+            <p>{tr(Message::ChecksAgainstPlaylist)}</p>
+            {checks
+                .into_iter()
+                .map(|check| match check.problem {
+                    Some(problem) => {
+                        Either::Left(view! { <p class=VALIDATION_WARNING_CLASS>{problem}</p> })
+                    }
+                    None => {
+                        Either::Right(
+                            view! { <p class=VARIANT_CHECK_PASSED_CLASS>{check.passed}</p> },
+                        )
+                    }
+                })
+                .collect_view()}
         </div>
-    })
+    }
 }
 
 #[component]
 fn AtomName(
     atom: FourCC,
     depth: usize,
-    highlighted: impl Fn() -> bool + Send + Sync + 'static,
-    on_click: impl FnMut(MouseEvent) + 'static,
+    /// Whether the box has children, which can be hidden by collapsing it.
+    collapsible: bool,
+    collapsed: impl Fn() -> bool + Copy + Send + Sync + 'static,
+    on_toggle: impl Fn() + Copy + 'static,
+    highlighted: impl Fn() -> bool + Copy + Send + Sync + 'static,
+    on_select: impl Fn() + Copy + 'static,
 ) -> impl IntoView {
     let mut space = String::new();
     for _ in 0..depth {
         space.push_str("  ");
     }
+    // Boxes without children are padded to line up with the names of those that have a toggle.
+    let toggle = if collapsible {
+        Either::Left(view! {
+            <span
+                class="box-toggle"
+                aria-hidden="true"
+                on:click=move |ev| {
+                    ev.stop_propagation();
+                    on_toggle();
+                }
+            >
+                {move || if collapsed() { "▸ " } else { "▾ " }}
+            </span>
+        })
+    } else {
+        Either::Right("  ")
+    };
+    // A box highlighted from elsewhere (such as its bytes in the hex dump) is brought into view in
+    // the list, which is scrolled no further than it takes to see it.
+    let name = NodeRef::<Pre>::new();
+    Effect::watch(
+        highlighted,
+        move |highlighted, _, _| {
+            if *highlighted && let Some(name) = name.get_untracked() {
+                let options = ScrollIntoViewOptions::new();
+                options.set_block(ScrollLogicalPosition::Nearest);
+                name.scroll_into_view_with_scroll_into_view_options(&options);
+            }
+        },
+        false,
+    );
+    // Only the highlighted box is in the tab order, so that tabbing moves past the list of boxes in
+    // one step while the arrow keys move within it.
     view! {
-        <pre class:highlighted=highlighted on:click=on_click>
-            {format!("{space}{atom}")}
+        <pre
+            node_ref=name
+            role="option"
+            aria-selected=move || highlighted().to_string()
+            tabindex=move || if highlighted() { "0" } else { "-1" }
+            class:highlighted=highlighted
+            on:click=move |_| on_select()
+            on:focus=move |_| on_select()
+            on:keydown=move |ev| {
+                let expand = match ev.key().as_str() {
+                    "ArrowRight" => true,
+                    "ArrowLeft" => false,
+                    _ => return,
+                };
+                if collapsible && collapsed() == expand {
+                    ev.prevent_default();
+                    on_toggle();
+                }
+            }
+            aria-expanded=move || collapsible.then(|| (!collapsed()).to_string())
+        >
+            {space}
+            {toggle}
+            {atom.to_string()}
         </pre>
     }
 }
 
+// Moves the focus (and with it the highlight) to the box above or below the focused one.
+fn move_focus_between_atoms(event: KeyboardEvent) {
+    let Some(focused) = event
+        .target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+    else {
+        return;
+    };
+    let next = match event.key().as_str() {
+        "ArrowDown" => focused.next_element_sibling(),
+        "ArrowUp" => focused.previous_element_sibling(),
+        _ => return,
+    };
+    event.prevent_default();
+    if let Some(next) = next.and_then(|next| next.dyn_into::<HtmlElement>().ok())
+        && let Err(e) = next.focus()
+    {
+        log::warn!("failed to focus the next box due to {e:?}");
+    }
+}
+
 #[component]
 fn AtomInfo(properties: AtomProperties) -> impl IntoView {
     view! {
         <p>{properties.box_name}</p>
-        <table>
+        <table aria-label=properties.box_name>
             <tr>
-                <th>"Property"</th>
-                <th>"Value"</th>
+                <th scope="col">{tr(Message::BoxProperty)}</th>
+                <th scope="col">{tr(Message::BoxValue)}</th>
             </tr>
             {properties
                 .properties
@@ -142,7 +723,10 @@ fn AtomInfo(properties: AtomProperties) -> impl IntoView {
                                 {match value {
                                     AtomPropertyValue::Basic(v) => Either::Left(view_from_prop(v)),
                                     AtomPropertyValue::Table(v) => {
-                                        Either::Right(view! { <InnerTable properties=v.clone() /> })
+                                        let name = format!("{}_{key}", properties.box_name);
+                                        Either::Right(
+                                            view! { <InnerTable name properties=v.clone() /> },
+                                        )
                                     }
                                 }}
                             </td>
@@ -154,61 +738,164 @@ fn AtomInfo(properties: AtomProperties) -> impl IntoView {
     }
 }
 
+/// A table within a property, which is shown a page of rows at a time (with the whole table
+/// available as CSV) when it is too long to lay out at once.
 #[component]
-fn InnerTable(properties: TablePropertyValue) -> impl IntoView {
+fn InnerTable(
+    /// The name to save the table as, without the extension.
+    name: String,
+    properties: TablePropertyValue,
+) -> impl IntoView {
     if properties.rows.is_empty() || properties.rows.first().is_some_and(|row| row.is_empty()) {
-        EitherOf3::A(String::new())
-    } else if let Some(headers) = properties.headers {
-        EitherOf3::B(view! {
-            <table class=INNER_TABLE_CLASS>
-                <tr>{headers.iter().map(|header| view! { <th>{*header}</th> }).collect_view()}</tr>
-                {properties
-                    .rows
-                    .iter()
-                    .map(|row| {
-                        view! {
-                            <tr>
-                                {row
-                                    .iter()
-                                    .map(|col| view! { <td>{view_from_prop(col)}</td> })
-                                    .collect_view()}
-                            </tr>
-                        }
-                    })
-                    .collect_view()}
-            </table>
-        })
-    } else {
-        EitherOf3::C(view! {
-            <table>
-                {properties
-                    .rows
-                    .iter()
-                    .map(|row| {
-                        view! {
-                            <tr>
-                                {row
-                                    .iter()
-                                    .map(|col| view! { <td>{view_from_prop(col)}</td> })
-                                    .collect_view()}
-                            </tr>
-                        }
-                    })
-                    .collect_view()}
+        return EitherOf3::A(String::new());
+    }
+    let class = properties.headers.is_some().then_some(INNER_TABLE_CLASS);
+    let header_row = properties.headers.clone().map(|headers| {
+        let headers = headers
+            .into_iter()
+            .map(|header| view! { <th>{header}</th> });
+        view! { <tr>{headers.collect_view()}</tr> }
+    });
+    let row_count = properties.rows.len();
+    if row_count <= TABLE_PAGE_LENGTH {
+        return EitherOf3::B(view! {
+            <table class=class>
+                {header_row}
+                {properties.rows.iter().map(|row| table_row(row)).collect_view()}
             </table>
-        })
+        });
     }
+    let page_count = row_count.div_ceil(TABLE_PAGE_LENGTH);
+    let page = RwSignal::new(0);
+    let page_rows = move || {
+        let start = page.get() * TABLE_PAGE_LENGTH;
+        start..(start + TABLE_PAGE_LENGTH).min(row_count)
+    };
+    let table = StoredValue::new(properties);
+    let rows = move || {
+        table.with_value(|table| {
+            table.rows[page_rows()]
+                .iter()
+                .map(|row| table_row(row))
+                .collect_view()
+        })
+    };
+    EitherOf3::C(view! {
+        <div class=TABLE_PAGES_CLASS>
+            {tr_with(Message::TableRows, &[("count", &row_count.to_string())])}
+            <button
+                class="button"
+                type="button"
+                disabled=move || page.get() == 0
+                on:click=move |_| page.update(|page| *page = page.saturating_sub(1))
+            >
+                {tr(Message::Previous)}
+            </button>
+            {move || {
+                let rows = page_rows();
+                tr_with(
+                    Message::TableRowRange,
+                    &[
+                        ("start", &(rows.start + 1).to_string()),
+                        ("end", &rows.end.to_string()),
+                        ("count", &row_count.to_string()),
+                    ],
+                )
+            }}
+            <button
+                class="button"
+                type="button"
+                disabled=move || page.get() + 1 >= page_count
+                on:click=move |_| page.update(|page| *page = (*page + 1).min(page_count - 1))
+            >
+                {tr(Message::Next)}
+            </button>
+            <DownloadButton
+                file_name=format!("{name}.csv")
+                mime_type=CSV_MIME_TYPE
+                contents=move || table.with_value(|table| table.to_csv().into_bytes())
+            />
+        </div>
+        <table class=class>{header_row}{rows}</table>
+    })
+}
+
+fn table_row(row: &[BasicPropertyValue]) -> impl IntoView + use<> {
+    let cells = row
+        .iter()
+        .map(|col| view! { <td>{view_from_prop(col)}</td> });
+    view! { <tr>{cells.collect_view()}</tr> }
 }
 
 // Naming the type, rather than using impl IntoView, helps the borrow checker calm down when passing
 // the property by reference in the map closures.
-fn view_from_prop(
-    property: &BasicPropertyValue,
-) -> Either<View<leptos::html::HtmlElement<leptos::html::Pre, (), (String,)>>, String> {
+fn view_from_prop(property: &BasicPropertyValue) -> AnyView {
+    if let BasicPropertyValue::Hex(bytes) = property
+        && bytes.len() > HEX_PAGE_LENGTH
+    {
+        return view! { <HexPages bytes=bytes.clone() /> }.into_any();
+    }
     let string = String::from(property);
-    if property.is_hex() || property.is_binary_mask() {
-        Either::Left(view! { <pre>{string}</pre> })
+    if property.is_preformatted() {
+        view! { <pre>{string}</pre> }.into_any()
+    } else if let BasicPropertyValue::Url(url) = property {
+        view! {
+            <a href=url.clone() target="_blank" rel="noopener noreferrer">
+                {string}
+            </a>
+        }
+        .into_any()
     } else {
-        Either::Right(view! { {string} })
+        view! { {string} }.into_any()
+    }
+}
+
+/// A hex property too large to lay out at once (such as the data of a pssh or a preview of an
+/// mdat), which is shown a page at a time as it is asked for.
+#[component]
+fn HexPages(bytes: Vec<u8>) -> impl IntoView {
+    let length = bytes.len();
+    let page_count = length.div_ceil(HEX_PAGE_LENGTH);
+    let bytes = StoredValue::new(bytes);
+    let shown = RwSignal::new(1);
+    let shown_length = move || (shown.get() * HEX_PAGE_LENGTH).min(length);
+    let next_length = move || (length - shown_length()).min(HEX_PAGE_LENGTH);
+    // Keyed by page so that showing more only lays out the pages that were not already shown.
+    let page = move |index: usize| {
+        let start = index * HEX_PAGE_LENGTH;
+        let rows = bytes
+            .with_value(|bytes| hex_rows(&bytes[start..(start + HEX_PAGE_LENGTH).min(length)]));
+        if index == 0 {
+            rows
+        } else {
+            format!("\n{rows}")
+        }
+    };
+    view! {
+        <pre>
+            <For each=move || 0..shown.get() key=|index| *index children=page />
+        </pre>
+        <Show when=move || shown.get() < page_count>
+            <div class=HEX_PAGES_CLASS>
+                {move || {
+                    tr_with(
+                        Message::ShowingBytes,
+                        &[("shown", &shown_length().to_string()), ("length", &length.to_string())],
+                    )
+                }}
+                <button
+                    class="button"
+                    type="button"
+                    on:click=move |_| shown.update(|shown| *shown = (*shown + 1).min(page_count))
+                >
+                    {move || {
+                        tr_with(Message::ShowNextBytes, &[("count", &next_length().to_string())])
+                    }}
+                </button>
+                <button class="button" type="button" on:click=move |_| shown.set(page_count)>
+                    {tr(Message::ShowAll)}
+                </button>
+            </div>
+        </Show>
     }
 }
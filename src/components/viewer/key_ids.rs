@@ -0,0 +1,60 @@
+use super::{KEY_IDS_CLASS, VALIDATION_WARNING_CLASS};
+use crate::utils::{
+    encryption::KeyIdReference,
+    fairplay::key_id_as_uuid,
+    i18n::{Message, tr, tr_with},
+};
+use leptos::prelude::*;
+
+/// The key IDs of the EXT-X-KEY tags that apply to a segment alongside those of its `tenc` and
+/// `pssh` boxes, with any key ID that one DRM system is given but another is not called out.
+#[component]
+pub fn KeyIdCrossReference(references: Vec<KeyIdReference>) -> impl IntoView {
+    let mismatched = references
+        .iter()
+        .filter(|reference| !reference.problems.is_empty())
+        .count();
+    let summary = tr_with(
+        Message::KeyIdsSummary,
+        &[
+            ("count", &references.len().to_string()),
+            ("mismatched", &mismatched.to_string()),
+        ],
+    );
+    view! {
+        <details class=KEY_IDS_CLASS open={mismatched > 0}>
+            <summary>{summary}</summary>
+            <table>
+                <tr>
+                    <th>"KID"</th>
+                    <th>"EXT-X-KEY"</th>
+                    <th>{tr(Message::KeyIdSegment)}</th>
+                    <th>{tr(Message::KeyIdProblems)}</th>
+                </tr>
+                {references
+                    .into_iter()
+                    .map(|reference| {
+                        view! {
+                            <tr>
+                                <td>{key_id_as_uuid(&reference.key_id)}</td>
+                                <td>{or_dash(reference.keys.join(", "))}</td>
+                                <td>{or_dash(reference.media_sources.join(", "))}</td>
+                                <td class=VALIDATION_WARNING_CLASS>
+                                    {reference.problems.join(" ")}
+                                </td>
+                            </tr>
+                        }
+                    })
+                    .collect_view()}
+            </table>
+        </details>
+    }
+}
+
+fn or_dash(value: String) -> String {
+    if value.is_empty() {
+        String::from("-")
+    } else {
+        value
+    }
+}
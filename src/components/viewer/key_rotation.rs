@@ -0,0 +1,91 @@
+use super::{KEY_ROTATION_CLASS, VALIDATION_WARNING_CLASS, playlist::line_anchor_id};
+use crate::utils::{
+    encryption::{Aes128Segment, aes_128_segments},
+    hex::encode_hex,
+    i18n::{Message, tr, tr_with},
+};
+use leptos::prelude::*;
+
+/// The segments encrypted with METHOD=AES-128, with the key that each is decrypted with (shown where
+/// it is rotated) and its IV. Where the EXT-X-KEY has no IV attribute, the IV is the one a player
+/// derives from the Media Sequence Number, so that it can be checked against what was packaged.
+#[component]
+pub fn KeyRotation(playlist: String) -> Option<impl IntoView> {
+    let segments = aes_128_segments(&playlist);
+    if segments.is_empty() {
+        return None;
+    }
+    let mut key_lines = segments
+        .iter()
+        .map(|segment| segment.key_line)
+        .collect::<Vec<_>>();
+    key_lines.dedup();
+    let summary = tr_with(
+        Message::KeyRotationSummary,
+        &[
+            ("keys", &key_lines.len().to_string()),
+            ("segments", &segments.len().to_string()),
+        ],
+    );
+    let mut previous_key_line = None;
+    let rows = segments
+        .into_iter()
+        .map(|segment| {
+            let rotated = previous_key_line != Some(segment.key_line);
+            previous_key_line = Some(segment.key_line);
+            segment_row(segment, rotated)
+        })
+        .collect_view();
+    Some(view! {
+        <details class=KEY_ROTATION_CLASS>
+            <summary>{summary}</summary>
+            <table>
+                <tr>
+                    <th>{tr(Message::KeyRotationSegment)}</th>
+                    <th>{tr(Message::KeyRotationKey)}</th>
+                    <th>{tr(Message::KeyRotationIv)}</th>
+                    <th>{tr(Message::KeyRotationIvFrom)}</th>
+                </tr>
+                {rows}
+            </table>
+        </details>
+    })
+}
+
+fn segment_row(segment: Aes128Segment, rotated: bool) -> impl IntoView {
+    let key = rotated.then(|| {
+        view! {
+            <a href=format!("#{}", line_anchor_id(segment.key_line))>
+                {segment
+                    .key
+                    .uri
+                    .clone()
+                    .unwrap_or_else(|| String::from(tr(Message::KeyWithoutUri)))}
+            </a>
+        }
+    });
+    let (iv, iv_class) = match segment.iv {
+        Some(iv) => (format!("0x{}", encode_hex(&iv)), ""),
+        None => (
+            String::from(tr(Message::IvNotHex)),
+            VALIDATION_WARNING_CLASS,
+        ),
+    };
+    let iv_from = tr(if segment.key.iv.is_some() {
+        Message::IvAttribute
+    } else {
+        Message::MediaSequenceNumber
+    });
+    view! {
+        <tr>
+            <td>
+                <a href=format!("#{}", line_anchor_id(segment.line))>
+                    {segment.media_sequence}
+                </a>
+            </td>
+            <td>{key}</td>
+            <td class=iv_class>{iv}</td>
+            <td>{iv_from}</td>
+        </tr>
+    }
+}
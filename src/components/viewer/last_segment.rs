@@ -0,0 +1,22 @@
+use super::SegmentResponse;
+use leptos::prelude::*;
+
+/// The last segment of the current playlist that was fetched successfully, which stays on show
+/// (under an error) when the segment opened after it fails to load, so that stepping through the
+/// segments of a live stream is not held up by one failed request.
+#[derive(Clone, Copy, Default)]
+pub struct LastSegment(RwSignal<Option<SegmentResponse>>);
+
+impl LastSegment {
+    pub fn clear(&self) {
+        self.0.set(None);
+    }
+
+    pub(super) fn get_untracked(&self) -> Option<SegmentResponse> {
+        self.0.get_untracked()
+    }
+
+    pub(super) fn record(&self, segment: SegmentResponse) {
+        self.0.set(Some(segment));
+    }
+}
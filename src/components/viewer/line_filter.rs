@@ -0,0 +1,130 @@
+use super::{CHIP_CLASS, HIDDEN_LINES_CLASS, LINE_FILTER_CLASS};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    playlist_lines::{LineCategory, hidden_runs},
+};
+use leptos::prelude::*;
+use std::collections::HashMap;
+
+/// The categories of line that the playlist is filtered down to, where every line is shown while
+/// none are selected.
+#[derive(Clone, Copy)]
+pub struct PlaylistFilter {
+    selected: RwSignal<Vec<LineCategory>>,
+    visible: Memo<Vec<bool>>,
+    hidden_runs: Memo<HashMap<usize, usize>>,
+    /// The categories that appear in the playlist, which are the only ones offered.
+    present: Memo<Vec<LineCategory>>,
+}
+
+impl PlaylistFilter {
+    /// Filters lines with the given categories, in line order. The categories follow the playlist
+    /// as it is reloaded, while the selection is kept.
+    pub fn new(categories: Memo<Vec<Option<LineCategory>>>) -> Self {
+        let present = Memo::new(move |_| {
+            categories.with(|categories| {
+                LineCategory::ALL
+                    .into_iter()
+                    .filter(|category| categories.contains(&Some(*category)))
+                    .collect()
+            })
+        });
+        let selected = RwSignal::new(Vec::<LineCategory>::new());
+        let visible = Memo::new(move |_| {
+            selected.with(|selected| {
+                categories.with(|categories| {
+                    categories
+                        .iter()
+                        .map(|category| {
+                            selected.is_empty()
+                                || category.is_some_and(|category| selected.contains(&category))
+                        })
+                        .collect()
+                })
+            })
+        });
+        Self {
+            selected,
+            visible,
+            hidden_runs: Memo::new(move |_| visible.with(|visible| hidden_runs(visible))),
+            present,
+        }
+    }
+
+    /// Whether the line at the 0-based `index` is filtered out.
+    pub fn is_hidden(&self, index: usize) -> bool {
+        self.visible
+            .with(|visible| visible.get(index).is_some_and(|visible| !visible))
+    }
+
+    fn is_selected(&self, category: LineCategory) -> bool {
+        self.selected.with(|selected| selected.contains(&category))
+    }
+
+    fn toggle(&self, category: LineCategory) {
+        self.selected.update(|selected| {
+            if let Some(position) = selected.iter().position(|c| *c == category) {
+                selected.remove(position);
+            } else {
+                selected.push(category);
+            }
+        });
+    }
+}
+
+/// Chips that each filter the playlist down to a category of line.
+#[component]
+pub fn LineFilter(filter: PlaylistFilter) -> impl IntoView {
+    let chips = move || {
+        filter
+            .present
+            .get()
+            .into_iter()
+            .map(|category| {
+                view! {
+                    <button
+                        class=CHIP_CLASS
+                        class:selected=move || filter.is_selected(category)
+                        type="button"
+                        aria-pressed=move || filter.is_selected(category).to_string()
+                        on:click=move |_| filter.toggle(category)
+                    >
+                        {category.label()}
+                    </button>
+                }
+            })
+            .collect_view()
+    };
+    view! {
+        <div class=LINE_FILTER_CLASS>
+            <span>{tr(Message::ShowOnly)}</span>
+            {chips}
+            <Show when=move || filter.selected.with(|selected| !selected.is_empty())>
+                <button class="button" type="button" on:click=move |_| filter.selected.set(Vec::new())>
+                    {tr(Message::ShowAll)}
+                </button>
+            </Show>
+        </div>
+    }
+}
+
+/// Stands in for the run of filtered out lines that starts at the 0-based `index`, if any.
+#[component]
+pub fn HiddenLinesMarker(
+    filter: PlaylistFilter,
+    #[prop(into)] index: Signal<usize>,
+) -> impl IntoView {
+    move || {
+        filter
+            .hidden_runs
+            .with(|runs| runs.get(&index.get()).copied())
+            .map(|count| {
+                let label = if count == 1 {
+                    String::from(tr(Message::OneLineHidden))
+                } else {
+                    tr_with(Message::LinesHidden, &[("count", &count.to_string())])
+                };
+                view! { <p class=HIDDEN_LINES_CLASS>{label}</p> }
+            })
+    }
+}
@@ -0,0 +1,235 @@
+use super::{
+    LINE_CHUNK_PLACEHOLDER_CLASS,
+    playlist::{line_anchor_id, line_number_from_anchor_id},
+};
+use leptos::{either::Either, html::Div, prelude::*};
+use leptos_router::hooks::use_location;
+use leptos_use::{UseIntersectionObserverOptions, use_intersection_observer_with_options};
+use std::ops::Range;
+use web_sys::{ScrollIntoViewOptions, ScrollLogicalPosition};
+
+// Rendering every line of a playlist with tens of thousands of lines makes for an enormous DOM, so
+// the lines of long playlists are split into chunks that are only rendered while they are near the
+// viewport, and otherwise each chunk is a placeholder of roughly the same height. The views of the
+// lines of a chunk are built when it comes near and dropped once it has moved away again, so
+// anything that scrolls to a line renders its chunk first.
+const CHUNK_LINES: usize = 250;
+// Shorter playlists are rendered in full straight away, as the one chunk.
+const WINDOWED_MIN_LINES: usize = 2000;
+// How far outside of the viewport a chunk is rendered, so that it is ready before it scrolls in,
+// and how far it has to move away before it is dropped.
+const RENDER_MARGIN: &str = "2000px";
+
+/// Which chunks of the playlist lines are rendered.
+#[derive(Clone, Copy)]
+pub struct LineWindow {
+    // Chunks are added as they are first needed, as a reloaded playlist can have more of them.
+    rendered: StoredValue<Vec<ArcRwSignal<bool>>>,
+    windowed: bool,
+}
+
+impl LineWindow {
+    /// Whether the lines are windowed at all is settled by the `line_count` that the playlist was
+    /// first shown with.
+    pub fn new(line_count: usize) -> Self {
+        Self {
+            rendered: StoredValue::new(Vec::new()),
+            windowed: line_count >= WINDOWED_MIN_LINES,
+        }
+    }
+
+    fn chunk_lines(&self) -> usize {
+        if self.windowed {
+            CHUNK_LINES
+        } else {
+            usize::MAX
+        }
+    }
+
+    fn rendered(&self, chunk: usize) -> ArcRwSignal<bool> {
+        let windowed = self.windowed;
+        self.rendered
+            .try_update_value(|rendered| {
+                while rendered.len() <= chunk {
+                    rendered.push(ArcRwSignal::new(!windowed));
+                }
+                rendered[chunk].clone()
+            })
+            .unwrap_or_else(|| ArcRwSignal::new(!windowed))
+    }
+
+    /// Renders the chunk that holds the 1-based `line_number`, and scrolls the line into view once
+    /// it has been rendered.
+    pub fn scroll_to(&self, line_number: usize) {
+        self.scroll_to_with(line_number, ScrollLogicalPosition::Start);
+    }
+
+    // Only the chunk that holds the line is rendered for it, so the lines before it keep their
+    // placeholders and are never laid out.
+    fn scroll_to_with(&self, line_number: usize, block: ScrollLogicalPosition) {
+        let rendered = self.rendered(line_number.saturating_sub(1) / self.chunk_lines());
+        if !rendered.get_untracked() {
+            rendered.set(true);
+        }
+        request_animation_frame(move || {
+            if let Some(line) = document().get_element_by_id(&line_anchor_id(line_number)) {
+                let options = ScrollIntoViewOptions::new();
+                options.set_block(block);
+                line.scroll_into_view_with_scroll_into_view_options(&options);
+            }
+        });
+    }
+
+    /// Splits the lines, given by the `keys` that their views are kept by, into chunks, where
+    /// `line_view` builds the view of a line (given its 0-based index) while its chunk is rendered,
+    /// and `placeholder_lines` gives the number of lines a chunk would show (out of the 0-based
+    /// range of lines it holds) to size it while it is not. As the playlist is reloaded, a line
+    /// that keeps its key within the same chunk keeps its view.
+    pub fn chunks(
+        self,
+        keys: Memo<Vec<u64>>,
+        line_view: impl Fn(Signal<usize>) -> AnyView + Copy + Send + Sync + 'static,
+        placeholder_lines: impl Fn(Range<usize>) -> usize + Copy + Send + Sync + 'static,
+    ) -> impl IntoView {
+        let (windowed, chunk_lines) = (self.windowed, self.chunk_lines());
+        let chunk_count = Memo::new(move |_| keys.with(Vec::len).div_ceil(chunk_lines));
+        let chunk_view = move |chunk: usize| {
+            let start = chunk * chunk_lines;
+            let rendered = self.rendered(chunk);
+            let placeholder_lines = Signal::derive(move || {
+                placeholder_lines(start..keys.with(Vec::len).min(start.saturating_add(chunk_lines)))
+            });
+            view! {
+                <LineChunk keys start chunk_lines rendered windowed line_view placeholder_lines />
+            }
+        };
+        view! { <For each=move || 0..chunk_count.get() key=|chunk| *chunk children=chunk_view /> }
+    }
+
+    /// Brings the highlighted 1-based `line_number` (such as the URI of the segment that is open)
+    /// into view once the playlist is shown, however far down it is, unless the location hash
+    /// refers to a line of its own. The playlist is scrolled no further than it takes to see the
+    /// line, so choosing a segment that is already in view does not move it.
+    pub fn follow_highlight(self, line_number: usize, reveal: impl Fn(usize) + 'static) {
+        let hash = use_location().hash;
+        Effect::new(move |_| {
+            let refers_to_line = hash.with_untracked(|hash| {
+                hash.strip_prefix('#')
+                    .and_then(line_number_from_anchor_id)
+                    .is_some()
+            });
+            if !refers_to_line {
+                reveal(line_number);
+                self.scroll_to_with(line_number, ScrollLogicalPosition::Nearest);
+            }
+        });
+    }
+
+    /// Brings the 1-based line given by `line_at_top` to the top of the window whenever it gives
+    /// one, which keeps the place of whoever was reading the playlist as it is reloaded.
+    pub fn keep_at_top(
+        self,
+        line_at_top: impl Fn() -> Option<usize> + 'static,
+        reveal: impl Fn(usize) + 'static,
+    ) {
+        Effect::new(move |_| {
+            if let Some(line_number) = line_at_top() {
+                reveal(line_number);
+                self.scroll_to(line_number);
+            }
+        });
+    }
+
+    /// Reveals the lines that the location hash refers to, which the validation report and copied
+    /// links point at, where `reveal` shows the 1-based line first if it was hidden.
+    pub fn follow_line_anchors(self, reveal: impl Fn(usize) + 'static) {
+        let hash = use_location().hash;
+        Effect::new(move |_| {
+            if let Some(line_number) =
+                hash.with(|hash| hash.strip_prefix('#').and_then(line_number_from_anchor_id))
+            {
+                reveal(line_number);
+                self.scroll_to(line_number);
+            }
+        });
+    }
+}
+
+#[component]
+fn LineChunk(
+    keys: Memo<Vec<u64>>,
+    // The 0-based index of the first line of the chunk, and the most lines that it holds.
+    start: usize,
+    chunk_lines: usize,
+    rendered: ArcRwSignal<bool>,
+    windowed: bool,
+    line_view: impl Fn(Signal<usize>) -> AnyView + Copy + Send + Sync + 'static,
+    placeholder_lines: Signal<usize>,
+) -> impl IntoView {
+    let rendered = RwSignal::from(rendered);
+    let chunk = NodeRef::<Div>::new();
+    // The height that the chunk was last rendered at, along with the number of lines it showed, so
+    // that the placeholder that takes its place (while the same lines are shown) does not move the
+    // lines that follow it.
+    let rendered_height = RwSignal::new(None::<(usize, f64)>);
+    if windowed {
+        use_intersection_observer_with_options(
+            chunk,
+            move |entries, _| {
+                let Some(near) = entries.last().map(|entry| entry.is_intersecting()) else {
+                    return;
+                };
+                if near == rendered.get_untracked() {
+                    return;
+                }
+                // A playlist that is hidden (e.g. while its raw text is shown) has no height.
+                if !near
+                    && let Some(height) = chunk
+                        .get_untracked()
+                        .map(|chunk| chunk.get_bounding_client_rect().height())
+                        .filter(|height| *height > 0.0)
+                {
+                    rendered_height.set(Some((placeholder_lines.get_untracked(), height)));
+                }
+                rendered.set(near);
+            },
+            UseIntersectionObserverOptions::default().root_margin(RENDER_MARGIN),
+        );
+    }
+    // The rendered lines are kept by their keys, so that only the lines that are new to the chunk
+    // are built when the playlist is reloaded.
+    let chunk_keys = move || {
+        keys.with(|keys| {
+            keys.iter()
+                .skip(start)
+                .take(chunk_lines)
+                .copied()
+                .collect::<Vec<_>>()
+        })
+    };
+    let height = move || match rendered_height.get() {
+        Some((lines, height)) if lines == placeholder_lines.get() => format!("{height}px"),
+        _ => format!("{}lh", placeholder_lines.get()),
+    };
+    view! {
+        <div node_ref=chunk>
+            {move || {
+                if rendered.get() {
+                    Either::Left(view! {
+                        <ForEnumerate
+                            each=chunk_keys
+                            key=|key| *key
+                            children=move |index, _| {
+                                line_view(Signal::derive(move || start + index.get()))
+                            }
+                        />
+                    })
+                } else {
+                    Either::Right(
+                        view! { <div class=LINE_CHUNK_PLACEHOLDER_CLASS style:height=height /> },
+                    )
+                }
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,51 @@
+use crate::utils::{
+    i18n::{Message, tr},
+    settings::FeatureToggle,
+    storage::{load_flag, save_flag},
+};
+use leptos::prelude::*;
+
+// The keys that each view remembers its line wrapping under, as a long line is worth scrolling to
+// in one view (e.g. comparing attributes across a diff) while wrapping is better in another.
+pub const PLAYLIST_NO_WRAP_KEY: &str = FeatureToggle::PlaylistNoWrap.key();
+pub const DIFF_NO_WRAP_KEY: &str = FeatureToggle::DiffNoWrap.key();
+
+/// Whether the lines of a view wrap anywhere to fit its width (the default), or are kept whole on
+/// one line each, so that long tags such as `EXT-X-DATERANGE` and `EXT-X-STREAM-INF` read as they
+/// do in the playlist and the view scrolls horizontally instead.
+#[derive(Clone, Copy)]
+pub struct LineWrap(RwSignal<bool>);
+
+impl LineWrap {
+    pub fn load(key: &'static str) -> Self {
+        let no_wrap = RwSignal::new(load_flag(key));
+        Effect::watch(
+            move || no_wrap.get(),
+            move |no_wrap, _, _| save_flag(key, *no_wrap),
+            false,
+        );
+        Self(no_wrap)
+    }
+
+    pub fn is_no_wrap(&self) -> bool {
+        self.0.get()
+    }
+}
+
+#[component]
+pub fn LineWrapToggle(wrap: LineWrap) -> impl IntoView {
+    let LineWrap(no_wrap) = wrap;
+    view! {
+        <button
+            class="button"
+            type="button"
+            aria-pressed=move || no_wrap.get().to_string()
+            title=tr(Message::LineWrapTitle)
+            on:click=move |_| no_wrap.update(|no_wrap| *no_wrap = !*no_wrap)
+        >
+            {move || {
+                if no_wrap.get() { tr(Message::WrapLines) } else { tr(Message::DontWrapLines) }
+            }}
+        </button>
+    }
+}
@@ -0,0 +1,130 @@
+use super::{LIVE_TIMELINE_CLASS, VALIDATION_WARNING_CLASS};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    live_timeline::{format_time_of_day, live_timeline},
+    segment_runs::format_duration,
+};
+use leptos::prelude::*;
+use std::time::Duration;
+use web_sys::js_sys::Date;
+
+/// A timeline of a live playlist against the wall clock, placed by its EXT-X-PROGRAM-DATE-TIME
+/// tags, with the live edge that players start from, the hold-back window behind the end of the
+/// playlist, and a marker for the current time that moves as time passes. The gap between the end
+/// of the playlist and the current time shows whether segments are being published on schedule.
+#[component]
+pub fn LiveTimeline(playlist: String) -> Option<impl IntoView> {
+    let live = live_timeline(&playlist)?;
+    let now = RwSignal::new(Date::now());
+    match set_interval_with_handle(move || now.set(Date::now()), Duration::from_secs(1)) {
+        Ok(handle) => on_cleanup(move || handle.clear()),
+        Err(e) => log::error!("failed to schedule the live timeline clock: {e:?}"),
+    }
+    let (start, end, live_edge) = (
+        live.start_millis(),
+        live.end_millis(),
+        live.live_edge_millis(),
+    );
+    // The timeline spans the hold back (which can be longer than the playlist) through to the
+    // current time, so that the now marker is always on it.
+    let first = start.min(live_edge);
+    let last = move || end.max(now.get());
+    let percent = move |millis: f64| {
+        let span = (last() - first).max(1.0);
+        format!("{}%", (millis - first) / span * 100.0)
+    };
+    let segments = live
+        .segments
+        .iter()
+        .map(|(segment_start, duration)| {
+            let (segment_start, duration) = (*segment_start, *duration);
+            let title = tr_with(
+                Message::LiveSegmentTitle,
+                &[
+                    ("time", &format_time_of_day(segment_start)),
+                    ("duration", &format!("{duration:.3}")),
+                ],
+            );
+            view! {
+                <div
+                    class="timeline-segment"
+                    title=title
+                    style:left=move || percent(segment_start)
+                    style:width=move || percent(first + duration * 1000.0)
+                />
+            }
+        })
+        .collect_view();
+    let hold_back_title = match live.part_hold_back {
+        Some(part_hold_back) => tr_with(
+            Message::HoldBackWithPart,
+            &[
+                ("hold_back", &format_duration(live.hold_back)),
+                ("part_hold_back", &format_duration(part_hold_back)),
+            ],
+        ),
+        None => tr_with(
+            Message::HoldBack,
+            &[("hold_back", &format_duration(live.hold_back))],
+        ),
+    };
+    let status = move || {
+        let now = now.get();
+        let delay = live.publishing_delay(now);
+        let summary = tr_with(
+            if delay < 0.0 {
+                Message::LiveStatusAfter
+            } else {
+                Message::LiveStatusBefore
+            },
+            &[
+                ("now", &format_time_of_day(now)),
+                ("delay", &format_duration(delay.abs())),
+                ("hold_back", &format_duration(live.hold_back)),
+                ("live_edge", &format_time_of_day(live_edge)),
+            ],
+        );
+        let warning = if delay < 0.0 {
+            Some(String::from(tr(Message::PlaylistEndsInFuture)))
+        } else if live.is_behind_schedule(now) {
+            Some(tr_with(
+                Message::PlaylistBehindSchedule,
+                &[("target_duration", &format_duration(live.target_duration))],
+            ))
+        } else {
+            None
+        };
+        view! {
+            <p>{summary}</p>
+            {warning.map(|warning| view! { <p class=VALIDATION_WARNING_CLASS>{warning}</p> })}
+        }
+    };
+    Some(view! {
+        <div class=LIVE_TIMELINE_CLASS>
+            <div class="timeline-track">
+                {segments}
+                <div
+                    class="timeline-hold-back"
+                    title=hold_back_title
+                    style:left=move || percent(live_edge)
+                    style:width=move || percent(first + (end - live_edge))
+                />
+                <div
+                    class="timeline-live-edge"
+                    title=tr(Message::LiveEdge)
+                    style:left=move || percent(live_edge)
+                />
+                <div
+                    class="timeline-now"
+                    title=tr(Message::LiveNow)
+                    style:left=move || percent(now.get())
+                />
+            </div>
+            <div class="timeline-axis">
+                <span>{format_time_of_day(first)}</span>
+                <span>{move || format_time_of_day(last())}</span>
+            </div>
+            {status}
+        </div>
+    })
+}
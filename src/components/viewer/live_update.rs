@@ -0,0 +1,206 @@
+use super::{
+    LIVE_UPDATE_CLASS, VALIDATION_ERROR_CLASS, network::NetworkLog, validation::ValidationReport,
+};
+use crate::{
+    components::{AppSettings, LocalPlaylist},
+    utils::{
+        i18n::{Message, tr, tr_with},
+        network::{FetchError, FetchTextResponse, ResponseHeaders, Validators, revalidate_text},
+        settings::Settings,
+        validation::{is_live, validate_update},
+    },
+};
+use leptos::{either::Either, prelude::*, task::spawn_local};
+use std::time::Duration;
+
+/// The rechecks of a live playlist, which reload it in place in the viewer, so that the lines that
+/// did not change are left as they were. This is provided as context by the page, so that a viewer
+/// re-created while the playlist is open (such as for a segment that is opened) shows the playlist
+/// as it was last reloaded.
+#[derive(Clone, Copy)]
+pub struct LiveSnapshot {
+    playlist_url: Memo<Option<String>>,
+    // The version of the playlist that was shown before it was last reloaded.
+    previous: RwSignal<Option<Snapshot>>,
+    reloaded: RwSignal<Option<FetchTextResponse>>,
+    // Why the last recheck failed, if it did, in which case the playlist is shown as before.
+    failed: RwSignal<Option<FetchError>>,
+    rechecks: RwSignal<u32>,
+}
+
+#[derive(Clone)]
+struct Snapshot {
+    playlist: String,
+    validators: Validators,
+}
+
+impl LiveSnapshot {
+    /// Rechecks reload the playlist from `playlist_url`.
+    pub fn new(playlist_url: Memo<Option<String>>) -> Self {
+        Self {
+            playlist_url,
+            previous: RwSignal::new(None),
+            reloaded: RwSignal::new(None),
+            failed: RwSignal::new(None),
+            rechecks: RwSignal::new(0),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.previous.set(None);
+        self.reloaded.set(None);
+        self.failed.set(None);
+    }
+
+    /// The response that the playlist was last reloaded from, if it has been. Reading this
+    /// subscribes to reloads.
+    pub fn reloaded(&self) -> Option<FetchTextResponse> {
+        self.reloaded.get()
+    }
+
+    /// The text of the playlist as it was last reloaded, or else as it was first fetched.
+    pub fn playlist(&self, fetched: String) -> Memo<String> {
+        let reloaded = self.reloaded;
+        Memo::new(move |_| {
+            reloaded.with(|reloaded| match reloaded {
+                Some(response) => response.response_text.clone(),
+                None => fetched.clone(),
+            })
+        })
+    }
+
+    /// What the last recheck came to, if there has been one. Reading this subscribes to rechecks.
+    pub fn rechecked(&self) -> Option<Result<FetchTextResponse, FetchError>> {
+        if self.rechecks.get() == 0 {
+            return None;
+        }
+        match self.failed.get_untracked() {
+            Some(error) => Some(Err(error)),
+            None => self.reloaded.get_untracked().map(Ok),
+        }
+    }
+
+    // Reloads the `playlist` that is shown on condition that it changed since the response that
+    // `headers` came from.
+    fn recheck(&self, playlist: String, headers: ResponseHeaders, settings: Settings) {
+        let Some(url) = self.playlist_url.get_untracked() else {
+            return;
+        };
+        let snapshot = *self;
+        let validators = headers.validators();
+        spawn_local(async move {
+            let result =
+                revalidate_text(url.clone(), &validators, playlist.clone(), &settings).await;
+            // The page may have moved on to another playlist in the meantime.
+            if snapshot.playlist_url.get_untracked().as_deref() != Some(url.as_str()) {
+                return;
+            }
+            match result {
+                Ok(response) => {
+                    snapshot.failed.set(None);
+                    snapshot.previous.set(Some(Snapshot {
+                        playlist,
+                        validators,
+                    }));
+                    snapshot.reloaded.set(Some(response));
+                }
+                Err(error) => snapshot.failed.set(Some(error)),
+            }
+            snapshot.rechecks.update(|rechecks| *rechecks += 1);
+        });
+    }
+}
+
+#[component]
+pub fn LiveUpdateCheck(#[prop(into)] playlist: Signal<String>) -> Option<impl IntoView> {
+    let snapshot = use_context::<LiveSnapshot>()?;
+    let network_log = use_context::<NetworkLog>()?;
+    // A playlist opened from a file has nowhere to be reloaded from.
+    if use_context::<LocalPlaylist>().is_some_and(|local| local.get_untracked().is_some()) {
+        return None;
+    }
+    let settings = AppSettings::current();
+    let recheck = move || {
+        snapshot.recheck(
+            playlist.get_untracked(),
+            network_log.playlist_headers(),
+            settings.get_untracked(),
+        )
+    };
+    // With a recheck interval set, the playlist is rechecked on its own while it is still live,
+    // where each recheck schedules the next once it is done.
+    let interval = settings.with_untracked(|settings| settings.live_recheck_seconds);
+    if interval > 0 {
+        Effect::new(move |_| {
+            snapshot.rechecks.track();
+            if !playlist.with(|playlist| is_live(playlist)) {
+                return;
+            }
+            match set_timeout_with_handle(recheck, Duration::from_secs(u64::from(interval))) {
+                Ok(handle) => on_cleanup(move || handle.clear()),
+                Err(e) => log::error!("failed to schedule the recheck of the live playlist: {e:?}"),
+            }
+        });
+    }
+    let report = move || {
+        snapshot.previous.with(|previous| match previous {
+            Some(previous) => {
+                let summary = snapshot.reloaded.with(|reloaded| {
+                    reloaded.as_ref().map(|reloaded| {
+                        revalidation_summary(&previous.validators, &reloaded.headers)
+                    })
+                });
+                let findings =
+                    playlist.with(|playlist| validate_update(&previous.playlist, playlist));
+                Either::Left(view! {
+                    <p>{summary}</p>
+                    <ValidationReport findings title=tr(Message::LiveUpdateTitle) />
+                })
+            }
+            None => Either::Right(view! {
+                <p>{tr(Message::LiveUpdateHint)}</p>
+            }),
+        })
+    };
+    let failure = move || {
+        snapshot.failed.with(|failed| {
+            failed.as_ref().map(|error| {
+                let message = tr_with(Message::RecheckFailed, &[("error", &error.error())]);
+                view! { <p class=VALIDATION_ERROR_CLASS>{message}</p> }
+            })
+        })
+    };
+    // Once rechecked, the update is still reported if it ended the playlist.
+    let shown = move || {
+        snapshot.previous.with(Option::is_some) || playlist.with(|playlist| is_live(playlist))
+    };
+    Some(view! {
+        <Show when=shown>
+            <div class=LIVE_UPDATE_CLASS>
+                <button class="button" on:click=move |_| recheck()>
+                    {tr(Message::Recheck)}
+                </button>
+                {failure}
+                {report}
+            </div>
+        </Show>
+    })
+}
+
+fn revalidation_summary(validators: &Validators, headers: &ResponseHeaders) -> String {
+    if validators.is_empty() {
+        // ETag is not CORS-safelisted, so is commonly hidden from the page.
+        return String::from(tr(Message::RecheckUnconditional));
+    }
+    if headers.status == 304 {
+        String::from(tr(Message::RecheckNotModified))
+    } else {
+        tr_with(
+            Message::RecheckReloaded,
+            &[
+                ("status", &headers.status.to_string()),
+                ("status_text", &headers.status_text),
+            ],
+        )
+    }
+}
@@ -1,11 +1,12 @@
 use super::VIEWER_CLASS;
+use crate::utils::i18n::{Message, tr};
 use leptos::prelude::*;
 
 #[component]
 pub fn ViewerLoading() -> impl IntoView {
     view! {
         <div class=VIEWER_CLASS>
-            <p>"Loading..."</p>
+            <p>{tr(Message::Loading)}</p>
         </div>
     }
 }
@@ -0,0 +1,74 @@
+use super::{MEDIA_PREVIEW_CLASS, VALIDATION_WARNING_CLASS};
+use crate::utils::{
+    i18n::{Message, tr},
+    network::SharedBytes,
+    playback::{MediaSourcePlayback, attach_segment},
+};
+use leptos::{
+    either::Either,
+    html::{Audio, Video},
+    prelude::*,
+    task::spawn_local,
+};
+use web_sys::HtmlMediaElement;
+
+/// Plays an fMP4 media segment (after its initialization segment) through Media Source Extensions,
+/// so that a single segment (or part) can be spot-checked without a player: heard, e.g. to catch a
+/// silent or wrong-language segment, and seen, when it is video.
+#[component]
+pub fn MediaPreview(
+    /// The MIME type (with codecs) to open the MediaSource for, which is either `video/mp4` or
+    /// `audio/mp4`.
+    mime_type: String,
+    init_data: SharedBytes,
+    data: SharedBytes,
+) -> impl IntoView {
+    let video = NodeRef::<Video>::new();
+    let audio = NodeRef::<Audio>::new();
+    let attached = RwSignal::new(false);
+    let error = RwSignal::new(None::<String>);
+    let playback = StoredValue::new_local(None::<MediaSourcePlayback>);
+    let is_video = mime_type.starts_with("video/");
+    let play = move |_| {
+        let element = video
+            .get()
+            .map(HtmlMediaElement::from)
+            .or_else(|| audio.get().map(HtmlMediaElement::from));
+        let Some(element) = element else {
+            return;
+        };
+        let (mime_type, init_data, data) = (mime_type.clone(), init_data.clone(), data.clone());
+        spawn_local(async move {
+            match attach_segment(&element, &mime_type, &init_data, &data).await {
+                Ok(attached_segment) => {
+                    playback.set_value(Some(attached_segment));
+                    attached.set(true);
+                    error.set(None);
+                    let _ = element.play();
+                }
+                Err(e) => error.set(Some(e)),
+            }
+        });
+    };
+    let element = if is_video {
+        Either::Left(view! { <video node_ref=video controls hidden=move || !attached.get() /> })
+    } else {
+        Either::Right(view! { <audio node_ref=audio controls hidden=move || !attached.get() /> })
+    };
+    view! {
+        <div class=MEDIA_PREVIEW_CLASS>
+            <button
+                class="button"
+                type="button"
+                title=tr(Message::PlaySegmentTitle)
+                on:click=play
+            >
+                {tr(Message::Play)}
+            </button>
+            {element}
+            {move || {
+                error.get().map(|error| view! { <p class=VALIDATION_WARNING_CLASS>{error}</p> })
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,123 @@
+use super::{
+    MINIMAP_CLASS, PLAYLIST_LINE_CLASS, collapsed_segments::CollapsedRuns, line_window::LineWindow,
+    playlist::line_number_from_anchor_id,
+};
+use crate::utils::playlist_minimap::minimap;
+use leptos::{ev, html::Div, prelude::*};
+
+/// The number of rows that the lines of the playlist are gathered into on the minimap.
+const MINIMAP_ROWS: usize = 400;
+/// How far apart (in pixels) the points are that are tried in turn when looking for the line at
+/// the top or bottom of the window, as a point may fall between lines (e.g. on a collapsed run).
+const PROBE_STEP: f64 = 16.0;
+const PROBES: usize = 8;
+
+/// A thin column pinned to the side of the window while a long playlist is scrolled, which shows
+/// where its segments, discontinuities, dateranges and keys are across the whole playlist, along
+/// with the part of the playlist that is in view. Clicking the column scrolls to that part of the
+/// playlist.
+#[component]
+pub fn PlaylistMinimap(
+    playlist: String,
+    /// The lines of the playlist, which are measured to find the part of it in view.
+    lines: NodeRef<Div>,
+    line_window: LineWindow,
+    collapsed: CollapsedRuns,
+) -> impl IntoView {
+    let minimap = minimap(&playlist, MINIMAP_ROWS);
+    let line_count = minimap.line_count.max(1);
+    let percent = move |line: usize| format!("{}%", (line - 1) as f64 / line_count as f64 * 100.0);
+    // The 1-based numbers of the first and last lines in view, while the playlist is taller than
+    // the window and is on screen.
+    let in_view = RwSignal::new(None::<(usize, usize)>);
+    let update = move || {
+        let Some(lines) = lines.get_untracked() else {
+            return;
+        };
+        let window_height = window()
+            .inner_height()
+            .ok()
+            .and_then(|height| height.as_f64())
+            .unwrap_or_default();
+        let rect = lines.get_bounding_client_rect();
+        let visible =
+            rect.height() > window_height && rect.bottom() > 0.0 && rect.top() < window_height;
+        let line_at = |y: f64, step: f64| {
+            (0..PROBES).find_map(|probe| {
+                document()
+                    .element_from_point(rect.left() as f32 + 1.0, (y + step * probe as f64) as f32)?
+                    .closest(&format!(".{PLAYLIST_LINE_CLASS}"))
+                    .ok()
+                    .flatten()
+                    .and_then(|line| line_number_from_anchor_id(&line.id()))
+            })
+        };
+        let range = visible
+            .then(|| {
+                let first = line_at(rect.top().max(0.0) + 1.0, PROBE_STEP)?;
+                let last = line_at(rect.bottom().min(window_height) - 1.0, -PROBE_STEP)?;
+                Some((first, last.max(first)))
+            })
+            .flatten();
+        // The last known range is kept while the lines at the edges cannot be found.
+        if !visible || range.is_some() {
+            in_view.set(range);
+        }
+    };
+    let scroll = window_event_listener(ev::scroll, move |_| update());
+    let resize = window_event_listener(ev::resize, move |_| update());
+    on_cleanup(move || {
+        scroll.remove();
+        resize.remove();
+    });
+    request_animation_frame(update);
+    let column = NodeRef::<Div>::new();
+    let go_to = move |ev: ev::MouseEvent| {
+        let Some(column) = column.get_untracked() else {
+            return;
+        };
+        let rect = column.get_bounding_client_rect();
+        let fraction = ((f64::from(ev.client_y()) - rect.top()) / rect.height()).clamp(0.0, 1.0);
+        let line = ((fraction * line_count as f64) as usize + 1).min(line_count);
+        collapsed.reveal(line - 1);
+        line_window.scroll_to(line);
+    };
+    let marks = minimap
+        .marks
+        .into_iter()
+        .map(|(_, mark, line)| {
+            view! {
+                <div
+                    class=format!("minimap-mark {}", mark.label())
+                    style:top=percent(line)
+                    title=format!("{} at line {line}", mark.label())
+                />
+            }
+        })
+        .collect_view();
+    view! {
+        <div
+            node_ref=column
+            class=MINIMAP_CLASS
+            class:hidden=move || in_view.with(Option::is_none)
+            aria-hidden="true"
+            on:click=go_to
+        >
+            {marks}
+            <div
+                class="minimap-view"
+                style:top=move || {
+                    in_view.get().map(|(first, _)| percent(first)).unwrap_or_default()
+                }
+                style:height=move || {
+                    in_view
+                        .get()
+                        .map(|(first, last)| {
+                            format!("{}%", (last + 1 - first) as f64 / line_count as f64 * 100.0)
+                        })
+                        .unwrap_or_default()
+                }
+            />
+        </div>
+    }
+}
@@ -1,47 +1,182 @@
+mod ad_avails;
+mod ad_breaks;
 mod asset_list;
+mod audio_preview;
+mod automation;
+mod bandwidth;
+mod box_tree;
+mod breadcrumbs;
+mod collapsed_segments;
 mod daterange_schedule;
+mod decryption;
+mod deep_link;
 mod error;
+mod error_toast;
+mod find_bar;
+mod font_size;
+mod hex_dump;
 mod image;
+mod interstitials;
 mod isobmff;
+mod key_ids;
+mod key_rotation;
+mod last_segment;
+mod line_filter;
+mod line_window;
+mod line_wrap;
+mod live_timeline;
+mod live_update;
 mod loading;
+mod media_preview;
+mod minimap;
+mod network;
+mod pinned_view;
 mod playlist;
-mod preformatted;
+mod playlist_cache;
+mod playlist_diff;
+mod playlist_timeline;
+mod raw_text;
+mod report;
+mod sample_encryption;
 mod scte35;
+mod segment_context;
+mod segment_download;
+mod segment_navigation;
+mod split_layout;
+mod tag_legend;
+mod thumbnail;
+mod timeline_drift;
+mod validation;
+mod variant_summary;
+mod webvtt;
 
 use crate::{
-    components::viewer::daterange_schedule::DaterangeScheduleView,
+    components::{AppSettings, viewer::daterange_schedule::DaterangeScheduleView},
     utils::{
-        network::{fetch_array_buffer, fetch_text, FetchError, FetchTextResponse, RequestRange},
+        byterange::RequestRange,
+        download::download_file_name,
+        encryption::{SegmentKey, keys_for_segment},
+        fetch_timing::FetchTiming,
+        href::{RedirectedPlaylistUrl, resolve_playlist_relative_url},
+        i18n::{Message, tr, tr_with},
+        interstitials::interstitial,
+        network::{
+            FetchArrayBufferResonse, FetchError, FetchTextResponse, ResponseHeaders, SharedBytes,
+            fetch_segment_structure, fetch_text,
+        },
+        playlist_lines::{map_for_segment, variable_definitions},
         query_codec::{
             AssetListContext, DaterangeScheduleContext, MediaSegmentContext, PartSegmentContext,
-            SupplementalViewQueryContext,
+            PinnedViewQueryContext, SupplementalViewQueryContext, VariantContext,
         },
         response::{determine_segment_type, SegmentType},
+        segment_cache::{cached_segment, fetch_segment, fetch_segment_with_init},
+        settings::FeatureToggle,
+        storage::{load_flag, save_flag},
+        timeline_drift::{SegmentDateTime, program_date_time_for_segment},
+        webvtt::{SubtitleSegment, segment_window},
     },
 };
 use asset_list::AssetListView;
+use audio_preview::PackedAudioViewer;
+pub use automation::Automation;
+pub use box_tree::BoxTreeExpansion;
+pub use breadcrumbs::Breadcrumbs;
+use decryption::{Aes128Decryption, EncryptedSegment};
 use error::ViewerError;
+use error_toast::ErrorToast;
+use font_size::FontSize;
 use image::ImageViewer;
 use isobmff::IsobmffViewer;
-use leptos::{either::Either, prelude::*};
+pub use last_segment::LastSegment;
+use leptos::{either::Either, html::Div, prelude::*};
+pub use live_update::LiveSnapshot;
 pub use loading::ViewerLoading;
+use network::NetworkLog;
+use pinned_view::SupplementalTabs;
 use playlist::{Highlighted, PlaylistViewer};
-use preformatted::PreformattedViewer;
+pub use playlist_cache::PlaylistCache;
+pub use playlist_diff::PlaylistDiff;
+use report::SelectedBox;
 use scte35::Scte35Viewer;
-use std::collections::HashMap;
+use segment_download::{SegmentDownload, SegmentDownloadView};
+use split_layout::{SplitDivider, SplitLayout};
+use std::{collections::HashMap, sync::Arc};
+pub use timeline_drift::DriftHistory;
+use url::Url;
+pub use webvtt::SubtitleHistory;
+use webvtt::WebVttViewer;
 
 const VIEWER_CLASS: &str = "viewer-content";
+const LOCAL_SEGMENT_VIEWER_CLASS: &str = "viewer-content local-segment";
+const PLAYLIST_DIFF_CLASS: &str = "viewer-content playlist-diff";
 const MAIN_VIEW_CLASS: &str = "viewer-main";
 const SUPPLEMENTAL_VIEW_CLASS: &str = "viewer-supplemental supplemental-active";
 const ISOBMFF_VIEW_CLASS: &str = "viewer-supplemental isobmff-view supplemental-active";
 const IMAGE_VIEW_CLASS: &str = "viewer-supplemental image-view supplemental-active";
+const SUPPLEMENTAL_TABS_CLASS: &str = "viewer-supplemental supplemental-tabs supplemental-active";
 const MAIN_VIEW_WITH_SUPPLEMENTAL_CLASS: &str = "viewer-main supplemental-active";
 const ERROR_CONTAINER_CLASS: &str = "error-container";
 const ERROR_CLASS: &str = "error";
+const ERROR_TOAST_CLASS: &str = "error-toast";
 const TAG_CLASS: &str = "hls-line tag";
 const URI_CLASS: &str = "hls-line uri";
 const COMMENT_CLASS: &str = "hls-line comment";
 const BLANK_CLASS: &str = "hls-line blank";
+const BADGE_CLASS: &str = "badge";
+const BADGE_WARNING_CLASS: &str = "badge warning";
+const BADGE_CAUTION_CLASS: &str = "badge caution";
+const PLAYLIST_LINE_CLASS: &str = "playlist-line";
+const PLAYLIST_LINE_ERROR_CLASS: &str = "playlist-line diagnosed diagnosed-error";
+const PLAYLIST_LINE_WARNING_CLASS: &str = "playlist-line diagnosed diagnosed-warning";
+const VALIDATION_REPORT_CLASS: &str = "validation-report";
+const VALIDATION_PASSED_CLASS: &str = "validation-report passed";
+const VALIDATION_ERROR_CLASS: &str = "validation-error";
+const VALIDATION_WARNING_CLASS: &str = "validation-warning";
+const VALIDATION_EXPORT_CLASS: &str = "validation-export";
+const BANDWIDTH_CHECK_CLASS: &str = "bandwidth-check";
+const TIMELINE_DRIFT_CLASS: &str = "timeline-drift";
+const LIVE_UPDATE_CLASS: &str = "live-update";
+const LIVE_TIMELINE_CLASS: &str = "live-timeline";
+const AD_AVAILS_CLASS: &str = "validation-report ad-avails";
+const AD_BREAKS_CLASS: &str = "validation-report ad-breaks";
+const INTERSTITIALS_CLASS: &str = "validation-report interstitials";
+const KEY_IDS_CLASS: &str = "validation-report key-ids";
+const KEY_ROTATION_CLASS: &str = "validation-report key-rotation";
+const VARIANT_SUMMARY_CLASS: &str = "validation-report variant-summary";
+const SAMPLE_ENCRYPTION_CLASS: &str = "validation-report sample-encryption";
+const FIND_BAR_CLASS: &str = "find-bar";
+const LINE_FILTER_CLASS: &str = "line-filter";
+const CHIP_CLASS: &str = "chip";
+const TAG_LEGEND_CLASS: &str = "tag-legend";
+const HIDDEN_LINES_CLASS: &str = "hidden-lines";
+const COLLAPSED_SEGMENTS_CLASS: &str = "collapsed-segments";
+const LINE_CHUNK_PLACEHOLDER_CLASS: &str = "line-chunk-placeholder";
+const SEGMENT_CONTEXT_CLASS: &str = "segment-context";
+const SEGMENT_NAVIGATION_CLASS: &str = "segment-navigation";
+const DEEP_LINK_CLASS: &str = "deep-link";
+const DECRYPTION_CLASS: &str = "decryption";
+const DOWNLOAD_PROGRESS_CLASS: &str = "download-progress";
+const PLAYLIST_TIMELINE_CLASS: &str = "playlist-timeline";
+const MINIMAP_CLASS: &str = "playlist-minimap";
+const SPEC_TERM_CLASS: &str = "spec-term";
+const PLAYLIST_ACTIONS_CLASS: &str = "playlist-actions";
+const RAW_PLAYLIST_CLASS: &str = "raw-playlist";
+const RENDERED_PLAYLIST_CLASS: &str = "rendered-playlist";
+const FONT_SIZE_CONTROL_CLASS: &str = "font-size-control";
+const SPLIT_DIVIDER_CLASS: &str = "split-divider";
+const SPLIT_HANDLE_CLASS: &str = "split-handle";
+const NETWORK_PANEL_CLASS: &str = "network-panel";
+const NETWORK_HEADER_NOTABLE_CLASS: &str = "notable";
+const STRUCTURE_ONLY_CLASS: &str = "structure-only";
+const AUDIO_PREVIEW_CLASS: &str = "audio-preview";
+const ASSET_PREVIEW_CLASS: &str = "asset-preview";
+const MEDIA_PREVIEW_CLASS: &str = "media-preview";
+const THUMBNAIL_CLASS: &str = "thumbnail";
+const IFRAME_THUMBNAILS_CLASS: &str = "iframe-thumbnails";
+const HEX_DUMP_CLASS: &str = "hex-dump";
+const BOX_TREE_CONTROLS_CLASS: &str = "box-tree-controls";
+const BITRATE_GRAPH_CLASS: &str = "bitrate-graph";
 const HIGHLIGHTED: &str = "highlighted";
 const HIGHLIGHTED_URI_CLASS: &str = "hls-line uri highlighted";
 const UNDERLINED: &str = "underlined";
@@ -53,41 +188,82 @@ const SPACER_BOTTOM: &str = "spacer-bottom-large";
 pub fn Viewer(
     fetch_response: Result<FetchTextResponse, FetchError>,
     supplemental_context: Option<String>,
+    pinned_context: Option<String>,
     imported_definitions: HashMap<String, String>,
+    #[prop(optional_no_strip)] variant: Option<VariantContext>,
 ) -> impl IntoView {
+    let live_snapshot = use_context::<LiveSnapshot>();
     let FetchTextResponse {
         response_text: playlist,
+        url,
+        headers,
+        timing,
     } = match fetch_response {
-        Ok(response) => response,
+        // A live playlist that has been rechecked is shown as it was last reloaded.
+        Ok(response) => live_snapshot
+            .and_then(|snapshot| snapshot.reloaded())
+            .unwrap_or(response),
         Err(error) => {
             return view! {
                 <Container>
-                    <ViewerError error=error.error extra_info=error.extra_info />
+                    <ViewerError error=error.error() extra_info=error.extra_info() />
                 </Container>
             };
         }
     };
-    let Some(context) = supplemental_context else {
-        return view! {
-            <Container>
-                <ErrorBounded>
-                    <PlaylistViewer playlist imported_definitions />
-                </ErrorBounded>
-            </Container>
-        };
+    if url != headers.url
+        && let Ok(url) = Url::parse(&url)
+    {
+        provide_context(RedirectedPlaylistUrl(url));
+    }
+    let network_log = NetworkLog::new(headers, timing);
+    provide_context(network_log);
+    // A live playlist is reloaded in place when it is rechecked, rather than the viewer being
+    // re-created, so that only the lines that changed are rendered again.
+    let text = playlist.clone();
+    let playlist = match live_snapshot {
+        Some(snapshot) => {
+            Effect::watch(
+                move || snapshot.reloaded(),
+                move |reloaded, _, _| {
+                    if let Some(reloaded) = reloaded {
+                        let label = tr(Message::NetworkPlaylist);
+                        network_log.record(label, reloaded.headers.clone(), reloaded.timing);
+                    }
+                },
+                false,
+            );
+            Signal::from(snapshot.playlist(playlist))
+        }
+        None => Signal::derive(move || playlist.clone()),
     };
-    let context = match SupplementalViewQueryContext::try_from(context.as_str()) {
+    let pinned = pinned_context.map(|pinned| {
+        PinnedViewQueryContext::try_from(pinned.as_str()).map(|pinned| {
+            // The pinned view may have been opened from another playlist, so nothing that this
+            // playlist says of it is applied.
+            let (_, view) = supplemental_view(pinned.context.clone(), "", &HashMap::new(), None);
+            (pinned, view)
+        })
+    });
+    let context = supplemental_context
+        .map(|context| SupplementalViewQueryContext::try_from(context.as_str()))
+        .transpose();
+    let context = match context {
         Ok(context) => context,
         Err(e) => {
             return view! {
                 <Container>
                     <ErrorBounded>
-                        <PlaylistViewer playlist imported_definitions supplemental_showing=true />
+                        <PlaylistViewer
+                            playlist
+                            imported_definitions
+                            variant
+                            supplemental_showing=true
+                        />
                     </ErrorBounded>
                     <div class=SUPPLEMENTAL_VIEW_CLASS>
                         <ViewerError
-                            error="Error: unable to parse query parameter for supplemental view"
-                                .to_string()
+                            error=tr(Message::SupplementalViewQueryError).to_string()
                             extra_info=Some(e.to_string())
                         />
                     </div>
@@ -95,66 +271,97 @@ pub fn Viewer(
             };
         }
     };
+    let current = context.map(|context| {
+        let (highlighted, view) = supplemental_view(
+            context.clone(),
+            &text,
+            &imported_definitions,
+            variant.clone(),
+        );
+        (context, highlighted, view)
+    });
+    let (current, highlighted) = match current {
+        Some((context, highlighted, view)) => (Some((context, view)), Some(highlighted)),
+        None => (None, None),
+    };
+    if current.is_none() && pinned.is_none() {
+        return view! {
+            <Container>
+                <ErrorBounded>
+                    <PlaylistViewer playlist imported_definitions variant />
+                </ErrorBounded>
+            </Container>
+        };
+    }
+    view! {
+        <Container>
+            <ErrorBounded>
+                <PlaylistViewer
+                    playlist
+                    imported_definitions
+                    variant
+                    supplemental_showing=true
+                    highlighted=highlighted
+                />
+            </ErrorBounded>
+            <SupplementalTabs current pinned />
+        </Container>
+    }
+}
+
+// Provides the view of the resource that the supplemental context refers to, along with what to
+// highlight of it in the playlist.
+fn supplemental_view(
+    context: SupplementalViewQueryContext,
+    playlist: &str,
+    imported_definitions: &HashMap<String, String>,
+    variant: Option<VariantContext>,
+) -> (Highlighted, AnyView) {
     match context {
         SupplementalViewQueryContext::AssetList(asset_list_context) => {
             let AssetListContext { url, daterange_id } = asset_list_context;
-            view! {
-                <Container>
-                    <ErrorBounded>
-                        <PlaylistViewer
-                            playlist
-                            imported_definitions
-                            supplemental_showing=true
-                            highlighted=Highlighted::AssetList {
-                                daterange_id,
-                            }
-                        />
-                    </ErrorBounded>
-                    <FetchTextView
-                        url=url
-                        render_text=|text| view! { <AssetListView json=text /> }
-                    />
-                </Container>
-            }
+            let file_name = download_file_name(&url, None, DEFAULT_ASSET_LIST_FILE_NAME);
+            let asset_list_url = url.clone();
+            let break_duration =
+                interstitial(playlist, &daterange_id).and_then(|i| i.break_duration());
+            let view = view! {
+                <FetchTextView
+                    url=url
+                    label=tr(Message::NetworkAssetList)
+                    render_text=move |text| {
+                        view! {
+                            <AssetListView
+                                json=text
+                                file_name=file_name.clone()
+                                asset_list_url=asset_list_url.clone()
+                                break_duration
+                            />
+                        }
+                    }
+                />
+            };
+            (Highlighted::AssetList { daterange_id }, view.into_any())
         }
         SupplementalViewQueryContext::DaterangeSchedule(daterange_schedule_context) => {
             let DaterangeScheduleContext { url, daterange_id } = daterange_schedule_context;
-            view! {
-                <Container>
-                    <ErrorBounded>
-                        <PlaylistViewer
-                            playlist
-                            imported_definitions
-                            supplemental_showing=true
-                            highlighted=Highlighted::XUri { daterange_id }
-                        />
-                    </ErrorBounded>
-                    <FetchTextView
-                        url=url
-                        render_text=|text| view! { <DaterangeScheduleView json=text /> }
-                    />
-                </Container>
-            }
+            let view = view! {
+                <FetchTextView
+                    url=url
+                    label=tr(Message::NetworkSchedule)
+                    render_text=|text| view! { <DaterangeScheduleView json=text /> }
+                />
+            };
+            (Highlighted::XUri { daterange_id }, view.into_any())
         }
         SupplementalViewQueryContext::Scte35(scte35_context) => {
-            let daterange_id = scte35_context.daterange_id.clone();
-            let command_type = scte35_context.command_type;
-            view! {
-                <Container>
-                    <ErrorBounded>
-                        <PlaylistViewer
-                            playlist
-                            imported_definitions
-                            supplemental_showing=true
-                            highlighted=Highlighted::Scte35 {
-                                daterange_id,
-                                command_type,
-                            }
-                        />
-                    </ErrorBounded>
-                    <Scte35Viewer context=scte35_context />
-                </Container>
-            }
+            let highlighted = Highlighted::Scte35 {
+                daterange_id: scte35_context.daterange_id.clone(),
+                command_type: scte35_context.command_type,
+            };
+            let view = view! {
+                <Scte35Viewer context=scte35_context playlist=playlist.to_string() />
+            };
+            (highlighted, view.into_any())
         }
         SupplementalViewQueryContext::Segment(media_segment_context) => {
             let MediaSegmentContext {
@@ -162,21 +369,29 @@ pub fn Viewer(
                 media_sequence,
                 byterange,
             } = media_segment_context;
-            view! {
-                <Container>
-                    <ErrorBounded>
-                        <PlaylistViewer
-                            playlist
-                            imported_definitions
-                            supplemental_showing=true
-                            highlighted=Highlighted::Segment {
-                                media_sequence,
-                            }
-                        />
-                    </ErrorBounded>
-                    <SupplementalSegmentView segment_url=url.clone() byterange />
-                </Container>
-            }
+            let program_date_time = program_date_time_for_segment(playlist, media_sequence);
+            let init_segment = init_segment_request(playlist, media_sequence, imported_definitions);
+            let keys = keys_for_segment(playlist, media_sequence);
+            let decryption =
+                aes_128_decryption(&keys, Some(media_sequence), playlist, imported_definitions);
+            let subtitle_segment = SubtitleSegment {
+                media_sequence,
+                window: segment_window(playlist, media_sequence),
+            };
+            let view = view! {
+                <SupplementalSegmentView
+                    segment_url=url
+                    byterange
+                    label=tr(Message::NetworkSegment)
+                    variant
+                    init_segment
+                    keys
+                    decryption
+                    program_date_time
+                    subtitle_segment
+                />
+            };
+            (Highlighted::Segment { media_sequence }, view.into_any())
         }
         SupplementalViewQueryContext::Map(media_segment_context) => {
             let MediaSegmentContext {
@@ -184,24 +399,23 @@ pub fn Viewer(
                 media_sequence,
                 byterange,
             } = media_segment_context;
-            let url_for_playlist_viewer = url.clone();
-            let url_for_segment_viewer = url.clone();
-            view! {
-                <Container>
-                    <ErrorBounded>
-                        <PlaylistViewer
-                            playlist
-                            imported_definitions
-                            supplemental_showing=true
-                            highlighted=Highlighted::Map {
-                                url: url_for_playlist_viewer,
-                                min_media_sequence: media_sequence,
-                            }
-                        />
-                    </ErrorBounded>
-                    <SupplementalSegmentView segment_url=url_for_segment_viewer byterange />
-                </Container>
-            }
+            let keys = keys_for_segment(playlist, media_sequence);
+            let decryption = aes_128_decryption(&keys, None, playlist, imported_definitions);
+            let view = view! {
+                <SupplementalSegmentView
+                    segment_url=url.clone()
+                    byterange
+                    label=tr(Message::NetworkMap)
+                    variant
+                    keys
+                    decryption
+                />
+            };
+            let highlighted = Highlighted::Map {
+                url,
+                min_media_sequence: media_sequence,
+            };
+            (highlighted, view.into_any())
         }
         SupplementalViewQueryContext::Part(part_segment_context) => {
             let PartSegmentContext {
@@ -213,26 +427,79 @@ pub fn Viewer(
                 media_sequence,
                 byterange,
             } = segment_context;
-            view! {
-                <Container>
-                    <ErrorBounded>
-                        <PlaylistViewer
-                            playlist
-                            imported_definitions
-                            supplemental_showing=true
-                            highlighted=Highlighted::Part {
-                                media_sequence,
-                                part_index,
-                            }
-                        />
-                    </ErrorBounded>
-                    <SupplementalSegmentView segment_url=url byterange />
-                </Container>
-            }
+            let init_segment = init_segment_request(playlist, media_sequence, imported_definitions);
+            let keys = keys_for_segment(playlist, media_sequence);
+            let decryption = aes_128_decryption(&keys, None, playlist, imported_definitions);
+            let view = view! {
+                <SupplementalSegmentView
+                    segment_url=url
+                    byterange
+                    label=tr(Message::NetworkPart)
+                    variant
+                    init_segment
+                    keys
+                    decryption
+                />
+            };
+            let highlighted = Highlighted::Part {
+                media_sequence,
+                part_index,
+            };
+            (highlighted, view.into_any())
         }
     }
 }
 
+// The FRAME-RATE check, the timeline drift analysis and the playback of the segment all need what
+// the initialization segment describes of the tracks (their timescale, handler and sample entries),
+// which a media segment does not carry, so the initialization segment in effect for the segment is
+// fetched alongside it.
+fn init_segment_request(
+    playlist: &str,
+    media_sequence: u64,
+    imported_definitions: &HashMap<String, String>,
+) -> Option<(String, Option<RequestRange>)> {
+    let (uri, byterange) = map_for_segment(playlist, media_sequence)?;
+    let definitions = variable_definitions(playlist, imported_definitions);
+    let url = resolve_playlist_relative_url(&uri, &definitions)?;
+    Some((
+        url,
+        byterange.map(|(length, offset)| RequestRange::from_length_with_offset(length, offset)),
+    ))
+}
+
+// A resource encrypted with METHOD=AES-128 is decrypted with the IV attribute of its key, or else
+// with the Media Sequence Number of its segment. A map has no Media Sequence Number, and a part is
+// only a slice of the CBC chain of its segment, so neither can fall back on it.
+fn aes_128_decryption(
+    keys: &[SegmentKey],
+    media_sequence: Option<u64>,
+    playlist: &str,
+    imported_definitions: &HashMap<String, String>,
+) -> Option<Aes128Decryption> {
+    let key = keys.iter().find(|key| key.method == "AES-128")?;
+    let definitions = variable_definitions(playlist, imported_definitions);
+    let key_url = key
+        .uri
+        .as_deref()
+        .and_then(|uri| resolve_playlist_relative_url(uri, &definitions));
+    let iv = key.aes_128_iv(media_sequence);
+    let iv_source = match (&key.iv, media_sequence) {
+        (Some(_), _) if iv.is_none() => String::from(tr(Message::IvInvalid)),
+        (Some(_), _) => String::from(tr(Message::IvFromAttribute)),
+        (None, Some(media_sequence)) => tr_with(
+            Message::IvFromMediaSequence,
+            &[("media_sequence", &media_sequence.to_string())],
+        ),
+        (None, None) => String::from(tr(Message::IvMissing)),
+    };
+    Some(Aes128Decryption {
+        key_url,
+        iv,
+        iv_source,
+    })
+}
+
 #[component]
 fn ErrorBounded(children: Children) -> impl IntoView {
     view! {
@@ -252,76 +519,171 @@ fn ErrorBounded(children: Children) -> impl IntoView {
 
 #[component]
 fn Container(children: Children) -> impl IntoView {
-    view! { <div class=VIEWER_CLASS>{children()}</div> }
+    let container = NodeRef::<Div>::new();
+    let layout = SplitLayout::load();
+    let font_size = FontSize::load();
+    provide_context(font_size);
+    SelectedBox::provide();
+    view! {
+        <div
+            node_ref=container
+            class=VIEWER_CLASS
+            class:stacked=move || layout.stacked.get()
+            style=move || {
+                format!(
+                    "--supplemental-width: {}%; {}",
+                    layout.supplemental_width.get(),
+                    font_size.style(),
+                )
+            }
+        >
+            {children()}
+            <SplitDivider layout container />
+        </div>
+    }
 }
 
+// Whether segments are fetched only up to their media data, which is remembered across segments as
+// it is most useful when stepping through many large ones.
+const STRUCTURE_ONLY_KEY: &str = FeatureToggle::StructureOnly.key();
+const DEFAULT_SEGMENT_FILE_NAME: &str = "segment.mp4";
+const DEFAULT_ASSET_LIST_FILE_NAME: &str = "asset-list.json";
+
 #[component]
-fn SupplementalSegmentView(segment_url: String, byterange: Option<RequestRange>) -> impl IntoView {
-    let segment_result =
-        LocalResource::new(move || fetch_array_buffer(segment_url.clone(), byterange));
+fn SupplementalSegmentView(
+    segment_url: String,
+    byterange: Option<RequestRange>,
+    label: &'static str,
+    variant: Option<VariantContext>,
+    keys: Vec<SegmentKey>,
+    #[prop(optional_no_strip)] init_segment: Option<(String, Option<RequestRange>)>,
+    #[prop(optional_no_strip)] program_date_time: Option<SegmentDateTime>,
+    #[prop(optional)] subtitle_segment: Option<SubtitleSegment>,
+    #[prop(optional_no_strip)] decryption: Option<Aes128Decryption>,
+) -> impl IntoView {
+    let network_log = use_context::<NetworkLog>();
+    let last_segment = use_context::<LastSegment>();
+    let settings = AppSettings::current();
+    let download_name = download_file_name(&segment_url, byterange, DEFAULT_SEGMENT_FILE_NAME);
+    let structure_only = RwSignal::new(load_flag(STRUCTURE_ONLY_KEY));
+    Effect::watch(
+        move || structure_only.get(),
+        move |structure_only, _, _| save_flag(STRUCTURE_ONLY_KEY, *structure_only),
+        false,
+    );
+    // When the initialization segment sits right next to the segment in the same resource, both are
+    // fetched with the one request (unless only the structure of the segment is wanted).
+    let coalesced_init = init_segment.clone().and_then(|(init_url, init_byterange)| {
+        let (init_byterange, byterange) = (init_byterange?, byterange?);
+        (init_url == segment_url && init_byterange.coalesced_with(byterange).is_some())
+            .then_some((init_byterange, byterange))
+    });
+    // A segment fetched whole is read as it downloads, so that its boxes can be shown as they
+    // arrive.
+    let download = SegmentDownload::default();
+    let segment_result = LocalResource::new(move || {
+        let segment_url = segment_url.clone();
+        let structure_only = structure_only.get();
+        let settings = settings.get_untracked();
+        download.reset();
+        async move {
+            let alone = |response| SegmentResponse {
+                response,
+                init_response: None,
+            };
+            let result = match coalesced_init.filter(|_| !structure_only) {
+                Some((init_byterange, byterange)) => {
+                    fetch_segment_with_init(segment_url, init_byterange, byterange, &settings)
+                        .await
+                        .map(|(init, response)| SegmentResponse {
+                            response,
+                            init_response: Some(init),
+                        })
+                }
+                // The whole segment says more than its structure, so is shown when it is cached.
+                None if structure_only => match cached_segment(&segment_url, byterange) {
+                    Some(response) => Ok(alone(response)),
+                    None => fetch_segment_structure(segment_url, byterange, &settings)
+                        .await
+                        .map(|response| alone(Arc::new(response))),
+                },
+                None => fetch_segment(segment_url, byterange, &settings, |body, progress| {
+                    download.update(body, progress)
+                })
+                .await
+                .map(alone),
+            };
+            if let (Some(log), Ok(SegmentResponse { response, .. })) = (network_log, &result) {
+                log.record(label, response.headers.clone(), response.timing);
+            }
+            if let (Some(last_segment), Ok(segment)) = (last_segment, &result) {
+                last_segment.record(segment.clone());
+            }
+            result
+        }
+    });
+    // The initialization segment only adds to the view of the segment (its checks and playback), so
+    // failing to fetch it is not treated as an error for the segment view.
+    let init_result = LocalResource::new(move || {
+        let init_segment = init_segment.clone();
+        let coalesced = coalesced_init.is_some() && !structure_only.get();
+        let settings = settings.get_untracked();
+        async move {
+            if coalesced {
+                return None;
+            }
+            let (url, byterange) = init_segment?;
+            let response = fetch_segment(url, byterange, &settings, |_, _| {}).await.ok()?;
+            if let Some(log) = network_log {
+                log.record(
+                    tr(Message::NetworkInitializationSegment),
+                    response.headers.clone(),
+                    response.timing,
+                );
+            }
+            Some(response)
+        }
+    });
     view! {
-        <Suspense fallback=|| {
-            view! { <div class=SUPPLEMENTAL_VIEW_CLASS>"Loading..."</div> }
-        }>
+        <Suspense fallback=move || view! { <SegmentDownloadView download /> }>
             <ErrorBounded>
                 {move || {
                     segment_result
                         .get()
                         .map(|fetch_response| {
                             match fetch_response {
-                                Ok(r) => {
-                                    match determine_segment_type(&r) {
-                                        SegmentType::WebVtt => {
-                                            view! {
-                                                <PreformattedViewer contents=String::from_utf8_lossy(
-                                                        &r.response_body,
-                                                    )
-                                                    .to_string() />
-                                            }
-                                                .into_any()
-                                        }
-                                        SegmentType::Mp4 => {
-                                            view! { <IsobmffViewer data=r.response_body /> }.into_any()
-                                        }
-                                        SegmentType::Image => {
-                                            if let Some(content_type) = &r.content_type {
-                                                view! {
-                                                    <ImageViewer
-                                                        contents=r.response_body
-                                                        content_type=content_type.clone()
-                                                    />
-                                                }
-                                                    .into_any()
-                                            } else {
-                                                // This case shuoldn't happen since we already
-                                                // checked the content type when determining the
-                                                // segment type.
-                                                view! {
-                                                    <ViewerError error="Error: unknown content type for image segment"
-                                                        .to_string() />
-                                                }
-                                                    .into_any()
-                                            }
+                                Ok(SegmentResponse { response, init_response }) => {
+                                    let init_data = init_response
+                                        .or_else(|| init_result.get().flatten());
+                                    let variant = variant.clone();
+                                    let keys = keys.clone();
+                                    let download_name = download_name.clone();
+                                    let content = move |response: Arc<FetchArrayBufferResonse>| {
+                                        view! {
+                                            <SegmentContent
+                                                response
+                                                variant=variant.clone()
+                                                init_data=init_data.clone().map(SharedBytes::from)
+                                                keys=Some(keys.clone())
+                                                program_date_time
+                                                subtitle_segment
+                                                structure_only=Some(structure_only)
+                                                download_name=Some(download_name.clone())
+                                            />
                                         }
-                                        SegmentType::Unknown => {
+                                    };
+                                    match decryption.clone() {
+                                        Some(decryption) => {
                                             view! {
-                                                <div class=SUPPLEMENTAL_VIEW_CLASS>
-                                                    <ViewerError
-                                                        error="Error: unsupported segment type".to_string()
-                                                        extra_info=Some(
-                                                            "Currently only WebVTT and Fragmented MPEG-4 segments are supported"
-                                                                .to_string(),
-                                                        )
-                                                    />
-                                                </div>
+                                                <EncryptedSegment response decryption content />
                                             }
                                                 .into_any()
                                         }
+                                        None => content(response),
                                     }
                                 }
                                 Err(e) => {
-                                    view! { <ViewerError error=e.error extra_info=e.extra_info /> }
-                                        .into_any()
+                                    segment_error(e, last_segment, move || segment_result.refetch())
                                 }
                             }
                         })
@@ -331,16 +693,170 @@ fn SupplementalSegmentView(segment_url: String, byterange: Option<RequestRange>)
     }
 }
 
+// A segment that failed to load leaves the last one that loaded on show, under a banner with the
+// error, or else the error takes its place. Either way the request can be retried from there.
+fn segment_error(
+    error: FetchError,
+    last_segment: Option<LastSegment>,
+    retry: impl Fn() + Copy + 'static,
+) -> AnyView {
+    match last_segment.and_then(|last_segment| last_segment.get_untracked()) {
+        Some(SegmentResponse {
+            response,
+            init_response,
+        }) => {
+            let note = tr_with(Message::ShowingLastSegment, &[("url", &response.url)]);
+            view! {
+                <ErrorToast
+                    error=error.error()
+                    extra_info=error.extra_info()
+                    fallback_note=Some(note)
+                    on_retry=retry
+                />
+                <SegmentContent
+                    response
+                    init_data=init_response.map(SharedBytes::from)
+                />
+            }
+            .into_any()
+        }
+        None => view! {
+            <ViewerError error=error.error() extra_info=error.extra_info() />
+            <button class="button" type="button" on:click=move |_| retry()>
+                {tr(Message::Retry)}
+            </button>
+        }
+        .into_any(),
+    }
+}
+
+// Shows a segment with the viewer for its type.
 #[component]
-fn FetchTextView<F, IV>(url: String, render_text: F) -> impl IntoView
+fn SegmentContent(
+    response: Arc<FetchArrayBufferResonse>,
+    #[prop(optional_no_strip)] variant: Option<VariantContext>,
+    #[prop(optional_no_strip)] init_data: Option<SharedBytes>,
+    #[prop(optional_no_strip)] keys: Option<Vec<SegmentKey>>,
+    #[prop(optional_no_strip)] program_date_time: Option<SegmentDateTime>,
+    #[prop(optional_no_strip)] subtitle_segment: Option<SubtitleSegment>,
+    #[prop(optional_no_strip)] structure_only: Option<RwSignal<bool>>,
+    #[prop(optional_no_strip)] download_name: Option<String>,
+) -> AnyView {
+    match determine_segment_type(&response) {
+        SegmentType::WebVtt => view! {
+            <WebVttViewer
+                contents=String::from_utf8_lossy(&response.response_body)
+                    .to_string()
+                segment=subtitle_segment
+            />
+        }
+        .into_any(),
+        SegmentType::Mp4 => {
+            let truncated = response.body_truncated;
+            view! {
+                <IsobmffViewer
+                    truncated
+                    data=SharedBytes::from(response)
+                    variant
+                    init_data
+                    keys
+                    program_date_time
+                    structure_only
+                    download_name
+                />
+            }
+            .into_any()
+        }
+        SegmentType::Image => {
+            if let Some(content_type) = response.content_type.clone() {
+                view! { <ImageViewer contents=SharedBytes::from(response) content_type /> }
+                .into_any()
+            } else {
+                // This case shuoldn't happen since we already
+                // checked the content type when determining the
+                // segment type.
+                view! {
+                    <ViewerError error=tr(Message::UnknownImageContentType).to_string() />
+                }
+                .into_any()
+            }
+        }
+        SegmentType::PackedAudio(format) => view! {
+            <PackedAudioViewer data=SharedBytes::from(response) format download_name />
+        }
+        .into_any(),
+        SegmentType::Unknown => view! {
+            <div class=SUPPLEMENTAL_VIEW_CLASS>
+                <ViewerError
+                    error=tr(Message::UnsupportedSegmentType).to_string()
+                    extra_info=Some(tr(Message::SupportedSegmentTypes).to_string())
+                />
+            </div>
+        }
+        .into_any(),
+    }
+}
+
+/// Shows a segment opened from a local file on its own, without a playlist to place it in.
+#[component]
+pub fn LocalSegmentViewer(
+    name: String,
+    data: Vec<u8>,
+    content_type: Option<String>,
+) -> impl IntoView {
+    let response = FetchArrayBufferResonse {
+        response_body: data,
+        content_type,
+        url: name.clone(),
+        headers: ResponseHeaders {
+            url: name,
+            status: 200,
+            status_text: String::from(tr(Message::LocalFileStatus)),
+            headers: Vec::new(),
+        },
+        timing: FetchTiming::default(),
+        body_truncated: false,
+    };
+    let font_size = FontSize::load();
+    provide_context(font_size);
+    view! {
+        <div class=LOCAL_SEGMENT_VIEWER_CLASS style=move || font_size.style()>
+            <ErrorBounded>
+                <SegmentContent response=Arc::new(response) />
+            </ErrorBounded>
+        </div>
+    }
+}
+
+#[derive(Clone)]
+struct SegmentResponse {
+    response: Arc<FetchArrayBufferResonse>,
+    /// The initialization segment, when it was fetched along with the segment.
+    init_response: Option<Arc<FetchArrayBufferResonse>>,
+}
+
+#[component]
+fn FetchTextView<F, IV>(url: String, label: &'static str, render_text: F) -> impl IntoView
 where
     F: Fn(String) -> IV + Send + 'static,
     IV: IntoView + 'static,
 {
-    let text_result = LocalResource::new(move || fetch_text(url.clone()));
+    let network_log = use_context::<NetworkLog>();
+    let settings = AppSettings::current();
+    let text_result = LocalResource::new(move || {
+        let url = url.clone();
+        let settings = settings.get_untracked();
+        async move {
+            let response = fetch_text(url, &settings).await;
+            if let (Some(log), Ok(response)) = (network_log, &response) {
+                log.record(label, response.headers.clone(), response.timing);
+            }
+            response
+        }
+    });
     view! {
         <Suspense fallback=|| {
-            view! { <div class=SUPPLEMENTAL_VIEW_CLASS>"LOADING..."</div> }
+            view! { <div class=SUPPLEMENTAL_VIEW_CLASS>{tr(Message::LoadingFallback)}</div> }
         }>
             <ErrorBounded>
                 {move || {
@@ -352,7 +868,7 @@ where
                                 Err(e) => {
                                     Either::Right(
                                         view! {
-                                            <ViewerError error=e.error extra_info=e.extra_info />
+                                            <ViewerError error=e.error() extra_info=e.extra_info() />
                                         },
                                     )
                                 }
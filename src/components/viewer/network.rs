@@ -0,0 +1,163 @@
+use super::{NETWORK_HEADER_NOTABLE_CLASS, NETWORK_PANEL_CLASS};
+use crate::utils::{
+    fetch_timing::{FetchTiming, format_throughput},
+    i18n::{Message, tr, tr_with},
+    network::ResponseHeaders,
+};
+use leptos::prelude::*;
+
+// The headers that say most about how a response was cached, on the way from the origin and at the
+// CDN, which are emphasised among the rest.
+const NOTABLE_HEADERS: &[&str] = &[
+    "content-type",
+    "content-length",
+    "content-range",
+    "cache-control",
+    "age",
+    "expires",
+    "last-modified",
+    "etag",
+    "date",
+    "via",
+    "x-cache",
+    "x-cache-hits",
+    "x-served-by",
+    "cf-ray",
+    "cf-cache-status",
+    "x-amz-cf-pop",
+    "x-amz-cf-id",
+    "akamai-cache-status",
+];
+
+/// The responses fetched for the view currently shown, labelled by what they were fetched for. The
+/// viewer is re-created for every view, so this is provided as context by the viewer itself.
+#[derive(Clone, Copy)]
+pub struct NetworkLog(RwSignal<Vec<NetworkEntry>>);
+
+#[derive(Clone)]
+struct NetworkEntry {
+    label: &'static str,
+    headers: ResponseHeaders,
+    timing: FetchTiming,
+}
+
+impl NetworkLog {
+    pub fn new(headers: ResponseHeaders, timing: FetchTiming) -> Self {
+        Self(RwSignal::new(vec![NetworkEntry {
+            label: tr(Message::NetworkPlaylist),
+            headers,
+            timing,
+        }]))
+    }
+
+    /// The headers of the response for the playlist.
+    pub fn playlist_headers(&self) -> ResponseHeaders {
+        self.0.with_untracked(|entries| {
+            entries
+                .iter()
+                .find(|entry| entry.label == tr(Message::NetworkPlaylist))
+                .map(|entry| entry.headers.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn record(&self, label: &'static str, headers: ResponseHeaders, timing: FetchTiming) {
+        self.0.update(|entries| {
+            entries.retain(|entry| entry.label != label);
+            entries.push(NetworkEntry {
+                label,
+                headers,
+                timing,
+            });
+        });
+    }
+}
+
+#[component]
+pub fn NetworkPanel() -> Option<impl IntoView> {
+    let log = use_context::<NetworkLog>()?;
+    let responses = move || {
+        log.0
+            .get()
+            .into_iter()
+            .map(|entry| {
+                view! {
+                    <NetworkResponse
+                        label=entry.label
+                        headers=entry.headers
+                        timing=entry.timing
+                    />
+                }
+            })
+            .collect_view()
+    };
+    Some(view! {
+        <details class=NETWORK_PANEL_CLASS>
+            <summary>{tr(Message::Network)}</summary>
+            {responses}
+            <p>{tr(Message::NetworkExposedHeaders)}</p>
+        </details>
+    })
+}
+
+#[component]
+fn NetworkResponse(
+    label: &'static str,
+    headers: ResponseHeaders,
+    timing: FetchTiming,
+) -> impl IntoView {
+    let ResponseHeaders {
+        url,
+        status,
+        status_text,
+        headers,
+    } = headers;
+    let rows = headers
+        .into_iter()
+        .map(|(name, value)| {
+            let class = NOTABLE_HEADERS
+                .contains(&name.as_str())
+                .then_some(NETWORK_HEADER_NOTABLE_CLASS);
+            view! {
+                <tr class=class>
+                    <td>{name}</td>
+                    <td>{value}</td>
+                </tr>
+            }
+        })
+        .collect_view();
+    view! {
+        <p>
+            {tr_with(
+                Message::NetworkResponseStatus,
+                &[("label", label), ("status", &status.to_string()), ("status_text", &status_text)],
+            )}
+        </p>
+        <p>{url}</p>
+        <p>{timing_summary(&timing)}</p>
+        <table>{rows}</table>
+    }
+}
+
+fn timing_summary(timing: &FetchTiming) -> String {
+    let throughput = timing
+        .throughput()
+        .map(|throughput| format!(", {}", format_throughput(throughput)))
+        .unwrap_or_default();
+    let source = if timing.resource_timing {
+        tr(Message::TimingFromResourceTiming)
+    } else {
+        tr(Message::TimingAroundFetch)
+    };
+    tr_with(
+        Message::TimingSummary,
+        &[
+            ("start", &format!("{:.0}", timing.start)),
+            ("ttfb", &format!("{:.0}", timing.time_to_first_byte())),
+            ("download", &format!("{:.0}", timing.download_duration())),
+            ("bytes", &timing.bytes.to_string()),
+            ("throughput", &throughput),
+            ("source", source),
+        ],
+    )
+}
@@ -0,0 +1,168 @@
+use super::{SUPPLEMENTAL_TABS_CLASS, error::ViewerError};
+use crate::utils::{
+    href::{PINNED_VIEW_QUERY_NAME, replace_query_value},
+    i18n::{Message, tr, tr_with},
+    query_codec::{
+        PinnedViewLayout, PinnedViewQueryContext, SupplementalViewQueryContext,
+        SupplementalViewQueryContextDecodeError, encode_pinned_view,
+    },
+};
+use leptos::prelude::*;
+use leptos_router::hooks::use_url;
+
+/// Holds the supplemental view of the resource opened from the playlist, alongside the view pinned
+/// for comparison (if any), either as tabs or side by side. Pinning, unpinning and switching layout
+/// are all links, as the pinned view is kept in the query.
+#[component]
+pub fn SupplementalTabs(
+    current: Option<(SupplementalViewQueryContext, AnyView)>,
+    pinned: Option<
+        Result<(PinnedViewQueryContext, AnyView), SupplementalViewQueryContextDecodeError>,
+    >,
+) -> impl IntoView {
+    let search = use_url().get_untracked().search().to_string();
+    let pinned_href = |layout: PinnedViewLayout, context: &SupplementalViewQueryContext| {
+        let value = encode_pinned_view(layout, context);
+        replace_query_value(&search, PINNED_VIEW_QUERY_NAME, Some(&value))
+    };
+    let unpin_href = replace_query_value(&search, PINNED_VIEW_QUERY_NAME, None);
+    let current_label = current.as_ref().map(|(context, _)| context.label());
+    let (current_context, current_view) = current.unzip();
+    let layout = match &pinned {
+        Some(Ok((pinned, _))) => Some(pinned.layout),
+        Some(Err(_)) => Some(PinnedViewLayout::Tabs),
+        None => None,
+    };
+    let split = layout == Some(PinnedViewLayout::Split);
+    // With nothing opened from the playlist the pinned view is all there is to show.
+    let show_pinned = RwSignal::new(current_view.is_none());
+
+    let pin_current = current_context.as_ref().map(|context| {
+        let (label, title) = if pinned.is_some() {
+            (tr(Message::PinInstead), tr(Message::PinInsteadTitle))
+        } else {
+            (
+                tr(Message::PinForComparison),
+                tr(Message::PinForComparisonTitle),
+            )
+        };
+        let href = pinned_href(layout.unwrap_or(PinnedViewLayout::Tabs), context);
+        view! {
+            <a class="button" href=href title=title>
+                {label}
+            </a>
+        }
+    });
+    let (pinned_label, layout_toggle, pinned_view) = match pinned {
+        Some(Ok((pinned, view))) => {
+            let (label, other_layout) = match pinned.layout {
+                PinnedViewLayout::Tabs => (tr(Message::PinnedSideBySide), PinnedViewLayout::Split),
+                PinnedViewLayout::Split => (tr(Message::PinnedAsTabs), PinnedViewLayout::Tabs),
+            };
+            let href = pinned_href(other_layout, &pinned.context);
+            let toggle = view! {
+                <a class="button" href=href>
+                    {label}
+                </a>
+            };
+            (Some(pinned.context.label()), Some(toggle), Some(view))
+        }
+        Some(Err(e)) => {
+            let view = view! {
+                <ViewerError
+                    error=tr(Message::PinnedViewQueryError).to_string()
+                    extra_info=Some(e.to_string())
+                />
+            };
+            (
+                Some(String::from(tr(Message::Pinned))),
+                None,
+                Some(view.into_any()),
+            )
+        }
+        None => (None, None, None),
+    };
+    let unpin = pinned_view.is_some().then(|| {
+        view! {
+            <a class="button" href=unpin_href title=tr(Message::UnpinTitle)>
+                {tr(Message::Unpin)}
+            </a>
+        }
+    });
+    // Tabs are only needed to choose between two views that are not already side by side.
+    let tabs = (!split)
+        .then(|| pinned_label.clone().zip(current_label.clone()))
+        .flatten()
+        .map(|(pinned_label, current_label)| {
+            view! {
+                <div
+                    class="supplemental-tab-list"
+                    role="tablist"
+                    aria-label=tr(Message::SupplementalViews)
+                >
+                    <button
+                        type="button"
+                        role="tab"
+                        aria-selected=move || show_pinned.get().to_string()
+                        class:selected=move || show_pinned.get()
+                        on:click=move |_| show_pinned.set(true)
+                    >
+                        {tr_with(Message::PinnedLabel, &[("label", &pinned_label)])}
+                    </button>
+                    <button
+                        type="button"
+                        role="tab"
+                        aria-selected=move || (!show_pinned.get()).to_string()
+                        class:selected=move || !show_pinned.get()
+                        on:click=move |_| show_pinned.set(false)
+                    >
+                        {current_label}
+                    </button>
+                </div>
+            }
+        });
+    // Side by side, each view is a region of its own, whereas as tabs only one is shown at a time.
+    let pane_role = if split { "region" } else { "tabpanel" };
+    let pinned_pane = pinned_view.map(|view| {
+        view! {
+            <div
+                class="supplemental-pane"
+                class:hidden=move || !split && !show_pinned.get()
+                role=pane_role
+                aria-label=pinned_label
+                    .clone()
+                    .map(|label| tr_with(Message::PinnedLabel, &[("label", &label)]))
+            >
+                {split
+                    .then(|| {
+                        pinned_label
+                            .map(|label| {
+                                view! {
+                                    <p>{tr_with(Message::PinnedLabel, &[("label", &label)])}</p>
+                                }
+                            })
+                    })}
+                {view}
+            </div>
+        }
+    });
+    let current_pane = current_view.map(|view| {
+        view! {
+            <div
+                class="supplemental-pane"
+                class:hidden=move || !split && show_pinned.get()
+                role=pane_role
+                aria-label=current_label.clone()
+            >
+                {split.then(|| current_label.map(|label| view! { <p>{label}</p> }))}
+                {view}
+            </div>
+        }
+    });
+    view! {
+        <div class=SUPPLEMENTAL_TABS_CLASS class:split=split>
+            <div class="supplemental-tab-bar">{tabs} {pin_current} {layout_toggle} {unpin}</div>
+            <div class="supplemental-panes">{pinned_pane} {current_pane}</div>
+        </div>
+    }
+}
@@ -1,20 +1,65 @@
 use super::{
-    BLANK_CLASS, COMMENT_CLASS, HIGHLIGHTED, HIGHLIGHTED_URI_CLASS, MAIN_VIEW_CLASS,
-    MAIN_VIEW_WITH_SUPPLEMENTAL_CLASS, TAG_CLASS, URI_CLASS,
+    BADGE_CLASS, BADGE_WARNING_CLASS, BLANK_CLASS, COMMENT_CLASS, HIGHLIGHTED,
+    HIGHLIGHTED_URI_CLASS, MAIN_VIEW_CLASS, MAIN_VIEW_WITH_SUPPLEMENTAL_CLASS,
+    PLAYLIST_ACTIONS_CLASS, PLAYLIST_LINE_CLASS, PLAYLIST_LINE_ERROR_CLASS,
+    PLAYLIST_LINE_WARNING_CLASS, RENDERED_PLAYLIST_CLASS, TAG_CLASS, URI_CLASS,
+    ad_avails::AdAvails,
+    ad_breaks::AdBreaks,
+    automation::Automation,
+    bandwidth::BandwidthCheck,
+    collapsed_segments::{CollapsedRuns, CollapsedSegments},
+    deep_link::{CopyLinkButton, line_at_top},
+    find_bar::{FindBar, PlaylistFind},
+    font_size::FontSizeControl,
+    interstitials::InterstitialPlayout,
+    key_rotation::KeyRotation,
+    line_filter::{HiddenLinesMarker, LineFilter, PlaylistFilter},
+    line_window::LineWindow,
+    line_wrap::{LineWrap, LineWrapToggle, PLAYLIST_NO_WRAP_KEY},
+    live_timeline::LiveTimeline,
+    live_update::LiveUpdateCheck,
+    minimap::PlaylistMinimap,
+    network::NetworkPanel,
+    playlist_cache::{PlaylistAnalysis, playlist_analysis},
+    playlist_timeline::PlaylistTimeline,
+    raw_text::{RawPlaylist, RawText, RawTextToggle},
+    report::{JsonExportButton, ReportButton},
+    segment_context::SegmentContextHeader,
+    segment_navigation::SegmentNavigation,
+    tag_legend::TagLegend,
+    thumbnail::IFrameThumbnails,
+    validation::{LineDiagnostics, ValidationReport},
+    variant_summary::VariantSummaryTable,
 };
 use crate::{
-    components::CopyButton,
+    components::{CopyButton, DownloadButton, LocalPlaylist},
     utils::{
+        byterange::RequestRange,
+        data_uri::data_uri_media_type,
+        daterange_cue::Cue,
+        download::download_file_name,
+        encryption::sample_encryption_scheme,
+        fairplay::{FAIRPLAY_KEYFORMAT, SkdUri, key_id_as_uuid},
+        hls_spec::{SpecTerm, TagCategory, spec_terms},
         href::{
-            asset_list_href, daterange_schedule_href, map_href, media_playlist_href, part_href,
-            resolve_playlist_relative_url, scte35_href, segment_href,
+            PLAYLIST_URL_QUERY_NAME, asset_list_href, daterange_schedule_href, map_href,
+            media_playlist_href, part_href, resolve_playlist_relative_url, scte35_href,
+            segment_href, variant_playlist_href, with_automation, with_parent_playlist,
         },
-        network::RequestRange,
-        query_codec::Scte35CommandType,
+        i18n::{Message, tr, tr_with},
+        playlist_diff::LineUpdate,
+        playlist_lines::LineCategory,
+        query_codec::{Scte35CommandType, VariantContext},
+        validation::{Finding, Severity, findings_by_line},
+        variant_summary::AudioDelivery,
+        video_layout::VideoLayout,
     },
 };
-use leptos::{either::EitherOf3, prelude::*};
-use leptos_router::hooks::use_query_map;
+use leptos::{either::EitherOf3, html::Div, prelude::*};
+use leptos_router::{
+    NavigateOptions,
+    hooks::{use_navigate, use_query_map},
+};
 use quick_m3u8::{
     HlsLine, Reader,
     config::ParsingOptionsBuilder,
@@ -27,13 +72,14 @@ use quick_m3u8::{
 };
 use std::{borrow::Cow, collections::HashMap, error::Error, fmt::Display};
 
-macro_rules! tag_into_view {
+macro_rules! tag_into_line {
     ($tag:ident) => {{
         let line = $tag.into_inner();
-        view! { <p class=TAG_CLASS>{String::from_utf8_lossy(line.value())}</p> }.into_any()
+        LineContent::Tag(String::from_utf8_lossy(line.value()).into_owned())
     }};
 }
 
+#[derive(Clone)]
 pub enum Highlighted {
     Segment {
         media_sequence: u64,
@@ -75,36 +121,359 @@ pub struct HighlightedScte35Info {
 
 #[component]
 pub fn PlaylistViewer(
-    playlist: String,
+    #[prop(into)] playlist: Signal<String>,
     imported_definitions: HashMap<String, String>,
     #[prop(default = false)] supplemental_showing: bool,
-    #[prop(optional)] highlighted: Option<Highlighted>,
+    #[prop(optional_no_strip)] highlighted: Option<Highlighted>,
+    #[prop(optional_no_strip)] variant: Option<VariantContext>,
 ) -> Result<impl IntoView, PlaylistError> {
-    if playlist.is_empty() {
+    let first = playlist.get_untracked();
+    if first.is_empty() {
         return Ok(EitherOf3::A(view! { <div class=MAIN_VIEW_CLASS /> }));
     }
-    match try_get_lines(&playlist, imported_definitions, highlighted) {
-        Ok(lines) => {
-            if supplemental_showing {
-                Ok(EitherOf3::B(view! {
-                    <div class=MAIN_VIEW_WITH_SUPPLEMENTAL_CLASS>
-                        <CopyButton text=move || playlist.clone() />
-                        {lines}
-                    </div>
-                }))
-            } else {
-                Ok(EitherOf3::C(view! {
-                    <div class=MAIN_VIEW_CLASS>
-                        <CopyButton text=move || playlist.clone() />
-                        {lines}
+    let lines = try_get_lines(
+        &first,
+        imported_definitions.clone(),
+        highlighted.clone(),
+        &playlist_analysis(&first),
+    )?;
+    let highlighted_segment = match &highlighted {
+        Some(Highlighted::Segment { media_sequence }) => Some(*media_sequence),
+        _ => None,
+    };
+    let automation = use_context::<Automation>();
+    if !supplemental_showing && let Some(automation) = automation {
+        open_requested_segment(automation, lines.segment_hrefs.clone());
+    }
+    let highlighted_line = lines.highlighted_lines.first().copied();
+    let line_window = LineWindow::new(lines.lines.len());
+    let rendered_lines = NodeRef::<Div>::new();
+    // A live playlist is reloaded in place as it is rechecked. Each line is keyed by the line of
+    // the version before that it is the same as or changed from, so that the views of the lines
+    // that stayed the same are kept, and only the lines that are new or changed are rendered.
+    let next_key = StoredValue::new(lines.lines.len() as u64);
+    let first = StoredValue::new(Some(PlaylistVersion {
+        keys: (0..next_key.get_value()).collect(),
+        playlist: first,
+        lines,
+        ..Default::default()
+    }));
+    let version = Memo::new({
+        let imported_definitions = imported_definitions.clone();
+        move |previous: Option<&PlaylistVersion>| {
+            let playlist = playlist.get();
+            let Some(previous) = previous else {
+                return first
+                    .try_update_value(Option::take)
+                    .flatten()
+                    .unwrap_or_default();
+            };
+            let lines = match try_get_lines(
+                &playlist,
+                imported_definitions.clone(),
+                highlighted.clone(),
+                &playlist_analysis(&playlist),
+            ) {
+                Ok(lines) => lines,
+                Err(e) => {
+                    log::warn!("the reloaded playlist is shown as before due to {e}");
+                    return previous.clone();
+                }
+            };
+            let update = LineUpdate::new(&previous.playlist, &playlist);
+            let keys = (1..=lines.lines.len())
+                .map(|line_number| {
+                    update
+                        .source(line_number)
+                        .and_then(|source| previous.keys.get(source - 1).copied())
+                        .unwrap_or_else(|| {
+                            let key = next_key.get_value();
+                            next_key.set_value(key + 1);
+                            key
+                        })
+                })
+                .collect();
+            // The line at the top of the window is read before the reload is rendered.
+            let line_at_top = rendered_lines
+                .get_untracked()
+                .and_then(|lines| line_at_top(&lines))
+                .and_then(|line_number| update.position(line_number));
+            PlaylistVersion {
+                playlist,
+                lines,
+                keys,
+                updated: update.updated,
+                line_at_top,
+            }
+        }
+    });
+    let shown = Memo::new(move |_| version.with(|version| version.playlist.clone()));
+    let keys = Memo::new(move |_| version.with(|version| version.keys.clone()));
+    let categories = Memo::new(move |_| {
+        version.with(|version| {
+            version
+                .lines
+                .lines
+                .iter()
+                .map(|line| line.category)
+                .collect()
+        })
+    });
+    let diagnosed = Memo::new(move |_| {
+        version.with(|version| {
+            version
+                .lines
+                .lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| !line.findings.is_empty())
+                .map(|(index, _)| index)
+                .collect()
+        })
+    });
+    let find = PlaylistFind::new(shown.into());
+    let filter = PlaylistFilter::new(categories);
+    let collapsed = CollapsedRuns::new(shown.into(), highlighted_segment, diagnosed);
+    line_window
+        .follow_line_anchors(move |line_number| collapsed.reveal(line_number.saturating_sub(1)));
+    // The place in a reloaded playlist is kept, while the highlight is only brought into view when
+    // the playlist is first shown.
+    line_window.keep_at_top(
+        move || version.with(|version| version.line_at_top),
+        move |line_number| collapsed.reveal(line_number.saturating_sub(1)),
+    );
+    if let Some(line_number) = highlighted_line {
+        line_window.follow_highlight(line_number, move |line_number| {
+            collapsed.reveal(line_number.saturating_sub(1))
+        });
+    }
+    // Each line is given an anchor so that validation findings can link to it. The view of a line
+    // is only built while the chunk that holds it is rendered, and is built again if the line
+    // changes when the playlist is reloaded.
+    let line_view = move |index: Signal<usize>| {
+        let line = Memo::new(move |_| {
+            version.with(|version| version.lines.lines.get(index.get()).cloned())
+        });
+        let line_number = move || index.get() + 1;
+        let is_updated =
+            move || version.with(|version| version.updated.binary_search(&line_number()).is_ok());
+        let line_content = move || {
+            line.with(|line| {
+                let PlaylistLine {
+                    content,
+                    findings,
+                    tag_category,
+                    ..
+                } = line.as_ref()?;
+                let class = match findings.iter().map(|f| f.severity).min() {
+                    Some(Severity::Error) => PLAYLIST_LINE_ERROR_CLASS,
+                    Some(Severity::Warning) => PLAYLIST_LINE_WARNING_CLASS,
+                    None => PLAYLIST_LINE_CLASS,
+                };
+                let class = match tag_category {
+                    Some(tag_category) => format!("{class} {}", tag_category.class()),
+                    None => class.to_string(),
+                };
+                let diagnostics = if findings.is_empty() {
+                    None
+                } else {
+                    Some(view! { <LineDiagnostics findings=findings.clone() /> })
+                };
+                Some(view! {
+                    <div
+                        class=class
+                        class:find-match=move || find.is_match(line_number())
+                        class:find-current=move || find.is_current(line_number())
+                        class:filtered-out=move || filter.is_hidden(index.get())
+                        class:collapsed=move || collapsed.is_collapsed(index.get())
+                        class:updated=is_updated
+                        id=move || line_anchor_id(line_number())
+                    >
+                        {content.view()}
+                        {diagnostics}
                     </div>
-                }))
+                })
+            })
+        };
+        view! {
+            <HiddenLinesMarker filter index />
+            <CollapsedSegments runs=collapsed index />
+            {line_content}
+        }
+        .into_any()
+    };
+    let lines = line_window.chunks(keys, line_view, move |range| {
+        range
+            .filter(|index| !filter.is_hidden(*index) && !collapsed.is_collapsed(*index))
+            .count()
+    });
+    let raw = RawText::load();
+    let wrap = LineWrap::load(PLAYLIST_NO_WRAP_KEY);
+    let lines = view! {
+        {move || view! { <SegmentContextHeader playlist=shown.get() /> }}
+        {move || {
+            view! {
+                <PlaylistMinimap
+                    playlist=shown.get()
+                    lines=rendered_lines
+                    line_window
+                    collapsed
+                />
+            }
+        }}
+        <div
+            node_ref=rendered_lines
+            class=RENDERED_PLAYLIST_CLASS
+            class:no-wrap=move || wrap.is_no_wrap()
+            class:hidden=move || raw.is_shown()
+        >
+            {lines}
+        </div>
+        {move || view! { <RawPlaylist raw playlist=shown.get() /> }}
+    };
+    // Besides the lines, what is shown of the playlist is built again for each version of it.
+    let bandwidth_check = variant.map(|variant| {
+        let imported_definitions = imported_definitions.clone();
+        move || {
+            view! {
+                <BandwidthCheck
+                    playlist=shown.get()
+                    variant=variant.clone()
+                    imported_definitions=imported_definitions.clone()
+                />
             }
         }
-        Err(error) => Err(error),
+    });
+    let segment_navigation = highlighted_segment.map(|media_sequence| {
+        move || {
+            let segment_hrefs = version.with(|version| version.lines.segment_hrefs.clone());
+            view! { <SegmentNavigation media_sequence segment_hrefs /> }
+        }
+    });
+    // The report is only brought into view for the version of the playlist that the link opened.
+    let requested = StoredValue::new(automation.is_some_and(|automation| automation.validate()));
+    let report = view! {
+        <CopyLinkButton />
+        <div class=PLAYLIST_ACTIONS_CLASS>
+            <RawTextToggle raw />
+            <LineWrapToggle wrap />
+            <FontSizeControl />
+            {move || {
+                version
+                    .with(|version| {
+                        view! {
+                            <ReportButton
+                                playlist=version.playlist.clone()
+                                findings=playlist_analysis(&version.playlist).findings.clone()
+                                highlighted_lines=version.lines.highlighted_lines.clone()
+                            />
+                            <JsonExportButton playlist=version.playlist.clone() />
+                        }
+                    })
+            }}
+            <DownloadButton
+                file_name=playlist_file_name()
+                mime_type=PLAYLIST_MIME_TYPE
+                contents=move || shown.get_untracked().into_bytes()
+            />
+        </div>
+        {segment_navigation}
+        <FindBar find line_window />
+        <LineFilter filter />
+        {move || {
+            let playlist = shown.get();
+            let findings = playlist_analysis(&playlist).findings.clone();
+            let requested = requested.try_update_value(std::mem::take).unwrap_or_default();
+            view! {
+                <TagLegend playlist=playlist.clone() />
+                <PlaylistTimeline playlist=playlist.clone() line_window collapsed />
+                <IFrameThumbnails
+                    playlist=playlist.clone()
+                    imported_definitions=imported_definitions.clone()
+                />
+                <VariantSummaryTable playlist=playlist.clone() />
+                <ValidationReport findings requested />
+                <LiveTimeline playlist=playlist.clone() />
+                <AdAvails playlist=playlist.clone() />
+                <AdBreaks playlist=playlist.clone() />
+                <InterstitialPlayout playlist=playlist.clone() />
+                <KeyRotation playlist />
+            }
+        }}
+        <LiveUpdateCheck playlist=shown />
+        <NetworkPanel />
+    };
+    if supplemental_showing {
+        Ok(EitherOf3::B(view! {
+            <div
+                class=MAIN_VIEW_WITH_SUPPLEMENTAL_CLASS
+                role="region"
+                aria-label=tr(Message::PlaylistRegion)
+            >
+                <CopyButton text=move || shown.get_untracked() />
+                {report}
+                {bandwidth_check}
+                {lines}
+            </div>
+        }))
+    } else {
+        Ok(EitherOf3::C(view! {
+            <div class=MAIN_VIEW_CLASS role="region" aria-label=tr(Message::PlaylistRegion)>
+                <CopyButton text=move || shown.get_untracked() />
+                {report}
+                {bandwidth_check}
+                {lines}
+            </div>
+        }))
     }
 }
 
+const PLAYLIST_MIME_TYPE: &str = "application/vnd.apple.mpegurl";
+const DEFAULT_PLAYLIST_FILE_NAME: &str = "playlist.m3u8";
+
+// The name that the playlist is downloaded as, which is the name of the file that it was opened from
+// or else the last segment of its URL.
+fn playlist_file_name() -> String {
+    if let Some(file) = use_context::<LocalPlaylist>().and_then(|local| local.get_untracked()) {
+        return file.name;
+    }
+    let playlist_url = use_query_map()
+        .get_untracked()
+        .get(PLAYLIST_URL_QUERY_NAME)
+        .unwrap_or_default();
+    download_file_name(&playlist_url, None, DEFAULT_PLAYLIST_FILE_NAME)
+}
+
+// The segment asked for by the link is opened once the playlist has loaded, in place of the link
+// (so that going back does not open it again), keeping whatever else the link asked for.
+fn open_requested_segment(automation: Automation, segment_hrefs: Vec<(u64, String)>) {
+    let navigate = use_navigate();
+    Effect::new(move |_| {
+        let Some(selector) = automation.segment() else {
+            return;
+        };
+        let Some(href) = selector.select(&segment_hrefs) else {
+            log::warn!("the segment asked for by the link ({selector:?}) is not in the playlist");
+            return;
+        };
+        let options = NavigateOptions {
+            replace: true,
+            ..Default::default()
+        };
+        navigate(&with_automation(href.clone()), options);
+    });
+}
+
+/// The element ID given to the 1-based `line_number` of the playlist.
+pub fn line_anchor_id(line_number: usize) -> String {
+    format!("line-{line_number}")
+}
+
+/// The 1-based line number that was given the element ID, if it is one from [`line_anchor_id`].
+pub fn line_number_from_anchor_id(id: &str) -> Option<usize> {
+    id.strip_prefix("line-")?.parse().ok()
+}
+
 #[derive(Debug)]
 pub enum PlaylistError {
     PlaylistIdentifierNotPresent,
@@ -113,18 +482,53 @@ impl Display for PlaylistError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::PlaylistIdentifierNotPresent => {
-                write!(f, "Error: playlist identifier (#EXTM3U) not present")
+                write!(f, "{}", tr(Message::PlaylistIdentifierNotPresent))
             }
         }
     }
 }
 impl Error for PlaylistError {}
 
+#[derive(Clone, PartialEq)]
+struct PlaylistLine {
+    content: LineContent,
+    /// The validation findings for the line.
+    findings: Vec<Finding>,
+    /// The category that the line is filtered by, if any.
+    category: Option<LineCategory>,
+    /// The category of the tag on the line, which it is coloured by, if any.
+    tag_category: Option<TagCategory>,
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct PlaylistLines {
+    lines: Vec<PlaylistLine>,
+    /// The href of each Media Segment, by Media Sequence Number.
+    segment_hrefs: Vec<(u64, String)>,
+    /// The 1-based numbers of the lines with anything highlighted on them.
+    highlighted_lines: Vec<usize>,
+}
+
+/// A version of the playlist as it is shown, which is replaced each time that it is reloaded.
+#[derive(Clone, Default, PartialEq)]
+struct PlaylistVersion {
+    playlist: String,
+    lines: PlaylistLines,
+    /// The key of each line, by which its view is kept from one version to the next.
+    keys: Vec<u64>,
+    /// The 1-based numbers of the lines that are new or changed since the version before.
+    updated: Vec<usize>,
+    /// The 1-based line to show at the top of the window, in place of the one that was there when
+    /// the playlist was reloaded.
+    line_at_top: Option<usize>,
+}
+
 fn try_get_lines(
     playlist: &str,
     imported_definitions: HashMap<String, String>,
     highlighted: Option<Highlighted>,
-) -> Result<Vec<AnyView>, PlaylistError> {
+    analysis: &PlaylistAnalysis,
+) -> Result<PlaylistLines, PlaylistError> {
     let mut reader = Reader::from_str(
         playlist,
         ParsingOptionsBuilder::new()
@@ -134,11 +538,15 @@ fn try_get_lines(
             .with_parsing_for_define()
             .build(),
     );
-    let mut parsing_state = ParsingState::new(imported_definitions, highlighted);
+    let mut parsing_state = ParsingState::new(
+        imported_definitions,
+        highlighted,
+        analysis.audio_delivery.clone(),
+    );
 
     match reader.read_line() {
         Ok(Some(HlsLine::KnownTag(KnownTag::Hls(Tag::M3u(tag))))) => {
-            parsing_state.lines.push(tag_into_view!(tag))
+            parsing_state.lines.push(tag_into_line!(tag))
         }
         _ => return Err(PlaylistError::PlaylistIdentifierNotPresent),
     }
@@ -150,7 +558,7 @@ fn try_get_lines(
                     Tag::Byterange(tag) => x_byterange(tag, &mut parsing_state),
                     Tag::Define(tag) => x_define(tag, &mut parsing_state),
                     tag => {
-                        parsing_state.lines.push(tag_into_view!(tag));
+                        parsing_state.lines.push(tag_into_line!(tag));
                     }
                 },
                 KnownTag::Custom(_) => panic!("No custom tags registered"),
@@ -158,7 +566,7 @@ fn try_get_lines(
             HlsLine::Uri(uri) => uri_line(&uri, &mut parsing_state),
             HlsLine::Comment(comment) => parsing_state
                 .lines
-                .push(view! { <p class=COMMENT_CLASS>"#" {comment}</p> }.into_any()),
+                .push(LineContent::Comment(comment.to_string())),
             HlsLine::UnknownTag(tag) => {
                 let tag_name = TagName::try_from(tag.name()).ok();
                 if !parsing_state.is_media_playlist && is_media_tag(tag_name) {
@@ -166,56 +574,92 @@ fn try_get_lines(
                 }
                 match tag_name {
                     Some(TagName::Media) => playlist_uri_tag(&tag, &mut parsing_state),
+                    Some(TagName::StreamInf) => x_stream_inf(&tag, &mut parsing_state),
                     Some(TagName::IFrameStreamInf) => playlist_uri_tag(&tag, &mut parsing_state),
+                    Some(TagName::Key) => x_key(&tag, &mut parsing_state),
                     Some(TagName::Map) => x_map(&tag, &mut parsing_state),
                     Some(TagName::Part) => x_part(&tag, &mut parsing_state),
                     Some(TagName::Daterange) => x_daterange(&tag, &mut parsing_state),
-                    None if tag.name() == "-X-IMAGE-STREAM-INF" => playlist_uri_tag(&tag, &mut parsing_state),
-                    _ => parsing_state.lines.push(
-                        view! { <p class=TAG_CLASS>{String::from_utf8_lossy(tag.as_bytes())}</p> }
-                            .into_any(),
-                    ),
+                    None if tag.name() == "-X-IMAGE-STREAM-INF" => {
+                        playlist_uri_tag(&tag, &mut parsing_state)
+                    }
+                    _ => parsing_state.lines.push(LineContent::Tag(
+                        String::from_utf8_lossy(tag.as_bytes()).into_owned(),
+                    )),
                 }
             }
-            HlsLine::Blank => parsing_state
-                .lines
-                .push(view! { <p class=BLANK_CLASS></p> }.into_any()),
+            HlsLine::Blank => parsing_state.lines.push(LineContent::Blank),
         }
     }
-    Ok(parsing_state.lines)
+    // Exactly one line is produced per line of the playlist, so the position of each provides its
+    // (0-based) line number.
+    let mut findings = findings_by_line(&analysis.findings);
+    let lines = parsing_state
+        .lines
+        .into_iter()
+        .enumerate()
+        .map(|(index, content)| PlaylistLine {
+            content,
+            findings: findings.remove(&(index + 1)).unwrap_or_default(),
+            category: analysis.categories.get(index).copied().flatten(),
+            tag_category: analysis.tag_categories.get(index).copied().flatten(),
+        })
+        .collect();
+    Ok(PlaylistLines {
+        lines,
+        segment_hrefs: parsing_state.segment_hrefs,
+        highlighted_lines: parsing_state.highlighted_lines,
+    })
 }
 
 // Uri line handling
 
 fn uri_line(uri: &str, state: &mut ParsingState) {
-    let uri_class = if Some(state.media_sequence) == state.highlighted_segment {
-        HIGHLIGHTED_URI_CLASS
-    } else {
-        URI_CLASS
-    };
+    let highlighted = Some(state.media_sequence) == state.highlighted_segment;
+    if highlighted {
+        state.highlighted_lines.push(state.lines.len() + 1);
+    }
     let uri_type = if state.is_media_playlist {
         UriType::Segment
+    } else if let Some(attributes) = state.variant_attributes.take() {
+        UriType::Variant { attributes }
     } else {
         UriType::Playlist
     };
     let byterange = state.segment_byterange;
-    state.lines.push(
-        view! {
-            <a
-                href=resolve_href(ResolveOptions {
-                    uri,
-                    uri_type,
-                    media_sequence: state.media_sequence,
-                    byterange,
-                    definitions: &state.local_definitions,
-                })
-                class=uri_class
-            >
-                {uri}
-            </a>
-        }
-        .into_any(),
-    );
+    let is_segment = matches!(uri_type, UriType::Segment);
+    let href = resolve_href(ResolveOptions {
+        uri,
+        line_number: state.lines.len() + 1,
+        uri_type,
+        media_sequence: state.media_sequence,
+        byterange,
+        definitions: &state.local_definitions,
+    });
+    if is_segment && let Some(href) = &href {
+        state
+            .segment_hrefs
+            .push((state.media_sequence, href.clone()));
+    }
+    let sample_encryption = state
+        .key_methods
+        .iter()
+        .find_map(|(_, method)| Some((method, sample_encryption_scheme(method)?)))
+        .filter(|_| is_segment)
+        .map(|(method, scheme)| {
+            let title = format!(
+                "The samples of the segment are encrypted with METHOD={method} (the {scheme} \
+                 scheme of Common Encryption), while its boxes are in the clear. Open the segment \
+                 to see which of its boxes describe the encryption."
+            );
+            (method.clone(), title)
+        });
+    state.lines.push(LineContent::Uri {
+        uri: uri.to_string(),
+        href,
+        highlighted,
+        sample_encryption,
+    });
     // Reset segment state.
     state.media_sequence += 1;
     state.part_index = 0;
@@ -237,6 +681,7 @@ fn playlist_uri_tag(tag: &UnknownTag, state: &mut ParsingState) {
         |_, value| {
             resolve_href(ResolveOptions {
                 uri: value,
+                line_number: state.lines.len() + 1,
                 uri_type: UriType::Playlist,
                 media_sequence: state.media_sequence,
                 byterange: None,
@@ -245,7 +690,71 @@ fn playlist_uri_tag(tag: &UnknownTag, state: &mut ParsingState) {
         },
         |_, _| false,
     );
-    state.lines.push(view_from_markup(markup));
+    state.push_markup(markup);
+}
+
+fn x_stream_inf(tag: &UnknownTag, state: &mut ParsingState) {
+    // The attribute list is carried to the Media Playlist via the URI line that follows so that the
+    // media can later be checked against what is advertised here.
+    state.variant_attributes = String::from_utf8_lossy(tag.as_bytes())
+        .split_once(':')
+        .map(|(_, attributes)| attributes.to_string());
+    let mut markup = split_tag_as_markup(tag, [], |_, _| None, |_, _| false);
+    if let Some(layout) = quoted_attribute(tag, "REQ-VIDEO-LAYOUT").map(|v| VideoLayout::parse(&v))
+        && let Some(label) = layout.badge_label()
+    {
+        markup.push(Markup::Badge {
+            label: label.to_string(),
+            title: layout.description(),
+            style: BadgeStyle::Info,
+        });
+    }
+    // Whether the audio is muxed decides whether a player fetches a playlist for it as well.
+    if let Some(delivery) = state.audio_delivery.get(&(state.lines.len() + 1)) {
+        markup.push(Markup::Badge {
+            label: delivery.label().to_string(),
+            title: delivery.description().to_string(),
+            style: BadgeStyle::Info,
+        });
+    }
+    state.push_markup(markup);
+}
+
+fn x_key(tag: &UnknownTag, state: &mut ParsingState) {
+    // Like the keys themselves, the METHOD in effect is tracked per KEYFORMAT, so that the segments
+    // that follow can be marked when their samples are encrypted.
+    let method = attribute(tag, "METHOD").unwrap_or_else(|| String::from("NONE"));
+    let keyformat = attribute(tag, "KEYFORMAT").unwrap_or_else(|| String::from("identity"));
+    if method == "NONE" {
+        state.key_methods.clear();
+    } else {
+        state.key_methods.retain(|(format, _)| *format != keyformat);
+        state.key_methods.push((keyformat, method));
+    }
+    let mut markup = split_tag_as_markup(tag, [], |_, _| None, |_, _| false);
+    if quoted_attribute(tag, "KEYFORMAT").as_deref() == Some(FAIRPLAY_KEYFORMAT)
+        && let Some(skd) = quoted_attribute(tag, "URI").and_then(|uri| SkdUri::parse(&uri))
+    {
+        let label = match &skd.key_id {
+            Some(key_id) => tr_with(
+                Message::FairPlayKeyId,
+                &[("key_id", &key_id_as_uuid(key_id))],
+            ),
+            None => String::from(tr(Message::FairPlayAssetId)),
+        };
+        markup.push(Markup::Badge {
+            label,
+            title: tr_with(
+                Message::FairPlayAssetIdTitle,
+                &[
+                    ("asset_id", &skd.asset_id),
+                    ("convention", &skd.convention_description()),
+                ],
+            ),
+            style: BadgeStyle::Info,
+        });
+    }
+    state.push_markup(markup);
 }
 
 fn x_map(tag: &UnknownTag, state: &mut ParsingState) {
@@ -256,6 +765,7 @@ fn x_map(tag: &UnknownTag, state: &mut ParsingState) {
         |_, value| {
             resolve_href(ResolveOptions {
                 uri: value,
+                line_number: state.lines.len() + 1,
                 uri_type: UriType::Map,
                 media_sequence: state.media_sequence,
                 byterange,
@@ -274,12 +784,12 @@ fn x_map(tag: &UnknownTag, state: &mut ParsingState) {
             }
         },
     );
-    state.lines.push(view_from_markup(markup));
+    state.push_markup(markup);
 }
 
 fn x_media_sequence(tag: MediaSequence, state: &mut ParsingState) {
     state.media_sequence = tag.media_sequence();
-    state.lines.push(tag_into_view!(tag));
+    state.lines.push(tag_into_line!(tag));
 }
 
 fn x_byterange(tag: Byterange, state: &mut ParsingState) {
@@ -290,7 +800,7 @@ fn x_byterange(tag: Byterange, state: &mut ParsingState) {
     let byterange = RequestRange::from_length_with_offset(length, offset);
     state.segment_byterange = Some(byterange);
     state.offset_after_last_segment_byterange = byterange.end + 1;
-    state.lines.push(tag_into_view!(tag));
+    state.lines.push(tag_into_line!(tag));
 }
 
 fn x_define(tag: Define, state: &mut ParsingState) {
@@ -319,7 +829,7 @@ fn x_define(tag: Define, state: &mut ParsingState) {
             }
         }
     }
-    state.lines.push(tag_into_view!(tag));
+    state.lines.push(tag_into_line!(tag));
 }
 
 fn x_part(tag: &UnknownTag, state: &mut ParsingState) {
@@ -346,6 +856,7 @@ fn x_part(tag: &UnknownTag, state: &mut ParsingState) {
         |_, value| {
             resolve_href(ResolveOptions {
                 uri: value,
+                line_number: state.lines.len() + 1,
                 uri_type: UriType::Part {
                     part_index: state.part_index,
                 },
@@ -356,7 +867,7 @@ fn x_part(tag: &UnknownTag, state: &mut ParsingState) {
         },
         |_, _| is_highlighted,
     );
-    state.lines.push(view_from_markup(markup));
+    state.push_markup(markup);
     // Based on https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-3.2
     //    Each Partial Segment has a Part Index, which is an integer indicating
     //    the position of the Partial Segment within its Parent Segment.  The
@@ -370,6 +881,8 @@ fn x_daterange(tag: &UnknownTag, state: &mut ParsingState) {
     let mut id = None;
     let mut class = None;
     let mut target_class = None;
+    let mut cue = None;
+    let mut timeline_occupies = None;
     if let Some(attribute_list) = tag
         .value()
         .and_then(|v| v.try_as_ordered_attribute_list().ok())
@@ -381,10 +894,14 @@ fn x_daterange(tag: &UnknownTag, state: &mut ParsingState) {
                 class = value.quoted().map(String::from);
             } else if name == "X-TARGET-CLASS" {
                 target_class = value.quoted().map(String::from);
+            } else if name == "CUE" {
+                cue = value.quoted().map(Cue::parse);
+            } else if name == "X-TIMELINE-OCCUPIES" {
+                timeline_occupies = value.quoted().map(String::from);
             }
         }
     }
-    let markup = split_tag_as_markup(
+    let mut markup = split_tag_as_markup(
         tag,
         [
             "SCTE35-OUT",
@@ -474,7 +991,22 @@ fn x_daterange(tag: &UnknownTag, state: &mut ParsingState) {
             _ => false,
         },
     );
-    state.lines.push(view_from_markup(markup));
+    if let Some(cue) = cue {
+        markup.push(Markup::Badge {
+            label: cue.label(),
+            title: cue.explanation(),
+            style: BadgeStyle::Info,
+        });
+        let problems = cue.problems(class.as_deref(), timeline_occupies.as_deref());
+        if !problems.is_empty() {
+            markup.push(Markup::Badge {
+                label: String::from(tr(Message::InvalidCue)),
+                title: problems.join("\n"),
+                style: BadgeStyle::Warning,
+            });
+        }
+    }
+    state.push_markup(markup);
 }
 
 // General href utility
@@ -482,13 +1014,18 @@ fn x_daterange(tag: &UnknownTag, state: &mut ParsingState) {
 fn resolve_href(opts: ResolveOptions) -> Option<String> {
     let ResolveOptions {
         uri,
+        line_number,
         uri_type,
         media_sequence,
         byterange,
         definitions,
     } = opts;
     match uri_type {
-        UriType::Playlist => media_playlist_href(uri, definitions),
+        // Playlists of these types are only linked to from a Multivariant Playlist.
+        UriType::Playlist => media_playlist_href(uri, definitions)
+            .map(|href| with_parent_playlist(href, line_number)),
+        UriType::Variant { attributes } => variant_playlist_href(uri, definitions, &attributes)
+            .map(|href| with_parent_playlist(href, line_number)),
         UriType::Segment => segment_href(uri, media_sequence, byterange, definitions),
         UriType::Map => map_href(uri, media_sequence, byterange, definitions),
         UriType::Part { part_index } => {
@@ -502,6 +1039,15 @@ enum InterstitialType {
     AssetList,
 }
 fn guess_interstitial_type_from_uri(uri: &str) -> Option<InterstitialType> {
+    if let Some(media_type) = data_uri_media_type(uri) {
+        return if media_type.eq_ignore_ascii_case("application/json") {
+            Some(InterstitialType::AssetList)
+        } else if media_type.to_ascii_lowercase().contains("mpegurl") {
+            Some(InterstitialType::Uri)
+        } else {
+            None
+        };
+    }
     let before_query = uri.split('?').next()?;
     let last_path = before_query.split('/').next_back()?;
     let extension = last_path.split('.').next_back()?;
@@ -514,14 +1060,36 @@ fn guess_interstitial_type_from_uri(uri: &str) -> Option<InterstitialType> {
     }
 }
 
+fn tag_view(line: &str) -> AnyView {
+    let terms = spec_terms(line);
+    view! { <p class=TAG_CLASS>{spec_text(line, 0, &terms)}</p> }.into_any()
+}
+
 fn view_from_markup(markup: Vec<Markup>) -> AnyView {
+    // The strings and links make up the whole line, which is where the names that the
+    // specification defines are found.
+    let line = markup
+        .iter()
+        .filter_map(|markup| match markup {
+            Markup::String(s) => Some(s.as_str()),
+            Markup::Link { value, .. } => Some(value.as_str()),
+            Markup::Badge { .. } => None,
+        })
+        .collect::<String>();
+    let terms = spec_terms(&line);
+    let mut offset = 0;
     view! {
         <p class=TAG_CLASS>
             {markup
                 .into_iter()
                 .map(|markup| match markup {
-                    Markup::String(s) => view! { {s} }.into_any(),
+                    Markup::String(s) => {
+                        let view = spec_text(&s, offset, &terms);
+                        offset += s.len();
+                        view
+                    }
                     Markup::Link { href, value, highlighted } => {
+                        offset += value.len();
                         let class = if highlighted { HIGHLIGHTED } else { "" };
                         view! {
                             <a class=class href=href>
@@ -530,6 +1098,7 @@ fn view_from_markup(markup: Vec<Markup>) -> AnyView {
                         }
                             .into_any()
                     }
+                    Markup::Badge { label, title, style } => badge_view(label, title, &style),
                 })
                 .collect_view()}
         </p>
@@ -537,6 +1106,53 @@ fn view_from_markup(markup: Vec<Markup>) -> AnyView {
     .into_any()
 }
 
+fn badge_view(label: String, title: String, style: &BadgeStyle) -> AnyView {
+    let class = match style {
+        BadgeStyle::Info => BADGE_CLASS,
+        BadgeStyle::Warning => BADGE_WARNING_CLASS,
+    };
+    view! {
+        <span class=class title=title>
+            {label}
+        </span>
+    }
+    .into_any()
+}
+
+// Part of the text of a tag line, starting at the byte `offset` of the line, where the names that
+// the specification defines link to their definitions (which are shown on hover). The terms are
+// left out of the tab order, as every tag and attribute name would otherwise come
+// between the links to the playlists and segments that the keyboard is used to move through.
+fn spec_text(text: &str, offset: usize, terms: &[SpecTerm]) -> AnyView {
+    let mut views = Vec::new();
+    let mut position = 0;
+    for term in terms
+        .iter()
+        .filter(|term| term.range.start >= offset && term.range.end <= offset + text.len())
+    {
+        let (start, end) = (term.range.start - offset, term.range.end - offset);
+        views.push(view! { {text[position..start].to_string()} }.into_any());
+        views.push(
+            view! {
+                <a
+                    class=SPEC_TERM_CLASS
+                    href=term.href.clone()
+                    target="_blank"
+                    rel="noopener noreferrer"
+                    tabindex="-1"
+                    title=term.title.clone()
+                >
+                    {text[start..end].to_string()}
+                </a>
+            }
+            .into_any(),
+        );
+        position = end;
+    }
+    views.push(view! { {text[position..].to_string()} }.into_any());
+    views.into_any()
+}
+
 /// Split up a tag into markup of strings and links. The links are intended to be wrapped in anchor
 /// tags.
 ///
@@ -607,6 +1223,27 @@ where
     markup
 }
 
+// The value of the attribute whether it is quoted or not, such as the enumerated METHOD of a key.
+fn attribute(tag: &UnknownTag, name: &str) -> Option<String> {
+    let list = tag.value()?.try_as_ordered_attribute_list().ok()?;
+    list.iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, value)| match value {
+            AttributeValue::Unquoted(v) => String::from_utf8_lossy(v.0).into_owned(),
+            AttributeValue::Quoted(s) => s.to_string(),
+        })
+}
+
+fn quoted_attribute(tag: &UnknownTag, name: &str) -> Option<String> {
+    tag.value()
+        .and_then(|v| v.try_as_ordered_attribute_list().ok())
+        .and_then(|list| {
+            list.iter()
+                .find(|(n, _)| *n == name)
+                .and_then(|(_, v)| v.quoted().map(String::from))
+        })
+}
+
 // Helper for determining whether playlist is mvp or media
 
 fn is_media_tag(tag_name: Option<TagName>) -> bool {
@@ -660,8 +1297,10 @@ struct ParsingState {
     highlighted_scte35_info: Option<HighlightedScte35Info>,
     highlighted_asset_list_daterange_id: Option<String>,
     highlighted_x_uri_daterange_id: Option<String>,
+    /// How the audio of each variant is delivered, by the line number of its EXT-X-STREAM-INF.
+    audio_delivery: HashMap<usize, AudioDelivery>,
     // Constructed by default
-    lines: Vec<AnyView>,
+    lines: Vec<LineContent>,
     media_sequence: u64,
     part_index: u32,
     is_media_playlist: bool,
@@ -670,11 +1309,19 @@ struct ParsingState {
     offset_after_last_part_byterange: u64,
     segment_byterange: Option<RequestRange>,
     local_definitions: HashMap<String, String>,
+    variant_attributes: Option<String>,
+    /// The KEYFORMAT and METHOD of each EXT-X-KEY in effect.
+    key_methods: Vec<(String, String)>,
+    /// The href of each Media Segment, by Media Sequence Number.
+    segment_hrefs: Vec<(u64, String)>,
+    /// The 1-based numbers of the lines with anything highlighted on them.
+    highlighted_lines: Vec<usize>,
 }
 impl ParsingState {
     fn new(
         imported_definitions: HashMap<String, String>,
         highlighted: Option<Highlighted>,
+        audio_delivery: HashMap<usize, AudioDelivery>,
     ) -> Self {
         let (
             highlighted_segment,
@@ -745,6 +1392,7 @@ impl ParsingState {
             highlighted_scte35_info,
             highlighted_asset_list_daterange_id,
             highlighted_x_uri_daterange_id,
+            audio_delivery,
             lines: Default::default(),
             media_sequence: Default::default(),
             part_index: Default::default(),
@@ -754,12 +1402,33 @@ impl ParsingState {
             offset_after_last_part_byterange: Default::default(),
             segment_byterange: Default::default(),
             local_definitions: Default::default(),
+            variant_attributes: Default::default(),
+            key_methods: Default::default(),
+            segment_hrefs: Default::default(),
+            highlighted_lines: Default::default(),
+        }
+    }
+
+    fn push_markup(&mut self, markup: Vec<Markup>) {
+        let highlighted = markup.iter().any(|markup| {
+            matches!(
+                markup,
+                Markup::Link {
+                    highlighted: true,
+                    ..
+                }
+            )
+        });
+        if highlighted {
+            self.highlighted_lines.push(self.lines.len() + 1);
         }
+        self.lines.push(LineContent::Markup(markup));
     }
 }
 
 enum UriType {
     Playlist,
+    Variant { attributes: String },
     Segment,
     Map,
     Part { part_index: u32 },
@@ -767,13 +1436,66 @@ enum UriType {
 
 struct ResolveOptions<'a> {
     uri: &'a str,
+    /// The 1-based number of the line with the URI.
+    line_number: usize,
     uri_type: UriType,
     media_sequence: u64,
     byterange: Option<RequestRange>,
     definitions: &'a HashMap<String, String>,
 }
 
-#[derive(Debug, PartialEq)]
+/// What a line of the playlist shows, from which the view of the line is built when it is rendered.
+#[derive(Debug, Clone, PartialEq)]
+enum LineContent {
+    Tag(String),
+    /// A tag with links to what it refers to, or badges that describe it.
+    Markup(Vec<Markup>),
+    Uri {
+        uri: String,
+        href: Option<String>,
+        highlighted: bool,
+        /// The METHOD that the samples of a segment are encrypted with, and a description of it.
+        sample_encryption: Option<(String, String)>,
+    },
+    Comment(String),
+    Blank,
+}
+impl LineContent {
+    fn view(&self) -> AnyView {
+        match self {
+            Self::Tag(line) => tag_view(line),
+            Self::Markup(markup) => view_from_markup(markup.clone()),
+            Self::Uri {
+                uri,
+                href,
+                highlighted,
+                sample_encryption,
+            } => {
+                let class = if *highlighted {
+                    HIGHLIGHTED_URI_CLASS
+                } else {
+                    URI_CLASS
+                };
+                let sample_encryption = sample_encryption
+                    .clone()
+                    .map(|(method, title)| badge_view(method, title, &BadgeStyle::Info));
+                view! {
+                    <a href=href.clone() class=class>
+                        {uri.clone()}
+                    </a>
+                    {sample_encryption}
+                }
+                .into_any()
+            }
+            Self::Comment(comment) => {
+                view! { <p class=COMMENT_CLASS>"#" {comment.clone()}</p> }.into_any()
+            }
+            Self::Blank => view! { <p class=BLANK_CLASS></p> }.into_any(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 enum Markup {
     String(String),
     Link {
@@ -781,6 +1503,18 @@ enum Markup {
         value: String,
         highlighted: bool,
     },
+    /// A short label rendered alongside the tag, with more detail provided on hover.
+    Badge {
+        label: String,
+        title: String,
+        style: BadgeStyle,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BadgeStyle {
+    Info,
+    Warning,
 }
 
 #[cfg(test)]
@@ -0,0 +1,83 @@
+use crate::utils::{
+    hls_spec::{TagCategory, tag_category},
+    playlist_lines::{LineCategory, LineKind, categorize, parse},
+    validation::{Finding, validate},
+    variant_summary::{AudioDelivery, variant_summaries},
+};
+use leptos::prelude::*;
+use std::{collections::HashMap, sync::Arc};
+
+/// What is worked out from the text of a playlist alone, which does not change when a different
+/// segment is opened (and with it, what is highlighted in the playlist).
+pub(super) struct PlaylistAnalysis {
+    playlist: String,
+    pub findings: Vec<Finding>,
+    /// The category that each line is filtered by, if any, by 0-based line number.
+    pub categories: Vec<Option<LineCategory>>,
+    /// The category of the tag on each line, if any, by 0-based line number.
+    pub tag_categories: Vec<Option<TagCategory>>,
+    /// How the audio of each variant is delivered, by the 1-based line number of its
+    /// EXT-X-STREAM-INF tag.
+    pub audio_delivery: HashMap<usize, AudioDelivery>,
+}
+
+impl PlaylistAnalysis {
+    fn new(playlist: &str) -> Self {
+        let parsed = parse(playlist);
+        Self {
+            playlist: playlist.to_string(),
+            findings: validate(playlist),
+            categories: categorize(&parsed),
+            tag_categories: parsed
+                .iter()
+                .map(|line| match &line.kind {
+                    LineKind::Tag(tag) => tag_category(tag.name),
+                    _ => None,
+                })
+                .collect(),
+            audio_delivery: variant_summaries(playlist)
+                .into_iter()
+                .filter_map(|variant| Some((variant.line, variant.audio_delivery?)))
+                .collect(),
+        }
+    }
+}
+
+/// The analysis of the playlist last shown. The playlist is shown again whenever the supplemental
+/// view changes, so without this a large playlist would be validated over again on opening each of
+/// its segments. It is keyed by the text, so a live playlist is analysed again once it changes.
+#[derive(Clone, Copy)]
+pub struct PlaylistCache(StoredValue<Option<Arc<PlaylistAnalysis>>>);
+
+impl Default for PlaylistCache {
+    fn default() -> Self {
+        Self(StoredValue::new(None))
+    }
+}
+
+impl PlaylistCache {
+    pub fn clear(&self) {
+        self.0.set_value(None);
+    }
+
+    pub(super) fn analysis(&self, playlist: &str) -> Arc<PlaylistAnalysis> {
+        if let Some(analysis) = self
+            .0
+            .get_value()
+            .filter(|analysis| analysis.playlist == playlist)
+        {
+            return analysis;
+        }
+        let analysis = Arc::new(PlaylistAnalysis::new(playlist));
+        self.0.set_value(Some(Arc::clone(&analysis)));
+        analysis
+    }
+}
+
+/// The analysis of the playlist, from the cache when one has been provided.
+pub(super) fn playlist_analysis(playlist: &str) -> Arc<PlaylistAnalysis> {
+    match use_context::<PlaylistCache>() {
+        Some(cache) => cache.analysis(playlist),
+        None => Arc::new(PlaylistAnalysis::new(playlist)),
+    }
+}
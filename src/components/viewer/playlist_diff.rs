@@ -0,0 +1,113 @@
+use super::{
+    PLAYLIST_DIFF_CLASS,
+    error::ViewerError,
+    line_wrap::{DIFF_NO_WRAP_KEY, LineWrap, LineWrapToggle},
+};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    network::{FetchError, FetchTextResponse},
+    playlist_diff::{DiffLine, DiffRow, attribute_spans, diff},
+};
+use leptos::{either::Either, prelude::*};
+
+/// The lines of two playlists aligned side by side, where lines that are only in one of them or
+/// that differ between them are colored, along with the attributes that differ within a tag.
+#[component]
+pub fn PlaylistDiff(
+    left: Result<FetchTextResponse, FetchError>,
+    right: Result<FetchTextResponse, FetchError>,
+) -> impl IntoView {
+    let (left, right) = match (left, right) {
+        (Ok(left), Ok(right)) => (left.response_text, right.response_text),
+        (Err(error), _) | (_, Err(error)) => {
+            return Either::Left(view! {
+                <div class=PLAYLIST_DIFF_CLASS>
+                    <ViewerError error=error.error() extra_info=error.extra_info() />
+                </div>
+            });
+        }
+    };
+    let rows = diff(&left, &right);
+    let count = |matches: fn(&DiffRow) -> bool| rows.iter().filter(|row| matches(row)).count();
+    let changed = count(|row| matches!(row, DiffRow::Changed { .. }));
+    let removed = count(|row| matches!(row, DiffRow::Removed(_)));
+    let added = count(|row| matches!(row, DiffRow::Added(_)));
+    let summary = if changed + removed + added == 0 {
+        String::from(tr(Message::PlaylistsIdentical))
+    } else {
+        tr_with(
+            Message::PlaylistDiffSummary,
+            &[
+                ("changed", &changed.to_string()),
+                ("removed", &removed.to_string()),
+                ("added", &added.to_string()),
+            ],
+        )
+    };
+    let rows = rows
+        .into_iter()
+        .map(|row| match row {
+            DiffRow::Same(left, right) => view! {
+                <tr class="diff-same">
+                    {diff_cells(Some(left), &[])}
+                    {diff_cells(Some(right), &[])}
+                </tr>
+            }
+            .into_any(),
+            DiffRow::Removed(left) => view! {
+                <tr class="diff-removed">{diff_cells(Some(left), &[])} {diff_cells(None, &[])}</tr>
+            }
+            .into_any(),
+            DiffRow::Added(right) => view! {
+                <tr class="diff-added">{diff_cells(None, &[])} {diff_cells(Some(right), &[])}</tr>
+            }
+            .into_any(),
+            DiffRow::Changed {
+                left,
+                right,
+                attributes,
+            } => view! {
+                <tr class="diff-changed">
+                    {diff_cells(Some(left), &attributes)}
+                    {diff_cells(Some(right), &attributes)}
+                </tr>
+            }
+            .into_any(),
+        })
+        .collect_view();
+    let wrap = LineWrap::load(DIFF_NO_WRAP_KEY);
+    Either::Right(view! {
+        <div class=PLAYLIST_DIFF_CLASS class:no-wrap=move || wrap.is_no_wrap()>
+            <div class="diff-summary">
+                <p>{summary}</p>
+                <LineWrapToggle wrap />
+            </div>
+            <table>
+                <tbody>{rows}</tbody>
+            </table>
+        </div>
+    })
+}
+
+// The line number and text of one side of a row, which is left empty for a line that is only on
+// the other side.
+fn diff_cells(line: Option<DiffLine>, attributes: &[&str]) -> impl IntoView + use<> {
+    let Some(line) = line else {
+        return Either::Left(view! {
+            <td class="diff-line-number" />
+            <td />
+        });
+    };
+    let text = attribute_spans(line.text, attributes)
+        .into_iter()
+        .map(|(span, changed)| {
+            view! { <span class:diff-attribute=changed>{span.to_string()}</span> }
+        })
+        .collect_view();
+    Either::Right(view! {
+        <td class="diff-line-number">{line.number}</td>
+        <td>
+            <pre>{text}</pre>
+        </td>
+    })
+}
@@ -0,0 +1,109 @@
+use super::{
+    PLAYLIST_TIMELINE_CLASS, collapsed_segments::CollapsedRuns, line_window::LineWindow,
+    playlist::line_anchor_id,
+};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    playlist_timeline::timeline,
+    segment_runs::format_duration,
+};
+use leptos::{ev::MouseEvent, prelude::*};
+
+/// A timeline of a media playlist, with its segments as blocks sized by their duration, markers at
+/// its discontinuities, and its dateranges (ad breaks among them) as overlays. Clicking any part of
+/// it scrolls to the line that it came from.
+#[component]
+pub fn PlaylistTimeline(
+    playlist: String,
+    line_window: LineWindow,
+    collapsed: CollapsedRuns,
+) -> Option<impl IntoView> {
+    let timeline = timeline(&playlist);
+    let duration = timeline.duration();
+    if duration <= 0.0 {
+        return None;
+    }
+    let percent = move |seconds: f64| format!("{}%", seconds / duration * 100.0);
+    let go_to = move |line: usize| {
+        move |_: MouseEvent| {
+            collapsed.reveal(line - 1);
+            line_window.scroll_to(line);
+        }
+    };
+    let segments = timeline
+        .segments
+        .into_iter()
+        .map(|segment| {
+            let title = tr_with(
+                Message::TimelineSegmentTitle,
+                &[
+                    ("media_sequence", &segment.media_sequence.to_string()),
+                    ("duration", &format!("{:.3}", segment.duration)),
+                ],
+            );
+            let discontinuity = segment.discontinuity.then(|| {
+                view! {
+                    <div
+                        class="timeline-discontinuity"
+                        style:left=percent(segment.start)
+                        title=tr(Message::TimelineDiscontinuityTitle)
+                    />
+                }
+            });
+            view! {
+                {discontinuity}
+                <a
+                    class="timeline-segment"
+                    class:gap=segment.gap
+                    href=format!("#{}", line_anchor_id(segment.line))
+                    title=title
+                    style:left=percent(segment.start)
+                    style:width=percent(segment.duration)
+                    on:click=go_to(segment.line)
+                />
+            }
+        })
+        .collect_view();
+    // Dateranges wholly outside of the playlist (e.g. those yet to come in a live playlist) have
+    // nowhere to be shown.
+    let dateranges = timeline
+        .dateranges
+        .into_iter()
+        .filter(|daterange| {
+            daterange.start <= duration
+                && daterange.start + daterange.duration.unwrap_or(0.0) >= 0.0
+        })
+        .map(|daterange| {
+            let start = daterange.start.max(0.0);
+            let end = (daterange.start + daterange.duration.unwrap_or(0.0)).min(duration);
+            let title = match daterange.duration {
+                Some(seconds) => format!("{} ({})", daterange.id, format_duration(seconds)),
+                None => daterange.id,
+            };
+            view! {
+                <a
+                    class="timeline-daterange"
+                    class:ad-break=daterange.ad_break
+                    href=format!("#{}", line_anchor_id(daterange.line))
+                    title=title
+                    style:left=percent(start)
+                    style:width=percent(end - start)
+                    on:click=go_to(daterange.line)
+                />
+            }
+        })
+        .collect::<Vec<_>>();
+    let dateranges = (!dateranges.is_empty()).then(|| {
+        view! { <div class="timeline-track timeline-dateranges">{dateranges}</div> }
+    });
+    Some(view! {
+        <div class=PLAYLIST_TIMELINE_CLASS>
+            <div class="timeline-track">{segments}</div>
+            {dateranges}
+            <div class="timeline-axis">
+                <span>"0s"</span>
+                <span>{format_duration(duration)}</span>
+            </div>
+        </div>
+    })
+}
@@ -0,0 +1,59 @@
+use super::RAW_PLAYLIST_CLASS;
+use crate::utils::{
+    i18n::{Message, tr},
+    settings::FeatureToggle,
+    storage::{load_flag, save_flag},
+};
+use leptos::prelude::*;
+
+// Whether playlists are shown as their raw text, which is remembered as it is usually wanted for
+// every playlist while copying out of them.
+const RAW_TEXT_KEY: &str = FeatureToggle::RawText.key();
+
+/// Whether the playlist is shown exactly as it was fetched, in place of the rendered lines (with
+/// their links, highlights and badges), so that any portion of it can be selected and copied as is.
+#[derive(Clone, Copy)]
+pub struct RawText(RwSignal<bool>);
+
+impl RawText {
+    pub fn load() -> Self {
+        let shown = RwSignal::new(load_flag(RAW_TEXT_KEY));
+        Effect::watch(
+            move || shown.get(),
+            move |shown, _, _| save_flag(RAW_TEXT_KEY, *shown),
+            false,
+        );
+        Self(shown)
+    }
+
+    pub fn is_shown(&self) -> bool {
+        self.0.get()
+    }
+}
+
+#[component]
+pub fn RawTextToggle(raw: RawText) -> impl IntoView {
+    let RawText(shown) = raw;
+    view! {
+        <button
+            class="button"
+            type="button"
+            aria-pressed=move || shown.get().to_string()
+            title=tr(Message::RawTextTitle)
+            on:click=move |_| shown.update(|shown| *shown = !*shown)
+        >
+            {move || {
+                if shown.get() { tr(Message::ShowRendered) } else { tr(Message::ShowRawText) }
+            }}
+        </button>
+    }
+}
+
+#[component]
+pub fn RawPlaylist(raw: RawText, playlist: String) -> impl IntoView {
+    view! {
+        <pre class=RAW_PLAYLIST_CLASS class:hidden=move || !raw.is_shown()>
+            {playlist}
+        </pre>
+    }
+}
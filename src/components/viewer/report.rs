@@ -0,0 +1,114 @@
+use crate::{
+    components::LocalPlaylist,
+    utils::{
+        download::download_text,
+        href::{PLAYLIST_URL_QUERY_NAME, playlist_name},
+        html_report::{Report, html_report},
+        i18n::{Message, tr},
+        mp4_atom_properties::AtomProperties,
+        playlist_json::{JSON_MIME_TYPE, playlist_json},
+        validation::Finding,
+    },
+};
+use leptos::prelude::*;
+use leptos_router::{hooks::use_query_map, params::ParamsMap};
+
+const REPORT_MIME_TYPE: &str = "text/html";
+
+/// The properties of the box last selected in a box view of the viewer, which the report includes,
+/// as the box is usually what the report was wanted for.
+#[derive(Clone, Copy)]
+pub struct SelectedBox(RwSignal<Option<AtomProperties>>);
+
+impl SelectedBox {
+    pub fn provide() {
+        provide_context(Self(RwSignal::new(None)));
+    }
+
+    pub fn set(&self, properties: Option<AtomProperties>) {
+        self.0.set(properties);
+    }
+}
+
+/// Saves what the viewer shows of the playlist as a standalone HTML page: a summary of it, its
+/// validation findings, the selected box (when a segment is open) and its lines with their
+/// highlights.
+#[component]
+pub fn ReportButton(
+    playlist: String,
+    findings: Vec<Finding>,
+    highlighted_lines: Vec<usize>,
+) -> impl IntoView {
+    let local_playlist = use_context::<LocalPlaylist>();
+    let query = use_query_map();
+    let selected_box = use_context::<SelectedBox>();
+    let export = move |_| {
+        let (title, source) = title_and_source(local_playlist, query);
+        let box_properties = selected_box.and_then(|selected| selected.0.get_untracked());
+        let html = html_report(&Report {
+            title: &title,
+            source: &source,
+            playlist: &playlist,
+            highlighted_lines: &highlighted_lines,
+            findings: &findings,
+            box_properties: box_properties.as_ref(),
+        });
+        download_text(
+            &format!("{}-report.html", file_stem(&title)),
+            REPORT_MIME_TYPE,
+            &html,
+        );
+    };
+    view! {
+        <button
+            class="button"
+            type="button"
+            title=tr(Message::ExportReportTitle)
+            on:click=export
+        >
+            {tr(Message::ExportReport)}
+        </button>
+    }
+}
+
+/// Saves the playlist as parsed, with the segments and variant streams worked out, as JSON.
+#[component]
+pub fn JsonExportButton(playlist: String) -> impl IntoView {
+    let local_playlist = use_context::<LocalPlaylist>();
+    let query = use_query_map();
+    let export = move |_| {
+        let (title, _) = title_and_source(local_playlist, query);
+        download_text(
+            &format!("{}.json", file_stem(&title)),
+            JSON_MIME_TYPE,
+            &playlist_json(&playlist),
+        );
+    };
+    view! {
+        <button class="button" type="button" title=tr(Message::ExportJsonTitle) on:click=export>
+            {tr(Message::ExportJson)}
+        </button>
+    }
+}
+
+// The name of the playlist and where it came from. A playlist opened from a file is named by the
+// file rather than by its base URL.
+pub(super) fn title_and_source(
+    local_playlist: Option<LocalPlaylist>,
+    query: Memo<ParamsMap>,
+) -> (String, String) {
+    match local_playlist.and_then(|local| local.get_untracked()) {
+        Some(file) => (file.name.clone(), file.name),
+        None => {
+            let url = query
+                .get_untracked()
+                .get(PLAYLIST_URL_QUERY_NAME)
+                .unwrap_or_default();
+            (playlist_name(&url), url)
+        }
+    }
+}
+
+pub(super) fn file_stem(title: &str) -> &str {
+    title.rsplit_once('.').map_or(title, |(stem, _)| stem)
+}
@@ -0,0 +1,86 @@
+use super::SAMPLE_ENCRYPTION_CLASS;
+use crate::utils::{
+    encryption::sample_encryption_scheme,
+    i18n::{Message, tr, tr_with},
+};
+use leptos::{either::Either, prelude::*};
+
+/// A box of a segment (or its initialization segment) that describes how its samples are encrypted.
+pub struct SampleEncryptionBox {
+    pub kind: String,
+    pub role: &'static str,
+    /// The index of the box among those of the segment, or None when it is in the initialization
+    /// segment (which is not on show).
+    pub index: Option<usize>,
+}
+
+/// Explains which boxes carry the sample encryption of a segment, as with SAMPLE-AES the boxes are
+/// in the clear while the media data is not.
+#[component]
+pub fn SampleEncryption(
+    /// The METHOD of the EXT-X-KEY that applies to the segment, if any.
+    method: Option<String>,
+    boxes: Vec<SampleEncryptionBox>,
+    on_select: impl Fn(usize) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let explanation = match method
+        .as_deref()
+        .and_then(|method| Some((method, sample_encryption_scheme(method)?)))
+    {
+        Some((method, scheme)) => tr_with(
+            Message::SampleEncryptionScheme,
+            &[("method", method), ("scheme", scheme)],
+        ),
+        None => String::from(tr(Message::SampleEncryptionExplained)),
+    };
+    let summary = tr_with(
+        Message::SampleEncryptionSummary,
+        &[("count", &boxes.len().to_string())],
+    );
+    view! {
+        <details class=SAMPLE_ENCRYPTION_CLASS>
+            <summary>{summary}</summary>
+            <p>{explanation}</p>
+            <table>
+                <tr>
+                    <th>{tr(Message::SampleEncryptionBox)}</th>
+                    <th>{tr(Message::SampleEncryptionRole)}</th>
+                </tr>
+                {boxes
+                    .into_iter()
+                    .map(|encryption_box| {
+                        let kind = match encryption_box.index {
+                            Some(index) => {
+                                Either::Left(
+                                    view! {
+                                        <button
+                                            class="button"
+                                            type="button"
+                                            on:click=move |_| on_select(index)
+                                        >
+                                            {encryption_box.kind}
+                                        </button>
+                                    },
+                                )
+                            }
+                            None => {
+                                Either::Right(
+                                    tr_with(
+                                        Message::InInitializationSegment,
+                                        &[("kind", &encryption_box.kind)],
+                                    ),
+                                )
+                            }
+                        };
+                        view! {
+                            <tr>
+                                <td>{kind}</td>
+                                <td>{encryption_box.role}</td>
+                            </tr>
+                        }
+                    })
+                    .collect_view()}
+            </table>
+        </details>
+    }
+}
@@ -1,111 +1,567 @@
-use super::{LINE_BREAK_ANYWHERE, LINE_BREAK_WORD, SUPPLEMENTAL_VIEW_CLASS, UNDERLINED};
+use super::{
+    LINE_BREAK_ANYWHERE, LINE_BREAK_WORD, SUPPLEMENTAL_VIEW_CLASS, UNDERLINED,
+    VALIDATION_ERROR_CLASS, VALIDATION_PASSED_CLASS, VALIDATION_REPORT_CLASS,
+    VALIDATION_WARNING_CLASS, playlist::line_anchor_id, timeline_drift::DriftHistory,
+};
 use crate::{
     components::viewer::error::ViewerError,
     utils::{
-        hex::{DecodeHexError, decode_hex},
+        download::download_text,
+        hex::encode_hex,
+        i18n::{Message, tr, tr_with},
+        playlist_json::JSON_MIME_TYPE,
         query_codec::{Scte35CommandType, Scte35Context},
+        scte35::{
+            SCTE35_TIMESCALE, SectionCheck, SpliceCommand, SpliceInfoSection, SpliceTime,
+            decode_message, segmentation_type_name, splice_info_section,
+        },
+        scte35_xml::{XML_MIME_TYPE, scte35_xml},
+        splice_point::{SpliceClock, SplicePoint, SpliceTiming, splice_point, splice_timing},
+        timeline_drift::DRIFT_TOLERANCE_MILLIS,
     },
 };
 use leptos::{either::Either, prelude::*};
 use scte35::parse_splice_info_section;
 use serde_json::to_string_pretty;
 use std::{error::Error, fmt::Display, io};
+use wasm_bindgen::JsValue;
+use web_sys::js_sys::Date;
 
 const SCTE35_TABLE: &str = "scte35-info-table";
+const SCTE35_EXPORT_CLASS: &str = "scte35-export";
 
 #[component]
-pub fn Scte35Viewer(context: Scte35Context) -> impl IntoView {
+pub fn Scte35Viewer(context: Scte35Context, playlist: String) -> impl IntoView {
     let Scte35Context {
         message,
         daterange_id,
         command_type,
     } = context;
-    match decode_message(&message) {
+    let (encoding, bytes) = match decode_message(&message) {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            return Either::Right(view! {
+                <div class=SUPPLEMENTAL_VIEW_CLASS>
+                    <ViewerError
+                        error=String::from(tr(Message::Scte35ReadError))
+                        extra_info=Some(format!("{e}"))
+                    />
+                </div>
+            });
+        }
+    };
+    // The message is read here as far as it goes, so that the checks show where a message that
+    // cannot be parsed in full went wrong.
+    let section = splice_info_section(&bytes);
+    // The splice point is placed again as segments are inspected, as each gives another media
+    // timeline to place the pts_time against.
+    let splice = section.as_ref().and_then(splice_timing).map(|timing| {
+        let history = use_context::<DriftHistory>();
+        let daterange_id = daterange_id.clone();
+        move || {
+            let samples = history.map(|history| history.samples()).unwrap_or_default();
+            splice_point(&playlist, &daterange_id, timing, &samples)
+                .map(|point| view! { <SplicePointTable point /> })
+        }
+    });
+    let file_stem = format!("scte35-{daterange_id}");
+    let xml = section.as_ref().map(scte35_xml);
+    let decoded = match decode_json(&bytes) {
         Ok(json) => Either::Left(view! {
-            <div class=SUPPLEMENTAL_VIEW_CLASS>
-                <table class=SCTE35_TABLE>
-                    <tr>
-                        <td class=LINE_BREAK_WORD>"ID"</td>
-                        <td>{daterange_id}</td>
-                    </tr>
-                    <tr>
-                        <td class=LINE_BREAK_WORD>"Type"</td>
-                        <td>
-                            {match command_type {
-                                Scte35CommandType::Out => "SCTE35-OUT",
-                                Scte35CommandType::In => "SCTE35-IN",
-                                Scte35CommandType::Cmd => "SCTE35-CMD",
-                            }}
-                        </td>
-                    </tr>
-                    <tr>
-                        <td class=LINE_BREAK_WORD>"Message"</td>
-                        <td class=LINE_BREAK_ANYWHERE>
-                            <code>{message}</code>
-                        </td>
-                    </tr>
-                </table>
-                <p class=UNDERLINED>"Decoded"</p>
-                <pre>{json}</pre>
-            </div>
+            <pre>{json.clone()}</pre>
+            <Scte35Export file_stem json xml />
         }),
         Err(e) => {
-            let (error, extra_info) = match e {
-                DecodeMessageError::Hex(e) => (
-                    String::from("Error reading hex string"),
-                    Some(format!("{e}")),
-                ),
-                DecodeMessageError::Scte35(e) => (
-                    String::from("Error parsing SCTE35 data"),
-                    Some(format!("{e}")),
-                ),
-                DecodeMessageError::Json(e) => (
-                    String::from("Error converting to JSON"),
-                    Some(format!("{e}")),
-                ),
+            let error = match e {
+                DecodeMessageError::Scte35(_) => tr(Message::Scte35ParseError),
+                DecodeMessageError::Json(_) => tr(Message::Scte35JsonError),
             };
-            Either::Right(view! {
-                <div class=SUPPLEMENTAL_VIEW_CLASS>
-                    <ViewerError error extra_info />
-                </div>
-            })
+            let extra_info = Some(format!("{e}"));
+            Either::Right(view! { <ViewerError error=String::from(error) extra_info /> })
         }
+    };
+    Either::Left(view! {
+        <div class=SUPPLEMENTAL_VIEW_CLASS>
+            <table class=SCTE35_TABLE>
+                <tr>
+                    <td class=LINE_BREAK_WORD>{tr(Message::Scte35Id)}</td>
+                    <td>{daterange_id}</td>
+                </tr>
+                <tr>
+                    <td class=LINE_BREAK_WORD>{tr(Message::Scte35Type)}</td>
+                    <td>
+                        {match command_type {
+                            Scte35CommandType::Out => "SCTE35-OUT",
+                            Scte35CommandType::In => "SCTE35-IN",
+                            Scte35CommandType::Cmd => "SCTE35-CMD",
+                        }}
+                    </td>
+                </tr>
+                <tr>
+                    <td class=LINE_BREAK_WORD>{tr(Message::Scte35Message)}</td>
+                    <td class=LINE_BREAK_ANYWHERE>
+                        <code>{message}</code>
+                    </td>
+                </tr>
+                <tr>
+                    <td class=LINE_BREAK_WORD>{tr(Message::Scte35Encoding)}</td>
+                    <td>{encoding.to_string()}</td>
+                </tr>
+            </table>
+            {splice}
+            {section
+                .map(|section| {
+                    view! {
+                        <Scte35Checks checks=section.checks(&bytes) />
+                        <p class=UNDERLINED>{tr(Message::Scte35Fields)}</p>
+                        <table class=SCTE35_TABLE>
+                            {section_fields(&section)
+                                .into_iter()
+                                .map(|(name, value)| {
+                                    view! {
+                                        <tr>
+                                            <td class=LINE_BREAK_WORD>{name}</td>
+                                            <td class=LINE_BREAK_ANYWHERE>{value}</td>
+                                        </tr>
+                                    }
+                                })
+                                .collect_view()}
+                        </table>
+                    }
+                })}
+            <p class=UNDERLINED>{tr(Message::Scte35Decoded)}</p>
+            {decoded}
+        </div>
+    })
+}
+
+/// Saves the decoded message as JSON, and as the XML representation of the standard when the message
+/// could be read.
+#[component]
+fn Scte35Export(file_stem: String, json: String, xml: Option<String>) -> impl IntoView {
+    let json_name = format!("{file_stem}.json");
+    let xml_button = xml.map(|xml| {
+        let xml_name = format!("{file_stem}.xml");
+        view! {
+            <button
+                class="button"
+                type="button"
+                title=tr(Message::ExportXmlTitle)
+                on:click=move |_| download_text(&xml_name, XML_MIME_TYPE, &xml)
+            >
+                {tr(Message::ExportXml)}
+            </button>
+        }
+    });
+    view! {
+        <div class=SCTE35_EXPORT_CLASS>
+            <button
+                class="button"
+                type="button"
+                title=tr(Message::ExportScte35JsonTitle)
+                on:click=move |_| download_text(&json_name, JSON_MIME_TYPE, &json)
+            >
+                {tr(Message::ExportJson)}
+            </button>
+            {xml_button}
+        </div>
     }
 }
 
-fn decode_message(message: &str) -> Result<String, DecodeMessageError> {
-    let message = if message.starts_with("0x") || message.starts_with("0X") {
-        &message[2..]
-    } else {
-        message
+#[component]
+fn Scte35Checks(checks: Vec<SectionCheck>) -> impl IntoView {
+    if checks.iter().all(|check| check.passed) {
+        return Either::Left(view! {
+            <p class=VALIDATION_PASSED_CLASS>{tr(Message::Scte35ChecksPassed)}</p>
+        });
+    }
+    let failed = checks.iter().filter(|check| !check.passed).count();
+    Either::Right(view! {
+        <details class=VALIDATION_REPORT_CLASS open>
+            <summary>
+                {tr_with(Message::Scte35ChecksFailed, &[("count", &failed.to_string())])}
+            </summary>
+            <table>
+                {checks
+                    .into_iter()
+                    .map(|SectionCheck { field, passed, message }| {
+                        view! {
+                            <tr>
+                                <td class=if passed { "" } else { VALIDATION_ERROR_CLASS }>
+                                    {if passed {
+                                        tr(Message::CheckPassed)
+                                    } else {
+                                        tr(Message::CheckFailed)
+                                    }}
+                                </td>
+                                <td>{field}</td>
+                                <td>{message}</td>
+                            </tr>
+                        }
+                    })
+                    .collect_view()}
+            </table>
+        </details>
+    })
+}
+
+#[component]
+fn SplicePointTable(point: SplicePoint) -> impl IntoView {
+    let splice_time = match point.timing {
+        SpliceTiming::Immediate => String::from("splice_immediate"),
+        SpliceTiming::Pts(pts) => {
+            tr_with(Message::SpliceTimeWithAdjustment, &[("ticks", &ticks(pts))])
+        }
     };
-    let hex = decode_hex(message)?;
-    let splice_info_section = parse_splice_info_section(&hex)?;
+    let (wall_clock_from, note) = match (point.timing, point.clock) {
+        (_, SpliceClock::MediaTime { media_sequence }) => {
+            let media_sequence = media_sequence.to_string();
+            (
+                tr_with(
+                    Message::SpliceClockMediaTimeline,
+                    &[("media_sequence", &media_sequence)],
+                ),
+                None,
+            )
+        }
+        (SpliceTiming::Immediate, SpliceClock::StartDate) => (
+            String::from("START-DATE"),
+            Some(tr(Message::SpliceImmediateNote)),
+        ),
+        (SpliceTiming::Pts(_), SpliceClock::StartDate) => {
+            (String::from("START-DATE"), Some(tr(Message::SplicePtsNote)))
+        }
+    };
+    let start_date_offset = matches!(point.clock, SpliceClock::MediaTime { .. }).then(|| {
+        let offset = point.start_date_offset();
+        let class = if offset.abs() > DRIFT_TOLERANCE_MILLIS {
+            VALIDATION_WARNING_CLASS
+        } else {
+            ""
+        };
+        view! {
+            <tr>
+                <td class=LINE_BREAK_WORD>{tr(Message::FromStartDate)}</td>
+                <td class=class>{format!("{:+.3}s", offset / 1000.0)}</td>
+            </tr>
+        }
+    });
+    let segment = match point.segment {
+        Some(segment) => Either::Left(view! {
+            <a href=format!("#{}", line_anchor_id(segment.line))>
+                {segment.media_sequence}
+            </a>
+            {tr_with(
+                Message::SpliceSegmentOffset,
+                &[
+                    ("offset", &format!("{:.3}", segment.offset)),
+                    ("duration", &format!("{:.3}", segment.duration)),
+                ],
+            )}
+        }),
+        None => Either::Right(tr(Message::OutsideOfPlaylist)),
+    };
+    view! {
+        <p class=UNDERLINED>{tr(Message::SplicePoint)}</p>
+        <table class=SCTE35_TABLE>
+            <tr>
+                <td class=LINE_BREAK_WORD>{tr(Message::SpliceTime)}</td>
+                <td>{splice_time}</td>
+            </tr>
+            <tr>
+                <td class=LINE_BREAK_WORD>{tr(Message::WallClockTime)}</td>
+                <td>
+                    {tr_with(
+                        Message::WallClockTimeFrom,
+                        &[("date_time", &date_time(point.wall_clock)), ("clock", &wall_clock_from)],
+                    )}
+                </td>
+            </tr>
+            {start_date_offset}
+            <tr>
+                <td class=LINE_BREAK_WORD>{tr(Message::SpliceSegment)}</td>
+                <td>{segment}</td>
+            </tr>
+        </table>
+        {note.map(|note| view! { <p>{note}</p> })}
+    }
+}
+
+fn date_time(millis: f64) -> String {
+    String::from(Date::new(&JsValue::from_f64(millis)).to_iso_string())
+}
+
+fn decode_json(bytes: &[u8]) -> Result<String, DecodeMessageError> {
+    let splice_info_section = parse_splice_info_section(bytes)?;
     let pretty_json = to_string_pretty(&splice_info_section)?;
     Ok(pretty_json)
 }
 
+/// Every field of the splice_info_section, named as in the standard, in the order they appear.
+fn section_fields(section: &SpliceInfoSection) -> Vec<(&'static str, String)> {
+    let mut fields = Vec::new();
+    let mut field = |name: &'static str, value: String| fields.push((name, value));
+    field("table_id", format!("0x{:02X}", section.table_id));
+    field(
+        "section_syntax_indicator",
+        section.section_syntax_indicator.to_string(),
+    );
+    field("private_indicator", section.private_indicator.to_string());
+    field("sap_type", sap_type(section.sap_type).to_string());
+    field("section_length", section.section_length.to_string());
+    field("protocol_version", section.protocol_version.to_string());
+    field("encrypted_packet", section.encrypted_packet.to_string());
+    if section.encrypted_packet {
+        field(
+            "encryption_algorithm",
+            section.encryption_algorithm.to_string(),
+        );
+    }
+    field("pts_adjustment", ticks(section.pts_adjustment));
+    if section.encrypted_packet {
+        field("cw_index", section.cw_index.to_string());
+    }
+    field("tier", format!("0x{:03X}", section.tier));
+    field(
+        "splice_command_length",
+        section.splice_command_length.to_string(),
+    );
+    field(
+        "splice_command_type",
+        format!(
+            "0x{:02X} ({})",
+            section.splice_command_type,
+            SpliceCommand::name(section.splice_command_type)
+        ),
+    );
+    match &section.command {
+        SpliceCommand::Null | SpliceCommand::BandwidthReservation => (),
+        SpliceCommand::Schedule(bytes) | SpliceCommand::Unreadable(bytes) => {
+            field("command bytes", encode_hex(bytes));
+        }
+        SpliceCommand::Private {
+            identifier,
+            private_bytes,
+        } => {
+            field("identifier", identifier_string(*identifier));
+            field("private_byte", encode_hex(private_bytes));
+        }
+        SpliceCommand::TimeSignal(time) => field("splice_time", splice_time(time)),
+        SpliceCommand::Insert(insert) => {
+            field("splice_event_id", event_id(insert.splice_event_id));
+            field(
+                "splice_event_cancel_indicator",
+                insert.splice_event_cancel.to_string(),
+            );
+            if !insert.splice_event_cancel {
+                field(
+                    "out_of_network_indicator",
+                    insert.out_of_network.to_string(),
+                );
+                field(
+                    "program_splice_flag",
+                    tr_with(
+                        Message::SpliceMode,
+                        &[
+                            ("flag", &insert.program_splice.to_string()),
+                            (
+                                "mode",
+                                if insert.program_splice {
+                                    tr(Message::SpliceModeProgram)
+                                } else {
+                                    tr(Message::SpliceModeComponent)
+                                },
+                            ),
+                        ],
+                    ),
+                );
+                field("duration_flag", insert.break_duration.is_some().to_string());
+                field("splice_immediate_flag", insert.splice_immediate.to_string());
+                field(
+                    "event_id_compliance_flag",
+                    insert.event_id_compliance.to_string(),
+                );
+                if let Some(time) = &insert.splice_time {
+                    field("splice_time", splice_time(time));
+                }
+                if !insert.program_splice {
+                    field("component_count", insert.components.len().to_string());
+                }
+                for (tag, time) in &insert.components {
+                    field(
+                        "component_tag",
+                        match time {
+                            Some(time) => format!("{tag}, splice_time {}", splice_time(time)),
+                            None => tag.to_string(),
+                        },
+                    );
+                }
+                if let Some(duration) = &insert.break_duration {
+                    field("auto_return", duration.auto_return.to_string());
+                    field("duration", ticks(duration.duration));
+                }
+                field("unique_program_id", insert.unique_program_id.to_string());
+                field("avail_num", insert.avail_num.to_string());
+                field("avails_expected", insert.avails_expected.to_string());
+            }
+        }
+    }
+    if let Some(length) = section.descriptor_loop_length {
+        field("descriptor_loop_length", length.to_string());
+    }
+    for descriptor in &section.descriptors {
+        field(
+            "splice_descriptor",
+            format!(
+                "tag 0x{:02X}, length {}, identifier {}, data {}",
+                descriptor.splice_descriptor_tag,
+                descriptor.descriptor_length,
+                identifier_string(descriptor.identifier),
+                encode_hex(&descriptor.data)
+            ),
+        );
+        let Some(segmentation) = descriptor.segmentation() else {
+            continue;
+        };
+        field(
+            "segmentation_event_id",
+            event_id(segmentation.segmentation_event_id),
+        );
+        field(
+            "segmentation_event_cancel_indicator",
+            segmentation.segmentation_event_cancel.to_string(),
+        );
+        field(
+            "segmentation_event_id_compliance_indicator",
+            segmentation.event_id_compliance.to_string(),
+        );
+        if segmentation.segmentation_event_cancel {
+            continue;
+        }
+        field(
+            "program_segmentation_flag",
+            segmentation.program_segmentation.to_string(),
+        );
+        field(
+            "delivery_not_restricted_flag",
+            segmentation.delivery_restrictions.is_none().to_string(),
+        );
+        if let Some(restrictions) = &segmentation.delivery_restrictions {
+            field(
+                "web_delivery_allowed_flag",
+                restrictions.web_delivery_allowed.to_string(),
+            );
+            field(
+                "no_regional_blackout_flag",
+                restrictions.no_regional_blackout.to_string(),
+            );
+            field(
+                "archive_allowed_flag",
+                restrictions.archive_allowed.to_string(),
+            );
+            field(
+                "device_restrictions",
+                format!(
+                    "{} ({})",
+                    restrictions.device_restrictions,
+                    restrictions.device_restrictions_name()
+                ),
+            );
+        }
+        for (tag, pts_offset) in &segmentation.components {
+            field(
+                "component_tag",
+                format!("{tag}, pts_offset {}", ticks(*pts_offset)),
+            );
+        }
+        if let Some(duration) = segmentation.segmentation_duration {
+            field("segmentation_duration", ticks(duration));
+        }
+        field(
+            "segmentation_upid_type",
+            format!(
+                "0x{:02X} ({})",
+                segmentation.upid.upid_type,
+                segmentation.upid.type_name()
+            ),
+        );
+        field(
+            "segmentation_upid_length",
+            segmentation.upid.upid.len().to_string(),
+        );
+        field("segmentation_upid", segmentation.upid.describe());
+        field(
+            "segmentation_type_id",
+            format!(
+                "0x{:02X} ({})",
+                segmentation.segmentation_type_id,
+                segmentation_type_name(segmentation.segmentation_type_id)
+            ),
+        );
+        field("segment_num", segmentation.segment_num.to_string());
+        field(
+            "segments_expected",
+            segmentation.segments_expected.to_string(),
+        );
+        if let Some((sub_segment_num, sub_segments_expected)) = segmentation.sub_segments {
+            field("sub_segment_num", sub_segment_num.to_string());
+            field("sub_segments_expected", sub_segments_expected.to_string());
+        }
+    }
+    if let Some(crc) = section.crc_32 {
+        field("CRC_32", format!("0x{crc:08X}"));
+    }
+    fields
+}
+
+fn sap_type(sap_type: u8) -> &'static str {
+    match sap_type {
+        0 => "0 (Type 1)",
+        1 => "1 (Type 2)",
+        2 => "2 (Type 3)",
+        _ => "3 (not specified)",
+    }
+}
+
+fn ticks(ticks: u64) -> String {
+    format!("{ticks} ({:.3}s)", ticks as f64 / SCTE35_TIMESCALE)
+}
+
+fn splice_time(time: &SpliceTime) -> String {
+    match time.pts_time {
+        Some(pts_time) => ticks(pts_time),
+        None => String::from(tr(Message::NotSpecified)),
+    }
+}
+
+fn event_id(id: u32) -> String {
+    format!("{id} (0x{id:08X})")
+}
+
+// Identifiers are registered as four characters (e.g. CUEI), so are shown as such where possible.
+fn identifier_string(identifier: u32) -> String {
+    let bytes = identifier.to_be_bytes();
+    if bytes.iter().all(u8::is_ascii_graphic) {
+        format!("0x{identifier:08X} ({})", String::from_utf8_lossy(&bytes))
+    } else {
+        format!("0x{identifier:08X}")
+    }
+}
+
 #[derive(Debug)]
 enum DecodeMessageError {
-    Hex(DecodeHexError),
     Scte35(io::Error),
     Json(serde_json::Error),
 }
 impl Display for DecodeMessageError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            DecodeMessageError::Hex(e) => e.fmt(f),
             DecodeMessageError::Scte35(e) => e.fmt(f),
             DecodeMessageError::Json(e) => e.fmt(f),
         }
     }
 }
 impl Error for DecodeMessageError {}
-impl From<DecodeHexError> for DecodeMessageError {
-    fn from(value: DecodeHexError) -> Self {
-        Self::Hex(value)
-    }
-}
 impl From<io::Error> for DecodeMessageError {
     fn from(value: io::Error) -> Self {
         Self::Scte35(value)
@@ -0,0 +1,81 @@
+use super::{PLAYLIST_LINE_CLASS, SEGMENT_CONTEXT_CLASS, playlist::line_number_from_anchor_id};
+use crate::utils::{
+    i18n::{Message, tr},
+    playlist_lines::{SegmentContext, segment_contexts},
+};
+use leptos::{ev, html::Div, prelude::*};
+
+/// A header pinned to the top of the window while a media playlist is scrolled, which shows the
+/// EXT-X-MAP, EXT-X-KEY and EXT-X-PROGRAM-DATE-TIME that apply to the first segment in view.
+#[component]
+pub fn SegmentContextHeader(playlist: String) -> Option<impl IntoView> {
+    let contexts = segment_contexts(&playlist);
+    if contexts.is_empty() {
+        return None;
+    }
+    let contexts = StoredValue::new(contexts);
+    // The marker sits at the top of the playlist, so that lines are only looked for while it has
+    // been scrolled past.
+    let marker = NodeRef::<Div>::new();
+    let header = NodeRef::<Div>::new();
+    let current = RwSignal::new(None::<SegmentContext>);
+    let update = move || {
+        let Some(marker) = marker.get_untracked() else {
+            return;
+        };
+        let header_height = header
+            .get_untracked()
+            .map(|header| header.offset_height())
+            .unwrap_or_default();
+        let top = marker.get_bounding_client_rect();
+        let context = (top.top() < 0.0)
+            .then(|| {
+                document().element_from_point(top.left() as f32 + 1.0, header_height as f32 + 1.0)
+            })
+            .flatten()
+            .and_then(|element| element.closest(&format!(".{PLAYLIST_LINE_CLASS}")).ok())
+            .flatten()
+            .and_then(|line| line_number_from_anchor_id(&line.id()))
+            .and_then(|line_number| {
+                contexts.with_value(|contexts| {
+                    // The tags of a segment precede its URI, so the first segment in view is the
+                    // first whose URI is at or below the line in view.
+                    let index = contexts.partition_point(|(uri_line, _)| *uri_line < line_number);
+                    contexts.get(index).map(|(_, context)| context.clone())
+                })
+            });
+        if current.with_untracked(|current| *current != context) {
+            current.set(context);
+        }
+    };
+    let handle = window_event_listener(ev::scroll, move |_| update());
+    on_cleanup(move || handle.remove());
+    let tag_line = |label: Message, line: Option<String>| {
+        view! {
+            <p>
+                <span class="segment-context-label">{tr(label)}</span>
+                {line.unwrap_or_else(|| String::from(tr(Message::SegmentContextNone)))}
+            </p>
+        }
+    };
+    Some(view! {
+        <div node_ref=marker />
+        <div
+            node_ref=header
+            class=SEGMENT_CONTEXT_CLASS
+            class:hidden=move || current.with(Option::is_none)
+        >
+            {move || {
+                current
+                    .get()
+                    .map(|context| {
+                        view! {
+                            {tag_line(Message::SegmentContextMap, context.map)}
+                            {tag_line(Message::SegmentContextKey, context.key)}
+                            {tag_line(Message::SegmentContextDate, context.program_date_time)}
+                        }
+                    })
+            }}
+        </div>
+    })
+}
@@ -0,0 +1,93 @@
+use super::{
+    DOWNLOAD_PROGRESS_CLASS, ErrorBounded, SUPPLEMENTAL_VIEW_CLASS, isobmff::IsobmffViewer,
+};
+use crate::utils::{
+    box_structure::readable_length,
+    i18n::{Message, tr, tr_with},
+    network::{DownloadProgress, SharedBytes},
+};
+use leptos::prelude::*;
+
+/// What has arrived of a segment while it downloads, so that its boxes can be shown as soon as
+/// they are whole (the styp, prft and moof usually arrive well before the bulk of the mdat).
+#[derive(Clone, Copy, Default)]
+pub struct SegmentDownload {
+    progress: RwSignal<Option<DownloadProgress>>,
+    /// The boxes that have arrived whole, which only changes as each top-level box completes.
+    readable: RwSignal<Option<SharedBytes>>,
+}
+
+impl SegmentDownload {
+    pub fn reset(&self) {
+        self.progress.set(None);
+        self.readable.set(None);
+    }
+
+    pub fn update(&self, body: &[u8], progress: DownloadProgress) {
+        self.progress.set(Some(progress));
+        let length = readable_length(body);
+        let readable_so_far = self.readable.with_untracked(|readable| {
+            readable
+                .as_ref()
+                .map(|readable| readable.len())
+                .unwrap_or_default()
+        });
+        if length > readable_so_far {
+            self.readable
+                .set(Some(SharedBytes::from(body[..length].to_vec())));
+        }
+    }
+}
+
+/// Stands in for a segment until it has downloaded, showing its boxes so far.
+#[component]
+pub fn SegmentDownloadView(download: SegmentDownload) -> impl IntoView {
+    let progress = download.progress;
+    move || match download.readable.get() {
+        Some(data) => view! {
+            <ErrorBounded>
+                <IsobmffViewer data truncated=true progress=Some(progress.into()) />
+            </ErrorBounded>
+        }
+        .into_any(),
+        None => view! {
+            <div class=SUPPLEMENTAL_VIEW_CLASS>
+                {tr(Message::Loading)} <DownloadProgressBar progress=progress.into() />
+            </div>
+        }
+        .into_any(),
+    }
+}
+
+#[component]
+pub fn DownloadProgressBar(progress: Signal<Option<DownloadProgress>>) -> impl IntoView {
+    move || {
+        progress.get().map(|DownloadProgress { received, total }| {
+            // A compressed response can be longer once decoded than its Content-Length says, and
+            // without a total the progress bar is left indeterminate.
+            let total = total.filter(|total| *total >= received);
+            let text = match total {
+                Some(total) => tr_with(
+                    Message::BytesOfTotal,
+                    &[
+                        ("received", &received.to_string()),
+                        ("total", &total.to_string()),
+                    ],
+                ),
+                None => tr_with(
+                    Message::BytesReceived,
+                    &[("received", &received.to_string())],
+                ),
+            };
+            view! {
+                <p class=DOWNLOAD_PROGRESS_CLASS>
+                    <progress
+                        max=total.map(|total| total as f64)
+                        value=total.map(|_| received as f64)
+                    ></progress>
+                    {text}
+                </p>
+            }
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use super::SEGMENT_NAVIGATION_CLASS;
+use crate::utils::i18n::{Message, tr};
+use leptos::{ev, prelude::*};
+use leptos_router::{
+    NavigateOptions,
+    hooks::{use_location, use_navigate},
+};
+use web_sys::Element;
+
+/// Steps from the segment open in the supplemental view to the one before or after it in the
+/// playlist, with the buttons or with the left and right arrow keys.
+#[component]
+pub fn SegmentNavigation(
+    media_sequence: u64,
+    /// The href of each Media Segment in the playlist, by Media Sequence Number.
+    segment_hrefs: Vec<(u64, String)>,
+) -> Option<impl IntoView> {
+    let position = segment_hrefs
+        .iter()
+        .position(|(segment, _)| *segment == media_sequence)?;
+    let href_at = |position: Option<usize>| {
+        position
+            .and_then(|position| segment_hrefs.get(position))
+            .map(|(_, href)| href.clone())
+    };
+    let previous = href_at(position.checked_sub(1));
+    let next = href_at(Some(position + 1));
+    let navigate = use_navigate();
+    let pathname = use_location().pathname;
+    let handle = window_event_listener(ev::keydown, {
+        let (previous, next) = (previous.clone(), next.clone());
+        move |ev| {
+            if ev.alt_key() || ev.ctrl_key() || ev.meta_key() || ev.shift_key() {
+                return;
+            }
+            // The arrow keys keep moving the caret while typing into a field.
+            let target = event_target::<Element>(&ev).tag_name();
+            if matches!(target.as_str(), "INPUT" | "TEXTAREA" | "SELECT") {
+                return;
+            }
+            let href = match ev.key().as_str() {
+                "ArrowLeft" => previous.as_deref(),
+                "ArrowRight" => next.as_deref(),
+                _ => None,
+            };
+            if let Some(href) = href {
+                ev.prevent_default();
+                let path = format!("{}{href}", pathname.get_untracked());
+                navigate(&path, NavigateOptions::default());
+            }
+        }
+    });
+    on_cleanup(move || handle.remove());
+    Some(view! {
+        <div class=SEGMENT_NAVIGATION_CLASS>
+            {previous
+                .map(|href| {
+                    view! {
+                        <a class="button" href=href title=tr(Message::PreviousSegmentTitle)>
+                            {tr(Message::PreviousSegment)}
+                        </a>
+                    }
+                })}
+            {next
+                .map(|href| {
+                    view! {
+                        <a class="button" href=href title=tr(Message::NextSegmentTitle)>
+                            {tr(Message::NextSegment)}
+                        </a>
+                    }
+                })}
+        </div>
+    })
+}
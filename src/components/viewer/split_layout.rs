@@ -0,0 +1,105 @@
+use super::{SPLIT_DIVIDER_CLASS, SPLIT_HANDLE_CLASS};
+use crate::utils::{
+    i18n::{Message, tr},
+    storage::{load_flag, load_item, save_flag, save_item},
+};
+use leptos::{ev, html::Div, prelude::*};
+
+// The split between the playlist and the supplemental view is remembered across sessions, as the
+// size that suits depends mostly on the screen it is viewed on.
+const SUPPLEMENTAL_WIDTH_KEY: &str = "split_supplemental_width";
+const STACKED_KEY: &str = "split_stacked";
+const DEFAULT_SUPPLEMENTAL_WIDTH: f64 = 40.0;
+const MIN_SUPPLEMENTAL_WIDTH: f64 = 15.0;
+const MAX_SUPPLEMENTAL_WIDTH: f64 = 85.0;
+
+/// How the playlist and the supplemental view are laid out.
+#[derive(Clone, Copy)]
+pub struct SplitLayout {
+    /// The percentage of the width given to the supplemental view when side by side.
+    pub supplemental_width: RwSignal<f64>,
+    /// Whether the supplemental view is stacked below the playlist.
+    pub stacked: RwSignal<bool>,
+}
+
+impl SplitLayout {
+    pub fn load() -> Self {
+        let supplemental_width = load_item(SUPPLEMENTAL_WIDTH_KEY)
+            .and_then(|width| width.parse::<f64>().ok())
+            .map(|width| width.clamp(MIN_SUPPLEMENTAL_WIDTH, MAX_SUPPLEMENTAL_WIDTH))
+            .unwrap_or(DEFAULT_SUPPLEMENTAL_WIDTH);
+        Self {
+            supplemental_width: RwSignal::new(supplemental_width),
+            stacked: RwSignal::new(load_flag(STACKED_KEY)),
+        }
+    }
+
+    fn save(&self) {
+        save_item(
+            SUPPLEMENTAL_WIDTH_KEY,
+            &self.supplemental_width.get_untracked().to_string(),
+        );
+        save_flag(STACKED_KEY, self.stacked.get_untracked());
+    }
+}
+
+/// The divider between the playlist and the supplemental view, which is dragged to resize them and
+/// has a toggle to stack them instead. It is only shown while there is a supplemental view.
+#[component]
+pub fn SplitDivider(layout: SplitLayout, container: NodeRef<Div>) -> impl IntoView {
+    let dragging = RwSignal::new(false);
+    let on_move = window_event_listener(ev::pointermove, move |ev| {
+        if !dragging.get_untracked() {
+            return;
+        }
+        let Some(container) = container.get_untracked() else {
+            return;
+        };
+        let rect = container.get_bounding_client_rect();
+        if rect.width() > 0.0 {
+            let width = (rect.right() - f64::from(ev.client_x())) / rect.width() * 100.0;
+            layout
+                .supplemental_width
+                .set(width.clamp(MIN_SUPPLEMENTAL_WIDTH, MAX_SUPPLEMENTAL_WIDTH));
+        }
+    });
+    let on_up = window_event_listener(ev::pointerup, move |_| {
+        if dragging.get_untracked() {
+            dragging.set(false);
+            layout.save();
+        }
+    });
+    on_cleanup(move || {
+        on_move.remove();
+        on_up.remove();
+    });
+    view! {
+        <div class=SPLIT_DIVIDER_CLASS class:dragging=move || dragging.get()>
+            <button
+                class="split-toggle"
+                type="button"
+                title=move || {
+                    if layout.stacked.get() {
+                        tr(Message::SplitBesideTitle)
+                    } else {
+                        tr(Message::SplitStackTitle)
+                    }
+                }
+                on:click=move |_| {
+                    layout.stacked.update(|stacked| *stacked = !*stacked);
+                    layout.save();
+                }
+            >
+                {move || if layout.stacked.get() { "⇆" } else { "⇅" }}
+            </button>
+            <div
+                class=SPLIT_HANDLE_CLASS
+                title=tr(Message::SplitResizeTitle)
+                on:pointerdown=move |ev| {
+                    ev.prevent_default();
+                    dragging.set(true);
+                }
+            />
+        </div>
+    }
+}
@@ -0,0 +1,32 @@
+use super::TAG_LEGEND_CLASS;
+use crate::utils::{
+    hls_spec::{TagCategory, tag_category},
+    playlist_lines::{LineKind, parse},
+};
+use leptos::prelude::*;
+
+/// The colours that the names of tags are given by their category, for those categories that
+/// appear in the playlist.
+#[component]
+pub fn TagLegend(playlist: String) -> Option<impl IntoView> {
+    let present = parse(&playlist)
+        .into_iter()
+        .filter_map(|line| match line.kind {
+            LineKind::Tag(tag) => tag_category(tag.name),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let entries = TagCategory::ALL
+        .into_iter()
+        .filter(|category| present.contains(category))
+        .map(|category| {
+            view! {
+                <span class=format!("tag-legend-entry {}", category.class())>
+                    <span class="tag-legend-swatch" />
+                    {category.label()}
+                </span>
+            }
+        })
+        .collect::<Vec<_>>();
+    (!entries.is_empty()).then(|| view! { <div class=TAG_LEGEND_CLASS>{entries}</div> })
+}
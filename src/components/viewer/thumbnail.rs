@@ -0,0 +1,200 @@
+use super::{
+    IFRAME_THUMBNAILS_CLASS, THUMBNAIL_CLASS, VALIDATION_WARNING_CLASS,
+    isobmff::init_segment_codecs,
+};
+use crate::{
+    components::AppSettings,
+    utils::{
+        bandwidth::{SegmentSample, sample_segments},
+        byterange::RequestRange,
+        href::resolve_playlist_relative_url,
+        i18n::{Message, tr, tr_with},
+        keyframe::{VideoSampleDescription, first_keyframe, video_sample_description},
+        network::fetch_array_buffer,
+        playlist_lines::variable_definitions,
+        settings::Settings,
+        thumbnail::decode_thumbnail,
+    },
+};
+use leptos::{either::Either, prelude::*};
+use std::collections::HashMap;
+
+/// The number of I-frames that are sampled (evenly across the playlist) for thumbnails.
+const IFRAME_THUMBNAIL_COUNT: usize = 12;
+
+/// The first keyframe of a video segment, decoded with WebCodecs.
+#[component]
+pub fn Thumbnail(
+    /// The codec string of the sample entry that the keyframe is decoded with.
+    codec: String,
+    sample_description: VideoSampleDescription,
+    keyframe: Vec<u8>,
+) -> impl IntoView {
+    let thumbnail = LocalResource::new(move || {
+        let (codec, sample_description, keyframe) =
+            (codec.clone(), sample_description.clone(), keyframe.clone());
+        async move { decode_thumbnail(&codec, &sample_description, &keyframe).await }
+    });
+    view! {
+        <div class=THUMBNAIL_CLASS>
+            <Suspense fallback=|| view! { <p>{tr(Message::DecodingFirstKeyframe)}</p> }>
+                {move || {
+                    thumbnail
+                        .get()
+                        .map(|result| match result {
+                            Ok(src) => {
+                                Either::Left(
+                                    view! { <img src=src alt=tr(Message::FirstKeyframe) /> },
+                                )
+                            }
+                            Err(e) => {
+                                Either::Right(view! { <p class=VALIDATION_WARNING_CLASS>{e}</p> })
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+/// Thumbnails of a sample of the I-frames of an I-frame playlist, each decoded from its byte range
+/// of the media along with the initialization segment (EXT-X-MAP) that applies to it.
+#[component]
+pub fn IFrameThumbnails(
+    playlist: String,
+    imported_definitions: HashMap<String, String>,
+) -> impl IntoView {
+    if !playlist.contains("#EXT-X-I-FRAMES-ONLY") {
+        return Either::Left(());
+    }
+    let (requested, set_requested) = signal(false);
+    let definitions = variable_definitions(&playlist, &imported_definitions);
+    let settings = AppSettings::current();
+    let thumbnails = LocalResource::new(move || {
+        // As with the bandwidth check, URLs are resolved before the future is suspended, while the
+        // router context is still available.
+        let requests = requested.get().then(|| {
+            sample_segments(&playlist, IFRAME_THUMBNAIL_COUNT)
+                .into_iter()
+                .map(|sample| iframe_request(sample, &definitions))
+                .collect::<Vec<_>>()
+        });
+        let settings = settings.get_untracked();
+        async move {
+            let mut thumbnails = Vec::new();
+            let mut init = None::<(IFrameResource, Result<Vec<u8>, String>)>;
+            for request in requests? {
+                let thumbnail = match request {
+                    Ok(request) => {
+                        // The initialization segment is usually shared by every I-frame, so it is
+                        // only fetched again when it changes.
+                        let init_data = match &init {
+                            Some((map, data)) if *map == request.map => data.clone(),
+                            _ => {
+                                let data = fetch(&request.map, &settings).await;
+                                init = Some((request.map, data.clone()));
+                                data
+                            }
+                        };
+                        iframe_thumbnail(&request.iframe, init_data, &settings).await
+                    }
+                    Err(e) => Err(e),
+                };
+                thumbnails.push(thumbnail);
+            }
+            Some(thumbnails)
+        }
+    });
+    Either::Right(view! {
+        <div class=IFRAME_THUMBNAILS_CLASS>
+            <button class="button" on:click=move |_| set_requested.set(true)>
+                {tr_with(
+                    Message::ShowIFrameThumbnails,
+                    &[("count", &IFRAME_THUMBNAIL_COUNT.to_string())],
+                )}
+            </button>
+            <Suspense fallback=|| view! { <p>{tr(Message::DecodingIFrames)}</p> }>
+                {move || {
+                    thumbnails
+                        .get()
+                        .flatten()
+                        .map(|thumbnails| {
+                            thumbnails
+                                .into_iter()
+                                .map(|thumbnail| match thumbnail {
+                                    Ok(src) => {
+                                        Either::Left(
+                                            view! { <img src=src alt=tr(Message::IFrame) /> },
+                                        )
+                                    }
+                                    Err(e) => {
+                                        Either::Right(
+                                            view! { <p class=VALIDATION_WARNING_CLASS>{e}</p> },
+                                        )
+                                    }
+                                })
+                                .collect_view()
+                        })
+                }}
+            </Suspense>
+        </div>
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct IFrameResource {
+    url: String,
+    byterange: Option<RequestRange>,
+}
+
+struct IFrameRequest {
+    iframe: IFrameResource,
+    map: IFrameResource,
+}
+
+fn iframe_request(
+    sample: SegmentSample,
+    definitions: &HashMap<String, String>,
+) -> Result<IFrameRequest, String> {
+    let resolve = |uri: &str, byterange: Option<(u64, u64)>| {
+        Some(IFrameResource {
+            url: resolve_playlist_relative_url(uri, definitions)?,
+            byterange: byterange
+                .map(|(length, offset)| RequestRange::from_length_with_offset(length, offset)),
+        })
+    };
+    let (map_uri, map_byterange) = sample
+        .map
+        .ok_or_else(|| tr_with(Message::IFrameNotFmp4, &[("uri", &sample.uri)]))?;
+    Ok(IFrameRequest {
+        iframe: resolve(&sample.uri, sample.byterange)
+            .ok_or_else(|| tr_with(Message::UnresolvedSegmentUri, &[("uri", &sample.uri)]))?,
+        map: resolve(&map_uri, map_byterange)
+            .ok_or_else(|| tr_with(Message::UnresolvedMapUri, &[("uri", &map_uri)]))?,
+    })
+}
+
+async fn fetch(resource: &IFrameResource, settings: &Settings) -> Result<Vec<u8>, String> {
+    fetch_array_buffer(resource.url.clone(), resource.byterange, settings)
+        .await
+        .map(|response| response.response_body)
+        .map_err(|e| e.to_string())
+}
+
+async fn iframe_thumbnail(
+    iframe: &IFrameResource,
+    init_data: Result<Vec<u8>, String>,
+    settings: &Settings,
+) -> Result<String, String> {
+    let init_data = init_data?;
+    let sample_description = video_sample_description(&init_data)
+        .ok_or_else(|| String::from(tr(Message::NoVideoSampleDescription)))?;
+    let data = fetch(iframe, settings).await?;
+    let keyframe = first_keyframe(&data)
+        .ok_or_else(|| tr_with(Message::NoKeyframe, &[("url", &iframe.url)]))?;
+    let codec = init_segment_codecs(init_data)
+        .ok()
+        .and_then(|codecs| sample_description.codec_string(&codecs))
+        .ok_or_else(|| String::from(tr(Message::UnknownInitializationCodec)))?;
+    decode_thumbnail(&codec, &sample_description, keyframe.data).await
+}
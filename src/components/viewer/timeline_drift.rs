@@ -0,0 +1,126 @@
+use super::{TIMELINE_DRIFT_CLASS, VALIDATION_WARNING_CLASS};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    timeline_drift::{DRIFT_TOLERANCE_MILLIS, DriftSample},
+};
+use leptos::{either::Either, prelude::*};
+
+/// The segments of the current playlist that have been inspected so far, so that drift can be
+/// tracked across them. The viewer is re-created each time a segment is opened, so the history is
+/// provided as context by the page instead.
+#[derive(Clone, Copy, Default)]
+pub struct DriftHistory(RwSignal<Vec<DriftSample>>);
+
+impl DriftHistory {
+    pub fn clear(&self) {
+        self.0.set(Vec::new());
+    }
+
+    /// The segments inspected so far, tracking them when called within a reactive scope.
+    pub fn samples(&self) -> Vec<DriftSample> {
+        self.0.get()
+    }
+
+    fn record(&self, sample: DriftSample) {
+        let media_sequence = sample.program_date_time.media_sequence;
+        self.0.update(|samples| {
+            samples.retain(|s| s.program_date_time.media_sequence != media_sequence);
+            samples.push(sample);
+            samples.sort_by_key(|s| s.program_date_time.media_sequence);
+        });
+    }
+}
+
+#[component]
+pub fn TimelineDrift(sample: DriftSample) -> impl IntoView {
+    let history = use_context::<DriftHistory>();
+    if let Some(history) = history {
+        history.record(sample);
+    }
+    let absolute_drift = sample.absolute_drift_millis().map(|drift| {
+        view! {
+            <p class=drift_class(drift)>
+                {tr_with(Message::AbsoluteDrift, &[("drift", &format!("{drift:+.0}"))])}
+            </p>
+        }
+    });
+    let samples = move || {
+        history
+            .map(|history| history.samples())
+            .unwrap_or_else(|| vec![sample])
+    };
+    view! {
+        <div class=TIMELINE_DRIFT_CLASS>
+            <p>{tr(Message::DriftHeading)}</p>
+            {absolute_drift}
+            {move || {
+                let samples = samples();
+                match samples.first() {
+                    Some(first) if samples.len() > 1 => {
+                        Either::Left(view! { <DriftTable first=*first samples /> })
+                    }
+                    _ => {
+                        Either::Right(
+                            view! {
+                                <p>{tr(Message::DriftOpenFurtherSegments)}</p>
+                            },
+                        )
+                    }
+                }
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn DriftTable(first: DriftSample, samples: Vec<DriftSample>) -> impl IntoView {
+    view! {
+        <table>
+            <tr>
+                <th>{tr(Message::DriftSegment)}</th>
+                <th>{tr(Message::DriftPlaylistElapsed)}</th>
+                <th>{tr(Message::DriftMediaElapsed)}</th>
+                <th>{tr(Message::DriftMillis)}</th>
+            </tr>
+            {samples
+                .into_iter()
+                .map(|sample| {
+                    let playlist_elapsed = sample.program_date_time.millis
+                        - first.program_date_time.millis;
+                    let media_elapsed = sample.media_time.decode_time_millis
+                        - first.media_time.decode_time_millis;
+                    let drift = sample.drift_since_millis(&first);
+                    let class = drift.map(drift_class).unwrap_or_default();
+                    // Without its own tag, the date-time of the segment is only as accurate as the
+                    // EXTINF durations that it was calculated from.
+                    let (segment, title) = if sample.program_date_time.extrapolated {
+                        (
+                            format!("{}*", sample.program_date_time.media_sequence),
+                            tr(Message::DriftExtrapolatedTitle),
+                        )
+                    } else {
+                        (sample.program_date_time.media_sequence.to_string(), "")
+                    };
+                    view! {
+                        <tr>
+                            <td title=title>{segment}</td>
+                            <td>{format!("{:.3}", playlist_elapsed / 1000.0)}</td>
+                            <td>{format!("{:.3}", media_elapsed / 1000.0)}</td>
+                            <td class=class>
+                                {drift.map(|d| format!("{d:+.0}")).unwrap_or_default()}
+                            </td>
+                        </tr>
+                    }
+                })
+                .collect_view()}
+        </table>
+    }
+}
+
+fn drift_class(drift: f64) -> &'static str {
+    if drift.abs() > DRIFT_TOLERANCE_MILLIS {
+        VALIDATION_WARNING_CLASS
+    } else {
+        ""
+    }
+}
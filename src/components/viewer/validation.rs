@@ -0,0 +1,188 @@
+use super::{
+    BADGE_CAUTION_CLASS, BADGE_WARNING_CLASS, VALIDATION_ERROR_CLASS, VALIDATION_EXPORT_CLASS,
+    VALIDATION_PASSED_CLASS, VALIDATION_REPORT_CLASS, VALIDATION_WARNING_CLASS,
+    automation::bring_into_view,
+    playlist::line_anchor_id,
+    report::{file_stem, title_and_source},
+};
+use crate::{
+    components::LocalPlaylist,
+    utils::{
+        csv::CSV_MIME_TYPE,
+        download::download_text,
+        i18n::{Message, tr, tr_with},
+        playlist_json::JSON_MIME_TYPE,
+        validation::{Finding, Severity, findings_csv, findings_json},
+    },
+};
+use leptos::{
+    either::Either,
+    html::{Details, P},
+    prelude::*,
+};
+use leptos_router::hooks::use_query_map;
+
+#[component]
+pub fn ValidationReport(
+    findings: Vec<Finding>,
+    #[prop(optional)] title: Option<&'static str>,
+    /// Whether the link asked for the report, which opens it and brings it into view.
+    #[prop(optional)]
+    requested: bool,
+) -> impl IntoView {
+    let title = title.unwrap_or_else(|| tr(Message::ValidationTitle));
+    if findings.is_empty() {
+        let passed = NodeRef::<P>::new();
+        if requested {
+            bring_into_view(passed);
+        }
+        return Either::Left(view! {
+            <p node_ref=passed class=VALIDATION_PASSED_CLASS>
+                {tr_with(Message::ValidationPassed, &[("title", title)])}
+            </p>
+        });
+    }
+    let report = NodeRef::<Details>::new();
+    if requested {
+        bring_into_view(report);
+    }
+    let errors = count(&findings, Severity::Error);
+    let warnings = count(&findings, Severity::Warning);
+    let summary = tr_with(
+        Message::ValidationSummary,
+        &[
+            ("title", title),
+            (
+                "errors",
+                &plural(errors, Message::OneError, Message::Errors),
+            ),
+            (
+                "warnings",
+                &plural(warnings, Message::OneWarning, Message::Warnings),
+            ),
+        ],
+    );
+    let (json, csv) = (findings_json(&findings), findings_csv(&findings));
+    Either::Right(view! {
+        <details node_ref=report class=VALIDATION_REPORT_CLASS open={errors > 0 || requested}>
+            <summary>{summary}</summary>
+            <table>
+                {findings.into_iter().map(finding_row).collect_view()}
+            </table>
+            <FindingsExport title json csv />
+        </details>
+    })
+}
+
+// The findings are named after the playlist and the report that they are from, so that the live
+// update findings are not saved over those of the playlist.
+#[component]
+fn FindingsExport(title: &'static str, json: String, csv: String) -> impl IntoView {
+    let local_playlist = use_context::<LocalPlaylist>();
+    let query = use_query_map();
+    let file_name = move |extension: &str| {
+        let (playlist_title, _) = title_and_source(local_playlist, query);
+        let report = title.to_lowercase().replace(' ', "-");
+        format!("{}-{report}.{extension}", file_stem(&playlist_title))
+    };
+    view! {
+        <div class=VALIDATION_EXPORT_CLASS>
+            <button
+                class="button"
+                type="button"
+                title=tr(Message::ExportFindingsJsonTitle)
+                on:click=move |_| download_text(&file_name("json"), JSON_MIME_TYPE, &json)
+            >
+                {tr(Message::ExportJson)}
+            </button>
+            <button
+                class="button"
+                type="button"
+                title=tr(Message::ExportFindingsCsvTitle)
+                on:click=move |_| download_text(&file_name("csv"), CSV_MIME_TYPE, &csv)
+            >
+                {tr(Message::ExportCsv)}
+            </button>
+        </div>
+    }
+}
+
+fn finding_row(finding: Finding) -> impl IntoView {
+    let Finding {
+        severity,
+        line,
+        rule,
+        message,
+    } = finding;
+    let severity_class = match severity {
+        Severity::Error => VALIDATION_ERROR_CLASS,
+        Severity::Warning => VALIDATION_WARNING_CLASS,
+    };
+    view! {
+        <tr>
+            <td class=severity_class>{severity.to_string()}</td>
+            <td>
+                {match line {
+                    Some(line) => {
+                        Either::Left(
+                            view! {
+                                <a href=format!("#{}", line_anchor_id(line))>
+                                    {tr_with(Message::FindingLine, &[("line", &line.to_string())])}
+                                </a>
+                            },
+                        )
+                    }
+                    None => Either::Right(tr(Message::FindingPlaylist)),
+                }}
+            </td>
+            <td>{message}</td>
+            <td title=tr(Message::FindingRule)>{rule}</td>
+        </tr>
+    }
+}
+
+/// A badge summarising the findings for a single line, with the details of each finding shown on
+/// hover.
+#[component]
+pub fn LineDiagnostics(findings: Vec<Finding>) -> impl IntoView {
+    let errors = count(&findings, Severity::Error);
+    let warnings = count(&findings, Severity::Warning);
+    let (class, label) = if errors > 0 {
+        (
+            BADGE_WARNING_CLASS,
+            plural(errors, Message::OneError, Message::Errors),
+        )
+    } else {
+        (
+            BADGE_CAUTION_CLASS,
+            plural(warnings, Message::OneWarning, Message::Warnings),
+        )
+    };
+    let title = findings
+        .iter()
+        .map(|finding| {
+            format!(
+                "{} ({}): {}",
+                finding.severity, finding.rule, finding.message
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    view! {
+        <span class=class title=title>
+            {label}
+        </span>
+    }
+}
+
+fn plural(count: usize, one: Message, many: Message) -> String {
+    if count == 1 {
+        tr(one).to_string()
+    } else {
+        tr_with(many, &[("count", &count.to_string())])
+    }
+}
+
+fn count(findings: &[Finding], severity: Severity) -> usize {
+    findings.iter().filter(|f| f.severity == severity).count()
+}
@@ -0,0 +1,89 @@
+use super::{
+    BADGE_CLASS, VARIANT_SUMMARY_CLASS,
+    automation::{Automation, bring_into_view},
+    playlist::line_anchor_id,
+};
+use crate::utils::{
+    fetch_timing::format_throughput,
+    i18n::{Message, tr, tr_with},
+    query_codec::PlaylistView,
+    variant_summary::{VariantSummary, variant_summaries},
+};
+use leptos::{html::Details, prelude::*};
+
+/// The Variant Streams of a Multivariant Playlist side by side, each linking to the line of its
+/// EXT-X-STREAM-INF tag. The table is opened when the link asks for the summary view.
+#[component]
+pub fn VariantSummaryTable(playlist: String) -> Option<impl IntoView> {
+    let variants = variant_summaries(&playlist);
+    if variants.is_empty() {
+        return None;
+    }
+    let requested = use_context::<Automation>()
+        .is_some_and(|automation| automation.view() == Some(PlaylistView::Summary));
+    let details = NodeRef::<Details>::new();
+    if requested {
+        bring_into_view(details);
+    }
+    let summary = tr_with(
+        Message::VariantsSummary,
+        &[("count", &variants.len().to_string())],
+    );
+    Some(view! {
+        <details node_ref=details class=VARIANT_SUMMARY_CLASS open=requested>
+            <summary>{summary}</summary>
+            <table>
+                <tr>
+                    <th>{tr(Message::VariantLine)}</th>
+                    <th>"BANDWIDTH"</th>
+                    <th>"AVERAGE-BANDWIDTH"</th>
+                    <th>"RESOLUTION"</th>
+                    <th>"FRAME-RATE"</th>
+                    <th>"CODECS"</th>
+                    <th>"AUDIO"</th>
+                    <th>"SUBTITLES"</th>
+                    <th>"CLOSED-CAPTIONS"</th>
+                    <th>"URI"</th>
+                </tr>
+                {variants.into_iter().map(variant_row).collect_view()}
+            </table>
+        </details>
+    })
+}
+
+fn variant_row(variant: VariantSummary) -> impl IntoView {
+    let bitrate = |bandwidth: Option<u64>| {
+        bandwidth.map_or_else(dash, |bandwidth| format_throughput(bandwidth as f64))
+    };
+    view! {
+        <tr>
+            <td>
+                <a href=format!("#{}", line_anchor_id(variant.line))>{variant.line}</a>
+            </td>
+            <td>{bitrate(variant.bandwidth)}</td>
+            <td>{bitrate(variant.average_bandwidth)}</td>
+            <td>{variant.resolution.map_or_else(dash, |resolution| resolution.to_string())}</td>
+            <td>{variant.frame_rate.map_or_else(dash, |frame_rate| frame_rate.to_string())}</td>
+            <td>{variant.codecs.unwrap_or_else(dash)}</td>
+            <td>
+                {variant.audio.unwrap_or_else(dash)}
+                {variant
+                    .audio_delivery
+                    .map(|delivery| {
+                        view! {
+                            <span class=BADGE_CLASS title=delivery.description()>
+                                {delivery.label()}
+                            </span>
+                        }
+                    })}
+            </td>
+            <td>{variant.subtitles.unwrap_or_else(dash)}</td>
+            <td>{variant.closed_captions.unwrap_or_else(dash)}</td>
+            <td>{variant.uri}</td>
+        </tr>
+    }
+}
+
+fn dash() -> String {
+    String::from("-")
+}
@@ -0,0 +1,109 @@
+use super::{
+    SUPPLEMENTAL_VIEW_CLASS, VALIDATION_ERROR_CLASS, VALIDATION_PASSED_CLASS,
+    VALIDATION_REPORT_CLASS, VALIDATION_WARNING_CLASS,
+};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    validation::Severity,
+    webvtt::{Cue, SubtitleSegment, WebVttFinding, validate_webvtt},
+};
+use leptos::{either::Either, prelude::*};
+
+/// The cues of the subtitle segments of the current playlist that have been opened so far, so that
+/// each segment can be compared with the one before it.
+#[derive(Clone, Copy, Default)]
+pub struct SubtitleHistory(RwSignal<Vec<(u64, Vec<Cue>)>>);
+
+impl SubtitleHistory {
+    pub fn clear(&self) {
+        self.0.set(Vec::new());
+    }
+
+    fn cues(&self, media_sequence: u64) -> Vec<Cue> {
+        self.0.with_untracked(|segments| {
+            segments
+                .iter()
+                .find(|(sequence, _)| *sequence == media_sequence)
+                .map(|(_, cues)| cues.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    fn record(&self, media_sequence: u64, cues: Vec<Cue>) {
+        self.0.update(|segments| {
+            segments.retain(|(sequence, _)| *sequence != media_sequence);
+            segments.push((media_sequence, cues));
+        });
+    }
+}
+
+#[component]
+pub fn WebVttViewer(
+    contents: String,
+    #[prop(optional_no_strip)] segment: Option<SubtitleSegment>,
+) -> impl IntoView {
+    let history = use_context::<SubtitleHistory>();
+    let previous_segment = match (history, segment) {
+        (Some(history), Some(segment)) if segment.media_sequence > 0 => {
+            history.cues(segment.media_sequence - 1)
+        }
+        _ => Vec::new(),
+    };
+    let report = validate_webvtt(
+        &contents,
+        segment.and_then(|segment| segment.window),
+        &previous_segment,
+    );
+    if let (Some(history), Some(segment)) = (history, segment) {
+        history.record(segment.media_sequence, report.cues);
+    }
+    view! {
+        <div class=SUPPLEMENTAL_VIEW_CLASS>
+            <WebVttReport findings=report.findings />
+            <pre>{contents}</pre>
+        </div>
+    }
+}
+
+#[component]
+fn WebVttReport(findings: Vec<WebVttFinding>) -> impl IntoView {
+    if findings.is_empty() {
+        return Either::Left(view! {
+            <p class=VALIDATION_PASSED_CLASS>{tr(Message::WebVttPassed)}</p>
+        });
+    }
+    Either::Right(view! {
+        <details class=VALIDATION_REPORT_CLASS open>
+            <summary>
+                {tr_with(Message::WebVttSummary, &[("count", &findings.len().to_string())])}
+            </summary>
+            <table>
+                {findings
+                    .into_iter()
+                    .map(|WebVttFinding { severity, line, message }| {
+                        let class = match severity {
+                            Severity::Error => VALIDATION_ERROR_CLASS,
+                            Severity::Warning => VALIDATION_WARNING_CLASS,
+                        };
+                        view! {
+                            <tr>
+                                <td class=class>{severity.to_string()}</td>
+                                <td>
+                                    {line
+                                        .map(|line| {
+                                            tr_with(
+                                                Message::FindingLine,
+                                                &[("line", &line.to_string())],
+                                            )
+                                        })
+                                        .unwrap_or_default()}
+                                </td>
+                                <td>{message}</td>
+                            </tr>
+                        }
+                    })
+                    .collect_view()}
+            </table>
+        </details>
+    })
+}
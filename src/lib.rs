@@ -1,57 +1,26 @@
-use leptos::prelude::*;
-use leptos_meta::*;
-use leptos_router::{components::*, path};
+// The library is the viewer (behind the `web` feature, which is on by default) and the parsing that
+// it is built on. Without the `web` feature only the parsing is built, which has no need of Leptos
+// or wasm-bindgen, so that CLI tools and services can inspect playlists, boxes and SCTE-35 messages
+// the same way that the viewer does.
 
 // Modules
+#[cfg(feature = "web")]
+mod app;
+#[cfg(feature = "web")]
 mod components;
+#[cfg(feature = "web")]
 mod pages;
+// Without the viewer, whatever of the parsing is there for the viewer alone goes unused.
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
 mod utils;
-// Pages
-use crate::pages::{about::About, examples::Examples, home::Home, not_found::NotFound};
 
-/// An app router which renders the homepage and handles 404's
-#[component]
-pub fn App() -> impl IntoView {
-    provide_meta_context();
-
-    view! {
-        <Html attr:lang="en" attr:dir="ltr" />
-        <Meta charset="UTF-8" />
-        <Meta name="viewport" content="width=device-width, initial-scale=1.0" />
-        <Title text="HLS Manifest Viewer" />
-        <Link rel="icon" href="/hls-manifest-viewer/quick-m3u8-logo.ico" attr::type="image/ico" />
-        <Link
-            rel="mask-icon"
-            href="/hls-manifest-viewer/quick-m3u8-logo.ico"
-            attr::color="#000000"
-        />
-
-        <Router>
-            <nav>
-                <a class="button" href="/hls-manifest-viewer">
-                    "Home"
-                </a>
-                <a class="button" href="/hls-manifest-viewer/examples">
-                    "Examples"
-                </a>
-                <a class="button" href="/hls-manifest-viewer/about">
-                    "About"
-                </a>
-                <a
-                    class="button"
-                    href="https://github.com/theRealRobG/hls-manifest-viewer"
-                    target="_blank"
-                >
-                    <img src="/hls-manifest-viewer/github-mark-white.svg" />
-                </a>
-            </nav>
-            <main>
-                <Routes fallback=NotFound>
-                    <Route path=path!("/hls-manifest-viewer") view=Home />
-                    <Route path=path!("/hls-manifest-viewer/examples") view=Examples />
-                    <Route path=path!("/hls-manifest-viewer/about") view=About />
-                </Routes>
-            </main>
-        </Router>
-    }
-}
+#[cfg(feature = "web")]
+pub use app::App;
+// The parsing
+pub use utils::{
+    byterange, mp4_atom_properties, mp4_parsing, playlist_lines, pssh_data, query_codec,
+    resolution, scte35, validation,
+};
+// The boxes are read with the same mp4-atom as `mp4_atom_properties` is built on, which is a git
+// dependency that other projects could otherwise not match.
+pub use mp4_atom;
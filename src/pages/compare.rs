@@ -0,0 +1,70 @@
+use crate::{
+    components::{AppSettings, PlaylistDiff, ViewerLoading},
+    utils::{
+        i18n::{Message, tr},
+        network::fetch_text,
+    },
+};
+use leptos::prelude::*;
+use leptos_router::{components::Form, hooks::use_query_map};
+
+const LEFT_URL_QUERY_NAME: &str = "left_url";
+const RIGHT_URL_QUERY_NAME: &str = "right_url";
+
+#[component]
+pub fn Compare() -> impl IntoView {
+    let query = use_query_map();
+    let query_url = move |query_name: &'static str| {
+        Memo::new(move |_| query.read().get(query_name).filter(|url| !url.is_empty()))
+    };
+    let left_url = query_url(LEFT_URL_QUERY_NAME);
+    let right_url = query_url(RIGHT_URL_QUERY_NAME);
+    let settings = AppSettings::current();
+    let fetch = move |url: Memo<Option<String>>| {
+        LocalResource::new(move || {
+            let url = url.get();
+            let settings = settings.get_untracked();
+            async move {
+                match url {
+                    Some(url) => Some(fetch_text(url, &settings).await),
+                    None => None,
+                }
+            }
+        })
+    };
+    let left = fetch(left_url);
+    let right = fetch(right_url);
+    view! {
+        <h1 class="body-content">{tr(Message::CompareHeading)}</h1>
+        <p class="body-content body-text">{tr(Message::CompareIntro)}</p>
+        <Form attr:class="url-input-form" method="GET" action="">
+            <div class="url-input-form-inner-container compare-form">
+                <input
+                    class="url-input"
+                    type="url"
+                    name=LEFT_URL_QUERY_NAME
+                    value=move || left_url.get().unwrap_or_default()
+                    placeholder="https://cdn-a.example.com/media.m3u8"
+                    pattern="https?://.*"
+                    aria-label=tr(Message::LeftPlaylistUrlLabel)
+                />
+                <input
+                    class="url-input"
+                    type="url"
+                    name=RIGHT_URL_QUERY_NAME
+                    value=move || right_url.get().unwrap_or_default()
+                    placeholder="https://cdn-b.example.com/media.m3u8"
+                    pattern="https?://.*"
+                    aria-label=tr(Message::RightPlaylistUrlLabel)
+                />
+                <input class="button" type="submit" value=tr(Message::CompareSubmit) />
+            </div>
+        </Form>
+        <Suspense fallback=ViewerLoading>
+            {move || {
+                let (left, right) = (left.get()??, right.get()??);
+                Some(view! { <PlaylistDiff left right /> })
+            }}
+        </Suspense>
+    }
+}
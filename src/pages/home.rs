@@ -1,27 +1,90 @@
 use crate::{
-    components::{UrlInputForm, Viewer, ViewerLoading},
+    components::{
+        AppSettings, Automation, BoxTreeExpansion, Breadcrumbs, DriftHistory, EmbedBridge,
+        LastSegment, LiveSnapshot, LocalFileInput, LocalPlaylist, LocalSegment, LocalSegmentViewer,
+        OfflineCacheSettings, PlaylistCache, ProxySettings, RequestSettings, SessionExport,
+        SubtitleHistory, UrlInputForm, Viewer, ViewerLoading,
+    },
     utils::{
         href::{
-            DEFINITIONS_QUERY_NAME, PLAYLIST_URL_QUERY_NAME, SUPPLEMENTAL_VIEW_QUERY_NAME,
+            COMPRESSED_QUERY_QUERY_NAME, DEFINITIONS_QUERY_NAME, PINNED_VIEW_QUERY_NAME,
+            PLAYLIST_URL_QUERY_NAME, SEGMENT_QUERY_NAME, SUPPLEMENTAL_VIEW_QUERY_NAME,
+            VALIDATE_QUERY_NAME, VARIANT_QUERY_NAME, VIEW_QUERY_NAME, normalized_playlist_url,
             query_value_from_leptos_url,
         },
         network::fetch_text,
-        query_codec::{decode_definitions, percent_decode},
+        query_codec::{VariantContext, decode_definitions, decompress_query, percent_decode},
     },
 };
 use leptos::prelude::*;
-use leptos_router::hooks::use_url;
+use leptos_router::{
+    NavigateOptions,
+    hooks::{use_navigate, use_url},
+};
 
 #[component]
 pub fn Home() -> impl IntoView {
-    let playlist_url = query_string_signal(PLAYLIST_URL_QUERY_NAME, true);
+    expand_compressed_query();
+    let playlist_url = playlist_url_signal();
     let supplemental_context = query_string_signal(SUPPLEMENTAL_VIEW_QUERY_NAME, true);
+    let pinned_context = query_string_signal(PINNED_VIEW_QUERY_NAME, true);
     // definitions are decoded separately so we do not decode the raw query value.
     let imported_definitions = query_string_signal(DEFINITIONS_QUERY_NAME, false);
+    let variant_attributes = query_string_signal(VARIANT_QUERY_NAME, true);
+    provide_context(Automation::new(
+        query_string_signal(VALIDATE_QUERY_NAME, true),
+        query_string_signal(VIEW_QUERY_NAME, true),
+        query_string_signal(SEGMENT_QUERY_NAME, true),
+    ));
+    // Rechecking a live playlist reloads it on condition that it changed, and compares it against
+    // the one shown before.
+    let live_snapshot = LiveSnapshot::new(playlist_url);
+    provide_context(live_snapshot);
+    let local_playlist = LocalPlaylist::default();
+    provide_context(local_playlist);
+    let local_segment = LocalSegment::default();
+    provide_context(local_segment);
+    let settings = AppSettings::current();
     let playlist_result = LocalResource::new(move || {
         let playlist_url = playlist_url.get().unwrap_or_default();
-        fetch_text(playlist_url)
+        let local_file = local_playlist.get();
+        let settings = settings.get_untracked();
+        async move {
+            if let Some(local_file) = local_file {
+                return Ok(local_file.response());
+            }
+            fetch_text(playlist_url, &settings).await
+        }
     });
+    // Segments inspected for one playlist are not comparable with (or a fallback for) those of
+    // another.
+    let drift_history = DriftHistory::default();
+    provide_context(drift_history);
+    let subtitle_history = SubtitleHistory::default();
+    provide_context(subtitle_history);
+    let last_segment = LastSegment::default();
+    provide_context(last_segment);
+    let box_tree_expansion = BoxTreeExpansion::default();
+    provide_context(box_tree_expansion);
+    let playlist_cache = PlaylistCache::default();
+    provide_context(playlist_cache);
+    Effect::watch(
+        move || playlist_url.get(),
+        move |playlist_url, _, _| {
+            local_playlist.retain_for(playlist_url.as_deref());
+            // A segment opened on its own gives way to any playlist entered after it.
+            if playlist_url.is_some() {
+                local_segment.clear();
+            }
+            drift_history.clear();
+            subtitle_history.clear();
+            last_segment.clear();
+            box_tree_expansion.clear();
+            playlist_cache.clear();
+            live_snapshot.clear();
+        },
+        false,
+    );
     view! {
         <h1 class="body-content">"HLS Manifest Viewer"</h1>
         <p class="body-content body-text">
@@ -31,9 +94,33 @@ pub fn Home() -> impl IntoView {
             correctly against the MVP."#
         </p>
         <UrlInputForm />
+        <LocalFileInput />
+        <ProxySettings />
+        <RequestSettings />
+        <OfflineCacheSettings />
+        <SessionExport />
+        <EmbedBridge playlist_url playlist_result live_snapshot />
+        <Breadcrumbs />
+        {move || {
+            local_segment
+                .get()
+                .map(|file| {
+                    view! {
+                        <LocalSegmentViewer
+                            name=file.name
+                            data=file.data
+                            content_type=file.content_type
+                        />
+                    }
+                })
+        }}
         <Suspense fallback=ViewerLoading>
             {move || {
+                if local_segment.is_open() {
+                    return None;
+                }
                 let supplemental_context = move || supplemental_context.get();
+                let pinned_context = move || pinned_context.get();
                 let imported_definitions = move || {
                     imported_definitions
                         .get()
@@ -46,6 +133,9 @@ pub fn Home() -> impl IntoView {
                         })
                         .unwrap_or_default()
                 };
+                let variant = move || {
+                    variant_attributes.get().map(|attributes| VariantContext::decode(&attributes))
+                };
                 playlist_result
                     .get()
                     .map(|fetch_response| {
@@ -53,7 +143,9 @@ pub fn Home() -> impl IntoView {
                             <Viewer
                                 fetch_response
                                 supplemental_context=supplemental_context()
+                                pinned_context=pinned_context()
                                 imported_definitions=imported_definitions()
+                                variant=variant()
                             />
                         }
                     })
@@ -62,6 +154,44 @@ pub fn Home() -> impl IntoView {
     }
 }
 
+// A shared link may have its whole query compressed into the one value, which is expanded back out
+// in place (without adding to the history), keeping the line that the link points at.
+fn expand_compressed_query() {
+    let url = use_url();
+    let navigate = use_navigate();
+    Effect::new(move |_| {
+        // The URL is read in full before navigating, which updates it.
+        let (path, value) = url.with(|url| {
+            let value = query_value_from_leptos_url(url, COMPRESSED_QUERY_QUERY_NAME)
+                .map(|value| value.to_string());
+            (url.path().to_string(), value)
+        });
+        let Some(value) = value else {
+            return;
+        };
+        match decompress_query(&value) {
+            Ok(search) => {
+                let hash = window().location().hash().unwrap_or_default();
+                let options = NavigateOptions {
+                    replace: true,
+                    ..Default::default()
+                };
+                navigate(&format!("{path}?{search}{hash}"), options);
+            }
+            Err(e) => log::error!("query expansion for shared link failed due to {e}"),
+        }
+    });
+}
+
+// The links out of the viewer give the playlist URL as it was parsed, which can differ in form from
+// the URL that was entered, so it is compared in that form. Otherwise opening the first segment (a
+// change to the supplemental view alone) would fetch the playlist over again, and clear everything
+// kept for it.
+fn playlist_url_signal() -> Memo<Option<String>> {
+    let playlist_url = query_string_signal(PLAYLIST_URL_QUERY_NAME, true);
+    Memo::new(move |_| playlist_url.get().map(normalized_playlist_url))
+}
+
 // We define our own function to extract the query memoized query value, rather than using
 // leptos_router::hooks::query_signal, because the existing query_signal method has issues with
 // double decoding the URL. This method allows us more control over the percent decode.
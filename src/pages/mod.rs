@@ -1,4 +1,7 @@
 pub mod about;
+pub mod compare;
 pub mod examples;
 pub mod home;
 pub mod not_found;
+pub mod open;
+pub mod settings;
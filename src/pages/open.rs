@@ -0,0 +1,41 @@
+use crate::utils::{
+    href::PLAYLIST_URL_QUERY_NAME,
+    i18n::{Message, tr, tr_with},
+    launch::launch_target,
+    query_codec::percent_encode,
+};
+use leptos::prelude::*;
+use leptos_router::{
+    NavigateOptions,
+    hooks::{use_navigate, use_url},
+};
+
+/// Where a bookmarklet, the debug overlay of a player or a `web+hls:` link launches the viewer
+/// from. The playlist that the page is given is opened in its place.
+#[component]
+pub fn Open() -> impl IntoView {
+    let url = use_url().get_untracked();
+    let target = launch_target(url.path(), url.search(), url.hash());
+    if let Some(target) = &target {
+        let href = format!(
+            "/hls-manifest-viewer?{PLAYLIST_URL_QUERY_NAME}={}",
+            percent_encode(target)
+        );
+        let navigate = use_navigate();
+        Effect::new(move |_| {
+            let options = NavigateOptions {
+                replace: true,
+                ..Default::default()
+            };
+            navigate(&href, options);
+        });
+    }
+    let message = match target {
+        Some(target) => tr_with(Message::OpeningTarget, &[("target", &target)]),
+        None => String::from(tr(Message::NothingToOpen)),
+    };
+    view! {
+        <h1 class="body-content">{tr(Message::OpeningPlaylist)}</h1>
+        <p class="body-content body-text">{message}</p>
+    }
+}
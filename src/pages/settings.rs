@@ -0,0 +1,173 @@
+use crate::{
+    components::{AppSettings, OfflineCacheSettings, ProxySettings, RequestSettings},
+    utils::{
+        i18n::{Message, tr, tr_with},
+        launch::{OPEN_PATH, PROTOCOL_SCHEME, TARGET_QUERY_NAME, bookmarklet},
+        settings::{FeatureToggle, Theme},
+        storage::{load_flag, save_flag},
+    },
+};
+use leptos::prelude::*;
+
+#[component]
+pub fn Settings() -> impl IntoView {
+    let settings = AppSettings::current();
+    view! {
+        <h1 class="body-content">{tr(Message::SettingsTitle)}</h1>
+        <p class="body-content body-text">{tr(Message::SettingsIntro)}</p>
+        <div class="body-content settings">
+            <h2>{tr(Message::SettingsRequests)}</h2>
+            <ProxySettings open=true />
+            <RequestSettings open=true />
+            <label>
+                {tr(Message::SegmentCacheBefore)}
+                <input
+                    type="number"
+                    min="0"
+                    title=tr(Message::SegmentCacheTitle)
+                    prop:value=move || {
+                        settings.with(|settings| settings.segment_cache_megabytes.to_string())
+                    }
+                    on:change=move |ev| {
+                        let Ok(megabytes) = event_target_value(&ev).trim().parse::<u32>() else {
+                            return;
+                        };
+                        settings.update(|settings| settings.segment_cache_megabytes = megabytes);
+                    }
+                />
+                {tr(Message::SegmentCacheAfter)}
+            </label>
+            <OfflineCacheSettings open=true />
+            <h2>{tr(Message::SettingsAppearance)}</h2>
+            <label>
+                {tr(Message::SettingsTheme)}
+                <select on:change=move |ev| {
+                    if let Some(theme) = Theme::from_name(&event_target_value(&ev)) {
+                        settings.update(|settings| settings.theme = theme);
+                    }
+                }>
+                    {Theme::ALL
+                        .into_iter()
+                        .map(|theme| {
+                            view! {
+                                <option
+                                    value=theme.name()
+                                    selected=move || settings.with(|settings| settings.theme == theme)
+                                >
+                                    {theme_label(theme)}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </label>
+            <h2>{tr(Message::SettingsLivePlaylists)}</h2>
+            <label>
+                {tr(Message::LiveRecheckBefore)}
+                <input
+                    type="number"
+                    min="0"
+                    title=tr(Message::LiveRecheckTitle)
+                    prop:value=move || {
+                        settings.with(|settings| settings.live_recheck_seconds.to_string())
+                    }
+                    on:change=move |ev| {
+                        let Ok(seconds) = event_target_value(&ev).trim().parse::<u32>() else {
+                            return;
+                        };
+                        settings.update(|settings| settings.live_recheck_seconds = seconds);
+                    }
+                />
+                {tr(Message::LiveRecheckAfter)}
+            </label>
+            <h2>{tr(Message::SettingsViewer)}</h2>
+            {FeatureToggle::ALL
+                .into_iter()
+                .map(|toggle| {
+                    view! {
+                        <label>
+                            <input
+                                type="checkbox"
+                                prop:checked=load_flag(toggle.key())
+                                on:change=move |ev| {
+                                    save_flag(toggle.key(), event_target_checked(&ev))
+                                }
+                            />
+                            {toggle_label(toggle)}
+                        </label>
+                    }
+                })
+                .collect_view()}
+            <h2>{tr(Message::SettingsLaunching)}</h2>
+            <LaunchSettings />
+        </div>
+    }
+}
+
+// The viewer can be launched against the playlist being played elsewhere, from a bookmarklet or
+// from links of its own scheme, which the browser opens in the viewer once it is registered.
+#[component]
+fn LaunchSettings() -> impl IntoView {
+    let open_url = format!(
+        "{}{OPEN_PATH}",
+        window().location().origin().unwrap_or_default()
+    );
+    let registration = RwSignal::new(None::<String>);
+    let register = {
+        let handler_url = format!("{open_url}?{TARGET_QUERY_NAME}=%s");
+        move |_| {
+            let result = window().navigator().register_protocol_handler(
+                PROTOCOL_SCHEME,
+                &handler_url,
+                tr(Message::ProtocolHandlerTitle),
+            );
+            registration.set(Some(match result {
+                Ok(()) => tr_with(
+                    Message::ProtocolHandlerRegistered,
+                    &[("scheme", PROTOCOL_SCHEME)],
+                ),
+                Err(e) => {
+                    log::error!("registering the {PROTOCOL_SCHEME} handler failed due to {e:?}");
+                    String::from(tr(Message::ProtocolHandlerRefused))
+                }
+            }));
+        }
+    };
+    view! {
+        <div class="launch-settings">
+            <a
+                class="button"
+                href=bookmarklet(&open_url)
+                title=tr(Message::BookmarkletTitle)
+            >
+                {tr(Message::Bookmarklet)}
+            </a>
+            <button
+                class="button"
+                title=tr_with(Message::RegisterProtocolHandlerTitle, &[("scheme", PROTOCOL_SCHEME)])
+                on:click=register
+            >
+                {tr_with(Message::RegisterProtocolHandler, &[("scheme", PROTOCOL_SCHEME)])}
+            </button>
+            {move || registration.get().map(|message| view! { <span>{message}</span> })}
+        </div>
+    }
+}
+
+fn theme_label(theme: Theme) -> &'static str {
+    tr(match theme {
+        Theme::System => Message::ThemeSystem,
+        Theme::Light => Message::ThemeLight,
+        Theme::Dark => Message::ThemeDark,
+    })
+}
+
+fn toggle_label(toggle: FeatureToggle) -> &'static str {
+    tr(match toggle {
+        FeatureToggle::StructureOnly => Message::ToggleStructureOnly,
+        FeatureToggle::RawText => Message::ToggleRawText,
+        FeatureToggle::PlaylistNoWrap => Message::TogglePlaylistNoWrap,
+        FeatureToggle::DiffNoWrap => Message::ToggleDiffNoWrap,
+        FeatureToggle::CompressedLinks => Message::ToggleCompressedLinks,
+    })
+}
@@ -0,0 +1,198 @@
+// The ad avails of a playlist, being every break signalled with SCTE-35 or scheduled as an
+// interstitial. Servers that insert ads with interstitials often keep the SCTE35-OUT of the break
+// that they fill, either in the same daterange or in one of its own at the same START-DATE, so the
+// two are brought together as one avail.
+
+use crate::utils::{
+    ad_breaks::ad_breaks,
+    interstitials::interstitials,
+    playlist_lines::{LineKind, parse},
+    timeline_drift::parse_date_time,
+};
+use std::collections::HashMap;
+
+/// How close (in milliseconds) the START-DATE of an interstitial must be to that of a break for it
+/// to be taken to fill the break, to allow for dates written with less precision.
+const SAME_START_TOLERANCE_MILLIS: f64 = 100.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdAvail {
+    pub id: String,
+    /// The START-DATE, in milliseconds since the epoch.
+    pub start_date: Option<f64>,
+    /// The length of the avail in seconds, from the dates of the break, its PLANNED-DURATION, or
+    /// else from the interstitial that fills it.
+    pub duration: Option<f64>,
+    /// The 1-based number of the line with the SCTE35-OUT.
+    pub signalled_line: Option<usize>,
+    /// The 1-based number of the line with the interstitial that fills the avail.
+    pub interstitial_line: Option<usize>,
+    /// The 1-based number of the line with the SCTE35-IN.
+    pub returned_line: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdAvailTotals {
+    /// The total length of the avails whose length is known, in seconds.
+    pub duration: f64,
+    /// The average length of the avails whose length is known, in seconds.
+    pub average: Option<f64>,
+}
+
+impl AdAvail {
+    /// The first line of the playlist that the avail is found on.
+    pub fn line(&self) -> usize {
+        [
+            self.signalled_line,
+            self.interstitial_line,
+            self.returned_line,
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or_default()
+    }
+}
+
+pub fn ad_avails(playlist: &str) -> Vec<AdAvail> {
+    // The breaks only give the lines of their dateranges, so the dates are looked up by line.
+    let start_dates = parse(playlist)
+        .into_iter()
+        .filter_map(|line| match line.kind {
+            LineKind::Tag(tag) if tag.name == "EXT-X-DATERANGE" => Some((
+                line.number,
+                tag.attribute_str("START-DATE").and_then(parse_date_time)?,
+            )),
+            _ => None,
+        })
+        .collect::<HashMap<_, _>>();
+    let mut avails = ad_breaks(playlist)
+        .into_iter()
+        .map(|ad_break| AdAvail {
+            start_date: [ad_break.out_line, ad_break.in_line]
+                .into_iter()
+                .flatten()
+                .find_map(|line| start_dates.get(&line).copied()),
+            duration: ad_break.signalled_duration.or(ad_break.planned_duration),
+            signalled_line: ad_break.out_line,
+            interstitial_line: None,
+            returned_line: ad_break.in_line,
+            id: ad_break.id,
+        })
+        .collect::<Vec<_>>();
+    for interstitial in interstitials(playlist) {
+        // Pre-rolls and post-rolls play regardless of their START-DATE, so are only matched by ID.
+        let dated = !interstitial.cue.pre && !interstitial.cue.post;
+        let same_start = |avail: &AdAvail| {
+            dated
+                && avail.start_date.zip(interstitial.start_date).is_some_and(
+                    |(avail_start, interstitial_start)| {
+                        (avail_start - interstitial_start).abs() <= SAME_START_TOLERANCE_MILLIS
+                    },
+                )
+        };
+        let unfilled = |avail: &AdAvail| avail.interstitial_line.is_none();
+        let filled = avails
+            .iter()
+            .position(|avail| unfilled(avail) && avail.id == interstitial.id)
+            .or_else(|| {
+                avails
+                    .iter()
+                    .position(|avail| unfilled(avail) && same_start(avail))
+            });
+        match filled {
+            Some(index) => {
+                let avail = &mut avails[index];
+                avail.interstitial_line = Some(interstitial.line);
+                avail.duration = avail.duration.or_else(|| interstitial.break_duration());
+            }
+            None => avails.push(AdAvail {
+                start_date: interstitial.start_date,
+                duration: interstitial.break_duration(),
+                signalled_line: None,
+                interstitial_line: Some(interstitial.line),
+                returned_line: None,
+                id: interstitial.id,
+            }),
+        }
+    }
+    avails.sort_by_key(AdAvail::line);
+    avails
+}
+
+pub fn ad_avail_totals(avails: &[AdAvail]) -> AdAvailTotals {
+    let durations = avails
+        .iter()
+        .filter_map(|avail| avail.duration)
+        .collect::<Vec<_>>();
+    let duration = durations.iter().sum::<f64>();
+    AdAvailTotals {
+        duration,
+        average: (!durations.is_empty()).then(|| duration / durations.len() as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn breaks_and_interstitials_are_brought_together() {
+        let playlist = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00Z\n",
+            "#EXT-X-DATERANGE:ID=\"1\",START-DATE=\"2025-01-01T00:00:00Z\",",
+            "PLANNED-DURATION=30,SCTE35-OUT=0xFC\n",
+            "#EXT-X-DATERANGE:ID=\"ad-1\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2025-01-01T00:00:00.05Z\",DURATION=15,X-ASSET-URI=\"ad.m3u8\"\n",
+            "#EXTINF:6,\n",
+            "0.ts\n",
+            "#EXT-X-DATERANGE:ID=\"1\",START-DATE=\"2025-01-01T00:00:00Z\",DURATION=30,",
+            "SCTE35-IN=0xFC\n",
+            "#EXT-X-DATERANGE:ID=\"2\",START-DATE=\"2025-01-01T00:00:06Z\",SCTE35-OUT=0xFC\n",
+            "#EXT-X-DATERANGE:ID=\"pre\",CLASS=\"com.apple.hls.interstitial\",CUE=\"PRE\",",
+            "START-DATE=\"2025-01-01T00:00:00Z\",X-ASSET-URI=\"pre.m3u8\"\n",
+            "#EXTINF:6,\n",
+            "1.ts\n",
+        );
+        let start = 1_735_689_600_000.0;
+        let avails = ad_avails(playlist);
+        assert_eq!(
+            vec![
+                AdAvail {
+                    id: String::from("1"),
+                    start_date: Some(start),
+                    duration: Some(30.0),
+                    signalled_line: Some(3),
+                    interstitial_line: Some(4),
+                    returned_line: Some(7),
+                },
+                AdAvail {
+                    id: String::from("2"),
+                    start_date: Some(start + 6_000.0),
+                    duration: None,
+                    signalled_line: Some(8),
+                    interstitial_line: None,
+                    returned_line: None,
+                },
+                AdAvail {
+                    id: String::from("pre"),
+                    start_date: Some(start),
+                    duration: None,
+                    signalled_line: None,
+                    interstitial_line: Some(9),
+                    returned_line: None,
+                },
+            ],
+            avails
+        );
+        assert_eq!(
+            AdAvailTotals {
+                duration: 30.0,
+                average: Some(30.0),
+            },
+            ad_avail_totals(&avails)
+        );
+    }
+}
@@ -0,0 +1,338 @@
+// The ad breaks signalled by the SCTE35-OUT and SCTE35-IN attributes of EXT-X-DATERANGE tags. Each
+// SCTE35-OUT is paired with the SCTE35-IN that ends the break, which the specification places in
+// the same tag or in a later tag with the same ID. Some packagers give the SCTE35-IN a new ID
+// though, in which case it is paired by the event id in the SCTE-35 message instead.
+//
+// The signalled duration of a break comes from the dates of its dateranges, whereas the actual
+// duration is that of the segments that the break covers, found by placing those dates against the
+// segments using EXT-X-PROGRAM-DATE-TIME. Players can only leave and rejoin content at segment
+// boundaries, so it is the actual duration that viewers get.
+
+use crate::utils::{
+    playlist_lines::{LineKind, parse},
+    playlist_timeline::timeline,
+    scte35::{decode_attribute, splice_info_section},
+    timeline_drift::parse_date_time,
+};
+
+/// How far (in seconds) the actual duration of a break may be from its PLANNED-DURATION before
+/// they are said to disagree, as breaks rarely line up with segment boundaries to the frame.
+const PLANNED_DURATION_TOLERANCE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakPairing {
+    /// The SCTE35-IN is in a daterange with the same ID as the SCTE35-OUT.
+    SameId,
+    /// The SCTE35-IN has a different ID, but carries the same event id as the SCTE35-OUT.
+    EventId,
+    /// The break has no SCTE35-IN, or (in a live playlist that has moved on) no SCTE35-OUT.
+    Unpaired,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdBreak {
+    pub id: String,
+    /// The 1-based number of the line with the SCTE35-OUT.
+    pub out_line: Option<usize>,
+    /// The 1-based number of the line with the SCTE35-IN.
+    pub in_line: Option<usize>,
+    pub pairing: BreakPairing,
+    /// The event id of the SCTE35-OUT (or of the SCTE35-IN when there is no SCTE35-OUT).
+    pub event_id: Option<u32>,
+    pub planned_duration: Option<f64>,
+    /// The duration in seconds given by the dates of the dateranges.
+    pub signalled_duration: Option<f64>,
+    /// The duration in seconds of the segments between the boundaries closest to the start and end
+    /// of the break, when the whole break (up to its SCTE35-IN) is within the playlist.
+    pub actual_duration: Option<f64>,
+    /// The start of the break in seconds from the start of the playlist.
+    pub start: Option<f64>,
+    /// The IDs of the other breaks that this one overlaps.
+    pub overlaps: Vec<String>,
+}
+
+impl AdBreak {
+    /// How much longer (or shorter, when negative) the actual duration is than PLANNED-DURATION,
+    /// when they disagree.
+    pub fn planned_duration_mismatch(&self) -> Option<f64> {
+        let difference = self.actual_duration? - self.planned_duration?;
+        (difference.abs() > PLANNED_DURATION_TOLERANCE).then_some(difference)
+    }
+
+    fn end(&self) -> Option<f64> {
+        Some(self.start? + self.signalled_duration?)
+    }
+}
+
+/// The dates (in milliseconds) of the dateranges that make up a break.
+#[derive(Default)]
+struct BreakDates {
+    start_date: Option<f64>,
+    end_date: Option<f64>,
+}
+
+pub fn ad_breaks(playlist: &str) -> Vec<AdBreak> {
+    let mut breaks = Vec::<(AdBreak, BreakDates)>::new();
+    for line in parse(playlist) {
+        let LineKind::Tag(tag) = line.kind else {
+            continue;
+        };
+        if tag.name != "EXT-X-DATERANGE" {
+            continue;
+        }
+        let Some(id) = tag.attribute_str("ID") else {
+            continue;
+        };
+        let start_date = tag.attribute_str("START-DATE").and_then(parse_date_time);
+        let end_date = tag
+            .attribute_str("END-DATE")
+            .and_then(parse_date_time)
+            .or_else(|| {
+                let duration = tag.attribute_str("DURATION")?.parse::<f64>().ok()?;
+                Some(start_date? + duration * 1000.0)
+            });
+        let event_id = |name| {
+            decode_attribute(tag.attribute_str(name)?)
+                .as_deref()
+                .and_then(splice_info_section)?
+                .event_id()
+        };
+        let planned_duration = tag
+            .attribute_str("PLANNED-DURATION")
+            .and_then(|duration| duration.parse().ok());
+        // Any tag with the ID of a break adds to what is known of it, whether it carries SCTE-35 or
+        // not (e.g. a later tag that gives the DURATION once the break has ended).
+        let existing = breaks.iter_mut().find(|(ad_break, _)| ad_break.id == id);
+        let is_out = tag.attribute("SCTE35-OUT").is_some();
+        let is_in = tag.attribute("SCTE35-IN").is_some();
+        let (ad_break, dates) = match existing {
+            Some(existing) => existing,
+            None if is_out => {
+                breaks.push((
+                    AdBreak {
+                        id: id.to_string(),
+                        out_line: None,
+                        in_line: None,
+                        pairing: BreakPairing::Unpaired,
+                        event_id: None,
+                        planned_duration: None,
+                        signalled_duration: None,
+                        actual_duration: None,
+                        start: None,
+                        overlaps: Vec::new(),
+                    },
+                    BreakDates::default(),
+                ));
+                breaks.last_mut().unwrap()
+            }
+            None if is_in => {
+                let in_event_id = event_id("SCTE35-IN");
+                // A break that is still waiting for its SCTE35-IN, with the same event id.
+                let paired = breaks.iter_mut().find(|(ad_break, _)| {
+                    ad_break.in_line.is_none()
+                        && ad_break.out_line.is_some()
+                        && in_event_id.is_some()
+                        && ad_break.event_id == in_event_id
+                });
+                match paired {
+                    Some((ad_break, dates)) => {
+                        ad_break.in_line = Some(line.number);
+                        ad_break.pairing = BreakPairing::EventId;
+                        dates.end_date = dates.end_date.or(start_date);
+                    }
+                    None => breaks.push((
+                        AdBreak {
+                            id: id.to_string(),
+                            out_line: None,
+                            in_line: Some(line.number),
+                            pairing: BreakPairing::Unpaired,
+                            event_id: in_event_id,
+                            planned_duration,
+                            signalled_duration: None,
+                            actual_duration: None,
+                            start: None,
+                            overlaps: Vec::new(),
+                        },
+                        BreakDates {
+                            start_date: None,
+                            end_date: start_date,
+                        },
+                    )),
+                }
+                continue;
+            }
+            None => continue,
+        };
+        if is_out && ad_break.out_line.is_none() {
+            ad_break.out_line = Some(line.number);
+            ad_break.event_id = event_id("SCTE35-OUT");
+        }
+        if is_in && ad_break.in_line.is_none() && ad_break.out_line.is_some() {
+            ad_break.in_line = Some(line.number);
+            ad_break.pairing = BreakPairing::SameId;
+        }
+        ad_break.planned_duration = ad_break.planned_duration.or(planned_duration);
+        dates.start_date = dates.start_date.or(start_date);
+        dates.end_date = dates.end_date.or(end_date);
+    }
+    let timeline = timeline(playlist);
+    let end_of_playlist = timeline.duration();
+    // Players can only switch at the start of a segment, or at the end of the last one.
+    let boundaries = timeline
+        .segments
+        .iter()
+        .map(|segment| segment.start)
+        .chain(std::iter::once(end_of_playlist))
+        .collect::<Vec<_>>();
+    let nearest_boundary = |time: f64| {
+        boundaries
+            .iter()
+            .copied()
+            .min_by(|a, b| (a - time).abs().total_cmp(&(b - time).abs()))
+    };
+    let mut breaks = breaks
+        .into_iter()
+        .map(|(mut ad_break, dates)| {
+            ad_break.signalled_duration = dates
+                .start_date
+                .zip(dates.end_date)
+                .map(|(start_date, end_date)| (end_date - start_date) / 1000.0);
+            // The timeline places each daterange against the segments from its START-DATE.
+            ad_break.start = ad_break.out_line.and_then(|out_line| {
+                timeline
+                    .dateranges
+                    .iter()
+                    .find(|daterange| daterange.line == out_line)
+                    .map(|daterange| daterange.start)
+            });
+            // Without a SCTE35-IN there is no end of the break to measure up to.
+            ad_break.actual_duration = ad_break
+                .start
+                .zip(ad_break.end())
+                .filter(|_| ad_break.in_line.is_some())
+                .filter(|(start, end)| *start >= 0.0 && *end <= end_of_playlist)
+                .and_then(|(start, end)| Some(nearest_boundary(end)? - nearest_boundary(start)?));
+            ad_break
+        })
+        .collect::<Vec<_>>();
+    for index in 0..breaks.len() {
+        let Some((start, end)) = breaks[index].start.zip(breaks[index].end()) else {
+            continue;
+        };
+        let overlaps =
+            breaks
+                .iter()
+                .enumerate()
+                .filter(|(other, ad_break)| {
+                    *other != index
+                        && ad_break.start.zip(ad_break.end()).is_some_and(
+                            |(other_start, other_end)| other_start < end && start < other_end,
+                        )
+                })
+                .map(|(_, ad_break)| ad_break.id.clone())
+                .collect();
+        breaks[index].overlaps = overlaps;
+    }
+    breaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // A splice_insert out of network with a splice_event_id of 0x4800008F.
+    const OUT: &str = concat!(
+        "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF500000000000A0008",
+        "435545490000013562DBA30A",
+    );
+
+    #[test]
+    fn breaks_are_paired_and_measured_against_the_segments() {
+        let playlist = format!(
+            "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00Z
+#EXTINF:6,
+0.ts
+#EXT-X-DATERANGE:ID=\"1\",START-DATE=\"2025-01-01T00:00:06Z\",PLANNED-DURATION=60,SCTE35-OUT={OUT}
+#EXTINF:6,
+1.ts
+#EXTINF:6,
+2.ts
+#EXT-X-DATERANGE:ID=\"1\",START-DATE=\"2025-01-01T00:00:06Z\",DURATION=11.9,SCTE35-IN=0xFC
+#EXTINF:6,
+3.ts
+#EXT-X-DATERANGE:ID=\"2\",START-DATE=\"2025-01-01T00:00:20Z\",SCTE35-OUT=0xFC
+#EXT-X-ENDLIST"
+        );
+        assert_eq!(
+            vec![
+                AdBreak {
+                    id: String::from("1"),
+                    out_line: Some(6),
+                    in_line: Some(11),
+                    pairing: BreakPairing::SameId,
+                    event_id: Some(0x4800008F),
+                    planned_duration: Some(60.0),
+                    signalled_duration: Some(11.9),
+                    actual_duration: Some(12.0),
+                    start: Some(6.0),
+                    overlaps: vec![],
+                },
+                AdBreak {
+                    id: String::from("2"),
+                    out_line: Some(14),
+                    in_line: None,
+                    pairing: BreakPairing::Unpaired,
+                    event_id: None,
+                    planned_duration: None,
+                    signalled_duration: None,
+                    actual_duration: None,
+                    start: Some(20.0),
+                    overlaps: vec![],
+                },
+            ],
+            ad_breaks(&playlist)
+        );
+        assert_eq!(
+            Some(-48.0),
+            ad_breaks(&playlist)[0].planned_duration_mismatch()
+        );
+    }
+
+    #[test]
+    fn breaks_are_paired_by_event_id_and_overlaps_are_found() {
+        // The SCTE35-IN carries a splice_insert with the same splice_event_id as the SCTE35-OUT.
+        let back_in = "0xFC302000000000000000FFF00F054800008F7F4FFE7369C02E0000000000002208547B";
+        let playlist = format!(
+            "#EXTM3U
+#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00Z
+#EXT-X-DATERANGE:ID=\"out\",START-DATE=\"2025-01-01T00:00:00Z\",SCTE35-OUT={OUT}
+#EXT-X-DATERANGE:ID=\"other\",START-DATE=\"2025-01-01T00:00:02Z\",DURATION=2,SCTE35-OUT=0xFC
+#EXTINF:6,
+0.ts
+#EXT-X-DATERANGE:ID=\"in\",START-DATE=\"2025-01-01T00:00:06Z\",SCTE35-IN={back_in}
+#EXTINF:6,
+1.ts"
+        );
+        let breaks = ad_breaks(&playlist);
+        assert_eq!(
+            vec![
+                ("out", Some(7), BreakPairing::EventId, Some(6.0)),
+                ("other", None, BreakPairing::Unpaired, None),
+            ],
+            breaks
+                .iter()
+                .map(|ad_break| (
+                    ad_break.id.as_str(),
+                    ad_break.in_line,
+                    ad_break.pairing,
+                    ad_break.actual_duration
+                ))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![String::from("other")], breaks[0].overlaps);
+        assert_eq!(vec![String::from("out")], breaks[1].overlaps);
+    }
+}
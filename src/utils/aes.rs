@@ -0,0 +1,101 @@
+// AES-128 decryption in CBC mode with PKCS7 padding, which is how a segment is encrypted when its
+// EXT-X-KEY has a METHOD of AES-128.
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-5.2
+//
+//    An encryption method of AES-128 signals that Media Segments are
+//    completely encrypted using the Advanced Encryption Standard (AES)
+//    [AES_128] with a 128-bit key, Cipher Block Chaining (CBC), and
+//    Public-Key Cryptography Standards #7 (PKCS7) padding [RFC5652].  CBC
+//    is restarted on each segment boundary, using either the
+//    Initialization Vector (IV) attribute value or the Media Sequence
+//    Number as the IV.
+//
+// The block cipher and its chaining are left to the RustCrypto aes and cbc crates, leaving only
+// the PKCS7 padding to be checked here (as a wrong key or IV shows up as invalid padding).
+
+use aes::Aes128;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit, block_padding::NoPadding};
+use std::{error::Error, fmt::Display};
+
+const BLOCK_SIZE: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecryptError {
+    /// The data is not a whole number of blocks.
+    Length(usize),
+    /// The padding of the last block is not valid PKCS7, which usually means the key or IV is wrong.
+    Padding,
+}
+impl Display for DecryptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Length(length) => write!(
+                f,
+                "{length} bytes is not a whole number of {BLOCK_SIZE} byte AES blocks"
+            ),
+            Self::Padding => write!(
+                f,
+                "the decrypted data does not end with valid PKCS7 padding, so the key or IV is \
+                 likely wrong"
+            ),
+        }
+    }
+}
+impl Error for DecryptError {}
+
+/// Decrypts AES-128-CBC data and removes its PKCS7 padding.
+pub fn decrypt_aes_128_cbc(
+    key: &[u8; 16],
+    iv: &[u8; 16],
+    data: &[u8],
+) -> Result<Vec<u8>, DecryptError> {
+    if data.is_empty() || !data.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(DecryptError::Length(data.len()));
+    }
+    let mut plaintext = cbc::Decryptor::<Aes128>::new(key.into(), iv.into())
+        .decrypt_padded_vec_mut::<NoPadding>(data)
+        .map_err(|_| DecryptError::Length(data.len()))?;
+    let padding = usize::from(*plaintext.last().unwrap_or(&0));
+    if padding == 0
+        || padding > BLOCK_SIZE
+        || !plaintext[plaintext.len() - padding..]
+            .iter()
+            .all(|byte| usize::from(*byte) == padding)
+    {
+        return Err(DecryptError::Padding);
+    }
+    plaintext.truncate(plaintext.len() - padding);
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::hex::decode_hex;
+    use pretty_assertions::assert_eq;
+
+    fn block(hex: &str) -> [u8; 16] {
+        decode_hex(hex).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn cbc_decrypts_and_removes_padding() {
+        let key = block("000102030405060708090a0b0c0d0e0f");
+        let iv = block("00000000000000000000000000000007");
+        let data =
+            decode_hex("fe0c7f71cfc70c031ce7685d53f3d6a61899b3b76970550ddafdd1122889a508").unwrap();
+        assert_eq!(
+            Ok(b"HLS segment data!!".to_vec()),
+            decrypt_aes_128_cbc(&key, &iv, &data)
+        );
+        assert_eq!(
+            Err(DecryptError::Padding),
+            decrypt_aes_128_cbc(&block("000102030405060708090a0b0c0d0e0e"), &iv, &data)
+        );
+        assert_eq!(
+            Err(DecryptError::Length(31)),
+            decrypt_aes_128_cbc(&key, &iv, &data[1..])
+        );
+    }
+}
@@ -0,0 +1,141 @@
+// Recognition and measurement of packed audio segments (raw elementary streams, as described in the
+// HLS spec), so that they can be played in the viewer.
+
+/// The elementary stream formats that HLS allows for packed audio segments.
+///
+/// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-3.1.4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackedAudioFormat {
+    /// AAC in ADTS frames.
+    Aac,
+    /// MPEG-1 or MPEG-2 audio (e.g. MP3).
+    Mpeg,
+    Ac3,
+    Eac3,
+}
+
+impl PackedAudioFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Aac => "AAC (ADTS)",
+            Self::Mpeg => "MPEG audio",
+            Self::Ac3 => "AC-3",
+            Self::Eac3 => "E-AC-3",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Aac => "audio/aac",
+            Self::Mpeg => "audio/mpeg",
+            Self::Ac3 => "audio/ac3",
+            Self::Eac3 => "audio/eac3",
+        }
+    }
+
+    pub fn from_mime_type(mime_type: &str) -> Option<Self> {
+        match mime_type {
+            "audio/aac" | "audio/x-aac" => Some(Self::Aac),
+            "audio/mpeg" | "audio/mp3" => Some(Self::Mpeg),
+            "audio/ac3" => Some(Self::Ac3),
+            "audio/eac3" => Some(Self::Eac3),
+            _ => None,
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "aac" => Some(Self::Aac),
+            "mp3" => Some(Self::Mpeg),
+            "ac3" => Some(Self::Ac3),
+            "ec3" => Some(Self::Eac3),
+            _ => None,
+        }
+    }
+}
+
+/// Recognises a packed audio segment by the sync word of its first frame, which follows the ID3
+/// tag that each packed audio segment starts with.
+pub fn probe_packed_audio(data: &[u8]) -> Option<PackedAudioFormat> {
+    // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-3.1.4
+    //
+    // Each Packed Audio Segment MUST signal the timestamp of its first sample with an ID3 Private
+    // frame (PRIV) tag [ID3] at the beginning of the segment.
+    let frame = data.get(id3_tag_length(data)..)?;
+    match frame {
+        // ISO/IEC 13818-7 Table 6: syncword, ID, layer (always 0 for ADTS).
+        [0xFF, second, ..] if second & 0xF6 == 0xF0 => Some(PackedAudioFormat::Aac),
+        // ISO/IEC 11172-3: frame sync, version, then a layer other than the reserved 0.
+        [0xFF, second, ..] if second & 0xE0 == 0xE0 && second & 0x06 != 0 => {
+            Some(PackedAudioFormat::Mpeg)
+        }
+        // ETSI TS 102 366 4.4.1 and E.1.2: syncword, then bsid in the top bits of the sixth byte,
+        // which is at most 10 for AC-3 and 16 for E-AC-3.
+        [0x0B, 0x77, _, _, _, bsi, ..] => match bsi >> 3 {
+            0..=10 => Some(PackedAudioFormat::Ac3),
+            11..=16 => Some(PackedAudioFormat::Eac3),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// The length of the ID3v2 tag at the start of the data (or 0 without one), from its header.
+// https://id3.org/id3v2.4.0-structure section 3.1
+fn id3_tag_length(data: &[u8]) -> usize {
+    let [b'I', b'D', b'3', _, _, flags, size @ ..] = data else {
+        return 0;
+    };
+    let Some(size) = size.get(..4) else {
+        return 0;
+    };
+    // The size is a synchsafe integer, with 7 bits in each byte, excluding the header and footer.
+    let size = size
+        .iter()
+        .fold(0, |size, byte| (size << 7) | usize::from(byte & 0x7F));
+    let footer = if flags & 0x10 == 0 { 0 } else { 10 };
+    10 + size + footer
+}
+
+/// The peak level of the samples in dBFS, or `None` when every sample is silent.
+pub fn peak_dbfs(samples: impl IntoIterator<Item = f32>) -> Option<f64> {
+    let peak = samples.into_iter().map(f32::abs).fold(0.0_f32, f32::max);
+    (peak > 0.0).then(|| 20.0 * f64::from(peak).log10())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn probe_packed_audio_should_skip_the_id3_tag() {
+        let mut aac = b"ID3\x04\x00\x00\x00\x00\x00\x03abc".to_vec();
+        aac.extend([0xFF, 0xF1, 0x50, 0x80]);
+        assert_eq!(Some(PackedAudioFormat::Aac), probe_packed_audio(&aac));
+        assert_eq!(
+            Some(PackedAudioFormat::Mpeg),
+            probe_packed_audio(&[0xFF, 0xFB, 0x90, 0x64])
+        );
+        assert_eq!(
+            Some(PackedAudioFormat::Ac3),
+            probe_packed_audio(&[0x0B, 0x77, 0, 0, 0, 0x40])
+        );
+        assert_eq!(
+            Some(PackedAudioFormat::Eac3),
+            probe_packed_audio(&[0x0B, 0x77, 0, 0, 0, 0x80])
+        );
+        assert_eq!(
+            None,
+            probe_packed_audio(b"ID3\x04\x00\x00\x00\x00\x00\x03ab")
+        );
+        assert_eq!(None, probe_packed_audio(b"\x00\x00\x00\x18ftyp"));
+    }
+
+    #[test]
+    fn peak_dbfs_should_be_none_for_silence() {
+        assert_eq!(None, peak_dbfs([0.0, -0.0, 0.0]));
+        assert_eq!(Some(0.0), peak_dbfs([0.25, -1.0]));
+        assert_eq!(Some(-20.0), peak_dbfs([0.1_f32]).map(|peak| peak.round()));
+    }
+}
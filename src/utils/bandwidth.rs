@@ -0,0 +1,240 @@
+use crate::utils::playlist_lines::{self, LineKind};
+
+// Measurement of the bit rate of a Media Playlist from a sample of its segments, for comparison
+// against the BANDWIDTH and AVERAGE-BANDWIDTH attributes of the variant that references it.
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.6.2
+//
+//    BANDWIDTH
+//
+//    The value is a decimal-integer of bits per second.  It represents the peak segment bit rate of
+//    the Variant Stream.
+//
+//    AVERAGE-BANDWIDTH
+//
+//    The value is a decimal-integer of bits per second.  It represents the average segment bit rate
+//    of the Variant Stream.
+
+/// A segment chosen to be downloaded as part of the sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentSample {
+    /// The URI as written in the playlist (variables are not yet substituted).
+    pub uri: String,
+    pub duration: f64,
+    /// The byte range of the segment as (length, offset), when the segment is a sub-range.
+    pub byterange: Option<(u64, u64)>,
+    /// The EXT-X-MAP in effect for the segment, as its URI along with its byte range as (length,
+    /// offset) when one is set.
+    pub map: Option<(String, Option<(u64, u64)>)>,
+}
+
+/// Chooses up to `count` segments spread evenly across the playlist.
+pub fn sample_segments(playlist: &str, count: usize) -> Vec<SegmentSample> {
+    let segments = segments(playlist);
+    if count == 0 || segments.is_empty() {
+        return Vec::new();
+    }
+    if segments.len() <= count {
+        return segments;
+    }
+    let step = segments.len() as f64 / count as f64;
+    (0..count)
+        .map(|i| segments[(i as f64 * step) as usize].clone())
+        .collect()
+}
+
+fn segments(playlist: &str) -> Vec<SegmentSample> {
+    let mut segments = Vec::new();
+    let mut duration = None;
+    let mut byterange = None;
+    let mut next_offset = 0;
+    let mut map = None;
+    for line in playlist_lines::parse(playlist) {
+        match line.kind {
+            LineKind::Tag(tag) if tag.name == "EXTINF" => {
+                duration = tag
+                    .value
+                    .map(|v| v.split_once(',').map(|(d, _)| d).unwrap_or(v).trim())
+                    .and_then(|d| d.parse::<f64>().ok());
+            }
+            LineKind::Tag(tag) if tag.name == "EXT-X-BYTERANGE" => {
+                byterange = tag.value.and_then(|value| {
+                    let (length, offset) = match value.split_once('@') {
+                        Some((length, offset)) => (length, Some(offset)),
+                        None => (value, None),
+                    };
+                    let length = length.trim().parse::<u64>().ok()?;
+                    let offset = match offset {
+                        Some(offset) => offset.trim().parse::<u64>().ok()?,
+                        // Without an offset the sub-range begins at the next byte following the
+                        // sub-range of the previous segment.
+                        None => next_offset,
+                    };
+                    Some((length, offset))
+                });
+            }
+            LineKind::Tag(tag) if tag.name == "EXT-X-MAP" => map = tag.map(),
+            LineKind::Uri(uri) => {
+                if let Some((length, offset)) = byterange {
+                    next_offset = offset + length;
+                }
+                if let Some(duration) = duration.take().filter(|d| *d > 0.0) {
+                    segments.push(SegmentSample {
+                        uri: uri.to_string(),
+                        duration,
+                        byterange: byterange.take(),
+                        map: map.clone(),
+                    });
+                }
+                byterange = None;
+            }
+            _ => (),
+        }
+    }
+    segments
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BandwidthMeasurement {
+    /// The highest bit rate of any one sampled segment, in bits per second.
+    pub peak: u64,
+    /// The bit rate across all sampled segments, in bits per second.
+    pub average: u64,
+}
+
+/// Measures the bit rate from (duration in seconds, size in bytes) pairs.
+pub fn measure(segments: &[(f64, u64)]) -> Option<BandwidthMeasurement> {
+    let total_duration = segments.iter().map(|(duration, _)| duration).sum::<f64>();
+    if total_duration <= 0.0 {
+        return None;
+    }
+    let total_bits = segments.iter().map(|(_, bytes)| bytes * 8).sum::<u64>();
+    let peak = segments
+        .iter()
+        .filter_map(|(duration, bytes)| segment_bitrate(*duration, *bytes))
+        .max()?;
+    Some(BandwidthMeasurement {
+        peak,
+        average: (total_bits as f64 / total_duration).round() as u64,
+    })
+}
+
+/// The bit rate of a segment in bits per second, from its duration in seconds and size in bytes.
+pub fn segment_bitrate(duration: f64, bytes: u64) -> Option<u64> {
+    (duration > 0.0).then(|| ((bytes * 8) as f64 / duration).round() as u64)
+}
+
+/// The indices of the segments with a bit rate above BANDWIDTH, which no segment should exceed as
+/// it is the peak segment bit rate.
+pub fn bandwidth_outliers(bitrates: &[Option<u64>], bandwidth: u64) -> Vec<usize> {
+    bitrates
+        .iter()
+        .enumerate()
+        .filter(|(_, bitrate)| bitrate.is_some_and(|bitrate| bitrate > bandwidth))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// The percentage by which the measured value differs from the advertised value (positive when the
+/// measured value is higher).
+pub fn deviation_percent(measured: u64, advertised: u64) -> Option<f64> {
+    if advertised == 0 {
+        return None;
+    }
+    Some((measured as f64 - advertised as f64) / advertised as f64 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn segments_are_sampled_evenly() {
+        let playlist = (0..10).fold(
+            String::from("#EXTM3U\n#EXT-X-TARGETDURATION:4\n"),
+            |playlist, i| format!("{playlist}#EXTINF:4,\nsegment{i}.mp4\n"),
+        );
+        assert_eq!(
+            vec!["segment0.mp4", "segment3.mp4", "segment6.mp4"],
+            sample_segments(&playlist, 3)
+                .iter()
+                .map(|s| s.uri.as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert_eq!(10, sample_segments(&playlist, 20).len());
+    }
+
+    #[test]
+    fn byterange_offsets_follow_previous_segment() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXTINF:4,
+#EXT-X-BYTERANGE:1000@0
+media.mp4
+#EXTINF:4,
+#EXT-X-BYTERANGE:1500
+media.mp4
+";
+        assert_eq!(
+            vec![Some((1000, 0)), Some((1500, 1000))],
+            sample_segments(playlist, 5)
+                .iter()
+                .map(|s| s.byterange)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn segments_carry_the_map_in_effect() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXT-X-I-FRAMES-ONLY
+#EXT-X-MAP:URI=\"main.mp4\",BYTERANGE=\"720@0\"
+#EXTINF:4,
+#EXT-X-BYTERANGE:5000@720
+main.mp4
+";
+        assert_eq!(
+            vec![Some((String::from("main.mp4"), Some((720, 0))))],
+            sample_segments(playlist, 5)
+                .into_iter()
+                .map(|s| s.map)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn measure_provides_peak_and_average() {
+        assert_eq!(
+            Some(BandwidthMeasurement {
+                peak: 2_000_000,
+                average: 1_500_000,
+            }),
+            measure(&[(4.0, 500_000), (4.0, 1_000_000)])
+        );
+        assert_eq!(None, measure(&[]));
+    }
+
+    #[test]
+    fn outliers_exceed_the_bandwidth() {
+        let bitrates = [
+            segment_bitrate(4.0, 500_000),
+            segment_bitrate(4.0, 1_000_000),
+            None,
+            segment_bitrate(0.0, 1_000_000),
+        ];
+        assert_eq!(vec![1], bandwidth_outliers(&bitrates, 1_500_000));
+        assert_eq!(
+            Vec::<usize>::new(),
+            bandwidth_outliers(&bitrates, 2_000_000)
+        );
+    }
+
+    #[test]
+    fn deviation_is_relative_to_advertised() {
+        assert_eq!(Some(25.0), deviation_percent(1_250_000, 1_000_000));
+        assert_eq!(Some(-50.0), deviation_percent(500_000, 1_000_000));
+        assert_eq!(None, deviation_percent(500_000, 0));
+    }
+}
@@ -0,0 +1,191 @@
+// The tree of boxes of a segment as JSON, for tools that compare segments or check them in CI. Each
+// box gives its type, where it starts in the segment and how large it is (header included), along
+// with the properties that the ISOBMFF viewer shows for it and the boxes nested within it, much as
+// mp4box.js lists them.
+
+use crate::utils::{
+    hex::encode_hex,
+    mp4_atom_properties::{AtomProperties, AtomPropertyValue, BasicPropertyValue},
+};
+use serde_json::{Map, Value, json};
+use std::{iter::Peekable, slice::Iter};
+
+/// A box of the segment, in the order that the boxes are in the file.
+pub struct BoxEntry<'a> {
+    pub kind: &'a str,
+    pub depth: usize,
+    /// The position of the start of the box in the segment.
+    pub offset: usize,
+    pub size: usize,
+    pub properties: &'a AtomProperties,
+}
+
+/// The boxes as a pretty printed JSON document, with each box nested within its parent.
+pub fn box_tree_json(boxes: &[BoxEntry]) -> String {
+    let json = json!({ "boxes": nested_boxes(&mut boxes.iter().peekable(), 0) });
+    serde_json::to_string_pretty(&json).unwrap_or_default()
+}
+
+// The boxes from here on that are at `depth` (or deeper, should a level be skipped), each with the
+// boxes that follow deeper than it as its children.
+fn nested_boxes(boxes: &mut Peekable<Iter<BoxEntry>>, depth: usize) -> Vec<Value> {
+    let mut nested = Vec::new();
+    while let Some(entry) = boxes.next_if(|entry| entry.depth >= depth) {
+        let mut json = json!({
+            "type": entry.kind,
+            "name": entry.properties.box_name,
+            "start": entry.offset,
+            "size": entry.size,
+            "properties": properties_json(entry.properties),
+        });
+        let children = nested_boxes(boxes, entry.depth + 1);
+        if !children.is_empty() {
+            json["boxes"] = Value::Array(children);
+        }
+        nested.push(json);
+    }
+    nested
+}
+
+fn properties_json(properties: &AtomProperties) -> Value {
+    properties
+        .properties
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                AtomPropertyValue::Basic(value) => basic_json(value),
+                // The rows of a table with headers are objects keyed by them.
+                AtomPropertyValue::Table(table) => table
+                    .rows
+                    .iter()
+                    .map(|row| match &table.headers {
+                        Some(headers) => Value::Object(
+                            headers
+                                .iter()
+                                .zip(row)
+                                .map(|(header, value)| (header.to_string(), basic_json(value)))
+                                .collect(),
+                        ),
+                        None => Value::Array(row.iter().map(basic_json).collect()),
+                    })
+                    .collect(),
+            };
+            (name.to_string(), value)
+        })
+        .collect::<Map<_, _>>()
+        .into()
+}
+
+fn basic_json(value: &BasicPropertyValue) -> Value {
+    match value {
+        BasicPropertyValue::String(s) | BasicPropertyValue::Url(s) => json!(s),
+        BasicPropertyValue::U64(u) => json!(u),
+        BasicPropertyValue::U32(u) => json!(u),
+        BasicPropertyValue::U16(u) => json!(u),
+        BasicPropertyValue::U8(u) => json!(u),
+        BasicPropertyValue::I32(i) => json!(i),
+        BasicPropertyValue::I16(i) => json!(i),
+        BasicPropertyValue::I8(i) => json!(i),
+        BasicPropertyValue::Usize(u) => json!(u),
+        BasicPropertyValue::Bool(b) => json!(b),
+        BasicPropertyValue::Hex(bytes) => json!(encode_hex(bytes)),
+        BasicPropertyValue::BinaryMask(_) => json!(String::from(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mp4_atom_properties::TablePropertyValue;
+    use pretty_assertions::assert_eq;
+
+    fn properties(
+        box_name: &'static str,
+        properties: Vec<(&'static str, AtomPropertyValue)>,
+    ) -> AtomProperties {
+        AtomProperties {
+            box_name,
+            properties: properties
+                .into_iter()
+                .map(|(name, value)| (name.into(), value))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn boxes_are_nested_within_their_parents() {
+        let moof = properties("MovieFragmentBox", vec![]);
+        let mfhd = properties(
+            "MovieFragmentHeaderBox",
+            vec![(
+                "sequence_number",
+                AtomPropertyValue::Basic(BasicPropertyValue::U32(7)),
+            )],
+        );
+        let trun = properties(
+            "TrackRunBox",
+            vec![(
+                "samples",
+                AtomPropertyValue::Table(TablePropertyValue {
+                    headers: Some(vec!["duration", "size"]),
+                    rows: vec![vec![
+                        BasicPropertyValue::U32(1024),
+                        BasicPropertyValue::U32(300),
+                    ]],
+                }),
+            )],
+        );
+        let mdat = properties("MediaDataBox", vec![]);
+        let boxes = [
+            ("moof", 0, 0, 40, &moof),
+            ("mfhd", 1, 8, 16, &mfhd),
+            ("trun", 1, 24, 16, &trun),
+            ("mdat", 0, 40, 308, &mdat),
+        ]
+        .map(|(kind, depth, offset, size, properties)| BoxEntry {
+            kind,
+            depth,
+            offset,
+            size,
+            properties,
+        });
+        let json = serde_json::from_str::<Value>(&box_tree_json(&boxes)).unwrap();
+        assert_eq!(
+            json!({
+                "boxes": [
+                    {
+                        "type": "moof",
+                        "name": "MovieFragmentBox",
+                        "start": 0,
+                        "size": 40,
+                        "properties": {},
+                        "boxes": [
+                            {
+                                "type": "mfhd",
+                                "name": "MovieFragmentHeaderBox",
+                                "start": 8,
+                                "size": 16,
+                                "properties": { "sequence_number": 7 },
+                            },
+                            {
+                                "type": "trun",
+                                "name": "TrackRunBox",
+                                "start": 24,
+                                "size": 16,
+                                "properties": { "samples": [{ "duration": 1024, "size": 300 }] },
+                            },
+                        ],
+                    },
+                    {
+                        "type": "mdat",
+                        "name": "MediaDataBox",
+                        "start": 40,
+                        "size": 308,
+                        "properties": {},
+                    },
+                ]
+            }),
+            json
+        );
+    }
+}
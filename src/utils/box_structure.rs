@@ -0,0 +1,213 @@
+// Scanning of the top-level boxes of an ISOBMFF segment, so that only the boxes that come before
+// the media data need to be downloaded in order to inspect its structure (styp, sidx, prft, moof,
+// etc.). The first mdat marks the end of what is needed, as its payload is the bulk of the segment
+// and is not broken down any further by the viewer.
+//
+// ISO/IEC 14496-12, 4.2 Object structure
+//
+//    aligned(8) class Box (unsigned int(32) boxtype,
+//          optional unsigned int(8)[16] extended_type) {
+//       unsigned int(32) size;
+//       unsigned int(32) type = boxtype;
+//       if (size==1) {
+//          unsigned int(64) largesize;
+//       } else if (size==0) {
+//          // box extends to end of file
+//       }
+//       ...
+//    }
+
+/// How far the data of a segment reaches into its structure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StructureScan {
+    /// The boxes up to and including the header of the first mdat end at this length.
+    Complete(usize),
+    /// The box at the end of the data continues past it, and at least this many bytes are needed
+    /// to read on to the header of the next box.
+    Incomplete(usize),
+    /// The data ends at a box boundary without having reached an mdat.
+    Ended,
+    /// The data cannot be scanned as boxes (e.g. it is not ISOBMFF, or a box other than mdat
+    /// extends to the end of the file), so the whole segment is needed.
+    Unscannable,
+}
+
+pub fn scan_structure(data: &[u8]) -> StructureScan {
+    let mut offset = 0;
+    loop {
+        if offset == data.len() {
+            return if offset == 0 {
+                StructureScan::Incomplete(8)
+            } else {
+                StructureScan::Ended
+            };
+        }
+        let (kind, header_size, size) = match read_header(data, offset) {
+            HeaderRead::Header {
+                kind,
+                header_size,
+                size,
+            } => (kind, header_size, size),
+            HeaderRead::Incomplete => return StructureScan::Incomplete(offset + 16),
+            HeaderRead::Invalid => return StructureScan::Unscannable,
+        };
+        if &kind == b"mdat" {
+            return StructureScan::Complete(offset + header_size);
+        }
+        let Some(size) = size else {
+            return StructureScan::Unscannable;
+        };
+        let end = offset + size;
+        if end > data.len() {
+            return StructureScan::Incomplete(end + 8);
+        }
+        offset = end;
+    }
+}
+
+/// How much of the start of a segment that is still downloading is made of whole boxes, and so can
+/// be parsed already. The payload of an mdat is not broken down, so the mdat counts from as soon as
+/// its header has arrived. None of the data counts when it cannot be scanned as boxes.
+pub fn readable_length(data: &[u8]) -> usize {
+    let mut offset = 0;
+    while offset < data.len() {
+        let (kind, header_size, size) = match read_header(data, offset) {
+            HeaderRead::Header {
+                kind,
+                header_size,
+                size,
+            } => (kind, header_size, size),
+            HeaderRead::Incomplete => break,
+            HeaderRead::Invalid => return 0,
+        };
+        let end = size.map(|size| offset + size);
+        match end {
+            Some(end) if end <= data.len() => offset = end,
+            _ if &kind == b"mdat" => return offset + header_size,
+            Some(_) => break,
+            None => return 0,
+        }
+    }
+    offset
+}
+
+enum HeaderRead {
+    Header {
+        kind: [u8; 4],
+        header_size: usize,
+        /// The size of the whole box, unless it extends to the end of the file.
+        size: Option<usize>,
+    },
+    /// The data ends within the header.
+    Incomplete,
+    /// The bytes are not a box header.
+    Invalid,
+}
+
+fn read_header(data: &[u8], offset: usize) -> HeaderRead {
+    let Some(header) = data.get(offset..offset + 8) else {
+        return HeaderRead::Incomplete;
+    };
+    let kind = [header[4], header[5], header[6], header[7]];
+    if !kind
+        .iter()
+        .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+    {
+        return HeaderRead::Invalid;
+    }
+    let (header_size, size) = match u32::from_be_bytes([header[0], header[1], header[2], header[3]])
+    {
+        0 => (8, None),
+        1 => {
+            let Some(large_size) = data.get(offset + 8..offset + 16) else {
+                return HeaderRead::Incomplete;
+            };
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(large_size);
+            (16, Some(u64::from_be_bytes(bytes)))
+        }
+        size => (8, Some(u64::from(size))),
+    };
+    let size = match size.map(usize::try_from) {
+        Some(Ok(size)) if size < header_size => return HeaderRead::Invalid,
+        Some(Ok(size)) => Some(size),
+        Some(Err(_)) => return HeaderRead::Invalid,
+        None => None,
+    };
+    HeaderRead::Header {
+        kind,
+        header_size,
+        size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn mp4_box(kind: &[u8; 4], payload_size: usize) -> Vec<u8> {
+        let mut data = ((payload_size + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(kind);
+        data.extend(std::iter::repeat_n(0, payload_size));
+        data
+    }
+
+    #[test]
+    fn structure_is_complete_at_the_mdat_header() {
+        let mut segment = mp4_box(b"styp", 16);
+        segment.extend(mp4_box(b"moof", 100));
+        let mdat_offset = segment.len();
+        segment.extend(mp4_box(b"mdat", 1000));
+        assert_eq!(
+            StructureScan::Complete(mdat_offset + 8),
+            scan_structure(&segment)
+        );
+        assert_eq!(
+            StructureScan::Complete(mdat_offset + 8),
+            scan_structure(&segment[..mdat_offset + 8])
+        );
+        assert_eq!(
+            StructureScan::Ended,
+            scan_structure(&segment[..mdat_offset])
+        );
+        assert_eq!(
+            StructureScan::Incomplete(mdat_offset + 8),
+            scan_structure(&segment[..50])
+        );
+        assert_eq!(
+            StructureScan::Incomplete(mdat_offset + 16),
+            scan_structure(&segment[..mdat_offset + 4])
+        );
+    }
+
+    #[test]
+    fn non_isobmff_data_is_unscannable() {
+        assert_eq!(
+            StructureScan::Unscannable,
+            scan_structure(b"WEBVTT\nX-TIMESTAMP-MAP=MPEGTS:900000,LOCAL:00:00:00.000\n")
+        );
+        assert_eq!(StructureScan::Incomplete(8), scan_structure(&[]));
+    }
+
+    #[test]
+    fn readable_length_stops_at_the_last_whole_box() {
+        let mut segment = mp4_box(b"styp", 16);
+        let moof_offset = segment.len();
+        segment.extend(mp4_box(b"moof", 100));
+        let mdat_offset = segment.len();
+        segment.extend(mp4_box(b"mdat", 1000));
+        assert_eq!(0, readable_length(&segment[..4]));
+        assert_eq!(moof_offset, readable_length(&segment[..50]));
+        assert_eq!(mdat_offset, readable_length(&segment[..mdat_offset + 4]));
+        assert_eq!(
+            mdat_offset + 8,
+            readable_length(&segment[..mdat_offset + 500])
+        );
+        assert_eq!(segment.len(), readable_length(&segment));
+        assert_eq!(
+            0,
+            readable_length(b"WEBVTT\n\n00:00.000 --> 00:01.000\nHello\n")
+        );
+    }
+}
@@ -0,0 +1,112 @@
+// The shape of the tree of boxes listed by the ISOBMFF viewer, which is a flat list of boxes each
+// with its depth, so that container boxes can be collapsed. Each box is identified by its path from
+// the top level (e.g. `moof[0]/traf[1]`), which stays the same from one segment of a rendition to
+// the next, so that what was collapsed in one segment can be collapsed in the next.
+
+use std::collections::HashMap;
+
+/// The path of each box, given the type and depth of each box in order. Boxes of the same type
+/// under the same parent are told apart by their position among them.
+pub fn box_paths<'a>(boxes: impl IntoIterator<Item = (&'a str, usize)>) -> Vec<String> {
+    // The path of each ancestor of the current box, along with how many of its children of each
+    // type have been seen so far.
+    let mut ancestors = Vec::<(String, HashMap<&str, usize>)>::new();
+    let mut top_level = HashMap::<&str, usize>::new();
+    boxes
+        .into_iter()
+        .map(|(kind, depth)| {
+            ancestors.truncate(depth);
+            let (parent, siblings) = match ancestors.last_mut() {
+                Some((path, children)) => (path.as_str(), children),
+                None => ("", &mut top_level),
+            };
+            let count = siblings.entry(kind).or_default();
+            let path = if parent.is_empty() {
+                format!("{kind}[{count}]")
+            } else {
+                format!("{parent}/{kind}[{count}]")
+            };
+            *count += 1;
+            ancestors.push((path.clone(), HashMap::new()));
+            path
+        })
+        .collect()
+}
+
+/// Whether the box at `index` has any children, which are the boxes that follow it deeper in the
+/// tree.
+pub fn has_children(depths: &[usize], index: usize) -> bool {
+    depths
+        .get(index)
+        .zip(depths.get(index + 1))
+        .is_some_and(|(depth, next)| next > depth)
+}
+
+/// Whether each box is hidden, which is when any of its ancestors is collapsed.
+pub fn hidden_boxes(depths: &[usize], collapsed: &[bool]) -> Vec<bool> {
+    // The depth of the collapsed box that the following boxes are hidden within, if any.
+    let mut collapsed_depth = None;
+    depths
+        .iter()
+        .enumerate()
+        .map(|(index, depth)| {
+            if collapsed_depth.is_some_and(|collapsed_depth| *depth > collapsed_depth) {
+                return true;
+            }
+            collapsed_depth = collapsed
+                .get(index)
+                .copied()
+                .unwrap_or_default()
+                .then_some(*depth);
+            false
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const BOXES: [(&str, usize); 7] = [
+        ("moof", 0),
+        ("mfhd", 1),
+        ("traf", 1),
+        ("trun", 2),
+        ("traf", 1),
+        ("trun", 2),
+        ("mdat", 0),
+    ];
+
+    #[test]
+    fn paths_tell_apart_siblings_of_the_same_type() {
+        assert_eq!(
+            vec![
+                "moof[0]",
+                "moof[0]/mfhd[0]",
+                "moof[0]/traf[0]",
+                "moof[0]/traf[0]/trun[0]",
+                "moof[0]/traf[1]",
+                "moof[0]/traf[1]/trun[0]",
+                "mdat[0]",
+            ],
+            box_paths(BOXES)
+        );
+    }
+
+    #[test]
+    fn collapsed_boxes_hide_their_descendants() {
+        let depths = BOXES.map(|(_, depth)| depth);
+        assert!(has_children(&depths, 0));
+        assert!(!has_children(&depths, 1));
+        assert!(!has_children(&depths, 6));
+        assert_eq!(
+            vec![false, false, false, true, false, false, false],
+            hidden_boxes(&depths, &[false, false, true, false, false, false, false])
+        );
+        assert_eq!(
+            vec![false, true, true, true, true, true, false],
+            hidden_boxes(&depths, &[true, false, true, false, false, false, false])
+        );
+    }
+}
@@ -0,0 +1,44 @@
+// The range of bytes of a resource to request, as given by EXT-X-BYTERANGE or the BYTERANGE of
+// EXT-X-MAP, with both ends inclusive as in the Range header.
+
+use quick_m3u8::tag::hls::MapByterange;
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RequestRange {
+    pub start: u64,
+    pub end: u64,
+}
+impl RequestRange {
+    pub fn from_length_with_offset(length: u64, offset: u64) -> Self {
+        Self {
+            start: offset,
+            end: (offset + length) - 1,
+        }
+    }
+
+    pub fn range_header_value(&self) -> String {
+        format!("bytes={}-{}", self.start, self.end)
+    }
+
+    /// The single range that covers both ranges, when they overlap or are adjacent.
+    pub fn coalesced_with(&self, other: RequestRange) -> Option<RequestRange> {
+        if self.start > other.end.saturating_add(1) || other.start > self.end.saturating_add(1) {
+            return None;
+        }
+        Some(Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+}
+impl From<MapByterange> for RequestRange {
+    fn from(value: MapByterange) -> Self {
+        Self::from_length_with_offset(value.length, value.offset)
+    }
+}
+impl Display for RequestRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
@@ -0,0 +1,607 @@
+use crate::utils::{
+    mp4_atom_properties::AtomProperties,
+    sample_entries::{SampleEntry, number_property, string_property},
+};
+
+// Synthesis of RFC 6381 codec strings (as used in the HLS CODECS attribute) from the sample entries
+// found in the `stsd` box of an initialization segment, along with comparison against the CODECS
+// attribute advertised for the variant.
+//
+// The configuration values are read from the same properties that are shown in the ISOBMFF viewer,
+// so the codec string shown to the user can always be traced back to the box values it came from.
+
+/// The values from a sample entry and its child boxes that contribute to the codec string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodecConfiguration {
+    Avc {
+        profile: u8,
+        compatibility: u8,
+        level: u8,
+    },
+    Hevc {
+        profile_space: u8,
+        tier: bool,
+        profile_idc: u8,
+        compatibility_flags: u32,
+        constraint_flags: Vec<u8>,
+        level: u8,
+    },
+    Aac {
+        object_type_indication: u8,
+        audio_object_type: u8,
+    },
+    Av1 {
+        profile: u8,
+        level: u8,
+        tier: u8,
+        bit_depth: u8,
+    },
+    Vp9 {
+        profile: u8,
+        level: u8,
+        bit_depth: u8,
+    },
+    DolbyVision {
+        profile: u8,
+        level: u8,
+    },
+    /// Only the sample entry type is known (e.g. `ac-3`, `ec-3`, `wvtt`).
+    SampleEntryOnly,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleEntryCodec {
+    /// The sample entry type, taking into account the original format of protected sample entries
+    /// (e.g. `avc1` rather than `encv`).
+    pub sample_entry: String,
+    pub configuration: CodecConfiguration,
+}
+
+impl SampleEntryCodec {
+    pub fn codec_string(&self) -> String {
+        let sample_entry = &self.sample_entry;
+        match &self.configuration {
+            CodecConfiguration::Avc {
+                profile,
+                compatibility,
+                level,
+            } => format!("{sample_entry}.{profile:02x}{compatibility:02x}{level:02x}"),
+            // ISO/IEC 14496-15 Annex E.3
+            CodecConfiguration::Hevc {
+                profile_space,
+                tier,
+                profile_idc,
+                compatibility_flags,
+                constraint_flags,
+                level,
+            } => {
+                let profile_space = match profile_space {
+                    1 => "A",
+                    2 => "B",
+                    3 => "C",
+                    _ => "",
+                };
+                let tier = if *tier { "H" } else { "L" };
+                // The compatibility flags are written in reverse bit order.
+                let compatibility = compatibility_flags.reverse_bits();
+                let mut codec = format!(
+                    "{sample_entry}.{profile_space}{profile_idc}.{compatibility:X}.{tier}{level}"
+                );
+                // Trailing bytes of the constraint flags that are zero may be omitted.
+                let significant_bytes = constraint_flags
+                    .iter()
+                    .rposition(|byte| *byte != 0)
+                    .map(|index| index + 1)
+                    .unwrap_or(0);
+                for byte in &constraint_flags[..significant_bytes] {
+                    codec.push_str(&format!(".{byte:X}"));
+                }
+                codec
+            }
+            // RFC 6381 Section 3.3
+            CodecConfiguration::Aac {
+                object_type_indication,
+                audio_object_type,
+            } => {
+                if *object_type_indication == 0x40 {
+                    format!("{sample_entry}.{object_type_indication:02x}.{audio_object_type}")
+                } else {
+                    format!("{sample_entry}.{object_type_indication:02x}")
+                }
+            }
+            // https://aomediacodec.github.io/av1-isobmff/#codecsparam
+            CodecConfiguration::Av1 {
+                profile,
+                level,
+                tier,
+                bit_depth,
+            } => {
+                let tier = if *tier == 0 { "M" } else { "H" };
+                format!("{sample_entry}.{profile}.{level:02}{tier}.{bit_depth:02}")
+            }
+            // https://www.webmproject.org/vp9/mp4/#codecs-parameter-string
+            CodecConfiguration::Vp9 {
+                profile,
+                level,
+                bit_depth,
+            } => format!("{sample_entry}.{profile:02}.{level:02}.{bit_depth:02}"),
+            CodecConfiguration::DolbyVision { profile, level } => {
+                format!("{sample_entry}.{profile:02}.{level:02}")
+            }
+            CodecConfiguration::SampleEntryOnly => sample_entry.clone(),
+        }
+    }
+}
+
+/// Builds the codec information for each of the sample entries.
+pub fn sample_entry_codecs(entries: &[SampleEntry]) -> Vec<SampleEntryCodec> {
+    entries
+        .iter()
+        .map(|entry| {
+            let mut builder = SampleEntryBuilder::new(entry.kind.to_string());
+            for (kind, properties) in &entry.children {
+                builder.add_child(kind, properties);
+            }
+            builder.build()
+        })
+        .collect()
+}
+
+struct SampleEntryBuilder {
+    sample_entry: String,
+    original_format: Option<String>,
+    configuration: Option<CodecConfiguration>,
+}
+
+impl SampleEntryBuilder {
+    fn new(sample_entry: String) -> Self {
+        Self {
+            sample_entry,
+            original_format: None,
+            configuration: None,
+        }
+    }
+
+    fn add_child(&mut self, kind: &str, properties: &AtomProperties) {
+        let number = |key: &str| number_property(properties, key);
+        let configuration = match kind {
+            "frma" => {
+                self.original_format = string_property(properties, "data_format");
+                return;
+            }
+            "avcC" => (|| {
+                Some(CodecConfiguration::Avc {
+                    profile: number("avc_profile_indication")?,
+                    compatibility: number("profile_compatibility")?,
+                    level: number("avc_level_indication")?,
+                })
+            })(),
+            "hvcC" => (|| {
+                Some(CodecConfiguration::Hevc {
+                    profile_space: number("general_profile_space")?,
+                    tier: number("general_tier_flag")? != 0,
+                    profile_idc: number("general_profile_idc")?,
+                    compatibility_flags: u32::from_be_bytes(
+                        bytes_property(properties, "general_profile_compatibility_flags")?
+                            .try_into()
+                            .ok()?,
+                    ),
+                    constraint_flags: bytes_property(
+                        properties,
+                        "general_constraint_indicator_flags",
+                    )?,
+                    level: number("general_level_idc")?,
+                })
+            })(),
+            "esds" => (|| {
+                Some(CodecConfiguration::Aac {
+                    object_type_indication: number("decoder_config_object_type_indication")?,
+                    audio_object_type: number("decoder_specific_profile")?,
+                })
+            })(),
+            "av1C" => (|| {
+                let bit_depth = match (number("high_bitdepth")?, number("twelve_bit")?) {
+                    (0, _) => 8,
+                    (_, 0) => 10,
+                    _ => 12,
+                };
+                Some(CodecConfiguration::Av1 {
+                    profile: number("seq_profile")?,
+                    level: number("seq_level_idx_0")?,
+                    tier: number("seq_tier_0")?,
+                    bit_depth,
+                })
+            })(),
+            "vpcC" => (|| {
+                Some(CodecConfiguration::Vp9 {
+                    profile: number("profile")?,
+                    level: number("level")?,
+                    bit_depth: number("bit_depth")?,
+                })
+            })(),
+            "dvcC" | "dvvC" => (|| {
+                Some(CodecConfiguration::DolbyVision {
+                    profile: number("dv_profile")?,
+                    level: number("dv_level")?,
+                })
+            })(),
+            _ => None,
+        };
+        // Dolby Vision configuration is only used for the codec string when the sample entry is
+        // itself a Dolby Vision sample entry (otherwise it describes a backwards compatible
+        // enhancement of the base layer).
+        if let Some(configuration) = configuration
+            && (self.configuration.is_none()
+                || matches!(configuration, CodecConfiguration::DolbyVision { .. }))
+        {
+            self.configuration = Some(configuration);
+        }
+    }
+
+    fn build(self) -> SampleEntryCodec {
+        let sample_entry = self.original_format.unwrap_or(self.sample_entry);
+        let is_dolby_vision = matches!(sample_entry.as_str(), "dvh1" | "dvhe" | "dva1" | "dvav");
+        let configuration = match self.configuration {
+            Some(CodecConfiguration::DolbyVision { .. }) if !is_dolby_vision => {
+                CodecConfiguration::SampleEntryOnly
+            }
+            Some(configuration) => configuration,
+            None => CodecConfiguration::SampleEntryOnly,
+        };
+        SampleEntryCodec {
+            sample_entry,
+            configuration,
+        }
+    }
+}
+
+// Flag arrays are presented as a comma separated list, either of byte values or of individual bits.
+fn bytes_property(properties: &AtomProperties, key: &str) -> Option<Vec<u8>> {
+    let string = string_property(properties, key)?;
+    let items = string.split(',').map(str::trim).collect::<Vec<&str>>();
+    if items.iter().all(|item| *item == "true" || *item == "false") {
+        Some(
+            items
+                .chunks(8)
+                .map(|bits| {
+                    bits.iter()
+                        .fold(0u8, |byte, bit| (byte << 1) | u8::from(*bit == "true"))
+                })
+                .collect(),
+        )
+    } else {
+        items.iter().map(|item| item.parse::<u8>().ok()).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MediaKind {
+    Video,
+    Audio,
+    Text,
+    Unknown,
+}
+
+fn media_kind(codec: &str) -> MediaKind {
+    match sample_entry_of(codec).as_str() {
+        "avc1" | "avc3" | "hvc1" | "hev1" | "dvh1" | "dvhe" | "dva1" | "dvav" | "av01" | "vp08"
+        | "vp09" | "uncv" => MediaKind::Video,
+        "mp4a" | "ac-3" | "ec-3" | "ac-4" | "opus" | "flac" | "alac" | "mhm1" | "mha1" => {
+            MediaKind::Audio
+        }
+        "wvtt" | "stpp" => MediaKind::Text,
+        _ => MediaKind::Unknown,
+    }
+}
+
+/// Whether the codec (as given in a CODECS attribute) is an audio codec.
+pub fn is_audio_codec(codec: &str) -> bool {
+    media_kind(codec) == MediaKind::Audio
+}
+
+/// The MIME type with which to play media with the given codecs through Media Source Extensions:
+/// `video/mp4` when any of them are video, `audio/mp4` when all of them are audio, and otherwise
+/// (e.g. for subtitles) none.
+pub fn media_source_type(codecs: &[SampleEntryCodec]) -> Option<String> {
+    let codec_strings = codecs
+        .iter()
+        .map(SampleEntryCodec::codec_string)
+        .collect::<Vec<_>>();
+    let kinds = codec_strings
+        .iter()
+        .map(|codec| media_kind(codec))
+        .collect::<Vec<_>>();
+    let container = if kinds.contains(&MediaKind::Video)
+        && kinds
+            .iter()
+            .all(|kind| matches!(kind, MediaKind::Video | MediaKind::Audio))
+    {
+        "video/mp4"
+    } else if !kinds.is_empty() && kinds.iter().all(|kind| *kind == MediaKind::Audio) {
+        "audio/mp4"
+    } else {
+        return None;
+    };
+    Some(format!(
+        "{container}; codecs=\"{}\"",
+        codec_strings.join(",")
+    ))
+}
+
+fn sample_entry_of(codec: &str) -> String {
+    codec
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// The outcome of comparing one codec found in the media against the advertised CODECS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodecComparison {
+    pub actual: String,
+    pub advertised: Option<String>,
+    pub problem: Option<String>,
+}
+
+/// Compares the codecs found in the media against the CODECS attribute of the variant. Codec
+/// strings are compared case-insensitively.
+pub fn compare_codecs(advertised: &str, actual: &[SampleEntryCodec]) -> Vec<CodecComparison> {
+    let advertised = advertised
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>();
+    actual
+        .iter()
+        .map(|codec| {
+            let actual = codec.codec_string();
+            let exact = advertised.iter().find(|a| {
+                if codec.configuration == CodecConfiguration::SampleEntryOnly {
+                    sample_entry_of(a) == sample_entry_of(&actual)
+                } else {
+                    a.eq_ignore_ascii_case(&actual)
+                }
+            });
+            if let Some(advertised) = exact {
+                return CodecComparison {
+                    actual,
+                    advertised: Some(advertised.to_string()),
+                    problem: None,
+                };
+            }
+            if let Some(advertised) = advertised
+                .iter()
+                .find(|a| sample_entry_of(a) == sample_entry_of(&actual))
+            {
+                return CodecComparison {
+                    problem: Some(format!(
+                        "CODECS advertises {advertised} but the media signals {actual} (the \
+                         profile, level or other parameters differ)."
+                    )),
+                    actual,
+                    advertised: Some(advertised.to_string()),
+                };
+            }
+            let kind = media_kind(&actual);
+            if let Some(advertised) = advertised
+                .iter()
+                .find(|a| kind != MediaKind::Unknown && media_kind(a) == kind)
+            {
+                return CodecComparison {
+                    problem: Some(format!(
+                        "CODECS advertises {advertised} but the media contains {actual}."
+                    )),
+                    actual,
+                    advertised: Some(advertised.to_string()),
+                };
+            }
+            CodecComparison {
+                problem: Some(format!(
+                    "The media contains {actual}, which is not listed in CODECS."
+                )),
+                actual,
+                advertised: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        mp4_atom_properties::{AtomPropertyValue, BasicPropertyValue},
+        sample_entries::sample_entries,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn props(entries: Vec<(&'static str, BasicPropertyValue)>) -> AtomProperties {
+        AtomProperties {
+            box_name: "Test",
+            properties: entries
+                .into_iter()
+                .map(|(k, v)| (k.into(), AtomPropertyValue::Basic(v)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn avc_codec_string() {
+        let codec = SampleEntryCodec {
+            sample_entry: String::from("avc1"),
+            configuration: CodecConfiguration::Avc {
+                profile: 0x64,
+                compatibility: 0x00,
+                level: 0x1f,
+            },
+        };
+        assert_eq!("avc1.64001f", codec.codec_string());
+    }
+
+    #[test]
+    fn hevc_codec_string() {
+        let codec = SampleEntryCodec {
+            sample_entry: String::from("hvc1"),
+            configuration: CodecConfiguration::Hevc {
+                profile_space: 0,
+                tier: false,
+                profile_idc: 2,
+                compatibility_flags: 0x20000000,
+                constraint_flags: vec![0xB0, 0, 0, 0, 0, 0],
+                level: 123,
+            },
+        };
+        assert_eq!("hvc1.2.4.L123.B0", codec.codec_string());
+    }
+
+    #[test]
+    fn aac_codec_string() {
+        let codec = SampleEntryCodec {
+            sample_entry: String::from("mp4a"),
+            configuration: CodecConfiguration::Aac {
+                object_type_indication: 0x40,
+                audio_object_type: 2,
+            },
+        };
+        assert_eq!("mp4a.40.2", codec.codec_string());
+    }
+
+    #[test]
+    fn codecs_from_protected_init_segment_boxes() {
+        let empty = props(vec![]);
+        let avcc = props(vec![
+            ("avc_profile_indication", BasicPropertyValue::U8(0x4d)),
+            ("profile_compatibility", BasicPropertyValue::U8(0x40)),
+            ("avc_level_indication", BasicPropertyValue::U8(0x1e)),
+        ]);
+        let frma = props(vec![(
+            "data_format",
+            BasicPropertyValue::String(String::from("avc1")),
+        )]);
+        let atoms = vec![
+            ("moov", 0, &empty),
+            ("trak", 1, &empty),
+            ("stsd", 5, &empty),
+            ("encv", 6, &empty),
+            ("avcC", 7, &avcc),
+            ("sinf", 7, &empty),
+            ("frma", 8, &frma),
+            ("stts", 5, &empty),
+        ];
+        assert_eq!(
+            vec![SampleEntryCodec {
+                sample_entry: String::from("avc1"),
+                configuration: CodecConfiguration::Avc {
+                    profile: 0x4d,
+                    compatibility: 0x40,
+                    level: 0x1e
+                }
+            }],
+            sample_entry_codecs(&sample_entries(atoms))
+        );
+    }
+
+    #[test]
+    fn hevc_flags_are_read_from_byte_lists() {
+        let empty = props(vec![]);
+        let hvcc = props(vec![
+            ("general_profile_space", BasicPropertyValue::U8(0)),
+            ("general_tier_flag", BasicPropertyValue::Bool(false)),
+            ("general_profile_idc", BasicPropertyValue::U8(1)),
+            (
+                "general_profile_compatibility_flags",
+                BasicPropertyValue::String(String::from("96, 0, 0, 0")),
+            ),
+            (
+                "general_constraint_indicator_flags",
+                BasicPropertyValue::String(String::from("144, 0, 0, 0, 0, 0")),
+            ),
+            ("general_level_idc", BasicPropertyValue::U8(93)),
+        ]);
+        let atoms = vec![("stsd", 0, &empty), ("hvc1", 1, &empty), ("hvcC", 2, &hvcc)];
+        assert_eq!(
+            vec![String::from("hvc1.1.6.L93.90")],
+            sample_entry_codecs(&sample_entries(atoms))
+                .iter()
+                .map(SampleEntryCodec::codec_string)
+                .collect::<Vec<String>>()
+        );
+    }
+
+    #[test]
+    fn media_source_type_depends_on_the_kinds_of_track() {
+        let aac = SampleEntryCodec {
+            sample_entry: String::from("mp4a"),
+            configuration: CodecConfiguration::Aac {
+                object_type_indication: 0x40,
+                audio_object_type: 2,
+            },
+        };
+        let avc = SampleEntryCodec {
+            sample_entry: String::from("avc1"),
+            configuration: CodecConfiguration::Avc {
+                profile: 0x64,
+                compatibility: 0x00,
+                level: 0x1f,
+            },
+        };
+        let wvtt = SampleEntryCodec {
+            sample_entry: String::from("wvtt"),
+            configuration: CodecConfiguration::SampleEntryOnly,
+        };
+        assert_eq!(
+            Some(String::from("audio/mp4; codecs=\"mp4a.40.2\"")),
+            media_source_type(std::slice::from_ref(&aac))
+        );
+        assert_eq!(
+            Some(String::from("video/mp4; codecs=\"avc1.64001f,mp4a.40.2\"")),
+            media_source_type(&[avc, aac])
+        );
+        assert_eq!(None, media_source_type(&[wvtt]));
+        assert_eq!(None, media_source_type(&[]));
+    }
+
+    #[test]
+    fn compare_detects_codec_type_mismatch() {
+        let actual = [SampleEntryCodec {
+            sample_entry: String::from("hvc1"),
+            configuration: CodecConfiguration::SampleEntryOnly,
+        }];
+        assert_eq!(
+            vec![CodecComparison {
+                actual: String::from("hvc1"),
+                advertised: Some(String::from("avc1.64001f")),
+                problem: Some(String::from(
+                    "CODECS advertises avc1.64001f but the media contains hvc1."
+                )),
+            }],
+            compare_codecs("avc1.64001f,mp4a.40.2", &actual)
+        );
+    }
+
+    #[test]
+    fn compare_is_case_insensitive_and_detects_profile_mismatch() {
+        let actual = [
+            SampleEntryCodec {
+                sample_entry: String::from("avc1"),
+                configuration: CodecConfiguration::Avc {
+                    profile: 0x64,
+                    compatibility: 0,
+                    level: 0x1f,
+                },
+            },
+            SampleEntryCodec {
+                sample_entry: String::from("mp4a"),
+                configuration: CodecConfiguration::Aac {
+                    object_type_indication: 0x40,
+                    audio_object_type: 5,
+                },
+            },
+        ];
+        let comparisons = compare_codecs("avc1.64001F,mp4a.40.2", &actual);
+        assert_eq!(None, comparisons[0].problem);
+        assert_eq!(Some(String::from("mp4a.40.2")), comparisons[1].advertised);
+        assert!(comparisons[1].problem.is_some());
+    }
+}
@@ -0,0 +1,53 @@
+// Comma-separated values (RFC 4180), for exporting tables that are too long to read in the viewer.
+
+use std::borrow::Cow;
+
+pub const CSV_MIME_TYPE: &str = "text/csv";
+
+/// Joins the rows into CSV, with each row (the header included) ended by a CRLF.
+pub fn csv<Row, Field>(rows: impl IntoIterator<Item = Row>) -> String
+where
+    Row: IntoIterator<Item = Field>,
+    Field: AsRef<str>,
+{
+    let mut s = String::new();
+    for row in rows {
+        for (index, field) in row.into_iter().enumerate() {
+            if index > 0 {
+                s.push(',');
+            }
+            s.push_str(&csv_field(field.as_ref()));
+        }
+        s.push_str("\r\n");
+    }
+    s
+}
+
+// A field is quoted when it holds a separator, a quote, or a line break (as multi-line hex does),
+// with any quotes within it doubled.
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn csv_should_quote_fields_only_when_needed() {
+        assert_eq!(
+            "#,flags\r\n1,\"00 01\n02 03\"\r\n2,\"a, \"\"b\"\"\"\r\n",
+            csv([
+                vec!["#", "flags"],
+                vec!["1", "00 01\n02 03"],
+                vec!["2", "a, \"b\""],
+            ])
+        );
+        assert_eq!("", csv(Vec::<Vec<&str>>::new()));
+    }
+}
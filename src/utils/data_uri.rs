@@ -0,0 +1,142 @@
+// Decoding of data: URIs, which some playlists use to carry a key or an asset list inline rather
+// than leaving it to be requested.
+//
+// RFC 2397, 3. Syntax
+//
+//    dataurl    := "data:" [ mediatype ] [ ";base64" ] "," data
+//    mediatype  := [ type "/" subtype ] *( ";" parameter )
+//    data       := *urlchar
+//    parameter  := attribute "=" value
+
+use base64::prelude::*;
+use std::{error::Error, fmt::Display};
+
+const DEFAULT_MEDIA_TYPE: &str = "text/plain;charset=US-ASCII";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataUri {
+    /// The media type with its parameters, as given in the URI (or the default of text/plain).
+    pub media_type: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataUriError {
+    NotADataUri,
+    MissingComma,
+    InvalidBase64(String),
+}
+impl Error for DataUriError {}
+impl Display for DataUriError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotADataUri => write!(f, "the URI does not use the data scheme"),
+            Self::MissingComma => write!(f, "no comma separates the media type from the data"),
+            Self::InvalidBase64(e) => write!(f, "the data is not valid base64 ({e})"),
+        }
+    }
+}
+
+pub fn is_data_uri(uri: &str) -> bool {
+    uri.get(..5)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+}
+
+/// The media type declared by a data: URI, without its parameters.
+pub fn data_uri_media_type(uri: &str) -> Option<&str> {
+    if !is_data_uri(uri) {
+        return None;
+    }
+    let (header, _) = uri[5..].split_once(',')?;
+    let media_type = header.split(';').next().unwrap_or_default().trim();
+    if media_type.is_empty() {
+        Some("text/plain")
+    } else {
+        Some(media_type)
+    }
+}
+
+pub fn decode_data_uri(uri: &str) -> Result<DataUri, DataUriError> {
+    if !is_data_uri(uri) {
+        return Err(DataUriError::NotADataUri);
+    }
+    let (header, data) = uri[5..].split_once(',').ok_or(DataUriError::MissingComma)?;
+    let (media_type, base64) = match header.rsplit_once(';') {
+        Some((media_type, encoding)) if encoding.trim().eq_ignore_ascii_case("base64") => {
+            (media_type, true)
+        }
+        _ => (header, false),
+    };
+    let media_type = if media_type.trim().is_empty() {
+        String::from(DEFAULT_MEDIA_TYPE)
+    } else {
+        media_type.trim().to_string()
+    };
+    let data = percent_encoding::percent_decode_str(data).collect::<Vec<_>>();
+    let data = if base64 {
+        // Whitespace may have been kept from where the URI was wrapped across lines.
+        let data = data
+            .into_iter()
+            .filter(|byte| !byte.is_ascii_whitespace())
+            .collect::<Vec<_>>();
+        BASE64_STANDARD
+            .decode(&data)
+            .map_err(|e| DataUriError::InvalidBase64(e.to_string()))?
+    } else {
+        data
+    };
+    Ok(DataUri { media_type, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn data_uris_are_decoded() {
+        assert_eq!(
+            Ok(DataUri {
+                media_type: String::from("application/json"),
+                data: br#"{"ASSETS":[]}"#.to_vec(),
+            }),
+            decode_data_uri("data:application/json;base64,eyJBU1NFVFMiOltdfQ==")
+        );
+        assert_eq!(
+            Ok(DataUri {
+                media_type: String::from(DEFAULT_MEDIA_TYPE),
+                data: b"a b,c".to_vec(),
+            }),
+            decode_data_uri("data:,a%20b,c")
+        );
+        assert_eq!(
+            Ok(DataUri {
+                media_type: String::from("text/plain;charset=utf-8"),
+                data: vec![0, 1, 2, 3],
+            }),
+            decode_data_uri("DATA:text/plain;charset=utf-8;base64,AAEC%0AAw==")
+        );
+    }
+
+    #[test]
+    fn invalid_data_uris_are_rejected() {
+        assert_eq!(
+            Err(DataUriError::NotADataUri),
+            decode_data_uri("https://example.com/key")
+        );
+        assert_eq!(
+            Err(DataUriError::MissingComma),
+            decode_data_uri("data:text/plain")
+        );
+        assert!(matches!(
+            decode_data_uri("data:;base64,!!!"),
+            Err(DataUriError::InvalidBase64(_))
+        ));
+        assert_eq!(
+            Some("application/json"),
+            data_uri_media_type("data:application/json;base64,e30=")
+        );
+        assert_eq!(Some("text/plain"), data_uri_media_type("data:,hello"));
+        assert_eq!(None, data_uri_media_type("asset-list.json"));
+    }
+}
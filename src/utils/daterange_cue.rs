@@ -0,0 +1,169 @@
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.5.1
+//
+// CUE
+//
+//    The value of CUE is a quoted-string containing an enumerated-string-
+//    list of Trigger Identifiers.  [...]
+//
+//    PRE:  Indicates that an action is to be triggered before playback of
+//       the primary asset begins, regardless of where playback begins in
+//       the primary asset.
+//
+//    POST:  Indicates that an action is to be triggered after the primary
+//       asset has been played to its end without error.
+//
+//    ONCE:  Indicates that an action is to be triggered once.  It SHOULD
+//       NOT be triggered again, even if the user replays the portion of
+//       the primary asset that includes the trigger point.
+//
+//    The PRE and POST Trigger Identifiers are mutually exclusive.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Cue {
+    pub pre: bool,
+    pub post: bool,
+    pub once: bool,
+    pub unrecognized: Vec<String>,
+}
+
+pub const INTERSTITIAL_CLASS: &str = "com.apple.hls.interstitial";
+
+impl Cue {
+    pub fn parse(value: &str) -> Self {
+        let mut cue = Self::default();
+        for identifier in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match identifier {
+                "PRE" => cue.pre = true,
+                "POST" => cue.post = true,
+                "ONCE" => cue.once = true,
+                unknown => cue.unrecognized.push(unknown.to_string()),
+            }
+        }
+        cue
+    }
+
+    /// A short label suitable for a badge.
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if self.pre {
+            parts.push("pre-roll");
+        }
+        if self.post {
+            parts.push("post-roll");
+        }
+        if !self.pre && !self.post {
+            parts.push("at START-DATE");
+        }
+        if self.once {
+            parts.push("once");
+        }
+        format!("CUE: {}", parts.join(", "))
+    }
+
+    /// A description of when a client is expected to trigger the daterange.
+    pub fn explanation(&self) -> String {
+        let mut sentences = Vec::new();
+        if self.pre {
+            sentences.push(
+                "Triggered before playback of the primary asset begins, regardless of where \
+                 playback starts or of the START-DATE.",
+            );
+        }
+        if self.post {
+            sentences.push(
+                "Triggered after the primary asset has been played to its end without error, \
+                 regardless of the START-DATE.",
+            );
+        }
+        if !self.pre && !self.post {
+            sentences.push("Triggered when playback reaches the START-DATE.");
+        }
+        if self.once {
+            sentences.push(
+                "Triggered at most once; it should not trigger again if the user replays the \
+                 portion of the primary asset that includes the trigger point.",
+            );
+        }
+        sentences.join(" ")
+    }
+
+    /// Problems with the combination of trigger identifiers, taking into account the daterange
+    /// `CLASS` and `X-TIMELINE-OCCUPIES` values.
+    pub fn problems(&self, class: Option<&str>, timeline_occupies: Option<&str>) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.pre && self.post {
+            problems.push(String::from(
+                "The PRE and POST trigger identifiers are mutually exclusive.",
+            ));
+        }
+        for unknown in &self.unrecognized {
+            problems.push(format!("Unrecognized trigger identifier: {unknown}"));
+        }
+        if class == Some(INTERSTITIAL_CLASS)
+            && (self.pre || self.post)
+            && timeline_occupies == Some("RANGE")
+        {
+            problems.push(String::from(
+                "Pre-roll and post-roll interstitials are not positioned on the primary timeline, \
+                 so X-TIMELINE-OCCUPIES=\"RANGE\" cannot apply to them.",
+            ));
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_all_identifiers() {
+        assert_eq!(
+            Cue {
+                pre: true,
+                post: false,
+                once: true,
+                unrecognized: vec![]
+            },
+            Cue::parse("PRE,ONCE")
+        );
+    }
+
+    #[test]
+    fn label_without_pre_or_post_refers_to_start_date() {
+        assert_eq!("CUE: at START-DATE, once", Cue::parse("ONCE").label());
+        assert_eq!("CUE: post-roll", Cue::parse("POST").label());
+    }
+
+    #[test]
+    fn pre_and_post_together_is_a_problem() {
+        assert_eq!(
+            vec![String::from(
+                "The PRE and POST trigger identifiers are mutually exclusive."
+            )],
+            Cue::parse("PRE,POST").problems(None, None)
+        );
+    }
+
+    #[test]
+    fn unknown_identifier_is_a_problem() {
+        assert_eq!(
+            vec![String::from("Unrecognized trigger identifier: MIDDLE")],
+            Cue::parse("MIDDLE").problems(None, None)
+        );
+    }
+
+    #[test]
+    fn interstitial_pre_roll_occupying_range_is_a_problem() {
+        let cue = Cue::parse("PRE");
+        assert_eq!(
+            1,
+            cue.problems(Some(INTERSTITIAL_CLASS), Some("RANGE")).len()
+        );
+        assert!(
+            cue.problems(Some(INTERSTITIAL_CLASS), Some("POINT"))
+                .is_empty()
+        );
+        assert!(cue.problems(Some("com.example"), Some("RANGE")).is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+// Saving of text generated by the viewer (exports and reports), and of the resources that it
+// fetched, as a file, through a temporary link to a blob of the contents.
+
+use crate::utils::{byterange::RequestRange, query_codec::percent_decode};
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use web_sys::{
+    Blob, BlobPropertyBag, HtmlAnchorElement, Url,
+    js_sys::{Array, Uint8Array},
+};
+
+// The object URL is revoked once the download has had time to start.
+const REVOKE_DELAY_MILLIS: i32 = 10_000;
+
+pub fn download_text(file_name: &str, mime_type: &str, contents: &str) {
+    if let Err(e) = try_download(file_name, mime_type, &JsValue::from_str(contents)) {
+        log::error!("failed to download {file_name} due to {e:?}");
+    }
+}
+
+pub fn download_bytes(file_name: &str, mime_type: &str, contents: &[u8]) {
+    if let Err(e) = try_download(file_name, mime_type, &Uint8Array::from(contents)) {
+        log::error!("failed to download {file_name} due to {e:?}");
+    }
+}
+
+/// The name to save the resource at `url` as, which is the last segment of its path (or the
+/// `fallback` without one). A resource that was only fetched in part has the byte range in its
+/// name, so that the parts of a resource do not overwrite one another.
+pub fn download_file_name(url: &str, byterange: Option<RequestRange>, fallback: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let name = path
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty() && !path.ends_with(':'))
+        .map(|name| percent_decode(name).to_string())
+        .unwrap_or_else(|| String::from(fallback));
+    let Some(RequestRange { start, end }) = byterange else {
+        return name;
+    };
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => format!("{stem}_{start}-{end}.{extension}"),
+        _ => format!("{name}_{start}-{end}"),
+    }
+}
+
+fn try_download(file_name: &str, mime_type: &str, contents: &JsValue) -> Result<(), JsValue> {
+    let window = web_sys::window().expect("Window must be defined");
+    let document = window.document().expect("Document must be defined");
+    let options = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&Array::of1(contents), &options)?;
+    let object_url = Url::create_object_url_with_blob(&blob)?;
+    let anchor = document
+        .create_element("a")?
+        .dyn_into::<HtmlAnchorElement>()?;
+    anchor.set_href(&object_url);
+    anchor.set_download(file_name);
+    anchor.click();
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&object_url);
+    });
+    window.set_timeout_with_callback_and_timeout_and_arguments_0(
+        revoke.unchecked_ref(),
+        REVOKE_DELAY_MILLIS,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn download_file_name_should_be_the_last_segment_of_the_path() {
+        assert_eq!(
+            "segment 1.mp4",
+            download_file_name(
+                "https://example.com/hls/segment%201.mp4?token=abc",
+                None,
+                "x"
+            )
+        );
+        assert_eq!(
+            "playlist.m3u8",
+            download_file_name("https://example.com/", None, "playlist.m3u8")
+        );
+    }
+
+    #[test]
+    fn download_file_name_should_include_the_byterange() {
+        let byterange = Some(RequestRange {
+            start: 1000,
+            end: 1999,
+        });
+        assert_eq!(
+            "media_1000-1999.mp4",
+            download_file_name("https://example.com/media.mp4", byterange, "x")
+        );
+        assert_eq!(
+            "media_1000-1999",
+            download_file_name("https://example.com/media", byterange, "x")
+        );
+    }
+}
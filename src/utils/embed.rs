@@ -0,0 +1,197 @@
+// The messages that a page embedding the viewer in an iframe drives it with, and the events that
+// the viewer posts back, so that other dashboards can load playlists into it and read what it
+// found. Commands are objects (or their JSON) with a `type`:
+//
+//   { "type": "load", "url": "https://example.com/master.m3u8" }
+//   { "type": "setHeaders", "headers": { "Authorization": "Bearer token" } }
+//   { "type": "openSegment", "segment": "msn:1234" }
+//
+// where the segment is given as for the `segment` query parameter (`msn:N`, `first` or `last`).
+// Events are objects with the `source` "hls-manifest-viewer" and a `type`: `ready` once commands
+// are listened for, `loaded` when a playlist has been fetched (or failed to be), `validation` with
+// the findings of the playlist, and `error` for a command that was not understood.
+
+use crate::utils::{
+    query_codec::SegmentSelector,
+    settings::is_header_name,
+    validation::{Finding, findings_value},
+};
+use serde_json::{Value, json};
+use url::Url;
+
+/// The `source` of the events, for the embedding page to tell them apart from other messages.
+pub const EVENT_SOURCE: &str = "hls-manifest-viewer";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmbedCommand {
+    Load {
+        url: String,
+    },
+    /// Replaces the headers added to every request, which are then kept as if set in the settings.
+    SetHeaders {
+        headers: Vec<(String, String)>,
+    },
+    OpenSegment {
+        segment: SegmentSelector,
+    },
+}
+
+pub fn parse_command(message: &str) -> Result<EmbedCommand, String> {
+    let message = serde_json::from_str::<Value>(message)
+        .map_err(|e| format!("the command is not JSON: {e}"))?;
+    let string = |name: &str| {
+        message[name]
+            .as_str()
+            .ok_or_else(|| format!("the command has no \"{name}\" string"))
+    };
+    match string("type")? {
+        "load" => Ok(EmbedCommand::Load {
+            url: string("url")?.to_string(),
+        }),
+        "setHeaders" => {
+            let headers = message["headers"]
+                .as_object()
+                .ok_or_else(|| String::from("the command has no \"headers\" object"))?;
+            let headers = headers
+                .iter()
+                .map(|(name, value)| match value.as_str() {
+                    Some(value) if is_header_name(name) => Ok((name.clone(), value.to_string())),
+                    Some(_) => Err(format!("\"{name}\" is not a valid header name")),
+                    None => Err(format!(
+                        "the value of the \"{name}\" header is not a string"
+                    )),
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(EmbedCommand::SetHeaders { headers })
+        }
+        "openSegment" => {
+            let segment = string("segment")?;
+            SegmentSelector::parse(segment)
+                .map(|segment| EmbedCommand::OpenSegment { segment })
+                .ok_or_else(|| format!("\"{segment}\" is not msn:N, first or last"))
+        }
+        unknown => Err(format!("\"{unknown}\" is not a command")),
+    }
+}
+
+pub fn ready_event() -> String {
+    event("ready", json!({}))
+}
+
+/// The event for the playlist requested from `url` having been fetched, from the URL that it was
+/// redirected to and with the status of the response, or having failed to be.
+pub fn loaded_event(url: &str, response: Result<(&str, u16), &str>) -> String {
+    let details = match response {
+        Ok((redirected_url, status)) => json!({
+            "url": url,
+            "ok": true,
+            "responseUrl": redirected_url,
+            "status": status,
+        }),
+        Err(error) => json!({
+            "url": url,
+            "ok": false,
+            "error": error,
+        }),
+    };
+    event("loaded", details)
+}
+
+/// The event for the findings of the playlist at `url`, given as they are exported.
+pub fn validation_event(url: &str, findings: &[Finding]) -> String {
+    let mut details = findings_value(findings);
+    details["url"] = json!(url);
+    event("validation", details)
+}
+
+pub fn error_event(message: &str) -> String {
+    event("error", json!({ "message": message }))
+}
+
+fn event(kind: &str, mut details: Value) -> String {
+    details["source"] = json!(EVENT_SOURCE);
+    details["type"] = json!(kind);
+    details.to_string()
+}
+
+/// The origin of `url` (such as the referrer of the embedded viewer), to post events to, or `None`
+/// when it has no origin that a message can be addressed to.
+pub fn origin_of(url: &str) -> Option<String> {
+    let origin = Url::parse(url).ok()?.origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!(
+            Ok(EmbedCommand::Load {
+                url: String::from("https://example.com/master.m3u8")
+            }),
+            parse_command(r#"{"type":"load","url":"https://example.com/master.m3u8"}"#)
+        );
+        assert_eq!(
+            Ok(EmbedCommand::SetHeaders {
+                headers: vec![(String::from("Authorization"), String::from("Bearer token"))]
+            }),
+            parse_command(r#"{"type":"setHeaders","headers":{"Authorization":"Bearer token"}}"#)
+        );
+        assert_eq!(
+            Ok(EmbedCommand::OpenSegment {
+                segment: SegmentSelector::MediaSequence(1234)
+            }),
+            parse_command(r#"{"type":"openSegment","segment":"msn:1234"}"#)
+        );
+        assert_eq!(
+            Err(String::from("\"Bad Name\" is not a valid header name")),
+            parse_command(r#"{"type":"setHeaders","headers":{"Bad Name":"value"}}"#)
+        );
+        assert_eq!(
+            Err(String::from("\"play\" is not a command")),
+            parse_command(r#"{"type":"play"}"#)
+        );
+    }
+
+    #[test]
+    fn events_are_tagged_with_their_source() {
+        let event = |event: String| serde_json::from_str::<Value>(&event).unwrap();
+        assert_eq!(
+            json!({
+                "source": "hls-manifest-viewer",
+                "type": "loaded",
+                "url": "https://example.com/master.m3u8",
+                "ok": false,
+                "error": "Network error: Failed to fetch",
+            }),
+            event(loaded_event(
+                "https://example.com/master.m3u8",
+                Err("Network error: Failed to fetch")
+            ))
+        );
+        assert_eq!(
+            json!({
+                "source": "hls-manifest-viewer",
+                "type": "validation",
+                "url": "https://example.com/master.m3u8",
+                "errors": 0,
+                "warnings": 0,
+                "findings": [],
+            }),
+            event(validation_event("https://example.com/master.m3u8", &[]))
+        );
+    }
+
+    #[test]
+    fn origin_of_referrer() {
+        assert_eq!(
+            Some(String::from("https://dashboard.example.com:8443")),
+            origin_of("https://dashboard.example.com:8443/streams?id=1")
+        );
+        assert_eq!(None, origin_of("data:text/html,hello"));
+        assert_eq!(None, origin_of(""));
+    }
+}
@@ -0,0 +1,782 @@
+use crate::utils::{
+    fairplay::key_id_as_uuid,
+    hex::{decode_hex, encode_hex},
+    mp4_atom_properties::{AtomProperties, AtomPropertyValue, BasicPropertyValue},
+    playlist_lines::{self, LineKind, TagLine},
+    sample_entries::{sample_entries, string_property},
+};
+use base64::prelude::*;
+
+// Comparison of the EXT-X-KEY tags that apply to a segment against the protection that the
+// segment itself signals.
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.4.4
+//
+//    The EXT-X-KEY tag specifies how to decrypt them.  It applies to every Media Segment and to
+//    every Media Initialization Section declared by an EXT-X-MAP tag that appears between it and
+//    the next EXT-X-KEY tag in the Playlist file with the same KEYFORMAT attribute (or the end of
+//    the Playlist file).
+
+/// An EXT-X-KEY that applies to a segment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentKey {
+    pub method: String,
+    pub keyformat: String,
+    pub keyformat_versions: Option<String>,
+    pub uri: Option<String>,
+    pub iv: Option<String>,
+}
+
+impl SegmentKey {
+    /// The IV that an AES-128 key decrypts the segment with: the IV attribute, or else the Media
+    /// Sequence Number of the segment as a 128-bit big-endian integer. An EXT-X-MAP has no Media
+    /// Sequence Number of its own, so needs the IV attribute.
+    pub fn aes_128_iv(&self, media_sequence: Option<u64>) -> Option<[u8; 16]> {
+        match &self.iv {
+            Some(iv) => key_bytes(iv.strip_prefix("0x").or(iv.strip_prefix("0X"))?),
+            None => media_sequence.map(|sequence| u128::from(sequence).to_be_bytes()),
+        }
+    }
+}
+
+/// 16 bytes given as hex (optionally with a 0x prefix) or base64, as a key is pasted in.
+pub fn key_bytes(value: &str) -> Option<[u8; 16]> {
+    let value = value.trim();
+    let hex = value
+        .strip_prefix("0x")
+        .or(value.strip_prefix("0X"))
+        .unwrap_or(value);
+    // Slicing non-ASCII text into pairs of hex digits could split a character.
+    let bytes = hex
+        .is_ascii()
+        .then(|| decode_hex(hex).ok())
+        .flatten()
+        .or_else(|| BASE64_STANDARD.decode(value).ok())?;
+    bytes.try_into().ok()
+}
+
+/// The keys that apply to the Media Segment with the given Media Sequence Number. An EXT-X-MAP is
+/// treated as being covered by the keys of the first segment that follows it.
+pub fn keys_for_segment(playlist: &str, media_sequence: u64) -> Vec<SegmentKey> {
+    let mut current_sequence = 0;
+    let mut keys = Vec::new();
+    for line in playlist_lines::parse(playlist) {
+        match line.kind {
+            LineKind::Tag(tag) if tag.name == "EXT-X-MEDIA-SEQUENCE" => {
+                current_sequence = tag.value.and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+            }
+            LineKind::Tag(tag) if tag.name == "EXT-X-KEY" => {
+                apply_key(&mut keys, line.number, &tag)
+            }
+            LineKind::Uri(_) if current_sequence == media_sequence => return without_lines(keys),
+            LineKind::Uri(_) => current_sequence += 1,
+            _ => (),
+        }
+    }
+    // The segment may only exist as partial segments so far, in which case there is no URI line.
+    if current_sequence == media_sequence {
+        without_lines(keys)
+    } else {
+        Vec::new()
+    }
+}
+
+/// A Media Segment encrypted with METHOD=AES-128, along with the key and IV it is decrypted with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aes128Segment {
+    pub media_sequence: u64,
+    /// The line of the URI of the segment.
+    pub line: usize,
+    /// The line of the EXT-X-KEY, which changes when the key is rotated.
+    pub key_line: usize,
+    pub key: SegmentKey,
+    pub iv: Option<[u8; 16]>,
+}
+
+/// The Media Segments that are encrypted with METHOD=AES-128, in playlist order.
+pub fn aes_128_segments(playlist: &str) -> Vec<Aes128Segment> {
+    let mut media_sequence = 0;
+    let mut keys = Vec::new();
+    let mut segments = Vec::new();
+    for line in playlist_lines::parse(playlist) {
+        match line.kind {
+            LineKind::Tag(tag) if tag.name == "EXT-X-MEDIA-SEQUENCE" => {
+                media_sequence = tag.value.and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+            }
+            LineKind::Tag(tag) if tag.name == "EXT-X-KEY" => {
+                apply_key(&mut keys, line.number, &tag)
+            }
+            LineKind::Uri(_) => {
+                if let Some((key_line, key)) = keys.iter().find(|(_, key)| key.method == "AES-128")
+                {
+                    segments.push(Aes128Segment {
+                        media_sequence,
+                        line: line.number,
+                        key_line: *key_line,
+                        key: key.clone(),
+                        iv: key.aes_128_iv(Some(media_sequence)),
+                    });
+                }
+                media_sequence += 1;
+            }
+            _ => (),
+        }
+    }
+    segments
+}
+
+// An EXT-X-KEY replaces the key with the same KEYFORMAT, unless its METHOD is NONE, which ends the
+// encryption of every KEYFORMAT.
+fn apply_key(keys: &mut Vec<(usize, SegmentKey)>, line: usize, tag: &TagLine) {
+    let method = tag.attribute_str("METHOD").unwrap_or("NONE");
+    if method == "NONE" {
+        keys.clear();
+        return;
+    }
+    let keyformat = tag.attribute_str("KEYFORMAT").unwrap_or("identity");
+    keys.retain(|(_, key)| key.keyformat != keyformat);
+    keys.push((
+        line,
+        SegmentKey {
+            method: method.to_string(),
+            keyformat: keyformat.to_string(),
+            keyformat_versions: tag.attribute_str("KEYFORMATVERSIONS").map(String::from),
+            uri: tag.attribute_str("URI").map(String::from),
+            iv: tag.attribute_str("IV").map(String::from),
+        },
+    ));
+}
+
+fn without_lines(keys: Vec<(usize, SegmentKey)>) -> Vec<SegmentKey> {
+    keys.into_iter().map(|(_, key)| key).collect()
+}
+
+/// What the boxes of a segment signal about its encryption.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaProtection {
+    /// Whether the segment is a media segment (has a `moof`) rather than an initialization segment.
+    pub fragmented: bool,
+    pub sample_entry_count: usize,
+    /// The kinds of the protected sample entries (e.g. `encv`, `enca`).
+    pub protected_entries: Vec<String>,
+    /// The scheme types from the `schm` boxes (e.g. `cenc`, `cbcs`).
+    pub scheme_types: Vec<String>,
+    /// The sample encryption boxes (`senc`, `saio`, `saiz`) that are present.
+    pub encryption_boxes: Vec<String>,
+    /// The key IDs from the `tenc` and `pssh` boxes, as lowercase hex.
+    pub key_ids: Vec<MediaKeyId>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaKeyId {
+    pub key_id: String,
+    /// The box (and field) that the key ID was found in.
+    pub source: &'static str,
+}
+
+impl MediaProtection {
+    /// Where the key ID (as lowercase hex) is found in the segment.
+    pub fn key_id_sources(&self, key_id: &str) -> Vec<&'static str> {
+        self.key_ids
+            .iter()
+            .filter(|media_key_id| media_key_id.key_id == key_id)
+            .map(|media_key_id| media_key_id.source)
+            .collect()
+    }
+
+    fn push_key_id(&mut self, key_id: String, source: &'static str) {
+        let media_key_id = MediaKeyId { key_id, source };
+        if !self.key_ids.contains(&media_key_id) {
+            self.key_ids.push(media_key_id);
+        }
+    }
+}
+
+const PROTECTED_SAMPLE_ENTRIES: &[&str] = &["encv", "enca", "enct", "encs"];
+const SAMPLE_ENCRYPTION_BOXES: &[&str] = &["senc", "saio", "saiz"];
+
+/// Reads the protection signalled by the boxes of a segment. The boxes are expected in file order
+/// along with their depth in the box hierarchy.
+pub fn media_protection<'a>(
+    atoms: impl IntoIterator<Item = (&'a str, usize, &'a AtomProperties)>,
+) -> MediaProtection {
+    let atoms = atoms.into_iter().collect::<Vec<_>>();
+    let entries = sample_entries(atoms.iter().copied());
+    let mut protection = MediaProtection {
+        sample_entry_count: entries.len(),
+        protected_entries: entries
+            .iter()
+            .filter(|entry| PROTECTED_SAMPLE_ENTRIES.contains(&entry.kind))
+            .map(|entry| entry.kind.to_string())
+            .collect(),
+        ..Default::default()
+    };
+    for (kind, _, properties) in atoms {
+        match kind {
+            "moof" => protection.fragmented = true,
+            "schm" => {
+                if let Some(scheme_type) = string_property(properties, "scheme_type")
+                    && !protection.scheme_types.contains(&scheme_type)
+                {
+                    protection.scheme_types.push(scheme_type);
+                }
+            }
+            "tenc" => {
+                if let Some(key_id) = string_property(properties, "default_KID") {
+                    protection.push_key_id(key_id, "tenc default_KID");
+                }
+            }
+            "pssh" => {
+                for key_id in pssh_key_ids(properties) {
+                    protection.push_key_id(key_id, "pssh KID");
+                }
+                for (source, key_id) in pssh_data_key_ids(properties) {
+                    protection.push_key_id(key_id, source);
+                }
+            }
+            kind if SAMPLE_ENCRYPTION_BOXES.contains(&kind)
+                && !protection.encryption_boxes.iter().any(|b| b == kind) =>
+            {
+                protection.encryption_boxes.push(kind.to_string());
+            }
+            _ => (),
+        }
+    }
+    protection
+}
+
+// The KIDs of a version 1 `pssh` box, which are listed ahead of its system-specific data.
+fn pssh_key_ids(properties: &AtomProperties) -> Vec<String> {
+    let Some((_, AtomPropertyValue::Table(table))) =
+        properties.properties.iter().find(|(k, _)| k == "key_ids")
+    else {
+        return Vec::new();
+    };
+    table
+        .rows
+        .iter()
+        .filter_map(|row| match row.first() {
+            Some(BasicPropertyValue::String(key_id)) => Some(key_id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+// The key IDs of the PlayReady and Widevine data of a `pssh` box, with where they were found.
+fn pssh_data_key_ids(properties: &AtomProperties) -> Vec<(&'static str, String)> {
+    let Some((_, AtomPropertyValue::Table(table))) =
+        properties.properties.iter().find(|(k, _)| k == "pssh_data")
+    else {
+        return Vec::new();
+    };
+    table
+        .rows
+        .iter()
+        .filter_map(|row| match row.as_slice() {
+            [
+                BasicPropertyValue::String(name),
+                BasicPropertyValue::String(value),
+            ] => {
+                if name == "kid" {
+                    Some(("PlayReady KID", playready_key_id(value)?))
+                } else if name.starts_with("key_id ") {
+                    Some(("Widevine key_id", value.clone()))
+                } else if name.starts_with("kid ") {
+                    Some(("ClearKey kid", value.clone()))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// PlayReady writes a key ID as the base64 of a GUID, whose first three fields are little-endian,
+/// so the bytes are swapped back to give the key ID as lowercase hex.
+pub fn playready_key_id(value: &str) -> Option<String> {
+    let mut bytes = BASE64_STANDARD.decode(value.trim()).ok()?;
+    if bytes.len() != 16 {
+        return None;
+    }
+    bytes[0..4].reverse();
+    bytes[4..6].reverse();
+    bytes[6..8].reverse();
+    Some(encode_hex(&bytes))
+}
+
+/// A key ID cross-referenced between the EXT-X-KEY tags that apply to a segment and its boxes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyIdReference {
+    /// The key ID as lowercase hex.
+    pub key_id: String,
+    /// The EXT-X-KEY tags that carry the key ID, by the name of their KEYFORMAT.
+    pub keys: Vec<String>,
+    /// The boxes that carry the key ID.
+    pub media_sources: Vec<&'static str>,
+    pub problems: Vec<String>,
+}
+
+/// Cross-references the key IDs carried by the EXT-X-KEY tags that apply to a segment (each given
+/// by the name of its KEYFORMAT) with those in its boxes. The media is encrypted with the
+/// `default_KID` of the `tenc` box, so every key in the playlist that carries key IDs, and the
+/// `pssh` boxes, are expected to include it. Packaging each DRM system with a different key ID is
+/// a common mistake that only shows up when playback fails on one platform.
+pub fn key_id_references(
+    playlist_key_ids: &[(String, Vec<String>)],
+    media: &MediaProtection,
+) -> Vec<KeyIdReference> {
+    const TENC: &str = "tenc default_KID";
+    let default_key_ids = media
+        .key_ids
+        .iter()
+        .filter(|media_key_id| media_key_id.source == TENC)
+        .map(|media_key_id| media_key_id.key_id.as_str())
+        .collect::<Vec<_>>();
+    let has_pssh_key_ids = media
+        .key_ids
+        .iter()
+        .any(|media_key_id| media_key_id.source != TENC);
+    let mut key_ids = default_key_ids
+        .iter()
+        .map(|key_id| key_id.to_string())
+        .collect::<Vec<_>>();
+    let others = playlist_key_ids
+        .iter()
+        .flat_map(|(_, key_ids)| key_ids.iter())
+        .chain(
+            media
+                .key_ids
+                .iter()
+                .map(|media_key_id| &media_key_id.key_id),
+        );
+    for key_id in others {
+        if !key_ids.contains(key_id) {
+            key_ids.push(key_id.clone());
+        }
+    }
+    key_ids
+        .into_iter()
+        .map(|key_id| {
+            let keys = playlist_key_ids
+                .iter()
+                .filter(|(_, key_ids)| key_ids.contains(&key_id))
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+            let media_sources = media.key_id_sources(&key_id);
+            let mut problems = Vec::new();
+            if default_key_ids.contains(&key_id.as_str()) {
+                for (name, key_ids) in playlist_key_ids {
+                    if !key_ids.is_empty() && !key_ids.contains(&key_id) {
+                        problems.push(format!(
+                            "The {name} EXT-X-KEY carries {} instead.",
+                            key_ids
+                                .iter()
+                                .map(|key_id| key_id_as_uuid(key_id))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ));
+                    }
+                }
+                if has_pssh_key_ids && media_sources.len() == 1 {
+                    problems.push(String::from("No pssh box carries the key ID."));
+                }
+            } else if !keys.is_empty() && !default_key_ids.is_empty() {
+                problems.push(String::from(
+                    "The key ID is not the tenc default_KID that the media is encrypted with.",
+                ));
+            } else if !keys.is_empty() && media_sources.is_empty() && !media.key_ids.is_empty() {
+                problems.push(String::from("The key ID is not found in the segment."));
+            }
+            KeyIdReference {
+                key_id,
+                keys,
+                media_sources,
+                problems,
+            }
+        })
+        .collect()
+}
+
+/// The Common Encryption scheme that a sample encryption METHOD corresponds to.
+pub fn sample_encryption_scheme(method: &str) -> Option<&'static str> {
+    match method {
+        // SAMPLE-AES is the cbcs scheme of Common Encryption, whereas SAMPLE-AES-CTR is cenc.
+        "SAMPLE-AES" => Some("cbcs"),
+        "SAMPLE-AES-CTR" => Some("cenc"),
+        _ => None,
+    }
+}
+
+/// What a box has to do with the encryption of samples, if anything.
+pub fn sample_encryption_role(kind: &str) -> Option<&'static str> {
+    let role = match kind {
+        "encv" | "enca" | "enct" | "encs" => {
+            "A protected sample entry, which stands in for the original one so that a player \
+             without the key does not try to decode the samples."
+        }
+        "sinf" => "Holds how the samples of the sample entry are protected.",
+        "frma" => "The kind of the original sample entry (e.g. avc1 or mp4a).",
+        "schm" => {
+            "The protection scheme, which is cbcs for SAMPLE-AES and cenc for SAMPLE-AES-CTR."
+        }
+        "schi" => "Holds what is specific to the protection scheme.",
+        "tenc" => {
+            "The defaults for the samples of the track: whether they are protected, their key ID \
+             (default_KID), the size of their IVs, and for cbcs the pattern of encrypted and clear \
+             blocks along with any constant IV."
+        }
+        "pssh" => "Data for a DRM system to acquire the key with.",
+        "senc" => {
+            "The IV of each sample (unless the tenc gives a constant IV) and its subsamples, which \
+             are the ranges of clear and encrypted bytes."
+        }
+        "saiz" => "The size of the auxiliary information (the IV and subsamples) of each sample.",
+        "saio" => {
+            "Where the auxiliary information of the samples starts, which is usually within the \
+             senc."
+        }
+        _ => return None,
+    };
+    Some(role)
+}
+
+/// Describes how the protection of the segment contradicts the keys that apply to it, if at all.
+pub fn encryption_problem(keys: &[SegmentKey], media: &MediaProtection) -> Option<String> {
+    let Some(key) = keys.first() else {
+        if media.fragmented && !media.encryption_boxes.is_empty() {
+            return Some(format!(
+                "No EXT-X-KEY applies to the segment (METHOD=NONE), but it contains sample \
+                 encryption boxes ({}).",
+                media.encryption_boxes.join(", ")
+            ));
+        }
+        if !media.protected_entries.is_empty() {
+            return Some(format!(
+                "No EXT-X-KEY applies to the segment (METHOD=NONE), but its sample entries are \
+                 protected ({}).",
+                media.protected_entries.join(", ")
+            ));
+        }
+        return None;
+    };
+    let method = key.method.as_str();
+    match method {
+        // The whole segment is encrypted, so it would not have been readable as ISOBMFF.
+        "AES-128" => Some(String::from(
+            "EXT-X-KEY METHOD=AES-128 encrypts the whole segment, but the segment is readable as \
+             ISOBMFF, so it does not appear to be encrypted.",
+        )),
+        "SAMPLE-AES" | "SAMPLE-AES-CTR" => {
+            if media.fragmented && media.encryption_boxes.is_empty() {
+                return Some(format!(
+                    "EXT-X-KEY METHOD={method} signals sample encryption, but the segment has no \
+                     senc, saio or saiz boxes."
+                ));
+            }
+            if media.sample_entry_count > 0 && media.protected_entries.is_empty() {
+                return Some(format!(
+                    "EXT-X-KEY METHOD={method} signals sample encryption, but none of the sample \
+                     entries are protected (encv, enca)."
+                ));
+            }
+            let expected_scheme = sample_encryption_scheme(method)?;
+            media
+                .scheme_types
+                .iter()
+                .find(|scheme| *scheme != expected_scheme)
+                .map(|scheme| {
+                    format!(
+                        "EXT-X-KEY METHOD={method} corresponds to the {expected_scheme} scheme, \
+                         but the schm box signals {scheme}."
+                    )
+                })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mp4_atom_properties::TablePropertyValue;
+    use pretty_assertions::assert_eq;
+
+    fn methods(playlist: &str, media_sequence: u64) -> Vec<String> {
+        keys_for_segment(playlist, media_sequence)
+            .into_iter()
+            .map(|key| key.method)
+            .collect()
+    }
+
+    #[test]
+    fn keys_apply_until_replaced() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://a\",KEYFORMAT=\"com.apple.streamingkeydelivery\"
+#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"data:text/plain;base64,AAAA\",KEYFORMAT=\"urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed\"
+#EXTINF:4,
+a.mp4
+#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"skd://b\",KEYFORMAT=\"com.apple.streamingkeydelivery\"
+#EXTINF:4,
+b.mp4
+#EXT-X-KEY:METHOD=NONE
+#EXTINF:4,
+c.mp4
+";
+        assert_eq!(2, methods(playlist, 0).len());
+        assert_eq!(2, methods(playlist, 1).len());
+        assert_eq!(Vec::<String>::new(), methods(playlist, 2));
+    }
+
+    #[test]
+    fn missing_sample_encryption_is_reported() {
+        let empty = AtomProperties {
+            box_name: "Test",
+            properties: Vec::new(),
+        };
+        let segment = vec![
+            ("moof", 0, &empty),
+            ("traf", 1, &empty),
+            ("tfhd", 2, &empty),
+            ("trun", 2, &empty),
+        ];
+        let key = SegmentKey {
+            method: String::from("SAMPLE-AES"),
+            keyformat: String::from("identity"),
+            keyformat_versions: None,
+            uri: None,
+            iv: None,
+        };
+        let media = media_protection(segment.clone());
+        assert_eq!(
+            Some(String::from(
+                "EXT-X-KEY METHOD=SAMPLE-AES signals sample encryption, but the segment has no \
+                 senc, saio or saiz boxes."
+            )),
+            encryption_problem(std::slice::from_ref(&key), &media)
+        );
+        assert_eq!(None, encryption_problem(&[], &media));
+
+        let encrypted = [segment, vec![("senc", 2, &empty)]].concat();
+        let media = media_protection(encrypted);
+        assert_eq!(None, encryption_problem(&[key], &media));
+        assert_eq!(
+            Some(String::from(
+                "No EXT-X-KEY applies to the segment (METHOD=NONE), but it contains sample \
+                 encryption boxes (senc)."
+            )),
+            encryption_problem(&[], &media)
+        );
+    }
+
+    #[test]
+    fn scheme_must_match_method() {
+        let empty = AtomProperties {
+            box_name: "Test",
+            properties: Vec::new(),
+        };
+        let schm = AtomProperties {
+            box_name: "SchemeTypeBox",
+            properties: vec![(
+                "scheme_type".into(),
+                AtomPropertyValue::Basic(BasicPropertyValue::String(String::from("cenc"))),
+            )],
+        };
+        let init = vec![
+            ("moov", 0, &empty),
+            ("trak", 1, &empty),
+            ("stsd", 2, &empty),
+            ("encv", 3, &empty),
+            ("sinf", 4, &empty),
+            ("schm", 5, &schm),
+        ];
+        let media = media_protection(init);
+        assert_eq!(vec![String::from("encv")], media.protected_entries);
+        let key = |method: &str| SegmentKey {
+            method: method.to_string(),
+            keyformat: String::from("identity"),
+            keyformat_versions: None,
+            uri: None,
+            iv: None,
+        };
+        assert_eq!(None, encryption_problem(&[key("SAMPLE-AES-CTR")], &media));
+        assert_eq!(
+            Some(String::from(
+                "EXT-X-KEY METHOD=SAMPLE-AES corresponds to the cbcs scheme, but the schm box \
+                 signals cenc."
+            )),
+            encryption_problem(&[key("SAMPLE-AES")], &media)
+        );
+    }
+
+    #[test]
+    fn key_ids_are_collected_from_tenc_and_pssh() {
+        let empty = AtomProperties {
+            box_name: "Test",
+            properties: Vec::new(),
+        };
+        let kid = "9eb4050de44b4802932e27d75083e266";
+        let tenc = AtomProperties {
+            box_name: "TrackEncryptionBox",
+            properties: vec![(
+                "default_KID".into(),
+                AtomPropertyValue::Basic(BasicPropertyValue::String(String::from(kid))),
+            )],
+        };
+        let pssh = AtomProperties {
+            box_name: "ProtectionSystemSpecificHeaderBox",
+            properties: vec![(
+                "key_ids".into(),
+                AtomPropertyValue::Table(TablePropertyValue {
+                    headers: None,
+                    rows: vec![
+                        vec![BasicPropertyValue::String(String::from(kid))],
+                        vec![BasicPropertyValue::String(String::from(
+                            "00000000000000000000000000000001",
+                        ))],
+                    ],
+                }),
+            )],
+        };
+        let init = vec![
+            ("moov", 0, &empty),
+            ("pssh", 1, &pssh),
+            ("trak", 1, &empty),
+            ("tenc", 2, &tenc),
+        ];
+        let media = media_protection(init);
+        assert_eq!(3, media.key_ids.len());
+        assert_eq!(
+            vec!["pssh KID", "tenc default_KID"],
+            media.key_id_sources(kid)
+        );
+        assert_eq!(
+            Vec::<&str>::new(),
+            media.key_id_sources("00000000000000000000000000000002")
+        );
+    }
+
+    #[test]
+    fn playready_key_ids_are_byte_swapped() {
+        assert_eq!(
+            Some(String::from("9eb4050de44b4802932e27d75083e266")),
+            playready_key_id("DQW0nkvkAkiTLifXUIPiZg==")
+        );
+        assert_eq!(None, playready_key_id("AAAA"));
+    }
+
+    #[test]
+    fn key_ids_are_cross_referenced() {
+        let kid = String::from("9eb4050de44b4802932e27d75083e266");
+        let other = String::from("00000000000000000000000000000001");
+        let media = MediaProtection {
+            key_ids: vec![
+                MediaKeyId {
+                    key_id: kid.clone(),
+                    source: "tenc default_KID",
+                },
+                MediaKeyId {
+                    key_id: kid.clone(),
+                    source: "PlayReady KID",
+                },
+            ],
+            ..Default::default()
+        };
+        let playlist = vec![
+            (String::from("FairPlay"), vec![kid.clone()]),
+            (String::from("Widevine"), vec![other.clone()]),
+            (String::from("identity"), Vec::new()),
+        ];
+        assert_eq!(
+            vec![
+                KeyIdReference {
+                    key_id: kid,
+                    keys: vec![String::from("FairPlay")],
+                    media_sources: vec!["tenc default_KID", "PlayReady KID"],
+                    problems: vec![String::from(
+                        "The Widevine EXT-X-KEY carries 00000000-0000-0000-0000-000000000001 \
+                         instead."
+                    )],
+                },
+                KeyIdReference {
+                    key_id: other,
+                    keys: vec![String::from("Widevine")],
+                    media_sources: Vec::new(),
+                    problems: vec![String::from(
+                        "The key ID is not the tenc default_KID that the media is encrypted with."
+                    )],
+                },
+            ],
+            key_id_references(&playlist, &media)
+        );
+    }
+
+    #[test]
+    fn aes_128_iv_defaults_to_the_media_sequence_number() {
+        let key = |iv: Option<&str>| SegmentKey {
+            method: String::from("AES-128"),
+            keyformat: String::from("identity"),
+            keyformat_versions: None,
+            uri: Some(String::from("key.bin")),
+            iv: iv.map(String::from),
+        };
+        let mut expected = [0; 16];
+        expected[14..].copy_from_slice(&[0x01, 0x2c]);
+        assert_eq!(Some(expected), key(None).aes_128_iv(Some(300)));
+        assert_eq!(None, key(None).aes_128_iv(None));
+        assert_eq!(
+            Some([0xab; 16]),
+            key(Some("0xABABABABABABABABABABABABABABABAB")).aes_128_iv(None)
+        );
+        assert_eq!(None, key(Some("ABAB")).aes_128_iv(Some(300)));
+        assert_eq!(Some([0xab; 16]), key_bytes(" q6urq6urq6urq6urq6urqw== "));
+        assert_eq!(None, key_bytes("abab"));
+        assert_eq!(None, key_bytes("é"));
+    }
+
+    #[test]
+    fn aes_128_segments_follow_key_rotation() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXT-X-MEDIA-SEQUENCE:255
+#EXT-X-KEY:METHOD=AES-128,URI=\"k1\"
+#EXTINF:4,
+a.ts
+#EXT-X-KEY:METHOD=AES-128,URI=\"k2\",IV=0x000102030405060708090A0B0C0D0E0F
+#EXTINF:4,
+b.ts
+#EXT-X-KEY:METHOD=NONE
+#EXTINF:4,
+c.ts
+";
+        let segments = aes_128_segments(playlist)
+            .into_iter()
+            .map(|segment| {
+                (
+                    segment.media_sequence,
+                    segment.line,
+                    segment.key_line,
+                    segment.iv.map(|iv| encode_hex(&iv)),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (
+                    255,
+                    6,
+                    4,
+                    Some(String::from("000000000000000000000000000000ff"))
+                ),
+                (
+                    256,
+                    9,
+                    7,
+                    Some(String::from("000102030405060708090a0b0c0d0e0f"))
+                ),
+            ],
+            segments
+        );
+    }
+}
@@ -0,0 +1,189 @@
+// FairPlay Streaming keys are signalled with an EXT-X-KEY whose KEYFORMAT is
+// "com.apple.streamingkeydelivery" and whose URI uses the skd scheme. Everything after "skd://" is
+// handed to the app as the asset ID (the content identifier of the key request), and is otherwise
+// opaque to the player. Packagers have settled on a handful of conventions for what goes there,
+// most of which carry the key ID that the media is encrypted with, so that the key can be matched
+// against the `tenc` and `pssh` boxes of the initialization segment.
+
+use crate::utils::hex::{decode_hex, encode_hex};
+use base64::prelude::*;
+
+pub const FAIRPLAY_KEYFORMAT: &str = "com.apple.streamingkeydelivery";
+
+/// What an skd:// URI carries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkdUri {
+    /// Everything after "skd://", which is what the key request is made for.
+    pub asset_id: String,
+    /// The key ID found in the asset ID, as lowercase hex.
+    pub key_id: Option<String>,
+    pub convention: SkdConvention,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkdConvention {
+    /// The asset ID is the key ID as 32 hex digits.
+    HexKeyId,
+    /// The asset ID is the key ID as a UUID.
+    UuidKeyId,
+    /// The asset ID is the key ID as base64.
+    Base64KeyId,
+    /// The key ID is given by the named query parameter.
+    QueryParameter(String),
+    /// The key ID is the last part of a path, after a key server host or content ID.
+    PathKeyId,
+    /// No key ID could be found, so the asset ID is only meaningful to the key server.
+    Opaque,
+}
+
+const KEY_ID_PARAMETERS: &[&str] = &["kid", "keyid", "key_id", "key-id"];
+
+impl SkdUri {
+    pub fn parse(uri: &str) -> Option<Self> {
+        let scheme = uri.get(..6)?;
+        if !scheme.eq_ignore_ascii_case("skd://") {
+            return None;
+        }
+        let asset_id = uri[6..].to_string();
+        let (key_id, convention) = key_id(&asset_id);
+        Some(Self {
+            asset_id,
+            key_id,
+            convention,
+        })
+    }
+
+    /// How the key ID was found within the asset ID.
+    pub fn convention_description(&self) -> String {
+        match &self.convention {
+            SkdConvention::HexKeyId => String::from("The asset ID is the key ID in hex."),
+            SkdConvention::UuidKeyId => String::from("The asset ID is the key ID as a UUID."),
+            SkdConvention::Base64KeyId => String::from("The asset ID is the key ID in base64."),
+            SkdConvention::QueryParameter(name) => {
+                format!("The key ID is given by the \"{name}\" query parameter of the asset ID.")
+            }
+            SkdConvention::PathKeyId => {
+                String::from("The key ID is the last part of the asset ID, after the key server.")
+            }
+            SkdConvention::Opaque => String::from(
+                "No key ID was found in the asset ID, which is only meaningful to the key server.",
+            ),
+        }
+    }
+}
+
+fn key_id(asset_id: &str) -> (Option<String>, SkdConvention) {
+    if let Some((kid, convention)) = parse_key_id(asset_id) {
+        return (Some(kid), convention);
+    }
+    if let Some((_, query)) = asset_id.split_once('?') {
+        for (name, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            if KEY_ID_PARAMETERS.contains(&name.to_ascii_lowercase().as_str())
+                && let Some((kid, _)) = parse_key_id(value)
+            {
+                return (Some(kid), SkdConvention::QueryParameter(name.to_string()));
+            }
+        }
+    }
+    let path = asset_id.split(['?', '#']).next().unwrap_or_default();
+    if let Some(last) = path
+        .split(['/', ';', ':'])
+        .rev()
+        .find(|part| !part.is_empty())
+        && last.len() < path.len()
+        && let Some((kid, _)) = parse_key_id(last)
+    {
+        return (Some(kid), SkdConvention::PathKeyId);
+    }
+    (None, SkdConvention::Opaque)
+}
+
+/// The 16 bytes of a key ID written as hex, a UUID or base64, as lowercase hex.
+fn parse_key_id(value: &str) -> Option<(String, SkdConvention)> {
+    let is_hex = |s: &str| s.bytes().all(|b| b.is_ascii_hexdigit());
+    if value.len() == 32 && is_hex(value) {
+        return Some((value.to_ascii_lowercase(), SkdConvention::HexKeyId));
+    }
+    let groups = value.split('-').collect::<Vec<_>>();
+    if groups.iter().map(|group| group.len()).eq([8, 4, 4, 4, 12])
+        && groups.iter().all(|g| is_hex(g))
+    {
+        return Some((
+            groups.concat().to_ascii_lowercase(),
+            SkdConvention::UuidKeyId,
+        ));
+    }
+    // Only padded base64 is taken to be a key ID, as 22 letters and digits are as likely to be an
+    // asset ID of the packager's own.
+    let bytes = BASE64_STANDARD
+        .decode(value)
+        .or_else(|_| BASE64_URL_SAFE.decode(value))
+        .ok()?;
+    (bytes.len() == 16).then(|| (encode_hex(&bytes), SkdConvention::Base64KeyId))
+}
+
+/// Formats a key ID given as lowercase hex as a UUID, the form most DRM tools print them in.
+pub fn key_id_as_uuid(key_id: &str) -> String {
+    match decode_hex(key_id) {
+        Ok(bytes) if bytes.len() == 16 => {
+            let hex = encode_hex(&bytes);
+            format!(
+                "{}-{}-{}-{}-{}",
+                &hex[..8],
+                &hex[8..12],
+                &hex[12..16],
+                &hex[16..20],
+                &hex[20..]
+            )
+        }
+        _ => key_id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const KID: &str = "9eb4050de44b4802932e27d75083e266";
+
+    fn parsed(uri: &str) -> (Option<String>, SkdConvention) {
+        let skd = SkdUri::parse(uri).expect("skd URI");
+        (skd.key_id, skd.convention)
+    }
+
+    #[test]
+    fn skd_key_id_conventions() {
+        let kid = Some(String::from(KID));
+        assert_eq!(
+            (kid.clone(), SkdConvention::HexKeyId),
+            parsed("skd://9EB4050DE44B4802932E27D75083E266")
+        );
+        assert_eq!(
+            (kid.clone(), SkdConvention::UuidKeyId),
+            parsed("skd://9eb4050d-e44b-4802-932e-27d75083e266")
+        );
+        assert_eq!(
+            (kid.clone(), SkdConvention::Base64KeyId),
+            parsed("skd://nrQFDeRLSAKTLifXUIPiZg==")
+        );
+        assert_eq!(
+            (
+                kid.clone(),
+                SkdConvention::QueryParameter(String::from("KID"))
+            ),
+            parsed("skd://drm.example.com/fps?KID=9eb4050d-e44b-4802-932e-27d75083e266")
+        );
+        assert_eq!(
+            (kid, SkdConvention::PathKeyId),
+            parsed("skd://fps.example.com/;9eb4050de44b4802932e27d75083e266")
+        );
+        assert_eq!((None, SkdConvention::Opaque), parsed("skd://twelve"));
+        assert_eq!(None, SkdUri::parse("https://example.com/key"));
+    }
+
+    #[test]
+    fn key_ids_are_shown_as_uuids() {
+        assert_eq!("9eb4050d-e44b-4802-932e-27d75083e266", key_id_as_uuid(KID));
+    }
+}
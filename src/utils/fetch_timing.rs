@@ -0,0 +1,147 @@
+// Timing of the requests made by the viewer, measured around the fetch itself and refined with the
+// Resource Timing entry of the request where the browser provides one.
+//
+// For a cross-origin response that does not pass the timing allow check (the server did not send a
+// matching Timing-Allow-Origin header), the detailed attributes of the entry, such as responseStart
+// and encodedBodySize, are zero. The measurement around the fetch is then kept, where the time to
+// first byte is taken as the point the response headers became available to the page.
+//
+// https://www.w3.org/TR/resource-timing/#sec-timing-allow-origin
+
+/// The timing of one request. Times are in milliseconds since the time origin of the page.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FetchTiming {
+    pub start: f64,
+    pub first_byte: f64,
+    pub end: f64,
+    /// The size of the response body as transferred (before any content decoding, where known).
+    pub bytes: u64,
+    /// Whether the timing came from the Resource Timing entry of the request.
+    pub resource_timing: bool,
+}
+
+impl FetchTiming {
+    pub fn time_to_first_byte(&self) -> f64 {
+        (self.first_byte - self.start).max(0.0)
+    }
+
+    pub fn download_duration(&self) -> f64 {
+        (self.end - self.first_byte).max(0.0)
+    }
+
+    /// The throughput of the download of the body, in bits per second.
+    pub fn throughput(&self) -> Option<f64> {
+        let duration = self.download_duration();
+        if duration <= 0.0 || self.bytes == 0 {
+            return None;
+        }
+        Some((self.bytes * 8) as f64 / (duration / 1000.0))
+    }
+
+    /// Replaces the measured timing with that of the Resource Timing entry of the request, when the
+    /// entry passed the timing allow check. An entry that started before the measurement belongs to
+    /// an earlier request for the same URL.
+    pub fn refine(self, entry: Option<ResourceTimingEntry>) -> Self {
+        let Some(entry) =
+            entry.filter(|entry| entry.response_start > 0.0 && entry.start_time >= self.start)
+        else {
+            return self;
+        };
+        let bytes = if entry.encoded_body_size > 0.0 {
+            entry.encoded_body_size as u64
+        } else {
+            self.bytes
+        };
+        Self {
+            start: entry.start_time,
+            first_byte: entry.response_start,
+            end: entry.response_end.max(entry.response_start),
+            bytes,
+            resource_timing: true,
+        }
+    }
+}
+
+/// The attributes of a `PerformanceResourceTiming` entry that the timing is refined with.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourceTimingEntry {
+    pub start_time: f64,
+    pub response_start: f64,
+    pub response_end: f64,
+    pub encoded_body_size: f64,
+}
+
+/// Formats a throughput in bits per second with a unit suited to its magnitude.
+pub fn format_throughput(bits_per_second: f64) -> String {
+    if bits_per_second >= 1_000_000.0 {
+        format!("{:.2} Mbps", bits_per_second / 1_000_000.0)
+    } else if bits_per_second >= 1_000.0 {
+        format!("{:.1} kbps", bits_per_second / 1_000.0)
+    } else {
+        format!("{bits_per_second:.0} bps")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const MEASURED: FetchTiming = FetchTiming {
+        start: 100.0,
+        first_byte: 180.0,
+        end: 280.0,
+        bytes: 250_000,
+        resource_timing: false,
+    };
+
+    #[test]
+    fn measured_timing_is_derived() {
+        assert_eq!(80.0, MEASURED.time_to_first_byte());
+        assert_eq!(100.0, MEASURED.download_duration());
+        assert_eq!(Some(20_000_000.0), MEASURED.throughput());
+        assert_eq!(
+            None,
+            FetchTiming {
+                end: 180.0,
+                ..MEASURED
+            }
+            .throughput()
+        );
+        assert_eq!("20.00 Mbps", format_throughput(20_000_000.0));
+        assert_eq!("512.0 kbps", format_throughput(512_000.0));
+        assert_eq!("800 bps", format_throughput(800.0));
+    }
+
+    #[test]
+    fn resource_timing_is_used_only_when_allowed() {
+        let restricted = ResourceTimingEntry {
+            start_time: 101.0,
+            response_start: 0.0,
+            response_end: 279.0,
+            encoded_body_size: 0.0,
+        };
+        assert_eq!(MEASURED, MEASURED.refine(Some(restricted)));
+        assert_eq!(MEASURED, MEASURED.refine(None));
+        let allowed = ResourceTimingEntry {
+            response_start: 150.0,
+            encoded_body_size: 200_000.0,
+            ..restricted
+        };
+        let stale = ResourceTimingEntry {
+            start_time: 20.0,
+            ..allowed
+        };
+        assert_eq!(MEASURED, MEASURED.refine(Some(stale)));
+        assert_eq!(
+            FetchTiming {
+                start: 101.0,
+                first_byte: 150.0,
+                end: 279.0,
+                bytes: 200_000,
+                resource_timing: true,
+            },
+            MEASURED.refine(Some(allowed))
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use crate::utils::{
+    mp4_atom_properties::AtomProperties,
+    sample_entries::{number_property, string_property, table_column_numbers},
+};
+use std::collections::HashMap;
+
+// Measurement of the video frame rate from the sample timing of an ISOBMFF segment, for comparison
+// against the FRAME-RATE attribute of the variant.
+//
+// The timescale and handler of each track are only found in the initialization segment, whereas
+// for fragmented media the sample durations are found in the media segments (`tfhd`/`trun`, falling
+// back to the defaults in `trex`).
+
+/// Sample durations as runs of (sample count, duration in timescale units).
+type DurationRuns = Vec<(u64, u64)>;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackTiming {
+    pub track_id: u32,
+    pub handler: Option<String>,
+    pub timescale: Option<u32>,
+    /// The `trex` default sample duration for fragments of this track.
+    pub default_sample_duration: Option<u32>,
+    /// The `stts` entries, which are only populated for non-fragmented media.
+    pub sample_durations: DurationRuns,
+}
+
+/// Reads the timing information of each track from the boxes of an initialization segment. The
+/// boxes are expected in file order along with their depth in the box hierarchy.
+pub fn track_timings<'a>(
+    atoms: impl IntoIterator<Item = (&'a str, usize, &'a AtomProperties)>,
+) -> Vec<TrackTiming> {
+    let mut tracks = Vec::new();
+    let mut trex_defaults = HashMap::new();
+    let mut current: Option<(usize, TrackTiming)> = None;
+    for (kind, depth, properties) in atoms {
+        if let Some((trak_depth, _)) = &current
+            && depth <= *trak_depth
+            && let Some((_, track)) = current.take()
+        {
+            tracks.push(track);
+        }
+        match (kind, &mut current) {
+            ("trak", _) => current = Some((depth, TrackTiming::default())),
+            ("trex", _) => {
+                if let Some(track_id) = number_property::<u32>(properties, "track_id") {
+                    let default = number_property::<u32>(properties, "default_sample_duration");
+                    trex_defaults.insert(track_id, default);
+                }
+            }
+            ("tkhd", Some((_, track))) => {
+                track.track_id = number_property(properties, "track_id").unwrap_or_default();
+            }
+            ("mdhd", Some((_, track))) => {
+                track.timescale = number_property(properties, "timescale")
+            }
+            // Only the handler of the media box (trak/mdia/hdlr) identifies the track type, as
+            // opposed to that of any metadata box within the track.
+            ("hdlr", Some((trak_depth, track))) if depth == *trak_depth + 2 => {
+                track.handler = string_property(properties, "handler");
+            }
+            ("stts", Some((_, track))) => {
+                let counts = table_column_numbers(properties, "entries", "count");
+                let deltas = table_column_numbers(properties, "entries", "delta");
+                if let (Some(counts), Some(deltas)) = (counts, deltas) {
+                    track.sample_durations = counts
+                        .into_iter()
+                        .zip(deltas)
+                        .filter_map(|(count, delta)| Some((count?, delta?)))
+                        .collect();
+                }
+            }
+            _ => (),
+        }
+    }
+    if let Some((_, track)) = current {
+        tracks.push(track);
+    }
+    for track in &mut tracks {
+        track.default_sample_duration = trex_defaults.get(&track.track_id).copied().flatten();
+    }
+    tracks
+}
+
+/// Reads the sample durations of each track fragment from the boxes of a media segment, keyed by
+/// track ID.
+pub fn fragment_durations<'a>(
+    atoms: impl IntoIterator<Item = (&'a str, usize, &'a AtomProperties)>,
+    tracks: &[TrackTiming],
+) -> HashMap<u32, DurationRuns> {
+    let mut durations = HashMap::<u32, DurationRuns>::new();
+    let mut track_id = None;
+    let mut default_duration = None;
+    for (kind, _, properties) in atoms {
+        match kind {
+            "tfhd" => {
+                track_id = number_property::<u32>(properties, "track_id");
+                default_duration = number_property::<u64>(properties, "default_sample_duration")
+                    .or_else(|| {
+                        tracks
+                            .iter()
+                            .find(|track| Some(track.track_id) == track_id)
+                            .and_then(|track| track.default_sample_duration)
+                            .map(u64::from)
+                    });
+            }
+            "trun" => {
+                let Some(track_id) = track_id else {
+                    continue;
+                };
+                let Some(entries) = table_column_numbers(properties, "entries", "duration") else {
+                    continue;
+                };
+                let runs = durations.entry(track_id).or_default();
+                for duration in entries {
+                    if let Some(duration) = duration.or(default_duration) {
+                        runs.push((1, duration));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    durations
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeasuredFrameRate {
+    pub frames_per_second: f64,
+    /// Whether every sample has the same duration.
+    pub constant: bool,
+}
+
+/// Measures the frame rate of each video track, preferring the durations found in the fragments
+/// of a media segment, then the `stts` entries, and finally the `trex` default duration.
+pub fn video_frame_rates(
+    tracks: &[TrackTiming],
+    fragments: &HashMap<u32, DurationRuns>,
+) -> Vec<MeasuredFrameRate> {
+    tracks
+        .iter()
+        .filter(|track| track.handler.as_deref() == Some("vide"))
+        .filter_map(|track| {
+            let timescale = track.timescale.filter(|t| *t > 0)?;
+            let runs = match fragments.get(&track.track_id) {
+                Some(runs) if !runs.is_empty() => runs.clone(),
+                _ if !track.sample_durations.is_empty() => track.sample_durations.clone(),
+                _ => vec![(1, u64::from(track.default_sample_duration?))],
+            };
+            frame_rate(timescale, &runs)
+        })
+        .collect()
+}
+
+fn frame_rate(timescale: u32, runs: &[(u64, u64)]) -> Option<MeasuredFrameRate> {
+    let samples = runs.iter().map(|(count, _)| count).sum::<u64>();
+    let duration = runs
+        .iter()
+        .map(|(count, duration)| count * duration)
+        .sum::<u64>();
+    if samples == 0 || duration == 0 {
+        return None;
+    }
+    let first_duration = runs.first().map(|(_, duration)| *duration);
+    Some(MeasuredFrameRate {
+        frames_per_second: samples as f64 * f64::from(timescale) / duration as f64,
+        constant: runs
+            .iter()
+            .all(|(_, duration)| Some(*duration) == first_duration),
+    })
+}
+
+// FRAME-RATE is a decimal-floating-point that "SHOULD be rounded to three decimal places", so any
+// difference beyond that rounding is reported (e.g. 29.97 vs 30).
+const FRAME_RATE_TOLERANCE: f64 = 0.001;
+
+/// Describes how the measured frame rate differs from the advertised FRAME-RATE, if at all.
+pub fn frame_rate_problem(advertised: f64, measured: MeasuredFrameRate) -> Option<String> {
+    if (advertised - measured.frames_per_second).abs() <= FRAME_RATE_TOLERANCE {
+        return None;
+    }
+    let variation = if measured.constant {
+        ""
+    } else {
+        " on average (sample durations vary)"
+    };
+    Some(format!(
+        "FRAME-RATE is {advertised} but the video samples are timed at {:.3} frames per \
+         second{variation}.",
+        measured.frames_per_second
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mp4_atom_properties::{
+        AtomPropertyValue, BasicPropertyValue, TablePropertyValue,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn props(entries: Vec<(&'static str, AtomPropertyValue)>) -> AtomProperties {
+        AtomProperties {
+            box_name: "Test",
+            properties: entries.into_iter().map(|(k, v)| (k.into(), v)).collect(),
+        }
+    }
+
+    fn basic(value: BasicPropertyValue) -> AtomPropertyValue {
+        AtomPropertyValue::Basic(value)
+    }
+
+    #[test]
+    fn frame_rate_is_measured_from_fragment_durations() {
+        let empty = props(vec![]);
+        let tkhd = props(vec![("track_id", basic(BasicPropertyValue::U32(1)))]);
+        let mdhd = props(vec![("timescale", basic(BasicPropertyValue::U32(30000)))]);
+        let hdlr = props(vec![(
+            "handler",
+            basic(BasicPropertyValue::String(String::from("vide"))),
+        )]);
+        let trex = props(vec![
+            ("track_id", basic(BasicPropertyValue::U32(1))),
+            (
+                "default_sample_duration",
+                basic(BasicPropertyValue::U32(1001)),
+            ),
+        ]);
+        let init = vec![
+            ("moov", 0, &empty),
+            ("trak", 1, &empty),
+            ("tkhd", 2, &tkhd),
+            ("mdia", 2, &empty),
+            ("mdhd", 3, &mdhd),
+            ("hdlr", 3, &hdlr),
+            ("mvex", 1, &empty),
+            ("trex", 2, &trex),
+        ];
+        let tracks = track_timings(init);
+        assert_eq!(Some(1001), tracks[0].default_sample_duration);
+
+        let tfhd = props(vec![
+            ("track_id", basic(BasicPropertyValue::U32(1))),
+            (
+                "default_sample_duration",
+                basic(BasicPropertyValue::String(String::new())),
+            ),
+        ]);
+        let trun = props(vec![(
+            "entries",
+            AtomPropertyValue::Table(TablePropertyValue {
+                headers: Some(vec!["#", "duration", "size", "flags", "cts"]),
+                rows: vec![
+                    vec![
+                        BasicPropertyValue::Usize(1),
+                        BasicPropertyValue::String(String::new()),
+                    ],
+                    vec![
+                        BasicPropertyValue::Usize(2),
+                        BasicPropertyValue::String(String::new()),
+                    ],
+                ],
+            }),
+        )]);
+        let segment = vec![
+            ("moof", 0, &empty),
+            ("traf", 1, &empty),
+            ("tfhd", 2, &tfhd),
+            ("trun", 2, &trun),
+        ];
+        let fragments = fragment_durations(segment, &tracks);
+        let measured = video_frame_rates(&tracks, &fragments);
+        assert_eq!(1, measured.len());
+        assert!(measured[0].constant);
+        assert_eq!(None, frame_rate_problem(29.97, measured[0]));
+        assert_eq!(
+            Some(String::from(
+                "FRAME-RATE is 30 but the video samples are timed at 29.970 frames per second."
+            )),
+            frame_rate_problem(30.0, measured[0])
+        );
+    }
+
+    #[test]
+    fn varying_durations_are_reported_as_an_average() {
+        let measured = frame_rate(90000, &[(1, 3000), (1, 6000)]).unwrap();
+        assert!(!measured.constant);
+        assert_eq!(
+            Some(String::from(
+                "FRAME-RATE is 30 but the video samples are timed at 20.000 frames per second on \
+                 average (sample durations vary)."
+            )),
+            frame_rate_problem(30.0, measured)
+        );
+    }
+}
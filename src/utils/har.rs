@@ -0,0 +1,284 @@
+// A record of the requests made during the session, which can be exported in the HTTP Archive
+// format so that it can be attached to tickets or opened in other tools. With the bodies of the
+// responses included, the export is a session bundle, which can be imported again to look into the
+// session offline (see `session_bundle`).
+//
+// http://www.softwareishard.com/blog/har-12-spec/
+
+use crate::utils::{fetch_timing::FetchTiming, network::ResponseHeaders};
+use base64::prelude::*;
+use serde_json::{Value, json};
+use std::cell::RefCell;
+use url::Url;
+
+// Only the most recent requests are kept, so that a long session does not keep growing the log.
+const MAX_RECORDED_REQUESTS: usize = 1000;
+// Bodies take far more memory than the rest of a request, so only so much of them is kept, letting
+// the oldest go first. Their requests are still kept, without a body.
+const MAX_RECORDED_BODY_BYTES: usize = 128 * 1024 * 1024;
+
+thread_local! {
+    static RECORDED_REQUESTS: RefCell<Vec<RecordedRequest>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A request made during the session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    /// The wall clock time at which the request started, as an ISO 8601 date.
+    pub started_date_time: String,
+    pub method: &'static str,
+    pub url: String,
+    /// The URL that was asked for, when the request went through the proxy under another.
+    pub original_url: Option<String>,
+    pub request_headers: Vec<(String, String)>,
+    /// The response, when one was received.
+    pub response: Option<ResponseHeaders>,
+    pub timing: FetchTiming,
+    pub error: Option<String>,
+    /// The body of the response, until it is let go to keep within the budget for bodies.
+    pub body: Option<Vec<u8>>,
+}
+
+pub fn record_request(request: RecordedRequest) {
+    RECORDED_REQUESTS.with_borrow_mut(|requests| {
+        if requests.len() == MAX_RECORDED_REQUESTS {
+            requests.remove(0);
+        }
+        requests.push(request);
+        let mut body_bytes = requests
+            .iter()
+            .filter_map(|request| request.body.as_ref())
+            .map(Vec::len)
+            .sum::<usize>();
+        for request in requests.iter_mut() {
+            if body_bytes <= MAX_RECORDED_BODY_BYTES {
+                break;
+            }
+            if let Some(body) = request.body.take() {
+                body_bytes -= body.len();
+            }
+        }
+    });
+}
+
+/// The requests recorded so far, as a HAR document.
+pub fn session_har() -> String {
+    let har = RECORDED_REQUESTS.with_borrow(|requests| to_har(requests, false));
+    serde_json::to_string_pretty(&har).unwrap_or_default()
+}
+
+/// The requests recorded so far along with the bodies of their responses, as a HAR document that
+/// also gives the URL of the viewer, so that importing it opens the same view.
+pub fn session_bundle(viewer_url: &str) -> String {
+    let mut har = RECORDED_REQUESTS.with_borrow(|requests| to_har(requests, true));
+    har["log"]["_viewerUrl"] = json!(viewer_url);
+    serde_json::to_string_pretty(&har).unwrap_or_default()
+}
+
+fn to_har(requests: &[RecordedRequest], with_bodies: bool) -> Value {
+    json!({
+        "log": {
+            "version": "1.2",
+            "creator": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "entries": requests
+                .iter()
+                .map(|request| entry(request, with_bodies))
+                .collect::<Vec<_>>(),
+        }
+    })
+}
+
+fn entry(request: &RecordedRequest, with_body: bool) -> Value {
+    let timing = &request.timing;
+    let (status, status_text, response_headers) = match &request.response {
+        Some(response) => (
+            response.status,
+            response.status_text.as_str(),
+            response.headers.as_slice(),
+        ),
+        None => (0, "", [].as_slice()),
+    };
+    let mime_type = request
+        .response
+        .as_ref()
+        .and_then(|response| response.header("Content-Type"))
+        .unwrap_or_default();
+    let mut entry = json!({
+        "startedDateTime": request.started_date_time,
+        "time": timing.time_to_first_byte() + timing.download_duration(),
+        "request": {
+            "method": request.method,
+            "url": request.url,
+            "httpVersion": "",
+            "cookies": [],
+            "headers": headers(&request.request_headers),
+            "queryString": query_string(&request.url),
+            "headersSize": -1,
+            "bodySize": 0,
+        },
+        "response": {
+            "status": status,
+            "statusText": status_text,
+            "httpVersion": "",
+            "cookies": [],
+            "headers": headers(response_headers),
+            "content": {
+                "size": timing.bytes,
+                "mimeType": mime_type,
+            },
+            "redirectURL": "",
+            "headersSize": -1,
+            "bodySize": if request.response.is_some() { timing.bytes as i64 } else { -1 },
+        },
+        "cache": {},
+        "timings": {
+            "send": 0,
+            "wait": timing.time_to_first_byte(),
+            "receive": timing.download_duration(),
+        },
+    });
+    // Custom fields are prefixed with an underscore.
+    if let Some(error) = &request.error {
+        entry["_error"] = json!(error);
+    }
+    if let Some(original_url) = &request.original_url {
+        entry["_originalUrl"] = json!(original_url);
+    }
+    // A body is given as text when it is text, which keeps playlists readable, and as base64
+    // otherwise. Either way, the bytes are given back as they were when the bundle is imported.
+    if with_body && let Some(body) = &request.body {
+        entry["response"]["content"]["text"] = match std::str::from_utf8(body) {
+            Ok(text) => json!(text),
+            Err(_) => {
+                entry["response"]["content"]["encoding"] = json!("base64");
+                json!(BASE64_STANDARD.encode(body))
+            }
+        };
+    }
+    entry
+}
+
+fn headers(headers: &[(String, String)]) -> Vec<Value> {
+    headers
+        .iter()
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect()
+}
+
+fn query_string(url: &str) -> Vec<Value> {
+    let Ok(url) = Url::parse(url) else {
+        return Vec::new();
+    };
+    url.query_pairs()
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn requests_are_converted_to_har_entries() {
+        let request = RecordedRequest {
+            started_date_time: String::from("2025-01-01T00:00:00.000Z"),
+            method: "GET",
+            url: String::from("https://example.com/a.m4s?token=abc"),
+            original_url: None,
+            request_headers: vec![(String::from("Range"), String::from("bytes=0-99"))],
+            response: Some(ResponseHeaders {
+                url: String::from("https://example.com/a.m4s?token=abc"),
+                status: 206,
+                status_text: String::from("Partial Content"),
+                headers: vec![(String::from("content-type"), String::from("video/mp4"))],
+            }),
+            timing: FetchTiming {
+                start: 10.0,
+                first_byte: 30.0,
+                end: 35.0,
+                bytes: 100,
+                resource_timing: false,
+            },
+            error: None,
+            body: Some(vec![0xff; 100]),
+        };
+        let har = to_har(&[request], false);
+        let entry = &har["log"]["entries"][0];
+        assert_eq!(json!(25.0), entry["time"]);
+        assert_eq!(
+            json!([{ "name": "token", "value": "abc" }]),
+            entry["request"]["queryString"]
+        );
+        assert_eq!(
+            json!([{ "name": "Range", "value": "bytes=0-99" }]),
+            entry["request"]["headers"]
+        );
+        assert_eq!(json!(206), entry["response"]["status"]);
+        assert_eq!(json!("video/mp4"), entry["response"]["content"]["mimeType"]);
+        assert_eq!(json!(100), entry["response"]["bodySize"]);
+        assert_eq!(json!(20.0), entry["timings"]["wait"]);
+        assert_eq!(Value::Null, entry["_error"]);
+        assert_eq!(Value::Null, entry["response"]["content"]["text"]);
+    }
+
+    #[test]
+    fn failed_requests_keep_the_error() {
+        let request = RecordedRequest {
+            started_date_time: String::from("2025-01-01T00:00:00.000Z"),
+            method: "HEAD",
+            url: String::from("https://example.com/a.m3u8"),
+            original_url: None,
+            request_headers: Vec::new(),
+            response: None,
+            timing: FetchTiming::default(),
+            error: Some(String::from("Network error: Failed to fetch")),
+            body: None,
+        };
+        let entry = &to_har(&[request], false)["log"]["entries"][0];
+        assert_eq!(json!("HEAD"), entry["request"]["method"]);
+        assert_eq!(json!(0), entry["response"]["status"]);
+        assert_eq!(json!(-1), entry["response"]["bodySize"]);
+        assert_eq!(json!("Network error: Failed to fetch"), entry["_error"]);
+    }
+
+    #[test]
+    fn bundles_keep_bodies_as_text_or_base64() {
+        let request = |url: &str, body: &[u8]| RecordedRequest {
+            started_date_time: String::from("2025-01-01T00:00:00.000Z"),
+            method: "GET",
+            url: format!("https://proxy.example.com/?url={url}"),
+            original_url: Some(url.to_string()),
+            request_headers: Vec::new(),
+            response: Some(ResponseHeaders::default()),
+            timing: FetchTiming::default(),
+            error: None,
+            body: Some(body.to_vec()),
+        };
+        let har = to_har(
+            &[
+                request("a.m3u8", b"#EXTM3U\n"),
+                request("a.m4s", &[0x00, 0x00, 0x00, 0x08, b'f', b'r', b'e', 0xee]),
+            ],
+            true,
+        );
+        let entries = &har["log"]["entries"];
+        assert_eq!(json!("a.m3u8"), entries[0]["_originalUrl"]);
+        assert_eq!(
+            json!("#EXTM3U\n"),
+            entries[0]["response"]["content"]["text"]
+        );
+        assert_eq!(Value::Null, entries[0]["response"]["content"]["encoding"]);
+        assert_eq!(
+            json!("AAAACGZyZe4="),
+            entries[1]["response"]["content"]["text"]
+        );
+        assert_eq!(
+            json!("base64"),
+            entries[1]["response"]["content"]["encoding"]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::{error::Error, fmt::Display, num::ParseIntError};
+use std::{error::Error, fmt::Display, num::ParseIntError, ops::Range};
 
 const HEX_CHARS_LOWER: &[u8; 16] = b"0123456789abcdef";
 
@@ -42,3 +42,124 @@ impl Display for DecodeHexError {
     }
 }
 impl Error for DecodeHexError {}
+
+const HEX_CHARS_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// The number of bytes of a hex property that are laid out at a time, as laying out a large payload
+/// (e.g. pssh data or an mdat) all at once holds up the page. It is a whole number of rows.
+pub const HEX_PAGE_LENGTH: usize = 4096;
+
+/// Lays out bytes as uppercase hex in rows of 16 bytes, split into groups of 4 bytes.
+pub fn hex_rows(bytes: &[u8]) -> String {
+    // Each byte takes 2 digits and is followed by a space, with an extra space between groups.
+    let mut s = String::with_capacity(bytes.len() * 3 + bytes.len() / 4);
+    for (index, byte) in bytes.iter().enumerate() {
+        if index > 0 {
+            s.push_str(match index % 16 {
+                0 => "\n",
+                4 | 8 | 12 => "  ",
+                _ => " ",
+            });
+        }
+        s.push(HEX_CHARS_UPPER[(byte >> 4) as usize] as char);
+        s.push(HEX_CHARS_UPPER[(byte & 0x0F) as usize] as char);
+    }
+    s
+}
+
+/// The number of bytes shown on each line of a hex dump.
+pub const HEX_DUMP_LINE_LENGTH: usize = 16;
+
+/// A line of a hex dump, where each byte is given along with the index of the region (e.g. the box)
+/// that it belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HexDumpLine {
+    pub offset: usize,
+    pub bytes: Vec<(u8, Option<usize>)>,
+}
+
+/// The lines of a hex dump of `data` within `range`, which starts at the start of a line. The
+/// regions are expected to be in order and not to overlap.
+pub fn hex_dump_lines(
+    data: &[u8],
+    range: Range<usize>,
+    regions: &[Range<usize>],
+) -> Vec<HexDumpLine> {
+    let range = range.start.min(data.len())..range.end.min(data.len());
+    data[range.clone()]
+        .chunks(HEX_DUMP_LINE_LENGTH)
+        .enumerate()
+        .map(|(index, bytes)| {
+            let offset = range.start + index * HEX_DUMP_LINE_LENGTH;
+            HexDumpLine {
+                offset,
+                bytes: bytes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, byte)| (*byte, region_at(regions, offset + i)))
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// The index of the region that contains the offset, given regions that are in order and do not
+/// overlap.
+pub fn region_at(regions: &[Range<usize>], offset: usize) -> Option<usize> {
+    let index = regions
+        .partition_point(|region| region.start <= offset)
+        .checked_sub(1)?;
+    regions[index].contains(&offset).then_some(index)
+}
+
+/// The character shown for the byte alongside the hex, which is `.` for anything but printable
+/// ASCII.
+pub fn printable_char(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        char::from(byte)
+    } else {
+        '.'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn region_at_should_skip_gaps_between_regions() {
+        let regions = [0..8, 8..16, 24..40];
+        assert_eq!(Some(0), region_at(&regions, 0));
+        assert_eq!(Some(1), region_at(&regions, 15));
+        assert_eq!(None, region_at(&regions, 20));
+        assert_eq!(Some(2), region_at(&regions, 24));
+        assert_eq!(None, region_at(&regions, 40));
+    }
+
+    #[test]
+    fn hex_dump_lines_should_stop_at_the_end_of_the_data() {
+        let data = (0..20).collect::<Vec<u8>>();
+        let lines = hex_dump_lines(&data, 16..32, &[0..8, 8..18]);
+        assert_eq!(
+            vec![HexDumpLine {
+                offset: 16,
+                bytes: vec![(16, Some(1)), (17, Some(1)), (18, None), (19, None)],
+            }],
+            lines
+        );
+        assert_eq!('f', printable_char(b'f'));
+        assert_eq!('.', printable_char(0));
+    }
+
+    #[test]
+    fn hex_rows_should_group_bytes_in_fours_and_rows_of_sixteen() {
+        let data = (0..20).collect::<Vec<u8>>();
+        assert_eq!(
+            "00 01 02 03  04 05 06 07  08 09 0A 0B  0C 0D 0E 0F\n10 11 12 13",
+            hex_rows(&data)
+        );
+        assert_eq!("FF", hex_rows(&[0xFF]));
+        assert_eq!("", hex_rows(&[]));
+    }
+}
@@ -0,0 +1,911 @@
+// Definitions of the tags and attributes of HLS, as summarised from the specification, for the
+// tooltips on the tag names and attribute names of a playlist. Each points at the section of the
+// specification that defines it, where the full definition can be read.
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4
+
+use std::ops::Range;
+
+const SPEC_URL: &str = "https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17";
+
+struct TagSpec {
+    name: &'static str,
+    section: &'static str,
+    definition: &'static str,
+    /// The attributes of the tag (when it has an attribute list) by name, with their definitions.
+    attributes: &'static [(&'static str, &'static str)],
+}
+
+impl TagSpec {
+    fn href(&self) -> String {
+        format!("{SPEC_URL}#section-{}", self.section)
+    }
+
+    fn attribute(&self, name: &str) -> Option<&'static str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| *attribute == name)
+            .map(|(_, definition)| *definition)
+            // Any client-defined attribute of an EXT-X-DATERANGE begins with X-.
+            .or_else(|| {
+                (self.name == "EXT-X-DATERANGE" && name.starts_with("X-")).then_some(
+                    "A client-defined attribute, whose name is prefixed with a reverse-DNS \
+                    identifier by convention. The value is a quoted-string, hexadecimal-sequence \
+                    or decimal-floating-point.",
+                )
+            })
+    }
+}
+
+/// A tag name or attribute name within a line that the specification defines.
+#[derive(Debug, PartialEq)]
+pub struct SpecTerm {
+    /// The byte range of the name within the line.
+    pub range: Range<usize>,
+    pub title: String,
+    pub href: String,
+}
+
+fn tag_spec(name: &str) -> Option<&'static TagSpec> {
+    TAGS.iter().find(|tag| tag.name == name)
+}
+
+/// The categories of tag, as the specification groups them by section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagCategory {
+    Basic,
+    MediaOrMultivariant,
+    MediaPlaylist,
+    MediaSegment,
+    MediaMetadata,
+    Multivariant,
+}
+
+impl TagCategory {
+    pub const ALL: [TagCategory; 6] = [
+        Self::Basic,
+        Self::MediaOrMultivariant,
+        Self::MediaPlaylist,
+        Self::MediaSegment,
+        Self::MediaMetadata,
+        Self::Multivariant,
+    ];
+
+    /// The title of the section of the specification that defines the tags.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Basic => "Basic Tags",
+            Self::MediaOrMultivariant => "Media or Multivariant Playlist Tags",
+            Self::MediaPlaylist => "Media Playlist Tags",
+            Self::MediaSegment => "Media Segment Tags",
+            Self::MediaMetadata => "Media Metadata Tags",
+            Self::Multivariant => "Multivariant Playlist Tags",
+        }
+    }
+
+    /// The class that gives the tags of the category their colour.
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Basic => "tag-basic",
+            Self::MediaOrMultivariant => "tag-media-or-multivariant",
+            Self::MediaPlaylist => "tag-media-playlist",
+            Self::MediaSegment => "tag-media-segment",
+            Self::MediaMetadata => "tag-media-metadata",
+            Self::Multivariant => "tag-multivariant",
+        }
+    }
+}
+
+/// The category of the tag with the given name (without the leading `#`), where the specification
+/// defines it.
+pub fn tag_category(name: &str) -> Option<TagCategory> {
+    let section = tag_spec(name)?.section;
+    let category = match section.strip_prefix("4.4.")?.split('.').next()? {
+        "1" => TagCategory::Basic,
+        "2" => TagCategory::MediaOrMultivariant,
+        "3" => TagCategory::MediaPlaylist,
+        "4" => TagCategory::MediaSegment,
+        "5" => TagCategory::MediaMetadata,
+        "6" => TagCategory::Multivariant,
+        _ => return None,
+    };
+    Some(category)
+}
+
+/// Finds the name of the tag on the line, and the names of its attributes, where the specification
+/// defines them.
+pub fn spec_terms(line: &str) -> Vec<SpecTerm> {
+    let Some(tag) = line.strip_prefix('#') else {
+        return Vec::new();
+    };
+    let (name, value) = match tag.split_once(':') {
+        Some((name, value)) => (name, Some(value)),
+        None => (tag, None),
+    };
+    let Some(spec) = tag_spec(name) else {
+        return Vec::new();
+    };
+    let mut terms = vec![SpecTerm {
+        range: 1..1 + name.len(),
+        title: format!("{name} (section {})\n{}", spec.section, spec.definition),
+        href: spec.href(),
+    }];
+    let Some(value) = value.filter(|_| !spec.attributes.is_empty()) else {
+        return terms;
+    };
+    let value_start = line.len() - value.len();
+    for (start, attribute) in attribute_names(value) {
+        if let Some(definition) = spec.attribute(attribute) {
+            let start = value_start + start;
+            terms.push(SpecTerm {
+                range: start..start + attribute.len(),
+                title: format!(
+                    "{attribute} ({name}, section {})\n{definition}",
+                    spec.section
+                ),
+                href: spec.href(),
+            });
+        }
+    }
+    terms
+}
+
+// The names in an attribute list along with where they start, skipping over any commas within
+// quoted-string values.
+fn attribute_names(list: &str) -> Vec<(usize, &str)> {
+    let mut names = Vec::new();
+    let mut in_quotes = false;
+    let mut name_start = Some(0);
+    for (index, char) in list.char_indices() {
+        match char {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => name_start = Some(index + 1),
+            '=' if !in_quotes => {
+                if let Some(start) = name_start.take() {
+                    names.push((start, &list[start..index]));
+                }
+            }
+            _ => (),
+        }
+    }
+    names
+}
+
+const TAGS: &[TagSpec] = &[
+    TagSpec {
+        name: "EXTM3U",
+        section: "4.4.1.1",
+        definition: "Indicates that the file is an Extended M3U Playlist file. It MUST be the \
+            first line of every Media Playlist and every Multivariant Playlist.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-VERSION",
+        section: "4.4.1.2",
+        definition: "Indicates the compatibility version of the Playlist file, its associated \
+            media, and its server. A Playlist file MUST NOT contain more than one EXT-X-VERSION \
+            tag.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-INDEPENDENT-SEGMENTS",
+        section: "4.4.2.1",
+        definition: "Indicates that all media samples in a Media Segment can be decoded without \
+            information from other segments. It applies to every Media Segment in the Playlist.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-START",
+        section: "4.4.2.2",
+        definition: "Indicates a preferred point at which to start playing a Playlist. It MUST \
+            NOT appear more than once.",
+        attributes: &[
+            (
+                "TIME-OFFSET",
+                "REQUIRED. A signed-decimal-floating-point number of seconds, from the start of \
+                the Playlist when positive or from the end of the last Media Segment when \
+                negative.",
+            ),
+            (
+                "PRECISE",
+                "YES or NO (the default). When YES, clients should start playback at the Media \
+                Segment containing the TIME-OFFSET, but not render samples before it.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-DEFINE",
+        section: "4.4.2.3",
+        definition: "Provides a Playlist variable definition or declaration. It MUST contain \
+            exactly one of NAME, IMPORT or QUERYPARAM.",
+        attributes: &[
+            (
+                "NAME",
+                "The name of the variable, as a quoted-string of [a-zA-Z0-9_-] characters.",
+            ),
+            (
+                "VALUE",
+                "The value of the variable, as a quoted-string. REQUIRED with NAME.",
+            ),
+            (
+                "IMPORT",
+                "The name of a variable defined in the Multivariant Playlist that referenced this \
+                Media Playlist, whose value is imported. Only allowed in a Media Playlist.",
+            ),
+            (
+                "QUERYPARAM",
+                "The name of a query parameter of the URI of the Playlist, whose value is taken \
+                as the value of the variable.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-TARGETDURATION",
+        section: "4.4.3.1",
+        definition: "REQUIRED in a Media Playlist. Specifies the maximum Media Segment duration, \
+            in seconds. The EXTINF duration of each segment, rounded to the nearest integer, MUST \
+            be less than or equal to it.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-MEDIA-SEQUENCE",
+        section: "4.4.3.2",
+        definition: "Indicates the Media Sequence Number of the first Media Segment in the \
+            Playlist (0 when absent). It MUST appear before the first Media Segment.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-DISCONTINUITY-SEQUENCE",
+        section: "4.4.3.3",
+        definition: "Allows synchronization between different Renditions of the same Variant \
+            Stream or different Variant Streams with EXT-X-DISCONTINUITY tags. It MUST appear \
+            before the first Media Segment and before any EXT-X-DISCONTINUITY tag.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-ENDLIST",
+        section: "4.4.3.4",
+        definition: "Indicates that no more Media Segments will be added to the Media Playlist \
+            file.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-PLAYLIST-TYPE",
+        section: "4.4.3.5",
+        definition: "Provides mutability information about the Media Playlist file. EVENT means \
+            that segments can only be added to the end, VOD that the Playlist cannot change.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-I-FRAMES-ONLY",
+        section: "4.4.3.6",
+        definition: "Indicates that each Media Segment in the Playlist describes a single \
+            I-frame. It requires a compatibility version of 4 or greater.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-PART-INF",
+        section: "4.4.3.7",
+        definition: "Provides information about the Partial Segments in the Playlist. It is \
+            REQUIRED if the Playlist contains one or more EXT-X-PART tags.",
+        attributes: &[(
+            "PART-TARGET",
+            "REQUIRED. A decimal-floating-point number of seconds for the Part Target Duration, \
+            the maximum duration of any Partial Segment.",
+        )],
+    },
+    TagSpec {
+        name: "EXT-X-SERVER-CONTROL",
+        section: "4.4.3.8",
+        definition: "Allows the Server to indicate support for Delivery Directives.",
+        attributes: &[
+            (
+                "CAN-SKIP-UNTIL",
+                "The Skip Boundary in seconds, indicating that the Server can produce Playlist \
+                Delta Updates. It MUST be at least six times the Target Duration.",
+            ),
+            (
+                "CAN-SKIP-DATERANGES",
+                "YES indicates that the Server can skip EXT-X-DATERANGE tags in Playlist Delta \
+                Updates. It requires CAN-SKIP-UNTIL.",
+            ),
+            (
+                "HOLD-BACK",
+                "The minimum distance in seconds from the end of the Playlist at which clients \
+                should begin to play. It MUST be at least three times the Target Duration.",
+            ),
+            (
+                "PART-HOLD-BACK",
+                "The minimum distance in seconds from the end of the Playlist at which clients \
+                should begin to play in Low-Latency Mode. It MUST be at least twice the Part \
+                Target Duration, and is REQUIRED with EXT-X-PART-INF.",
+            ),
+            (
+                "CAN-BLOCK-RELOAD",
+                "YES indicates that the Server supports Blocking Playlist Reload.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXTINF",
+        section: "4.4.4.1",
+        definition: "Specifies the duration of a Media Segment, in seconds, optionally followed \
+            by a title. It is REQUIRED for each Media Segment and applies only to the next Media \
+            Segment.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-BYTERANGE",
+        section: "4.4.4.2",
+        definition: "Indicates that a Media Segment is a sub-range of the resource identified by \
+            its URI, as <n>[@<o>] for a length of n bytes at an offset of o. Without an offset \
+            the sub-range begins at the next byte following the previous sub-range.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-DISCONTINUITY",
+        section: "4.4.4.3",
+        definition: "Indicates a discontinuity between the Media Segment that follows it and the \
+            one that preceded it, such as a change of file format, encoding or timestamps.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-KEY",
+        section: "4.4.4.4",
+        definition: "Specifies how to decrypt Media Segments. It applies to every Media Segment \
+            and Media Initialization Section that follows it until the next EXT-X-KEY with the \
+            same KEYFORMAT.",
+        attributes: &[
+            (
+                "METHOD",
+                "REQUIRED. NONE, AES-128, SAMPLE-AES or SAMPLE-AES-CTR. When NONE, the other \
+                attributes MUST NOT be present.",
+            ),
+            (
+                "URI",
+                "The URI that specifies how to obtain the key. REQUIRED unless the METHOD is \
+                NONE.",
+            ),
+            (
+                "IV",
+                "A hexadecimal-sequence of the 128-bit Initialization Vector. Without it, the \
+                Media Sequence Number is used as the IV when decrypting with AES-128.",
+            ),
+            (
+                "KEYFORMAT",
+                "How the key is represented in the resource identified by the URI. It defaults \
+                to \"identity\".",
+            ),
+            (
+                "KEYFORMATVERSIONS",
+                "The versions of the KEYFORMAT that this instance complies with, as a \
+                quoted-string of slash-separated integers. It defaults to \"1\".",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-MAP",
+        section: "4.4.4.5",
+        definition: "Specifies how to obtain the Media Initialization Section required to parse \
+            the applicable Media Segments. It applies to every Media Segment that follows it \
+            until the next EXT-X-MAP.",
+        attributes: &[
+            (
+                "URI",
+                "REQUIRED. The URI of the resource containing the Media Initialization Section.",
+            ),
+            (
+                "BYTERANGE",
+                "A quoted-string of <n>@<o> giving the byte range of the Media Initialization \
+                Section within the resource. Without it, the whole resource is used.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-PROGRAM-DATE-TIME",
+        section: "4.4.4.6",
+        definition: "Associates the first sample of a Media Segment with an absolute date and/or \
+            time, as ISO 8601 with a time zone. It applies only to the next Media Segment.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-GAP",
+        section: "4.4.4.7",
+        definition: "Indicates that the segment URI to which it applies does not contain media \
+            data and SHOULD NOT be loaded by clients.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-BITRATE",
+        section: "4.4.4.8",
+        definition: "Identifies the approximate segment bit rate, in kbps, of the Media Segments \
+            that follow it until the next EXT-X-BITRATE. It is ignored for segments with an \
+            EXT-X-BYTERANGE.",
+        attributes: &[],
+    },
+    TagSpec {
+        name: "EXT-X-PART",
+        section: "4.4.4.9",
+        definition: "Identifies a Partial Segment, a subset of the Media Segment that follows it. \
+            Partial Segments are only added to the Playlist near its live edge.",
+        attributes: &[
+            ("URI", "REQUIRED. The URI of the Partial Segment."),
+            (
+                "DURATION",
+                "REQUIRED. The duration of the Partial Segment in seconds.",
+            ),
+            (
+                "INDEPENDENT",
+                "YES indicates that the Partial Segment contains an independent frame.",
+            ),
+            (
+                "BYTERANGE",
+                "A quoted-string of <n>[@<o>] indicating that the Partial Segment is a sub-range \
+                of the resource identified by the URI.",
+            ),
+            (
+                "GAP",
+                "YES indicates that the Partial Segment is not available, and SHOULD NOT be \
+                loaded by clients.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-DATERANGE",
+        section: "4.4.5.1",
+        definition: "Associates a Date Range (a range of time defined by a starting and ending \
+            date) with a set of attribute/value pairs.",
+        attributes: &[
+            (
+                "ID",
+                "REQUIRED. Uniquely identifies the Date Range in the Playlist. Tags with the same \
+                ID MUST have the same values for the attributes that they share.",
+            ),
+            (
+                "CLASS",
+                "A client-defined string that specifies a set of attributes and their \
+                semantics. All Date Ranges with the same CLASS MUST adhere to them.",
+            ),
+            (
+                "START-DATE",
+                "The date at which the Date Range begins, as ISO 8601. REQUIRED, except on tags \
+                that update a Date Range with the same ID.",
+            ),
+            (
+                "CUE",
+                "When to trigger an action associated with the Date Range, as a quoted-string of \
+                PRE, POST and ONCE separated by commas.",
+            ),
+            (
+                "END-DATE",
+                "The date at which the Date Range ends. It MUST be equal to or later than the \
+                START-DATE.",
+            ),
+            (
+                "DURATION",
+                "The duration of the Date Range in seconds. It MUST NOT be negative.",
+            ),
+            (
+                "PLANNED-DURATION",
+                "The expected duration of the Date Range in seconds, for when the actual \
+                duration is not yet known.",
+            ),
+            (
+                "SCTE35-CMD",
+                "A hexadecimal-sequence of an SCTE-35 splice_info_section that is not an \
+                out/in splice.",
+            ),
+            (
+                "SCTE35-OUT",
+                "A hexadecimal-sequence of the SCTE-35 splice_info_section of a splice out.",
+            ),
+            (
+                "SCTE35-IN",
+                "A hexadecimal-sequence of the SCTE-35 splice_info_section of a splice in.",
+            ),
+            (
+                "END-ON-NEXT",
+                "YES indicates that the end of the Date Range is the start of the following Date \
+                Range with the same CLASS. It requires a CLASS, and forbids DURATION and \
+                END-DATE.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-SKIP",
+        section: "4.4.5.2",
+        definition: "Indicates that Media Segment tags have been replaced by the tag in a \
+            Playlist Delta Update.",
+        attributes: &[
+            (
+                "SKIPPED-SEGMENTS",
+                "REQUIRED. The number of Media Segments that were replaced by the EXT-X-SKIP tag.",
+            ),
+            (
+                "RECENTLY-REMOVED-DATERANGES",
+                "A quoted-string of tab-delimited IDs of Date Ranges that have been removed from \
+                the Playlist recently.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-PRELOAD-HINT",
+        section: "4.4.5.3",
+        definition: "Allows a client loading media from a live stream to reduce the time to \
+            obtain a resource from the Server, by requesting it before it is available.",
+        attributes: &[
+            (
+                "TYPE",
+                "REQUIRED. PART or MAP, the type of the hinted resource.",
+            ),
+            ("URI", "REQUIRED. The URI of the hinted resource."),
+            (
+                "BYTERANGE-START",
+                "The byte offset of the first byte of the hinted resource (0 by default).",
+            ),
+            (
+                "BYTERANGE-LENGTH",
+                "The length of the hinted resource in bytes (to the end of the resource by \
+                default).",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-RENDITION-REPORT",
+        section: "4.4.5.4",
+        definition: "Carries information about an associated Rendition that is as up-to-date as \
+            the Playlist that contains it.",
+        attributes: &[
+            (
+                "URI",
+                "REQUIRED. The URI of the Media Playlist of the specified Rendition.",
+            ),
+            (
+                "LAST-MSN",
+                "The Media Sequence Number of the last Media Segment in the Rendition.",
+            ),
+            (
+                "LAST-PART",
+                "The Part Index of the last Partial Segment in the Rendition.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-MEDIA",
+        section: "4.4.6.1",
+        definition: "Used to relate Media Playlists that contain alternative Renditions of the \
+            same content. All EXT-X-MEDIA tags with the same GROUP-ID and TYPE form a Group of \
+            Renditions.",
+        attributes: &[
+            (
+                "TYPE",
+                "REQUIRED. AUDIO, VIDEO, SUBTITLES or CLOSED-CAPTIONS.",
+            ),
+            (
+                "URI",
+                "The URI of the Media Playlist of the Rendition. It MUST NOT be present when the \
+                TYPE is CLOSED-CAPTIONS.",
+            ),
+            (
+                "GROUP-ID",
+                "REQUIRED. The group to which the Rendition belongs.",
+            ),
+            (
+                "LANGUAGE",
+                "The primary language used in the Rendition, as a BCP 47 language tag.",
+            ),
+            (
+                "ASSOC-LANGUAGE",
+                "A language (as a BCP 47 language tag) associated with the Rendition, such as \
+                for a different role.",
+            ),
+            (
+                "NAME",
+                "REQUIRED. A human-readable description of the Rendition, which MUST be unique \
+                within its group.",
+            ),
+            (
+                "STABLE-RENDITION-ID",
+                "A stable identifier for the URI of the Rendition within the Multivariant \
+                Playlist.",
+            ),
+            (
+                "DEFAULT",
+                "YES indicates that the client should play this Rendition in the absence of \
+                information from the user. NO is the default.",
+            ),
+            (
+                "AUTOSELECT",
+                "YES indicates that the client may choose to play this Rendition in the absence \
+                of explicit user preference. It MUST be YES if DEFAULT is YES.",
+            ),
+            (
+                "FORCED",
+                "YES indicates that the Rendition contains content that is considered essential \
+                to play. Only for a TYPE of SUBTITLES.",
+            ),
+            (
+                "INSTREAM-ID",
+                "REQUIRED for CLOSED-CAPTIONS. The Rendition within the segments of the Media \
+                Playlist, as CC1-CC4 or SERVICE1-SERVICE63.",
+            ),
+            (
+                "BIT-DEPTH",
+                "The audio bit depth of the Rendition, for audio formats where it is \
+                applicable.",
+            ),
+            ("SAMPLE-RATE", "The audio sample rate of the Rendition."),
+            (
+                "CHARACTERISTICS",
+                "One or more comma-separated Uniform Type Identifiers of the characteristics of \
+                the Rendition, such as public.accessibility.describes-video.",
+            ),
+            (
+                "CHANNELS",
+                "An ordered, slash-separated list of parameters, the first of which is the \
+                number of independent, simultaneous audio channels.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-STREAM-INF",
+        section: "4.4.6.2",
+        definition: "Specifies a Variant Stream, a set of Renditions that can be combined to \
+            play the presentation. The URI line that follows it specifies a Media Playlist of \
+            the Variant Stream.",
+        attributes: &VARIANT_ATTRIBUTES,
+    },
+    TagSpec {
+        name: "EXT-X-I-FRAME-STREAM-INF",
+        section: "4.4.6.3",
+        definition: "Identifies a Media Playlist file containing the I-frames of a multimedia \
+            presentation. It supports the attributes of EXT-X-STREAM-INF other than FRAME-RATE, \
+            AUDIO, SUBTITLES and CLOSED-CAPTIONS, and requires a URI.",
+        attributes: &I_FRAME_VARIANT_ATTRIBUTES,
+    },
+    TagSpec {
+        name: "EXT-X-SESSION-DATA",
+        section: "4.4.6.4",
+        definition: "Allows arbitrary session data to be carried in a Multivariant Playlist.",
+        attributes: &[
+            (
+                "DATA-ID",
+                "REQUIRED. Identifies the data value, using reverse-DNS naming by convention.",
+            ),
+            (
+                "VALUE",
+                "The data value. Each tag MUST contain either a VALUE or a URI, but not both.",
+            ),
+            (
+                "URI",
+                "The URI of a resource holding the data value, as JSON unless the FORMAT says \
+                otherwise.",
+            ),
+            (
+                "FORMAT",
+                "JSON (the default) or RAW, the format of the resource at the URI.",
+            ),
+            (
+                "LANGUAGE",
+                "The language of the VALUE, as a BCP 47 language tag.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-SESSION-KEY",
+        section: "4.4.6.5",
+        definition: "Allows encryption keys from Media Playlists to be specified in a \
+            Multivariant Playlist, so that clients can preload them. It takes the attributes of \
+            EXT-X-KEY, other than a METHOD of NONE.",
+        attributes: &[
+            ("METHOD", "REQUIRED. AES-128, SAMPLE-AES or SAMPLE-AES-CTR."),
+            (
+                "URI",
+                "REQUIRED. The URI that specifies how to obtain the key.",
+            ),
+            (
+                "IV",
+                "A hexadecimal-sequence of the 128-bit Initialization Vector.",
+            ),
+            (
+                "KEYFORMAT",
+                "How the key is represented in the resource identified by the URI.",
+            ),
+            (
+                "KEYFORMATVERSIONS",
+                "The versions of the KEYFORMAT that this instance complies with.",
+            ),
+        ],
+    },
+    TagSpec {
+        name: "EXT-X-CONTENT-STEERING",
+        section: "4.4.6.6",
+        definition: "Allows a server to provide a Content Steering Manifest, which clients use \
+            to choose between Pathways (such as CDNs).",
+        attributes: &[
+            ("SERVER-URI", "REQUIRED. The URI of the Steering Manifest."),
+            (
+                "PATHWAY-ID",
+                "The Pathway to apply until the Steering Manifest has been obtained.",
+            ),
+        ],
+    },
+];
+
+// The attributes shared by EXT-X-STREAM-INF and EXT-X-I-FRAME-STREAM-INF.
+const BANDWIDTH: (&str, &str) = (
+    "BANDWIDTH",
+    "REQUIRED. The peak segment bit rate of the Variant Stream, in bits per second.",
+);
+const AVERAGE_BANDWIDTH: (&str, &str) = (
+    "AVERAGE-BANDWIDTH",
+    "The average segment bit rate of the Variant Stream, in bits per second.",
+);
+const SCORE: (&str, &str) = (
+    "SCORE",
+    "An abstract, relative measure of the playback quality-of-experience of the Variant Stream, \
+    where a larger value is preferred.",
+);
+const CODECS: (&str, &str) = (
+    "CODECS",
+    "SHOULD be present. A comma-separated list of formats, each of which specifies a media \
+    sample type that is present in one or more Renditions of the Variant Stream.",
+);
+const SUPPLEMENTAL_CODECS: (&str, &str) = (
+    "SUPPLEMENTAL-CODECS",
+    "A comma-separated list of formats that describe media samples which can be decoded with \
+    the codecs in CODECS, such as enhancement layers or Dolby Vision profiles.",
+);
+const RESOLUTION: (&str, &str) = (
+    "RESOLUTION",
+    "The optimal pixel resolution at which to display all the video in the Variant Stream, as \
+    <width>x<height>.",
+);
+const HDCP_LEVEL: (&str, &str) = (
+    "HDCP-LEVEL",
+    "TYPE-0, TYPE-1 or NONE, indicating the HDCP protection that is required for the output of \
+    the Variant Stream.",
+);
+const ALLOWED_CPC: (&str, &str) = (
+    "ALLOWED-CPC",
+    "Allows the server to indicate the Content Protection Configurations, per KEYFORMAT, that \
+    are acceptable for the Variant Stream.",
+);
+const VIDEO_RANGE: (&str, &str) = (
+    "VIDEO-RANGE",
+    "SDR, HLG or PQ, the video dynamic range of the Variant Stream.",
+);
+const REQ_VIDEO_LAYOUT: (&str, &str) = (
+    "REQ-VIDEO-LAYOUT",
+    "The video layouts that the client is required to support to play the Variant Stream, such \
+    as CH-STEREO for stereoscopic video.",
+);
+const STABLE_VARIANT_ID: (&str, &str) = (
+    "STABLE-VARIANT-ID",
+    "A stable identifier for the URI of the Variant Stream within the Multivariant Playlist.",
+);
+const VIDEO: (&str, &str) = (
+    "VIDEO",
+    "The GROUP-ID of the EXT-X-MEDIA tags of TYPE VIDEO that are the video Renditions of the \
+    Variant Stream.",
+);
+const PATHWAY_ID: (&str, &str) = (
+    "PATHWAY-ID",
+    "The Content Steering Pathway that the Variant Stream belongs to.",
+);
+const VARIANT_ATTRIBUTES: [(&str, &str); 18] = [
+    BANDWIDTH,
+    AVERAGE_BANDWIDTH,
+    SCORE,
+    CODECS,
+    SUPPLEMENTAL_CODECS,
+    RESOLUTION,
+    (
+        "FRAME-RATE",
+        "The maximum frame rate for all the video in the Variant Stream, rounded to three \
+        decimal places.",
+    ),
+    HDCP_LEVEL,
+    ALLOWED_CPC,
+    VIDEO_RANGE,
+    REQ_VIDEO_LAYOUT,
+    STABLE_VARIANT_ID,
+    (
+        "AUDIO",
+        "The GROUP-ID of the EXT-X-MEDIA tags of TYPE AUDIO that are the audio Renditions of \
+        the Variant Stream.",
+    ),
+    VIDEO,
+    (
+        "SUBTITLES",
+        "The GROUP-ID of the EXT-X-MEDIA tags of TYPE SUBTITLES that are the subtitle \
+        Renditions of the Variant Stream.",
+    ),
+    (
+        "CLOSED-CAPTIONS",
+        "The GROUP-ID of the EXT-X-MEDIA tags of TYPE CLOSED-CAPTIONS for the Variant Stream, or \
+        NONE when it has no closed captions.",
+    ),
+    PATHWAY_ID,
+    (
+        "PROGRAM-ID",
+        "Removed from the protocol in version 6; it is ignored by clients.",
+    ),
+];
+const I_FRAME_VARIANT_ATTRIBUTES: [(&str, &str); 14] = [
+    BANDWIDTH,
+    AVERAGE_BANDWIDTH,
+    SCORE,
+    CODECS,
+    SUPPLEMENTAL_CODECS,
+    RESOLUTION,
+    HDCP_LEVEL,
+    ALLOWED_CPC,
+    VIDEO_RANGE,
+    REQ_VIDEO_LAYOUT,
+    STABLE_VARIANT_ID,
+    VIDEO,
+    PATHWAY_ID,
+    (
+        "URI",
+        "REQUIRED. The URI of the I-frame Media Playlist file.",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn spec_terms_should_find_the_tag_and_its_defined_attributes() {
+        let line = r#"#EXT-X-KEY:METHOD=AES-128,URI="a,IV=b",X-UNKNOWN=1"#;
+        let terms = spec_terms(line)
+            .into_iter()
+            .map(|term| &line[term.range])
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["EXT-X-KEY", "METHOD", "URI"], terms);
+    }
+
+    #[test]
+    fn spec_terms_should_include_client_attributes_of_dateranges() {
+        let line = r#"#EXT-X-DATERANGE:ID="ad",X-COM-EXAMPLE-AD-ID="XYZ123""#;
+        let terms = spec_terms(line)
+            .into_iter()
+            .map(|term| &line[term.range])
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["EXT-X-DATERANGE", "ID", "X-COM-EXAMPLE-AD-ID"], terms);
+    }
+
+    #[test]
+    fn tag_category_should_follow_the_section_of_the_tag() {
+        assert_eq!(Some(TagCategory::Basic), tag_category("EXTM3U"));
+        assert_eq!(Some(TagCategory::MediaSegment), tag_category("EXTINF"));
+        assert_eq!(
+            Some(TagCategory::MediaMetadata),
+            tag_category("EXT-X-DATERANGE")
+        );
+        assert_eq!(
+            Some(TagCategory::Multivariant),
+            tag_category("EXT-X-STREAM-INF")
+        );
+        assert_eq!(None, tag_category("EXT-X-UNKNOWN"));
+    }
+
+    #[test]
+    fn spec_terms_should_be_empty_for_unknown_tags_and_other_lines() {
+        assert_eq!(Vec::<SpecTerm>::new(), spec_terms("#EXT-X-UNKNOWN:A=B"));
+        assert_eq!(Vec::<SpecTerm>::new(), spec_terms("segment.ts"));
+        assert_eq!(
+            vec![SpecTerm {
+                range: 1..7,
+                title: format!(
+                    "EXTINF (section 4.4.4.1)\n{}",
+                    tag_spec("EXTINF").unwrap().definition
+                ),
+                href: format!("{SPEC_URL}#section-4.4.4.1"),
+            }],
+            spec_terms("#EXTINF:6.006,")
+        );
+    }
+}
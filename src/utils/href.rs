@@ -1,11 +1,12 @@
 use crate::utils::{
-    network::RequestRange,
+    byterange::RequestRange,
+    data_uri::is_data_uri,
     query_codec::{
         Scte35CommandType, encode_asset_list, encode_daterange_schedule, encode_definitions,
         encode_map, encode_part, encode_scte35, encode_segment, percent_decode, percent_encode,
     },
 };
-use leptos::prelude::GetUntracked;
+use leptos::prelude::{GetUntracked, use_context};
 use leptos_router::hooks::use_url;
 use std::{borrow::Cow, collections::HashMap};
 use url::Url;
@@ -13,6 +14,38 @@ use url::Url;
 pub const PLAYLIST_URL_QUERY_NAME: &str = "playlist_url";
 pub const SUPPLEMENTAL_VIEW_QUERY_NAME: &str = "supplemental_view_context";
 pub const DEFINITIONS_QUERY_NAME: &str = "imported_definitions";
+pub const VARIANT_QUERY_NAME: &str = "variant_attributes";
+/// A supplemental view pinned for comparison, which is carried along in every href so that it stays
+/// open while other resources (including those of other playlists) are browsed.
+pub const PINNED_VIEW_QUERY_NAME: &str = "pinned_view_context";
+/// The names of the query parameters of the playlist URL (comma separated, or `*` for all of them)
+/// that are forwarded onto every request made for a child of the playlist, which is what tokenized
+/// streams need to be browsed end to end.
+pub const FORWARD_QUERY_QUERY_NAME: &str = "forward_query";
+/// The Multivariant Playlist that a playlist was opened from, as the 1-based number of the line that
+/// links to the playlist followed by the query of the Multivariant Playlist (comma separated), so
+/// that there is a way back up to it.
+pub const PARENT_PLAYLIST_QUERY_NAME: &str = "parent_playlist";
+/// The whole query of a shared link, compressed into the one value (see
+/// [`crate::utils::query_codec::compress_query`]), which is expanded back out when the link is
+/// opened.
+pub const COMPRESSED_QUERY_QUERY_NAME: &str = "state";
+/// Opens the validation report once the playlist has loaded (`validate=1`).
+pub const VALIDATE_QUERY_NAME: &str = "validate";
+/// Opens a view of the playlist once it has loaded (see
+/// [`crate::utils::query_codec::PlaylistView`]).
+pub const VIEW_QUERY_NAME: &str = "view";
+/// Opens a Media Segment once the playlist has loaded (see
+/// [`crate::utils::query_codec::SegmentSelector`]). It is dropped from the URL as the segment is
+/// opened, so that going back to the playlist does not open it again.
+pub const SEGMENT_QUERY_NAME: &str = "segment";
+
+/// The URL that the playlist was served from when the request for it was redirected. Relative URIs
+/// in the playlist resolve against it rather than against the URL in the query, which is still the
+/// one carried along in hrefs so that the same request is made again. The viewer provides this as
+/// context.
+#[derive(Debug, Clone)]
+pub struct RedirectedPlaylistUrl(pub Url);
 
 pub fn query_value_from_leptos_url<'a>(
     url: &'a leptos_router::location::Url,
@@ -31,11 +64,116 @@ pub fn query_value_from_leptos_url<'a>(
     })
 }
 
+/// Provides the href for the current page with the (already query encoded) value of `query_name`
+/// replaced, or removed when there is no value, where `search` is the query of the current URL.
+pub fn replace_query_value(search: &str, query_name: &str, query_value: Option<&str>) -> String {
+    let pairs = search
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| pair.split('=').next() != Some(query_name))
+        .map(String::from)
+        .chain(query_value.map(|value| format!("{query_name}={value}")))
+        .collect::<Vec<_>>();
+    format!("?{}", pairs.join("&"))
+}
+
+/// The playlist URL as the links of the viewer give it, which is as [`Url`] serializes it (with the
+/// scheme and host in lower case, a path of at least `/`, and so on). A URL that does not parse is
+/// left as it is.
+pub fn normalized_playlist_url(url: String) -> String {
+    Url::parse(&url).map(String::from).unwrap_or(url)
+}
+
+/// A short name for the playlist at `url` (the last segment of its path), for where the whole URL
+/// would be too long to show.
+pub fn playlist_name(url: &str) -> String {
+    let Ok(parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    parsed
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()))
+        .map(|segment| percent_decode(segment).to_string())
+        .or_else(|| parsed.host_str().map(String::from))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Records the current playlist as the parent of the playlist at `href`, where `line_number` is the
+/// line of the current playlist that links to it.
+pub fn with_parent_playlist(href: String, line_number: usize) -> String {
+    let url = use_url().get_untracked();
+    append_query_value(
+        href,
+        PARENT_PLAYLIST_QUERY_NAME,
+        Some(parent_playlist_value(url.search(), line_number)),
+    )
+}
+
+/// The playlist that the current playlist was opened from.
+#[derive(Debug, PartialEq)]
+pub struct ParentPlaylist {
+    pub href: String,
+    pub playlist_url: String,
+    /// The 1-based number of the line of the parent that links to the current playlist.
+    pub line_number: usize,
+}
+
+/// Provides the parent playlist recorded in `search` (the query of the current URL). The pinned
+/// view is carried back up to it as it is now, rather than as it was when the parent was left.
+pub fn parent_playlist(search: &str) -> Option<ParentPlaylist> {
+    let value = query_pairs(search).find_map(|(name, value)| {
+        (name == PARENT_PLAYLIST_QUERY_NAME).then(|| percent_decode(value))
+    })?;
+    let (line_number, parent_search) = value.split_once(',')?;
+    let playlist_url = query_pairs(parent_search).find_map(|(name, value)| {
+        (name == PLAYLIST_URL_QUERY_NAME).then(|| percent_decode(value).to_string())
+    })?;
+    let pinned = query_pairs(search)
+        .find_map(|(name, value)| (name == PINNED_VIEW_QUERY_NAME).then(|| value.to_string()));
+    Some(ParentPlaylist {
+        href: append_query_value(format!("?{parent_search}"), PINNED_VIEW_QUERY_NAME, pinned),
+        playlist_url,
+        line_number: line_number.parse().ok()?,
+    })
+}
+
 pub fn media_playlist_href(
     relative_uri: &str,
     definitions: &HashMap<String, String>,
 ) -> Option<String> {
-    playlist_href(base_url()?, relative_uri, definitions)
+    let base_url = base_url()?;
+    let forward_query = forward_query_value();
+    let uri = redirected_uri(relative_uri, definitions, redirected_url().as_ref());
+    let uri = forwarded_uri(&base_url, &uri, definitions, forward_query.as_deref());
+    let href = playlist_href(base_url, &uri, definitions)?;
+    Some(append_forward_query(
+        append_pinned_view(href),
+        forward_query,
+    ))
+}
+
+/// Provides the href for a Media Playlist that is referenced by an EXT-X-STREAM-INF tag. The
+/// attribute list of the tag is carried along in the query so that the media of the variant can be
+/// checked against what the Multivariant Playlist advertises.
+pub fn variant_playlist_href(
+    relative_uri: &str,
+    definitions: &HashMap<String, String>,
+    variant_attributes: &str,
+) -> Option<String> {
+    let base_url = base_url()?;
+    let forward_query = forward_query_value();
+    let uri = redirected_uri(relative_uri, definitions, redirected_url().as_ref());
+    let uri = forwarded_uri(&base_url, &uri, definitions, forward_query.as_deref());
+    let href = playlist_href(base_url, &uri, definitions)?;
+    let href = append_query_value(
+        href,
+        VARIANT_QUERY_NAME,
+        Some(percent_encode(variant_attributes).to_string()),
+    );
+    Some(append_forward_query(
+        append_pinned_view(href),
+        forward_query,
+    ))
 }
 
 pub fn segment_href(
@@ -44,15 +182,24 @@ pub fn segment_href(
     byterange: Option<RequestRange>,
     definitions: &HashMap<String, String>,
 ) -> Option<String> {
-    media_segment_href(
-        base_url()?,
-        segment_uri,
+    let base_url = base_url()?;
+    let forward_query = forward_query_value();
+    let uri = redirected_uri(segment_uri, definitions, redirected_url().as_ref());
+    let uri = forwarded_uri(&base_url, &uri, definitions, forward_query.as_deref());
+    let href = media_segment_href(
+        base_url,
+        &uri,
         media_sequence,
         byterange,
         SegmentType::Segment,
         definitions_query_value(),
         definitions,
-    )
+    )?;
+    let href = append_query_value(href, VARIANT_QUERY_NAME, variant_query_value());
+    Some(append_forward_query(
+        append_pinned_view(append_parent_playlist(href)),
+        forward_query,
+    ))
 }
 
 pub fn map_href(
@@ -61,15 +208,24 @@ pub fn map_href(
     byterange: Option<RequestRange>,
     definitions: &HashMap<String, String>,
 ) -> Option<String> {
-    media_segment_href(
-        base_url()?,
-        segment_uri,
+    let base_url = base_url()?;
+    let forward_query = forward_query_value();
+    let uri = redirected_uri(segment_uri, definitions, redirected_url().as_ref());
+    let uri = forwarded_uri(&base_url, &uri, definitions, forward_query.as_deref());
+    let href = media_segment_href(
+        base_url,
+        &uri,
         media_sequence,
         byterange,
         SegmentType::Map,
         definitions_query_value(),
         definitions,
-    )
+    )?;
+    let href = append_query_value(href, VARIANT_QUERY_NAME, variant_query_value());
+    Some(append_forward_query(
+        append_pinned_view(append_parent_playlist(href)),
+        forward_query,
+    ))
 }
 
 pub fn part_href(
@@ -79,15 +235,24 @@ pub fn part_href(
     byterange: Option<RequestRange>,
     definitions: &HashMap<String, String>,
 ) -> Option<String> {
-    media_segment_href(
-        base_url()?,
-        part_uri,
+    let base_url = base_url()?;
+    let forward_query = forward_query_value();
+    let uri = redirected_uri(part_uri, definitions, redirected_url().as_ref());
+    let uri = forwarded_uri(&base_url, &uri, definitions, forward_query.as_deref());
+    let href = media_segment_href(
+        base_url,
+        &uri,
         media_sequence,
         byterange,
         SegmentType::Part { part_index },
         definitions_query_value(),
         definitions,
-    )
+    )?;
+    let href = append_query_value(href, VARIANT_QUERY_NAME, variant_query_value());
+    Some(append_forward_query(
+        append_pinned_view(append_parent_playlist(href)),
+        forward_query,
+    ))
 }
 
 pub fn scte35_href(
@@ -95,12 +260,16 @@ pub fn scte35_href(
     daterange_id: &str,
     command_type: Scte35CommandType,
 ) -> Option<String> {
-    Some(media_scte35_href(
+    let href = media_scte35_href(
         base_url()?,
         definitions_query_value(),
         scte35_message,
         daterange_id,
         command_type,
+    );
+    Some(append_forward_query(
+        append_pinned_view(append_parent_playlist(href)),
+        forward_query_value(),
     ))
 }
 
@@ -109,14 +278,22 @@ pub fn asset_list_href(
     daterange_id: &str,
     definitions: &HashMap<String, String>,
 ) -> Option<String> {
-    json_href(
-        base_url()?,
+    let base_url = base_url()?;
+    let forward_query = forward_query_value();
+    let uri = redirected_uri(asset_list_uri, definitions, redirected_url().as_ref());
+    let uri = forwarded_uri(&base_url, &uri, definitions, forward_query.as_deref());
+    let href = json_href(
+        base_url,
         definitions_query_value(),
-        asset_list_uri,
+        &uri,
         daterange_id,
         definitions,
         encode_asset_list,
-    )
+    )?;
+    Some(append_forward_query(
+        append_pinned_view(append_parent_playlist(href)),
+        forward_query,
+    ))
 }
 
 pub fn daterange_schedule_href(
@@ -124,22 +301,32 @@ pub fn daterange_schedule_href(
     daterange_id: &str,
     definitions: &HashMap<String, String>,
 ) -> Option<String> {
-    json_href(
-        base_url()?,
+    let base_url = base_url()?;
+    let forward_query = forward_query_value();
+    let uri = redirected_uri(x_uri, definitions, redirected_url().as_ref());
+    let uri = forwarded_uri(&base_url, &uri, definitions, forward_query.as_deref());
+    let href = json_href(
+        base_url,
         definitions_query_value(),
-        x_uri,
+        &uri,
         daterange_id,
         definitions,
         encode_daterange_schedule,
-    )
+    )?;
+    Some(append_forward_query(
+        append_pinned_view(append_parent_playlist(href)),
+        forward_query,
+    ))
 }
 
 pub fn resolve_playlist_relative_url(
     relative_uri: &str,
     definitions: &HashMap<String, String>,
 ) -> Option<String> {
-    let uri = replace_hls_variables(relative_uri, definitions);
     let base = base_url()?;
+    let uri = redirected_uri(relative_uri, definitions, redirected_url().as_ref());
+    let uri = forwarded_uri(&base, &uri, definitions, forward_query_value().as_deref());
+    let uri = replace_hls_variables(&uri, definitions);
     base.join(&uri).ok().map(|url| url.to_string())
 }
 
@@ -151,10 +338,143 @@ fn base_url() -> Option<Url> {
     let base_url_query_parameter = query_value_from_leptos_url(&url, PLAYLIST_URL_QUERY_NAME)?;
     Url::parse(&percent_decode(&base_url_query_parameter)).ok()
 }
+fn redirected_url() -> Option<Url> {
+    use_context::<RedirectedPlaylistUrl>().map(|RedirectedPlaylistUrl(url)| url)
+}
 fn definitions_query_value() -> Option<String> {
     let url = use_url().get_untracked();
     query_value_from_leptos_url(&url, DEFINITIONS_QUERY_NAME).map(|cow| cow.to_string())
 }
+fn variant_query_value() -> Option<String> {
+    let url = use_url().get_untracked();
+    query_value_from_leptos_url(&url, VARIANT_QUERY_NAME).map(|cow| cow.to_string())
+}
+fn forward_query_value() -> Option<String> {
+    let url = use_url().get_untracked();
+    query_value_from_leptos_url(&url, FORWARD_QUERY_QUERY_NAME)
+        .filter(|value| !value.is_empty())
+        .map(|cow| cow.to_string())
+}
+
+fn pinned_view_query_value() -> Option<String> {
+    let url = use_url().get_untracked();
+    query_value_from_leptos_url(&url, PINNED_VIEW_QUERY_NAME).map(|cow| cow.to_string())
+}
+
+// The views within the parent are left behind with it, and so is its own parent (if it had one).
+fn parent_playlist_value(search: &str, line_number: usize) -> String {
+    let parent_search = query_pairs(search)
+        .filter(|(name, _)| {
+            ![
+                SUPPLEMENTAL_VIEW_QUERY_NAME,
+                PINNED_VIEW_QUERY_NAME,
+                PARENT_PLAYLIST_QUERY_NAME,
+            ]
+            .contains(name)
+        })
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&");
+    percent_encode(&format!("{line_number},{parent_search}")).to_string()
+}
+
+fn query_pairs(search: &str) -> impl Iterator<Item = (&str, &str)> {
+    search.split('&').filter_map(|pair| pair.split_once('='))
+}
+
+// The views of a playlist stay within it, so they keep the way back up to its parent.
+fn append_parent_playlist(href: String) -> String {
+    let url = use_url().get_untracked();
+    let parent_playlist = query_value_from_leptos_url(&url, PARENT_PLAYLIST_QUERY_NAME);
+    append_query_value(
+        href,
+        PARENT_PLAYLIST_QUERY_NAME,
+        parent_playlist.map(|cow| cow.to_string()),
+    )
+}
+
+/// Carries the validation report and view asked for by the current URL onto `href`, for a segment
+/// opened on behalf of the `segment` query parameter.
+pub fn with_automation(href: String) -> String {
+    let url = use_url().get_untracked();
+    [VALIDATE_QUERY_NAME, VIEW_QUERY_NAME]
+        .into_iter()
+        .fold(href, |href, query_name| {
+            let value = query_value_from_leptos_url(&url, query_name);
+            append_query_value(href, query_name, value.map(|cow| cow.to_string()))
+        })
+}
+
+fn append_pinned_view(href: String) -> String {
+    append_query_value(href, PINNED_VIEW_QUERY_NAME, pinned_view_query_value())
+}
+
+fn append_forward_query(href: String, forward_query: Option<String>) -> String {
+    append_query_value(href, FORWARD_QUERY_QUERY_NAME, forward_query)
+}
+
+// Resolves the URI against the URL that the playlist was redirected to, so that it no longer
+// depends on the base URL from the query. The HLS variables are replaced first, as resolving would
+// percent encode their braces.
+fn redirected_uri<'a>(
+    uri: &'a str,
+    local_definitions: &HashMap<String, String>,
+    redirected_url: Option<&Url>,
+) -> Cow<'a, str> {
+    let Some(redirected_url) = redirected_url else {
+        return Cow::Borrowed(uri);
+    };
+    match redirected_url.join(&replace_hls_variables(uri, local_definitions)) {
+        Ok(url) => Cow::Owned(url.to_string()),
+        Err(_) => Cow::Borrowed(uri),
+    }
+}
+
+// Resolves the URI against the playlist URL and copies across the query parameters named by the
+// (query encoded) `forward_query`, unless the URI already sets them. Without any parameters to
+// forward the URI is left to be resolved as normal.
+fn forwarded_uri<'a>(
+    base_url: &Url,
+    uri: &'a str,
+    local_definitions: &HashMap<String, String>,
+    forward_query: Option<&str>,
+) -> Cow<'a, str> {
+    // A data: URI is not requested from anywhere, so there is nothing to forward onto it.
+    let Some(forward_query) = forward_query.filter(|_| !is_data_uri(uri)) else {
+        return Cow::Borrowed(uri);
+    };
+    let forward_query = percent_decode(forward_query);
+    let names = forward_query
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    let Ok(mut url) = base_url.join(&replace_hls_variables(uri, local_definitions)) else {
+        return Cow::Borrowed(uri);
+    };
+    let existing = url
+        .query_pairs()
+        .map(|(name, _)| name.into_owned())
+        .collect::<Vec<_>>();
+    let forwarded = base_url
+        .query_pairs()
+        .filter(|(name, _)| names.contains(&"*") || names.contains(&name.as_ref()))
+        .filter(|(name, _)| !existing.iter().any(|existing| existing == name))
+        .collect::<Vec<_>>();
+    if forwarded.is_empty() {
+        return Cow::Borrowed(uri);
+    }
+    url.query_pairs_mut().extend_pairs(forwarded);
+    Cow::Owned(url.to_string())
+}
+
+// The value is expected to already be query encoded.
+fn append_query_value(href: String, query_name: &str, query_value: Option<String>) -> String {
+    match query_value {
+        Some(query_value) => format!("{href}&{query_name}={query_value}"),
+        None => href,
+    }
+}
 
 fn playlist_href(
     base_url: Url,
@@ -314,6 +634,69 @@ mod tests {
     use crate::utils::tests::assert_definitions_string_equality;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn replace_query_value_should_replace_or_remove_the_named_value() {
+        let search = "playlist_url=a&pinned_view_context=old&variant_attributes=b";
+        assert_eq!(
+            "?playlist_url=a&variant_attributes=b&pinned_view_context=new",
+            replace_query_value(search, PINNED_VIEW_QUERY_NAME, Some("new"))
+        );
+        assert_eq!(
+            "?playlist_url=a&variant_attributes=b",
+            replace_query_value(search, PINNED_VIEW_QUERY_NAME, None)
+        );
+        assert_eq!(
+            "?pinned_view_context=new",
+            replace_query_value("", PINNED_VIEW_QUERY_NAME, Some("new"))
+        );
+    }
+
+    #[test]
+    fn parent_playlist_should_round_trip_through_the_query_of_the_child() {
+        let parent_search = "playlist_url=mvp&imported_definitions=defs&pinned_view_context=old";
+        let value = parent_playlist_value(parent_search, 12);
+        let child_search = format!(
+            "playlist_url=media&{PARENT_PLAYLIST_QUERY_NAME}={value}&pinned_view_context=new"
+        );
+        assert_eq!(
+            Some(ParentPlaylist {
+                href: String::from(
+                    "?playlist_url=mvp&imported_definitions=defs&pinned_view_context=new"
+                ),
+                playlist_url: String::from("mvp"),
+                line_number: 12,
+            }),
+            parent_playlist(&child_search)
+        );
+        assert_eq!(None, parent_playlist("playlist_url=media"));
+    }
+
+    #[test]
+    fn normalized_playlist_url_should_match_the_links_of_the_viewer() {
+        assert_eq!(
+            "https://example.com/",
+            normalized_playlist_url(String::from("HTTPS://Example.com"))
+        );
+        assert_eq!(
+            "https://example.com/hls/main.m3u8",
+            normalized_playlist_url(String::from("https://example.com/hls/main.m3u8"))
+        );
+        assert_eq!(
+            "not a url",
+            normalized_playlist_url(String::from("not a url"))
+        );
+    }
+
+    #[test]
+    fn playlist_name_should_be_the_last_segment_of_the_path() {
+        assert_eq!(
+            "video 1.m3u8",
+            playlist_name("https://example.com/hls/video%201.m3u8?token=abc")
+        );
+        assert_eq!("example.com", playlist_name("https://example.com/"));
+        assert_eq!("not a url", playlist_name("not a url"));
+    }
+
     #[test]
     fn resolve_href_should_provide_local_uri_with_query_for_relative_uri() {
         let base_url = Url::parse("https://example.com/hls/mvp.m3u8").unwrap();
@@ -682,6 +1065,121 @@ mod tests {
         );
     }
 
+    #[test]
+    fn append_query_value_only_appends_when_some() {
+        let href = String::from("?playlist_url=https://example.com/hls/hi/media.m3u8");
+        assert_eq!(
+            href.clone(),
+            append_query_value(href.clone(), VARIANT_QUERY_NAME, None)
+        );
+        assert_eq!(
+            format!("{href}&variant_attributes=CODECS%3D%22avc1.64001f%22"),
+            append_query_value(
+                href,
+                VARIANT_QUERY_NAME,
+                Some(percent_encode("CODECS=\"avc1.64001f\"").to_string()),
+            )
+        );
+    }
+
+    #[test]
+    fn forwarded_uri_copies_named_query_parameters() {
+        let base_url = Url::parse("https://example.com/hls/mvp.m3u8?token=abc&session=1").unwrap();
+        let definitions = HashMap::new();
+        assert_eq!(
+            "https://example.com/hls/hi/video.m3u8?token=abc",
+            forwarded_uri(&base_url, "hi/video.m3u8", &definitions, Some("token"))
+        );
+        assert_eq!(
+            "https://example.com/hls/hi/video.m3u8?a=1&token=abc&session=1",
+            forwarded_uri(&base_url, "hi/video.m3u8?a=1", &definitions, Some("*"))
+        );
+        // The URI keeps its own value of a parameter.
+        assert_eq!(
+            "https://cdn.com/seg.mp4?token=xyz&session=1",
+            forwarded_uri(
+                &base_url,
+                "https://cdn.com/seg.mp4?token=xyz",
+                &definitions,
+                Some("token%2Csession")
+            )
+        );
+        assert_eq!(
+            "hi/video.m3u8",
+            forwarded_uri(&base_url, "hi/video.m3u8", &definitions, None)
+        );
+        assert_eq!(
+            "hi/video.m3u8",
+            forwarded_uri(&base_url, "hi/video.m3u8", &definitions, Some("missing"))
+        );
+        assert_eq!(
+            "data:application/json;base64,e30=",
+            forwarded_uri(
+                &base_url,
+                "data:application/json;base64,e30=",
+                &definitions,
+                Some("*")
+            )
+        );
+    }
+
+    #[test]
+    fn redirected_uri_resolves_against_the_redirected_url() {
+        let redirected_url = Url::parse("https://edge.cdn.com/v1/hls/mvp.m3u8?s=1").unwrap();
+        let definitions = HashMap::from([(String::from("v"), String::from("hi"))]);
+        assert_eq!(
+            "https://edge.cdn.com/v1/hls/hi/video.m3u8",
+            redirected_uri("{$v}/video.m3u8", &definitions, Some(&redirected_url))
+        );
+        assert_eq!(
+            "https://other.com/video.m3u8",
+            redirected_uri(
+                "https://other.com/video.m3u8",
+                &definitions,
+                Some(&redirected_url)
+            )
+        );
+        assert_eq!(
+            "{$v}/video.m3u8",
+            redirected_uri("{$v}/video.m3u8", &definitions, None)
+        );
+        // The query of the playlist URL is still what is forwarded.
+        let base_url = Url::parse("https://example.com/hls/mvp.m3u8?token=abc").unwrap();
+        let uri = redirected_uri("video.m3u8", &definitions, Some(&redirected_url));
+        assert_eq!(
+            "https://edge.cdn.com/v1/hls/video.m3u8?token=abc",
+            forwarded_uri(&base_url, &uri, &definitions, Some("token"))
+        );
+        assert_eq!(
+            Some(format!(
+                "?{PLAYLIST_URL_QUERY_NAME}={}",
+                percent_encode("https://edge.cdn.com/v1/hls/video.m3u8")
+            )),
+            playlist_href(base_url, &uri, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn json_href_keeps_data_uris_intact() {
+        let base_url = Url::parse("https://example.com/hls/mvp.m3u8").unwrap();
+        let data_uri = "data:application/json;base64,eyJBU1NFVFMiOltdfQ==";
+        assert_eq!(
+            Some(format!(
+                "?{PLAYLIST_URL_QUERY_NAME}={}&{SUPPLEMENTAL_VIEW_QUERY_NAME}={}",
+                percent_encode(base_url.as_str()),
+                encode_asset_list(data_uri, "ad1"),
+            )),
+            json_href(
+                base_url,
+                None,
+                data_uri,
+                "ad1",
+                &HashMap::new(),
+                encode_asset_list
+            )
+        );
+    }
+
     const SCTE35_OUT_MESSAGE: &str = concat!(
         "0xfc303e0000000000000000c00506fe702f81fa0028022643554549000000017fff0000e297d00e1270636b5",
         "f455030343435303730333036393522040695798fb9",
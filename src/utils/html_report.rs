@@ -0,0 +1,312 @@
+// A standalone HTML report of what the viewer shows of a playlist, so that an analysis can be saved
+// and attached to a ticket and then read without the app (or the playlist still being available).
+// The report carries its own styles and has no scripts, links only within itself, and is built from
+// the same findings, timeline and box properties that the viewer renders.
+
+use crate::utils::{
+    mp4_atom_properties::{AtomProperties, AtomPropertyValue, BasicPropertyValue},
+    playlist_timeline::timeline,
+    segment_runs::format_duration,
+    validation::{Finding, Severity},
+};
+use std::fmt::Write;
+
+const STYLE: &str = "
+body { margin: 2rem; font-family: system-ui, sans-serif; color: #1c1917; }
+h1 { font-size: 1.5rem; margin-bottom: 0.25rem; }
+h2 { font-size: 1.125rem; margin-top: 2rem; }
+.source { color: #57534e; overflow-wrap: anywhere; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d6d3d1; padding: 0.125rem 0.5rem; text-align: start; }
+td { vertical-align: top; }
+pre, .playlist { font-family: ui-monospace, monospace; font-size: 0.875rem; }
+td pre { margin: 0; }
+.error { color: #b91c1c; }
+.warning { color: #b45309; }
+.playlist { background: #292524; color: #e0f2fe; padding: 0.75rem; overflow-x: auto; }
+.line { display: flex; white-space: pre-wrap; overflow-wrap: anywhere; }
+.line-number { flex: none; width: 4rem; color: #a8a29e; user-select: none; }
+.line.highlighted { background: #075985; }
+.line.error { border-left: 3px solid #f87171; color: inherit; }
+.line.warning { border-left: 3px solid #fbbf24; color: inherit; }
+.line:target { outline: 1px solid #7dd3fc; }
+";
+
+/// What the report is made of, as it was shown in the viewer when the report was exported.
+pub struct Report<'a> {
+    /// The name of the playlist, which titles the report.
+    pub title: &'a str,
+    /// Where the playlist was opened from (its URL or the name of its file).
+    pub source: &'a str,
+    pub playlist: &'a str,
+    /// The 1-based numbers of the lines that were highlighted for the resource opened from them.
+    pub highlighted_lines: &'a [usize],
+    pub findings: &'a [Finding],
+    /// The properties of the box that was selected in the box view, if one was open.
+    pub box_properties: Option<&'a AtomProperties>,
+}
+
+pub fn html_report(report: &Report) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n<p class=\"source\">{source}</p>\n\
+         <p>Exported from {name} {version}.</p>\n",
+        title = escape_html(report.title),
+        source = escape_html(report.source),
+        name = env!("CARGO_PKG_NAME"),
+        version = env!("CARGO_PKG_VERSION"),
+    );
+    summary(&mut html, report);
+    findings(&mut html, report.findings);
+    if let Some(properties) = report.box_properties {
+        box_properties(&mut html, properties);
+    }
+    playlist(&mut html, report);
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn summary(html: &mut String, report: &Report) {
+    let timeline = timeline(report.playlist);
+    let count = |severity| {
+        report
+            .findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    };
+    let mut rows = vec![("Lines", report.playlist.lines().count().to_string())];
+    if !timeline.segments.is_empty() {
+        let discontinuities = timeline
+            .segments
+            .iter()
+            .filter(|segment| segment.discontinuity)
+            .count();
+        rows.extend([
+            ("Segments", timeline.segments.len().to_string()),
+            ("Duration", format_duration(timeline.duration())),
+            ("Discontinuities", discontinuities.to_string()),
+            ("Date ranges", timeline.dateranges.len().to_string()),
+        ]);
+    }
+    rows.extend([
+        ("Errors", count(Severity::Error).to_string()),
+        ("Warnings", count(Severity::Warning).to_string()),
+    ]);
+    html.push_str("<h2>Summary</h2>\n<table>\n");
+    for (name, value) in rows {
+        let _ = writeln!(
+            html,
+            "<tr><th scope=\"row\">{name}</th><td>{value}</td></tr>"
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn findings(html: &mut String, findings: &[Finding]) {
+    html.push_str("<h2>Validation</h2>\n");
+    if findings.is_empty() {
+        html.push_str("<p>No problems were found.</p>\n");
+        return;
+    }
+    html.push_str(
+        "<table>\n<tr><th>Severity</th><th>Line</th><th>Rule</th><th>Finding</th></tr>\n",
+    );
+    for finding in findings {
+        let (class, severity) = severity_names(finding.severity);
+        let line = finding
+            .line
+            .map(|line| format!("<a href=\"#line-{line}\">{line}</a>"))
+            .unwrap_or_default();
+        let _ = writeln!(
+            html,
+            "<tr class=\"{class}\"><td>{severity}</td><td>{line}</td><td>{}</td><td>{}</td></tr>",
+            finding.rule,
+            escape_html(&finding.message),
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn box_properties(html: &mut String, properties: &AtomProperties) {
+    let _ = writeln!(
+        html,
+        "<h2>{}</h2>\n<table>\n<tr><th>Property</th><th>Value</th></tr>",
+        escape_html(properties.box_name)
+    );
+    for (key, value) in &properties.properties {
+        let value = match value {
+            AtomPropertyValue::Basic(value) => property_html(value),
+            AtomPropertyValue::Table(table) if table.rows.iter().all(Vec::is_empty) => {
+                String::new()
+            }
+            AtomPropertyValue::Table(table) => {
+                let mut inner = String::from("<table>");
+                if let Some(headers) = &table.headers {
+                    inner.push_str("<tr>");
+                    for header in headers {
+                        let _ = write!(inner, "<th>{}</th>", escape_html(header));
+                    }
+                    inner.push_str("</tr>");
+                }
+                for row in &table.rows {
+                    inner.push_str("<tr>");
+                    for column in row {
+                        let _ = write!(inner, "<td>{}</td>", property_html(column));
+                    }
+                    inner.push_str("</tr>");
+                }
+                inner.push_str("</table>");
+                inner
+            }
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{value}</td></tr>",
+            escape_html(key)
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+// Hex and binary masks are laid out in rows and columns, which only line up preformatted, as they
+// are in the box view, where links are kept too.
+fn property_html(value: &BasicPropertyValue) -> String {
+    let text = escape_html(&String::from(value));
+    if value.is_preformatted() {
+        format!("<pre>{text}</pre>")
+    } else if let BasicPropertyValue::Url(_) = value {
+        format!("<a href=\"{text}\">{text}</a>")
+    } else {
+        text
+    }
+}
+
+// The lines are given the same anchors as in the viewer, so that links into the playlist (from the
+// findings, or added to the report by hand) read the same.
+fn playlist(html: &mut String, report: &Report) {
+    html.push_str("<h2>Playlist</h2>\n<div class=\"playlist\">\n");
+    for (index, line) in report.playlist.lines().enumerate() {
+        let number = index + 1;
+        let mut classes = String::from("line");
+        if report.highlighted_lines.contains(&number) {
+            classes.push_str(" highlighted");
+        }
+        let severity = report
+            .findings
+            .iter()
+            .filter(|finding| finding.line == Some(number))
+            .map(|finding| finding.severity)
+            .min();
+        if let Some(severity) = severity {
+            let _ = write!(classes, " {}", severity_names(severity).0);
+        }
+        let _ = writeln!(
+            html,
+            "<div class=\"{classes}\" id=\"line-{number}\">\
+             <span class=\"line-number\">{number}</span><span>{}</span></div>",
+            escape_html(line)
+        );
+    }
+    html.push_str("</div>\n");
+}
+
+fn severity_names(severity: Severity) -> (&'static str, &'static str) {
+    match severity {
+        Severity::Error => ("error", "Error"),
+        Severity::Warning => ("warning", "Warning"),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = r#"#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXTINF:4,
+a.mp4
+#EXT-X-DISCONTINUITY
+#EXTINF:4,<b>
+b.mp4"#;
+
+    #[test]
+    fn report_should_escape_the_playlist_and_mark_its_lines() {
+        let findings = [Finding {
+            severity: Severity::Warning,
+            line: Some(6),
+            rule: "extinf-title",
+            message: String::from("title is \"<b>\""),
+        }];
+        let html = html_report(&Report {
+            title: "media.m3u8",
+            source: "https://example.com/media.m3u8?a=1&b=2",
+            playlist: PLAYLIST,
+            highlighted_lines: &[4],
+            findings: &findings,
+            box_properties: None,
+        });
+        assert!(html.contains("https://example.com/media.m3u8?a=1&amp;b=2"));
+        assert!(html.contains(
+            "<div class=\"line highlighted\" id=\"line-4\">\
+             <span class=\"line-number\">4</span><span>a.mp4</span></div>"
+        ));
+        assert!(html.contains(
+            "<div class=\"line warning\" id=\"line-6\">\
+             <span class=\"line-number\">6</span><span>#EXTINF:4,&lt;b&gt;</span></div>"
+        ));
+        assert!(html.contains(
+            "<td><a href=\"#line-6\">6</a></td><td>extinf-title</td>\
+             <td>title is &quot;&lt;b&gt;&quot;</td>"
+        ));
+        assert!(!html.contains("<b>"));
+    }
+
+    #[test]
+    fn report_should_summarize_media_playlists() {
+        let html = html_report(&Report {
+            title: "media.m3u8",
+            source: "media.m3u8",
+            playlist: PLAYLIST,
+            highlighted_lines: &[],
+            findings: &[],
+            box_properties: None,
+        });
+        let summary = html
+            .lines()
+            .filter(|line| line.starts_with("<tr><th scope=\"row\">"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "<tr><th scope=\"row\">Lines</th><td>7</td></tr>",
+                "<tr><th scope=\"row\">Segments</th><td>2</td></tr>",
+                "<tr><th scope=\"row\">Duration</th><td>8s</td></tr>",
+                "<tr><th scope=\"row\">Discontinuities</th><td>1</td></tr>",
+                "<tr><th scope=\"row\">Date ranges</th><td>0</td></tr>",
+                "<tr><th scope=\"row\">Errors</th><td>0</td></tr>",
+                "<tr><th scope=\"row\">Warnings</th><td>0</td></tr>",
+            ],
+            summary
+        );
+        assert!(html.contains("<p>No problems were found.</p>"));
+    }
+}
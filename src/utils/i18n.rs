@@ -0,0 +1,1422 @@
+// The text of the UI in the language of the reader. Each string is looked up by its `Message` key,
+// so that the text lives in one table per locale rather than throughout the components.
+//
+// English is the baseline, and its table must have every message (which the compiler checks, as it
+// is an exhaustive match). To add a locale:
+// - add a variant to `Locale`, along with its language tag;
+// - add a table for it alongside `english`, returning `None` for any message not yet translated so
+//   that English is shown in its place;
+// - keep the `{placeholders}` of each message as they are in English, as they are filled in by name.
+
+use std::cell::Cell;
+
+thread_local! {
+    static LOCALE: Cell<Option<Locale>> = const { Cell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    English,
+}
+
+impl Locale {
+    pub const ALL: [Self; 1] = [Self::English];
+
+    /// The BCP 47 language tag of the locale.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::English => "en",
+        }
+    }
+
+    /// Matches a language tag (e.g. `en-GB`) to a locale by its primary language.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_']).next()?;
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.tag().eq_ignore_ascii_case(language))
+    }
+
+    fn translate(self, message: Message) -> Option<&'static str> {
+        match self {
+            Self::English => Some(english(message)),
+        }
+    }
+}
+
+/// The first of the languages of the browser that there is a locale for, or else English.
+pub fn preferred_locale(languages: &[String]) -> Locale {
+    languages
+        .iter()
+        .find_map(|tag| Locale::from_tag(tag))
+        .unwrap_or(Locale::English)
+}
+
+/// The locale that the UI is shown in, which is settled on first use for the rest of the session.
+pub fn locale() -> Locale {
+    LOCALE.with(|current| {
+        if let Some(locale) = current.get() {
+            return locale;
+        }
+        // Outside of a browser (as in tests) there is nothing to go by but the baseline.
+        let locale = if cfg!(target_arch = "wasm32") {
+            browser_locale()
+        } else {
+            Locale::English
+        };
+        current.set(Some(locale));
+        locale
+    })
+}
+
+/// The text of `message` in the current locale.
+pub fn tr(message: Message) -> &'static str {
+    locale()
+        .translate(message)
+        .unwrap_or_else(|| english(message))
+}
+
+/// The text of `message` in the current locale, with its `{placeholders}` filled in by name.
+pub fn tr_with(message: Message, args: &[(&str, &str)]) -> String {
+    fill(tr(message), args)
+}
+
+// The placeholders are filled in one pass, so that a value is never mistaken for a placeholder, and
+// any placeholder without a value is left as it is.
+fn fill(template: &str, args: &[(&str, &str)]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = placeholder.find('}').and_then(|end| {
+            let name = &placeholder[1..end];
+            args.iter()
+                .find(|(arg, _)| *arg == name)
+                .map(|(_, value)| (*value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                text.push_str(value);
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+#[cfg(feature = "web")]
+fn browser_locale() -> Locale {
+    preferred_locale(&browser_languages())
+}
+
+// Without the web feature there is no browser to ask.
+#[cfg(not(feature = "web"))]
+fn browser_locale() -> Locale {
+    Locale::English
+}
+
+#[cfg(feature = "web")]
+fn browser_languages() -> Vec<String> {
+    let Some(window) = web_sys::window() else {
+        return Vec::new();
+    };
+    window
+        .navigator()
+        .languages()
+        .iter()
+        .filter_map(|language| language.as_string())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    // Navigation
+    NavHome,
+    NavCompare,
+    NavExamples,
+    NavAbout,
+    NavSettings,
+    // Errors
+    NetworkError,
+    TimeoutError,
+    TimeoutErrorInfo,
+    HttpStatusError,
+    CorsError,
+    RetriedOnceError,
+    RetriedError,
+    ProxiedError,
+    PlaylistIdentifierNotPresent,
+    SupplementalViewQueryError,
+    PinnedViewQueryError,
+    Retry,
+    Dismiss,
+    ShowingLastSegment,
+    // Validation
+    ValidationTitle,
+    ValidationPassed,
+    ValidationSummary,
+    SeverityError,
+    SeverityWarning,
+    OneError,
+    Errors,
+    OneWarning,
+    Warnings,
+    FindingLine,
+    FindingPlaylist,
+    FindingRule,
+    // Validation findings
+    PlaylistHeaderMissing,
+    PlaylistKindMixed,
+    PlaylistKindMedia,
+    PlaylistKindMultivariant,
+    UniqueTagRepeated,
+    TargetDurationRequired,
+    TagAfterFirstSegment,
+    ExtinfDurationMissing,
+    ExtinfDurationTooLong,
+    ExtinfWithoutUri,
+    SegmentUriWithoutExtinf,
+    VariantUriWithoutStreamInf,
+    StreamInfWithoutUri,
+    AttributeMalformed,
+    AttributeRepeated,
+    AttributeInvalid,
+    AttributeRequired,
+    KeyUriRequired,
+    KeyMethodNoneWithAttributes,
+    ClosedCaptionsWithUri,
+    InstreamIdRequired,
+    InstreamIdNotAllowed,
+    AutoselectRequired,
+    SessionKeyMethodNone,
+    SessionDataValueOrUri,
+    CodecsRecommended,
+    DefineSourceRequired,
+    DefineValueRequired,
+    ByterangeValueInvalid,
+    TagValueInvalid,
+    ValueQuoted,
+    ValueInvalid,
+    ValueNotQuoted,
+    ValueNotQuotedOrNone,
+    TypeDecimalInteger,
+    TypeDecimalFloatingPoint,
+    TypeSignedDecimalFloatingPoint,
+    TypeDecimalResolution,
+    TypeHexadecimalSequence,
+    TypeEnumeratedString,
+    VersionRequired,
+    VersionUnnecessary,
+    FeatureVariableSubstitution,
+    FeatureKeyIv,
+    FeatureKeyFormat,
+    FeatureDecimalDuration,
+    FeatureByterange,
+    FeatureIFramesOnly,
+    FeatureMap,
+    FeatureMapWithoutIFramesOnly,
+    FeatureInstreamIdService,
+    FeatureDefineQueryparam,
+    FeatureSkipDateranges,
+    FeatureSkip,
+    FeatureReqAttribute,
+    // Playlist actions
+    ShowRawText,
+    ShowRendered,
+    RawTextTitle,
+    WrapLines,
+    DontWrapLines,
+    LineWrapTitle,
+    ExportReport,
+    ExportReportTitle,
+    ExportJson,
+    ExportJsonTitle,
+    ExportBoxesJsonTitle,
+    ExportCsv,
+    ExportFindingsJsonTitle,
+    ExportFindingsCsvTitle,
+    // Loading
+    Loading,
+    LoadingFallback,
+    // Asset list
+    AssetListTotal,
+    AssetListBreakDuration,
+    AssetListJsonError,
+    PreviewAsset,
+    HideAssetPreview,
+    NetworkAsset,
+    AssetMultivariantSummary,
+    AssetMediaSummary,
+    // Bandwidth
+    MeasureBandwidth,
+    BandwidthSampleOf,
+    BandwidthSampleSegments,
+    DownloadingSegments,
+    GraphSegmentBitrates,
+    RequestingSegmentSizes,
+    SegmentWithoutContentLength,
+    SegmentBitrateTitle,
+    SegmentsExceedBandwidth,
+    SegmentsNotSized,
+    BandwidthPeak,
+    BandwidthAverage,
+    BandwidthMeasured,
+    BitsPerSecond,
+    BandwidthAdvertised,
+    BandwidthDeviation,
+    AttributeNotSet,
+    BandwidthMeasuredNote,
+    UnresolvedSegmentUri,
+    NoSegmentsToMeasure,
+    // Common labels
+    Previous,
+    Next,
+    ShowAll,
+    // Breadcrumbs
+    MediaPlaylistCrumb,
+    PlaylistCrumb,
+    MultivariantPlaylistCrumb,
+    ParentPlaylistTitle,
+    BackToMultivariant,
+    BackToMultivariantTitle,
+    // Collapsed segments
+    CollapsedSegments,
+    CollapsedSegmentsTitle,
+    // Deep link
+    CopyLink,
+    CopyLinkCopied,
+    CopyLinkTitle,
+    CompressLink,
+    CompressLinkTitle,
+    // Find
+    FindNoMatches,
+    FindMatch,
+    FindPlaceholder,
+    FindLabel,
+    FindTitle,
+    // Font size
+    TextSize,
+    SmallerText,
+    SmallerTextTitle,
+    ResetTextSize,
+    ResetTextSizeTitle,
+    LargerText,
+    LargerTextTitle,
+    // Date range schedule
+    DaterangesJsonError,
+    // Line filter
+    ShowOnly,
+    OneLineHidden,
+    LinesHidden,
+    // Live update
+    LiveUpdateTitle,
+    LiveUpdateHint,
+    Recheck,
+    RecheckUnconditional,
+    RecheckNotModified,
+    RecheckReloaded,
+    RecheckFailed,
+    // Network
+    Network,
+    NetworkPlaylist,
+    NetworkSegment,
+    NetworkMap,
+    NetworkPart,
+    NetworkInitializationSegment,
+    NetworkKey,
+    NetworkExposedHeaders,
+    NetworkResponseStatus,
+    TimingFromResourceTiming,
+    TimingAroundFetch,
+    TimingSummary,
+    // Viewer
+    NetworkAssetList,
+    NetworkSchedule,
+    UnknownImageContentType,
+    UnsupportedSegmentType,
+    SupportedSegmentTypes,
+    LocalFileStatus,
+    // Pinned view
+    PinInstead,
+    PinInsteadTitle,
+    PinForComparison,
+    PinForComparisonTitle,
+    PinnedSideBySide,
+    PinnedAsTabs,
+    Pinned,
+    PinnedLabel,
+    Unpin,
+    UnpinTitle,
+    SupplementalViews,
+    // Playlist diff
+    PlaylistsIdentical,
+    PlaylistDiffSummary,
+    // Playlist timeline
+    TimelineSegmentTitle,
+    TimelineDiscontinuityTitle,
+    // Segment context
+    SegmentContextMap,
+    SegmentContextKey,
+    SegmentContextDate,
+    SegmentContextNone,
+    // Segment navigation
+    PreviousSegment,
+    PreviousSegmentTitle,
+    NextSegment,
+    NextSegmentTitle,
+    // Split layout
+    SplitBesideTitle,
+    SplitStackTitle,
+    SplitResizeTitle,
+    // Timeline drift
+    AbsoluteDrift,
+    DriftHeading,
+    DriftOpenFurtherSegments,
+    DriftExtrapolatedTitle,
+    DriftSegment,
+    DriftPlaylistElapsed,
+    DriftMediaElapsed,
+    DriftMillis,
+    // WebVTT
+    WebVttPassed,
+    WebVttSummary,
+    // ISOBMFF
+    SamplesEncrypted,
+    ParseStructureOnly,
+    MediaDataNotDownloaded,
+    Boxes,
+    CodecsMatch,
+    ResolutionMatches,
+    FrameRateMatches,
+    EncryptionConsistent,
+    ChecksAgainstPlaylist,
+    BoxProperty,
+    BoxValue,
+    TableRows,
+    TableRowRange,
+    ShowingBytes,
+    ShowNextBytes,
+    // SCTE-35
+    Scte35ReadError,
+    Scte35ParseError,
+    Scte35JsonError,
+    Scte35Id,
+    Scte35Type,
+    Scte35Message,
+    Scte35Encoding,
+    Scte35Fields,
+    Scte35Decoded,
+    ExportXml,
+    ExportXmlTitle,
+    ExportScte35JsonTitle,
+    Scte35ChecksPassed,
+    Scte35ChecksFailed,
+    CheckPassed,
+    CheckFailed,
+    SpliceTimeWithAdjustment,
+    SpliceClockMediaTimeline,
+    SpliceImmediateNote,
+    SplicePtsNote,
+    FromStartDate,
+    SpliceSegmentOffset,
+    OutsideOfPlaylist,
+    SplicePoint,
+    SpliceTime,
+    WallClockTime,
+    WallClockTimeFrom,
+    SpliceSegment,
+    SpliceMode,
+    SpliceModeProgram,
+    SpliceModeComponent,
+    NotSpecified,
+    // Playlist
+    PlaylistRegion,
+    InvalidCue,
+    // Settings
+    SettingsTitle,
+    SettingsIntro,
+    SettingsRequests,
+    SegmentCacheBefore,
+    SegmentCacheAfter,
+    SegmentCacheTitle,
+    SettingsAppearance,
+    SettingsTheme,
+    ThemeSystem,
+    ThemeLight,
+    ThemeDark,
+    SettingsLivePlaylists,
+    LiveRecheckBefore,
+    LiveRecheckAfter,
+    LiveRecheckTitle,
+    SettingsViewer,
+    ToggleStructureOnly,
+    ToggleRawText,
+    TogglePlaylistNoWrap,
+    ToggleDiffNoWrap,
+    ToggleCompressedLinks,
+    SettingsLaunching,
+    ProtocolHandlerTitle,
+    ProtocolHandlerRegistered,
+    ProtocolHandlerRefused,
+    Bookmarklet,
+    BookmarkletTitle,
+    RegisterProtocolHandler,
+    RegisterProtocolHandlerTitle,
+    // Request settings
+    RequestTimeoutNone,
+    RequestTimeout,
+    OneExtraHeader,
+    ExtraHeaders,
+    RequestTimeoutBefore,
+    RequestTimeoutAfter,
+    RequestTimeoutTitle,
+    ExtraHeadersLabel,
+    ExtraHeadersPlaceholder,
+    ExtraHeadersTitle,
+    // Proxy settings
+    CorsProxy,
+    CorsProxyOff,
+    ProxyPrefixTitle,
+    UseCorsProxy,
+    ProxyPrefix,
+    ProxyBypassHosts,
+    ProxyBypassHostsTitle,
+    // Offline cache settings
+    OfflineCacheOff,
+    OfflineCacheKeeping,
+    OfflineCacheServing,
+    OneResponseKept,
+    ResponsesKept,
+    OfflineCacheUnavailable,
+    OfflineCache,
+    ClearOfflineCache,
+    ClearOfflineCacheTitle,
+    OfflineCacheModeOff,
+    OfflineCacheModeKeep,
+    OfflineCacheModeServe,
+    // Ad avails
+    AdAvailsSummary,
+    AdAvailsCount,
+    AdId,
+    AdAvailStart,
+    AdAvailDuration,
+    AdAvailStatus,
+    AdAvailSignalled,
+    AdAvailFilled,
+    AdAvailReturned,
+    // Ad breaks
+    AdBreaksSummary,
+    AdBreakEventId,
+    AdBreakPlanned,
+    AdBreakSignalled,
+    AdBreakActual,
+    AdBreakProblems,
+    PairedByEventId,
+    AdBreakWithoutIn,
+    AdBreakWithoutOut,
+    AdBreakLonger,
+    AdBreakShorter,
+    AdBreakOverlaps,
+    // Audio preview
+    PackedAudioSegment,
+    Play,
+    PlayAudioTitle,
+    Stop,
+    AudioPeak,
+    AudioSilent,
+    AudioDecoded,
+    // Box tree
+    ExpandAll,
+    CollapseAll,
+    ExpandToDepth,
+    Depth,
+    // Decryption
+    DecryptionKeyInvalid,
+    DecryptionFailed,
+    FetchedKeyLength,
+    FetchKey,
+    DecryptionIv,
+    DecryptionIvMissing,
+    SegmentEncrypted,
+    DecryptionKey,
+    DecryptionKeyPlaceholder,
+    Decrypt,
+    IvInvalid,
+    IvFromAttribute,
+    IvFromMediaSequence,
+    IvMissing,
+    // Hex dump
+    HideHexDump,
+    ShowHexDump,
+    HexDumpBytes,
+    // Interstitials
+    InterstitialsPlaying,
+    InterstitialsCount,
+    InterstitialScheduled,
+    InterstitialPlaysFor,
+    InterstitialResumes,
+    InterstitialTotalPlayed,
+    PrimaryDelayTitle,
+    PrimaryDelay,
+    PreRoll,
+    PostRoll,
+    PlayoutLimited,
+    ResumeUnknown,
+    ResumeByDefault,
+    ResumeAt,
+    ResumeIntoPrimary,
+    // Key IDs
+    KeyIdsSummary,
+    KeyIdSegment,
+    KeyIdProblems,
+    // Key rotation
+    KeyRotationSummary,
+    KeyRotationSegment,
+    KeyRotationKey,
+    KeyRotationIv,
+    KeyRotationIvFrom,
+    KeyWithoutUri,
+    IvNotHex,
+    IvAttribute,
+    MediaSequenceNumber,
+    // Live timeline
+    LiveSegmentTitle,
+    HoldBackWithPart,
+    HoldBack,
+    LiveStatusAfter,
+    LiveStatusBefore,
+    PlaylistEndsInFuture,
+    PlaylistBehindSchedule,
+    LiveEdge,
+    LiveNow,
+    // Media preview
+    PlaySegmentTitle,
+    // Sample encryption
+    SampleEncryptionScheme,
+    SampleEncryptionExplained,
+    SampleEncryptionSummary,
+    SampleEncryptionBox,
+    SampleEncryptionRole,
+    InInitializationSegment,
+    // Segment download
+    BytesOfTotal,
+    BytesReceived,
+    // Thumbnails
+    DecodingFirstKeyframe,
+    FirstKeyframe,
+    ShowIFrameThumbnails,
+    DecodingIFrames,
+    IFrame,
+    IFrameNotFmp4,
+    UnresolvedMapUri,
+    NoVideoSampleDescription,
+    NoKeyframe,
+    UnknownInitializationCodec,
+    // Variant summary
+    VariantsSummary,
+    VariantLine,
+    // FairPlay
+    FairPlayKeyId,
+    FairPlayAssetId,
+    FairPlayAssetIdTitle,
+    // Launching
+    OpeningPlaylist,
+    OpeningTarget,
+    NothingToOpen,
+    // Session export
+    ExportHarTitle,
+    ExportHar,
+    ExportSessionTitle,
+    ExportSession,
+    ImportSession,
+    ReplayingSession,
+    StopReplaying,
+    // URL input
+    EnterUrl,
+    PastePlaylist,
+    PlaylistUrlLabel,
+    PlaylistUrlTitle,
+    ForwardQueryPlaceholder,
+    ForwardQueryLabel,
+    ForwardQueryTitle,
+    PlaylistTextLabel,
+    ShowPastedPlaylist,
+    PastedPlaylistName,
+    RecentPlaylists,
+    RemoveRecentPlaylist,
+    RemoveRecentPlaylistTitle,
+    ClearRecentPlaylists,
+    // Local file
+    OpenLocalFile,
+    BaseUrlPlaceholder,
+    BaseUrlTitle,
+    ShowingLocalFile,
+    CloseLocalFile,
+    // Download
+    Download,
+    DownloadTitle,
+    // Compare
+    CompareHeading,
+    CompareIntro,
+    LeftPlaylistUrlLabel,
+    RightPlaylistUrlLabel,
+    CompareSubmit,
+    // Supplemental views
+    SupplementalSegment,
+    SupplementalMap,
+    SupplementalPart,
+    SupplementalScte35,
+    SupplementalAssetList,
+    SupplementalSchedule,
+    // Line categories
+    LineCategorySegments,
+    LineCategoryDateranges,
+    LineCategoryKeys,
+    LineCategoryParts,
+    LineCategoryComments,
+    LineCategoryVariants,
+}
+
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::NavHome => "Home",
+        Message::NavCompare => "Compare",
+        Message::NavExamples => "Examples",
+        Message::NavAbout => "About",
+        Message::NavSettings => "Settings",
+        Message::NetworkError => "Network error: {message}",
+        Message::TimeoutError => "Error: the request timed out",
+        Message::TimeoutErrorInfo => {
+            "The timeout applies to each attempt, including the download of the response body, \
+            and can be changed in the request settings."
+        }
+        Message::HttpStatusError => "Bad HTTP status code: {status} {status_text}",
+        Message::CorsError => {
+            "CORS error: the server responded without allowing this page to read the response"
+        }
+        Message::RetriedOnceError => "{error} (failed after 1 retry)",
+        Message::RetriedError => "{error} (failed after {retries} retries)",
+        Message::ProxiedError => "{error} (requested through the CORS proxy as {proxied_url})",
+        Message::PlaylistIdentifierNotPresent => "Error: playlist identifier (#EXTM3U) not present",
+        Message::SupplementalViewQueryError => {
+            "Error: unable to parse query parameter for supplemental view"
+        }
+        Message::PinnedViewQueryError => "Error: unable to parse query parameter for pinned view",
+        Message::Retry => "Retry",
+        Message::Dismiss => "Dismiss",
+        Message::ShowingLastSegment => "Still showing the last segment that loaded ({url})",
+        Message::ValidationTitle => "Validation",
+        Message::ValidationPassed => "{title}: no problems found",
+        Message::ValidationSummary => "{title}: {errors}, {warnings}",
+        Message::SeverityError => "Error",
+        Message::SeverityWarning => "Warning",
+        Message::OneError => "1 error",
+        Message::Errors => "{count} errors",
+        Message::OneWarning => "1 warning",
+        Message::Warnings => "{count} warnings",
+        Message::FindingLine => "Line {line}",
+        Message::FindingPlaylist => "Playlist",
+        Message::FindingRule => "Rule",
+        Message::PlaylistHeaderMissing => "The first line of the playlist must be #EXTM3U.",
+        Message::PlaylistKindMixed => {
+            "#{tag} cannot appear in a {kind}; a playlist must not mix Media and Multivariant \
+             Playlist tags."
+        }
+        Message::PlaylistKindMedia => "Media Playlist",
+        Message::PlaylistKindMultivariant => "Multivariant Playlist",
+        Message::UniqueTagRepeated => {
+            "#{tag} must not appear more than once (first seen on line {line})."
+        }
+        Message::TargetDurationRequired => {
+            "A Media Playlist must contain an #EXT-X-TARGETDURATION tag."
+        }
+        Message::TagAfterFirstSegment => "#{tag} must appear before the first Media Segment.",
+        Message::ExtinfDurationMissing => {
+            "#EXTINF must begin with a decimal-floating-point duration."
+        }
+        Message::ExtinfDurationTooLong => {
+            "Segment duration {duration} rounds to more than the target duration of \
+             {target_duration}."
+        }
+        Message::ExtinfWithoutUri => "#EXTINF is not followed by a Media Segment URI.",
+        Message::SegmentUriWithoutExtinf => "Media Segment URI is not preceded by an #EXTINF tag.",
+        Message::VariantUriWithoutStreamInf => {
+            "URI line is not preceded by an #EXT-X-STREAM-INF tag."
+        }
+        Message::StreamInfWithoutUri => "#EXT-X-STREAM-INF must be followed by a URI line.",
+        Message::AttributeMalformed => "Malformed attribute \"{entry}\"; expected NAME=VALUE.",
+        Message::AttributeRepeated => "Attribute {name} must not appear more than once in #{tag}.",
+        Message::AttributeInvalid => "{name} {problem}.",
+        Message::AttributeRequired => "#{tag} is missing the required {name} attribute.",
+        Message::KeyUriRequired => {
+            "#EXT-X-KEY is missing the URI attribute, which is required unless the METHOD is NONE."
+        }
+        Message::KeyMethodNoneWithAttributes => {
+            "When METHOD is NONE, the other attributes of #EXT-X-KEY must not be present."
+        }
+        Message::ClosedCaptionsWithUri => "URI must not be present when TYPE is CLOSED-CAPTIONS.",
+        Message::InstreamIdRequired => "INSTREAM-ID is required when TYPE is CLOSED-CAPTIONS.",
+        Message::InstreamIdNotAllowed => {
+            "INSTREAM-ID must only be specified when TYPE is CLOSED-CAPTIONS."
+        }
+        Message::AutoselectRequired => "AUTOSELECT must be YES when DEFAULT is YES.",
+        Message::SessionKeyMethodNone => "The METHOD of #EXT-X-SESSION-KEY must not be NONE.",
+        Message::SessionDataValueOrUri => {
+            "#EXT-X-SESSION-DATA must contain either a VALUE or a URI attribute, but not both."
+        }
+        Message::CodecsRecommended => "#{tag} should include a CODECS attribute.",
+        Message::DefineSourceRequired => {
+            "#EXT-X-DEFINE must contain exactly one of NAME, IMPORT or QUERYPARAM."
+        }
+        Message::DefineValueRequired => {
+            "#EXT-X-DEFINE with a NAME attribute must also contain a VALUE attribute."
+        }
+        Message::ByterangeValueInvalid => {
+            "#EXT-X-BYTERANGE must have a value of the form <n>[@<o>]."
+        }
+        Message::TagValueInvalid => "The value of #{tag} {problem}.",
+        Message::ValueQuoted => "must be {type}, not a quoted-string",
+        Message::ValueInvalid => "must be {type} but was {value}",
+        Message::ValueNotQuoted => "must be a quoted-string",
+        Message::ValueNotQuotedOrNone => "must be a quoted-string or NONE",
+        Message::TypeDecimalInteger => "a decimal-integer",
+        Message::TypeDecimalFloatingPoint => "a decimal-floating-point",
+        Message::TypeSignedDecimalFloatingPoint => "a signed-decimal-floating-point",
+        Message::TypeDecimalResolution => "a decimal-resolution (e.g. 1920x1080)",
+        Message::TypeHexadecimalSequence => "a hexadecimal-sequence",
+        Message::TypeEnumeratedString => "one of {values}",
+        Message::VersionRequired => {
+            "{feature} requires EXT-X-VERSION {required} or greater (playlist is version \
+             {version})."
+        }
+        Message::VersionUnnecessary => {
+            "EXT-X-VERSION is {version} but the features used only require version {required}, \
+             which limits compatibility with older clients."
+        }
+        Message::FeatureVariableSubstitution => "Variable substitution",
+        Message::FeatureKeyIv => "The IV attribute of EXT-X-KEY",
+        Message::FeatureKeyFormat => "The KEYFORMAT and KEYFORMATVERSIONS attributes of EXT-X-KEY",
+        Message::FeatureDecimalDuration => "A decimal-floating-point #EXTINF duration",
+        Message::FeatureByterange => "EXT-X-BYTERANGE",
+        Message::FeatureIFramesOnly => "EXT-X-I-FRAMES-ONLY",
+        Message::FeatureMap => "EXT-X-MAP",
+        Message::FeatureMapWithoutIFramesOnly => {
+            "EXT-X-MAP in a playlist without EXT-X-I-FRAMES-ONLY"
+        }
+        Message::FeatureInstreamIdService => "A SERVICE value for INSTREAM-ID",
+        Message::FeatureDefineQueryparam => "The QUERYPARAM attribute of EXT-X-DEFINE",
+        Message::FeatureSkipDateranges => "EXT-X-SKIP with RECENTLY-REMOVED-DATERANGES",
+        Message::FeatureSkip => "EXT-X-SKIP",
+        Message::FeatureReqAttribute => "An attribute name starting with REQ-",
+        Message::ShowRawText => "Show raw text",
+        Message::ShowRendered => "Show rendered",
+        Message::RawTextTitle => "switch between the rendered playlist and its exact text",
+        Message::WrapLines => "Wrap lines",
+        Message::DontWrapLines => "Don't wrap lines",
+        Message::LineWrapTitle => {
+            "switch between wrapping long lines and scrolling horizontally to read them"
+        }
+        Message::ExportReport => "Export report",
+        Message::ExportReportTitle => {
+            "save a standalone HTML report of this playlist to attach to a ticket"
+        }
+        Message::ExportJson => "Export JSON",
+        Message::ExportJsonTitle => {
+            "save the parsed tags, segments and variant streams of this playlist as JSON for \
+             scripts to read"
+        }
+        Message::ExportBoxesJsonTitle => {
+            "save the boxes of this segment, with their offsets, sizes and properties, as JSON"
+        }
+        Message::ExportCsv => "Export CSV",
+        Message::ExportFindingsJsonTitle => {
+            "save these findings, with a count of errors and warnings, as JSON to archive or check \
+             in CI"
+        }
+        Message::ExportFindingsCsvTitle => "save these findings as CSV to open in a spreadsheet",
+        Message::Loading => "Loading...",
+        Message::LoadingFallback => "LOADING...",
+        Message::AssetListTotal => "Total",
+        Message::AssetListBreakDuration => {
+            "The assets play for {duration}s of the {break_duration}s break ({difference}s)"
+        }
+        Message::AssetListJsonError => "Error deserializing JSON",
+        Message::PreviewAsset => "Preview",
+        Message::HideAssetPreview => "Hide",
+        Message::NetworkAsset => "Asset",
+        Message::AssetMultivariantSummary => {
+            "Multivariant playlist with {variants} variant stream(s)"
+        }
+        Message::AssetMediaSummary => {
+            "Media playlist with {segments} segment(s) playing for {duration}s, against a DURATION \
+             of {asset_duration}s"
+        }
+        Message::MeasureBandwidth => "Measure bandwidth",
+        Message::BandwidthSampleOf => "from a sample of",
+        Message::BandwidthSampleSegments => "segments",
+        Message::DownloadingSegments => "Downloading segments...",
+        Message::GraphSegmentBitrates => "Graph segment bit rates",
+        Message::RequestingSegmentSizes => "Requesting segment sizes...",
+        Message::SegmentWithoutContentLength => "{url}: no Content-Length",
+        Message::SegmentBitrateTitle => "{url} ({duration}s): {bitrate} bits/s",
+        Message::SegmentsExceedBandwidth => {
+            "{exceeding} of {count} segments exceed BANDWIDTH={bandwidth} bits/s."
+        }
+        Message::SegmentsNotSized => "{count} segments could not be sized",
+        Message::BandwidthPeak => "Peak",
+        Message::BandwidthAverage => "Average",
+        Message::BandwidthMeasured => "Measured",
+        Message::BitsPerSecond => "bits/s",
+        Message::BandwidthAdvertised => "Advertised",
+        Message::BandwidthDeviation => "Deviation",
+        Message::AttributeNotSet => "{attribute} not set",
+        Message::BandwidthMeasuredNote => {
+            "Only the segments of this Media Playlist are measured, whereas the advertised values \
+             also include any renditions (e.g. audio) that are played alongside it."
+        }
+        Message::UnresolvedSegmentUri => "Error: unable to resolve segment URI {uri}",
+        Message::NoSegmentsToMeasure => "Error: no segments with a duration were found to measure",
+        Message::Previous => "Previous",
+        Message::Next => "Next",
+        Message::ShowAll => "Show all",
+        Message::MediaPlaylistCrumb => "Media playlist: {name}",
+        Message::PlaylistCrumb => "Playlist: {name}",
+        Message::MultivariantPlaylistCrumb => "Multivariant playlist: {name}",
+        Message::ParentPlaylistTitle => "{url} (line {line})",
+        Message::BackToMultivariant => "Back to multivariant",
+        Message::BackToMultivariantTitle => {
+            "back to the variant in the multivariant playlist that linked here"
+        }
+        Message::CollapsedSegments => "… {count} segments ({duration}) …",
+        Message::CollapsedSegmentsTitle => "show the collapsed segments",
+        Message::CopyLink => "Copy link",
+        Message::CopyLinkCopied => "Copied",
+        Message::CopyLinkTitle => {
+            "copy a link to this view, scrolled to the line at the top of the window"
+        }
+        Message::CompressLink => "Compress",
+        Message::CompressLinkTitle => "pack the query of the link into one compressed value",
+        Message::FindNoMatches => "No matches",
+        Message::FindMatch => "{current} of {count}",
+        Message::FindPlaceholder => "Find in playlist",
+        Message::FindLabel => "find in playlist",
+        Message::FindTitle => {
+            "searches tag names, attribute values and URIs (Enter for the next match, Shift+Enter \
+             for the previous)"
+        }
+        Message::TextSize => "Text size",
+        Message::SmallerText => "Smaller text",
+        Message::SmallerTextTitle => "make the text smaller",
+        Message::ResetTextSize => "Reset text size",
+        Message::ResetTextSizeTitle => "reset the text to its usual size",
+        Message::LargerText => "Larger text",
+        Message::LargerTextTitle => "make the text larger",
+        Message::DaterangesJsonError => "Could not deserialize DATERANGES from JSON",
+        Message::ShowOnly => "Show only",
+        Message::OneLineHidden => "⋯ 1 line hidden",
+        Message::LinesHidden => "⋯ {count} lines hidden",
+        Message::LiveUpdateTitle => "Live update",
+        Message::LiveUpdateHint => {
+            "Recheck to reload the playlist and validate the update against the version shown \
+             here."
+        }
+        Message::Recheck => "Recheck",
+        Message::RecheckUnconditional => {
+            "The recheck was unconditional as the previous response had no readable ETag or \
+             Last-Modified header."
+        }
+        Message::RecheckNotModified => {
+            "The origin returned 304 Not Modified to the conditional recheck."
+        }
+        Message::RecheckReloaded => {
+            "The origin returned {status} {status_text} to the conditional recheck, so the \
+             playlist was reloaded."
+        }
+        Message::RecheckFailed => "The recheck failed, so the playlist is shown as before: {error}",
+        Message::Network => "Network",
+        Message::NetworkPlaylist => "Playlist",
+        Message::NetworkSegment => "Segment",
+        Message::NetworkMap => "Map",
+        Message::NetworkPart => "Part",
+        Message::NetworkInitializationSegment => "Initialization segment",
+        Message::NetworkKey => "Key",
+        Message::NetworkExposedHeaders => {
+            "Cross-origin responses only include the headers that the server lists in \
+             Access-Control-Expose-Headers, beyond the CORS-safelisted ones."
+        }
+        Message::NetworkResponseStatus => "{label}: {status} {status_text}",
+        Message::TimingFromResourceTiming => "resource timing",
+        Message::TimingAroundFetch => "measured around the fetch",
+        Message::TimingSummary => {
+            "Started {start} ms, TTFB {ttfb} ms, download {download} ms, {bytes} bytes{throughput} \
+             ({source})"
+        }
+        Message::NetworkAssetList => "Asset list",
+        Message::NetworkSchedule => "Schedule",
+        Message::UnknownImageContentType => "Error: unknown content type for image segment",
+        Message::UnsupportedSegmentType => "Error: unsupported segment type",
+        Message::SupportedSegmentTypes => {
+            "Currently only WebVTT, Fragmented MPEG-4 and packed audio segments are supported"
+        }
+        Message::LocalFileStatus => "(local file)",
+        Message::PinInstead => "Pin this instead",
+        Message::PinInsteadTitle => "replace the pinned view with this one",
+        Message::PinForComparison => "Pin for comparison",
+        Message::PinForComparisonTitle => "keep this open while other resources are opened",
+        Message::PinnedSideBySide => "Side by side",
+        Message::PinnedAsTabs => "As tabs",
+        Message::Pinned => "Pinned",
+        Message::PinnedLabel => "Pinned: {label}",
+        Message::Unpin => "Unpin",
+        Message::UnpinTitle => "close the pinned view",
+        Message::SupplementalViews => "Supplemental views",
+        Message::PlaylistsIdentical => "The playlists are identical.",
+        Message::PlaylistDiffSummary => {
+            "{changed} lines changed, {removed} only on the left, {added} only on the right."
+        }
+        Message::TimelineSegmentTitle => "segment {media_sequence} ({duration}s)",
+        Message::TimelineDiscontinuityTitle => "discontinuity",
+        Message::SegmentContextMap => "map",
+        Message::SegmentContextKey => "key",
+        Message::SegmentContextDate => "date",
+        Message::SegmentContextNone => "none",
+        Message::PreviousSegment => "Previous segment",
+        Message::PreviousSegmentTitle => "previous segment (left arrow)",
+        Message::NextSegment => "Next segment",
+        Message::NextSegmentTitle => "next segment (right arrow)",
+        Message::SplitBesideTitle => "show the supplemental view beside the playlist",
+        Message::SplitStackTitle => "stack the supplemental view below the playlist",
+        Message::SplitResizeTitle => "drag to resize",
+        Message::AbsoluteDrift => {
+            "The producer reference time (prft) places the start of the segment {drift} ms from \
+             its EXT-X-PROGRAM-DATE-TIME."
+        }
+        Message::DriftHeading => "Media timeline (tfdt) against EXT-X-PROGRAM-DATE-TIME",
+        Message::DriftOpenFurtherSegments => {
+            "Open further segments of this playlist to track drift across them."
+        }
+        Message::DriftExtrapolatedTitle => {
+            "EXT-X-PROGRAM-DATE-TIME extrapolated from the preceding EXTINF durations"
+        }
+        Message::DriftSegment => "Segment",
+        Message::DriftPlaylistElapsed => "PDT elapsed (s)",
+        Message::DriftMediaElapsed => "Media elapsed (s)",
+        Message::DriftMillis => "Drift (ms)",
+        Message::WebVttPassed => "WebVTT validation: no problems found",
+        Message::WebVttSummary => "WebVTT validation: {count} finding(s)",
+        Message::SamplesEncrypted => {
+            "The samples are encrypted ({schemes}), so the segment cannot be played without its \
+             key."
+        }
+        Message::ParseStructureOnly => "Parse structure only",
+        Message::MediaDataNotDownloaded => "(the media data was not downloaded)",
+        Message::Boxes => "Boxes",
+        Message::CodecsMatch => "{actual} matches CODECS {advertised}.",
+        Message::ResolutionMatches => "Video dimensions match RESOLUTION {advertised}.",
+        Message::FrameRateMatches => "Video frame rate {measured} matches FRAME-RATE {advertised}.",
+        Message::EncryptionConsistent => {
+            "Segment encryption is consistent with EXT-X-KEY METHOD={methods}."
+        }
+        Message::ChecksAgainstPlaylist => "Checks against the playlist",
+        Message::BoxProperty => "Property",
+        Message::BoxValue => "Value",
+        Message::TableRows => "{count} rows",
+        Message::TableRowRange => "Rows {start}-{end} of {count}",
+        Message::ShowingBytes => "Showing {shown} of {length} bytes",
+        Message::ShowNextBytes => "Show next {count} bytes",
+        Message::Scte35ReadError => "Error reading SCTE35 message",
+        Message::Scte35ParseError => "Error parsing SCTE35 data",
+        Message::Scte35JsonError => "Error converting to JSON",
+        Message::Scte35Id => "ID",
+        Message::Scte35Type => "Type",
+        Message::Scte35Message => "Message",
+        Message::Scte35Encoding => "Encoding",
+        Message::Scte35Fields => "Fields",
+        Message::Scte35Decoded => "Decoded",
+        Message::ExportXml => "Export XML",
+        Message::ExportXmlTitle => "save the splice_info_section as SCTE-35 XML",
+        Message::ExportScte35JsonTitle => "save the decoded splice_info_section as JSON",
+        Message::Scte35ChecksPassed => {
+            "Lengths and CRC: section_length, descriptor_loop_length and CRC_32 are valid"
+        }
+        Message::Scte35ChecksFailed => "Lengths and CRC: {count} check(s) failed",
+        Message::CheckPassed => "Pass",
+        Message::CheckFailed => "Fail",
+        Message::SpliceTimeWithAdjustment => "{ticks} with pts_adjustment",
+        Message::SpliceClockMediaTimeline => "media timeline of segment {media_sequence}",
+        Message::SpliceImmediateNote => {
+            "The splice happens at the first opportunity after the message, which is taken to be \
+             the START-DATE of the daterange."
+        }
+        Message::SplicePtsNote => {
+            "Inspect a segment to place the pts_time against its media timeline, rather than \
+             taking the START-DATE of the daterange to be the splice point."
+        }
+        Message::FromStartDate => "From START-DATE",
+        Message::SpliceSegmentOffset => ", {offset}s into its {duration}s",
+        Message::OutsideOfPlaylist => "Outside of the playlist",
+        Message::SplicePoint => "Splice point",
+        Message::SpliceTime => "Splice time",
+        Message::WallClockTime => "Wall-clock time",
+        Message::WallClockTimeFrom => "{date_time} (from the {clock})",
+        Message::SpliceSegment => "Segment",
+        Message::SpliceMode => "{flag} ({mode} splice mode)",
+        Message::SpliceModeProgram => "program",
+        Message::SpliceModeComponent => "component",
+        Message::NotSpecified => "not specified",
+        Message::PlaylistRegion => "Playlist",
+        Message::InvalidCue => "Invalid CUE",
+        Message::SettingsTitle => "Settings",
+        Message::SettingsIntro => {
+            "Settings are saved in this browser and apply to every page of the viewer from the \
+             moment they are changed. None of them are carried along in shared links."
+        }
+        Message::SettingsRequests => "Requests",
+        Message::SegmentCacheBefore => "Keep up to",
+        Message::SegmentCacheAfter => "MB of fetched segments",
+        Message::SegmentCacheTitle => {
+            "megabytes of fetched segments kept for opening them again (0 to keep none)"
+        }
+        Message::SettingsAppearance => "Appearance",
+        Message::SettingsTheme => "Theme",
+        Message::ThemeSystem => "Same as the system",
+        Message::ThemeLight => "Light",
+        Message::ThemeDark => "Dark",
+        Message::SettingsLivePlaylists => "Live playlists",
+        Message::LiveRecheckBefore => "Recheck live playlists every",
+        Message::LiveRecheckAfter => "seconds",
+        Message::LiveRecheckTitle => {
+            "seconds between rechecks of a live playlist while it is viewed (0 to only recheck on \
+             request)"
+        }
+        Message::SettingsViewer => "Viewer",
+        Message::ToggleStructureOnly => {
+            "Fetch segments only up to their media data, for their box structure"
+        }
+        Message::ToggleRawText => "Show playlists as their raw text",
+        Message::TogglePlaylistNoWrap => "Scroll to long playlist lines rather than wrapping them",
+        Message::ToggleDiffNoWrap => {
+            "Scroll to long lines of playlist diffs rather than wrapping them"
+        }
+        Message::ToggleCompressedLinks => "Compress the query of copied links",
+        Message::SettingsLaunching => "Launching",
+        Message::ProtocolHandlerTitle => "HLS Manifest Viewer",
+        Message::ProtocolHandlerRegistered => {
+            "{scheme}: links are opened in the viewer once allowed"
+        }
+        Message::ProtocolHandlerRefused => "This browser did not allow the viewer to open links",
+        Message::Bookmarklet => "Open in HLS Manifest Viewer",
+        Message::BookmarkletTitle => {
+            "drag to the bookmarks bar, then click it on a page playing HLS to open its playlist \
+             here"
+        }
+        Message::RegisterProtocolHandler => "Open {scheme}: links",
+        Message::RegisterProtocolHandlerTitle => {
+            "open {scheme}://example.com/master.m3u8 links in the viewer"
+        }
+        Message::RequestTimeoutNone => "Request timeout: none",
+        Message::RequestTimeout => "Request timeout: {seconds} s",
+        Message::OneExtraHeader => "{timeout}, 1 extra header",
+        Message::ExtraHeaders => "{timeout}, {count} extra headers",
+        Message::RequestTimeoutBefore => "Abort requests after",
+        Message::RequestTimeoutAfter => "seconds",
+        Message::RequestTimeoutTitle => {
+            "seconds allowed for each attempt at a request, including the download of the body (0 \
+             for no timeout)"
+        }
+        Message::ExtraHeadersLabel => "Extra headers",
+        Message::ExtraHeadersPlaceholder => "Authorization: Bearer token",
+        Message::ExtraHeadersTitle => {
+            "headers added to every request, one per line as Name: value (any that are not \
+             CORS-safelisted need the server to allow them in its response to the preflight)"
+        }
+        Message::CorsProxy => "CORS proxy: {prefix}",
+        Message::CorsProxyOff => "CORS proxy: off",
+        Message::ProxyPrefixTitle => {
+            "requests are sent to this prefix followed by the percent encoded URL, or with the URL \
+             in place of {placeholder} if the prefix contains it"
+        }
+        Message::UseCorsProxy => "Send requests through a CORS proxy",
+        Message::ProxyPrefix => "Proxy prefix",
+        Message::ProxyBypassHosts => "Request directly from",
+        Message::ProxyBypassHostsTitle => {
+            "comma separated hosts that are requested without the proxy (a leading . includes \
+             subdomains)"
+        }
+        Message::OfflineCacheOff => "Offline cache: off",
+        Message::OfflineCacheKeeping => "Offline cache: keeping responses",
+        Message::OfflineCacheServing => "Offline cache: answering requests",
+        Message::OneResponseKept => "1 response kept",
+        Message::ResponsesKept => "{count} responses kept",
+        Message::OfflineCacheUnavailable => "The offline cache is unavailable in this browser",
+        Message::OfflineCache => "Offline cache",
+        Message::ClearOfflineCache => "Clear offline cache",
+        Message::ClearOfflineCacheTitle => "remove every response kept in this browser",
+        Message::OfflineCacheModeOff => "Off",
+        Message::OfflineCacheModeKeep => "Keep fetched playlists and segments",
+        Message::OfflineCacheModeServe => {
+            "Answer requests from the kept responses, fetching the rest"
+        }
+        Message::AdAvailsSummary => {
+            "Ad avails: {count} breaks, {duration}s of ads ({average}s on average)"
+        }
+        Message::AdAvailsCount => "Ad avails: {count} breaks",
+        Message::AdId => "ID",
+        Message::AdAvailStart => "Start",
+        Message::AdAvailDuration => "Duration",
+        Message::AdAvailStatus => "Status",
+        Message::AdAvailSignalled => "Signalled",
+        Message::AdAvailFilled => "Filled by interstitial",
+        Message::AdAvailReturned => "Returned",
+        Message::AdBreaksSummary => {
+            "Ad breaks: {count} ({unpaired} unpaired, {overlapping} overlapping)"
+        }
+        Message::AdBreakEventId => "Event ID",
+        Message::AdBreakPlanned => "Planned",
+        Message::AdBreakSignalled => "Signalled",
+        Message::AdBreakActual => "Actual",
+        Message::AdBreakProblems => "Problems",
+        Message::PairedByEventId => "(by event id)",
+        Message::AdBreakWithoutIn => "No SCTE35-IN ends the break.",
+        Message::AdBreakWithoutOut => "No SCTE35-OUT starts the break.",
+        Message::AdBreakLonger => "The break is {difference}s longer than planned.",
+        Message::AdBreakShorter => "The break is {difference}s shorter than planned.",
+        Message::AdBreakOverlaps => "Overlaps {ids}.",
+        Message::PackedAudioSegment => "Packed audio segment: {format}, {size} bytes",
+        Message::Play => "Play",
+        Message::PlayAudioTitle => "decode the audio of this segment and play it in the browser",
+        Message::Stop => "Stop",
+        Message::AudioPeak => "peaking at {peak} dBFS",
+        Message::AudioSilent => "silent throughout",
+        Message::AudioDecoded => {
+            "Decoded {duration}s of audio in {channels} channel(s) at {sample_rate} Hz, {level}."
+        }
+        Message::ExpandAll => "Expand all",
+        Message::CollapseAll => "Collapse all",
+        Message::ExpandToDepth => "Expand to depth",
+        Message::Depth => "Depth",
+        Message::DecryptionKeyInvalid => {
+            "The key must be 16 bytes, written as 32 hex digits or in base64."
+        }
+        Message::DecryptionFailed => "Could not decrypt the segment: {error}",
+        Message::FetchedKeyLength => "The key that was fetched is {length} bytes rather than 16.",
+        Message::FetchKey => "Fetch key",
+        Message::DecryptionIv => "IV: 0x{iv} ({source})",
+        Message::DecryptionIvMissing => {
+            "IV: {source}, so the segment cannot be decrypted on its own"
+        }
+        Message::SegmentEncrypted => "The segment is encrypted with METHOD=AES-128.",
+        Message::DecryptionKey => "Key ",
+        Message::DecryptionKeyPlaceholder => "32 hex digits or base64",
+        Message::Decrypt => "Decrypt",
+        Message::IvInvalid => "the IV attribute is not 16 bytes of hex",
+        Message::IvFromAttribute => "from the IV attribute",
+        Message::IvFromMediaSequence => "the Media Sequence Number, {media_sequence}",
+        Message::IvMissing => "there is no IV attribute",
+        Message::HideHexDump => "Hide hex dump",
+        Message::ShowHexDump => "Show hex dump",
+        Message::HexDumpBytes => "Bytes {start}-{end} of {length}",
+        Message::InterstitialsPlaying => "Interstitials: {count} (playing for {playout}s)",
+        Message::InterstitialsCount => "Interstitials: {count}",
+        Message::InterstitialScheduled => "Scheduled",
+        Message::InterstitialPlaysFor => "Plays for",
+        Message::InterstitialResumes => "Resumes",
+        Message::InterstitialTotalPlayed => "Total played",
+        Message::PrimaryDelayTitle => "How far primary playback has fallen behind its dates",
+        Message::PrimaryDelay => "Primary delay",
+        Message::PreRoll => "Pre-roll",
+        Message::PostRoll => "Post-roll",
+        Message::PlayoutLimited => "{playout}s (limited to {limit}s)",
+        Message::ResumeUnknown => "Unknown",
+        Message::ResumeByDefault => " by default",
+        Message::ResumeAt => "{time} (+{offset}s{default})",
+        Message::ResumeIntoPrimary => "+{offset}s{default} into the primary",
+        Message::KeyIdsSummary => "Key IDs: {count} ({mismatched} mismatched)",
+        Message::KeyIdSegment => "Segment",
+        Message::KeyIdProblems => "Problems",
+        Message::KeyRotationSummary => "AES-128 keys: {keys} over {segments} segments",
+        Message::KeyRotationSegment => "Segment",
+        Message::KeyRotationKey => "Key",
+        Message::KeyRotationIv => "IV",
+        Message::KeyRotationIvFrom => "IV from",
+        Message::KeyWithoutUri => "(no URI)",
+        Message::IvNotHex => "Not 16 bytes of hex",
+        Message::IvAttribute => "IV attribute",
+        Message::MediaSequenceNumber => "Media Sequence Number",
+        Message::LiveSegmentTitle => "{time} ({duration}s)",
+        Message::HoldBackWithPart => {
+            "hold back of {hold_back} (part hold back of {part_hold_back})"
+        }
+        Message::HoldBack => "hold back of {hold_back}",
+        Message::LiveStatusAfter => {
+            "Now {now}: the playlist ends {delay} after now, and players start {hold_back} behind \
+             its end at {live_edge}."
+        }
+        Message::LiveStatusBefore => {
+            "Now {now}: the playlist ends {delay} before now, and players start {hold_back} behind \
+             its end at {live_edge}."
+        }
+        Message::PlaylistEndsInFuture => {
+            "The playlist ends in the future, so either EXT-X-PROGRAM-DATE-TIME is ahead of the \
+             wall clock or the clock of this device is behind."
+        }
+        Message::PlaylistBehindSchedule => {
+            "The playlist is behind schedule: a new segment is due at least every \
+             EXT-X-TARGETDURATION ({target_duration}), unless the clock of this device is ahead."
+        }
+        Message::LiveEdge => "live edge",
+        Message::LiveNow => "now",
+        Message::PlaySegmentTitle => "play this segment in the browser",
+        Message::SampleEncryptionScheme => {
+            "METHOD={method} encrypts only the samples, with the {scheme} scheme of Common \
+             Encryption, so every box can be read without the key while the media data can only be \
+             played with it."
+        }
+        Message::SampleEncryptionExplained => {
+            "The samples are encrypted, so every box can be read without the key while the media \
+             data can only be played with it."
+        }
+        Message::SampleEncryptionSummary => "Sample encryption: {count} boxes",
+        Message::SampleEncryptionBox => "Box",
+        Message::SampleEncryptionRole => "Role",
+        Message::InInitializationSegment => "{kind} (initialization segment)",
+        Message::BytesOfTotal => "{received} of {total} bytes",
+        Message::BytesReceived => "{received} bytes",
+        Message::DecodingFirstKeyframe => "Decoding the first keyframe...",
+        Message::FirstKeyframe => "first keyframe",
+        Message::ShowIFrameThumbnails => "Show thumbnails of {count} I-frames",
+        Message::DecodingIFrames => "Decoding I-frames...",
+        Message::IFrame => "I-frame",
+        Message::IFrameNotFmp4 => {
+            "{uri} has no EXT-X-MAP, so it is not fMP4 (only fMP4 I-frames can be decoded)."
+        }
+        Message::UnresolvedMapUri => "Error: unable to resolve map URI {uri}",
+        Message::NoVideoSampleDescription => {
+            "The initialization segment has no avcC or hvcC to decode with."
+        }
+        Message::NoKeyframe => "No keyframe was found in {url}.",
+        Message::UnknownInitializationCodec => {
+            "The codec of the initialization segment is not known."
+        }
+        Message::VariantsSummary => "Variants: {count}",
+        Message::VariantLine => "Line",
+        Message::FairPlayKeyId => "FairPlay KID: {key_id}",
+        Message::FairPlayAssetId => "FairPlay asset ID",
+        Message::FairPlayAssetIdTitle => "Asset ID: {asset_id}\n{convention}",
+        Message::OpeningPlaylist => "Opening playlist",
+        Message::OpeningTarget => "Opening {target}…",
+        Message::NothingToOpen => {
+            "No playlist URL was given to open. Give it as /open?u=<URL>, or launch the viewer \
+             from the bookmarklet or a web+hls: link (both set up in Settings)."
+        }
+        Message::ExportHarTitle => "download the requests made during this session as a HAR file",
+        Message::ExportHar => "Export HAR",
+        Message::ExportSessionTitle => {
+            "download the playlists, segments, headers and timings of this session to import later"
+        }
+        Message::ExportSession => "Export session",
+        Message::ImportSession => "Import session",
+        Message::ReplayingSession => "Replaying {name} ({count} requests) offline",
+        Message::StopReplaying => "Stop replaying",
+        Message::EnterUrl => "Enter URL",
+        Message::PastePlaylist => "Paste playlist",
+        Message::PlaylistUrlLabel => "playlist url",
+        Message::PlaylistUrlTitle => {
+            "url with http or https scheme (e.g. https://example.com/mvp.m3u8)"
+        }
+        Message::ForwardQueryPlaceholder => "forward query (e.g. token)",
+        Message::ForwardQueryLabel => "query parameters to forward",
+        Message::ForwardQueryTitle => {
+            "comma separated names of query parameters on the playlist url to forward onto every \
+             child request, or * for all of them"
+        }
+        Message::PlaylistTextLabel => "playlist text",
+        Message::ShowPastedPlaylist => "Show",
+        Message::PastedPlaylistName => "pasted playlist",
+        Message::RecentPlaylists => "Recent playlists",
+        Message::RemoveRecentPlaylist => "Remove",
+        Message::RemoveRecentPlaylistTitle => "remove from recent playlists",
+        Message::ClearRecentPlaylists => "Clear all",
+        Message::OpenLocalFile => "Open a local playlist or segment (or drop it here)",
+        Message::BaseUrlPlaceholder => "base URL for relative URIs (optional)",
+        Message::BaseUrlTitle => {
+            "relative URIs in the file resolve against this URL, and are not linked without one"
+        }
+        Message::ShowingLocalFile => "Showing {name}",
+        Message::CloseLocalFile => "Close",
+        Message::Download => "Download",
+        Message::DownloadTitle => "save as {file_name}",
+        Message::CompareHeading => "Compare playlists",
+        Message::CompareIntro => {
+            "Enter the URLs of two playlists (e.g. the same stream from two CDNs, or from two \
+             versions of a packager) to see their lines side by side, with the differences between \
+             them highlighted."
+        }
+        Message::LeftPlaylistUrlLabel => "left playlist url",
+        Message::RightPlaylistUrlLabel => "right playlist url",
+        Message::CompareSubmit => "Compare",
+        Message::SupplementalSegment => "Segment {media_sequence}",
+        Message::SupplementalMap => "Map (segment {media_sequence})",
+        Message::SupplementalPart => "Part {media_sequence}.{part_index}",
+        Message::SupplementalScte35 => "SCTE35-{command_type} ({id})",
+        Message::SupplementalAssetList => "Asset list ({id})",
+        Message::SupplementalSchedule => "Schedule ({id})",
+        Message::LineCategorySegments => "segments",
+        Message::LineCategoryDateranges => "dateranges",
+        Message::LineCategoryKeys => "keys",
+        Message::LineCategoryParts => "parts",
+        Message::LineCategoryComments => "comments",
+        Message::LineCategoryVariants => "variant tags",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn preferred_locale_should_match_primary_language() {
+        assert_eq!(Some(Locale::English), Locale::from_tag("en-GB"));
+        assert_eq!(Some(Locale::English), Locale::from_tag("EN_us"));
+        assert_eq!(None, Locale::from_tag("fr-FR"));
+        let languages = [String::from("fr-FR"), String::from("en-US")];
+        assert_eq!(Locale::English, preferred_locale(&languages));
+        assert_eq!(Locale::English, preferred_locale(&[]));
+    }
+
+    #[test]
+    fn fill_should_replace_placeholders_by_name() {
+        assert_eq!(
+            "Bad HTTP status code: 404 Not Found",
+            fill(
+                english(Message::HttpStatusError),
+                &[("status", "404"), ("status_text", "Not Found")]
+            )
+        );
+        assert_eq!(
+            "Validation: 2 errors, 1 warning",
+            fill(
+                english(Message::ValidationSummary),
+                &[
+                    ("title", "Validation"),
+                    ("errors", "2 errors"),
+                    ("warnings", "1 warning")
+                ]
+            )
+        );
+        assert_eq!(
+            "Network error: {status} {missing}",
+            fill(
+                "Network error: {message} {missing}",
+                &[("message", "{status}")]
+            )
+        );
+    }
+}
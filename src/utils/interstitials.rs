@@ -0,0 +1,310 @@
+// The interstitials scheduled by EXT-X-DATERANGE tags with a CLASS of "com.apple.hls.interstitial",
+// gathered across the tags that share an ID, as a live playlist may only give the DURATION of an
+// interstitial in a later tag.
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#appendix-D.2
+//
+// X-RESUME-OFFSET
+//
+//    The value of X-RESUME-OFFSET is a decimal-floating-point of seconds
+//    that specifies where primary playback is to resume following the
+//    playback of the interstitial.  It is expressed as a time offset from
+//    where the interstitial playback was scheduled on the primary player
+//    timeline.  [...]  If X-RESUME-OFFSET is not present, the player uses
+//    the duration of interstitial playback for the resume offset, which is
+//    appropriate for live content where the interstitial is to replace
+//    content.
+//
+// X-PLAYOUT-LIMIT
+//
+//    The value of X-PLAYOUT-LIMIT is a decimal-floating-point of seconds
+//    that specifies a limit for the playout time of the entire
+//    interstitial.  If it is present, the player SHOULD end the
+//    interstitial if playback reaches the limit.
+//
+// X-TIMELINE-OCCUPIES
+//
+//    The value of X-TIMELINE-OCCUPIES is an enumerated-string: "POINT" or
+//    "RANGE".  It indicates whether the interstitial is intended to be
+//    presented as a single point or as a range on the timeline.  The
+//    default value is "POINT".
+
+use crate::utils::{
+    daterange_cue::{Cue, INTERSTITIAL_CLASS},
+    playlist_lines::{LineKind, parse},
+    timeline_drift::parse_date_time,
+};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Interstitial {
+    pub id: String,
+    /// The line of the first tag that schedules the interstitial.
+    pub line: usize,
+    /// The START-DATE, in milliseconds since the epoch.
+    pub start_date: Option<f64>,
+    pub cue: Cue,
+    /// The DURATION of the daterange, or the time from its START-DATE to its END-DATE, in seconds.
+    pub duration: Option<f64>,
+    pub planned_duration: Option<f64>,
+    /// The X-RESUME-OFFSET, in seconds.
+    pub resume_offset: Option<f64>,
+    /// The X-PLAYOUT-LIMIT, in seconds.
+    pub playout_limit: Option<f64>,
+    pub timeline_occupies: TimelineOccupies,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TimelineOccupies {
+    #[default]
+    Point,
+    Range,
+}
+
+impl Interstitial {
+    /// The length of the break in the primary content that the interstitial fills, in seconds.
+    pub fn break_duration(&self) -> Option<f64> {
+        self.duration.or(self.planned_duration)
+    }
+
+    /// How long the interstitial is expected to play for, in seconds: the length of the break, cut
+    /// short by the X-PLAYOUT-LIMIT.
+    pub fn playout(&self) -> Option<f64> {
+        match (self.break_duration(), self.playout_limit) {
+            (Some(duration), Some(limit)) => Some(duration.min(limit)),
+            (duration, limit) => duration.or(limit),
+        }
+    }
+
+    /// How far past its START-DATE that primary playback resumes once the interstitial has played,
+    /// in seconds, which is the time that the interstitial plays for when no X-RESUME-OFFSET is
+    /// given. A post-roll has nothing to resume.
+    pub fn effective_resume_offset(&self) -> Option<f64> {
+        if self.cue.post {
+            return None;
+        }
+        self.resume_offset.or_else(|| self.playout())
+    }
+
+    /// The date in the primary content that playback resumes from, in milliseconds since the epoch.
+    /// Pre-rolls resume from wherever playback of the primary was started, so have no date.
+    pub fn resume_date(&self) -> Option<f64> {
+        if self.cue.pre {
+            return None;
+        }
+        Some(self.start_date? + self.effective_resume_offset()? * 1000.0)
+    }
+
+    /// How the interstitial is placed on the presentation timeline that a player shows.
+    pub fn timeline_effect(&self) -> String {
+        let skipped = match self.effective_resume_offset() {
+            Some(offset) if offset > 0.0 => format!(", skipping {offset:.3}s of the primary"),
+            Some(_) => String::from(", without skipping any of the primary"),
+            None => String::new(),
+        };
+        match self.timeline_occupies {
+            TimelineOccupies::Point => format!("Shown as a single point{skipped}."),
+            TimelineOccupies::Range => match self.break_duration() {
+                Some(duration) => format!(
+                    "Shown over the {duration:.3}s of primary timeline that it fills{skipped}."
+                ),
+                None => format!("Shown over the range of primary timeline that it fills{skipped}."),
+            },
+        }
+    }
+}
+
+/// Running totals over the interstitials in the order that they play.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayoutTotals {
+    /// The time spent playing interstitials so far, in seconds.
+    pub playout: Option<f64>,
+    /// How far primary playback has fallen behind its own dates, in seconds, from interstitials
+    /// that play for longer than the primary content that they skip.
+    pub delay: Option<f64>,
+}
+
+/// The interstitials in the playlist, in the order that they play: pre-rolls, then those at a
+/// START-DATE, then post-rolls.
+pub fn interstitials(playlist: &str) -> Vec<Interstitial> {
+    let mut interstitials = Vec::<Interstitial>::new();
+    let mut indices = HashMap::new();
+    for line in parse(playlist) {
+        let LineKind::Tag(tag) = line.kind else {
+            continue;
+        };
+        if tag.name != "EXT-X-DATERANGE" {
+            continue;
+        }
+        let Some(id) = tag.attribute_str("ID") else {
+            continue;
+        };
+        // The CLASS only has to be given once, so later tags with the ID are taken to be part of
+        // the interstitial.
+        let index = match indices.get(id) {
+            Some(index) => *index,
+            None if tag.attribute_str("CLASS") == Some(INTERSTITIAL_CLASS) => {
+                indices.insert(id.to_string(), interstitials.len());
+                interstitials.push(Interstitial {
+                    id: id.to_string(),
+                    line: line.number,
+                    ..Default::default()
+                });
+                interstitials.len() - 1
+            }
+            None => continue,
+        };
+        let seconds = |name| tag.attribute_str(name)?.parse::<f64>().ok();
+        let start_date = tag.attribute_str("START-DATE").and_then(parse_date_time);
+        let duration = seconds("DURATION").or_else(|| {
+            let end_date = tag.attribute_str("END-DATE").and_then(parse_date_time)?;
+            Some((end_date - start_date?) / 1000.0)
+        });
+        let interstitial = &mut interstitials[index];
+        interstitial.start_date = interstitial.start_date.or(start_date);
+        interstitial.duration = interstitial.duration.or(duration);
+        interstitial.planned_duration = interstitial
+            .planned_duration
+            .or_else(|| seconds("PLANNED-DURATION"));
+        interstitial.resume_offset = interstitial
+            .resume_offset
+            .or_else(|| seconds("X-RESUME-OFFSET"));
+        interstitial.playout_limit = interstitial
+            .playout_limit
+            .or_else(|| seconds("X-PLAYOUT-LIMIT"));
+        if let Some(cue) = tag.attribute_str("CUE") {
+            interstitial.cue = Cue::parse(cue);
+        }
+        if tag.attribute_str("X-TIMELINE-OCCUPIES") == Some("RANGE") {
+            interstitial.timeline_occupies = TimelineOccupies::Range;
+        }
+    }
+    interstitials.sort_by(|a, b| {
+        let order = |i: &Interstitial| match (i.cue.pre, i.cue.post) {
+            (true, _) => 0,
+            (false, false) => 1,
+            (false, true) => 2,
+        };
+        order(a).cmp(&order(b)).then_with(|| {
+            a.start_date
+                .unwrap_or(f64::MAX)
+                .total_cmp(&b.start_date.unwrap_or(f64::MAX))
+        })
+    });
+    interstitials
+}
+
+/// The interstitial with the given ID.
+pub fn interstitial(playlist: &str, id: &str) -> Option<Interstitial> {
+    interstitials(playlist)
+        .into_iter()
+        .find(|interstitial| interstitial.id == id)
+}
+
+/// The running totals after each of the interstitials has played, which stop being known once an
+/// interstitial plays for an unknown time.
+pub fn playout_totals(interstitials: &[Interstitial]) -> Vec<PlayoutTotals> {
+    let mut playout = Some(0.0);
+    let mut delay = Some(0.0);
+    interstitials
+        .iter()
+        .map(|interstitial| {
+            let played = interstitial.playout();
+            playout = playout.zip(played).map(|(total, played)| total + played);
+            if !interstitial.cue.post {
+                let skipped = interstitial.effective_resume_offset();
+                delay = delay
+                    .zip(played.zip(skipped))
+                    .map(|(total, (played, skipped))| total + played - skipped);
+            }
+            PlayoutTotals { playout, delay }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn interstitial_gathers_the_tags_with_its_id() {
+        let playlist = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2025-01-01T00:00:00Z\",PLANNED-DURATION=30,",
+            "X-ASSET-LIST=\"list.json\"\n",
+            "#EXT-X-DATERANGE:ID=\"other\",START-DATE=\"2025-01-01T00:00:00Z\",DURATION=5\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2025-01-01T00:00:00Z\",",
+            "END-DATE=\"2025-01-01T00:00:31.5Z\",X-TIMELINE-OCCUPIES=\"RANGE\"\n",
+        );
+        assert_eq!(
+            Some(Interstitial {
+                id: String::from("ad"),
+                line: 2,
+                start_date: Some(1_735_689_600_000.0),
+                duration: Some(31.5),
+                planned_duration: Some(30.0),
+                timeline_occupies: TimelineOccupies::Range,
+                ..Default::default()
+            }),
+            interstitial(playlist, "ad")
+        );
+        assert_eq!(None, interstitial(playlist, "other"));
+    }
+
+    #[test]
+    fn interstitials_resume_and_total_their_playout() {
+        let playlist = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-DATERANGE:ID=\"mid\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2025-01-01T00:01:00Z\",DURATION=30,X-PLAYOUT-LIMIT=20,",
+            "X-ASSET-URI=\"mid.m3u8\"\n",
+            "#EXT-X-DATERANGE:ID=\"pre\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2025-01-01T00:00:00Z\",DURATION=15,CUE=\"PRE\",X-RESUME-OFFSET=0,",
+            "X-ASSET-URI=\"pre.m3u8\"\n",
+            "#EXT-X-DATERANGE:ID=\"post\",CLASS=\"com.apple.hls.interstitial\",",
+            "START-DATE=\"2025-01-01T00:00:00Z\",DURATION=10,CUE=\"POST\",",
+            "X-ASSET-URI=\"post.m3u8\"\n",
+        );
+        let interstitials = interstitials(playlist);
+        assert_eq!(
+            vec!["pre", "mid", "post"],
+            interstitials
+                .iter()
+                .map(|i| i.id.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Some(0.0), Some(20.0), None],
+            interstitials
+                .iter()
+                .map(Interstitial::effective_resume_offset)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![None, Some(1_735_689_680_000.0), None],
+            interstitials
+                .iter()
+                .map(Interstitial::resume_date)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                PlayoutTotals {
+                    playout: Some(15.0),
+                    delay: Some(15.0)
+                },
+                PlayoutTotals {
+                    playout: Some(35.0),
+                    delay: Some(15.0)
+                },
+                PlayoutTotals {
+                    playout: Some(45.0),
+                    delay: Some(15.0)
+                },
+            ],
+            playout_totals(&interstitials)
+        );
+    }
+}
@@ -0,0 +1,116 @@
+// The key IDs that an EXT-X-KEY carries in its URI, for the KEYFORMATs of the DRM systems that put
+// them there. FairPlay gives an skd:// URI, whereas Widevine, PlayReady and Clear Key embed the
+// same data as their `pssh` boxes in a data: URI (the whole `pssh` box for Widevine, and the
+// PlayReady Object for PlayReady).
+
+use crate::utils::{
+    data_uri::decode_data_uri,
+    encryption::{SegmentKey, playready_key_id},
+    fairplay::{FAIRPLAY_KEYFORMAT, SkdUri},
+    hex::encode_hex,
+    mp4_parsing::{Pssh, pssh::PsshData},
+    pssh_data::{
+        clearkey,
+        playready::{PlayReadyPsshData, parse_pssh_data},
+    },
+};
+use mp4_atom::{Atom, Header, ReadFrom};
+use std::io::Cursor;
+
+pub const WIDEVINE_KEYFORMAT: &str = "urn:uuid:edef8ba9-79d6-4ace-a3c8-27dcd51d21ed";
+pub const PLAYREADY_KEYFORMAT: &str = "com.microsoft.playready";
+pub const CLEARKEY_KEYFORMAT: &str = "org.w3.clearkey";
+// The W3C Common PSSH box, which Clear Key is signalled with as often as by its own KEYFORMAT.
+pub const COMMON_PSSH_KEYFORMAT: &str = "urn:uuid:1077efec-c0b2-4d02-ace3-3c1e52e2fb4b";
+
+/// The name of the DRM system that a KEYFORMAT belongs to, along with its KEYFORMATVERSIONS.
+pub fn key_name(key: &SegmentKey) -> String {
+    let name = match key.keyformat.as_str() {
+        FAIRPLAY_KEYFORMAT => "FairPlay",
+        WIDEVINE_KEYFORMAT => "Widevine",
+        PLAYREADY_KEYFORMAT => "PlayReady",
+        CLEARKEY_KEYFORMAT | COMMON_PSSH_KEYFORMAT => "ClearKey",
+        keyformat => keyformat,
+    };
+    match &key.keyformat_versions {
+        Some(versions) => format!("{name} (KEYFORMATVERSIONS=\"{versions}\")"),
+        None => name.to_string(),
+    }
+}
+
+/// The key IDs carried by the URI of the key, as lowercase hex.
+pub fn key_ids(key: &SegmentKey) -> Vec<String> {
+    let Some(uri) = &key.uri else {
+        return Vec::new();
+    };
+    match key.keyformat.as_str() {
+        FAIRPLAY_KEYFORMAT => SkdUri::parse(uri)
+            .and_then(|skd| skd.key_id)
+            .into_iter()
+            .collect(),
+        WIDEVINE_KEYFORMAT => decode_data_uri(uri)
+            .map(|data_uri| pssh_key_ids(data_uri.data))
+            .unwrap_or_default(),
+        PLAYREADY_KEYFORMAT => decode_data_uri(uri)
+            .ok()
+            .and_then(|data_uri| parse_pssh_data(&data_uri.data).ok())
+            .map(|data| playready_key_ids(&data))
+            .unwrap_or_default(),
+        // The data: URI is either a `pssh` box or the "keyids" JSON.
+        CLEARKEY_KEYFORMAT | COMMON_PSSH_KEYFORMAT => decode_data_uri(uri)
+            .map(|data_uri| match clearkey::parse_pssh_data(&data_uri.data) {
+                Some(data) => data.kids.iter().map(|kid| encode_hex(kid)).collect(),
+                None => pssh_key_ids(data_uri.data),
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn pssh_key_ids(data: Vec<u8>) -> Vec<String> {
+    let mut reader = Cursor::new(data);
+    let Ok(header) = Header::read_from(&mut reader) else {
+        return Vec::new();
+    };
+    if header.kind != Pssh::KIND {
+        return Vec::new();
+    }
+    let Ok(pssh) = Pssh::decode_atom(&header, &mut reader) else {
+        return Vec::new();
+    };
+    let mut key_ids = pssh
+        .key_ids
+        .iter()
+        .map(|key_id| encode_hex(key_id))
+        .collect::<Vec<_>>();
+    let data_key_ids = match &pssh.data {
+        #[cfg(feature = "widevine")]
+        Some(PsshData::Widevine(data)) => data
+            .key_ids
+            .iter()
+            .map(|key_id| encode_hex(key_id))
+            .collect(),
+        Some(PsshData::PlayReady(data)) => playready_key_ids(data),
+        Some(PsshData::ClearKey(data)) => data.kids.iter().map(|kid| encode_hex(kid)).collect(),
+        _ => Vec::new(),
+    };
+    for key_id in data_key_ids {
+        if !key_ids.contains(&key_id) {
+            key_ids.push(key_id);
+        }
+    }
+    key_ids
+}
+
+fn playready_key_ids(data: &PlayReadyPsshData) -> Vec<String> {
+    data.record
+        .iter()
+        .flat_map(|record| {
+            let data = &record.record_value.data;
+            data.kids
+                .iter()
+                .chain(data.protect_info.iter().flat_map(|info| info.kids.iter()))
+        })
+        .filter_map(|kid| playready_key_id(kid.value.as_deref()?))
+        .collect()
+}
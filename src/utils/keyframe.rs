@@ -0,0 +1,297 @@
+// Extraction of what is needed to decode the first keyframe of a video segment on its own: the
+// decoder configuration record of its sample entry (from the initialization segment) and the bytes
+// of its first sync sample (from the first track fragment of the segment).
+//
+// The boxes are walked directly rather than parsed in full, as only a few fields of a few boxes
+// are needed, and the media data must be addressed by its offset in the segment.
+
+use crate::utils::codecs::SampleEntryCodec;
+
+/// What a video decoder is configured with to decode the samples of a track.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoSampleDescription {
+    /// The sample entry type (e.g. `avc1`, `hvc1`).
+    pub sample_entry: String,
+    pub width: u16,
+    pub height: u16,
+    /// The body of the decoder configuration record (`avcC` or `hvcC`).
+    pub description: Vec<u8>,
+}
+
+impl VideoSampleDescription {
+    /// The codec string of the sample entry, from among the codecs of the initialization segment.
+    pub fn codec_string(&self, codecs: &[SampleEntryCodec]) -> Option<String> {
+        codecs
+            .iter()
+            .find(|codec| codec.sample_entry == self.sample_entry)
+            .map(SampleEntryCodec::codec_string)
+    }
+}
+
+/// A sample that can be decoded without reference to any other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe<'a> {
+    pub data: &'a [u8],
+    /// The decode time of the sample, in the timescale of its track (from `tfdt`).
+    pub decode_time: u64,
+}
+
+/// The description of the first video sample entry (that carries an `avcC` or `hvcC`) of the
+/// initialization segment.
+pub fn video_sample_description(init: &[u8]) -> Option<VideoSampleDescription> {
+    let stsd = find_path(
+        init,
+        &[b"moov", b"trak", b"mdia", b"minf", b"stbl", b"stsd"],
+    )?;
+    // A full box header (4 bytes) and the entry count (4 bytes) precede the entries.
+    boxes(stsd.get(8..)?).find_map(|entry| {
+        // A visual sample entry has 78 bytes of fields before its child boxes (ISO/IEC 14496-12
+        // 12.1.3.2), of which the width and height are at 24 and 26.
+        let fields = entry.body.get(..78)?;
+        let config =
+            boxes(&entry.body[78..]).find(|child| matches!(&child.kind, b"avcC" | b"hvcC"))?;
+        Some(VideoSampleDescription {
+            sample_entry: String::from_utf8_lossy(&entry.kind).into_owned(),
+            width: u16::from_be_bytes([fields[24], fields[25]]),
+            height: u16::from_be_bytes([fields[26], fields[27]]),
+            description: config.body.to_vec(),
+        })
+    })
+}
+
+/// The first sync sample of the first track fragment of the segment.
+pub fn first_keyframe(segment: &[u8]) -> Option<Keyframe<'_>> {
+    let moof = boxes(segment).find(|b| &b.kind == b"moof")?;
+    let traf = boxes(moof.body).find(|b| &b.kind == b"traf")?;
+    let tfhd = boxes(traf.body).find(|b| &b.kind == b"tfhd")?;
+    let trun = boxes(traf.body).find(|b| &b.kind == b"trun")?;
+    let decode_time = boxes(traf.body)
+        .find(|b| &b.kind == b"tfdt")
+        .and_then(|tfdt| {
+            let mut reader = Reader::new(tfdt.body);
+            let version = reader.u8()?;
+            reader.skip(3)?;
+            if version == 1 {
+                reader.u64()
+            } else {
+                reader.u32().map(u64::from)
+            }
+        })
+        .unwrap_or_default();
+
+    // ISO/IEC 14496-12 8.8.7
+    let mut reader = Reader::new(tfhd.body);
+    let flags = reader.u32()? & 0x00FF_FFFF;
+    reader.skip(4)?; // track_ID
+    let base_data_offset = if flags & 0x01 != 0 {
+        Some(usize::try_from(reader.u64()?).ok()?)
+    } else {
+        None
+    };
+    if flags & 0x02 != 0 {
+        reader.skip(4)?; // sample_description_index
+    }
+    if flags & 0x08 != 0 {
+        reader.skip(4)?; // default_sample_duration
+    }
+    let default_size = if flags & 0x10 != 0 {
+        Some(reader.u32()?)
+    } else {
+        None
+    };
+    let default_flags = if flags & 0x20 != 0 {
+        Some(reader.u32()?)
+    } else {
+        None
+    };
+    // Without an explicit base, the data of the first track fragment is addressed from the start
+    // of the moof (which is also what default-base-is-moof gives).
+    let base = base_data_offset.unwrap_or(moof.offset);
+
+    // ISO/IEC 14496-12 8.8.8
+    let mut reader = Reader::new(trun.body);
+    let flags = reader.u32()? & 0x00FF_FFFF;
+    let sample_count = reader.u32()?;
+    let data_offset = if flags & 0x001 != 0 {
+        i64::from(reader.u32()? as i32)
+    } else {
+        0
+    };
+    let first_sample_flags = if flags & 0x004 != 0 {
+        Some(reader.u32()?)
+    } else {
+        None
+    };
+    let mut offset = usize::try_from(i64::try_from(base).ok()? + data_offset).ok()?;
+    for index in 0..sample_count {
+        if flags & 0x100 != 0 {
+            reader.skip(4)?; // sample_duration
+        }
+        let size = if flags & 0x200 != 0 {
+            reader.u32()?
+        } else {
+            default_size?
+        };
+        let sample_flags = if flags & 0x400 != 0 {
+            Some(reader.u32()?)
+        } else if index == 0 {
+            first_sample_flags.or(default_flags)
+        } else {
+            default_flags
+        };
+        if flags & 0x800 != 0 {
+            reader.skip(4)?; // sample_composition_time_offset
+        }
+        let size = usize::try_from(size).ok()?;
+        // Without flags for the sample (which would otherwise come from the trex of the
+        // initialization segment), the first sample is taken to be a sync sample, as each fragment
+        // is expected to start with one.
+        let is_sync = match sample_flags {
+            Some(sample_flags) => sample_flags & 0x0001_0000 == 0,
+            None => index == 0,
+        };
+        if is_sync {
+            return Some(Keyframe {
+                data: segment.get(offset..offset.checked_add(size)?)?,
+                decode_time,
+            });
+        }
+        offset = offset.checked_add(size)?;
+    }
+    None
+}
+
+struct BoxRef<'a> {
+    kind: [u8; 4],
+    body: &'a [u8],
+    /// The offset of the start of the box within the data that was walked.
+    offset: usize,
+}
+
+// The boxes that follow one another in `data`, stopping at the first that does not fit.
+fn boxes(data: &[u8]) -> impl Iterator<Item = BoxRef<'_>> {
+    let mut position = 0;
+    std::iter::from_fn(move || {
+        let mut reader = Reader::new(data.get(position..)?);
+        let size = reader.u32()?;
+        let kind = reader.bytes::<4>()?;
+        let (header, size) = match size {
+            0 => (8, data.len() - position),
+            1 => (16, usize::try_from(reader.u64()?).ok()?),
+            size => (8, usize::try_from(size).ok()?),
+        };
+        let end = position.checked_add(size)?;
+        let body = data.get(position + header..end)?;
+        let offset = position;
+        position = end;
+        Some(BoxRef { kind, body, offset })
+    })
+}
+
+// The body of the box at the end of the path of box types, taking the first box of each type.
+fn find_path<'a>(data: &'a [u8], path: &[&[u8; 4]]) -> Option<&'a [u8]> {
+    path.iter().try_fold(data, |data, kind| {
+        boxes(data).find(|b| &b.kind == *kind).map(|b| b.body)
+    })
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (bytes, rest) = self.data.split_first_chunk::<N>()?;
+        self.data = rest;
+        Some(*bytes)
+    }
+
+    fn skip(&mut self, count: usize) -> Option<()> {
+        self.data = self.data.get(count..)?;
+        Some(())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes::<1>().map(|[byte]| byte)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes().map(u32::from_be_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.bytes().map(u64::from_be_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn mp4_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut data = u32::try_from(body.len() + 8)
+            .unwrap()
+            .to_be_bytes()
+            .to_vec();
+        data.extend(kind);
+        data.extend(body);
+        data
+    }
+
+    #[test]
+    fn video_sample_description_should_read_the_avcc_of_the_sample_entry() {
+        let mut fields = vec![0; 78];
+        fields[24..28].copy_from_slice(&[0x05, 0x00, 0x02, 0xD0]);
+        let avcc = [0x01, 0x64, 0x00, 0x1F, 0xFF];
+        let avc1 = mp4_box(b"avc1", &[fields, mp4_box(b"avcC", &avcc)].concat());
+        let stsd = mp4_box(b"stsd", &[vec![0, 0, 0, 0, 0, 0, 0, 1], avc1].concat());
+        let mut init = mp4_box(b"ftyp", b"iso6");
+        let path = [b"stbl", b"minf", b"mdia", b"trak", b"moov"];
+        init.extend(path.iter().fold(stsd, |body, kind| mp4_box(kind, &body)));
+        assert_eq!(
+            Some(VideoSampleDescription {
+                sample_entry: String::from("avc1"),
+                width: 1280,
+                height: 720,
+                description: avcc.to_vec(),
+            }),
+            video_sample_description(&init)
+        );
+    }
+
+    #[test]
+    fn first_keyframe_should_skip_non_sync_samples() {
+        let tfhd = mp4_box(b"tfhd", &[0, 0x02, 0, 0, 0, 0, 0, 1]);
+        let tfdt = mp4_box(b"tfdt", &[0, 0, 0, 0, 0, 0, 0x03, 0xE8]);
+        // Two samples of 2 and 3 bytes, with sizes and flags, the first of which is not a sync
+        // sample.
+        let mut trun = vec![0, 0, 0x06, 0x01, 0, 0, 0, 2];
+        let data_offset_position = trun.len();
+        trun.extend([0; 4]);
+        trun.extend([0, 0, 0, 2, 0x01, 0x01, 0, 0]);
+        trun.extend([0, 0, 0, 3, 0x02, 0, 0, 0]);
+        let moof_size = 8 + 16 + 8 + tfhd.len() + tfdt.len() + 8 + trun.len();
+        // The data offset is from the start of the moof to the first byte of the mdat body.
+        trun[data_offset_position..data_offset_position + 4]
+            .copy_from_slice(&u32::try_from(moof_size + 8).unwrap().to_be_bytes());
+        let traf = mp4_box(b"traf", &[tfhd, tfdt, mp4_box(b"trun", &trun)].concat());
+        let moof = mp4_box(b"moof", &[mp4_box(b"mfhd", &[0; 8]), traf].concat());
+        assert_eq!(moof_size, moof.len());
+        // The moof is not the first box of the segment, though it is still the base of the offset.
+        let mut segment = mp4_box(b"styp", b"msdh");
+        segment.extend(moof);
+        segment.extend(mp4_box(b"mdat", &[0xAA, 0xAA, 0xBB, 0xBB, 0xBB]));
+        assert_eq!(
+            Some(Keyframe {
+                data: &[0xBB, 0xBB, 0xBB],
+                decode_time: 1000,
+            }),
+            first_keyframe(&segment)
+        );
+    }
+}
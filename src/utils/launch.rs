@@ -0,0 +1,138 @@
+// The viewer launched from outside of it (by a bookmarklet, the debug overlay of a player or a
+// `web+hls:` link) against a playlist URL, given in whatever form is easiest for the launcher:
+//
+//   /hls-manifest-viewer/open?u=https%3A%2F%2Fexample.com%2Fmaster.m3u8
+//   /hls-manifest-viewer/open?u=https://example.com/master.m3u8?token=abc&expires=123
+//   /hls-manifest-viewer/open/https://example.com/master.m3u8
+//   /hls-manifest-viewer/open?u=web%2Bhls%3A%2F%2Fexample.com%2Fmaster.m3u8
+//
+// As `u` is the only parameter, it is taken to be the whole of the rest of the query, so that a URL
+// that was not encoded keeps its own query (and fragment). A URL that was encoded, even more than
+// once, is decoded.
+
+use crate::utils::query_codec::percent_decode;
+
+/// The path of the page that launches the viewer.
+pub const OPEN_PATH: &str = "/hls-manifest-viewer/open";
+/// The scheme of the links that the viewer can be registered to open, where
+/// `web+hls://example.com/master.m3u8` opens `https://example.com/master.m3u8`.
+pub const PROTOCOL_SCHEME: &str = "web+hls";
+pub const TARGET_QUERY_NAME: &str = "u";
+
+/// The playlist URL that the viewer was launched with, from the path, query (without the `?`) and
+/// fragment (with the `#`) of the URL of the open page.
+pub fn launch_target(path: &str, search: &str, hash: &str) -> Option<String> {
+    let in_path = path
+        .strip_prefix(OPEN_PATH)
+        .map(|rest| rest.trim_start_matches('/'))
+        .filter(|rest| !rest.is_empty());
+    let target = match in_path {
+        Some(rest) if search.is_empty() => format!("{rest}{hash}"),
+        Some(rest) => format!("{rest}?{search}{hash}"),
+        None => {
+            let prefix = format!("{TARGET_QUERY_NAME}=");
+            let start = if search.starts_with(&prefix) {
+                0
+            } else {
+                search.find(&format!("&{prefix}"))? + 1
+            };
+            format!("{}{hash}", &search[start + prefix.len()..])
+        }
+    };
+    let mut target = target.trim().to_string();
+    // Three rounds undo any encoding that a launcher is likely to have applied.
+    for _ in 0..3 {
+        if has_scheme(&target) {
+            break;
+        }
+        target = percent_decode(&target).trim().to_string();
+    }
+    if !has_scheme(&target) {
+        return None;
+    }
+    let target = match target
+        .strip_prefix(PROTOCOL_SCHEME)
+        .and_then(|t| t.strip_prefix(':'))
+    {
+        Some(rest) if rest.starts_with("//") => format!("https:{rest}"),
+        Some(rest) => rest.to_string(),
+        None => target,
+    };
+    Some(target)
+}
+
+// Whether the URL starts with a scheme, as an encoded URL does not (the `:` being encoded).
+fn has_scheme(url: &str) -> bool {
+    url.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+/// A bookmarklet that launches the viewer at `open_url` (the open page) against the playlist of the
+/// page that it is clicked on: the first HLS playlist that the page fetched (which is usually the
+/// multivariant playlist), or else the source of its video, or else one that is asked for.
+pub fn bookmarklet(open_url: &str) -> String {
+    // A `#` would end the `javascript:` URL, and so none is used.
+    format!(
+        "javascript:(()=>{{\
+         let u=performance.getEntriesByType('resource').map(e=>e.name)\
+         .find(n=>n.toLowerCase().includes('.m3u8'));\
+         const v=document.querySelector('video');\
+         if(!u&&v&&v.currentSrc&&!v.currentSrc.startsWith('blob:'))u=v.currentSrc;\
+         if(!u)u=prompt('HLS playlist URL');\
+         if(u)window.open('{open_url}?{TARGET_QUERY_NAME}='+encodeURIComponent(u));\
+         }})()"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = "https://example.com/master.m3u8?token=abc&expires=123";
+
+    #[test]
+    fn target_is_taken_in_any_encoding() {
+        let launch = |search: &str| launch_target(OPEN_PATH, search, "");
+        let playlist = Some(String::from(PLAYLIST));
+        let encoded = "https%3A%2F%2Fexample.com%2Fmaster.m3u8%3Ftoken%3Dabc%26expires%3D123";
+        assert_eq!(playlist, launch(&format!("u={encoded}")));
+        assert_eq!(
+            playlist,
+            launch(&format!("u={}", encoded.replace('%', "%25")))
+        );
+        assert_eq!(playlist, launch(&format!("u={PLAYLIST}")));
+        assert_eq!(playlist, launch(&format!("utm=1&u={PLAYLIST}")));
+        assert_eq!(
+            playlist,
+            launch_target(
+                "/hls-manifest-viewer/open/https://example.com/master.m3u8",
+                "token=abc&expires=123",
+                ""
+            )
+        );
+        assert_eq!(
+            Some(String::from("https://example.com/master.m3u8#t=10")),
+            launch_target(OPEN_PATH, "u=https://example.com/master.m3u8", "#t=10")
+        );
+        assert_eq!(None, launch(""));
+        assert_eq!(None, launch("u=master.m3u8"));
+    }
+
+    #[test]
+    fn protocol_links_are_opened_over_https() {
+        let launch = |search| launch_target(OPEN_PATH, search, "");
+        assert_eq!(
+            Some("https://example.com/master.m3u8"),
+            launch("u=web%2Bhls%3A%2F%2Fexample.com%2Fmaster.m3u8").as_deref()
+        );
+        assert_eq!(
+            Some("http://example.com/master.m3u8"),
+            launch("u=web%2Bhls%3Ahttp%3A%2F%2Fexample.com%2Fmaster.m3u8").as_deref()
+        );
+    }
+}
@@ -0,0 +1,171 @@
+// The placement of a live playlist on the wall clock, from its EXT-X-PROGRAM-DATE-TIME tags, so
+// that the end of the playlist, the live edge that players start from, and the hold-back window
+// between them can be compared against the current time.
+
+use crate::utils::{
+    playlist_lines::{LineKind, parse},
+    playlist_timeline::timeline,
+    timeline_drift::parse_date_time,
+    validation::is_live,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveTimeline {
+    /// The segments as (start, duration), with the start in milliseconds since the Unix epoch and
+    /// the duration in seconds.
+    pub segments: Vec<(f64, f64)>,
+    pub target_duration: f64,
+    /// How far from the end of the playlist players start (HOLD-BACK), in seconds.
+    pub hold_back: f64,
+    /// How far from the end of the playlist players start when playing at low latency
+    /// (PART-HOLD-BACK), in seconds.
+    pub part_hold_back: Option<f64>,
+}
+
+impl LiveTimeline {
+    /// The wall-clock time of the start of the playlist, in milliseconds since the Unix epoch.
+    pub fn start_millis(&self) -> f64 {
+        self.segments
+            .first()
+            .map(|(start, _)| *start)
+            .unwrap_or_default()
+    }
+
+    /// The wall-clock time of the end of the last segment, in milliseconds since the Unix epoch.
+    pub fn end_millis(&self) -> f64 {
+        self.segments
+            .last()
+            .map(|(start, duration)| start + duration * 1000.0)
+            .unwrap_or_default()
+    }
+
+    /// The wall-clock time that players start from, which is the hold back from the end of the
+    /// playlist.
+    pub fn live_edge_millis(&self) -> f64 {
+        self.end_millis() - self.hold_back * 1000.0
+    }
+
+    /// How far behind the current time the end of the playlist is, in seconds. This is negative
+    /// when the playlist ends after the current time, which means that the clocks disagree.
+    pub fn publishing_delay(&self, now_millis: f64) -> f64 {
+        (now_millis - self.end_millis()) / 1000.0
+    }
+
+    /// Whether the end of the playlist is further behind the current time than a server that keeps
+    /// to schedule would let it fall, which is the target duration (the longest that a segment can
+    /// take to be published) on top of the duration of the last segment.
+    pub fn is_behind_schedule(&self, now_millis: f64) -> bool {
+        let last_duration = self.segments.last().map(|(_, d)| *d).unwrap_or_default();
+        self.publishing_delay(now_millis) > self.target_duration + last_duration
+    }
+}
+
+/// The live timeline of a playlist that is still being updated, as long as it has segments along
+/// with an EXT-X-PROGRAM-DATE-TIME to place them by.
+pub fn live_timeline(playlist: &str) -> Option<LiveTimeline> {
+    if !is_live(playlist) {
+        return None;
+    }
+    let segments = timeline(playlist).segments;
+    let mut target_duration = None;
+    let mut hold_back = None;
+    let mut part_hold_back = None;
+    // The most recent EXT-X-PROGRAM-DATE-TIME (in milliseconds) along with its line number, as the
+    // date closest to the end of the playlist places the end most accurately.
+    let mut anchor = None;
+    for line in parse(playlist) {
+        let LineKind::Tag(tag) = line.kind else {
+            continue;
+        };
+        match tag.name {
+            "EXT-X-TARGETDURATION" => {
+                target_duration = tag.value.and_then(|value| value.trim().parse().ok());
+            }
+            "EXT-X-SERVER-CONTROL" => {
+                let seconds = |name| tag.attribute_str(name)?.parse::<f64>().ok();
+                hold_back = seconds("HOLD-BACK");
+                part_hold_back = seconds("PART-HOLD-BACK");
+            }
+            "EXT-X-PROGRAM-DATE-TIME" => {
+                if let Some(millis) = tag.value.and_then(parse_date_time) {
+                    anchor = Some((line.number, millis));
+                }
+            }
+            _ => (),
+        }
+    }
+    let target_duration: f64 = target_duration?;
+    let (anchor_line, millis) = anchor?;
+    // The date applies to the segment that follows the tag.
+    let anchor_start = segments
+        .iter()
+        .find(|segment| segment.line > anchor_line)?
+        .start;
+    Some(LiveTimeline {
+        segments: segments
+            .iter()
+            .map(|segment| {
+                let start = millis + (segment.start - anchor_start) * 1000.0;
+                (start, segment.duration)
+            })
+            .collect(),
+        target_duration,
+        // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.3.8
+        //
+        // The default value is 3 times the Target Duration.
+        hold_back: hold_back.unwrap_or(3.0 * target_duration),
+        part_hold_back,
+    })
+}
+
+/// The time of day of the wall-clock time (in milliseconds since the Unix epoch), in UTC.
+pub fn format_time_of_day(millis: f64) -> String {
+    let seconds = (millis / 1000.0).floor() as i64;
+    let seconds = seconds.rem_euclid(86_400);
+    format!(
+        "{:02}:{:02}:{:02}Z",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = concat!(
+        "#EXTM3U\n",
+        "#EXT-X-TARGETDURATION:4\n",
+        "#EXT-X-MEDIA-SEQUENCE:100\n",
+        "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00Z\n",
+        "#EXTINF:4,\n",
+        "100.mp4\n",
+        "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:05Z\n",
+        "#EXTINF:4,\n",
+        "101.mp4\n",
+    );
+
+    #[test]
+    fn live_timeline_is_placed_by_the_last_program_date_time() {
+        let midnight = parse_date_time("2025-01-01T00:00:00Z").unwrap();
+        let live = live_timeline(PLAYLIST).unwrap();
+        assert_eq!(
+            vec![(midnight + 1000.0, 4.0), (midnight + 5000.0, 4.0)],
+            live.segments
+        );
+        assert_eq!(12.0, live.hold_back);
+        assert_eq!(midnight - 3000.0, live.live_edge_millis());
+        assert_eq!(1.0, live.publishing_delay(midnight + 10_000.0));
+        assert!(!live.is_behind_schedule(midnight + 17_000.0));
+        assert!(live.is_behind_schedule(midnight + 17_001.0));
+        assert_eq!(None, live_timeline(&format!("{PLAYLIST}#EXT-X-ENDLIST\n")));
+    }
+
+    #[test]
+    fn time_of_day_is_in_utc() {
+        let millis = parse_date_time("2025-01-01T13:04:05.9+01:00").unwrap();
+        assert_eq!("12:04:05Z", format_time_of_day(millis));
+    }
+}
@@ -1,12 +1,80 @@
+pub mod ad_avails;
+pub mod ad_breaks;
+pub mod aes;
+pub mod audio;
+pub mod bandwidth;
 mod bitter;
+pub mod box_json;
+pub mod box_structure;
+pub mod box_tree;
+pub mod byterange;
+pub mod codecs;
+pub mod csv;
+pub mod data_uri;
+pub mod daterange_cue;
+#[cfg(feature = "web")]
+pub mod download;
+#[cfg(feature = "web")]
+pub mod embed;
+pub mod encryption;
+pub mod fairplay;
+pub mod fetch_timing;
+pub mod frame_rate;
+#[cfg(feature = "web")]
+pub mod har;
 pub mod hex;
+pub mod hls_spec;
+#[cfg(feature = "web")]
 pub mod href;
+pub mod html_report;
+pub mod i18n;
+pub mod interstitials;
+pub mod keyformat;
+pub mod keyframe;
+pub mod launch;
+pub mod live_timeline;
 pub mod mp4_atom_properties;
 pub mod mp4_parsing;
+#[cfg(feature = "web")]
 pub mod network;
-mod pssh_data;
+#[cfg(feature = "web")]
+pub mod offline_cache;
+#[cfg(feature = "web")]
+pub mod playback;
+pub mod playlist_diff;
+pub mod playlist_json;
+pub mod playlist_lines;
+pub mod playlist_minimap;
+pub mod playlist_timeline;
+#[cfg(feature = "web")]
+pub mod proxy;
+pub mod pssh_data;
 pub mod query_codec;
+pub mod resolution;
+#[cfg(feature = "web")]
 pub mod response;
+pub mod sample_entries;
+pub mod scte35;
+pub mod scte35_xml;
+#[cfg(feature = "web")]
+pub mod segment_cache;
+pub mod segment_runs;
+#[cfg(feature = "web")]
+pub mod session_bundle;
+#[cfg(feature = "web")]
+pub mod settings;
+pub mod splice_point;
+#[cfg(feature = "web")]
+pub mod storage;
+#[cfg(feature = "web")]
+pub mod thumbnail;
+pub mod timeline_drift;
+#[cfg(feature = "web")]
+pub mod url_history;
+pub mod validation;
+pub mod variant_summary;
+pub mod video_layout;
+pub mod webvtt;
 
 #[cfg(test)]
 mod tests {
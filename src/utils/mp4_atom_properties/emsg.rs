@@ -1,40 +1,13 @@
-use crate::utils::mp4_atom_properties::{
-    AtomProperties, AtomPropertyValue, AtomWithProperties, BasicPropertyValue, TablePropertyValue,
-};
+use crate::utils::mp4_atom_properties::{AtomProperties, AtomPropertyValue, AtomWithProperties};
+#[cfg(feature = "id3")]
+use crate::utils::mp4_atom_properties::{BasicPropertyValue, TablePropertyValue};
 use mp4_atom::Emsg;
 
 impl AtomWithProperties for Emsg {
     fn properties(&self) -> AtomProperties {
-        let message_data = if &self.scheme_id_uri == "https://aomedia.org/emsg/ID3" {
-            let message_data_reader = std::io::Cursor::new(self.message_data.clone());
-            match id3::Tag::read_from2(message_data_reader) {
-                Ok(id3_tag) => {
-                    let mut tags = Vec::new();
-                    for frame in id3_tag.frames() {
-                        let id = frame.id();
-                        let value = format!("{}", frame.content());
-                        tags.push((id, value));
-                    }
-                    AtomPropertyValue::Table(TablePropertyValue {
-                        headers: Some(vec!["id3 frame ID", "Value"]),
-                        rows: tags
-                            .iter()
-                            .map(|(name, value)| {
-                                vec![
-                                    BasicPropertyValue::from(*name),
-                                    BasicPropertyValue::from(value),
-                                ]
-                            })
-                            .collect(),
-                    })
-                }
-                Err(_) => {
-                    AtomPropertyValue::from(String::from_utf8_lossy(&self.message_data).to_string())
-                }
-            }
-        } else {
+        let message_data = id3_frames(self).unwrap_or_else(|| {
             AtomPropertyValue::from(String::from_utf8_lossy(&self.message_data).to_string())
-        };
+        });
         AtomProperties::from_static_keys(
             "EventMessageBox",
             vec![
@@ -62,3 +35,30 @@ impl AtomWithProperties for Emsg {
         )
     }
 }
+
+#[cfg(feature = "id3")]
+fn id3_frames(emsg: &Emsg) -> Option<AtomPropertyValue> {
+    if emsg.scheme_id_uri != "https://aomedia.org/emsg/ID3" {
+        return None;
+    }
+    let message_data_reader = std::io::Cursor::new(emsg.message_data.clone());
+    let id3_tag = id3::Tag::read_from2(message_data_reader).ok()?;
+    let rows = id3_tag
+        .frames()
+        .map(|frame| {
+            vec![
+                BasicPropertyValue::from(frame.id()),
+                BasicPropertyValue::from(format!("{}", frame.content())),
+            ]
+        })
+        .collect();
+    Some(AtomPropertyValue::Table(TablePropertyValue {
+        headers: Some(vec!["id3 frame ID", "Value"]),
+        rows,
+    }))
+}
+
+#[cfg(not(feature = "id3"))]
+fn id3_frames(_: &Emsg) -> Option<AtomPropertyValue> {
+    None
+}
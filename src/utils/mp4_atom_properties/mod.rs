@@ -1,4 +1,37 @@
-use crate::utils::mp4_parsing::{
+//! The properties of ISOBMFF boxes, as the ISOBMFF viewer lists them.
+//!
+//! Each box of a segment is read with [`get_properties`], which gives the name of the box and its
+//! fields as [`AtomProperties`]. The boxes that [`mp4_atom`](crate::mp4_atom) does not parse (such
+//! as `dac4`, `prft`, `pssh`, `tenc` and `senc`) are parsed by the atoms re-exported here, which
+//! can also be decoded on their own with [`mp4_atom::Atom`](crate::mp4_atom::Atom); the types of
+//! their fields are in [`mp4_parsing`](crate::mp4_parsing).
+//!
+//! ```
+//! use hls_manifest_viewer::mp4_atom::{Header, ReadFrom};
+//! use hls_manifest_viewer::mp4_atom_properties::{AtomPropertyValue, get_properties};
+//! use std::io::Cursor;
+//!
+//! let mfhd = [0, 0, 0, 16, b'm', b'f', b'h', b'd', 0, 0, 0, 0, 0, 0, 0, 7];
+//! let mut reader = Cursor::new(mfhd.to_vec());
+//! let header = Header::read_from(&mut reader)?;
+//! let properties = get_properties(&header, &mut reader)?.properties;
+//! assert_eq!("MovieFragmentHeaderBox", properties.box_name);
+//! assert_eq!(
+//!     ("sequence_number".into(), AtomPropertyValue::from(7u32)),
+//!     properties.properties[1]
+//! );
+//! # Ok::<(), hls_manifest_viewer::mp4_atom::Error>(())
+//! ```
+//!
+//! # Stability
+//!
+//! This module, along with `mp4_parsing`, follows semantic versioning: while the crate is below
+//! 1.0, a breaking change to either is only made along with a bump of the minor version. The box
+//! names and property names are part of that, as scripts match on them. New boxes, properties and
+//! kinds of value may be added at any time, which is why the types here are `#[non_exhaustive]`.
+
+use crate::utils::{csv::csv, hex::hex_rows};
+pub use crate::utils::mp4_parsing::{
     dvcc::Dvcc, Blin, Colr, Corg, Dac3, Dac4, Dadj, Dec3, Dvvc, Equi, Fish, Frma, Hequ, Hero,
     Hfov, Hvce, Lac4, Ldst, Lfad, Lhvc, Lnhd, Lnin, Must, Pkin, Prim, Prft, Prji, Pssh, Rdim,
     Rect, Schm, Senc, Stri, Tenc, Uqua,
@@ -104,9 +137,13 @@ mod vmhd;
 mod vpcc;
 mod year;
 
+/// The properties of a box.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct AtomProperties {
+    /// The name that the specification gives the box, such as `MovieFragmentHeaderBox`.
     pub box_name: &'static str,
+    /// The fields of the box, by name, in the order that they are in the box.
     pub properties: Vec<(Cow<'static, str>, AtomPropertyValue)>,
 }
 impl AtomProperties {
@@ -125,9 +162,12 @@ impl AtomProperties {
     }
 }
 
+/// The value of a field of a box.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum AtomPropertyValue {
     Basic(BasicPropertyValue),
+    /// A field that repeats, such as the samples of a `trun`.
     Table(TablePropertyValue),
 }
 impl<T> From<T> for AtomPropertyValue
@@ -139,7 +179,9 @@ where
     }
 }
 
+/// A single value. Its string form (through `String::from`) is how the viewer shows it.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum BasicPropertyValue {
     String(String),
     U64(u64),
@@ -151,22 +193,41 @@ pub enum BasicPropertyValue {
     I8(i8),
     Usize(usize),
     Bool(bool),
+    /// Bytes that are shown as hex, in rows of 16.
     Hex(Vec<u8>),
+    /// Bytes whose bits are flags, shown in binary.
     BinaryMask(Vec<u8>),
+    /// A URL that the box points to, which is shown as a link.
+    Url(String),
 }
 impl BasicPropertyValue {
-    pub fn is_hex(&self) -> bool {
-        matches!(self, Self::Hex(_))
+    /// A URL from the box, which is only linked to when it is http(s), as the box could otherwise
+    /// carry a javascript: URL.
+    pub fn url(value: &str) -> Self {
+        let scheme = value
+            .split_once("://")
+            .map(|(scheme, _)| scheme.to_ascii_lowercase());
+        if matches!(scheme.as_deref(), Some("http" | "https")) {
+            Self::Url(value.to_string())
+        } else {
+            Self::String(value.to_string())
+        }
     }
 
-    pub fn is_binary_mask(&self) -> bool {
-        matches!(self, Self::BinaryMask(_))
+    /// Whether the value only reads right preformatted, as hex and binary masks are laid out in
+    /// rows and columns, and some strings (such as XML) over several lines.
+    pub fn is_preformatted(&self) -> bool {
+        match self {
+            Self::Hex(_) | Self::BinaryMask(_) => true,
+            Self::String(s) => s.contains('\n'),
+            _ => false,
+        }
     }
 }
 impl From<&BasicPropertyValue> for String {
     fn from(value: &BasicPropertyValue) -> Self {
         match value {
-            BasicPropertyValue::String(s) => s.to_string(),
+            BasicPropertyValue::String(s) | BasicPropertyValue::Url(s) => s.to_string(),
             BasicPropertyValue::U64(u) => format!("{u}"),
             BasicPropertyValue::U32(u) => format!("{u}"),
             BasicPropertyValue::U16(u) => format!("{u}"),
@@ -176,32 +237,7 @@ impl From<&BasicPropertyValue> for String {
             BasicPropertyValue::I8(i) => format!("{i}"),
             BasicPropertyValue::Usize(u) => format!("{u}"),
             BasicPropertyValue::Bool(b) => format!("{b}"),
-            BasicPropertyValue::Hex(bytes) => {
-                // Rows of hex - 16 columns to a row
-                let mut rows = Vec::new();
-                // Columns of hex - 4 sections to a column
-                let mut columns = Vec::new();
-                // Sections of hex - 4 bytes to a section
-                let mut sections = Vec::new();
-                for byte in bytes {
-                    sections.push(format!("{byte:02X}"));
-                    if sections.len() == 4 {
-                        columns.push(sections.join(" "));
-                        sections.clear();
-                        if columns.len() == 4 {
-                            rows.push(columns.join("  "));
-                            columns.clear();
-                        }
-                    }
-                }
-                if !sections.is_empty() {
-                    columns.push(sections.join(" "));
-                }
-                if !columns.is_empty() {
-                    rows.push(columns.join("  "));
-                }
-                rows.join("\n")
-            }
+            BasicPropertyValue::Hex(bytes) => hex_rows(bytes),
             BasicPropertyValue::BinaryMask(bytes) => bytes
                 .iter()
                 .map(|b| format!("{b:08b}"))
@@ -313,17 +349,33 @@ where
     }
 }
 
+/// Rows of values, with the name of each column when the columns have names.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct TablePropertyValue {
     pub headers: Option<Vec<&'static str>>,
     pub rows: Vec<Vec<BasicPropertyValue>>,
 }
+impl TablePropertyValue {
+    /// The table as CSV, with the headers (if any) as the first row.
+    pub fn to_csv(&self) -> String {
+        let headers = self
+            .headers
+            .iter()
+            .map(|headers| headers.iter().map(|header| String::from(*header)).collect());
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(String::from).collect::<Vec<_>>());
+        csv(headers.chain(rows))
+    }
+}
 
 trait AtomWithProperties {
     fn properties(&self) -> AtomProperties;
 }
 
-pub fn get_properties_from_atom(atom: &Any) -> AtomProperties {
+fn get_properties_from_atom(atom: &Any) -> AtomProperties {
     #[deny(clippy::wildcard_enum_match_arm)]
     match atom {
         Any::Ftyp(ftyp) => ftyp.properties(),
@@ -419,15 +471,33 @@ pub fn get_properties_from_atom(atom: &Any) -> AtomProperties {
         Any::Moof(_) => unimplemented!(), // MovieFragmentBox
         Any::Traf(_) => unimplemented!(), // TrackFragmentBox
         Any::Mdat(_) => unimplemented!(), // MediaDataBox
-        unknown => todo!("missing props for {unknown:?}"),
+        // Boxes that mp4-atom has learnt to parse since the properties above were written.
+        unknown => AtomProperties {
+            box_name: "Unknown (unhandled box properties)",
+            properties: vec![("data".into(), AtomPropertyValue::from(format!("{unknown:?}")))],
+        },
     }
 }
 
+/// The properties of a box, along with where it ends when it is a container box.
+#[non_exhaustive]
 pub struct AtomPropertiesWithDepth {
     pub properties: AtomProperties,
+    /// For a container box, the position of the reader at which the box (and so the boxes within
+    /// it) ends. The boxes read until then are children of this one.
     pub new_depth_until: Option<u64>,
 }
 
+/// Reads the box that `header` was read for from `reader`, which must be positioned just after the
+/// header. The `size` of the box is always the first of its properties.
+///
+/// The reader is left after the box, except for a container box, which is left after the fields
+/// that come before its children so that they can be read next. A box that is not known is given
+/// as `Unknown` with its bytes as data, rather than as an error.
+///
+/// # Errors
+///
+/// If the box is known but could not be decoded.
 pub fn get_properties(
     header: &Header,
     reader: &mut Cursor<Vec<u8>>,
@@ -1,12 +1,15 @@
 use crate::utils::{
+    encryption::playready_key_id,
+    fairplay::key_id_as_uuid,
     hex::encode_hex,
     mp4_atom_properties::{
         AtomProperties, AtomPropertyValue, AtomWithProperties, BasicPropertyValue,
         TablePropertyValue,
     },
     mp4_parsing::{Pssh, pssh::PsshData},
-    pssh_data::playready::PlayReadyRecordType,
+    pssh_data::playready::{PlayReadyKid, PlayReadyRecordType, decode_checksum, pretty_print_xml},
 };
+#[cfg(feature = "widevine")]
 use widevine_proto::license_protocol::widevine_pssh_data::{Algorithm, Type};
 
 impl AtomWithProperties for Pssh {
@@ -81,8 +84,7 @@ impl AtomWithProperties for Pssh {
                                         BasicPropertyValue::from(""),
                                     ]);
                                     push_row(&mut rows, "algid", kid.algid.as_ref());
-                                    push_row(&mut rows, "checksum", kid.checksum.as_ref());
-                                    push_row(&mut rows, "kid", kid.value.as_ref());
+                                    push_kid_rows(&mut rows, kid);
                                 }
                                 if let Some(protect_info) = &header.data.protect_info {
                                     for kid in &protect_info.kids {
@@ -97,18 +99,30 @@ impl AtomWithProperties for Pssh {
                                             protect_info.algid.as_ref().or(kid.algid.as_ref()),
                                         );
                                         push_row(&mut rows, "keylen", protect_info.keylen);
-                                        push_row(&mut rows, "checksum", kid.checksum.as_ref());
-                                        push_row(&mut rows, "kid", kid.value.as_ref());
+                                        push_kid_rows(&mut rows, kid);
                                     }
                                 }
-                                push_row(&mut rows, "checksum", header.data.checksum.as_ref());
-                                push_row(&mut rows, "la_url", header.data.la_url.as_ref());
-                                push_row(&mut rows, "lui_url", header.data.lui_url.as_ref());
+                                push_checksum_rows(&mut rows, header.data.checksum.as_deref());
+                                push_row(
+                                    &mut rows,
+                                    "la_url",
+                                    header.data.la_url.as_deref().map(BasicPropertyValue::url),
+                                );
+                                push_row(
+                                    &mut rows,
+                                    "lui_url",
+                                    header.data.lui_url.as_deref().map(BasicPropertyValue::url),
+                                );
                                 push_row(&mut rows, "ds_id", header.data.ds_id.as_ref());
                                 push_row(
                                     &mut rows,
                                     "custom_attributes",
-                                    header.data.custom_attributes.as_ref(),
+                                    header.data.custom_attributes.as_deref().map(|xml| {
+                                        BasicPropertyValue::String(
+                                            pretty_print_xml(xml)
+                                                .unwrap_or_else(|| xml.to_string()),
+                                        )
+                                    }),
                                 );
                                 push_row(
                                     &mut rows,
@@ -121,6 +135,7 @@ impl AtomWithProperties for Pssh {
                                 rows,
                             })
                         }
+                        #[cfg(feature = "widevine")]
                         Some(PsshData::Widevine(data)) => {
                             let mut rows = Vec::new();
                             rows.extend(data.key_ids.iter().enumerate().map(|(index, kid)| {
@@ -288,6 +303,27 @@ impl AtomWithProperties for Pssh {
                                 rows,
                             })
                         }
+                        Some(PsshData::ClearKey(data)) => {
+                            let mut rows = data
+                                .kids
+                                .iter()
+                                .enumerate()
+                                .map(|(index, kid)| {
+                                    vec![
+                                        BasicPropertyValue::from(format!("kid {index}")),
+                                        BasicPropertyValue::from(encode_hex(kid)),
+                                    ]
+                                })
+                                .collect::<Vec<_>>();
+                            rows.push(vec![
+                                BasicPropertyValue::from("json"),
+                                BasicPropertyValue::from(&data.json),
+                            ]);
+                            AtomPropertyValue::Table(TablePropertyValue {
+                                headers: None,
+                                rows,
+                            })
+                        }
                         Some(PsshData::Raw(data)) => {
                             AtomPropertyValue::from(BasicPropertyValue::Hex(data.to_owned()))
                         }
@@ -299,6 +335,32 @@ impl AtomWithProperties for Pssh {
     }
 }
 
+// The KID is kept as the base64 it is written in, followed by the GUID that it encodes (whose first
+// three fields are little-endian in the base64).
+fn push_kid_rows(rows: &mut Vec<Vec<BasicPropertyValue>>, kid: &PlayReadyKid) {
+    push_checksum_rows(rows, kid.checksum.as_deref());
+    push_row(rows, "kid", kid.value.as_ref());
+    push_row(
+        rows,
+        "kid_guid",
+        kid.value
+            .as_deref()
+            .and_then(playready_key_id)
+            .map(|key_id| key_id_as_uuid(&key_id)),
+    );
+}
+
+fn push_checksum_rows(rows: &mut Vec<Vec<BasicPropertyValue>>, checksum: Option<&str>) {
+    push_row(rows, "checksum", checksum);
+    push_row(
+        rows,
+        "checksum_bytes",
+        checksum
+            .and_then(decode_checksum)
+            .map(BasicPropertyValue::Hex),
+    );
+}
+
 fn push_row<K, V>(rows: &mut Vec<Vec<BasicPropertyValue>>, key: K, value: Option<V>)
 where
     BasicPropertyValue: From<K>,
@@ -1,3 +1,8 @@
+//! Boxes that [`mp4_atom`](crate::mp4_atom) does not parse, each of which can be decoded with
+//! [`mp4_atom::Atom`](crate::mp4_atom::Atom). The atoms themselves are also re-exported from
+//! [`mp4_atom_properties`](crate::mp4_atom_properties), which lists their properties, and are
+//! covered by the same stability guarantees.
+
 pub mod blin;
 pub mod colr;
 pub mod corg;
@@ -41,6 +46,7 @@ pub use dac3::Dac3;
 pub use dac4::Dac4;
 pub use dadj::Dadj;
 pub use dec3::Dec3;
+pub use dvcc::Dvcc;
 pub use dvvc::Dvvc;
 pub use equi::Equi;
 pub use fish::Fish;
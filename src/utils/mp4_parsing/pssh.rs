@@ -1,8 +1,13 @@
-use crate::utils::pssh_data::playready::{self, PlayReadyPsshData};
+use crate::utils::pssh_data::{
+    clearkey::{self, ClearKeyPsshData},
+    playready::{self, PlayReadyPsshData},
+};
 use hex_literal::hex;
 use mp4_atom::{Atom, Buf, BufMut, Decode, FourCC, Result};
+#[cfg(feature = "widevine")]
 use protobuf::Message;
 use std::borrow::Cow;
+#[cfg(feature = "widevine")]
 use widevine_proto::license_protocol::WidevinePsshData;
 
 /// ProtectionSystemSpecificHeaderBox, ISO/IEC 23001-7:2016 Sect 8.1.1
@@ -12,10 +17,15 @@ pub struct Pssh {
     pub key_ids: Vec<[u8; 16]>,
     pub data: Option<PsshData>,
 }
+/// The data of the box, decoded for the DRM systems whose data is known. As the `widevine`
+/// feature adds a variant, a match on it always needs a wildcard.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum PsshData {
+    #[cfg(feature = "widevine")]
     Widevine(Box<WidevinePsshData>),
     PlayReady(PlayReadyPsshData),
+    ClearKey(ClearKeyPsshData),
     Raw(Vec<u8>),
 }
 const ABV_DRM_SYSTEM_ID: [u8; 16] = hex!("6dd8b3c3 45f4 4a68 bf3a 64168d01a4a6");
@@ -145,6 +155,7 @@ impl Atom for Pssh {
                     data: Some(PsshData::PlayReady(pssh_data)),
                 })
             }
+            #[cfg(feature = "widevine")]
             WIDEVINE_DRM_SYSTEM_ID => {
                 let pssh_data = WidevinePsshData::parse_from_bytes(&data)
                     .map_err(|e| mp4_atom::Error::InvalidString(format!("{e:?}")))?;
@@ -154,6 +165,17 @@ impl Atom for Pssh {
                     data: Some(PsshData::Widevine(Box::new(pssh_data))),
                 })
             }
+            W3C_COMMON_PSSH_DRM_SYSTEM_ID | CLEAR_DASH_IF_DRM_SYSTEM_ID => {
+                let data = match clearkey::parse_pssh_data(&data) {
+                    Some(pssh_data) => PsshData::ClearKey(pssh_data),
+                    None => PsshData::Raw(data),
+                };
+                Ok(Self {
+                    system_id,
+                    key_ids,
+                    data: Some(data),
+                })
+            }
             _ => Ok(Self {
                 system_id,
                 key_ids,
@@ -1,48 +1,133 @@
-use quick_m3u8::tag::hls::MapByterange;
-use std::{error::Error, fmt::Display};
-use wasm_bindgen::{JsCast, JsValue};
+use crate::utils::{
+    box_structure::{StructureScan, scan_structure},
+    byterange::RequestRange,
+    data_uri::{decode_data_uri, is_data_uri},
+    fetch_timing::{FetchTiming, ResourceTimingEntry},
+    har::{RecordedRequest, record_request},
+    i18n::{Message, tr, tr_with},
+    offline_cache::{OfflineCacheMode, cached_response, keep_response},
+    session_bundle::{BundledResponse, replayed_response},
+    settings::Settings,
+    storage::{load_item, save_item},
+};
+use std::{error::Error, fmt::Display, ops::Deref, sync::Arc};
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{
-    DomException, Request, Response,
-    js_sys::{ArrayBuffer, TypeError, Uint8Array},
+    AbortController, AbortSignal, DomException, PerformanceResourceTiming, ReadableStream,
+    ReadableStreamDefaultReader, ReadableStreamReadResult, Request, RequestInit, RequestMode,
+    Response,
+    js_sys::{Array, ArrayBuffer, Date, Promise, TypeError, Uint8Array},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct RequestRange {
-    pub start: u64,
-    pub end: u64,
+/// The status line and headers of a response, kept so that caching behaviour can be inspected.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResponseHeaders {
+    pub url: String,
+    pub status: u16,
+    pub status_text: String,
+    /// Header names are lowercase, as the fetch API provides them. Only the headers exposed to
+    /// the page by CORS are available.
+    pub headers: Vec<(String, String)>,
 }
-impl RequestRange {
-    pub fn from_length_with_offset(length: u64, offset: u64) -> Self {
+impl ResponseHeaders {
+    fn from_response(request_url: &str, response: &Response) -> Self {
+        let headers = response
+            .headers()
+            .entries()
+            .into_iter()
+            .filter_map(|entry| {
+                let entry = Array::from(&entry.ok()?);
+                Some((entry.get(0).as_string()?, entry.get(1).as_string()?))
+            })
+            .collect();
         Self {
-            start: offset,
-            end: (offset + length) - 1,
+            url: request_url.to_string(),
+            status: response.status(),
+            status_text: response.status_text(),
+            headers,
         }
     }
 
-    pub fn range_header_value(&self) -> String {
-        format!("bytes={}-{}", self.start, self.end)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
     }
+
+    pub fn validators(&self) -> Validators {
+        Validators {
+            etag: self.header("ETag").map(String::from),
+            last_modified: self.header("Last-Modified").map(String::from),
+        }
+    }
+}
+
+/// The validators of a response, with which the same resource can be requested again on condition
+/// that it has changed since.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
 }
-impl From<MapByterange> for RequestRange {
-    fn from(value: MapByterange) -> Self {
-        Self::from_length_with_offset(value.length, value.offset)
+impl Validators {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
     }
 }
-impl Display for RequestRange {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}-{}", self.start, self.end)
+
+// The headers that qualify a request beyond its URL, along with whether the request is only for the
+// headers of the response (a HEAD request).
+#[derive(Debug, Clone, Copy, Default)]
+struct RequestHeaders<'a> {
+    byterange: Option<RequestRange>,
+    validators: Option<&'a Validators>,
+    head: bool,
+}
+impl RequestHeaders<'_> {
+    fn method(&self) -> &'static str {
+        if self.head { "HEAD" } else { "GET" }
+    }
+
+    fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+        if let Some(byterange) = self.byterange {
+            headers.push(("Range", byterange.range_header_value()));
+        }
+        // Setting either header makes the browser bypass its own cache, so the 304 comes from the
+        // origin (or CDN) and is passed on to the page.
+        if let Some(Validators {
+            etag,
+            last_modified,
+        }) = self.validators
+        {
+            if let Some(etag) = etag {
+                headers.push(("If-None-Match", etag.clone()));
+            }
+            if let Some(last_modified) = last_modified {
+                headers.push(("If-Modified-Since", last_modified.clone()));
+            }
+        }
+        headers
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct FetchTextResponse {
     pub response_text: String,
+    /// The URL of the response, after any redirects were followed.
+    pub url: String,
+    pub headers: ResponseHeaders,
+    pub timing: FetchTiming,
 }
 impl FetchTextResponse {
     fn empty() -> Self {
         Self {
             response_text: String::new(),
+            url: String::new(),
+            headers: ResponseHeaders::default(),
+            timing: FetchTiming::default(),
         }
     }
 }
@@ -52,96 +137,1064 @@ pub struct FetchArrayBufferResonse {
     pub response_body: Vec<u8>,
     pub content_type: Option<String>,
     pub url: String,
+    pub headers: ResponseHeaders,
+    pub timing: FetchTiming,
+    /// Whether the body stops at the header of the first mdat, having been fetched with
+    /// [`fetch_segment_structure`].
+    pub body_truncated: bool,
+}
+
+impl AsRef<[u8]> for FetchArrayBufferResonse {
+    fn as_ref(&self) -> &[u8] {
+        &self.response_body
+    }
+}
+
+/// Bytes that are handed from view to view without being copied, such as the body of a response
+/// that the segment cache also holds on to.
+#[derive(Clone)]
+pub struct SharedBytes(Arc<dyn AsRef<[u8]> + Send + Sync>);
+
+impl Deref for SharedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        (*self.0).as_ref()
+    }
+}
+
+impl From<Vec<u8>> for SharedBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(Arc::new(bytes))
+    }
+}
+
+impl From<Arc<FetchArrayBufferResonse>> for SharedBytes {
+    fn from(response: Arc<FetchArrayBufferResonse>) -> Self {
+        Self(response)
+    }
+}
+
+/// How much of a response body has arrived while it is read as a stream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DownloadProgress {
+    pub received: u64,
+    /// The Content-Length of the response, when given.
+    pub total: Option<u64>,
 }
 
+// Called with the body received so far each time more of it arrives.
+type OnProgress<'a> = &'a mut dyn FnMut(&[u8], DownloadProgress);
+
+// The size of the first range requested when fetching only the structure of a segment, which is
+// usually enough to reach the mdat straight away. Each further request asks for at least twice as
+// much.
+const STRUCTURE_CHUNK_SIZE: u64 = 64 * 1024;
+// Transient failures are retried this many times, waiting twice as long before each retry.
+const MAX_RETRIES: u32 = 3;
+const INITIAL_RETRY_DELAY_MILLIS: u32 = 500;
+// Only the start of an error response body is kept, which is usually enough to explain the status.
+const BODY_EXCERPT_MAX_CHARS: usize = 1000;
+const FETCH_TIMEOUT_KEY: &str = "fetch_timeout_seconds";
+/// How long a request, including the download of its body, may take before it is aborted, unless
+/// changed in the request settings. Zero means that requests never time out.
+pub const DEFAULT_FETCH_TIMEOUT_SECONDS: u32 = 60;
+
 #[derive(Debug, Clone, PartialEq)]
-pub struct FetchError {
-    pub error: String,
-    pub extra_info: Option<String>,
+pub enum FetchError {
+    /// The request did not complete (e.g. the host could not be resolved or the connection was
+    /// reset).
+    Network { message: String },
+    /// The request was aborted because it took longer than the configured timeout.
+    Timeout,
+    /// The server responded with an unsuccessful status.
+    HttpStatus {
+        status: u16,
+        status_text: String,
+        body_excerpt: Option<String>,
+    },
+    /// The server responded, but not with the CORS headers that allow the page to read the
+    /// response.
+    Cors { message: String },
+    /// The resource could not be requested or the response could not be used.
+    Other { message: String },
+    /// The error of the last attempt of a request that was retried.
+    Retried {
+        retries: u32,
+        error: Box<FetchError>,
+    },
+    /// An error for a request that was sent through the CORS proxy.
+    Proxied {
+        proxied_url: String,
+        error: Box<FetchError>,
+    },
+}
+impl FetchError {
+    pub fn error(&self) -> String {
+        match self {
+            Self::Network { message } => tr_with(Message::NetworkError, &[("message", message)]),
+            Self::Timeout => tr(Message::TimeoutError).to_string(),
+            Self::HttpStatus {
+                status,
+                status_text,
+                ..
+            } => tr_with(
+                Message::HttpStatusError,
+                &[
+                    ("status", &status.to_string()),
+                    ("status_text", status_text),
+                ],
+            ),
+            Self::Cors { .. } => tr(Message::CorsError).to_string(),
+            Self::Other { message } => message.clone(),
+            Self::Retried { retries, error } => {
+                let message = if *retries == 1 {
+                    Message::RetriedOnceError
+                } else {
+                    Message::RetriedError
+                };
+                tr_with(
+                    message,
+                    &[("error", &error.error()), ("retries", &retries.to_string())],
+                )
+            }
+            Self::Proxied { proxied_url, error } => tr_with(
+                Message::ProxiedError,
+                &[("error", &error.error()), ("proxied_url", proxied_url)],
+            ),
+        }
+    }
+
+    pub fn extra_info(&self) -> Option<String> {
+        match self {
+            Self::HttpStatus { body_excerpt, .. } => body_excerpt.clone(),
+            Self::Cors { message } => Some(message.clone()),
+            Self::Timeout => Some(tr(Message::TimeoutErrorInfo).to_string()),
+            Self::Retried { error, .. } | Self::Proxied { error, .. } => error.extra_info(),
+            Self::Network { .. } | Self::Other { .. } => None,
+        }
+    }
+
+    // A request that timed out is not retried, as each attempt is given the whole of the timeout and
+    // so the retries would keep the request waiting several times longer than it was set to.
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Network { .. } => true,
+            Self::HttpStatus { status, .. } => matches!(status, 408 | 429 | 500 | 502 | 503 | 504),
+            Self::Proxied { error, .. } | Self::Retried { error, .. } => error.is_transient(),
+            Self::Timeout | Self::Cors { .. } | Self::Other { .. } => false,
+        }
+    }
 }
 impl Error for FetchError {}
 impl Display for FetchError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(extra_info) = &self.extra_info {
-            write!(f, "{}\n{}", self.error, extra_info)
+        if let Some(extra_info) = self.extra_info() {
+            write!(f, "{}\n{}", self.error(), extra_info)
         } else {
-            write!(f, "{}", self.error)
+            write!(f, "{}", self.error())
         }
     }
 }
 
-pub async fn fetch_text(request_url: String) -> Result<FetchTextResponse, FetchError> {
+/// What a request is made with: the settings, which the component that makes the request takes
+/// from context, and whether the request goes through the CORS proxy that they configure. Requests
+/// take the settings as they are, unless a request is opted out of the proxy with
+/// [`RequestOptions::direct`].
+#[derive(Debug, Clone, Copy)]
+pub struct RequestOptions<'a> {
+    pub settings: &'a Settings,
+    pub use_proxy: bool,
+}
+
+impl RequestOptions<'_> {
+    /// Opts the request out of the proxy, so that it is made directly whatever the proxy settings.
+    pub fn direct(self) -> Self {
+        Self {
+            use_proxy: false,
+            ..self
+        }
+    }
+
+    // The URL to request `url` through the proxy, or `None` when it is requested directly.
+    fn proxied_url(&self, url: &str) -> Option<String> {
+        self.use_proxy
+            .then(|| self.settings.proxy.proxied_url(url))
+            .flatten()
+    }
+}
+
+impl<'a> From<&'a Settings> for RequestOptions<'a> {
+    fn from(settings: &'a Settings) -> Self {
+        Self {
+            settings,
+            use_proxy: true,
+        }
+    }
+}
+
+pub fn load_fetch_timeout_seconds() -> u32 {
+    load_item(FETCH_TIMEOUT_KEY)
+        .and_then(|seconds| seconds.parse().ok())
+        .unwrap_or(DEFAULT_FETCH_TIMEOUT_SECONDS)
+}
+
+pub fn save_fetch_timeout_seconds(seconds: u32) {
+    save_item(FETCH_TIMEOUT_KEY, &seconds.to_string());
+}
+
+pub async fn fetch_text(
+    request_url: String,
+    options: impl Into<RequestOptions<'_>>,
+) -> Result<FetchTextResponse, FetchError> {
+    fetch_text_with(request_url, RequestHeaders::default(), options.into()).await
+}
+
+/// Requests `request_url` again on condition that it has changed since the response that
+/// `validators` came from. When the origin replies 304 Not Modified, the response is given
+/// `previous_text`, while its headers keep the 304 status.
+pub async fn revalidate_text(
+    request_url: String,
+    validators: &Validators,
+    previous_text: String,
+    options: impl Into<RequestOptions<'_>>,
+) -> Result<FetchTextResponse, FetchError> {
+    let headers = RequestHeaders {
+        validators: Some(validators),
+        ..RequestHeaders::default()
+    };
+    let mut response = fetch_text_with(request_url, headers, options.into()).await?;
+    if response.headers.status == 304 {
+        response.response_text = previous_text;
+    }
+    Ok(response)
+}
+
+async fn fetch_text_with(
+    request_url: String,
+    request_headers: RequestHeaders<'_>,
+    options: RequestOptions<'_>,
+) -> Result<FetchTextResponse, FetchError> {
+    let settings = options.settings;
     if request_url.is_empty() {
         return Ok(FetchTextResponse::empty());
     }
-    let response = response_from(&request_url, None).await?;
+    if is_data_uri(&request_url) {
+        let (body, headers, timing) = decode_locally(&request_url, request_headers.byterange)?;
+        return Ok(FetchTextResponse {
+            response_text: String::from_utf8_lossy(&body).into_owned(),
+            url: request_url,
+            headers,
+            timing,
+        });
+    }
+    if let Some(replayed) = replayed(&request_url, request_headers) {
+        let (body, headers, timing) = replayed?;
+        return Ok(FetchTextResponse {
+            response_text: String::from_utf8_lossy(&body).into_owned(),
+            url: request_url,
+            headers,
+            timing,
+        });
+    }
+    if let Some((body, headers, timing)) =
+        kept_offline(&request_url, request_headers, settings.offline_cache).await
+    {
+        return Ok(FetchTextResponse {
+            response_text: String::from_utf8_lossy(&body).into_owned(),
+            url: request_url,
+            headers,
+            timing,
+        });
+    }
+    let proxied_url = options.proxied_url(&request_url);
+    let fetched_url = proxied_url.as_deref().unwrap_or(&request_url);
+    let start = now();
+    let (response, deadline) = response_from(
+        &request_url,
+        proxied_url.as_deref(),
+        request_headers,
+        options,
+    )
+    .await
+    .inspect_err(|e| record_failure(&request_url, fetched_url, request_headers, start, e))?;
+    let first_byte = now();
+    let headers = ResponseHeaders::from_response(&request_url, &response);
+    // Redirects are followed by the proxy, out of sight of the page.
+    let url = if proxied_url.is_some() {
+        request_url.clone()
+    } else {
+        response.url()
+    };
     let response_text = JsFuture::from(response.text().map_err(fetch_failed)?)
         .await
         .map_err(fetch_failed)?
         .as_string()
         .expect("text() on a fetch Response must provide a String");
-    Ok(FetchTextResponse { response_text })
+    drop(deadline);
+    let timing = timing_of(
+        fetched_url,
+        FetchTiming {
+            start,
+            first_byte,
+            end: now(),
+            bytes: response_text.len() as u64,
+            resource_timing: false,
+        },
+    );
+    record(
+        &request_url,
+        fetched_url,
+        request_headers,
+        Some(&headers),
+        timing,
+        Some(response_text.as_bytes()),
+        None,
+    );
+    keep_response(
+        &request_url,
+        request_headers.byterange,
+        &headers,
+        response_text.as_bytes(),
+        settings.offline_cache,
+    );
+    Ok(FetchTextResponse {
+        response_text,
+        url,
+        headers,
+        timing,
+    })
 }
 
 pub async fn fetch_array_buffer(
     request_url: String,
     byterange: Option<RequestRange>,
+    options: impl Into<RequestOptions<'_>>,
+) -> Result<FetchArrayBufferResonse, FetchError> {
+    fetch_array_buffer_with(request_url, byterange, options.into(), None).await
+}
+
+/// Fetches like [`fetch_array_buffer`], but reads the body as a stream, calling `on_progress` with
+/// the body received so far after each chunk of it arrives.
+pub async fn fetch_array_buffer_streamed(
+    request_url: String,
+    byterange: Option<RequestRange>,
+    options: impl Into<RequestOptions<'_>>,
+    mut on_progress: impl FnMut(&[u8], DownloadProgress),
+) -> Result<FetchArrayBufferResonse, FetchError> {
+    let options = options.into();
+    fetch_array_buffer_with(request_url, byterange, options, Some(&mut on_progress)).await
+}
+
+async fn fetch_array_buffer_with(
+    request_url: String,
+    byterange: Option<RequestRange>,
+    options: RequestOptions<'_>,
+    on_progress: Option<OnProgress<'_>>,
 ) -> Result<FetchArrayBufferResonse, FetchError> {
-    let response = response_from(&request_url, byterange).await?;
+    let settings = options.settings;
+    if is_data_uri(&request_url) {
+        let (response_body, headers, timing) = decode_locally(&request_url, byterange)?;
+        return Ok(FetchArrayBufferResonse {
+            response_body,
+            content_type: headers.header("Content-Type").map(String::from),
+            url: request_url,
+            headers,
+            timing,
+            body_truncated: false,
+        });
+    }
+    let request_headers = RequestHeaders {
+        byterange,
+        ..RequestHeaders::default()
+    };
+    if let Some(replayed) = replayed(&request_url, request_headers) {
+        let (response_body, headers, timing) = replayed?;
+        return Ok(FetchArrayBufferResonse {
+            response_body,
+            content_type: headers.header("Content-Type").map(String::from),
+            url: request_url,
+            headers,
+            timing,
+            body_truncated: false,
+        });
+    }
+    if let Some((response_body, headers, timing)) =
+        kept_offline(&request_url, request_headers, settings.offline_cache).await
+    {
+        return Ok(FetchArrayBufferResonse {
+            response_body,
+            content_type: headers.header("Content-Type").map(String::from),
+            url: request_url,
+            headers,
+            timing,
+            body_truncated: false,
+        });
+    }
+    let proxied_url = options.proxied_url(&request_url);
+    let fetched_url = proxied_url.clone().unwrap_or_else(|| request_url.clone());
+    let start = now();
+    let (response, deadline) = response_from(
+        &request_url,
+        proxied_url.as_deref(),
+        request_headers,
+        options,
+    )
+    .await
+    .inspect_err(|e| record_failure(&request_url, &fetched_url, request_headers, start, e))?;
+    let first_byte = now();
     let content_type = content_type_from(&response);
-    let url = response.url();
-    let response_buf = JsFuture::from(response.array_buffer().map_err(fetch_failed)?)
-        .await
-        .map_err(fetch_failed)?;
-    let array_buf = response_buf
-        .dyn_into::<ArrayBuffer>()
-        .expect("array_buffer() on a fetch Response must provide an ArrayBuffer");
-    let data = Uint8Array::new(&array_buf);
-    let mut body = vec![0; data.length() as usize];
-    data.copy_to(&mut body);
+    let headers = ResponseHeaders::from_response(&request_url, &response);
+    // The URL of the proxy says nothing about the type of the segment.
+    let url = if proxied_url.is_some() {
+        request_url.clone()
+    } else {
+        response.url()
+    };
+    let body = match (on_progress, response.body()) {
+        (Some(on_progress), Some(stream)) => {
+            let total = headers
+                .header("Content-Length")
+                .and_then(|length| length.trim().parse().ok());
+            read_stream(stream, total, on_progress).await?
+        }
+        _ => {
+            let response_buf = JsFuture::from(response.array_buffer().map_err(fetch_failed)?)
+                .await
+                .map_err(fetch_failed)?;
+            let array_buf = response_buf
+                .dyn_into::<ArrayBuffer>()
+                .expect("array_buffer() on a fetch Response must provide an ArrayBuffer");
+            let data = Uint8Array::new(&array_buf);
+            let mut body = vec![0; data.length() as usize];
+            data.copy_to(&mut body);
+            body
+        }
+    };
+    drop(deadline);
+    let timing = timing_of(
+        &fetched_url,
+        FetchTiming {
+            start,
+            first_byte,
+            end: now(),
+            bytes: body.len() as u64,
+            resource_timing: false,
+        },
+    );
+    record(
+        &request_url,
+        &fetched_url,
+        request_headers,
+        Some(&headers),
+        timing,
+        Some(&body),
+        None,
+    );
+    keep_response(
+        &request_url,
+        byterange,
+        &headers,
+        &body,
+        settings.offline_cache,
+    );
     Ok(FetchArrayBufferResonse {
         response_body: body,
         content_type,
         url,
+        headers,
+        timing,
+        body_truncated: false,
     })
 }
 
-async fn response_from(
+// Reads the body of a response chunk by chunk as it arrives.
+async fn read_stream(
+    stream: ReadableStream,
+    total: Option<u64>,
+    on_progress: OnProgress<'_>,
+) -> Result<Vec<u8>, FetchError> {
+    let reader = stream
+        .get_reader()
+        .unchecked_into::<ReadableStreamDefaultReader>();
+    let mut body = Vec::new();
+    loop {
+        let result = JsFuture::from(reader.read())
+            .await
+            .map_err(fetch_failed)?
+            .unchecked_into::<ReadableStreamReadResult>();
+        if result.get_done().unwrap_or(true) {
+            return Ok(body);
+        }
+        let chunk = result.get_value().unchecked_into::<Uint8Array>();
+        let start = body.len();
+        body.resize(start + chunk.length() as usize, 0);
+        chunk.copy_to(&mut body[start..]);
+        on_progress(
+            &body,
+            DownloadProgress {
+                received: body.len() as u64,
+                total,
+            },
+        );
+    }
+}
+
+/// Requests only the headers of the resource (with a HEAD request), for the size of its body as
+/// given by Content-Length, which is `None` when the server does not provide it.
+pub async fn fetch_content_length(
+    request_url: String,
+    options: impl Into<RequestOptions<'_>>,
+) -> Result<Option<u64>, FetchError> {
+    let options = options.into();
+    let settings = options.settings;
+    if is_data_uri(&request_url) {
+        let (body, _, _) = decode_locally(&request_url, None)?;
+        return Ok(Some(body.len() as u64));
+    }
+    let request_headers = RequestHeaders {
+        head: true,
+        ..RequestHeaders::default()
+    };
+    if let Some(replayed) = replayed(&request_url, request_headers) {
+        let (_, headers, _) = replayed?;
+        return Ok(content_length(&headers));
+    }
+    // The length of a response kept offline is that of its body, whatever its headers say.
+    if let Some((body, _)) = cached_response(&request_url, None, settings.offline_cache).await {
+        return Ok(Some(body.len() as u64));
+    }
+    let proxied_url = options.proxied_url(&request_url);
+    let fetched_url = proxied_url.clone().unwrap_or_else(|| request_url.clone());
+    let start = now();
+    let (response, _deadline) = response_from(
+        &request_url,
+        proxied_url.as_deref(),
+        request_headers,
+        options,
+    )
+    .await
+    .inspect_err(|e| record_failure(&request_url, &fetched_url, request_headers, start, e))?;
+    let end = now();
+    let headers = ResponseHeaders::from_response(&request_url, &response);
+    let timing = FetchTiming {
+        start,
+        first_byte: end,
+        end,
+        ..FetchTiming::default()
+    };
+    record(
+        &request_url,
+        &fetched_url,
+        request_headers,
+        Some(&headers),
+        timing,
+        None,
+        None,
+    );
+    Ok(content_length(&headers))
+}
+
+fn content_length(headers: &ResponseHeaders) -> Option<u64> {
+    headers
+        .header("Content-Length")
+        .and_then(|length| length.trim().parse().ok())
+}
+
+/// Fetches two byteranges of the same resource (such as an initialization segment and a media
+/// segment that follows it) in one request covering both, and slices the response into the two.
+/// The ranges must be contiguous, as checked with [`RequestRange::coalesced_with`].
+pub async fn fetch_coalesced(
+    request_url: String,
+    first: RequestRange,
+    second: RequestRange,
+    options: impl Into<RequestOptions<'_>>,
+) -> Result<(FetchArrayBufferResonse, FetchArrayBufferResonse), FetchError> {
+    let Some(combined) = first.coalesced_with(second) else {
+        return Err(FetchError::Other {
+            message: format!("Error: byteranges {first} and {second} are not contiguous"),
+        });
+    };
+    let response = fetch_array_buffer(request_url, Some(combined), options).await?;
+    // A server that does not support ranges replies 200 with the whole resource.
+    let body_start = if response.headers.status == 206 {
+        combined.start
+    } else {
+        0
+    };
+    let slice = |range| FetchArrayBufferResonse {
+        response_body: slice_body(&response.response_body, body_start, range),
+        ..response.clone()
+    };
+    Ok((slice(first), slice(second)))
+}
+
+// The part of a body that starts at `body_start` of the resource that falls within `range`.
+fn slice_body(body: &[u8], body_start: u64, range: RequestRange) -> Vec<u8> {
+    let offset = |position: u64| {
+        usize::try_from(position.saturating_sub(body_start))
+            .unwrap_or(usize::MAX)
+            .min(body.len())
+    };
+    let start = offset(range.start);
+    let end = offset(range.end.saturating_add(1)).max(start);
+    body[start..end].to_vec()
+}
+
+/// Fetches only the boxes of an ISOBMFF segment that come before its media data, by extending the
+/// requested range until the header of the first mdat has been read. When the data turns out not to
+/// be made of boxes, or the server does not honour the range, the whole segment is provided.
+pub async fn fetch_segment_structure(
+    request_url: String,
+    byterange: Option<RequestRange>,
+    options: impl Into<RequestOptions<'_>>,
+) -> Result<FetchArrayBufferResonse, FetchError> {
+    let options = options.into();
+    let start = byterange.map(|range| range.start).unwrap_or(0);
+    let last = byterange.map(|range| range.end).unwrap_or(u64::MAX);
+    let mut response: Option<FetchArrayBufferResonse> = None;
+    let mut wanted = STRUCTURE_CHUNK_SIZE;
+    loop {
+        let fetched = response
+            .as_ref()
+            .map(|response| response.response_body.len() as u64)
+            .unwrap_or(0);
+        let range = RequestRange {
+            start: start + fetched,
+            end: start.saturating_add(wanted - 1).min(last),
+        };
+        let mut chunk = fetch_array_buffer(request_url.clone(), Some(range), options).await?;
+        // A server that does not support ranges replies 200 with the whole resource, and a short
+        // reply means that the end of the resource (or of the byte range) was reached.
+        let finished = chunk.headers.status != 206
+            || (chunk.response_body.len() as u64) < range.end - range.start + 1
+            || range.end == last;
+        let mut combined = match response.take() {
+            Some(mut combined) => {
+                combined.response_body.append(&mut chunk.response_body);
+                combined.timing = FetchTiming {
+                    end: chunk.timing.end,
+                    bytes: combined.timing.bytes + chunk.timing.bytes,
+                    ..combined.timing
+                };
+                combined.headers = chunk.headers;
+                combined
+            }
+            None => chunk,
+        };
+        match scan_structure(&combined.response_body) {
+            StructureScan::Complete(length) => {
+                combined.response_body.truncate(length);
+                combined.body_truncated = true;
+                return Ok(combined);
+            }
+            _ if finished => return Ok(combined),
+            StructureScan::Incomplete(needed) => {
+                wanted = (needed as u64).max(wanted * 2);
+            }
+            StructureScan::Ended => wanted *= 2,
+            StructureScan::Unscannable => {
+                return fetch_array_buffer(request_url, byterange, options).await;
+            }
+        }
+        response = Some(combined);
+    }
+}
+
+// The body, headers and timing of a response that is given without going to the network.
+type UnfetchedResponse = (Vec<u8>, ResponseHeaders, FetchTiming);
+
+// A data: URI carries its content inline, so it is decoded here rather than fetched, and is given
+// the response that a fetch of it would have (which is also why it is left out of the HAR).
+fn decode_locally(
     request_url: &str,
     byterange: Option<RequestRange>,
+) -> Result<UnfetchedResponse, FetchError> {
+    let start = now();
+    let data_uri = decode_data_uri(request_url).map_err(|e| FetchError::Other {
+        message: format!("Error: invalid data URI: {e}"),
+    })?;
+    let mut body = data_uri.data;
+    if let Some(byterange) = byterange {
+        let end = usize::try_from(byterange.end.saturating_add(1))
+            .unwrap_or(usize::MAX)
+            .min(body.len());
+        let start = usize::try_from(byterange.start)
+            .unwrap_or(usize::MAX)
+            .min(end);
+        body = body[start..end].to_vec();
+    }
+    let headers = ResponseHeaders {
+        url: request_url.to_string(),
+        status: 200,
+        status_text: String::from("OK"),
+        headers: vec![(String::from("content-type"), data_uri.media_type)],
+    };
+    let end = now();
+    let timing = FetchTiming {
+        start,
+        first_byte: end,
+        end,
+        bytes: body.len() as u64,
+        resource_timing: false,
+    };
+    Ok((body, headers, timing))
+}
+
+// While a session bundle is replayed, requests are answered from it rather than fetched. They are
+// recorded all the same, so that what was looked into can be exported again.
+fn replayed(
+    request_url: &str,
+    request_headers: RequestHeaders<'_>,
+) -> Option<Result<UnfetchedResponse, FetchError>> {
+    let start = now();
+    let replayed = replayed_response(
+        request_headers.method(),
+        request_url,
+        request_headers.byterange,
+    )?;
+    Some(match replayed {
+        Ok(BundledResponse {
+            headers,
+            body,
+            wait,
+            receive,
+        }) => {
+            let timing = FetchTiming {
+                start,
+                first_byte: start + wait,
+                end: start + wait + receive,
+                bytes: body.len() as u64,
+                resource_timing: false,
+            };
+            let body_kept = (!request_headers.head).then_some(body.as_slice());
+            record(
+                request_url,
+                request_url,
+                request_headers,
+                Some(&headers),
+                timing,
+                body_kept,
+                None,
+            );
+            Ok((body, headers, timing))
+        }
+        Err(e) => {
+            record_failure(request_url, request_url, request_headers, start, &e);
+            Err(e)
+        }
+    })
+}
+
+// While requests are served from the offline cache, the response kept for a request is given in
+// place of fetching it, and recorded as the replayed ones are.
+async fn kept_offline(
+    request_url: &str,
+    request_headers: RequestHeaders<'_>,
+    mode: OfflineCacheMode,
+) -> Option<UnfetchedResponse> {
+    let start = now();
+    let (body, headers) = cached_response(request_url, request_headers.byterange, mode).await?;
+    let end = now();
+    let timing = FetchTiming {
+        start,
+        first_byte: end,
+        end,
+        bytes: body.len() as u64,
+        resource_timing: false,
+    };
+    record(
+        request_url,
+        request_url,
+        request_headers,
+        Some(&headers),
+        timing,
+        Some(&body),
+        None,
+    );
+    Some((body, headers, timing))
+}
+
+async fn response_from(
+    request_url: &str,
+    proxied_url: Option<&str>,
+    request_headers: RequestHeaders<'_>,
+    options: RequestOptions<'_>,
+) -> Result<(Response, Option<Deadline>), FetchError> {
+    let mut retries = 0;
+    loop {
+        let deadline = Deadline::start(options.settings.fetch_timeout_seconds);
+        let signal = deadline.as_ref().map(Deadline::signal);
+        let attempted = attempt(
+            request_url,
+            proxied_url,
+            request_headers,
+            &options.settings.request_headers,
+            signal.as_ref(),
+        );
+        match attempted.await {
+            Ok(response) => return Ok((response, deadline)),
+            Err(e) if e.is_transient() && retries < MAX_RETRIES => {
+                sleep(retry_delay_millis(retries)).await;
+                retries += 1;
+            }
+            Err(e) if retries == 0 => return Err(e),
+            Err(e) => {
+                return Err(FetchError::Retried {
+                    retries,
+                    error: Box::new(e),
+                });
+            }
+        }
+    }
+}
+
+async fn attempt(
+    request_url: &str,
+    proxied_url: Option<&str>,
+    request_headers: RequestHeaders<'_>,
+    extra_headers: &[(String, String)],
+    signal: Option<&AbortSignal>,
+) -> Result<Response, FetchError> {
+    let Some(proxied_url) = proxied_url else {
+        return request(request_url, request_headers, extra_headers, signal).await;
+    };
+    request(proxied_url, request_headers, extra_headers, signal)
+        .await
+        .map_err(|e| FetchError::Proxied {
+            proxied_url: proxied_url.to_string(),
+            error: Box::new(e),
+        })
+}
+
+async fn request(
+    request_url: &str,
+    request_headers: RequestHeaders<'_>,
+    extra_headers: &[(String, String)],
+    signal: Option<&AbortSignal>,
 ) -> Result<Response, FetchError> {
     let window = web_sys::window().expect("Window must be defined");
-    let request = Request::new_with_str(request_url).map_err(fetch_failed)?;
-    if let Some(byterange) = byterange {
+    let init = RequestInit::new();
+    init.set_method(request_headers.method());
+    init.set_signal(signal);
+    let request = Request::new_with_str_and_init(request_url, &init).map_err(invalid_request)?;
+    for (name, value) in request_headers.entries() {
         request
             .headers()
-            .set("Range", &byterange.range_header_value())
-            .map_err(fetch_failed)?;
+            .set(name, &value)
+            .map_err(invalid_request)?;
     }
-    let response = JsFuture::from(window.fetch_with_request(&request))
-        .await
-        .map_err(fetch_failed)?;
+    // The headers from the settings are added to every request. Any that are not CORS-safelisted
+    // make the browser send a preflight, which the server (or proxy) must allow.
+    for (name, value) in extra_headers {
+        request
+            .headers()
+            .set(name, value)
+            .map_err(invalid_request)?;
+    }
+    let response = match JsFuture::from(window.fetch_with_request(&request)).await {
+        Ok(response) => response,
+        Err(e) => return Err(request_failed(request_url, e).await),
+    };
     let response: Response = response
         .dyn_into()
         .expect("Fetch must resolve to a Response");
-    validate(&response).await?;
+    validate(&response, request_headers.validators.is_some()).await?;
     Ok(response)
 }
 
+// The fetch API rejects with the same TypeError whether the server could not be reached or did not
+// allow the response to be read, so the two are told apart by repeating the request in no-cors mode,
+// which succeeds (with an opaque response) as long as the server responds at all.
+async fn request_failed(request_url: &str, e: JsValue) -> FetchError {
+    let error = fetch_failed(e);
+    let FetchError::Network { message } = error else {
+        return error;
+    };
+    let init = RequestInit::new();
+    init.set_mode(RequestMode::NoCors);
+    let Ok(request) = Request::new_with_str_and_init(request_url, &init) else {
+        return FetchError::Network { message };
+    };
+    let window = web_sys::window().expect("Window must be defined");
+    match JsFuture::from(window.fetch_with_request(&request)).await {
+        Ok(_) => FetchError::Cors { message },
+        Err(_) => FetchError::Network { message },
+    }
+}
+
+// Aborts a request, including the download of its body, once the timeout has passed. The timer is
+// cleared when the deadline is dropped, which is once the body has been read.
+struct Deadline {
+    controller: AbortController,
+    timer: i32,
+    _abort: Closure<dyn FnMut()>,
+}
+impl Deadline {
+    fn start(timeout_seconds: u32) -> Option<Self> {
+        if timeout_seconds == 0 {
+            return None;
+        }
+        let window = web_sys::window()?;
+        let controller = AbortController::new().ok()?;
+        let abort = Closure::once({
+            let controller = controller.clone();
+            move || controller.abort()
+        });
+        let timer = window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                abort.as_ref().unchecked_ref(),
+                i32::try_from(timeout_seconds.saturating_mul(1000)).unwrap_or(i32::MAX),
+            )
+            .ok()?;
+        Some(Self {
+            controller,
+            timer,
+            _abort: abort,
+        })
+    }
+
+    fn signal(&self) -> AbortSignal {
+        self.controller.signal()
+    }
+}
+impl Drop for Deadline {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            window.clear_timeout_with_handle(self.timer);
+        }
+    }
+}
+
+fn retry_delay_millis(retries: u32) -> u32 {
+    INITIAL_RETRY_DELAY_MILLIS.saturating_mul(2u32.saturating_pow(retries))
+}
+
+async fn sleep(millis: u32) {
+    let promise = Promise::new(&mut |resolve, _| {
+        let window = web_sys::window().expect("Window must be defined");
+        if window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                i32::try_from(millis).unwrap_or(i32::MAX),
+            )
+            .is_err()
+        {
+            let _ = resolve.call0(&JsValue::UNDEFINED);
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+// Keeps the request for the HAR export of the session, under the URL that was actually requested,
+// along with the URL that it was made for when the two differ.
+fn record(
+    request_url: &str,
+    fetched_url: &str,
+    request_headers: RequestHeaders<'_>,
+    response: Option<&ResponseHeaders>,
+    timing: FetchTiming,
+    body: Option<&[u8]>,
+    error: Option<&FetchError>,
+) {
+    let time_origin = web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.time_origin())
+        .unwrap_or_default();
+    let started = Date::new(&JsValue::from_f64(time_origin + timing.start));
+    record_request(RecordedRequest {
+        started_date_time: String::from(started.to_iso_string()),
+        method: request_headers.method(),
+        url: fetched_url.to_string(),
+        original_url: (request_url != fetched_url).then(|| request_url.to_string()),
+        request_headers: request_headers
+            .entries()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+        response: response.cloned(),
+        timing,
+        error: error.map(FetchError::error),
+        body: body.map(<[u8]>::to_vec),
+    });
+}
+
+fn record_failure(
+    request_url: &str,
+    fetched_url: &str,
+    request_headers: RequestHeaders<'_>,
+    start: f64,
+    error: &FetchError,
+) {
+    let end = now();
+    let timing = FetchTiming {
+        start,
+        first_byte: end,
+        end,
+        ..FetchTiming::default()
+    };
+    record(
+        request_url,
+        fetched_url,
+        request_headers,
+        None,
+        timing,
+        None,
+        Some(error),
+    );
+}
+
+fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or_default()
+}
+
+// Resource Timing entries are named by the URL that was requested, before any redirects.
+fn timing_of(fetched_url: &str, measured: FetchTiming) -> FetchTiming {
+    let entry = web_sys::window()
+        .and_then(|window| window.performance())
+        .and_then(|performance| {
+            performance
+                .get_entries_by_name(fetched_url)
+                .iter()
+                .rev()
+                .find_map(|entry| entry.dyn_into::<PerformanceResourceTiming>().ok())
+        })
+        .map(|entry| ResourceTimingEntry {
+            start_time: entry.start_time(),
+            response_start: entry.response_start(),
+            response_end: entry.response_end(),
+            encoded_body_size: entry.encoded_body_size(),
+        });
+    measured.refine(entry)
+}
+
+// A request that cannot be created (e.g. because the URL is malformed) would fail again if retried.
+fn invalid_request(e: JsValue) -> FetchError {
+    let message = match e.dyn_ref::<TypeError>() {
+        Some(e) => String::from(e.to_string()),
+        None => format!("{e:?}"),
+    };
+    FetchError::Other {
+        message: format!("Error: invalid request: {message}"),
+    }
+}
+
 fn fetch_failed(e: JsValue) -> FetchError {
     match e.dyn_into::<TypeError>() {
-        Ok(e) => FetchError {
-            error: String::from(e.to_string()),
-            extra_info: None,
+        Ok(e) => FetchError::Network {
+            message: String::from(e.to_string()),
         },
         Err(e) => match e.dyn_into::<DomException>() {
-            Ok(e) => FetchError {
-                error: String::from(e.to_string()),
-                extra_info: None,
+            // The only requests that are aborted are those that passed their deadline.
+            Ok(e) if e.name() == "TimeoutError" || e.name() == "AbortError" => FetchError::Timeout,
+            Ok(e) => FetchError::Network {
+                message: String::from(e.to_string()),
             },
-            Err(e) => FetchError {
-                error: format!("Fetch failed: {e:?}"),
-                extra_info: None,
+            Err(e) => FetchError::Other {
+                message: format!("Fetch failed: {e:?}"),
             },
         },
     }
@@ -151,43 +1204,183 @@ fn content_type_from(response: &Response) -> Option<String> {
     response.headers().get("Content-Type").ok().flatten()
 }
 
-async fn validate(response: &Response) -> Result<(), FetchError> {
-    if response.ok() || response.status() == 206 {
+async fn validate(response: &Response, conditional: bool) -> Result<(), FetchError> {
+    if response.ok() || response.status() == 206 || (conditional && response.status() == 304) {
         return Ok(());
     }
-    let error = format!(
-        "Bad HTTP status code: {} {}",
-        response.status(),
-        response.status_text()
-    );
-    let Some(content_type) = content_type_from(response) else {
-        return Err(FetchError {
-            error,
-            extra_info: None,
-        });
-    };
-    if content_type.contains("text/plain")
-        || content_type.contains("application/json")
-        || content_type.contains("application/x-www-form-urlencoded")
+    Err(FetchError::HttpStatus {
+        status: response.status(),
+        status_text: response.status_text(),
+        body_excerpt: body_excerpt(response).await,
+    })
+}
+
+async fn body_excerpt(response: &Response) -> Option<String> {
+    let content_type = content_type_from(response)?;
+    if !(content_type.starts_with("text/")
+        || content_type.contains("json")
+        || content_type.contains("xml")
+        || content_type.contains("application/x-www-form-urlencoded"))
     {
-        let Ok(response_text_promise) = response.text() else {
-            return Err(FetchError {
-                error,
-                extra_info: None,
-            });
+        return None;
+    }
+    let text = JsFuture::from(response.text().ok()?)
+        .await
+        .ok()?
+        .as_string()?;
+    Some(excerpt(text, BODY_EXCERPT_MAX_CHARS))
+}
+
+fn excerpt(mut text: String, max_chars: usize) -> String {
+    if let Some((index, _)) = text.char_indices().nth(max_chars) {
+        text.truncate(index);
+        text.push('…');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{proxy::ProxyConfig, settings::Theme};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn transient_errors_are_retried_with_backoff() {
+        assert_eq!(
+            vec![500, 1000, 2000],
+            (0..MAX_RETRIES).map(retry_delay_millis).collect::<Vec<_>>()
+        );
+        let status = |status| FetchError::HttpStatus {
+            status,
+            status_text: String::new(),
+            body_excerpt: None,
         };
-        let Ok(text) = JsFuture::from(response_text_promise).await else {
-            return Err(FetchError {
-                error,
-                extra_info: None,
-            });
+        assert!(status(503).is_transient());
+        assert!(!status(404).is_transient());
+        assert!(
+            FetchError::Proxied {
+                proxied_url: String::from("https://myproxy/?url=a"),
+                error: Box::new(FetchError::Network {
+                    message: String::from("Failed to fetch")
+                }),
+            }
+            .is_transient()
+        );
+        assert!(
+            !FetchError::Proxied {
+                proxied_url: String::from("https://myproxy/?url=a"),
+                error: Box::new(FetchError::Timeout),
+            }
+            .is_transient()
+        );
+        assert!(
+            !FetchError::Cors {
+                message: String::from("Failed to fetch")
+            }
+            .is_transient()
+        );
+        assert_eq!(
+            "Bad HTTP status code: 503 Service Unavailable (failed after 3 retries)",
+            FetchError::Retried {
+                retries: 3,
+                error: Box::new(FetchError::HttpStatus {
+                    status: 503,
+                    status_text: String::from("Service Unavailable"),
+                    body_excerpt: None,
+                }),
+            }
+            .error()
+        );
+    }
+
+    #[test]
+    fn validators_are_read_from_the_headers() {
+        let headers = ResponseHeaders {
+            headers: vec![
+                (String::from("etag"), String::from("\"abc\"")),
+                (
+                    String::from("content-type"),
+                    String::from("application/vnd.apple.mpegurl"),
+                ),
+            ],
+            ..ResponseHeaders::default()
         };
-        let extra_info = text.as_string();
-        Err(FetchError { error, extra_info })
-    } else {
-        Err(FetchError {
-            error,
-            extra_info: None,
-        })
+        assert_eq!(
+            Validators {
+                etag: Some(String::from("\"abc\"")),
+                last_modified: None,
+            },
+            headers.validators()
+        );
+        assert!(ResponseHeaders::default().validators().is_empty());
+    }
+
+    #[test]
+    fn contiguous_byteranges_are_coalesced_and_sliced() {
+        let map = RequestRange::from_length_with_offset(720, 0);
+        let segment = RequestRange::from_length_with_offset(1000, 720);
+        let combined = RequestRange {
+            start: 0,
+            end: 1719,
+        };
+        assert_eq!(Some(combined), map.coalesced_with(segment));
+        assert_eq!(Some(combined), segment.coalesced_with(map));
+        assert_eq!(
+            None,
+            map.coalesced_with(RequestRange::from_length_with_offset(1000, 721))
+        );
+        let body = (0..=255).collect::<Vec<u8>>();
+        let part = RequestRange {
+            start: 110,
+            end: 119,
+        };
+        assert_eq!((10..20).collect::<Vec<u8>>(), slice_body(&body, 100, part));
+        assert_eq!((110..120).collect::<Vec<u8>>(), slice_body(&body, 0, part));
+        assert_eq!(
+            (250..=255).collect::<Vec<u8>>(),
+            slice_body(
+                &body,
+                0,
+                RequestRange {
+                    start: 250,
+                    end: 300
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn body_excerpt_is_truncated_on_a_char_boundary() {
+        assert_eq!("short", excerpt(String::from("short"), 10));
+        assert_eq!("ééé…", excerpt(String::from("éééééé"), 3));
+    }
+
+    #[test]
+    fn requests_that_opt_out_of_the_proxy_are_made_directly() {
+        let settings = Settings {
+            proxy: ProxyConfig {
+                enabled: true,
+                prefix: String::from("https://myproxy/?url="),
+                bypass_hosts: vec![],
+            },
+            request_headers: vec![],
+            fetch_timeout_seconds: 0,
+            segment_cache_megabytes: 0,
+            offline_cache: OfflineCacheMode::Off,
+            theme: Theme::System,
+            live_recheck_seconds: 0,
+        };
+        let url = "https://example.com/key.bin";
+        assert_eq!(
+            Some(String::from(
+                "https://myproxy/?url=https%3A%2F%2Fexample.com%2Fkey.bin"
+            )),
+            RequestOptions::from(&settings).proxied_url(url)
+        );
+        assert_eq!(
+            None,
+            RequestOptions::from(&settings).direct().proxied_url(url)
+        );
     }
 }
@@ -0,0 +1,260 @@
+// Playlists and segments kept in IndexedDB as they are fetched, so that a stream captured during an
+// incident can be looked into again once the origin has moved on (a live playlist having slid past
+// the segments in question, say), even after the page has been reloaded. Responses are kept under
+// the URL and byterange that they were requested with, the latest replacing any kept before it.
+// Nothing is let go until the cache is cleared. The mode is given with each fetch, from the
+// settings that the request is made with.
+
+use crate::utils::{
+    byterange::RequestRange,
+    network::ResponseHeaders,
+    storage::{load_item, save_item},
+};
+use serde_json::{Value, json};
+use std::cell::RefCell;
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use wasm_bindgen_futures::{JsFuture, spawn_local};
+use web_sys::{
+    IdbDatabase, IdbRequest, IdbTransactionMode,
+    js_sys::{Object, Promise, Reflect, Uint8Array},
+};
+
+const OFFLINE_CACHE_KEY: &str = "offline_cache";
+const DATABASE_NAME: &str = "hls-manifest-viewer";
+const DATABASE_VERSION: u32 = 1;
+const STORE_NAME: &str = "responses";
+
+thread_local! {
+    static DATABASE: RefCell<Option<IdbDatabase>> = const { RefCell::new(None) };
+}
+
+/// Whether fetched responses are kept offline, and whether requests are answered from them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OfflineCacheMode {
+    #[default]
+    Off,
+    /// Responses are kept as they are fetched.
+    Keep,
+    /// Requests are answered from the cache when it has a response for them, and are otherwise
+    /// fetched (and the response kept).
+    Serve,
+}
+
+impl OfflineCacheMode {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Keep, Self::Serve];
+
+    /// The name of the mode, as it is saved.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Keep => "keep",
+            Self::Serve => "serve",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+pub fn load_offline_cache_mode() -> OfflineCacheMode {
+    load_item(OFFLINE_CACHE_KEY)
+        .and_then(|name| OfflineCacheMode::from_name(&name))
+        .unwrap_or_default()
+}
+
+pub fn save_offline_cache_mode(mode: OfflineCacheMode) {
+    save_item(OFFLINE_CACHE_KEY, mode.name());
+}
+
+/// The response kept for the request, when requests are being answered from the cache.
+pub async fn cached_response(
+    request_url: &str,
+    byterange: Option<RequestRange>,
+    mode: OfflineCacheMode,
+) -> Option<(Vec<u8>, ResponseHeaders)> {
+    if mode != OfflineCacheMode::Serve {
+        return None;
+    }
+    let key = cache_key(request_url, byterange);
+    let kept = read(&key)
+        .await
+        .inspect_err(|e| log::error!("reading {key} from the offline cache failed due to {e:?}"))
+        .ok()?;
+    if kept.is_undefined() {
+        return None;
+    }
+    let headers = Reflect::get(&kept, &JsValue::from_str("headers"))
+        .ok()?
+        .as_string()
+        .and_then(|headers| headers_from_json(&headers))?;
+    let body = Reflect::get(&kept, &JsValue::from_str("body"))
+        .ok()?
+        .dyn_into::<Uint8Array>()
+        .ok()?
+        .to_vec();
+    Some((body, headers))
+}
+
+/// Keeps a successful response in the cache (in the background), unless the cache is off.
+pub fn keep_response(
+    request_url: &str,
+    byterange: Option<RequestRange>,
+    headers: &ResponseHeaders,
+    body: &[u8],
+    mode: OfflineCacheMode,
+) {
+    if mode == OfflineCacheMode::Off || !(200..300).contains(&headers.status) {
+        return;
+    }
+    let key = cache_key(request_url, byterange);
+    let value = Object::new();
+    let set = |name: &str, value_of: &JsValue| {
+        let _ = Reflect::set(&value, &JsValue::from_str(name), value_of);
+    };
+    set("headers", &JsValue::from_str(&headers_to_json(headers)));
+    set("body", &Uint8Array::from(body));
+    spawn_local(async move {
+        if let Err(e) = write(&key, &value).await {
+            log::error!("keeping {key} in the offline cache failed due to {e:?}");
+        }
+    });
+}
+
+/// How many responses are kept.
+pub async fn offline_cache_count() -> Result<u32, JsValue> {
+    let database = database().await?;
+    let store = database
+        .transaction_with_str(STORE_NAME)?
+        .object_store(STORE_NAME)?;
+    let count = completion(&store.count()?).await?;
+    Ok(count.as_f64().unwrap_or_default() as u32)
+}
+
+pub async fn clear_offline_cache() -> Result<(), JsValue> {
+    let database = database().await?;
+    let store = database
+        .transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)?
+        .object_store(STORE_NAME)?;
+    completion(&store.clear()?).await?;
+    Ok(())
+}
+
+async fn read(key: &str) -> Result<JsValue, JsValue> {
+    let database = database().await?;
+    let store = database
+        .transaction_with_str(STORE_NAME)?
+        .object_store(STORE_NAME)?;
+    completion(&store.get(&JsValue::from_str(key))?).await
+}
+
+async fn write(key: &str, value: &JsValue) -> Result<(), JsValue> {
+    let database = database().await?;
+    let store = database
+        .transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)?
+        .object_store(STORE_NAME)?;
+    completion(&store.put_with_key(value, &JsValue::from_str(key))?).await?;
+    Ok(())
+}
+
+// The database is opened on first use, and kept open for the rest of the session.
+async fn database() -> Result<IdbDatabase, JsValue> {
+    if let Some(database) = DATABASE.with_borrow(Clone::clone) {
+        return Ok(database);
+    }
+    let factory = web_sys::window()
+        .expect("Window must be defined")
+        .indexed_db()?
+        .ok_or_else(|| JsValue::from_str("IndexedDB is unavailable"))?;
+    let request = factory.open_with_u32(DATABASE_NAME, DATABASE_VERSION)?;
+    let upgrade = {
+        let request = request.clone();
+        Closure::<dyn FnMut()>::new(move || {
+            let created = request.result().and_then(|database| {
+                database
+                    .unchecked_into::<IdbDatabase>()
+                    .create_object_store(STORE_NAME)
+            });
+            if let Err(e) = created {
+                log::error!("creating the offline cache failed due to {e:?}");
+            }
+        })
+    };
+    request.set_onupgradeneeded(Some(upgrade.as_ref().unchecked_ref()));
+    let database = completion(&request).await?.unchecked_into::<IdbDatabase>();
+    request.set_onupgradeneeded(None);
+    DATABASE.set(Some(database.clone()));
+    Ok(database)
+}
+
+// The result of the request, once it has succeeded.
+async fn completion(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    JsFuture::from(promise).await?;
+    request.result()
+}
+
+fn cache_key(request_url: &str, byterange: Option<RequestRange>) -> String {
+    match byterange {
+        Some(byterange) => format!("{request_url} {}", byterange.range_header_value()),
+        None => request_url.to_string(),
+    }
+}
+
+fn headers_to_json(headers: &ResponseHeaders) -> String {
+    json!({
+        "url": headers.url,
+        "status": headers.status,
+        "statusText": headers.status_text,
+        "headers": headers.headers,
+    })
+    .to_string()
+}
+
+fn headers_from_json(json: &str) -> Option<ResponseHeaders> {
+    let value = serde_json::from_str::<Value>(json).ok()?;
+    Some(ResponseHeaders {
+        url: value["url"].as_str()?.to_string(),
+        status: u16::try_from(value["status"].as_u64()?).ok()?,
+        status_text: value["statusText"].as_str().unwrap_or_default().to_string(),
+        headers: serde_json::from_value(value["headers"].clone()).ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn responses_are_kept_by_url_and_range() {
+        assert_eq!(
+            "https://example.com/1.m4s bytes=0-99",
+            cache_key(
+                "https://example.com/1.m4s",
+                Some(RequestRange { start: 0, end: 99 })
+            )
+        );
+        assert_eq!(
+            "https://example.com/1.m4s",
+            cache_key("https://example.com/1.m4s", None)
+        );
+    }
+
+    #[test]
+    fn headers_are_kept_as_json() {
+        let headers = ResponseHeaders {
+            url: String::from("https://example.com/live.m3u8"),
+            status: 200,
+            status_text: String::from("OK"),
+            headers: vec![(String::from("etag"), String::from("\"1\""))],
+        };
+        assert_eq!(
+            Some(headers.clone()),
+            headers_from_json(&headers_to_json(&headers))
+        );
+    }
+}
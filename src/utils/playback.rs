@@ -0,0 +1,138 @@
+// Playback of segments within the viewer, without a player. fMP4 segments are appended (after their
+// initialization segment) to a MediaSource attached to a media element, while packed audio is
+// decoded with Web Audio, as Media Source Extensions do not take raw elementary streams.
+
+use crate::utils::audio::peak_dbfs;
+use std::future::Future;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    AddEventListenerOptions, AudioBuffer, AudioContext, DomException, EventTarget,
+    HtmlMediaElement, MediaSource, MediaSourceReadyState, SourceBuffer, Url,
+    js_sys::{Promise, Uint8Array},
+};
+
+/// A segment attached to a media element through a MediaSource. The object URL of the MediaSource
+/// is revoked when this is dropped.
+pub struct MediaSourcePlayback {
+    object_url: String,
+}
+impl Drop for MediaSourcePlayback {
+    fn drop(&mut self) {
+        let _ = Url::revoke_object_url(&self.object_url);
+    }
+}
+
+/// Attaches the segment, after its initialization segment, to the media element, ready to play from
+/// its first buffered sample.
+pub async fn attach_segment(
+    element: &HtmlMediaElement,
+    mime_type: &str,
+    init_data: &[u8],
+    data: &[u8],
+) -> Result<MediaSourcePlayback, String> {
+    if !MediaSource::is_type_supported(mime_type) {
+        return Err(format!(
+            "This browser cannot play {mime_type} through Media Source Extensions."
+        ));
+    }
+    let media_source = MediaSource::new().map_err(js_error)?;
+    let playback = MediaSourcePlayback {
+        object_url: Url::create_object_url_with_source(&media_source).map_err(js_error)?,
+    };
+    let opened = next_event(&media_source, "sourceopen");
+    element.set_src(&playback.object_url);
+    opened.await?;
+    let source_buffer = media_source
+        .add_source_buffer(mime_type)
+        .map_err(js_error)?;
+    append(&media_source, &source_buffer, init_data).await?;
+    append(&media_source, &source_buffer, data).await?;
+    media_source.end_of_stream().map_err(js_error)?;
+    // A media segment keeps its place on the timeline of the stream, so it is buffered from its
+    // decode time rather than from 0.
+    let buffered = source_buffer.buffered().map_err(js_error)?;
+    if buffered.length() > 0 {
+        element.set_current_time(buffered.start(0).map_err(js_error)?);
+    }
+    Ok(playback)
+}
+
+async fn append(
+    media_source: &MediaSource,
+    source_buffer: &SourceBuffer,
+    data: &[u8],
+) -> Result<(), String> {
+    let updated = next_event(source_buffer, "updateend");
+    source_buffer
+        .append_buffer_with_array_buffer_view(&Uint8Array::from(data))
+        .map_err(js_error)?;
+    updated.await?;
+    // A segment that fails to parse ends the stream with a decode error.
+    if media_source.ready_state() == MediaSourceReadyState::Ended {
+        return Err(String::from(
+            "The browser could not parse the segment for playback.",
+        ));
+    }
+    Ok(())
+}
+
+/// What was decoded from a packed audio segment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedAudio {
+    pub duration: f64,
+    pub channels: u32,
+    pub sample_rate: f32,
+    /// The peak level across all channels, or `None` when the segment is silent.
+    pub peak_dbfs: Option<f64>,
+}
+
+/// Decodes the audio and starts playing it. Playback stops when the returned context is closed.
+pub async fn play_audio(data: &[u8]) -> Result<(AudioContext, DecodedAudio), String> {
+    let context = AudioContext::new().map_err(js_error)?;
+    let decoding = context
+        .decode_audio_data(&Uint8Array::from(data).buffer())
+        .map_err(js_error)?;
+    let buffer: AudioBuffer = JsFuture::from(decoding)
+        .await
+        .map_err(|_| String::from("The browser could not decode the audio of the segment."))?
+        .unchecked_into();
+    let decoded = DecodedAudio {
+        duration: buffer.duration(),
+        channels: buffer.number_of_channels(),
+        sample_rate: buffer.sample_rate(),
+        peak_dbfs: peak_dbfs(
+            (0..buffer.number_of_channels())
+                .filter_map(|channel| buffer.get_channel_data(channel).ok())
+                .flatten(),
+        ),
+    };
+    let source = context.create_buffer_source().map_err(js_error)?;
+    source.set_buffer(Some(&buffer));
+    source
+        .connect_with_audio_node(&context.destination())
+        .map_err(js_error)?;
+    source.start().map_err(js_error)?;
+    Ok((context, decoded))
+}
+
+// Resolves on the next time that the event is dispatched to the target. The listener is added
+// straight away, so that an event dispatched before the future is awaited is not missed.
+fn next_event(target: &EventTarget, event: &str) -> impl Future<Output = Result<(), String>> {
+    let promise = Promise::new(&mut |resolve, _| {
+        let options = AddEventListenerOptions::new();
+        options.set_once(true);
+        let _ = target.add_event_listener_with_callback_and_add_event_listener_options(
+            event, &resolve, &options,
+        );
+    });
+    async move { JsFuture::from(promise).await.map(|_| ()).map_err(js_error) }
+}
+
+/// The message of a JS error (which is usually a DOMException) to show to the user.
+pub fn js_error(error: JsValue) -> String {
+    match error.dyn_ref::<DomException>() {
+        Some(exception) => exception.message(),
+        None => format!("{error:?}"),
+    }
+}
@@ -0,0 +1,399 @@
+use crate::utils::playlist_lines::{LineKind, PlaylistLine, parse};
+
+// Lines are aligned by their longest common subsequence, which needs a table of the lengths for
+// every pair of lines that differ between the common start and end of the playlists. Beyond this
+// many pairs the table gets too large to build in the browser, so the remaining lines are instead
+// paired up in order, which still suits playlists that differ only in their URIs (e.g. two CDNs).
+const MAX_ALIGNED_PAIRS: usize = 16_000_000;
+
+/// A line of one of the playlists being compared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffLine<'a> {
+    /// The 1-based line number.
+    pub number: usize,
+    pub text: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffRow<'a> {
+    Same(DiffLine<'a>, DiffLine<'a>),
+    /// A line that is only in the left playlist.
+    Removed(DiffLine<'a>),
+    /// A line that is only in the right playlist.
+    Added(DiffLine<'a>),
+    /// Lines in the same place of both playlists that differ.
+    Changed {
+        left: DiffLine<'a>,
+        right: DiffLine<'a>,
+        /// The names of the attributes that differ, when both lines are the same tag with an
+        /// attribute list, and otherwise empty (in which case the whole line differs).
+        attributes: Vec<&'a str>,
+    },
+}
+
+/// Aligns the lines of the `left` and `right` playlists.
+pub fn diff<'a>(left: &'a str, right: &'a str) -> Vec<DiffRow<'a>> {
+    let left = parse(left)
+        .into_iter()
+        .zip(left.lines())
+        .map(|(line, text)| (line, text.trim_end()))
+        .collect::<Vec<_>>();
+    let right = parse(right)
+        .into_iter()
+        .zip(right.lines())
+        .map(|(line, text)| (line, text.trim_end()))
+        .collect::<Vec<_>>();
+    let prefix = left
+        .iter()
+        .zip(&right)
+        .take_while(|((_, l), (_, r))| l == r)
+        .count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|((_, l), (_, r))| l == r)
+        .count();
+    let line = |(line, text): &(PlaylistLine<'a>, &'a str)| DiffLine {
+        number: line.number,
+        text,
+    };
+    let mut rows = left[..prefix]
+        .iter()
+        .zip(&right[..prefix])
+        .map(|(l, r)| DiffRow::Same(line(l), line(r)))
+        .collect::<Vec<_>>();
+    let left_middle = &left[prefix..left.len() - suffix];
+    let right_middle = &right[prefix..right.len() - suffix];
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for (l, r) in align(left_middle, right_middle) {
+        match (l, r) {
+            (Some(l), Some(r)) => {
+                pair_changes(&mut rows, &mut removed, &mut added);
+                rows.push(DiffRow::Same(line(l), line(r)));
+            }
+            (Some(l), None) => removed.push(l),
+            (None, Some(r)) => added.push(r),
+            (None, None) => (),
+        }
+    }
+    pair_changes(&mut rows, &mut removed, &mut added);
+    rows.extend(
+        left[left.len() - suffix..]
+            .iter()
+            .zip(&right[right.len() - suffix..])
+            .map(|(l, r)| DiffRow::Same(line(l), line(r))),
+    );
+    rows
+}
+
+/// How the lines of a playlist moved when it was reloaded, so that the reloaded playlist can be
+/// shown from where the previous one was being read, with what changed in it marked.
+#[derive(Debug, PartialEq)]
+pub struct LineUpdate {
+    /// The 1-based numbers of the lines of the reloaded playlist that are new or changed.
+    pub updated: Vec<usize>,
+    // The 1-based line of the reloaded playlist for each line of the previous one, where a removed
+    // line is given the line that followed it.
+    positions: Vec<usize>,
+    // The 1-based line of the previous playlist for each line of the reloaded one that is the same
+    // as it or changed from it, where a new line has none.
+    sources: Vec<Option<usize>>,
+}
+
+impl LineUpdate {
+    pub fn new(previous: &str, current: &str) -> Self {
+        let rows = diff(previous, current);
+        let mut updated = Vec::new();
+        let mut positions = Vec::new();
+        let mut sources = Vec::new();
+        let mut removed = 0;
+        for row in &rows {
+            let current = match row {
+                DiffRow::Same(left, right) => {
+                    sources.push(Some(left.number));
+                    right.number
+                }
+                DiffRow::Changed { left, right, .. } => {
+                    updated.push(right.number);
+                    sources.push(Some(left.number));
+                    right.number
+                }
+                DiffRow::Added(right) => {
+                    updated.push(right.number);
+                    sources.push(None);
+                    positions.extend(std::iter::repeat_n(right.number, removed));
+                    removed = 0;
+                    continue;
+                }
+                DiffRow::Removed(_) => {
+                    removed += 1;
+                    continue;
+                }
+            };
+            positions.extend(std::iter::repeat_n(current, removed + 1));
+            removed = 0;
+        }
+        let last = current.lines().count().max(1);
+        positions.extend(std::iter::repeat_n(last, removed));
+        Self {
+            updated,
+            positions,
+            sources,
+        }
+    }
+
+    /// The 1-based line of the reloaded playlist that stands in place of the 1-based `line_number`
+    /// of the previous one.
+    pub fn position(&self, line_number: usize) -> Option<usize> {
+        self.positions.get(line_number.checked_sub(1)?).copied()
+    }
+
+    /// The 1-based line of the previous playlist that the 1-based `line_number` of the reloaded one
+    /// is the same as or changed from, if it is not a new line.
+    pub fn source(&self, line_number: usize) -> Option<usize> {
+        self.sources
+            .get(line_number.checked_sub(1)?)
+            .copied()
+            .flatten()
+    }
+}
+
+type Line<'a, 'b> = &'b (PlaylistLine<'a>, &'a str);
+
+// Aligns the lines as pairs of equal lines, or lines that are only on one side.
+fn align<'a, 'b>(
+    left: &'b [(PlaylistLine<'a>, &'a str)],
+    right: &'b [(PlaylistLine<'a>, &'a str)],
+) -> Vec<(Option<Line<'a, 'b>>, Option<Line<'a, 'b>>)> {
+    let (n, m) = (left.len(), right.len());
+    if n.saturating_mul(m) > MAX_ALIGNED_PAIRS {
+        // Keeping differing lines apart lets them be paired as changes afterwards.
+        return (0..n.max(m))
+            .flat_map(|index| match (left.get(index), right.get(index)) {
+                (Some(l), Some(r)) if l.1 != r.1 => vec![(Some(l), None), (None, Some(r))],
+                pair => vec![pair],
+            })
+            .collect();
+    }
+    // The LCS is at most the shorter side, which the limit above keeps within a u16.
+    let mut lengths = vec![0u16; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[at(i, j)] = if left[i].1 == right[j].1 {
+                lengths[at(i + 1, j + 1)] + 1
+            } else {
+                lengths[at(i + 1, j)].max(lengths[at(i, j + 1)])
+            };
+        }
+    }
+    let mut aligned = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if left[i].1 == right[j].1 {
+            aligned.push((Some(&left[i]), Some(&right[j])));
+            i += 1;
+            j += 1;
+        } else if lengths[at(i + 1, j)] >= lengths[at(i, j + 1)] {
+            aligned.push((Some(&left[i]), None));
+            i += 1;
+        } else {
+            aligned.push((None, Some(&right[j])));
+            j += 1;
+        }
+    }
+    aligned.extend(left[i..].iter().map(|l| (Some(l), None)));
+    aligned.extend(right[j..].iter().map(|r| (None, Some(r))));
+    aligned
+}
+
+// Lines removed and added between the same two equal lines are paired up in order as changes, and
+// whatever is left over on either side stays as it is.
+fn pair_changes<'a>(
+    rows: &mut Vec<DiffRow<'a>>,
+    removed: &mut Vec<Line<'a, '_>>,
+    added: &mut Vec<Line<'a, '_>>,
+) {
+    let line = |(line, text): Line<'a, '_>| DiffLine {
+        number: line.number,
+        text,
+    };
+    let paired = removed.len().min(added.len());
+    for (l, r) in removed.iter().zip(added.iter()) {
+        rows.push(DiffRow::Changed {
+            left: line(l),
+            right: line(r),
+            attributes: changed_attributes(&l.0, &r.0),
+        });
+    }
+    rows.extend(
+        removed
+            .drain(..)
+            .skip(paired)
+            .map(|l| DiffRow::Removed(line(l))),
+    );
+    rows.extend(
+        added
+            .drain(..)
+            .skip(paired)
+            .map(|r| DiffRow::Added(line(r))),
+    );
+}
+
+fn changed_attributes<'a>(left: &PlaylistLine<'a>, right: &PlaylistLine<'a>) -> Vec<&'a str> {
+    let (LineKind::Tag(left), LineKind::Tag(right)) = (&left.kind, &right.kind) else {
+        return Vec::new();
+    };
+    if left.name != right.name {
+        return Vec::new();
+    }
+    let left = left
+        .attributes()
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    let right = right
+        .attributes()
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    if left.is_empty() || right.is_empty() {
+        return Vec::new();
+    }
+    let mut changed = Vec::new();
+    for (name, value) in &left {
+        if !right.contains(&(*name, *value)) {
+            changed.push(*name);
+        }
+    }
+    for (name, _) in &right {
+        if !left.iter().any(|(n, _)| n == name) {
+            changed.push(*name);
+        }
+    }
+    changed
+}
+
+/// Splits the text of a tag line into its parts, marking the attributes (name and value) named in
+/// `attributes` so that they can be highlighted.
+pub fn attribute_spans<'a>(text: &'a str, attributes: &[&str]) -> Vec<(&'a str, bool)> {
+    let Some((name, value)) = text.split_once(':') else {
+        return vec![(text, false)];
+    };
+    let mut spans = vec![(&text[..name.len() + 1], false)];
+    let mut push_entry = |entry: &'a str| {
+        let changed = entry
+            .split_once('=')
+            .is_some_and(|(name, _)| attributes.contains(&name));
+        spans.push((entry, changed));
+    };
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (index, c) in value.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                push_entry(&value[start..index]);
+                push_entry(&value[index..index + 1]);
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    push_entry(&value[start..]);
+    spans.retain(|(span, _)| !span.is_empty());
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn line(number: usize, text: &str) -> DiffLine<'_> {
+        DiffLine { number, text }
+    }
+
+    #[test]
+    fn diff_aligns_inserted_and_removed_lines() {
+        let left = "#EXTM3U\n#EXTINF:6,\na.ts\n#EXTINF:6,\nb.ts\n";
+        let right = "#EXTM3U\n#EXTINF:6,\nb.ts\n#EXTINF:6,\nc.ts\n";
+        assert_eq!(
+            vec![
+                DiffRow::Same(line(1, "#EXTM3U"), line(1, "#EXTM3U")),
+                DiffRow::Same(line(2, "#EXTINF:6,"), line(2, "#EXTINF:6,")),
+                DiffRow::Removed(line(3, "a.ts")),
+                DiffRow::Removed(line(4, "#EXTINF:6,")),
+                DiffRow::Same(line(5, "b.ts"), line(3, "b.ts")),
+                DiffRow::Added(line(4, "#EXTINF:6,")),
+                DiffRow::Added(line(5, "c.ts")),
+            ],
+            diff(left, right)
+        );
+    }
+
+    #[test]
+    fn diff_pairs_changed_lines_with_the_attributes_that_differ() {
+        let left = "#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"a.key\",IV=0x1\nhttps://a.com/0.ts\n";
+        let right = "#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"b.key\",IV=0x1\nhttps://b.com/0.ts\n";
+        assert_eq!(
+            vec![
+                DiffRow::Same(line(1, "#EXTM3U"), line(1, "#EXTM3U")),
+                DiffRow::Changed {
+                    left: line(2, "#EXT-X-KEY:METHOD=AES-128,URI=\"a.key\",IV=0x1"),
+                    right: line(2, "#EXT-X-KEY:METHOD=AES-128,URI=\"b.key\",IV=0x1"),
+                    attributes: vec!["URI"],
+                },
+                DiffRow::Changed {
+                    left: line(3, "https://a.com/0.ts"),
+                    right: line(3, "https://b.com/0.ts"),
+                    attributes: vec![],
+                },
+            ],
+            diff(left, right)
+        );
+    }
+
+    #[test]
+    fn line_update_follows_lines_to_where_they_moved() {
+        let previous = "#EXTM3U\n#EXTINF:6,\na.ts\n#EXTINF:6,\nb.ts\n";
+        let current = "#EXTM3U\n#EXTINF:6,\nb.ts\n#EXTINF:4,\nc.ts\n";
+        let update = LineUpdate::new(previous, current);
+        assert_eq!(vec![4, 5], update.updated);
+        assert_eq!(
+            vec![Some(1), Some(2), Some(3), Some(3), Some(3), None],
+            (1..=6)
+                .map(|line| update.position(line))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn line_update_traces_lines_back_to_the_previous_playlist() {
+        let previous = "#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"a.key\"\n#EXTINF:6,\na.ts\n";
+        let current = "#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"b.key\"\n#EXTINF:6,\na.ts\n\
+                       #EXTINF:6,\nb.ts\n";
+        let update = LineUpdate::new(previous, current);
+        assert_eq!(vec![2, 5, 6], update.updated);
+        assert_eq!(
+            vec![Some(1), Some(2), Some(3), Some(4), None, None],
+            (1..=6).map(|line| update.source(line)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn attribute_spans_marks_the_named_attributes() {
+        assert_eq!(
+            vec![
+                ("#EXT-X-KEY:", false),
+                ("METHOD=AES-128", false),
+                (",", false),
+                ("URI=\"a,b.key\"", true),
+            ],
+            attribute_spans("#EXT-X-KEY:METHOD=AES-128,URI=\"a,b.key\"", &["URI"])
+        );
+        assert_eq!(vec![("a.ts", false)], attribute_spans("a.ts", &[]));
+    }
+}
@@ -0,0 +1,341 @@
+// The playlist as a structured JSON document, for scripts and CI checks to consume without parsing
+// the playlist themselves. Every tag is listed with its value (or its attributes) typed as the
+// specification defines it, and the Media Segments and Variant Streams are listed with everything
+// that applies to them worked out from the tags before them: sequence numbers, start times, byte
+// ranges, keys and maps.
+//
+// Like the rest of the analysis, this works from the lenient `playlist_lines` model, so anything
+// that cannot be typed is kept as the text it was written as.
+
+use crate::utils::{
+    playlist_lines::{AttributeValue, LineKind, TagLine, parse},
+    validation::{AttributeType, attribute_type},
+};
+use serde_json::{Map, Number, Value, json};
+
+pub const JSON_MIME_TYPE: &str = "application/json";
+
+/// The playlist as a pretty printed JSON document.
+pub fn playlist_json(playlist: &str) -> String {
+    serde_json::to_string_pretty(&to_json(playlist)).unwrap_or_default()
+}
+
+fn to_json(playlist: &str) -> Value {
+    let mut tags = Vec::new();
+    let mut segments = Vec::new();
+    let mut variants = Vec::new();
+    let mut state = SegmentState::default();
+    // The line and attributes of the EXT-X-STREAM-INF that the next URI is the stream of.
+    let mut stream_inf = None;
+    for line in parse(playlist) {
+        match &line.kind {
+            LineKind::Tag(tag) => {
+                tags.push(tag_json(line.number, tag));
+                if tag.name == "EXT-X-STREAM-INF" {
+                    stream_inf = Some((line.number, attributes_json(tag)));
+                } else {
+                    state.apply(tag);
+                }
+            }
+            LineKind::Uri(uri) => {
+                if let Some((tag_line, attributes)) = stream_inf.take() {
+                    variants.push(json!({
+                        "line": line.number,
+                        "tagLine": tag_line,
+                        "uri": uri,
+                        "attributes": attributes,
+                    }));
+                } else if let Some(segment) = state.segment(line.number, uri) {
+                    segments.push(segment);
+                }
+            }
+            LineKind::Comment(_) | LineKind::Blank => (),
+        }
+    }
+    json!({
+        "duration": state.start,
+        "tags": tags,
+        "segments": segments,
+        "variants": variants,
+    })
+}
+
+// What applies to the next Media Segment, from the tags read so far.
+#[derive(Default)]
+struct SegmentState {
+    media_sequence: u64,
+    discontinuity_sequence: u64,
+    /// The start of the next segment in seconds from the start of the playlist.
+    start: f64,
+    /// The duration and title of the next segment.
+    extinf: Option<(f64, String)>,
+    discontinuity: bool,
+    gap: bool,
+    /// The length and offset (if given) of the EXT-X-BYTERANGE of the next segment.
+    byterange: Option<(u64, Option<u64>)>,
+    /// The URI and end of the last byte range, which a byte range without an offset follows.
+    byterange_end: Option<(String, u64)>,
+    program_date_time: Option<String>,
+    keys: Vec<Value>,
+    map: Option<Value>,
+    has_segments: bool,
+}
+
+impl SegmentState {
+    fn apply(&mut self, tag: &TagLine) {
+        let value = tag.value.unwrap_or_default().trim();
+        match tag.name {
+            "EXTINF" => {
+                let (duration, title) = value.split_once(',').unwrap_or((value, ""));
+                let duration = duration.trim().parse().unwrap_or_default();
+                self.extinf = Some((duration, title.to_string()));
+            }
+            "EXT-X-MEDIA-SEQUENCE" => self.media_sequence = value.parse().unwrap_or_default(),
+            "EXT-X-DISCONTINUITY-SEQUENCE" => {
+                self.discontinuity_sequence = value.parse().unwrap_or_default();
+            }
+            "EXT-X-DISCONTINUITY" => self.discontinuity = true,
+            "EXT-X-GAP" => self.gap = true,
+            "EXT-X-BYTERANGE" => self.byterange = byterange(value),
+            "EXT-X-PROGRAM-DATE-TIME" => self.program_date_time = Some(value.to_string()),
+            // A key applies until replaced by another of the same KEYFORMAT, and METHOD=NONE
+            // removes them all.
+            "EXT-X-KEY" => {
+                let keyformat = tag.attribute_str("KEYFORMAT").unwrap_or("identity");
+                self.keys
+                    .retain(|key| key["keyformat"].as_str() != Some(keyformat));
+                match tag.attribute_str("METHOD") {
+                    Some("NONE") => self.keys.clear(),
+                    method => self.keys.push(json!({
+                        "method": method,
+                        "uri": tag.attribute_str("URI"),
+                        "iv": tag.attribute_str("IV"),
+                        "keyformat": keyformat,
+                    })),
+                }
+            }
+            "EXT-X-MAP" => {
+                self.map = tag.map().map(|(uri, byterange)| {
+                    let byterange =
+                        byterange.map(|(length, offset)| byterange_json(length, offset));
+                    json!({ "uri": uri, "byterange": byterange })
+                });
+            }
+            _ => (),
+        }
+    }
+
+    // The URI of a Media Segment follows its EXTINF, so a URI without one is not a segment.
+    fn segment(&mut self, line: usize, uri: &str) -> Option<Value> {
+        let (duration, title) = self.extinf.take()?;
+        if self.discontinuity && self.has_segments {
+            self.discontinuity_sequence += 1;
+        }
+        let byterange = self.byterange.take().map(|(length, offset)| {
+            // Without an offset, the range starts where the last one of the same resource ended.
+            let offset = offset.unwrap_or_else(|| match &self.byterange_end {
+                Some((previous, end)) if previous == uri => *end,
+                _ => 0,
+            });
+            self.byterange_end = Some((uri.to_string(), offset + length));
+            byterange_json(length, offset)
+        });
+        let segment = json!({
+            "line": line,
+            "uri": uri,
+            "mediaSequence": self.media_sequence,
+            "discontinuitySequence": self.discontinuity_sequence,
+            "start": self.start,
+            "duration": duration,
+            "title": title,
+            "discontinuity": std::mem::take(&mut self.discontinuity),
+            "gap": std::mem::take(&mut self.gap),
+            "byterange": byterange,
+            "programDateTime": self.program_date_time.take(),
+            "keys": self.keys,
+            "map": self.map,
+        });
+        self.media_sequence += 1;
+        self.start += duration;
+        self.has_segments = true;
+        Some(segment)
+    }
+}
+
+fn tag_json(line: usize, tag: &TagLine) -> Value {
+    let mut json = json!({ "line": line, "name": tag.name });
+    let Some(value) = tag.value else {
+        return json;
+    };
+    let value = value.trim();
+    match tag.name {
+        "EXTINF" => {
+            let (duration, title) = value.split_once(',').unwrap_or((value, ""));
+            json["value"] = json!({ "duration": number(duration.trim()), "title": title });
+        }
+        "EXT-X-TARGETDURATION"
+        | "EXT-X-MEDIA-SEQUENCE"
+        | "EXT-X-DISCONTINUITY-SEQUENCE"
+        | "EXT-X-VERSION"
+        | "EXT-X-BITRATE" => json["value"] = integer(value),
+        "EXT-X-BYTERANGE" => {
+            json["value"] = match byterange(value) {
+                Some((length, offset)) => json!({ "length": length, "offset": offset }),
+                None => Value::from(value),
+            };
+        }
+        "EXT-X-PROGRAM-DATE-TIME" | "EXT-X-PLAYLIST-TYPE" => json["value"] = Value::from(value),
+        // Anything else with a value is taken to be an attribute-list, unless it is not one.
+        _ if tag.attributes().iter().all(Result::is_ok) => {
+            json["attributes"] = Value::Object(attributes_json(tag));
+        }
+        _ => json["value"] = Value::from(value),
+    }
+    json
+}
+
+fn attributes_json(tag: &TagLine) -> Map<String, Value> {
+    tag.attributes()
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|(name, value)| (name.to_string(), attribute_json(tag.name, name, value)))
+        .collect()
+}
+
+fn attribute_json(tag: &str, name: &str, value: AttributeValue) -> Value {
+    let text = value.as_str();
+    match attribute_type(tag, name) {
+        Some(AttributeType::DecimalInteger) => integer(text),
+        Some(AttributeType::DecimalFloatingPoint | AttributeType::SignedDecimalFloatingPoint) => {
+            number(text)
+        }
+        Some(AttributeType::DecimalResolution) => match text.split_once('x') {
+            Some((width, height)) => match (width.parse::<u64>(), height.parse::<u64>()) {
+                (Ok(width), Ok(height)) => json!({ "width": width, "height": height }),
+                _ => Value::from(text),
+            },
+            None => Value::from(text),
+        },
+        _ => Value::from(text),
+    }
+}
+
+// The value as a JSON number, or as the text it was written as when it is not a valid one.
+fn integer(text: &str) -> Value {
+    text.parse::<u64>()
+        .map(Value::from)
+        .unwrap_or_else(|_| Value::from(text))
+}
+
+fn number(text: &str) -> Value {
+    text.parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::from(text))
+}
+
+// An EXT-X-BYTERANGE value, as its length and offset (if given).
+fn byterange(value: &str) -> Option<(u64, Option<u64>)> {
+    match value.split_once('@') {
+        Some((length, offset)) => Some((length.parse().ok()?, Some(offset.parse().ok()?))),
+        None => Some((value.parse().ok()?, None)),
+    }
+}
+
+fn byterange_json(length: u64, offset: u64) -> Value {
+    json!({ "length": length, "offset": offset })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn segments_have_what_applies_to_them_worked_out() {
+        let playlist = "#EXTM3U\n\
+            #EXT-X-TARGETDURATION:6\n\
+            #EXT-X-MEDIA-SEQUENCE:10\n\
+            #EXT-X-KEY:METHOD=AES-128,URI=\"a.key\"\n\
+            #EXTINF:6,first\n\
+            #EXT-X-BYTERANGE:100@0\n\
+            main.ts\n\
+            #EXT-X-DISCONTINUITY\n\
+            #EXT-X-KEY:METHOD=NONE\n\
+            #EXTINF:4.5,\n\
+            #EXT-X-BYTERANGE:50\n\
+            main.ts\n";
+        let json = to_json(playlist);
+        assert_eq!(json!(10.5), json["duration"]);
+        assert_eq!(
+            json!({ "line": 2, "name": "EXT-X-TARGETDURATION", "value": 6 }),
+            json["tags"][1]
+        );
+        assert_eq!(
+            json!({
+                "line": 4,
+                "name": "EXT-X-KEY",
+                "attributes": { "METHOD": "AES-128", "URI": "a.key" },
+            }),
+            json["tags"][3]
+        );
+        assert_eq!(
+            json!({
+                "line": 7,
+                "uri": "main.ts",
+                "mediaSequence": 10,
+                "discontinuitySequence": 0,
+                "start": 0.0,
+                "duration": 6.0,
+                "title": "first",
+                "discontinuity": false,
+                "gap": false,
+                "byterange": { "length": 100, "offset": 0 },
+                "programDateTime": null,
+                "keys": [{
+                    "method": "AES-128",
+                    "uri": "a.key",
+                    "iv": null,
+                    "keyformat": "identity",
+                }],
+                "map": null,
+            }),
+            json["segments"][0]
+        );
+        let second = &json["segments"][1];
+        assert_eq!(json!(11), second["mediaSequence"]);
+        assert_eq!(json!(1), second["discontinuitySequence"]);
+        assert_eq!(json!(6.0), second["start"]);
+        assert_eq!(json!({ "length": 50, "offset": 100 }), second["byterange"]);
+        assert_eq!(json!([]), second["keys"]);
+    }
+
+    #[test]
+    fn variants_have_typed_attributes() {
+        let playlist = "#EXTM3U\n\
+            #EXT-X-STREAM-INF:BANDWIDTH=1280000,RESOLUTION=1280x720,FRAME-RATE=29.97,\
+            CODECS=\"avc1.4d401f\"\n\
+            720p.m3u8\n\
+            #EXT-X-CUE-OUT:30\n";
+        let json = to_json(playlist);
+        assert_eq!(
+            json!([{
+                "line": 3,
+                "tagLine": 2,
+                "uri": "720p.m3u8",
+                "attributes": {
+                    "BANDWIDTH": 1280000,
+                    "RESOLUTION": { "width": 1280, "height": 720 },
+                    "FRAME-RATE": 29.97,
+                    "CODECS": "avc1.4d401f",
+                },
+            }]),
+            json["variants"]
+        );
+        assert_eq!(
+            json!({ "line": 4, "name": "EXT-X-CUE-OUT", "value": "30" }),
+            json["tags"][2]
+        );
+    }
+}
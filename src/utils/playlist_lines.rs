@@ -0,0 +1,533 @@
+use crate::utils::i18n::{Message, tr};
+use std::collections::HashMap;
+
+// A lenient, line-by-line model of a playlist used for analysis (validation, and what is worked out
+// from the playlist as a whole, such as its segments, variants and categories of line). The
+// quick-m3u8 `Reader` that the viewer renders with cannot serve this:
+// - reading stops at the first line that it fails to parse, while validation has to go on to
+//   report every line that is wrong;
+// - it requires `#EXTM3U` on the first line, rejecting the rest of a playlist that lacks it;
+// - it does not give the number of the line that it read, which every finding links to;
+// - an attribute list with one malformed attribute cannot be read at all, where validation needs
+//   the attributes that are well formed along with the one that is not.
+// So this never rejects input, and breaks malformed attribute lists down as far as possible. Line
+// numbers are 1-based and align with the lines rendered by the playlist viewer.
+//
+// Rendering a line is left to quick-m3u8 alone, so that the view of a line and the analysis of the
+// playlist do not each read the same line their own way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistLine<'a> {
+    pub number: usize,
+    pub kind: LineKind<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineKind<'a> {
+    Tag(TagLine<'a>),
+    Uri(&'a str),
+    Comment(&'a str),
+    Blank,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagLine<'a> {
+    /// The tag name without the leading `#` (e.g. `EXT-X-KEY`).
+    pub name: &'a str,
+    /// Everything after the first `:`, if present.
+    pub value: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttributeValue<'a> {
+    Quoted(&'a str),
+    Unquoted(&'a str),
+}
+
+pub fn parse(playlist: &str) -> Vec<PlaylistLine<'_>> {
+    playlist
+        .lines()
+        .enumerate()
+        .map(|(index, line)| PlaylistLine {
+            number: index + 1,
+            kind: line_kind(line),
+        })
+        .collect()
+}
+
+fn line_kind(line: &str) -> LineKind<'_> {
+    let line = line.trim_end();
+    if line.trim().is_empty() {
+        LineKind::Blank
+    } else if line.starts_with("#EXT") {
+        let tag = &line[1..];
+        match tag.split_once(':') {
+            Some((name, value)) => LineKind::Tag(TagLine {
+                name,
+                value: Some(value),
+            }),
+            None => LineKind::Tag(TagLine {
+                name: tag,
+                value: None,
+            }),
+        }
+    } else if let Some(comment) = line.strip_prefix('#') {
+        LineKind::Comment(comment)
+    } else {
+        LineKind::Uri(line.trim())
+    }
+}
+
+impl<'a> PlaylistLine<'a> {
+    pub fn tag(&self) -> Option<&TagLine<'a>> {
+        match &self.kind {
+            LineKind::Tag(tag) => Some(tag),
+            _ => None,
+        }
+    }
+
+    pub fn uri(&self) -> Option<&'a str> {
+        match self.kind {
+            LineKind::Uri(uri) => Some(uri),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> TagLine<'a> {
+    /// Breaks the tag value down as an attribute-list. Entries that are not of the form
+    /// `NAME=VALUE` are returned as `Err` containing the offending text.
+    pub fn attributes(&self) -> Vec<Result<(&'a str, AttributeValue<'a>), &'a str>> {
+        let Some(value) = self.value else {
+            return Vec::new();
+        };
+        split_attribute_list(value)
+            .into_iter()
+            .map(|entry| {
+                let Some((name, value)) = entry.split_once('=') else {
+                    return Err(entry);
+                };
+                if name.is_empty() {
+                    return Err(entry);
+                }
+                match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                    Some(quoted) => Ok((name, AttributeValue::Quoted(quoted))),
+                    None => Ok((name, AttributeValue::Unquoted(value))),
+                }
+            })
+            .collect()
+    }
+
+    /// The value of the first attribute with the given name, if present and well formed.
+    pub fn attribute(&self, name: &str) -> Option<AttributeValue<'a>> {
+        self.attributes()
+            .into_iter()
+            .filter_map(Result::ok)
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| value)
+    }
+
+    /// The URI of an EXT-X-MAP, along with its byte range as (length, offset) when one is set.
+    pub fn map(&self) -> Option<(String, Option<(u64, u64)>)> {
+        let uri = self.attribute_str("URI")?;
+        let byterange = self.attribute_str("BYTERANGE").and_then(|byterange| {
+            let (length, offset) = byterange.split_once('@')?;
+            Some((length.parse().ok()?, offset.parse().ok()?))
+        });
+        Some((uri.to_string(), byterange))
+    }
+
+    /// The raw text of the named attribute value regardless of whether it was quoted.
+    pub fn attribute_str(&self, name: &str) -> Option<&'a str> {
+        self.attribute(name).map(|value| value.as_str())
+    }
+}
+
+impl<'a> AttributeValue<'a> {
+    pub fn as_str(&self) -> &'a str {
+        match self {
+            Self::Quoted(s) | Self::Unquoted(s) => s,
+        }
+    }
+}
+
+// Splits on commas that are not within a quoted-string.
+fn split_attribute_list(value: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (index, c) in value.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                entries.push(&value[start..index]);
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    entries.push(&value[start..]);
+    entries.into_iter().filter(|e| !e.is_empty()).collect()
+}
+
+/// The variables that are available for substitution in the playlist, where IMPORT definitions are
+/// resolved against the values provided by the Multivariant Playlist.
+pub fn variable_definitions(
+    playlist: &str,
+    imported_definitions: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut definitions = HashMap::new();
+    for line in parse(playlist) {
+        let Some(tag) = line.tag().filter(|tag| tag.name == "EXT-X-DEFINE") else {
+            continue;
+        };
+        if let Some(name) = tag.attribute_str("NAME")
+            && let Some(value) = tag.attribute_str("VALUE")
+        {
+            definitions.insert(name.to_string(), value.to_string());
+        } else if let Some(name) = tag.attribute_str("IMPORT")
+            && let Some(value) = imported_definitions.get(name)
+        {
+            definitions.insert(name.to_string(), value.clone());
+        }
+    }
+    definitions
+}
+
+/// The EXT-X-MAP that applies to the Media Segment with the given Media Sequence Number, provided
+/// as the URI along with the byte range as (length, offset) when one is set.
+pub fn map_for_segment(
+    playlist: &str,
+    media_sequence: u64,
+) -> Option<(String, Option<(u64, u64)>)> {
+    let mut current_sequence = 0;
+    let mut current_map = None;
+    for line in parse(playlist) {
+        match line.kind {
+            LineKind::Tag(tag) if tag.name == "EXT-X-MEDIA-SEQUENCE" => {
+                current_sequence = tag.value.and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+            }
+            LineKind::Tag(tag) if tag.name == "EXT-X-MAP" => current_map = tag.map(),
+            LineKind::Uri(_) if current_sequence == media_sequence => return current_map,
+            LineKind::Uri(_) => current_sequence += 1,
+            _ => (),
+        }
+    }
+    // The segment may only exist as partial segments so far, in which case there is no URI line.
+    if current_sequence == media_sequence {
+        current_map
+    } else {
+        None
+    }
+}
+
+/// The tags that apply to a Media Segment, each as the line it was declared on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SegmentContext {
+    pub map: Option<String>,
+    pub key: Option<String>,
+    /// The most recent EXT-X-PROGRAM-DATE-TIME, which need not be on the segment itself.
+    pub program_date_time: Option<String>,
+}
+
+/// The context of each Media Segment in the playlist, along with the number of its URI line.
+pub fn segment_contexts(playlist: &str) -> Vec<(usize, SegmentContext)> {
+    let mut contexts = Vec::new();
+    let mut context = SegmentContext::default();
+    let mut in_segment = false;
+    for line in parse(playlist) {
+        match line.kind {
+            LineKind::Tag(tag) => {
+                let tag_line = || match tag.value {
+                    Some(value) => format!("#{}:{value}", tag.name),
+                    None => format!("#{}", tag.name),
+                };
+                match tag.name {
+                    "EXT-X-MAP" => context.map = Some(tag_line()),
+                    "EXT-X-KEY" => context.key = Some(tag_line()),
+                    "EXT-X-PROGRAM-DATE-TIME" => context.program_date_time = Some(tag_line()),
+                    "EXTINF" => in_segment = true,
+                    _ => (),
+                }
+            }
+            LineKind::Uri(_) if std::mem::take(&mut in_segment) => {
+                contexts.push((line.number, context.clone()));
+            }
+            _ => (),
+        }
+    }
+    contexts
+}
+
+/// The numbers of the lines that contain `query`, ignoring case. This covers tag names, attribute
+/// values and URIs alike, since the whole of each line is searched.
+pub fn search(playlist: &str, query: &str) -> Vec<usize> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    playlist
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.to_lowercase().contains(&query))
+        .map(|(index, _)| index + 1)
+        .collect()
+}
+
+/// The kinds of line that the playlist can be filtered down to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineCategory {
+    Segment,
+    Daterange,
+    Key,
+    Part,
+    Comment,
+    Variant,
+}
+
+impl LineCategory {
+    pub const ALL: [LineCategory; 6] = [
+        Self::Segment,
+        Self::Daterange,
+        Self::Key,
+        Self::Part,
+        Self::Comment,
+        Self::Variant,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Segment => tr(Message::LineCategorySegments),
+            Self::Daterange => tr(Message::LineCategoryDateranges),
+            Self::Key => tr(Message::LineCategoryKeys),
+            Self::Part => tr(Message::LineCategoryParts),
+            Self::Comment => tr(Message::LineCategoryComments),
+            Self::Variant => tr(Message::LineCategoryVariants),
+        }
+    }
+}
+
+/// The category of each line, where one applies. A URI line belongs to a variant when it follows
+/// an EXT-X-STREAM-INF, and to a segment otherwise.
+pub fn categorize(lines: &[PlaylistLine]) -> Vec<Option<LineCategory>> {
+    let mut follows_stream_inf = false;
+    lines
+        .iter()
+        .map(|line| match &line.kind {
+            LineKind::Tag(tag) => {
+                follows_stream_inf = tag.name == "EXT-X-STREAM-INF";
+                tag_category(tag.name)
+            }
+            LineKind::Uri(_) if std::mem::take(&mut follows_stream_inf) => {
+                Some(LineCategory::Variant)
+            }
+            LineKind::Uri(_) => Some(LineCategory::Segment),
+            LineKind::Comment(_) => Some(LineCategory::Comment),
+            LineKind::Blank => None,
+        })
+        .collect()
+}
+
+fn tag_category(name: &str) -> Option<LineCategory> {
+    match name {
+        "EXTINF"
+        | "EXT-X-BYTERANGE"
+        | "EXT-X-DISCONTINUITY"
+        | "EXT-X-PROGRAM-DATE-TIME"
+        | "EXT-X-GAP"
+        | "EXT-X-BITRATE"
+        | "EXT-X-MAP" => Some(LineCategory::Segment),
+        "EXT-X-DATERANGE" => Some(LineCategory::Daterange),
+        "EXT-X-KEY" | "EXT-X-SESSION-KEY" => Some(LineCategory::Key),
+        "EXT-X-PART" | "EXT-X-PART-INF" | "EXT-X-PRELOAD-HINT" => Some(LineCategory::Part),
+        "EXT-X-STREAM-INF"
+        | "EXT-X-I-FRAME-STREAM-INF"
+        | "EXT-X-IMAGE-STREAM-INF"
+        | "EXT-X-MEDIA" => Some(LineCategory::Variant),
+        _ => None,
+    }
+}
+
+/// The runs of lines that are not visible, as the index of the first line of each run mapped to
+/// the number of lines in the run, so that a marker can stand in for each run.
+pub fn hidden_runs(visible: &[bool]) -> HashMap<usize, usize> {
+    let mut runs = HashMap::new();
+    let mut run_start = None;
+    for (index, visible) in visible.iter().enumerate() {
+        match (visible, run_start) {
+            (false, None) => run_start = Some(index),
+            (true, Some(start)) => {
+                runs.insert(start, index - start);
+                run_start = None;
+            }
+            _ => (),
+        }
+    }
+    if let Some(start) = run_start {
+        runs.insert(start, visible.len() - start);
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_line_kinds() {
+        assert_eq!(
+            vec![
+                PlaylistLine {
+                    number: 1,
+                    kind: LineKind::Tag(TagLine {
+                        name: "EXTM3U",
+                        value: None
+                    })
+                },
+                PlaylistLine {
+                    number: 2,
+                    kind: LineKind::Tag(TagLine {
+                        name: "EXT-X-TARGETDURATION",
+                        value: Some("6")
+                    })
+                },
+                PlaylistLine {
+                    number: 3,
+                    kind: LineKind::Blank
+                },
+                PlaylistLine {
+                    number: 4,
+                    kind: LineKind::Comment(" comment")
+                },
+                PlaylistLine {
+                    number: 5,
+                    kind: LineKind::Uri("segment.mp4")
+                },
+            ],
+            parse("#EXTM3U\n#EXT-X-TARGETDURATION:6\n\n# comment\nsegment.mp4\n")
+        );
+    }
+
+    #[test]
+    fn attributes_respect_quoted_commas() {
+        let line = parse(r#"#EXT-X-STREAM-INF:BANDWIDTH=1000,CODECS="avc1.64001f,mp4a.40.2""#);
+        let tag = line[0].tag().unwrap();
+        assert_eq!(
+            vec![
+                Ok(("BANDWIDTH", AttributeValue::Unquoted("1000"))),
+                Ok(("CODECS", AttributeValue::Quoted("avc1.64001f,mp4a.40.2"))),
+            ],
+            tag.attributes()
+        );
+        assert_eq!(Some("avc1.64001f,mp4a.40.2"), tag.attribute_str("CODECS"));
+    }
+
+    #[test]
+    fn map_for_segment_uses_the_map_in_effect() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXT-X-MEDIA-SEQUENCE:10
+#EXT-X-MAP:URI=\"init-a.mp4\"
+#EXTINF:4,
+a.mp4
+#EXT-X-DISCONTINUITY
+#EXT-X-MAP:URI=\"media.mp4\",BYTERANGE=\"720@0\"
+#EXTINF:4,
+b.mp4
+";
+        assert_eq!(
+            Some((String::from("init-a.mp4"), None)),
+            map_for_segment(playlist, 10)
+        );
+        assert_eq!(
+            Some((String::from("media.mp4"), Some((720, 0)))),
+            map_for_segment(playlist, 11)
+        );
+        assert_eq!(None, map_for_segment(playlist, 13));
+    }
+
+    #[test]
+    fn malformed_attributes_are_reported() {
+        let line = parse("#EXT-X-KEY:METHOD=NONE,garbage");
+        assert_eq!(
+            vec![
+                Ok(("METHOD", AttributeValue::Unquoted("NONE"))),
+                Err("garbage"),
+            ],
+            line[0].tag().unwrap().attributes()
+        );
+    }
+
+    #[test]
+    fn search_matches_whole_lines_ignoring_case() {
+        let playlist =
+            "#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\"\n#EXTINF:6,\nSegment1.ts\n";
+        assert_eq!(vec![2], search(playlist, "ext-x-key"));
+        assert_eq!(vec![2], search(playlist, "aes-128"));
+        assert_eq!(vec![4], search(playlist, "segment1"));
+        assert_eq!(vec![1, 2, 3], search(playlist, "#EXT"));
+        assert_eq!(Vec::<usize>::new(), search(playlist, "  "));
+    }
+
+    #[test]
+    fn lines_are_categorized() {
+        let playlist = "#EXTM3U\n#EXT-X-KEY:METHOD=NONE\n# note\n#EXTINF:6,\na.ts\n\n#EXT-X-STREAM-INF:BANDWIDTH=1\nv.m3u8\n#EXT-X-PART:DURATION=1,URI=\"p.mp4\"\n";
+        assert_eq!(
+            vec![
+                None,
+                Some(LineCategory::Key),
+                Some(LineCategory::Comment),
+                Some(LineCategory::Segment),
+                Some(LineCategory::Segment),
+                None,
+                Some(LineCategory::Variant),
+                Some(LineCategory::Variant),
+                Some(LineCategory::Part),
+            ],
+            categorize(&parse(playlist))
+        );
+    }
+
+    #[test]
+    fn hidden_runs_are_found_between_visible_lines() {
+        assert_eq!(
+            HashMap::from([(0, 2), (3, 1), (5, 2)]),
+            hidden_runs(&[false, false, true, false, true, false, false])
+        );
+        assert_eq!(HashMap::new(), hidden_runs(&[true, true]));
+    }
+
+    #[test]
+    fn segment_contexts_carry_forward() {
+        let playlist = "#EXTM3U\n#EXT-X-MAP:URI=\"init.mp4\"\n#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00Z\n#EXTINF:6,\na.mp4\n#EXT-X-KEY:METHOD=AES-128,URI=\"k\"\n#EXTINF:6,\nb.mp4\n";
+        let map = Some(String::from("#EXT-X-MAP:URI=\"init.mp4\""));
+        let program_date_time = Some(String::from(
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00Z",
+        ));
+        assert_eq!(
+            vec![
+                (
+                    5,
+                    SegmentContext {
+                        map: map.clone(),
+                        key: None,
+                        program_date_time: program_date_time.clone(),
+                    }
+                ),
+                (
+                    8,
+                    SegmentContext {
+                        map,
+                        key: Some(String::from("#EXT-X-KEY:METHOD=AES-128,URI=\"k\"")),
+                        program_date_time,
+                    }
+                ),
+            ],
+            segment_contexts(playlist)
+        );
+        assert_eq!(
+            Vec::<(usize, SegmentContext)>::new(),
+            segment_contexts("#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1\nv.m3u8\n")
+        );
+    }
+}
@@ -0,0 +1,96 @@
+// The marks shown on the minimap of a playlist, which lays out the whole playlist (by line) down a
+// thin column. A long playlist has far more lines than the column has room for, so lines are
+// gathered into rows, with one mark of each kind per row.
+
+use crate::utils::playlist_lines::{LineKind, parse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MinimapMark {
+    Segment,
+    Discontinuity,
+    Daterange,
+    Key,
+}
+
+impl MinimapMark {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Segment => "segment",
+            Self::Discontinuity => "discontinuity",
+            Self::Daterange => "daterange",
+            Self::Key => "key",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Minimap {
+    pub line_count: usize,
+    /// The marks by row, with the 1-based number of the first line in the row that has the mark.
+    pub marks: Vec<(usize, MinimapMark, usize)>,
+}
+
+/// The marks of the playlist gathered into `rows` rows.
+pub fn minimap(playlist: &str, rows: usize) -> Minimap {
+    let lines = parse(playlist);
+    let line_count = lines.len();
+    let mut marks = Vec::<(usize, MinimapMark, usize)>::new();
+    if rows == 0 {
+        return Minimap { line_count, marks };
+    }
+    for line in lines {
+        let mark = match line.kind {
+            LineKind::Uri(_) => MinimapMark::Segment,
+            LineKind::Tag(tag) => match tag.name {
+                "EXT-X-DISCONTINUITY" => MinimapMark::Discontinuity,
+                "EXT-X-DATERANGE" => MinimapMark::Daterange,
+                "EXT-X-KEY" | "EXT-X-SESSION-KEY" => MinimapMark::Key,
+                _ => continue,
+            },
+            _ => continue,
+        };
+        let row = (line.number - 1) * rows / line_count;
+        // Lines are in order, so the marks already in the row are the last ones.
+        let seen = marks
+            .iter()
+            .rev()
+            .take_while(|(mark_row, _, _)| *mark_row == row)
+            .any(|(_, seen, _)| *seen == mark);
+        if !seen {
+            marks.push((row, mark, line.number));
+        }
+    }
+    Minimap { line_count, marks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn marks_are_gathered_into_rows() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXTINF:4,
+0.mp4
+#EXTINF:4,
+1.mp4
+#EXT-X-DISCONTINUITY
+#EXT-X-KEY:METHOD=NONE
+#EXTINF:4,
+2.mp4";
+        assert_eq!(
+            Minimap {
+                line_count: 10,
+                marks: vec![
+                    (0, MinimapMark::Segment, 4),
+                    (1, MinimapMark::Segment, 6),
+                    (1, MinimapMark::Discontinuity, 7),
+                    (1, MinimapMark::Key, 8),
+                ],
+            },
+            minimap(playlist, 2)
+        );
+    }
+}
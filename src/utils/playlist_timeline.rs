@@ -0,0 +1,226 @@
+// The layout of a media playlist over time, for the timeline drawn above its lines. Segments are
+// laid end to end from the start of the playlist, and dateranges are placed against them using the
+// EXT-X-PROGRAM-DATE-TIME tags, as that is the only way to relate their dates to the segments.
+
+use crate::utils::{
+    playlist_lines::{LineKind, parse},
+    timeline_drift::parse_date_time,
+};
+
+const INTERSTITIAL_CLASS: &str = "com.apple.hls.interstitial";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineSegment {
+    /// The 1-based number of the line with the URI of the segment.
+    pub line: usize,
+    pub media_sequence: u64,
+    /// The time in seconds from the start of the playlist that the segment starts at.
+    pub start: f64,
+    pub duration: f64,
+    /// Whether the segment follows an EXT-X-DISCONTINUITY.
+    pub discontinuity: bool,
+    pub gap: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineDaterange {
+    /// The 1-based number of the line with the EXT-X-DATERANGE tag.
+    pub line: usize,
+    pub id: String,
+    /// The time in seconds from the start of the playlist that the daterange starts at, which may
+    /// be outside of the playlist.
+    pub start: f64,
+    pub duration: Option<f64>,
+    /// Whether the daterange marks an ad break, either with SCTE-35 or as an interstitial.
+    pub ad_break: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Timeline {
+    pub segments: Vec<TimelineSegment>,
+    pub dateranges: Vec<TimelineDaterange>,
+}
+
+impl Timeline {
+    /// The total duration of the segments in seconds.
+    pub fn duration(&self) -> f64 {
+        self.segments
+            .last()
+            .map(|segment| segment.start + segment.duration)
+            .unwrap_or_default()
+    }
+}
+
+pub fn timeline(playlist: &str) -> Timeline {
+    let mut timeline = Timeline::default();
+    let mut media_sequence = 0;
+    let mut time = 0.0;
+    // The duration of the segment that the next URI belongs to, and whether it is a discontinuity
+    // or a gap.
+    let mut duration = None;
+    let mut discontinuity = false;
+    let mut gap = false;
+    // Each EXT-X-PROGRAM-DATE-TIME ties a date (in milliseconds) to a time in the playlist.
+    let mut anchors = Vec::new();
+    // The dateranges with their start dates (in milliseconds), placed once all anchors are known.
+    let mut dateranges = Vec::new();
+    for line in parse(playlist) {
+        match line.kind {
+            LineKind::Tag(tag) => match tag.name {
+                "EXTINF" => {
+                    duration = Some(
+                        tag.value
+                            .and_then(|value| value.split(',').next())
+                            .and_then(|duration| duration.trim().parse().ok())
+                            .unwrap_or_default(),
+                    );
+                }
+                "EXT-X-MEDIA-SEQUENCE" => {
+                    media_sequence = tag
+                        .value
+                        .and_then(|value| value.trim().parse().ok())
+                        .unwrap_or_default();
+                }
+                "EXT-X-DISCONTINUITY" => discontinuity = true,
+                "EXT-X-GAP" => gap = true,
+                "EXT-X-PROGRAM-DATE-TIME" => {
+                    if let Some(millis) = tag.value.and_then(parse_date_time) {
+                        anchors.push((time, millis));
+                    }
+                }
+                "EXT-X-DATERANGE" => {
+                    let Some(start_date) =
+                        tag.attribute_str("START-DATE").and_then(parse_date_time)
+                    else {
+                        continue;
+                    };
+                    let seconds = |name| {
+                        tag.attribute_str(name)
+                            .and_then(|value| value.parse::<f64>().ok())
+                    };
+                    let duration = seconds("DURATION")
+                        .or_else(|| {
+                            tag.attribute_str("END-DATE")
+                                .and_then(parse_date_time)
+                                .map(|end_date| (end_date - start_date) / 1000.0)
+                        })
+                        .or_else(|| seconds("PLANNED-DURATION"));
+                    let ad_break = tag.attribute_str("SCTE35-OUT").is_some()
+                        || tag.attribute_str("CLASS") == Some(INTERSTITIAL_CLASS);
+                    let daterange = TimelineDaterange {
+                        line: line.number,
+                        id: tag.attribute_str("ID").unwrap_or_default().to_string(),
+                        start: 0.0,
+                        duration,
+                        ad_break,
+                    };
+                    dateranges.push((daterange, start_date));
+                }
+                _ => (),
+            },
+            LineKind::Uri(_) => {
+                // A URI without an EXTINF is not a Media Segment (e.g. a variant stream).
+                if let Some(duration) = duration.take() {
+                    timeline.segments.push(TimelineSegment {
+                        line: line.number,
+                        media_sequence,
+                        start: time,
+                        duration,
+                        discontinuity: std::mem::take(&mut discontinuity),
+                        gap: std::mem::take(&mut gap),
+                    });
+                    time += duration;
+                }
+                media_sequence += 1;
+            }
+            _ => (),
+        }
+    }
+    if timeline.segments.is_empty() {
+        return Timeline::default();
+    }
+    timeline.dateranges = dateranges
+        .into_iter()
+        .filter_map(|(daterange, start_date)| {
+            // The closest date at or before the start of the daterange places it most accurately.
+            let (time, millis) = anchors
+                .iter()
+                .rev()
+                .find(|(_, millis)| *millis <= start_date)
+                .or(anchors.first())?;
+            Some(TimelineDaterange {
+                start: time + (start_date - millis) / 1000.0,
+                ..daterange
+            })
+        })
+        .collect();
+    timeline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn timeline_lays_out_segments_and_places_dateranges_by_date() {
+        let playlist = concat!(
+            "#EXTM3U\n",
+            "#EXT-X-MEDIA-SEQUENCE:10\n",
+            "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00Z\n",
+            "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2025-01-01T00:00:04Z\",",
+            "DURATION=8,SCTE35-OUT=0xFC\n",
+            "#EXTINF:6,\n",
+            "0.ts\n",
+            "#EXT-X-DISCONTINUITY\n",
+            "#EXTINF:4,\n",
+            "1.ts\n",
+            "#EXT-X-DATERANGE:ID=\"later\",START-DATE=\"2025-01-01T00:00:30Z\"\n",
+        );
+        assert_eq!(
+            Timeline {
+                segments: vec![
+                    TimelineSegment {
+                        line: 6,
+                        media_sequence: 10,
+                        start: 0.0,
+                        duration: 6.0,
+                        discontinuity: false,
+                        gap: false,
+                    },
+                    TimelineSegment {
+                        line: 9,
+                        media_sequence: 11,
+                        start: 6.0,
+                        duration: 4.0,
+                        discontinuity: true,
+                        gap: false,
+                    },
+                ],
+                dateranges: vec![
+                    TimelineDaterange {
+                        line: 4,
+                        id: String::from("ad"),
+                        start: 4.0,
+                        duration: Some(8.0),
+                        ad_break: true,
+                    },
+                    TimelineDaterange {
+                        line: 10,
+                        id: String::from("later"),
+                        start: 30.0,
+                        duration: None,
+                        ad_break: false,
+                    },
+                ],
+            },
+            timeline(playlist)
+        );
+    }
+
+    #[test]
+    fn timeline_is_empty_without_segments() {
+        let playlist = "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1000\nvideo.m3u8\n";
+        assert_eq!(Timeline::default(), timeline(playlist));
+    }
+}
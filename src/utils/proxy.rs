@@ -0,0 +1,133 @@
+use crate::utils::storage::{load_flag, load_item, save_flag, save_item};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use url::Url;
+
+// The unreserved characters of RFC 3986 are left as they are, so that the proxied URL remains
+// readable, while everything else is encoded so that the URL survives as a single query value.
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// The placeholder that marks where in the proxy prefix the requested URL is inserted. Without it,
+/// the URL is appended to the prefix.
+pub const PROXY_URL_PLACEHOLDER: &str = "{url}";
+
+/// A CORS proxy that outgoing requests are sent through, for servers that do not provide CORS
+/// headers themselves.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProxyConfig {
+    pub enabled: bool,
+    /// The prefix of the proxied URL (e.g. `https://myproxy/?url=`).
+    pub prefix: String,
+    /// Hosts that are requested directly. A leading `.` matches any subdomain as well.
+    pub bypass_hosts: Vec<String>,
+}
+
+impl ProxyConfig {
+    /// The URL to request `url` through the proxy, or `None` when the request should be made
+    /// directly.
+    pub fn proxied_url(&self, url: &str) -> Option<String> {
+        if !self.enabled || self.prefix.trim().is_empty() || url.starts_with(&self.prefix) {
+            return None;
+        }
+        let parsed = Url::parse(url).ok()?;
+        if !matches!(parsed.scheme(), "http" | "https") || self.bypasses(parsed.host_str()?) {
+            return None;
+        }
+        let encoded = utf8_percent_encode(url, URL_COMPONENT).to_string();
+        if self.prefix.contains(PROXY_URL_PLACEHOLDER) {
+            Some(self.prefix.replace(PROXY_URL_PLACEHOLDER, &encoded))
+        } else {
+            Some(format!("{}{encoded}", self.prefix))
+        }
+    }
+
+    fn bypasses(&self, host: &str) -> bool {
+        self.bypass_hosts.iter().any(|bypass| {
+            let bypass = bypass.trim();
+            match bypass.strip_prefix('.') {
+                Some(domain) => host == domain || host.ends_with(bypass),
+                None => !bypass.is_empty() && host == bypass,
+            }
+        })
+    }
+}
+
+const ENABLED_KEY: &str = "proxy_enabled";
+const PREFIX_KEY: &str = "proxy_prefix";
+const BYPASS_HOSTS_KEY: &str = "proxy_bypass_hosts";
+
+pub fn load_proxy_config() -> ProxyConfig {
+    ProxyConfig {
+        enabled: load_flag(ENABLED_KEY),
+        prefix: load_item(PREFIX_KEY).unwrap_or_default(),
+        bypass_hosts: load_item(BYPASS_HOSTS_KEY)
+            .map(|hosts| parse_hosts(&hosts))
+            .unwrap_or_default(),
+    }
+}
+
+pub fn save_proxy_config(config: &ProxyConfig) {
+    save_flag(ENABLED_KEY, config.enabled);
+    save_item(PREFIX_KEY, &config.prefix);
+    save_item(BYPASS_HOSTS_KEY, &config.bypass_hosts.join(","));
+}
+
+/// Parses a comma separated list of hosts.
+pub fn parse_hosts(hosts: &str) -> Vec<String> {
+    hosts
+        .split(',')
+        .map(str::trim)
+        .filter(|host| !host.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn config(prefix: &str) -> ProxyConfig {
+        ProxyConfig {
+            enabled: true,
+            prefix: prefix.to_string(),
+            bypass_hosts: vec![String::from(".cdn.com"), String::from("example.org")],
+        }
+    }
+
+    #[test]
+    fn proxied_url_encodes_the_request_url() {
+        assert_eq!(
+            Some(String::from(
+                "https://myproxy/?url=https%3A%2F%2Fexample.com%2Fhls%2Fmvp.m3u8%3Ftoken%3Da%26b%3D1"
+            )),
+            config("https://myproxy/?url=")
+                .proxied_url("https://example.com/hls/mvp.m3u8?token=a&b=1")
+        );
+        assert_eq!(
+            Some(String::from(
+                "https://myproxy/fetch/https%3A%2F%2Fexample.com%2Fa.ts?cache=no"
+            )),
+            config("https://myproxy/fetch/{url}?cache=no").proxied_url("https://example.com/a.ts")
+        );
+    }
+
+    #[test]
+    fn some_requests_are_made_directly() {
+        let proxy = config("https://myproxy/?url=");
+        assert_eq!(None, proxy.proxied_url("https://video.cdn.com/a.ts"));
+        assert_eq!(None, proxy.proxied_url("https://cdn.com/a.ts"));
+        assert_eq!(None, proxy.proxied_url("https://example.org/a.ts"));
+        assert_eq!(None, proxy.proxied_url("data:text/plain,hello"));
+        assert_eq!(None, proxy.proxied_url("https://myproxy/?url=a"));
+        assert!(proxy.proxied_url("https://www.example.org/a.ts").is_some());
+        let disabled = ProxyConfig {
+            enabled: false,
+            ..proxy
+        };
+        assert_eq!(None, disabled.proxied_url("https://example.com/a.ts"));
+    }
+}
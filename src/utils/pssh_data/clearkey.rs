@@ -0,0 +1,94 @@
+// Clear Key (org.w3.clearkey) is the key system that every EME implementation supports, where the
+// key itself is handed to the CDM, which makes it the usual choice for test streams. Its
+// initialization data is either the W3C Common PSSH box, or the "keyids" format.
+//
+// https://www.w3.org/TR/eme-initdata-cenc/#common-system
+//
+//    The SystemID is 1077efec-c0b2-4d02-ace3-3c1e52e2fb4b.
+//    ...
+//    The PSSH box version MUST be 1. The Data field is not used and DataSize MUST be 0.
+//
+// https://www.w3.org/TR/eme-initdata-keyids/
+//
+//    The initialization data is a JSON object containing the following member:
+//
+//    "kids"
+//        An array of key IDs. Each element of the array is the base64url encoding of the octet
+//        sequence containing the key ID value.
+//
+// Some packagers put the "keyids" JSON in the Data of the box all the same, so that is read too.
+
+use base64::prelude::*;
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClearKeyPsshData {
+    /// The key IDs of the "kids" member.
+    pub kids: Vec<[u8; 16]>,
+    /// The JSON, pretty-printed.
+    pub json: String,
+}
+
+/// Reads the "keyids" JSON, which is only taken to be Clear Key data when it is a JSON object.
+pub fn parse_pssh_data(data: &[u8]) -> Option<ClearKeyPsshData> {
+    let value = serde_json::from_slice::<Value>(data).ok()?;
+    let object = value.as_object()?;
+    let kids = object
+        .get("kids")
+        .and_then(Value::as_array)
+        .map(|kids| {
+            kids.iter()
+                .filter_map(Value::as_str)
+                .filter_map(decode_kid)
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(ClearKeyPsshData {
+        kids,
+        json: serde_json::to_string_pretty(&value).ok()?,
+    })
+}
+
+// The key IDs are meant to be unpadded base64url, though padded and standard base64 turn up too.
+fn decode_kid(kid: &str) -> Option<[u8; 16]> {
+    let kid = kid.trim_end_matches('=');
+    BASE64_URL_SAFE_NO_PAD
+        .decode(kid)
+        .or_else(|_| BASE64_STANDARD_NO_PAD.decode(kid))
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn keyids_json_lists_kids() {
+        let kid = [
+            0x9e, 0xb4, 0x05, 0x0d, 0xe4, 0x4b, 0x48, 0x02, 0x93, 0x2e, 0x27, 0xd7, 0x50, 0x83,
+            0xe2, 0x66,
+        ];
+        let data = parse_pssh_data(
+            br#"{"kids":["nrQFDeRLSAKTLifXUIPiZg","nrQFDeRLSAKTLifXUIPiZg=="],"type":"temporary"}"#,
+        )
+        .expect("Clear Key data");
+        assert_eq!(vec![kid, kid], data.kids);
+        assert_eq!(
+            concat!(
+                "{\n",
+                "  \"kids\": [\n",
+                "    \"nrQFDeRLSAKTLifXUIPiZg\",\n",
+                "    \"nrQFDeRLSAKTLifXUIPiZg==\"\n",
+                "  ],\n",
+                "  \"type\": \"temporary\"\n",
+                "}"
+            ),
+            data.json
+        );
+        assert_eq!(None, parse_pssh_data(b"[1, 2]"));
+        assert_eq!(None, parse_pssh_data(&[0x08, 0x01]));
+    }
+}
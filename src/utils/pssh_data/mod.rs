@@ -1 +1,5 @@
+//! The data of a `pssh` box for the DRM systems whose data is decoded, as given by
+//! [`PsshData`](crate::mp4_parsing::pssh::PsshData).
+
+pub mod clearkey;
 pub mod playready;
@@ -20,6 +20,7 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+use base64::prelude::*;
 use quick_xml::events::{BytesCData, BytesStart, BytesText, Event};
 use quick_xml::{Reader, Writer};
 use std::{
@@ -362,6 +363,26 @@ fn parse_playready_record(rdr: &mut Cursor<&[u8]>) -> Result<PlayReadyRecord, Pa
     })
 }
 
+/// Decodes a CHECKSUM, which is base64. For a key encrypted with AESCTR, it is the first 8 bytes of
+/// the KID encrypted with the content key, so that a license server can check it holds the right key.
+pub fn decode_checksum(checksum: &str) -> Option<Vec<u8>> {
+    BASE64_STANDARD.decode(checksum.trim()).ok()
+}
+
+/// Indents XML (such as that of CUSTOMATTRIBUTES, which is collected without whitespace) by element.
+pub fn pretty_print_xml(xml: &str) -> Option<String> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+    loop {
+        match reader.read_event().ok()? {
+            Event::Eof => break,
+            event => writer.write_event(event).ok()?,
+        }
+    }
+    String::from_utf8(writer.into_inner().into_inner()).ok()
+}
+
 fn key_id(bytes: &BytesStart) -> PlayReadyKid {
     let mut kid = PlayReadyKid::default();
     for a in bytes.attributes().flatten() {
@@ -494,3 +515,32 @@ impl LittleEndianReader for Cursor<&[u8]> {
         Ok(u32::from_le_bytes(buf))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn custom_attributes_are_indented() {
+        assert_eq!(
+            Some(String::from(
+                "<CUSTOMATTRIBUTES>\n  <IIS_DRM_VERSION>8.1.2304.31</IIS_DRM_VERSION>\n\
+                 </CUSTOMATTRIBUTES>"
+            )),
+            pretty_print_xml(
+                "<CUSTOMATTRIBUTES><IIS_DRM_VERSION>8.1.2304.31</IIS_DRM_VERSION>\
+                 </CUSTOMATTRIBUTES>"
+            )
+        );
+    }
+
+    #[test]
+    fn checksums_are_base64() {
+        assert_eq!(
+            Some(vec![0x4b, 0x2b, 0x2f, 0x9c, 0x41, 0x3e, 0x77, 0x0c]),
+            decode_checksum("SysvnEE+dww=")
+        );
+        assert_eq!(None, decode_checksum("not base64!"));
+    }
+}
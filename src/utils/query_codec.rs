@@ -1,8 +1,15 @@
-use crate::utils::network::RequestRange;
+use crate::utils::{
+    byterange::RequestRange,
+    i18n::{Message, tr_with},
+    playlist_lines::TagLine,
+    resolution::Resolution,
+};
+use base64::prelude::*;
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec_with_limit};
 use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
 use std::{
     borrow::Cow, collections::HashMap, error::Error, fmt::Display, num::ParseIntError,
-    str::Utf8Error,
+    str::Utf8Error, string::FromUtf8Error,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -64,6 +71,102 @@ pub struct DaterangeScheduleContext {
     pub daterange_id: String,
 }
 
+/// The attributes of the EXT-X-STREAM-INF tag that referenced the Media Playlist currently being
+/// viewed, used to check the media against what the Multivariant Playlist advertises.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VariantContext {
+    pub codecs: Option<String>,
+    pub resolution: Option<Resolution>,
+    pub bandwidth: Option<u64>,
+    pub average_bandwidth: Option<u64>,
+    pub frame_rate: Option<f64>,
+}
+
+impl VariantContext {
+    /// Decodes the context from the (already percent decoded) attribute list of the tag.
+    pub fn decode(attributes: &str) -> Self {
+        let tag = TagLine {
+            name: "EXT-X-STREAM-INF",
+            value: Some(attributes),
+        };
+        Self {
+            codecs: tag.attribute_str("CODECS").map(String::from),
+            resolution: tag.attribute_str("RESOLUTION").and_then(Resolution::parse),
+            bandwidth: tag.attribute_str("BANDWIDTH").and_then(|b| b.parse().ok()),
+            average_bandwidth: tag
+                .attribute_str("AVERAGE-BANDWIDTH")
+                .and_then(|b| b.parse().ok()),
+            frame_rate: tag.attribute_str("FRAME-RATE").and_then(|f| f.parse().ok()),
+        }
+    }
+}
+
+/// A view of the playlist that a link asks for it to be opened on (`view=summary`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaylistView {
+    /// The table of the Variant Streams of a Multivariant Playlist.
+    Summary,
+}
+
+impl PlaylistView {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "summary" => Some(Self::Summary),
+            _ => None,
+        }
+    }
+}
+
+/// The Media Segment that a link asks to be opened once its playlist has loaded, given as
+/// `msn:1234`, `first` or `last` (which, for a live playlist, is the latest).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentSelector {
+    MediaSequence(u64),
+    First,
+    Last,
+}
+
+impl SegmentSelector {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "first" => Some(Self::First),
+            "last" => Some(Self::Last),
+            _ => value
+                .strip_prefix("msn:")
+                .and_then(|msn| msn.parse().ok())
+                .map(Self::MediaSequence),
+        }
+    }
+
+    /// Picks the segment out of those of the playlist, which are given in order with their Media
+    /// Sequence Numbers.
+    pub fn select<T>(self, segments: &[(u64, T)]) -> Option<&T> {
+        let segment = match self {
+            Self::MediaSequence(msn) => segments
+                .iter()
+                .find(|(media_sequence, _)| *media_sequence == msn),
+            Self::First => segments.first(),
+            Self::Last => segments.last(),
+        };
+        segment.map(|(_, segment)| segment)
+    }
+}
+
+impl Display for SegmentSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MediaSequence(msn) => write!(f, "msn:{msn}"),
+            Self::First => write!(f, "first"),
+            Self::Last => write!(f, "last"),
+        }
+    }
+}
+
+/// Whether a flag of the query (such as `validate=1`) is on.
+pub fn is_flag_set(value: &str) -> bool {
+    matches!(value, "1" | "true")
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum SupplementalViewQueryContext {
     Segment(MediaSegmentContext),
@@ -74,6 +177,93 @@ pub enum SupplementalViewQueryContext {
     DaterangeSchedule(DaterangeScheduleContext),
 }
 
+/// How a supplemental view that is pinned for comparison is shown alongside the current one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PinnedViewLayout {
+    Tabs,
+    Split,
+}
+impl Display for PinnedViewLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tabs => write!(f, "TABS"),
+            Self::Split => write!(f, "SPLIT"),
+        }
+    }
+}
+
+/// A supplemental view that stays open while other resources are opened from the playlist (or from
+/// other playlists), so that the two can be compared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinnedViewQueryContext {
+    pub layout: PinnedViewLayout,
+    pub context: SupplementalViewQueryContext,
+}
+
+impl SupplementalViewQueryContext {
+    pub fn encode(&self) -> String {
+        match self {
+            Self::Segment(c) => encode_segment(&c.url, c.media_sequence, c.byterange),
+            Self::Map(c) => encode_map(&c.url, c.media_sequence, c.byterange),
+            Self::Part(p) => encode_part(
+                &p.segment_context.url,
+                p.segment_context.media_sequence,
+                p.part_index,
+                p.segment_context.byterange,
+            ),
+            Self::Scte35(s) => encode_scte35(&s.message, &s.daterange_id, s.command_type),
+            Self::AssetList(a) => encode_asset_list(&a.url, &a.daterange_id),
+            Self::DaterangeSchedule(d) => encode_daterange_schedule(&d.url, &d.daterange_id),
+        }
+    }
+
+    /// A short description of the resource, used to tell supplemental views apart.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Segment(c) => tr_with(
+                Message::SupplementalSegment,
+                &[("media_sequence", &c.media_sequence.to_string())],
+            ),
+            Self::Map(c) => tr_with(
+                Message::SupplementalMap,
+                &[("media_sequence", &c.media_sequence.to_string())],
+            ),
+            Self::Part(p) => tr_with(
+                Message::SupplementalPart,
+                &[
+                    (
+                        "media_sequence",
+                        &p.segment_context.media_sequence.to_string(),
+                    ),
+                    ("part_index", &p.part_index.to_string()),
+                ],
+            ),
+            Self::Scte35(s) => tr_with(
+                Message::SupplementalScte35,
+                &[
+                    ("command_type", &s.command_type.to_string()),
+                    ("id", &s.daterange_id),
+                ],
+            ),
+            Self::AssetList(a) => {
+                tr_with(Message::SupplementalAssetList, &[("id", &a.daterange_id)])
+            }
+            Self::DaterangeSchedule(d) => {
+                tr_with(Message::SupplementalSchedule, &[("id", &d.daterange_id)])
+            }
+        }
+    }
+}
+
+// The pinned view nests the (percent decoded) value of the supplemental view after its layout, so
+// that it is decoded just the same as the supplemental view once the layout is split off.
+pub fn encode_pinned_view(
+    layout: PinnedViewLayout,
+    context: &SupplementalViewQueryContext,
+) -> String {
+    percent_encode(&format!("{layout},{}", percent_decode(&context.encode()))).to_string()
+}
+
 pub fn encode_segment(url: &str, media_sequence: u64, byterange: Option<RequestRange>) -> String {
     percent_encode(&format!(
         "SEGMENT,{}",
@@ -187,6 +377,29 @@ pub fn decode_definitions(
     Ok(map)
 }
 
+// The highest level of compression, as the query is small enough for the time taken to not matter.
+const QUERY_COMPRESSION_LEVEL: u8 = 10;
+// A compressed query is not expanded past this many bytes, so that a crafted link cannot make it
+// balloon.
+const MAX_DECOMPRESSED_QUERY_LENGTH: usize = 1024 * 1024;
+
+/// Packs the whole query of a page (without the leading `?`) into a single query value, as the
+/// URL-safe base64 of the deflated query, which keeps links to views with long contexts and many
+/// definitions short enough to share.
+pub fn compress_query(search: &str) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(compress_to_vec(search.as_bytes(), QUERY_COMPRESSION_LEVEL))
+}
+
+/// Unpacks a query value from [`compress_query`] back into the query that it was packed from.
+pub fn decompress_query(value: &str) -> Result<String, DecompressQueryError> {
+    let compressed = BASE64_URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(DecompressQueryError::Base64)?;
+    let decompressed = decompress_to_vec_with_limit(&compressed, MAX_DECOMPRESSED_QUERY_LENGTH)
+        .map_err(|_| DecompressQueryError::Inflate)?;
+    String::from_utf8(decompressed).map_err(DecompressQueryError::Utf8Error)
+}
+
 // https://url.spec.whatwg.org/#query-percent-encode-set
 // The query percent-encode set is the C0 control percent-encode set and U+0020 SPACE, U+0022 ("),
 // U+0023 (#), U+003C (<), and U+003E (>).
@@ -316,6 +529,29 @@ impl TryFrom<&str> for SupplementalViewQueryContext {
     }
 }
 
+impl TryFrom<&str> for PinnedViewQueryContext {
+    type Error = SupplementalViewQueryContextDecodeError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let Some((layout, context)) = value.split_once(',') else {
+            return Err(SupplementalViewQueryContextDecodeError::MissingPinnedLayout);
+        };
+        let layout = match layout {
+            "TABS" => PinnedViewLayout::Tabs,
+            "SPLIT" => PinnedViewLayout::Split,
+            _ => {
+                return Err(
+                    SupplementalViewQueryContextDecodeError::UnknownPinnedLayout(
+                        layout.to_string(),
+                    ),
+                );
+            }
+        };
+        let context = SupplementalViewQueryContext::try_from(context)?;
+        Ok(Self { layout, context })
+    }
+}
+
 impl TryFrom<&str> for MediaSegmentContext {
     type Error = SupplementalViewQueryContextDecodeError;
 
@@ -353,25 +589,6 @@ impl TryFrom<&str> for MediaSegmentContext {
     }
 }
 
-#[cfg(test)]
-impl SupplementalViewQueryContext {
-    fn encode(&self) -> String {
-        match self {
-            Self::Segment(c) => encode_segment(&c.url, c.media_sequence, c.byterange),
-            Self::Map(c) => encode_map(&c.url, c.media_sequence, c.byterange),
-            Self::Part(p) => encode_part(
-                &p.segment_context.url,
-                p.segment_context.media_sequence,
-                p.part_index,
-                p.segment_context.byterange,
-            ),
-            Self::Scte35(s) => encode_scte35(&s.message, &s.daterange_id, s.command_type),
-            Self::AssetList(a) => encode_asset_list(&a.url, &a.daterange_id),
-            Self::DaterangeSchedule(d) => encode_daterange_schedule(&d.url, &d.daterange_id),
-        }
-    }
-}
-
 #[derive(Debug, Clone, PartialEq)]
 pub enum SupplementalViewQueryContextDecodeError {
     NoContextType,
@@ -389,6 +606,8 @@ pub enum SupplementalViewQueryContextDecodeError {
     MissingDaterangeId,
     MissingScte35Message,
     MissingAssetListUrl,
+    MissingPinnedLayout,
+    UnknownPinnedLayout(String),
 }
 impl Display for SupplementalViewQueryContextDecodeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -423,6 +642,8 @@ impl Display for SupplementalViewQueryContextDecodeError {
             Self::MissingDaterangeId => write!(f, "missing expected scte35 daterange id"),
             Self::MissingScte35Message => write!(f, "missing expected scte35 message"),
             Self::MissingAssetListUrl => write!(f, "missing expected asset list url"),
+            Self::MissingPinnedLayout => write!(f, "missing expected pinned view layout"),
+            Self::UnknownPinnedLayout(s) => write!(f, "unknown pinned view layout: {s}"),
         }
     }
 }
@@ -457,6 +678,23 @@ impl Display for DecodeDefinitionsError {
 }
 impl Error for DecodeDefinitionsError {}
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecompressQueryError {
+    Base64(base64::DecodeError),
+    Inflate,
+    Utf8Error(FromUtf8Error),
+}
+impl Display for DecompressQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Base64(e) => write!(f, "invalid base64 in compressed query: {e}"),
+            Self::Inflate => write!(f, "compressed query could not be decompressed"),
+            Self::Utf8Error(e) => write!(f, "invalid utf-8 in decompressed query: {e}"),
+        }
+    }
+}
+impl Error for DecompressQueryError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -469,6 +707,28 @@ mod tests {
     const MS: u64 = 100;
     const BYTERANGE: RequestRange = RequestRange { start: 0, end: 100 };
 
+    #[test]
+    fn compressed_query_should_decompress_to_the_original_query() {
+        let search = format!(
+            "playlist_url={}&imported_definitions={}&supplemental_view_context={}",
+            percent_encode(URL_ENCODING_NEEDED),
+            percent_encode("a%3D1%22b%3D2"),
+            percent_encode(&format!("SEGMENT,{MS},-,{URL}")),
+        );
+        let compressed = compress_query(&search);
+        assert!(
+            compressed
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+            "{compressed} is not URL safe"
+        );
+        assert_eq!(Ok(search), decompress_query(&compressed));
+        assert_eq!(
+            Err(DecompressQueryError::Inflate),
+            decompress_query(&BASE64_URL_SAFE_NO_PAD.encode(b"not deflated"))
+        );
+    }
+
     macro_rules! assert_codec_equality {
         ($str:expr, $context:expr) => {
             let string = format!($str);
@@ -655,6 +915,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encode_scte35_should_encode_base64_message() {
+        assert_codec_equality!(
+            input: SupplementalViewQueryContext::Scte35(Scte35Context {
+                message: String::from(SCTE35_BASE64_MESSAGE),
+                daterange_id: String::from("0x20-3-1755721822"),
+                command_type: Scte35CommandType::Out
+            }),
+            encoded: "SCTE35,OUT,0x20-3-1755721822%22{SCTE35_BASE64_MESSAGE_PAYLOAD}%3D",
+            decoded: "SCTE35,OUT,0x20-3-1755721822\"{SCTE35_BASE64_MESSAGE}"
+        );
+    }
+
     #[test]
     fn encode_scte35_should_encode_and_percent_encode_id() {
         assert_codec_equality!(
@@ -732,6 +1005,70 @@ mod tests {
         assert_eq!(Ok(definitions), decode_definitions(&query_value));
     }
 
+    #[test]
+    fn encode_decode_pinned_view_should_nest_supplemental_context() {
+        let context = SupplementalViewQueryContext::Segment(MediaSegmentContext {
+            url: URL_ENCODING_NEEDED.to_string(),
+            media_sequence: MS,
+            byterange: Some(BYTERANGE),
+        });
+        let encoded = encode_pinned_view(PinnedViewLayout::Split, &context);
+        assert_eq!(
+            format!("SPLIT,SEGMENT,{MS},{BYTERANGE},{ENCODED_STR}"),
+            encoded
+        );
+        assert_eq!(
+            Ok(PinnedViewQueryContext {
+                layout: PinnedViewLayout::Split,
+                context
+            }),
+            PinnedViewQueryContext::try_from(percent_decode(&encoded).as_ref())
+        );
+        assert_eq!(
+            Err(SupplementalViewQueryContextDecodeError::UnknownPinnedLayout(String::from("SIDE"))),
+            PinnedViewQueryContext::try_from("SIDE,SEGMENT,0,-,https://example.com/0.mp4")
+        );
+    }
+
+    #[test]
+    fn decode_variant_context() {
+        assert_eq!(
+            VariantContext {
+                codecs: Some(String::from("avc1.64001f,mp4a.40.2")),
+                resolution: Some(Resolution {
+                    width: 1280,
+                    height: 720
+                }),
+                bandwidth: Some(1280000),
+                average_bandwidth: None,
+                frame_rate: None,
+            },
+            VariantContext::decode(
+                "BANDWIDTH=1280000,CODECS=\"avc1.64001f,mp4a.40.2\",RESOLUTION=1280x720"
+            )
+        );
+        assert_eq!(
+            VariantContext {
+                bandwidth: Some(1280000),
+                ..Default::default()
+            },
+            VariantContext::decode("BANDWIDTH=1280000")
+        );
+    }
+
+    #[test]
+    fn segment_selector_picks_the_segment() {
+        let segments = [(10, "a.ts"), (11, "b.ts"), (12, "c.ts")];
+        let select = |value| SegmentSelector::parse(value).and_then(|s| s.select(&segments));
+        assert_eq!(Some(&"b.ts"), select("msn:11"));
+        assert_eq!(None, select("msn:13"));
+        assert_eq!(Some(&"a.ts"), select("first"));
+        assert_eq!(Some(&"c.ts"), select("last"));
+        assert_eq!(None, SegmentSelector::parse("msn:"));
+        assert_eq!(None, SegmentSelector::parse("11"));
+        assert_eq!("msn:11", SegmentSelector::MediaSequence(11).to_string());
+    }
+
     fn definitions_from<const N: usize>(
         values: [(&'static str, &'static str); N],
     ) -> HashMap<String, String> {
@@ -750,4 +1087,9 @@ mod tests {
         "0xfc30390000000000000000c00506fe702f81fa0023022143554549000000037fbf0e1270636b5f455030343",
         "435303730333036393521040752f6e800",
     );
+    // The base64 message without its padding, which is percent encoded in the query.
+    const SCTE35_BASE64_MESSAGE_PAYLOAD: &str =
+        "/DAvAAAAAAAA///wFAVIAACPf+/+c2nALv4AUsz1AAAAAAAKAAhDVUVJAAABNWLbowo";
+    const SCTE35_BASE64_MESSAGE: &str =
+        "/DAvAAAAAAAA///wFAVIAACPf+/+c2nALv4AUsz1AAAAAAAKAAhDVUVJAAABNWLbowo=";
 }
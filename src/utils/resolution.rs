@@ -0,0 +1,238 @@
+use crate::utils::sample_entries::{SampleEntry, number_property};
+use std::fmt::Display;
+
+// Comparison of the RESOLUTION attribute of a variant against the dimensions described by the video
+// sample entries of its initialization segment.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Resolution {
+    /// Parses a decimal-resolution (e.g. `1280x720`) as used by the RESOLUTION attribute.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (width, height) = value.trim().split_once('x')?;
+        Some(Self {
+            width: width.parse().ok()?,
+            height: height.parse().ok()?,
+        })
+    }
+}
+
+impl Display for Resolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+/// The dimensions that the boxes of a video track describe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoTrackResolution {
+    /// The width and height of the visual sample entry. For AVC and HEVC these are the dimensions
+    /// after any cropping signalled in the parameter sets (e.g. 1920x1080 for a 1080p stream that
+    /// is coded as 1920x1088).
+    pub coded: Resolution,
+    /// The dimensions after cropping by the `clap` box, when present.
+    pub clean_aperture: Option<Resolution>,
+    /// The presentation dimensions from the `tkhd` box, which account for the pixel aspect ratio.
+    pub presentation: Option<Resolution>,
+}
+
+impl VideoTrackResolution {
+    fn displayed(&self) -> Resolution {
+        self.clean_aperture.unwrap_or(self.coded)
+    }
+}
+
+/// Provides the dimensions of each visual sample entry. The presentation dimensions are looked up
+/// by the `track_id` of the `tkhd` of the entry within `track_dimensions`, which provides the width
+/// and height of each `tkhd` as read from the box itself.
+pub fn video_track_resolutions(
+    entries: &[SampleEntry],
+    track_dimensions: &[(u32, Resolution)],
+) -> Vec<VideoTrackResolution> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let coded = Resolution {
+                width: number_property(entry.properties, "width")?,
+                height: number_property(entry.properties, "height")?,
+            };
+            let clean_aperture = entry.child("clap").and_then(|clap| {
+                let ratio = |n, d| {
+                    let n = number_property::<u32>(clap, n)?;
+                    let d = number_property::<u32>(clap, d).filter(|d| *d != 0)?;
+                    Some((f64::from(n) / f64::from(d)).round() as u32)
+                };
+                Some(Resolution {
+                    width: ratio("clean_aperture_width_n", "clean_aperture_width_d")?,
+                    height: ratio("clean_aperture_height_n", "clean_aperture_height_d")?,
+                })
+            });
+            let presentation = entry
+                .track_header
+                .and_then(|tkhd| number_property::<u32>(tkhd, "track_id"))
+                .and_then(|track_id| {
+                    track_dimensions
+                        .iter()
+                        .find(|(id, _)| *id == track_id)
+                        .map(|(_, dimensions)| *dimensions)
+                })
+                .filter(|resolution| resolution.width > 0 && resolution.height > 0);
+            Some(VideoTrackResolution {
+                coded,
+                clean_aperture,
+                presentation,
+            })
+        })
+        .collect()
+}
+
+/// Describes how the video track differs from the advertised RESOLUTION, if at all.
+pub fn resolution_problem(advertised: Resolution, actual: &VideoTrackResolution) -> Option<String> {
+    let displayed = actual.displayed();
+    if displayed == advertised || actual.presentation == Some(advertised) {
+        return None;
+    }
+    let coded = actual.coded;
+    match actual.clean_aperture {
+        Some(clean_aperture) if coded == advertised => Some(format!(
+            "RESOLUTION matches the coded size {coded}, but the clean aperture (clap) box crops \
+             the picture to {clean_aperture}."
+        )),
+        _ => match actual.presentation {
+            Some(presentation) if presentation != displayed => Some(format!(
+                "RESOLUTION is {advertised} but the video track is {displayed} (presented at \
+                 {presentation})."
+            )),
+            _ => Some(format!(
+                "RESOLUTION is {advertised} but the video track is {displayed}."
+            )),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        mp4_atom_properties::{AtomProperties, AtomPropertyValue, BasicPropertyValue},
+        sample_entries::sample_entries,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn track(
+        coded: (u32, u32),
+        clean_aperture: Option<(u32, u32)>,
+        presentation: Option<(u32, u32)>,
+    ) -> VideoTrackResolution {
+        let resolution = |(width, height)| Resolution { width, height };
+        VideoTrackResolution {
+            coded: resolution(coded),
+            clean_aperture: clean_aperture.map(resolution),
+            presentation: presentation.map(resolution),
+        }
+    }
+
+    #[test]
+    fn parse_resolution() {
+        assert_eq!(
+            Some(Resolution {
+                width: 1280,
+                height: 720
+            }),
+            Resolution::parse("1280x720")
+        );
+        assert_eq!(None, Resolution::parse("1280"));
+    }
+
+    #[test]
+    fn matching_resolution_has_no_problem() {
+        let advertised = Resolution::parse("1920x1080").unwrap();
+        assert_eq!(
+            None,
+            resolution_problem(advertised, &track((1920, 1080), None, Some((1920, 1080))))
+        );
+        assert_eq!(
+            None,
+            resolution_problem(advertised, &track((1920, 1088), Some((1920, 1080)), None))
+        );
+        // Anamorphic content is presented at a different width to the coded width.
+        assert_eq!(
+            None,
+            resolution_problem(advertised, &track((1440, 1080), None, Some((1920, 1080))))
+        );
+    }
+
+    #[test]
+    fn uncropped_sample_entry_is_reported() {
+        // The sample entry should carry the dimensions after the cropping in the SPS, so the
+        // padded size being there is a mismatch like any other.
+        assert_eq!(
+            Some(String::from(
+                "RESOLUTION is 1920x1080 but the video track is 1920x1088."
+            )),
+            resolution_problem(
+                Resolution::parse("1920x1080").unwrap(),
+                &track((1920, 1088), None, Some((1920, 1088)))
+            )
+        );
+    }
+
+    #[test]
+    fn mismatched_resolution_is_reported() {
+        assert_eq!(
+            Some(String::from(
+                "RESOLUTION is 1280x720 but the video track is 960x540."
+            )),
+            resolution_problem(
+                Resolution::parse("1280x720").unwrap(),
+                &track((960, 540), None, Some((960, 540)))
+            )
+        );
+    }
+
+    #[test]
+    fn presentation_is_taken_from_the_dimensions_of_the_track() {
+        let empty = AtomProperties {
+            box_name: "Test",
+            properties: Vec::new(),
+        };
+        let tkhd = AtomProperties {
+            box_name: "TrackHeaderBox",
+            properties: vec![(
+                "track_id".into(),
+                AtomPropertyValue::Basic(BasicPropertyValue::U32(2)),
+            )],
+        };
+        let avc1 = AtomProperties {
+            box_name: "AVCSampleEntryBox",
+            properties: vec![
+                (
+                    "width".into(),
+                    AtomPropertyValue::Basic(BasicPropertyValue::U16(1440)),
+                ),
+                (
+                    "height".into(),
+                    AtomPropertyValue::Basic(BasicPropertyValue::U16(1080)),
+                ),
+            ],
+        };
+        let entries = sample_entries(vec![
+            ("trak", 0, &empty),
+            ("tkhd", 1, &tkhd),
+            ("stsd", 1, &empty),
+            ("avc1", 2, &avc1),
+        ]);
+        let dimensions = |width, height| Resolution { width, height };
+        assert_eq!(
+            vec![track((1440, 1080), None, Some((1920, 1080)))],
+            video_track_resolutions(
+                &entries,
+                &[(1, dimensions(1280, 720)), (2, dimensions(1920, 1080))]
+            )
+        );
+    }
+}
@@ -3,13 +3,17 @@ use std::io::BufReader;
 use mp4_atom::{Atom, Ftyp, Header, Moof, ReadAtom, ReadFrom};
 use url::Url;
 
-use crate::utils::network::FetchArrayBufferResonse;
+use crate::utils::{
+    audio::{PackedAudioFormat, probe_packed_audio},
+    network::FetchArrayBufferResonse,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SegmentType {
     WebVtt,
     Mp4,
     Image,
+    PackedAudio(PackedAudioFormat),
     Unknown,
 }
 
@@ -33,7 +37,7 @@ fn probe_content_type(content_type: &Option<String>) -> Option<SegmentType> {
         "text/vtt" => Some(SegmentType::WebVtt),
         "text/plain" => Some(SegmentType::WebVtt),
         t if t.starts_with("image/") => Some(SegmentType::Image),
-        _ => None,
+        t => PackedAudioFormat::from_mime_type(t).map(SegmentType::PackedAudio),
     }
 }
 
@@ -49,7 +53,7 @@ fn probe_url(url: &str) -> Option<SegmentType> {
             "mp4" => Some(SegmentType::Mp4),
             "m4s" => Some(SegmentType::Mp4),
             "vtt" => Some(SegmentType::WebVtt),
-            _ => None,
+            s => PackedAudioFormat::from_extension(s).map(SegmentType::PackedAudio),
         })
 }
 
@@ -59,7 +63,7 @@ fn probe_data(data: &[u8]) -> Option<SegmentType> {
     } else if probe_is_mp4(data) {
         Some(SegmentType::Mp4)
     } else {
-        None
+        probe_packed_audio(data).map(SegmentType::PackedAudio)
     }
 }
 
@@ -96,6 +100,19 @@ fn probe_is_webvtt(data: &[u8]) -> bool {
     data.starts_with(b"WEBVTT")
 }
 
+/// Whether the data looks like an HLS playlist rather than a segment.
+pub fn probe_is_playlist(data: &[u8]) -> bool {
+    // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.1.1
+    //
+    // The EXTM3U tag indicates that the file is an Extended M3U Playlist file. It MUST be the first
+    // line of every Media Playlist and every Multivariant Playlist.
+    //
+    // Playlists are UTF-8, so as with WebVTT the only BOM to allow for is the UTF-8 one.
+    const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+    let data = data.strip_prefix(UTF8_BOM).unwrap_or(data);
+    data.trim_ascii_start().starts_with(b"#EXTM3U")
+}
+
 fn probe_is_mp4(data: &[u8]) -> bool {
     // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-3.1.2
     //
@@ -150,6 +167,15 @@ mod tests {
         assert_eq!(Some(SegmentType::WebVtt), probe_url(url));
     }
 
+    #[test]
+    fn probe_url_packed_audio_file_extension_should_work() {
+        let url = "https://example.com/file.aac";
+        assert_eq!(
+            Some(SegmentType::PackedAudio(PackedAudioFormat::Aac)),
+            probe_url(url)
+        );
+    }
+
     #[test]
     fn probe_url_m3u8_file_extension_should_not_work() {
         let url = "https://example.com/file.m3u8";
@@ -161,4 +187,13 @@ mod tests {
         let url = "https://example.com/file";
         assert_eq!(None, probe_url(url));
     }
+
+    #[test]
+    fn probe_is_playlist_should_only_match_playlists() {
+        assert!(probe_is_playlist(b"#EXTM3U\n#EXT-X-VERSION:7\n"));
+        assert!(probe_is_playlist(b"\xEF\xBB\xBF#EXTM3U\n"));
+        assert!(probe_is_playlist(b"\r\n#EXTM3U\n"));
+        assert!(!probe_is_playlist(b"WEBVTT\n\n"));
+        assert!(!probe_is_playlist(b"\x00\x00\x00\x18ftypiso6"));
+    }
 }
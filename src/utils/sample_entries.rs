@@ -0,0 +1,186 @@
+use crate::utils::mp4_atom_properties::{AtomProperties, AtomPropertyValue, BasicPropertyValue};
+
+// Collection of the sample entries (the children of `stsd`) from the boxes of an initialization
+// segment, so that what the media describes about itself can be compared against what the playlist
+// advertises for it.
+
+/// A sample entry along with the boxes that describe it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleEntry<'a> {
+    pub kind: &'a str,
+    pub properties: &'a AtomProperties,
+    /// All boxes nested within the sample entry (e.g. `avcC`, `clap`, `sinf`, `frma`), in file order.
+    pub children: Vec<(&'a str, &'a AtomProperties)>,
+    /// The `tkhd` of the track that the sample entry belongs to.
+    pub track_header: Option<&'a AtomProperties>,
+}
+
+impl<'a> SampleEntry<'a> {
+    pub fn child(&self, kind: &str) -> Option<&'a AtomProperties> {
+        self.children
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, properties)| *properties)
+    }
+}
+
+/// Finds the sample entries within the provided boxes. The boxes are expected in file order along
+/// with their depth in the box hierarchy, as presented in the ISOBMFF viewer.
+pub fn sample_entries<'a>(
+    atoms: impl IntoIterator<Item = (&'a str, usize, &'a AtomProperties)>,
+) -> Vec<SampleEntry<'a>> {
+    let mut entries = Vec::new();
+    let mut track_header = None;
+    let mut stsd_depth = None;
+    let mut current: Option<(usize, SampleEntry)> = None;
+    for (kind, depth, properties) in atoms {
+        if let Some((entry_depth, _)) = &current
+            && depth <= *entry_depth
+            && let Some((_, entry)) = current.take()
+        {
+            entries.push(entry);
+        }
+        if stsd_depth.is_some_and(|stsd_depth| depth <= stsd_depth) {
+            stsd_depth = None;
+        }
+        match kind {
+            "trak" => track_header = None,
+            "tkhd" => track_header = Some(properties),
+            "stsd" => stsd_depth = Some(depth),
+            _ if stsd_depth.is_some_and(|stsd_depth| depth == stsd_depth + 1) => {
+                current = Some((
+                    depth,
+                    SampleEntry {
+                        kind,
+                        properties,
+                        children: Vec::new(),
+                        track_header,
+                    },
+                ));
+            }
+            _ => {
+                if let Some((_, entry)) = &mut current {
+                    entry.children.push((kind, properties));
+                }
+            }
+        }
+    }
+    if let Some((_, entry)) = current {
+        entries.push(entry);
+    }
+    entries
+}
+
+fn property<'a>(properties: &'a AtomProperties, key: &str) -> Option<&'a BasicPropertyValue> {
+    properties
+        .properties
+        .iter()
+        .find(|(k, _)| k == key)
+        .and_then(|(_, value)| match value {
+            AtomPropertyValue::Basic(value) => Some(value),
+            AtomPropertyValue::Table(_) => None,
+        })
+}
+
+pub fn number_property<T: TryFrom<u64>>(properties: &AtomProperties, key: &str) -> Option<T> {
+    number(property(properties, key)?).and_then(|n| T::try_from(n).ok())
+}
+
+/// The numeric values of the column (identified by its header) of a table property. Cells that do
+/// not hold a number (e.g. optional values that are absent) are provided as `None`.
+pub fn table_column_numbers(
+    properties: &AtomProperties,
+    key: &str,
+    column: &str,
+) -> Option<Vec<Option<u64>>> {
+    let table = properties
+        .properties
+        .iter()
+        .find(|(k, _)| k == key)
+        .and_then(|(_, value)| match value {
+            AtomPropertyValue::Table(table) => Some(table),
+            AtomPropertyValue::Basic(_) => None,
+        })?;
+    let index = table.headers.as_ref()?.iter().position(|h| *h == column)?;
+    Some(
+        table
+            .rows
+            .iter()
+            .map(|row| row.get(index).and_then(number))
+            .collect(),
+    )
+}
+
+fn number(value: &BasicPropertyValue) -> Option<u64> {
+    match value {
+        BasicPropertyValue::U64(n) => Some(*n),
+        BasicPropertyValue::U32(n) => Some(u64::from(*n)),
+        BasicPropertyValue::U16(n) => Some(u64::from(*n)),
+        BasicPropertyValue::U8(n) => Some(u64::from(*n)),
+        BasicPropertyValue::Usize(n) => Some(*n as u64),
+        BasicPropertyValue::Bool(b) => Some(u64::from(*b)),
+        _ => None,
+    }
+}
+
+pub fn string_property(properties: &AtomProperties, key: &str) -> Option<String> {
+    match property(properties, key)? {
+        BasicPropertyValue::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sample_entries_are_collected_per_track() {
+        let empty = AtomProperties {
+            box_name: "Test",
+            properties: Vec::new(),
+        };
+        let video_tkhd = AtomProperties {
+            box_name: "TrackHeaderBox",
+            properties: Vec::new(),
+        };
+        let atoms = vec![
+            ("moov", 0, &empty),
+            ("trak", 1, &empty),
+            ("tkhd", 2, &video_tkhd),
+            ("mdia", 2, &empty),
+            ("minf", 3, &empty),
+            ("stbl", 4, &empty),
+            ("stsd", 5, &empty),
+            ("avc1", 6, &empty),
+            ("avcC", 7, &empty),
+            ("pasp", 7, &empty),
+            ("stts", 5, &empty),
+            ("trak", 1, &empty),
+            ("mdia", 2, &empty),
+            ("stsd", 3, &empty),
+            ("mp4a", 4, &empty),
+            ("esds", 5, &empty),
+        ];
+        let entries = sample_entries(atoms);
+        assert_eq!(
+            vec![
+                ("avc1", vec!["avcC", "pasp"], true),
+                ("mp4a", vec!["esds"], false),
+            ],
+            entries
+                .iter()
+                .map(|entry| (
+                    entry.kind,
+                    entry
+                        .children
+                        .iter()
+                        .map(|(k, _)| *k)
+                        .collect::<Vec<&str>>(),
+                    entry.track_header.is_some()
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+}
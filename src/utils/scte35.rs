@@ -0,0 +1,1110 @@
+use crate::utils::hex::{DecodeHexError, decode_hex, encode_hex};
+use base64::prelude::*;
+use std::{error::Error, fmt::Display};
+
+// A reading of the splice_info_section, both to validate the scheduling of breaks against the
+// EXT-X-DATERANGE that carries it and to break the message down field by field for display. The
+// reading is lenient: a message that stops short (or runs on) is read as far as it goes, so that
+// what it does hold can still be shown.
+//
+// SCTE 35 2023r1, Sect 9.6 (splice_info_section), Sect 9.7 (splice commands), Sect 9.8 (time) and
+// Sect 10.3.3 (segmentation_descriptor).
+
+/// The 90 kHz clock that splice times and durations are expressed in.
+pub const SCTE35_TIMESCALE: f64 = 90_000.0;
+
+const SPLICE_NULL: u8 = 0x00;
+const SPLICE_SCHEDULE: u8 = 0x04;
+const SPLICE_INSERT: u8 = 0x05;
+const TIME_SIGNAL: u8 = 0x06;
+const BANDWIDTH_RESERVATION: u8 = 0x07;
+const PRIVATE_COMMAND: u8 = 0xFF;
+/// A splice_command_length of 0xFFF is allowed by older versions of the standard to mean that the
+/// length is not given.
+const UNKNOWN_COMMAND_LENGTH: u16 = 0xFFF;
+const SEGMENTATION_DESCRIPTOR: u8 = 0x02;
+/// The identifier ("CUEI") of the descriptors defined by the standard.
+const CUEI: u32 = 0x43554549;
+const MID_UPID: u8 = 0x0D;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpliceInfoSection {
+    pub table_id: u8,
+    pub section_syntax_indicator: bool,
+    pub private_indicator: bool,
+    pub sap_type: u8,
+    pub section_length: u16,
+    pub protocol_version: u8,
+    pub encrypted_packet: bool,
+    pub encryption_algorithm: u8,
+    /// The offset in 90 kHz ticks that is added to every pts_time in the message.
+    pub pts_adjustment: u64,
+    pub cw_index: u8,
+    pub tier: u16,
+    pub splice_command_length: u16,
+    pub splice_command_type: u8,
+    pub command: SpliceCommand,
+    /// The descriptor_loop_length, when the message reaches it.
+    pub descriptor_loop_length: Option<u16>,
+    /// The position in the message of the descriptor_loop_length.
+    pub descriptor_loop_offset: Option<usize>,
+    pub descriptors: Vec<SpliceDescriptor>,
+    /// The CRC_32 at the end of the section, when the message reaches it.
+    pub crc_32: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpliceCommand {
+    Null,
+    /// A splice_schedule, which is not broken down any further.
+    Schedule(Vec<u8>),
+    Insert(SpliceInsert),
+    TimeSignal(SpliceTime),
+    BandwidthReservation,
+    Private {
+        identifier: u32,
+        private_bytes: Vec<u8>,
+    },
+    /// A command of a reserved type, or one that cannot be read (as it is encrypted or cut short).
+    Unreadable(Vec<u8>),
+}
+
+impl SpliceCommand {
+    /// The name of the command as given by the standard.
+    pub fn name(command_type: u8) -> &'static str {
+        match command_type {
+            SPLICE_NULL => "splice_null",
+            SPLICE_SCHEDULE => "splice_schedule",
+            SPLICE_INSERT => "splice_insert",
+            TIME_SIGNAL => "time_signal",
+            BANDWIDTH_RESERVATION => "bandwidth_reservation",
+            PRIVATE_COMMAND => "private_command",
+            _ => "reserved",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpliceInsert {
+    pub splice_event_id: u32,
+    pub splice_event_cancel: bool,
+    pub out_of_network: bool,
+    pub program_splice: bool,
+    pub splice_immediate: bool,
+    pub event_id_compliance: bool,
+    /// The splice_time of the program, which is only given in program splice mode when the splice
+    /// is not immediate.
+    pub splice_time: Option<SpliceTime>,
+    /// The component_tag of each component in component splice mode, along with its splice_time
+    /// when the splice is not immediate.
+    pub components: Vec<(u8, Option<SpliceTime>)>,
+    pub break_duration: Option<BreakDuration>,
+    pub unique_program_id: u16,
+    pub avail_num: u8,
+    pub avails_expected: u8,
+}
+
+/// A splice_time, where the pts_time (in 90 kHz ticks) is only given when time_specified_flag is
+/// set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpliceTime {
+    pub pts_time: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BreakDuration {
+    pub auto_return: bool,
+    /// The duration of the break in 90 kHz ticks.
+    pub duration: u64,
+}
+
+impl BreakDuration {
+    pub fn seconds(&self) -> f64 {
+        self.duration as f64 / SCTE35_TIMESCALE
+    }
+}
+
+/// A splice_descriptor, with the bytes that follow its identifier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpliceDescriptor {
+    pub splice_descriptor_tag: u8,
+    pub descriptor_length: u8,
+    pub identifier: u32,
+    pub data: Vec<u8>,
+}
+
+impl SpliceDescriptor {
+    /// Reads the descriptor as a segmentation_descriptor, when it is one.
+    pub fn segmentation(&self) -> Option<SegmentationDescriptor> {
+        if self.splice_descriptor_tag != SEGMENTATION_DESCRIPTOR || self.identifier != CUEI {
+            return None;
+        }
+        read_segmentation_descriptor(&mut self.data.as_slice())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentationDescriptor {
+    pub segmentation_event_id: u32,
+    pub segmentation_event_cancel: bool,
+    pub event_id_compliance: bool,
+    pub program_segmentation: bool,
+    /// The restrictions on delivery, unless delivery_not_restricted_flag is set.
+    pub delivery_restrictions: Option<DeliveryRestrictions>,
+    /// The component_tag and pts_offset (in 90 kHz ticks) of each component, when the segmentation
+    /// is not of the whole program.
+    pub components: Vec<(u8, u64)>,
+    /// The segmentation_duration in 90 kHz ticks.
+    pub segmentation_duration: Option<u64>,
+    pub upid: SegmentationUpid,
+    pub segmentation_type_id: u8,
+    pub segment_num: u8,
+    pub segments_expected: u8,
+    /// The sub_segment_num and sub_segments_expected, which may follow the placement opportunity
+    /// and ad block start types.
+    pub sub_segments: Option<(u8, u8)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeliveryRestrictions {
+    pub web_delivery_allowed: bool,
+    pub no_regional_blackout: bool,
+    pub archive_allowed: bool,
+    pub device_restrictions: u8,
+}
+
+impl DeliveryRestrictions {
+    pub fn device_restrictions_name(&self) -> &'static str {
+        match self.device_restrictions {
+            0 => "Restrict Group 0",
+            1 => "Restrict Group 1",
+            2 => "Restrict Group 2",
+            _ => "None",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentationUpid {
+    pub upid_type: u8,
+    pub upid: Vec<u8>,
+}
+
+impl SegmentationUpid {
+    /// The name of the segmentation_upid_type.
+    pub fn type_name(&self) -> &'static str {
+        upid_type_name(self.upid_type)
+    }
+
+    /// The UPID in the form that it is usually written for its type, falling back to hex where the
+    /// bytes do not fit the type.
+    pub fn describe(&self) -> String {
+        describe_upid(self.upid_type, &self.upid).unwrap_or_else(|| encode_hex(&self.upid))
+    }
+}
+
+/// The name of a segmentation_type_id.
+pub fn segmentation_type_name(segmentation_type_id: u8) -> &'static str {
+    match segmentation_type_id {
+        0x00 => "Not Indicated",
+        0x01 => "Content Identification",
+        0x02 => "Private",
+        0x10 => "Program Start",
+        0x11 => "Program End",
+        0x12 => "Program Early Termination",
+        0x13 => "Program Breakaway",
+        0x14 => "Program Resumption",
+        0x15 => "Program Runover Planned",
+        0x16 => "Program Runover Unplanned",
+        0x17 => "Program Overlap Start",
+        0x18 => "Program Blackout Override",
+        0x19 => "Program Join",
+        0x20 => "Chapter Start",
+        0x21 => "Chapter End",
+        0x22 => "Break Start",
+        0x23 => "Break End",
+        0x24 => "Opening Credit Start",
+        0x25 => "Opening Credit End",
+        0x26 => "Closing Credit Start",
+        0x27 => "Closing Credit End",
+        0x30 => "Provider Advertisement Start",
+        0x31 => "Provider Advertisement End",
+        0x32 => "Distributor Advertisement Start",
+        0x33 => "Distributor Advertisement End",
+        0x34 => "Provider Placement Opportunity Start",
+        0x35 => "Provider Placement Opportunity End",
+        0x36 => "Distributor Placement Opportunity Start",
+        0x37 => "Distributor Placement Opportunity End",
+        0x38 => "Provider Overlay Placement Opportunity Start",
+        0x39 => "Provider Overlay Placement Opportunity End",
+        0x3A => "Distributor Overlay Placement Opportunity Start",
+        0x3B => "Distributor Overlay Placement Opportunity End",
+        0x3C => "Provider Promo Start",
+        0x3D => "Provider Promo End",
+        0x3E => "Distributor Promo Start",
+        0x3F => "Distributor Promo End",
+        0x40 => "Unscheduled Event Start",
+        0x41 => "Unscheduled Event End",
+        0x42 => "Alternate Content Opportunity Start",
+        0x43 => "Alternate Content Opportunity End",
+        0x44 => "Provider Ad Block Start",
+        0x45 => "Provider Ad Block End",
+        0x46 => "Distributor Ad Block Start",
+        0x47 => "Distributor Ad Block End",
+        0x50 => "Network Start",
+        0x51 => "Network End",
+        _ => "Reserved",
+    }
+}
+
+/// The name of a segmentation_upid_type.
+pub fn upid_type_name(upid_type: u8) -> &'static str {
+    match upid_type {
+        0x00 => "Not Used",
+        0x01 => "User Defined",
+        0x02 => "ISCI",
+        0x03 => "Ad-ID",
+        0x04 => "UMID",
+        0x05 => "ISAN (deprecated)",
+        0x06 => "ISAN",
+        0x07 => "TID",
+        0x08 => "TI",
+        0x09 => "ADI",
+        0x0A => "EIDR",
+        0x0B => "ATSC Content Identifier",
+        0x0C => "MPU",
+        MID_UPID => "MID",
+        0x0E => "ADS Information",
+        0x0F => "URI",
+        0x10 => "UUID",
+        0x11 => "SCR",
+        _ => "Reserved",
+    }
+}
+
+/// How the message in an SCTE35-CMD, SCTE35-OUT or SCTE35-IN attribute is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scte35Encoding {
+    Hex,
+    Base64,
+}
+
+impl Display for Scte35Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hex => "hexadecimal-sequence".fmt(f),
+            Self::Base64 => "base64".fmt(f),
+        }
+    }
+}
+
+/// Decodes the value of an SCTE35-CMD, SCTE35-OUT or SCTE35-IN attribute.
+pub fn decode_attribute(value: &str) -> Option<Vec<u8>> {
+    decode_message(value).ok().map(|(_, message)| message)
+}
+
+/// Decodes the value of an SCTE35-CMD, SCTE35-OUT or SCTE35-IN attribute, along with how it was
+/// encoded. The specification calls for a hexadecimal-sequence, but some encoders write the
+/// base64 that SCTE 35 uses elsewhere (often as a quoted-string), so both are accepted.
+pub fn decode_message(value: &str) -> Result<(Scte35Encoding, Vec<u8>), Scte35AttributeError> {
+    if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        return decode_hex(hex)
+            .map(|message| (Scte35Encoding::Hex, message))
+            .map_err(Scte35AttributeError::Hex);
+    }
+    // Hex digits are all base64 characters too, so hex without its prefix is preferred while it
+    // starts with the table_id of a splice_info_section (0xFC), which would be "/" in base64.
+    let hex = decode_hex(value);
+    if let Ok(message) = &hex
+        && message.first() == Some(&0xFC)
+    {
+        return Ok((Scte35Encoding::Hex, message.clone()));
+    }
+    let base64 = BASE64_STANDARD
+        .decode(value)
+        .or_else(|_| BASE64_STANDARD_NO_PAD.decode(value));
+    match (hex, base64) {
+        (_, Ok(message)) => Ok((Scte35Encoding::Base64, message)),
+        (Ok(message), Err(_)) => Ok((Scte35Encoding::Hex, message)),
+        (Err(hex), Err(base64)) => Err(Scte35AttributeError::Neither { hex, base64 }),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scte35AttributeError {
+    Hex(DecodeHexError),
+    Neither {
+        hex: DecodeHexError,
+        base64: base64::DecodeError,
+    },
+}
+impl Display for Scte35AttributeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hex(e) => write!(f, "invalid hexadecimal-sequence: {e}"),
+            Self::Neither { hex, base64 } => write!(
+                f,
+                "neither a hexadecimal-sequence ({hex}) nor base64 ({base64})"
+            ),
+        }
+    }
+}
+impl Error for Scte35AttributeError {}
+
+/// Reads the splice_info_section, as long as it holds at least the header and the command.
+pub fn splice_info_section(section: &[u8]) -> Option<SpliceInfoSection> {
+    let mut bytes = section;
+    let header = take(&mut bytes, 14)?;
+    let encrypted_packet = header[4] & 0x80 != 0;
+    let splice_command_length = u16::from_be_bytes([header[11], header[12]]) & 0x0FFF;
+    let splice_command_type = header[13];
+    let command_bytes = match splice_command_length {
+        UNKNOWN_COMMAND_LENGTH => bytes,
+        length => bytes.get(..usize::from(length))?,
+    };
+    let (command, command_length) = if encrypted_packet {
+        (
+            SpliceCommand::Unreadable(command_bytes.to_vec()),
+            command_bytes.len(),
+        )
+    } else {
+        let mut command_reader = command_bytes;
+        let command = splice_command(splice_command_type, &mut command_reader)
+            .unwrap_or_else(|| SpliceCommand::Unreadable(command_bytes.to_vec()));
+        (command, command_bytes.len() - command_reader.len())
+    };
+    let mut section_info = SpliceInfoSection {
+        table_id: header[0],
+        section_syntax_indicator: header[1] & 0x80 != 0,
+        private_indicator: header[1] & 0x40 != 0,
+        sap_type: (header[1] >> 4) & 0x03,
+        section_length: u16::from_be_bytes([header[1], header[2]]) & 0x0FFF,
+        protocol_version: header[3],
+        encrypted_packet,
+        encryption_algorithm: (header[4] >> 1) & 0x3F,
+        pts_adjustment: read_33_bits(&header[4..9]),
+        cw_index: header[9],
+        tier: u16::from_be_bytes([header[10], header[11]]) >> 4,
+        splice_command_length,
+        splice_command_type,
+        command,
+        descriptor_loop_length: None,
+        descriptor_loop_offset: None,
+        descriptors: Vec::new(),
+        crc_32: None,
+    };
+    bytes = &bytes[command_length..];
+    let descriptor_loop_offset = section.len() - bytes.len();
+    // The descriptors of an encrypted message are encrypted along with its command.
+    if !encrypted_packet && let Some(length) = take(&mut bytes, 2) {
+        let length = u16::from_be_bytes([length[0], length[1]]) & 0x03FF;
+        section_info.descriptor_loop_length = Some(length);
+        section_info.descriptor_loop_offset = Some(descriptor_loop_offset);
+        let mut loop_bytes = &bytes[..usize::from(length).min(bytes.len())];
+        while let Some(descriptor) = splice_descriptor(&mut loop_bytes) {
+            section_info.descriptors.push(descriptor);
+        }
+    }
+    // The CRC is the last four bytes of the section, which should also be the last of the message.
+    let section_end = 3 + usize::from(section_info.section_length);
+    section_info.crc_32 = section_end
+        .checked_sub(4)
+        .and_then(|crc_start| section.get(crc_start..section_end))
+        .map(|crc| u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]));
+    Some(section_info)
+}
+
+/// The outcome of checking one of the lengths, or the CRC, of a splice_info_section against the
+/// message that carries it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionCheck {
+    pub field: &'static str,
+    pub passed: bool,
+    pub message: String,
+}
+
+impl SpliceInfoSection {
+    /// The id of the event that the message signals, which is the splice_event_id of a
+    /// splice_insert, or else the segmentation_event_id of its first segmentation_descriptor.
+    pub fn event_id(&self) -> Option<u32> {
+        if let SpliceCommand::Insert(insert) = &self.command {
+            return Some(insert.splice_event_id);
+        }
+        self.descriptors
+            .iter()
+            .find_map(SpliceDescriptor::segmentation)
+            .map(|segmentation| segmentation.segmentation_event_id)
+    }
+
+    /// Checks the section_length, descriptor_loop_length and CRC_32 against the message that the
+    /// section was read from, as a truncated or corrupted message is read as far as it goes.
+    pub fn checks(&self, section: &[u8]) -> Vec<SectionCheck> {
+        let mut checks = Vec::new();
+        let section_end = 3 + usize::from(self.section_length);
+        let crc_start = section_end.saturating_sub(4);
+        checks.push(if section.len() == section_end {
+            SectionCheck {
+                field: "section_length",
+                passed: true,
+                message: format!(
+                    "section_length of {} matches the {} bytes of the message",
+                    self.section_length,
+                    section.len()
+                ),
+            }
+        } else {
+            SectionCheck {
+                field: "section_length",
+                passed: false,
+                message: format!(
+                    "section_length of {} gives a message of {section_end} bytes, but the message \
+                     has {} bytes",
+                    self.section_length,
+                    section.len()
+                ),
+            }
+        });
+        if !self.encrypted_packet {
+            let check = |passed, message| SectionCheck {
+                field: "descriptor_loop_length",
+                passed,
+                message,
+            };
+            checks.push(
+                match self.descriptor_loop_length.zip(self.descriptor_loop_offset) {
+                    None => check(
+                        false,
+                        String::from("The message ends before the descriptor_loop_length"),
+                    ),
+                    Some((length, offset)) => {
+                        let loop_end = offset + 2 + usize::from(length);
+                        let read = self
+                            .descriptors
+                            .iter()
+                            .map(|descriptor| 2 + usize::from(descriptor.descriptor_length))
+                            .sum::<usize>();
+                        if loop_end != crc_start {
+                            check(
+                                false,
+                                format!(
+                                    "descriptor_loop_length of {length} ends the descriptors at \
+                                     byte {loop_end}, but the CRC_32 starts at byte {crc_start}"
+                                ),
+                            )
+                        } else if read != usize::from(length) {
+                            check(
+                                false,
+                                format!(
+                                    "The descriptors take {read} of the {length} bytes given by \
+                                     descriptor_loop_length"
+                                ),
+                            )
+                        } else {
+                            check(
+                                true,
+                                format!(
+                                    "descriptor_loop_length of {length} holds {} descriptor(s) \
+                                     and ends at the CRC_32",
+                                    self.descriptors.len()
+                                ),
+                            )
+                        }
+                    }
+                },
+            );
+        }
+        checks.push(match self.crc_32 {
+            None => SectionCheck {
+                field: "CRC_32",
+                passed: false,
+                message: String::from("The message ends before the CRC_32"),
+            },
+            Some(crc_32) => {
+                let calculated = crc_32_mpeg2(&section[..crc_start]);
+                SectionCheck {
+                    field: "CRC_32",
+                    passed: calculated == crc_32,
+                    message: if calculated == crc_32 {
+                        format!("CRC_32 of 0x{crc_32:08X} matches the message")
+                    } else {
+                        format!(
+                            "CRC_32 is 0x{crc_32:08X}, but the message gives 0x{calculated:08X}"
+                        )
+                    },
+                }
+            }
+        });
+        checks
+    }
+}
+
+/// The CRC-32 of MPEG-2 systems (ISO/IEC 13818-1 Annex A), which is not reflected and has no final
+/// XOR.
+pub fn crc_32_mpeg2(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0xFFFF_FFFF, |crc, byte| {
+        (0..8).fold(crc ^ (u32::from(*byte) << 24), |crc, _| {
+            if crc & 0x8000_0000 != 0 {
+                crc << 1 ^ 0x04C1_1DB7
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+/// Reads the splice_insert command from a splice_info_section. Returns `None` when the section
+/// carries a different command, is encrypted, or is too short to hold the command.
+pub fn splice_insert(section: &[u8]) -> Option<SpliceInsert> {
+    match splice_info_section(section)?.command {
+        SpliceCommand::Insert(insert) => Some(insert),
+        _ => None,
+    }
+}
+
+fn splice_command(command_type: u8, bytes: &mut &[u8]) -> Option<SpliceCommand> {
+    let command = match command_type {
+        SPLICE_NULL => SpliceCommand::Null,
+        SPLICE_SCHEDULE => SpliceCommand::Schedule(take(bytes, bytes.len())?.to_vec()),
+        SPLICE_INSERT => SpliceCommand::Insert(read_splice_insert(bytes)?),
+        TIME_SIGNAL => SpliceCommand::TimeSignal(splice_time(bytes)?),
+        BANDWIDTH_RESERVATION => SpliceCommand::BandwidthReservation,
+        PRIVATE_COMMAND => SpliceCommand::Private {
+            identifier: read_u32(bytes)?,
+            private_bytes: take(bytes, bytes.len())?.to_vec(),
+        },
+        _ => SpliceCommand::Unreadable(take(bytes, bytes.len())?.to_vec()),
+    };
+    Some(command)
+}
+
+fn read_splice_insert(bytes: &mut &[u8]) -> Option<SpliceInsert> {
+    let splice_event_id = read_u32(bytes)?;
+    let splice_event_cancel = take(bytes, 1)?[0] & 0x80 != 0;
+    let mut insert = SpliceInsert {
+        splice_event_id,
+        splice_event_cancel,
+        out_of_network: false,
+        program_splice: false,
+        splice_immediate: false,
+        event_id_compliance: false,
+        splice_time: None,
+        components: Vec::new(),
+        break_duration: None,
+        unique_program_id: 0,
+        avail_num: 0,
+        avails_expected: 0,
+    };
+    if splice_event_cancel {
+        return Some(insert);
+    }
+    let flags = take(bytes, 1)?[0];
+    insert.out_of_network = flags & 0x80 != 0;
+    insert.program_splice = flags & 0x40 != 0;
+    let duration_flag = flags & 0x20 != 0;
+    insert.splice_immediate = flags & 0x10 != 0;
+    insert.event_id_compliance = flags & 0x08 != 0;
+    if insert.program_splice && !insert.splice_immediate {
+        insert.splice_time = Some(splice_time(bytes)?);
+    }
+    if !insert.program_splice {
+        let component_count = take(bytes, 1)?[0];
+        for _ in 0..component_count {
+            let component_tag = take(bytes, 1)?[0];
+            let time = if insert.splice_immediate {
+                None
+            } else {
+                Some(splice_time(bytes)?)
+            };
+            insert.components.push((component_tag, time));
+        }
+    }
+    if duration_flag {
+        let duration = take(bytes, 5)?;
+        insert.break_duration = Some(BreakDuration {
+            auto_return: duration[0] & 0x80 != 0,
+            duration: read_33_bits(duration),
+        });
+    }
+    let avail = take(bytes, 4)?;
+    insert.unique_program_id = u16::from_be_bytes([avail[0], avail[1]]);
+    insert.avail_num = avail[2];
+    insert.avails_expected = avail[3];
+    Some(insert)
+}
+
+// splice_time() is a single byte when time_specified_flag is 0, and otherwise five bytes that end
+// with the 33 bit pts_time.
+fn splice_time(bytes: &mut &[u8]) -> Option<SpliceTime> {
+    let time_specified = bytes.first()? & 0x80 != 0;
+    if time_specified {
+        Some(SpliceTime {
+            pts_time: Some(read_33_bits(take(bytes, 5)?)),
+        })
+    } else {
+        take(bytes, 1)?;
+        Some(SpliceTime { pts_time: None })
+    }
+}
+
+fn splice_descriptor(bytes: &mut &[u8]) -> Option<SpliceDescriptor> {
+    let header = take(bytes, 2)?;
+    let descriptor_length = header[1];
+    let mut body = take(bytes, usize::from(descriptor_length))?;
+    Some(SpliceDescriptor {
+        splice_descriptor_tag: header[0],
+        descriptor_length,
+        identifier: read_u32(&mut body)?,
+        data: body.to_vec(),
+    })
+}
+
+fn read_segmentation_descriptor(bytes: &mut &[u8]) -> Option<SegmentationDescriptor> {
+    let segmentation_event_id = read_u32(bytes)?;
+    let flags = take(bytes, 1)?[0];
+    let mut descriptor = SegmentationDescriptor {
+        segmentation_event_id,
+        segmentation_event_cancel: flags & 0x80 != 0,
+        event_id_compliance: flags & 0x40 != 0,
+        program_segmentation: true,
+        delivery_restrictions: None,
+        components: Vec::new(),
+        segmentation_duration: None,
+        upid: SegmentationUpid {
+            upid_type: 0,
+            upid: Vec::new(),
+        },
+        segmentation_type_id: 0,
+        segment_num: 0,
+        segments_expected: 0,
+        sub_segments: None,
+    };
+    if descriptor.segmentation_event_cancel {
+        return Some(descriptor);
+    }
+    let flags = take(bytes, 1)?[0];
+    descriptor.program_segmentation = flags & 0x80 != 0;
+    let duration_flag = flags & 0x40 != 0;
+    if flags & 0x20 == 0 {
+        descriptor.delivery_restrictions = Some(DeliveryRestrictions {
+            web_delivery_allowed: flags & 0x10 != 0,
+            no_regional_blackout: flags & 0x08 != 0,
+            archive_allowed: flags & 0x04 != 0,
+            device_restrictions: flags & 0x03,
+        });
+    }
+    if !descriptor.program_segmentation {
+        let component_count = take(bytes, 1)?[0];
+        for _ in 0..component_count {
+            let component = take(bytes, 6)?;
+            descriptor
+                .components
+                .push((component[0], read_33_bits(&component[1..])));
+        }
+    }
+    if duration_flag {
+        let duration = take(bytes, 5)?;
+        descriptor.segmentation_duration = Some(
+            duration
+                .iter()
+                .fold(0, |value, byte| value << 8 | u64::from(*byte)),
+        );
+    }
+    let upid_type = take(bytes, 2)?;
+    descriptor.upid = SegmentationUpid {
+        upid_type: upid_type[0],
+        upid: take(bytes, usize::from(upid_type[1]))?.to_vec(),
+    };
+    let segmentation_type = take(bytes, 3)?;
+    descriptor.segmentation_type_id = segmentation_type[0];
+    descriptor.segment_num = segmentation_type[1];
+    descriptor.segments_expected = segmentation_type[2];
+    descriptor.sub_segments = take(bytes, 2).map(|sub_segments| (sub_segments[0], sub_segments[1]));
+    Some(descriptor)
+}
+
+fn describe_upid(upid_type: u8, upid: &[u8]) -> Option<String> {
+    let text = || {
+        std::str::from_utf8(upid)
+            .ok()
+            .filter(|text| text.chars().all(|c| !c.is_control()))
+            .map(String::from)
+    };
+    let grouped_hex = |group: usize, separator: &str| {
+        upid.chunks(group)
+            .map(encode_hex)
+            .collect::<Vec<_>>()
+            .join(separator)
+            .to_uppercase()
+    };
+    match upid_type {
+        // ISCI, Ad-ID, TID, ADI, ADS Information, URI and SCR are all character strings.
+        0x02 | 0x03 | 0x07 | 0x09 | 0x0E | 0x0F | 0x11 => text(),
+        0x04 if upid.len() == 32 => Some(grouped_hex(4, ".")),
+        0x05 if upid.len() == 8 => Some(grouped_hex(2, "-")),
+        0x06 if upid.len() == 12 => Some(grouped_hex(2, "-")),
+        0x08 if upid.len() == 8 => {
+            let airing_id = u64::from_be_bytes(upid.try_into().ok()?);
+            Some(format!("{airing_id} (0x{airing_id:016X})"))
+        }
+        0x0A if upid.len() == 12 => {
+            // The first two bytes are the sub-prefix of the DOI (10.5240 for EIDR), followed by the
+            // suffix, which is written with the check character of ISO 7064 Mod 37,36.
+            let prefix = u16::from_be_bytes([upid[0], upid[1]]);
+            let suffix = encode_hex(&upid[2..]).to_uppercase();
+            let groups = suffix
+                .as_bytes()
+                .chunks(4)
+                .map(|group| std::str::from_utf8(group).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("-");
+            Some(format!(
+                "10.{prefix}/{groups}-{}",
+                eidr_check_character(&suffix)
+            ))
+        }
+        0x0B if upid.len() >= 4 => {
+            let transport_stream_id = u16::from_be_bytes([upid[0], upid[1]]);
+            let end_of_day = (upid[2] >> 1) & 0x1F;
+            let unique_for = u16::from_be_bytes([upid[2], upid[3]]) & 0x01FF;
+            let content_id = String::from_utf8_lossy(&upid[4..]);
+            Some(format!(
+                "TSID {transport_stream_id}, end_of_day {end_of_day}, unique_for {unique_for}, \
+                 content_id {content_id}"
+            ))
+        }
+        0x0C if upid.len() >= 4 => {
+            let format_identifier = String::from_utf8_lossy(&upid[..4]);
+            Some(format!(
+                "format_identifier {format_identifier}, private_data {}",
+                encode_hex(&upid[4..])
+            ))
+        }
+        MID_UPID => {
+            let mut bytes = upid;
+            let mut upids = Vec::new();
+            while !bytes.is_empty() {
+                let header = take(&mut bytes, 2)?;
+                let inner = SegmentationUpid {
+                    upid_type: header[0],
+                    upid: take(&mut bytes, usize::from(header[1]))?.to_vec(),
+                };
+                upids.push(format!("{}: {}", inner.type_name(), inner.describe()));
+            }
+            Some(upids.join("; "))
+        }
+        0x10 if upid.len() == 16 => {
+            let hex = encode_hex(upid);
+            Some(format!(
+                "{}-{}-{}-{}-{}",
+                &hex[..8],
+                &hex[8..12],
+                &hex[12..16],
+                &hex[16..20],
+                &hex[20..]
+            ))
+        }
+        _ => None,
+    }
+}
+
+// ISO 7064 Mod 37,36 over the hex digits of the suffix.
+fn eidr_check_character(suffix: &str) -> char {
+    const ALPHABET: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let product = suffix
+        .chars()
+        .filter_map(|c| c.to_digit(36))
+        .fold(36, |product, value| {
+            let sum = match (product + value) % 36 {
+                0 => 36,
+                sum => sum,
+            };
+            sum * 2 % 37
+        });
+    char::from(ALPHABET[((37 - product) % 36) as usize])
+}
+
+fn read_u32(bytes: &mut &[u8]) -> Option<u32> {
+    Some(u32::from_be_bytes(take(bytes, 4)?.try_into().ok()?))
+}
+
+fn read_33_bits(bytes: &[u8]) -> u64 {
+    let high = u64::from(bytes[0] & 0x01) << 32;
+    high | u64::from(u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]))
+}
+
+fn take<'a>(bytes: &mut &'a [u8], count: usize) -> Option<&'a [u8]> {
+    if bytes.len() < count {
+        return None;
+    }
+    let (taken, rest) = bytes.split_at(count);
+    *bytes = rest;
+    Some(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn splice_insert_break_duration() {
+        // splice_insert out of network at pts_time 0 with a 30 second auto-return break.
+        let section = decode_attribute(
+            "0xFC302500000000000000FFF01405000000017FEFFE00000000FE002932E00000000000000000",
+        )
+        .unwrap();
+        assert_eq!(
+            Some(SpliceInsert {
+                splice_event_id: 1,
+                splice_event_cancel: false,
+                out_of_network: true,
+                program_splice: true,
+                splice_immediate: false,
+                event_id_compliance: true,
+                splice_time: Some(SpliceTime { pts_time: Some(0) }),
+                components: vec![],
+                break_duration: Some(BreakDuration {
+                    auto_return: true,
+                    duration: 2_700_000,
+                }),
+                unique_program_id: 0,
+                avail_num: 0,
+                avails_expected: 0,
+            }),
+            splice_insert(&section)
+        );
+        assert_eq!(
+            30.0,
+            splice_insert(&section)
+                .unwrap()
+                .break_duration
+                .unwrap()
+                .seconds()
+        );
+    }
+
+    #[test]
+    fn other_commands_are_ignored() {
+        // time_signal
+        let section = decode_attribute("FC301100000000000000FFF0050680000000000000000000").unwrap();
+        assert_eq!(None, splice_insert(&section));
+        assert_eq!(None, splice_insert(&section[..10]));
+    }
+
+    #[test]
+    fn splice_info_section_with_splice_insert() {
+        let section = decode_attribute(
+            "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF500000000000A000843554549\
+             0000013562DBA30A",
+        )
+        .unwrap();
+        assert_eq!(
+            Some(SpliceInfoSection {
+                table_id: 0xFC,
+                section_syntax_indicator: false,
+                private_indicator: false,
+                sap_type: 3,
+                section_length: 47,
+                protocol_version: 0,
+                encrypted_packet: false,
+                encryption_algorithm: 0,
+                pts_adjustment: 0,
+                cw_index: 0xFF,
+                tier: 0xFFF,
+                splice_command_length: 20,
+                splice_command_type: 5,
+                command: SpliceCommand::Insert(SpliceInsert {
+                    splice_event_id: 0x4800008F,
+                    splice_event_cancel: false,
+                    out_of_network: true,
+                    program_splice: true,
+                    splice_immediate: false,
+                    event_id_compliance: true,
+                    splice_time: Some(SpliceTime {
+                        pts_time: Some(0x7369C02E),
+                    }),
+                    components: vec![],
+                    break_duration: Some(BreakDuration {
+                        auto_return: true,
+                        duration: 0x52CCF5,
+                    }),
+                    unique_program_id: 0,
+                    avail_num: 0,
+                    avails_expected: 0,
+                }),
+                descriptor_loop_length: Some(10),
+                descriptor_loop_offset: Some(34),
+                descriptors: vec![SpliceDescriptor {
+                    splice_descriptor_tag: 0,
+                    descriptor_length: 8,
+                    identifier: 0x43554549,
+                    data: vec![0x00, 0x00, 0x01, 0x35],
+                }],
+                crc_32: Some(0x62DBA30A),
+            }),
+            splice_info_section(&section)
+        );
+    }
+
+    #[test]
+    fn splice_info_section_with_time_signal() {
+        let section = decode_attribute(
+            "0xFC3034000000000000FFFFF00506FE72BD0050001E021C435545494800008E7FCF0001A599B00808\
+             000000002CA0A18A3402009AC9D17E",
+        )
+        .unwrap();
+        let section = splice_info_section(&section).unwrap();
+        assert_eq!(
+            SpliceCommand::TimeSignal(SpliceTime {
+                pts_time: Some(0x72BD0050),
+            }),
+            section.command
+        );
+        assert_eq!(
+            "time_signal",
+            SpliceCommand::name(section.splice_command_type)
+        );
+        assert_eq!(Some(30), section.descriptor_loop_length);
+        assert_eq!(1, section.descriptors.len());
+        assert_eq!(2, section.descriptors[0].splice_descriptor_tag);
+        assert_eq!(Some(0x9AC9D17E), section.crc_32);
+    }
+
+    #[test]
+    fn segmentation_descriptor() {
+        let section = decode_attribute(
+            "0xFC3034000000000000FFFFF00506FE72BD0050001E021C435545494800008E7FCF0001A599B00808\
+             000000002CA0A18A3402009AC9D17E",
+        )
+        .unwrap();
+        let section = splice_info_section(&section).unwrap();
+        assert_eq!(
+            Some(SegmentationDescriptor {
+                segmentation_event_id: 0x4800008E,
+                segmentation_event_cancel: false,
+                event_id_compliance: true,
+                program_segmentation: true,
+                delivery_restrictions: Some(DeliveryRestrictions {
+                    web_delivery_allowed: false,
+                    no_regional_blackout: true,
+                    archive_allowed: true,
+                    device_restrictions: 3,
+                }),
+                components: vec![],
+                segmentation_duration: Some(27_630_000),
+                upid: SegmentationUpid {
+                    upid_type: 8,
+                    upid: vec![0x00, 0x00, 0x00, 0x00, 0x2C, 0xA0, 0xA1, 0x8A],
+                },
+                segmentation_type_id: 0x34,
+                segment_num: 2,
+                segments_expected: 0,
+                sub_segments: None,
+            }),
+            section.descriptors[0].segmentation()
+        );
+        assert_eq!(
+            "Provider Placement Opportunity Start",
+            segmentation_type_name(0x34)
+        );
+    }
+
+    #[test]
+    fn upids_are_described_by_type() {
+        let describe = |upid_type, upid: &[u8]| {
+            SegmentationUpid {
+                upid_type,
+                upid: upid.to_vec(),
+            }
+            .describe()
+        };
+        assert_eq!(
+            "748724618 (0x000000002CA0A18A)",
+            describe(0x08, &[0, 0, 0, 0, 0x2C, 0xA0, 0xA1, 0x8A])
+        );
+        assert_eq!("ABCD0001000H", describe(0x03, b"ABCD0001000H"));
+        assert_eq!(
+            "10.5240/7791-8534-2C23-9030-8610-5",
+            describe(
+                0x0A,
+                &[
+                    0x14, 0x78, 0x77, 0x91, 0x85, 0x34, 0x2C, 0x23, 0x90, 0x30, 0x86, 0x10
+                ]
+            )
+        );
+        assert_eq!(
+            "ADI: SIGNAL:abc; URI: urn:x",
+            describe(0x0D, b"\x09\x0ASIGNAL:abc\x0F\x05urn:x")
+        );
+        assert_eq!("0102", describe(0x08, &[1, 2]));
+    }
+
+    #[test]
+    fn lengths_and_crc_are_checked() {
+        let message = decode_attribute(
+            "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF500000000000A000843554549\
+             0000013562DBA30A",
+        )
+        .unwrap();
+        let passed = |section: &[u8]| {
+            splice_info_section(section)
+                .unwrap()
+                .checks(section)
+                .into_iter()
+                .map(|check| (check.field, check.passed))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                ("section_length", true),
+                ("descriptor_loop_length", true),
+                ("CRC_32", true)
+            ],
+            passed(&message)
+        );
+        let mut corrupted = message.clone();
+        corrupted[20] ^= 0x01;
+        assert_eq!(
+            vec![
+                ("section_length", true),
+                ("descriptor_loop_length", true),
+                ("CRC_32", false)
+            ],
+            passed(&corrupted)
+        );
+        assert_eq!(
+            vec![
+                ("section_length", false),
+                ("descriptor_loop_length", false),
+                ("CRC_32", false)
+            ],
+            passed(&message[..40])
+        );
+    }
+
+    #[test]
+    fn base64_messages_are_decoded() {
+        let hex = concat!(
+            "FC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF500000000000A000843554549",
+            "0000013562DBA30A"
+        );
+        let base64 = "/DAvAAAAAAAA///wFAVIAACPf+/+c2nALv4AUsz1AAAAAAAKAAhDVUVJAAABNWLbowo=";
+        let (encoding, message) = decode_message(hex).unwrap();
+        assert_eq!(Scte35Encoding::Hex, encoding);
+        assert_eq!(
+            Ok((Scte35Encoding::Base64, message.clone())),
+            decode_message(base64)
+        );
+        assert_eq!(
+            Ok((Scte35Encoding::Hex, message)),
+            decode_message(&format!("0x{hex}"))
+        );
+        assert!(matches!(
+            decode_message("not a message"),
+            Err(Scte35AttributeError::Neither { .. })
+        ));
+    }
+}
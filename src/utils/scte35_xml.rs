@@ -0,0 +1,333 @@
+// The XML representation of a splice_info_section given by the standard (section 14 of ANSI/SCTE
+// 35), which ad-ops tooling such as ESAM servers and SCTE-35 libraries take as input.
+//
+// Everything that the message is broken down into is written out, and what is not broken down (a
+// splice_schedule, or a descriptor other than the avail and segmentation descriptors) is left as a
+// comment with its bytes. UPIDs are always written in hexbinary format, which is valid for any
+// type.
+
+use crate::utils::{
+    hex::encode_hex,
+    scte35::{SpliceCommand, SpliceDescriptor, SpliceInfoSection, SpliceTime},
+};
+use std::fmt::Write;
+
+pub const XML_MIME_TYPE: &str = "application/xml";
+
+const SCTE35_NAMESPACE: &str = "http://www.scte.org/schemas/35";
+const AVAIL_DESCRIPTOR: u8 = 0x00;
+const CUEI: u32 = 0x43554549;
+
+pub fn scte35_xml(section: &SpliceInfoSection) -> String {
+    let mut xml = XmlWriter::default();
+    xml.open(
+        "SpliceInfoSection",
+        &[
+            ("xmlns", SCTE35_NAMESPACE.to_string()),
+            ("sapType", section.sap_type.to_string()),
+            ("ptsAdjustment", section.pts_adjustment.to_string()),
+            ("protocolVersion", section.protocol_version.to_string()),
+            ("tier", section.tier.to_string()),
+        ],
+    );
+    if section.encrypted_packet {
+        xml.empty(
+            "EncryptedPacket",
+            &[
+                (
+                    "encryptionAlgorithm",
+                    section.encryption_algorithm.to_string(),
+                ),
+                ("cwIndex", section.cw_index.to_string()),
+            ],
+        );
+    }
+    command(&mut xml, &section.command);
+    for descriptor in &section.descriptors {
+        splice_descriptor(&mut xml, descriptor);
+    }
+    xml.close("SpliceInfoSection");
+    xml.xml
+}
+
+fn command(xml: &mut XmlWriter, command: &SpliceCommand) {
+    match command {
+        SpliceCommand::Null => xml.empty("SpliceNull", &[]),
+        SpliceCommand::Schedule(bytes) => xml.comment(&format!("splice_schedule {}", hex(bytes))),
+        SpliceCommand::Insert(insert) => {
+            let mut attributes = vec![
+                ("spliceEventId", insert.splice_event_id.to_string()),
+                (
+                    "spliceEventCancelIndicator",
+                    insert.splice_event_cancel.to_string(),
+                ),
+            ];
+            if insert.splice_event_cancel {
+                xml.empty("SpliceInsert", &attributes);
+                return;
+            }
+            attributes.extend([
+                ("outOfNetworkIndicator", insert.out_of_network.to_string()),
+                ("spliceImmediateFlag", insert.splice_immediate.to_string()),
+                (
+                    "eventIdComplianceFlag",
+                    insert.event_id_compliance.to_string(),
+                ),
+                ("uniqueProgramId", insert.unique_program_id.to_string()),
+                ("availNum", insert.avail_num.to_string()),
+                ("availsExpected", insert.avails_expected.to_string()),
+            ]);
+            xml.open("SpliceInsert", &attributes);
+            if insert.program_splice {
+                match &insert.splice_time {
+                    Some(time) => {
+                        xml.open("Program", &[]);
+                        splice_time(xml, time);
+                        xml.close("Program");
+                    }
+                    None => xml.empty("Program", &[]),
+                }
+            }
+            for (tag, time) in &insert.components {
+                let attributes = [("componentTag", tag.to_string())];
+                match time {
+                    Some(time) => {
+                        xml.open("Component", &attributes);
+                        splice_time(xml, time);
+                        xml.close("Component");
+                    }
+                    None => xml.empty("Component", &attributes),
+                }
+            }
+            if let Some(duration) = &insert.break_duration {
+                xml.empty(
+                    "BreakDuration",
+                    &[
+                        ("autoReturn", duration.auto_return.to_string()),
+                        ("duration", duration.duration.to_string()),
+                    ],
+                );
+            }
+            xml.close("SpliceInsert");
+        }
+        SpliceCommand::TimeSignal(time) => {
+            xml.open("TimeSignal", &[]);
+            splice_time(xml, time);
+            xml.close("TimeSignal");
+        }
+        SpliceCommand::BandwidthReservation => xml.empty("BandwidthReservation", &[]),
+        SpliceCommand::Private {
+            identifier,
+            private_bytes,
+        } => {
+            xml.open("PrivateCommand", &[("identifier", identifier.to_string())]);
+            xml.text("PrivateBytes", &[], &hex(private_bytes));
+            xml.close("PrivateCommand");
+        }
+        SpliceCommand::Unreadable(bytes) => {
+            xml.comment(&format!("unreadable splice command {}", hex(bytes)));
+        }
+    }
+}
+
+fn splice_time(xml: &mut XmlWriter, time: &SpliceTime) {
+    match time.pts_time {
+        Some(pts_time) => xml.empty("SpliceTime", &[("ptsTime", pts_time.to_string())]),
+        None => xml.empty("SpliceTime", &[]),
+    }
+}
+
+fn splice_descriptor(xml: &mut XmlWriter, descriptor: &SpliceDescriptor) {
+    if let Some(segmentation) = descriptor.segmentation() {
+        let mut attributes = vec![
+            (
+                "segmentationEventId",
+                segmentation.segmentation_event_id.to_string(),
+            ),
+            (
+                "segmentationEventCancelIndicator",
+                segmentation.segmentation_event_cancel.to_string(),
+            ),
+            (
+                "segmentationEventIdComplianceIndicator",
+                segmentation.event_id_compliance.to_string(),
+            ),
+        ];
+        if segmentation.segmentation_event_cancel {
+            xml.empty("SegmentationDescriptor", &attributes);
+            return;
+        }
+        if let Some(duration) = segmentation.segmentation_duration {
+            attributes.push(("segmentationDuration", duration.to_string()));
+        }
+        attributes.extend([
+            (
+                "segmentationTypeId",
+                segmentation.segmentation_type_id.to_string(),
+            ),
+            ("segmentNum", segmentation.segment_num.to_string()),
+            (
+                "segmentsExpected",
+                segmentation.segments_expected.to_string(),
+            ),
+        ]);
+        if let Some((sub_segment_num, sub_segments_expected)) = segmentation.sub_segments {
+            attributes.extend([
+                ("subSegmentNum", sub_segment_num.to_string()),
+                ("subSegmentsExpected", sub_segments_expected.to_string()),
+            ]);
+        }
+        xml.open("SegmentationDescriptor", &attributes);
+        if let Some(restrictions) = &segmentation.delivery_restrictions {
+            xml.empty(
+                "DeliveryRestrictions",
+                &[
+                    (
+                        "webDeliveryAllowedFlag",
+                        restrictions.web_delivery_allowed.to_string(),
+                    ),
+                    (
+                        "noRegionalBlackoutFlag",
+                        restrictions.no_regional_blackout.to_string(),
+                    ),
+                    (
+                        "archiveAllowedFlag",
+                        restrictions.archive_allowed.to_string(),
+                    ),
+                    (
+                        "deviceRestrictions",
+                        restrictions.device_restrictions.to_string(),
+                    ),
+                ],
+            );
+        }
+        xml.text(
+            "SegmentationUpid",
+            &[
+                (
+                    "segmentationUpidType",
+                    segmentation.upid.upid_type.to_string(),
+                ),
+                ("segmentationUpidFormat", String::from("hexbinary")),
+            ],
+            &hex(&segmentation.upid.upid),
+        );
+        for (tag, pts_offset) in &segmentation.components {
+            xml.empty(
+                "Component",
+                &[
+                    ("componentTag", tag.to_string()),
+                    ("ptsOffset", pts_offset.to_string()),
+                ],
+            );
+        }
+        xml.close("SegmentationDescriptor");
+    } else if descriptor.splice_descriptor_tag == AVAIL_DESCRIPTOR
+        && descriptor.identifier == CUEI
+        && let Some(provider_avail_id) = descriptor.data.first_chunk::<4>()
+    {
+        xml.empty(
+            "AvailDescriptor",
+            &[(
+                "providerAvailId",
+                u32::from_be_bytes(*provider_avail_id).to_string(),
+            )],
+        );
+    } else {
+        xml.comment(&format!(
+            "splice_descriptor tag {}, identifier {}, data {}",
+            descriptor.splice_descriptor_tag,
+            descriptor.identifier,
+            hex(&descriptor.data)
+        ));
+    }
+}
+
+// Hex is written in upper case, as the hexBinary examples of the standard are.
+fn hex(bytes: &[u8]) -> String {
+    encode_hex(bytes).to_ascii_uppercase()
+}
+
+// The document is made up of numbers, booleans and hex alone, none of which needs escaping.
+#[derive(Default)]
+struct XmlWriter {
+    xml: String,
+    depth: usize,
+}
+
+impl XmlWriter {
+    fn open(&mut self, name: &str, attributes: &[(&str, String)]) {
+        self.start(name, attributes);
+        self.xml.push_str(">\n");
+        self.depth += 1;
+    }
+
+    fn empty(&mut self, name: &str, attributes: &[(&str, String)]) {
+        self.start(name, attributes);
+        self.xml.push_str("/>\n");
+    }
+
+    fn text(&mut self, name: &str, attributes: &[(&str, String)], text: &str) {
+        self.start(name, attributes);
+        let _ = writeln!(self.xml, ">{text}</{name}>");
+    }
+
+    fn close(&mut self, name: &str) {
+        self.depth -= 1;
+        self.indent();
+        let _ = writeln!(self.xml, "</{name}>");
+    }
+
+    fn comment(&mut self, comment: &str) {
+        self.indent();
+        let _ = writeln!(self.xml, "<!-- {comment} -->");
+    }
+
+    fn start(&mut self, name: &str, attributes: &[(&str, String)]) {
+        self.indent();
+        let _ = write!(self.xml, "<{name}");
+        for (name, value) in attributes {
+            let _ = write!(self.xml, " {name}=\"{value}\"");
+        }
+    }
+
+    fn indent(&mut self) {
+        self.xml.push_str(&"  ".repeat(self.depth));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::scte35::{decode_message, splice_info_section};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn time_signal_with_segmentation_descriptor() {
+        // A time_signal with a Provider Placement Opportunity Start.
+        let (_, bytes) = decode_message(
+            "/DA0AAAAAAAA///wBQb+cr0AUAAeAhxDVUVJSAAAjn/PAAGlmbAICAAAAAAsoKGKNAIAmsnRfg==",
+        )
+        .unwrap();
+        let section = splice_info_section(&bytes).unwrap();
+        assert_eq!(
+            "<SpliceInfoSection xmlns=\"http://www.scte.org/schemas/35\" sapType=\"3\" \
+             ptsAdjustment=\"0\" protocolVersion=\"0\" tier=\"4095\">\n\
+             \x20 <TimeSignal>\n\
+             \x20   <SpliceTime ptsTime=\"1924989008\"/>\n\
+             \x20 </TimeSignal>\n\
+             \x20 <SegmentationDescriptor segmentationEventId=\"1207959694\" \
+             segmentationEventCancelIndicator=\"false\" \
+             segmentationEventIdComplianceIndicator=\"true\" segmentationDuration=\"27630000\" \
+             segmentationTypeId=\"52\" segmentNum=\"2\" segmentsExpected=\"0\">\n\
+             \x20   <DeliveryRestrictions webDeliveryAllowedFlag=\"false\" \
+             noRegionalBlackoutFlag=\"true\" archiveAllowedFlag=\"true\" \
+             deviceRestrictions=\"3\"/>\n\
+             \x20   <SegmentationUpid segmentationUpidType=\"8\" \
+             segmentationUpidFormat=\"hexbinary\">000000002CA0A18A</SegmentationUpid>\n\
+             \x20 </SegmentationDescriptor>\n\
+             </SpliceInfoSection>\n",
+            scte35_xml(&section)
+        );
+    }
+}
@@ -0,0 +1,207 @@
+// The segments fetched during the session, kept up to a budget of memory so that opening a segment
+// again, or going between a segment and its initialization segment, does not download it all over
+// again. Responses are kept under the URL and byterange that they were requested with, and the
+// least recently used are let go first once the budget is reached. The budget is given with each
+// fetch, from the settings that the request is made with. A response is shared between the cache
+// and the views of it rather than copied, as a segment can run to megabytes.
+
+use crate::utils::{
+    byterange::RequestRange,
+    network::{
+        DownloadProgress, FetchArrayBufferResonse, FetchError, RequestOptions,
+        fetch_array_buffer_streamed, fetch_coalesced,
+    },
+    settings::Settings,
+    storage::{load_item, save_item},
+};
+use std::{cell::RefCell, sync::Arc};
+
+const SEGMENT_CACHE_KEY: &str = "segment_cache_megabytes";
+/// How much of the fetched segments is kept, unless changed in the settings. Zero means that
+/// nothing is kept.
+pub const DEFAULT_SEGMENT_CACHE_MEGABYTES: u32 = 64;
+const BYTES_PER_MEGABYTE: usize = 1024 * 1024;
+
+thread_local! {
+    static SEGMENT_CACHE: RefCell<LruCache<Arc<FetchArrayBufferResonse>>> =
+        const { RefCell::new(LruCache::new()) };
+}
+
+pub fn load_segment_cache_megabytes() -> u32 {
+    load_item(SEGMENT_CACHE_KEY)
+        .and_then(|megabytes| megabytes.parse().ok())
+        .unwrap_or(DEFAULT_SEGMENT_CACHE_MEGABYTES)
+}
+
+pub fn save_segment_cache_megabytes(megabytes: u32) {
+    save_item(SEGMENT_CACHE_KEY, &megabytes.to_string());
+}
+
+/// The response for the segment, if it is still cached.
+pub fn cached_segment(
+    request_url: &str,
+    byterange: Option<RequestRange>,
+) -> Option<Arc<FetchArrayBufferResonse>> {
+    SEGMENT_CACHE.with_borrow_mut(|cache| cache.get(request_url, byterange))
+}
+
+/// Fetches like [`fetch_array_buffer_streamed`], unless the segment is cached (in which case
+/// `on_progress` is never called).
+pub async fn fetch_segment(
+    request_url: String,
+    byterange: Option<RequestRange>,
+    options: impl Into<RequestOptions<'_>>,
+    on_progress: impl FnMut(&[u8], DownloadProgress),
+) -> Result<Arc<FetchArrayBufferResonse>, FetchError> {
+    if let Some(response) = cached_segment(&request_url, byterange) {
+        return Ok(response);
+    }
+    let options = options.into();
+    let response =
+        fetch_array_buffer_streamed(request_url.clone(), byterange, options, on_progress).await?;
+    Ok(cache_segment(
+        request_url,
+        byterange,
+        response,
+        options.settings,
+    ))
+}
+
+/// Fetches like [`fetch_coalesced`], unless both parts are cached. The parts are cached
+/// separately, so that either can be found again when opened on its own.
+pub async fn fetch_segment_with_init(
+    request_url: String,
+    init_byterange: RequestRange,
+    byterange: RequestRange,
+    options: impl Into<RequestOptions<'_>>,
+) -> Result<(Arc<FetchArrayBufferResonse>, Arc<FetchArrayBufferResonse>), FetchError> {
+    if let (Some(init), Some(segment)) = (
+        cached_segment(&request_url, Some(init_byterange)),
+        cached_segment(&request_url, Some(byterange)),
+    ) {
+        return Ok((init, segment));
+    }
+    let options = options.into();
+    let (init, segment) =
+        fetch_coalesced(request_url.clone(), init_byterange, byterange, options).await?;
+    Ok((
+        cache_segment(
+            request_url.clone(),
+            Some(init_byterange),
+            init,
+            options.settings,
+        ),
+        cache_segment(request_url, Some(byterange), segment, options.settings),
+    ))
+}
+
+fn cache_segment(
+    request_url: String,
+    byterange: Option<RequestRange>,
+    response: FetchArrayBufferResonse,
+    settings: &Settings,
+) -> Arc<FetchArrayBufferResonse> {
+    let response = Arc::new(response);
+    // A body that stops short of the media data is not the segment, so is not kept in its place.
+    if response.body_truncated {
+        return response;
+    }
+    let budget = settings.segment_cache_megabytes as usize * BYTES_PER_MEGABYTE;
+    SEGMENT_CACHE.with_borrow_mut(|cache| {
+        cache.insert(
+            request_url,
+            byterange,
+            Arc::clone(&response),
+            response.response_body.len(),
+            budget,
+        )
+    });
+    response
+}
+
+struct LruCache<V> {
+    /// The least recently used entry first.
+    entries: Vec<CacheEntry<V>>,
+}
+
+struct CacheEntry<V> {
+    url: String,
+    byterange: Option<RequestRange>,
+    size: usize,
+    value: V,
+}
+
+impl<V: Clone> LruCache<V> {
+    const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    fn get(&mut self, url: &str, byterange: Option<RequestRange>) -> Option<V> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.url == url && entry.byterange == byterange)?;
+        let entry = self.entries.remove(index);
+        let value = entry.value.clone();
+        self.entries.push(entry);
+        Some(value)
+    }
+
+    fn insert(
+        &mut self,
+        url: String,
+        byterange: Option<RequestRange>,
+        value: V,
+        size: usize,
+        budget: usize,
+    ) {
+        self.entries
+            .retain(|entry| entry.url != url || entry.byterange != byterange);
+        if size > budget {
+            return;
+        }
+        let mut used = self.entries.iter().map(|entry| entry.size).sum::<usize>();
+        while used + size > budget {
+            used -= self.entries.remove(0).size;
+        }
+        self.entries.push(CacheEntry {
+            url,
+            byterange,
+            size,
+            value,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn urls(cache: &LruCache<u8>) -> Vec<&str> {
+        cache
+            .entries
+            .iter()
+            .map(|entry| entry.url.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_let_go_first() {
+        let mut cache = LruCache::new();
+        cache.insert(String::from("a"), None, 1, 4, 10);
+        cache.insert(String::from("b"), None, 2, 4, 10);
+        assert_eq!(Some(1), cache.get("a", None));
+        cache.insert(String::from("c"), None, 3, 4, 10);
+        assert_eq!(vec!["a", "c"], urls(&cache));
+        assert_eq!(None, cache.get("b", None));
+        // The same URL with another byterange is another entry.
+        let range = RequestRange::from_length_with_offset(4, 0);
+        assert_eq!(None, cache.get("a", Some(range)));
+        // An entry larger than the budget is not kept, and does not clear the cache to make room.
+        cache.insert(String::from("d"), None, 4, 11, 10);
+        assert_eq!(vec!["a", "c"], urls(&cache));
+    }
+}
@@ -0,0 +1,146 @@
+// Long VOD playlists are mostly made up of plain EXTINF and URI pairs, which say little on their
+// own. Runs of them are found here so that the viewer can collapse them, while every other line
+// (dateranges, keys, discontinuities, and so on) breaks a run and so always remains visible.
+
+use crate::utils::playlist_lines::{LineKind, parse};
+use std::ops::Range;
+
+/// The fewest consecutive plain segments that are collapsed. The first and last segment of a run
+/// are left visible, so that it is clear where the run starts and ends.
+const MIN_COLLAPSED_SEGMENTS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentRun {
+    /// The 0-based index of the first collapsed line.
+    pub start: usize,
+    pub line_count: usize,
+    /// The total duration of the collapsed segments in seconds.
+    pub duration: f64,
+    /// The Media Sequence Numbers of the collapsed segments.
+    pub media_sequences: Range<u64>,
+}
+
+impl SegmentRun {
+    pub fn segments(&self) -> usize {
+        self.line_count / 2
+    }
+}
+
+/// The runs of plain segments in the playlist that can be collapsed.
+pub fn collapsible_runs(playlist: &str) -> Vec<SegmentRun> {
+    let lines = parse(playlist);
+    let mut runs = Vec::new();
+    let mut media_sequence = 0;
+    // The index of the first line of the current run, its first Media Sequence Number, and the
+    // duration of each segment in it.
+    let mut current: Option<(usize, u64, Vec<f64>)> = None;
+    let mut index = 0;
+    while index < lines.len() {
+        match (
+            &lines[index].kind,
+            lines.get(index + 1).map(|line| &line.kind),
+        ) {
+            (LineKind::Tag(tag), Some(LineKind::Uri(_))) if tag.name == "EXTINF" => {
+                let duration = tag
+                    .value
+                    .and_then(|value| value.split(',').next())
+                    .and_then(|duration| duration.trim().parse().ok())
+                    .unwrap_or_default();
+                current
+                    .get_or_insert_with(|| (index, media_sequence, Vec::new()))
+                    .2
+                    .push(duration);
+                media_sequence += 1;
+                index += 2;
+                continue;
+            }
+            (LineKind::Tag(tag), _) if tag.name == "EXT-X-MEDIA-SEQUENCE" => {
+                media_sequence = tag
+                    .value
+                    .and_then(|value| value.trim().parse().ok())
+                    .unwrap_or_default();
+            }
+            (LineKind::Uri(_), _) => media_sequence += 1,
+            _ => (),
+        }
+        runs.extend(current.take().and_then(collapsed_run));
+        index += 1;
+    }
+    runs.extend(current.and_then(collapsed_run));
+    runs
+}
+
+fn collapsed_run(run: (usize, u64, Vec<f64>)) -> Option<SegmentRun> {
+    let (start, media_sequence, durations) = run;
+    if durations.len() < MIN_COLLAPSED_SEGMENTS {
+        return None;
+    }
+    let collapsed = &durations[1..durations.len() - 1];
+    let first = media_sequence + 1;
+    Some(SegmentRun {
+        start: start + 2,
+        line_count: collapsed.len() * 2,
+        duration: collapsed.iter().sum(),
+        media_sequences: first..first + collapsed.len() as u64,
+    })
+}
+
+/// Formats a duration in seconds to the nearest second (e.g. `50m 12s`).
+pub fn format_duration(seconds: f64) -> String {
+    let total = seconds.round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes}m {seconds}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn segments(count: usize) -> String {
+        (0..count)
+            .map(|index| format!("#EXTINF:6.0,\nsegment{index}.ts\n"))
+            .collect()
+    }
+
+    #[test]
+    fn runs_of_plain_segments_are_collapsed() {
+        let playlist = format!(
+            "#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:100\n{}#EXT-X-DISCONTINUITY\n{}",
+            segments(25),
+            segments(5)
+        );
+        assert_eq!(
+            vec![SegmentRun {
+                start: 4,
+                line_count: 46,
+                duration: 138.0,
+                media_sequences: 101..124,
+            }],
+            collapsible_runs(&playlist)
+        );
+    }
+
+    #[test]
+    fn runs_are_broken_by_other_lines() {
+        let playlist = format!(
+            "#EXTM3U\n{}#EXT-X-KEY:METHOD=NONE\n{}",
+            segments(15),
+            segments(15)
+        );
+        assert_eq!(Vec::<SegmentRun>::new(), collapsible_runs(&playlist));
+    }
+
+    #[test]
+    fn durations_are_formatted_to_the_second() {
+        assert_eq!("50m 12s", format_duration(3011.6));
+        assert_eq!("1h 0m 5s", format_duration(3605.0));
+        assert_eq!("6s", format_duration(6.006));
+    }
+}
@@ -0,0 +1,332 @@
+// Session bundles (as exported by `har::session_bundle`) imported again, so that the requests of
+// the viewer are answered from the bundle rather than the network. Where the offline cache keeps a
+// live incident for this browser alone, a bundle lets it be looked into on another device.
+//
+// A request is answered by the responses recorded for the same method, URL and Range, in the
+// order that they were recorded, so that rechecking a live playlist steps through its snapshots.
+// Once they run out, the last one is given again. While a bundle is replayed nothing else is
+// fetched, and the offline cache is neither read nor added to, so that what the viewer shows is
+// only what was captured.
+
+use crate::utils::{
+    byterange::RequestRange,
+    network::{FetchError, ResponseHeaders},
+};
+use base64::prelude::*;
+use serde_json::Value;
+use std::{cell::RefCell, collections::HashMap};
+
+thread_local! {
+    static REPLAY: RefCell<Option<Replay>> = const { RefCell::new(None) };
+}
+
+/// The requests of an imported session bundle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionBundle {
+    /// The URL of the viewer when the bundle was exported.
+    pub viewer_url: Option<String>,
+    entries: Vec<(RequestKey, Recorded)>,
+}
+
+impl SessionBundle {
+    pub fn request_count(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// A response of the bundle, given in place of the response to a fetch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundledResponse {
+    pub headers: ResponseHeaders,
+    pub body: Vec<u8>,
+    /// The time to first byte and the time taken to download the body when the response was
+    /// recorded, in milliseconds.
+    pub wait: f64,
+    pub receive: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RequestKey {
+    method: String,
+    url: String,
+    /// The value of the Range header of the request.
+    range: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Recorded {
+    /// The status line and headers of the response, or the error that the request failed with.
+    response: Result<ResponseHeaders, String>,
+    /// The body of the response, when it was kept.
+    body: Option<Vec<u8>>,
+    wait: f64,
+    receive: f64,
+}
+
+pub fn parse_session_bundle(text: &str) -> Result<SessionBundle, String> {
+    let har = serde_json::from_str::<Value>(text).map_err(|e| format!("not a HAR file: {e}"))?;
+    let log = &har["log"];
+    let entries = log["entries"]
+        .as_array()
+        .ok_or_else(|| String::from("not a HAR file: there is no log of entries"))?;
+    Ok(SessionBundle {
+        viewer_url: log["_viewerUrl"].as_str().map(String::from),
+        entries: entries.iter().filter_map(bundle_entry).collect(),
+    })
+}
+
+fn bundle_entry(entry: &Value) -> Option<(RequestKey, Recorded)> {
+    let request = &entry["request"];
+    // Requests made through the proxy are asked for again under the URL that they were made for.
+    let url = entry["_originalUrl"]
+        .as_str()
+        .or_else(|| request["url"].as_str())?;
+    let headers = name_value_pairs(&request["headers"]);
+    let range = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("Range"))
+        .map(|(_, value)| value.clone());
+    let key = RequestKey {
+        method: request["method"].as_str().unwrap_or("GET").to_string(),
+        url: url.to_string(),
+        range,
+    };
+    let response = &entry["response"];
+    let content = &response["content"];
+    let body = content["text"]
+        .as_str()
+        .and_then(|text| match content["encoding"].as_str() {
+            Some("base64") => BASE64_STANDARD.decode(text).ok(),
+            _ => Some(text.as_bytes().to_vec()),
+        });
+    let status = response["status"].as_u64().unwrap_or_default();
+    let response = match entry["_error"].as_str() {
+        Some(error) if status == 0 => Err(error.to_string()),
+        _ => Ok(ResponseHeaders {
+            url: url.to_string(),
+            status: u16::try_from(status).unwrap_or_default(),
+            status_text: response["statusText"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            headers: name_value_pairs(&response["headers"]),
+        }),
+    };
+    let timing = |name: &str| entry["timings"][name].as_f64().unwrap_or_default().max(0.0);
+    Some((
+        key,
+        Recorded {
+            response,
+            body,
+            wait: timing("wait"),
+            receive: timing("receive"),
+        },
+    ))
+}
+
+fn name_value_pairs(pairs: &Value) -> Vec<(String, String)> {
+    pairs
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|pair| {
+            Some((
+                pair["name"].as_str()?.to_string(),
+                pair["value"].as_str()?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Answers requests from the bundle from now on, in place of the network.
+pub fn replay(bundle: SessionBundle) {
+    REPLAY.set(Some(Replay::new(bundle)));
+}
+
+pub fn stop_replay() {
+    REPLAY.set(None);
+}
+
+/// The response to the request from the bundle being replayed, if any is. A request that the
+/// bundle has no response for fails, as does one whose body was not kept.
+pub fn replayed_response(
+    method: &str,
+    url: &str,
+    byterange: Option<RequestRange>,
+) -> Option<Result<BundledResponse, FetchError>> {
+    let key = RequestKey {
+        method: method.to_string(),
+        url: url.to_string(),
+        range: byterange.map(|byterange| byterange.range_header_value()),
+    };
+    REPLAY.with_borrow_mut(|replay| Some(replay.as_mut()?.next(&key)))
+}
+
+struct Replay {
+    /// The responses recorded for each request, with the index of the next one to give.
+    responses: HashMap<RequestKey, (Vec<Recorded>, usize)>,
+}
+
+impl Replay {
+    fn new(bundle: SessionBundle) -> Self {
+        let mut responses = HashMap::<RequestKey, (Vec<Recorded>, usize)>::new();
+        for (key, recorded) in bundle.entries {
+            responses.entry(key).or_default().0.push(recorded);
+        }
+        Self { responses }
+    }
+
+    fn next(&mut self, key: &RequestKey) -> Result<BundledResponse, FetchError> {
+        let not_in_bundle = |what: &str| FetchError::Other {
+            message: format!("Error: {what} {} is not in the imported session", key.url),
+        };
+        let (recorded, next) = self
+            .responses
+            .get_mut(key)
+            .ok_or_else(|| not_in_bundle("the response to"))?;
+        let index = (*next).min(recorded.len() - 1);
+        *next = index + 1;
+        let Recorded {
+            response,
+            body,
+            wait,
+            receive,
+        } = recorded[index].clone();
+        // The error was recorded as it was shown, so it is given back as it is.
+        let headers = response.map_err(|message| FetchError::Other { message })?;
+        let body = match body {
+            Some(body) => body,
+            None if key.method == "HEAD" || headers.status == 304 => Vec::new(),
+            None => return Err(not_in_bundle("the body of")),
+        };
+        Ok(BundledResponse {
+            headers,
+            body,
+            wait,
+            receive,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const BUNDLE: &str = r##"{
+        "log": {
+            "_viewerUrl": "/hls-manifest-viewer?playlist_url=https%3A%2F%2Fexample.com%2Flive.m3u8",
+            "entries": [
+                {
+                    "request": { "method": "GET", "url": "https://example.com/live.m3u8" },
+                    "response": {
+                        "status": 200,
+                        "statusText": "OK",
+                        "headers": [{ "name": "etag", "value": "\"1\"" }],
+                        "content": { "text": "#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:1\n" }
+                    },
+                    "timings": { "send": 0, "wait": 20, "receive": 5 }
+                },
+                {
+                    "request": {
+                        "method": "GET",
+                        "url": "https://proxy.example.com/?url=https%3A%2F%2Fexample.com%2F1.m4s",
+                        "headers": [{ "name": "Range", "value": "bytes=0-3" }]
+                    },
+                    "response": {
+                        "status": 206,
+                        "statusText": "Partial Content",
+                        "headers": [],
+                        "content": { "text": "AAAACA==", "encoding": "base64" }
+                    },
+                    "timings": { "send": 0, "wait": 40, "receive": 10 },
+                    "_originalUrl": "https://example.com/1.m4s"
+                },
+                {
+                    "request": { "method": "GET", "url": "https://example.com/live.m3u8" },
+                    "response": {
+                        "status": 200,
+                        "statusText": "OK",
+                        "headers": [],
+                        "content": { "text": "#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:2\n" }
+                    },
+                    "timings": { "send": 0, "wait": 20, "receive": 5 }
+                },
+                {
+                    "request": { "method": "GET", "url": "https://example.com/2.m4s" },
+                    "response": { "status": 0, "statusText": "", "headers": [], "content": {} },
+                    "timings": { "send": 0, "wait": 0, "receive": 0 },
+                    "_error": "Network error: Failed to fetch"
+                }
+            ]
+        }
+    }"##;
+
+    fn replay() -> Replay {
+        Replay::new(parse_session_bundle(BUNDLE).unwrap())
+    }
+
+    fn get(url: &str, range: Option<&str>) -> RequestKey {
+        RequestKey {
+            method: String::from("GET"),
+            url: url.to_string(),
+            range: range.map(String::from),
+        }
+    }
+
+    #[test]
+    fn snapshots_are_given_in_the_order_recorded() {
+        let mut replay = replay();
+        let live = get("https://example.com/live.m3u8", None);
+        let mut next_body = || String::from_utf8(replay.next(&live).unwrap().body).unwrap();
+        assert_eq!("#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:1\n", next_body());
+        assert_eq!("#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:2\n", next_body());
+        assert_eq!("#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:2\n", next_body());
+    }
+
+    #[test]
+    fn requests_are_matched_by_url_and_range() {
+        let bundle = parse_session_bundle(BUNDLE).unwrap();
+        assert_eq!(
+            Some("/hls-manifest-viewer?playlist_url=https%3A%2F%2Fexample.com%2Flive.m3u8"),
+            bundle.viewer_url.as_deref()
+        );
+        assert_eq!(4, bundle.request_count());
+        let mut replay = Replay::new(bundle);
+        let segment = replay
+            .next(&get("https://example.com/1.m4s", Some("bytes=0-3")))
+            .unwrap();
+        assert_eq!(
+            BundledResponse {
+                headers: ResponseHeaders {
+                    url: String::from("https://example.com/1.m4s"),
+                    status: 206,
+                    status_text: String::from("Partial Content"),
+                    headers: Vec::new(),
+                },
+                body: vec![0, 0, 0, 8],
+                wait: 40.0,
+                receive: 10.0,
+            },
+            segment
+        );
+        assert!(
+            replay
+                .next(&get("https://example.com/1.m4s", Some("bytes=4-7")))
+                .is_err()
+        );
+        assert!(
+            replay
+                .next(&get("https://example.com/1.m4s", None))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn failed_requests_fail_again() {
+        let error = replay()
+            .next(&get("https://example.com/2.m4s", None))
+            .unwrap_err();
+        assert_eq!("Network error: Failed to fetch", error.to_string());
+    }
+}
@@ -0,0 +1,192 @@
+// The preferences that apply across the app, which the settings page gathers in one place. Each is
+// kept in local storage under its own key (the same key that any control for it elsewhere in the
+// app uses), so that a change made in either place is seen in the other. The app provides them as
+// context, from which the components that make requests pass them on to the network layer.
+
+use crate::utils::{
+    network::{load_fetch_timeout_seconds, save_fetch_timeout_seconds},
+    offline_cache::{OfflineCacheMode, load_offline_cache_mode, save_offline_cache_mode},
+    proxy::{ProxyConfig, load_proxy_config, save_proxy_config},
+    segment_cache::{load_segment_cache_megabytes, save_segment_cache_megabytes},
+    storage::{load_item, save_item},
+};
+
+const REQUEST_HEADERS_KEY: &str = "request_headers";
+const THEME_KEY: &str = "theme";
+const LIVE_RECHECK_KEY: &str = "live_recheck_seconds";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub proxy: ProxyConfig,
+    /// Headers added to every request, such as an authorization token that the origin requires.
+    pub request_headers: Vec<(String, String)>,
+    /// How long each attempt at a request may take (0 for no timeout).
+    pub fetch_timeout_seconds: u32,
+    /// How much of the fetched segments is kept for opening them again (0 to keep none).
+    pub segment_cache_megabytes: u32,
+    /// Whether fetched responses are kept in IndexedDB, and requests answered from them.
+    pub offline_cache: OfflineCacheMode,
+    pub theme: Theme,
+    /// How often a live playlist is rechecked on its own (0 to only recheck on request).
+    pub live_recheck_seconds: u32,
+}
+
+pub fn load_settings() -> Settings {
+    Settings {
+        proxy: load_proxy_config(),
+        request_headers: load_request_headers(),
+        fetch_timeout_seconds: load_fetch_timeout_seconds(),
+        segment_cache_megabytes: load_segment_cache_megabytes(),
+        offline_cache: load_offline_cache_mode(),
+        theme: load_item(THEME_KEY)
+            .and_then(|name| Theme::from_name(&name))
+            .unwrap_or_default(),
+        live_recheck_seconds: load_item(LIVE_RECHECK_KEY)
+            .and_then(|seconds| seconds.parse().ok())
+            .unwrap_or_default(),
+    }
+}
+
+pub fn save_settings(settings: &Settings) {
+    save_proxy_config(&settings.proxy);
+    save_item(
+        REQUEST_HEADERS_KEY,
+        &format_headers(&settings.request_headers),
+    );
+    save_fetch_timeout_seconds(settings.fetch_timeout_seconds);
+    save_segment_cache_megabytes(settings.segment_cache_megabytes);
+    save_offline_cache_mode(settings.offline_cache);
+    save_item(THEME_KEY, settings.theme.name());
+    save_item(LIVE_RECHECK_KEY, &settings.live_recheck_seconds.to_string());
+}
+
+pub fn load_request_headers() -> Vec<(String, String)> {
+    load_item(REQUEST_HEADERS_KEY)
+        .map(|headers| parse_headers(&headers))
+        .unwrap_or_default()
+}
+
+/// Parses headers given one per line as `Name: value`. Lines without a valid header name are left
+/// out, as the fetch API would reject the request with them.
+pub fn parse_headers(headers: &str) -> Vec<(String, String)> {
+    headers
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let name = name.trim();
+            if !is_header_name(name) {
+                return None;
+            }
+            Some((name.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+pub fn format_headers(headers: &[(String, String)]) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether `name` is a valid header name (a token in RFC 9110).
+pub fn is_header_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(is_token_byte)
+}
+
+// The characters allowed in a header name.
+fn is_token_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+/// The colour scheme of the app, which follows that of the system unless one is chosen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Self; 3] = [Self::System, Self::Light, Self::Dark];
+
+    /// The name of the theme, as it is saved and set on the `data-theme` attribute of the page.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+}
+
+/// The views that the viewer has a toggle for, whose choice is remembered between playlists, and
+/// which the settings page lists so that they can be set before any playlist is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureToggle {
+    /// Segments are fetched only up to their media data, as far as their box structure goes.
+    StructureOnly,
+    /// Playlists are shown as their exact text rather than rendered.
+    RawText,
+    /// Long playlist lines are kept whole and scrolled to rather than wrapped.
+    PlaylistNoWrap,
+    /// Long lines of the diff of two playlists are kept whole and scrolled to rather than wrapped.
+    DiffNoWrap,
+    /// Links to the viewer are shared with the playlist compressed into them.
+    CompressedLinks,
+}
+
+impl FeatureToggle {
+    pub const ALL: [Self; 5] = [
+        Self::StructureOnly,
+        Self::RawText,
+        Self::PlaylistNoWrap,
+        Self::DiffNoWrap,
+        Self::CompressedLinks,
+    ];
+
+    /// The key in local storage that the toggle is saved under.
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::StructureOnly => "segment_structure_only",
+            Self::RawText => "playlist_raw_text",
+            Self::PlaylistNoWrap => "playlist_no_wrap",
+            Self::DiffNoWrap => "playlist_diff_no_wrap",
+            Self::CompressedLinks => "share_link_compressed",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_headers_should_skip_invalid_lines() {
+        assert_eq!(
+            vec![
+                (String::from("Authorization"), String::from("Bearer a:b")),
+                (String::from("X-Debug"), String::new()),
+            ],
+            parse_headers("Authorization: Bearer a:b\n\nno colon\nBad Name: x\n X-Debug :\n")
+        );
+    }
+
+    #[test]
+    fn headers_should_survive_being_saved() {
+        let headers = vec![
+            (String::from("Authorization"), String::from("Bearer token")),
+            (String::from("X-Client"), String::from("viewer")),
+        ];
+        assert_eq!(headers, parse_headers(&format_headers(&headers)));
+        assert_eq!(Some(Theme::Dark), Theme::from_name(Theme::Dark.name()));
+        assert_eq!(None, Theme::from_name("sepia"));
+    }
+}
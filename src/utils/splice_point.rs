@@ -0,0 +1,276 @@
+// Where the splice point of a SCTE-35 message falls on the timeline of a playlist. The message
+// gives the splice point as a presentation time (pts_time plus pts_adjustment, on the 33-bit 90 kHz
+// clock of the stream that it was cued in), which the playlist has no notion of. What the playlist
+// does have is the START-DATE of the EXT-X-DATERANGE that carries the message, which packagers set
+// to the wall-clock time of the splice point, and the EXT-X-PROGRAM-DATE-TIME of its segments.
+//
+// The presentation time can only be turned into a wall-clock time against the media timeline of a
+// segment whose EXT-X-PROGRAM-DATE-TIME is known, so once a segment has been inspected its base
+// media decode time (tfdt) is used, on the assumption that the packager carried the timestamps of
+// the stream over (as is usual). Until then, and for splice_immediate, the START-DATE is all there
+// is to go on.
+
+use crate::utils::{
+    playlist_lines::{LineKind, parse},
+    playlist_timeline::timeline,
+    scte35::{SCTE35_TIMESCALE, SpliceCommand, SpliceInfoSection},
+    timeline_drift::{DriftSample, parse_date_time},
+};
+
+/// Presentation times are 33-bit, so wrap around roughly every 26.5 hours.
+const PTS_WRAP: u64 = 1 << 33;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpliceTiming {
+    /// The splice happens at the first opportunity after the message is received.
+    Immediate,
+    /// The presentation time of the splice point in 90 kHz ticks, with pts_adjustment applied.
+    Pts(u64),
+}
+
+/// When the splice of a splice_insert or time_signal happens. There is no splice point for other
+/// commands, nor for a splice_insert that cancels an earlier one.
+pub fn splice_timing(section: &SpliceInfoSection) -> Option<SpliceTiming> {
+    let splice_time = match &section.command {
+        SpliceCommand::Insert(insert) if insert.splice_event_cancel => return None,
+        SpliceCommand::Insert(insert) if insert.splice_immediate => {
+            return Some(SpliceTiming::Immediate);
+        }
+        // In component splice mode each component has its own splice_time, of which the first is
+        // taken to be representative.
+        SpliceCommand::Insert(insert) => insert
+            .splice_time
+            .or_else(|| insert.components.iter().find_map(|(_, time)| *time))?,
+        SpliceCommand::TimeSignal(time) => *time,
+        _ => return None,
+    };
+    // A time_signal without a time has nothing else to say when it applies, so is taken to be
+    // immediate.
+    Some(match splice_time.pts_time {
+        Some(pts_time) => SpliceTiming::Pts((pts_time + section.pts_adjustment) % PTS_WRAP),
+        None => SpliceTiming::Immediate,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpliceClock {
+    /// The wall-clock time is the START-DATE of the daterange.
+    StartDate,
+    /// The wall-clock time was found by placing the presentation time against the media timeline
+    /// of the segment with the given Media Sequence Number.
+    MediaTime { media_sequence: u64 },
+}
+
+/// The segment that a splice point falls within.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpliceSegment {
+    /// The 1-based number of the line with the URI of the segment.
+    pub line: usize,
+    pub media_sequence: u64,
+    /// How far into the segment the splice point is, in seconds.
+    pub offset: f64,
+    pub duration: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplicePoint {
+    pub timing: SpliceTiming,
+    /// The START-DATE of the daterange, in milliseconds since the Unix epoch.
+    pub start_date: f64,
+    /// The wall-clock time of the splice point, in milliseconds since the Unix epoch.
+    pub wall_clock: f64,
+    pub clock: SpliceClock,
+    /// The segment the splice point falls within, when it is within the playlist.
+    pub segment: Option<SpliceSegment>,
+}
+
+impl SplicePoint {
+    /// How far the splice point is after the START-DATE of the daterange, in milliseconds.
+    pub fn start_date_offset(&self) -> f64 {
+        self.wall_clock - self.start_date
+    }
+}
+
+/// Places the splice point of a message carried by the daterange with the given ID, using the
+/// media timeline of whichever of the inspected segments is closest to the START-DATE.
+pub fn splice_point(
+    playlist: &str,
+    daterange_id: &str,
+    timing: SpliceTiming,
+    samples: &[DriftSample],
+) -> Option<SplicePoint> {
+    let start_date = daterange_start_date(playlist, daterange_id)?;
+    let reference = samples.iter().min_by(|a, b| {
+        let distance = |sample: &DriftSample| (sample.program_date_time.millis - start_date).abs();
+        distance(a).total_cmp(&distance(b))
+    });
+    let (wall_clock, clock) = match (timing, reference) {
+        (SpliceTiming::Pts(pts), Some(sample)) => (
+            pts_to_wall_clock(pts, sample),
+            SpliceClock::MediaTime {
+                media_sequence: sample.program_date_time.media_sequence,
+            },
+        ),
+        _ => (start_date, SpliceClock::StartDate),
+    };
+    let timeline = timeline(playlist);
+    let segment = timeline
+        .dateranges
+        .iter()
+        .find(|daterange| daterange.id == daterange_id)
+        .and_then(|daterange| {
+            let time = daterange.start + (wall_clock - start_date) / 1000.0;
+            timeline
+                .segments
+                .iter()
+                .find(|segment| segment.start <= time && time < segment.start + segment.duration)
+                .map(|segment| SpliceSegment {
+                    line: segment.line,
+                    media_sequence: segment.media_sequence,
+                    offset: time - segment.start,
+                    duration: segment.duration,
+                })
+        });
+    Some(SplicePoint {
+        timing,
+        start_date,
+        wall_clock,
+        clock,
+        segment,
+    })
+}
+
+fn daterange_start_date(playlist: &str, daterange_id: &str) -> Option<f64> {
+    parse(playlist)
+        .into_iter()
+        .find_map(|line| match line.kind {
+            LineKind::Tag(tag)
+                if tag.name == "EXT-X-DATERANGE"
+                    && tag.attribute_str("ID") == Some(daterange_id) =>
+            {
+                tag.attribute_str("START-DATE").and_then(parse_date_time)
+            }
+            _ => None,
+        })
+}
+
+// The difference between the two times is taken to be the shortest way around the 33-bit clock, so
+// that a splice point just after the clock wraps is not placed a day earlier.
+fn pts_to_wall_clock(pts: u64, sample: &DriftSample) -> f64 {
+    let decode_time = (sample.media_time.decode_time_millis * SCTE35_TIMESCALE / 1000.0).round()
+        as u64
+        % PTS_WRAP;
+    let difference = (pts + PTS_WRAP - decode_time) % PTS_WRAP;
+    let difference = if difference >= PTS_WRAP / 2 {
+        difference as f64 - PTS_WRAP as f64
+    } else {
+        difference as f64
+    };
+    sample.program_date_time.millis + difference / SCTE35_TIMESCALE * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        scte35::{decode_attribute, splice_info_section},
+        timeline_drift::{MediaTime, SegmentDateTime},
+    };
+    use pretty_assertions::assert_eq;
+
+    const PLAYLIST: &str = concat!(
+        "#EXTM3U\n",
+        "#EXT-X-MEDIA-SEQUENCE:10\n",
+        "#EXT-X-PROGRAM-DATE-TIME:2025-01-01T00:00:00Z\n",
+        "#EXT-X-DATERANGE:ID=\"ad\",START-DATE=\"2025-01-01T00:00:04Z\",SCTE35-OUT=0xFC\n",
+        "#EXTINF:6,\n",
+        "0.ts\n",
+        "#EXTINF:4,\n",
+        "1.ts\n",
+    );
+    const START_DATE: f64 = 1_735_689_604_000.0;
+
+    fn sample(media_sequence: u64, millis: f64, decode_time_millis: f64) -> DriftSample {
+        DriftSample {
+            program_date_time: SegmentDateTime {
+                media_sequence,
+                millis,
+                extrapolated: false,
+            },
+            media_time: MediaTime {
+                track_id: 1,
+                decode_time_millis,
+                wall_clock_millis: None,
+            },
+        }
+    }
+
+    #[test]
+    fn splice_timing_applies_pts_adjustment() {
+        let section = decode_attribute(
+            "0xFC302F000000000000FFFFF014054800008F7FEFFE7369C02EFE0052CCF500000000000A000843554549\
+             0000013562DBA30A",
+        )
+        .as_deref()
+        .and_then(splice_info_section)
+        .unwrap();
+        assert_eq!(Some(SpliceTiming::Pts(0x7369C02E)), splice_timing(&section));
+        let adjusted = SpliceInfoSection {
+            pts_adjustment: PTS_WRAP - 0x7369C02E + 90_000,
+            ..section
+        };
+        assert_eq!(Some(SpliceTiming::Pts(90_000)), splice_timing(&adjusted));
+    }
+
+    #[test]
+    fn splice_point_falls_at_start_date_until_media_time_is_known() {
+        assert_eq!(
+            Some(SplicePoint {
+                timing: SpliceTiming::Pts(900_000),
+                start_date: START_DATE,
+                wall_clock: START_DATE,
+                clock: SpliceClock::StartDate,
+                segment: Some(SpliceSegment {
+                    line: 6,
+                    media_sequence: 10,
+                    offset: 4.0,
+                    duration: 6.0,
+                }),
+            }),
+            splice_point(PLAYLIST, "ad", SpliceTiming::Pts(900_000), &[])
+        );
+        assert_eq!(
+            None,
+            splice_point(PLAYLIST, "other", SpliceTiming::Immediate, &[])
+        );
+    }
+
+    #[test]
+    fn splice_point_is_mapped_through_media_time() {
+        // Segment 11 starts 6 seconds into the playlist, at a decode time of 10 seconds, so a
+        // pts_time of 12 seconds is 2 seconds into it.
+        let samples = [sample(11, START_DATE + 2_000.0, 10_000.0)];
+        let point = splice_point(PLAYLIST, "ad", SpliceTiming::Pts(1_080_000), &samples);
+        assert_eq!(
+            Some((
+                START_DATE + 4_000.0,
+                SpliceClock::MediaTime { media_sequence: 11 }
+            )),
+            point.map(|point| (point.wall_clock, point.clock))
+        );
+        assert_eq!(
+            Some((11, 2.0)),
+            point
+                .and_then(|point| point.segment)
+                .map(|segment| (segment.media_sequence, segment.offset))
+        );
+        // A splice point just after the clock wraps is still later than the decode time.
+        let decode_time_millis = (PTS_WRAP - 90_000) as f64 / 90.0;
+        let samples = [sample(11, START_DATE + 2_000.0, decode_time_millis)];
+        assert_eq!(
+            Some(START_DATE + 4_000.0),
+            splice_point(PLAYLIST, "ad", SpliceTiming::Pts(90_000), &samples)
+                .map(|point| point.wall_clock)
+        );
+    }
+}
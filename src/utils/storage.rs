@@ -0,0 +1,30 @@
+// Preferences are kept in local storage so that they apply to every page of the app and survive
+// reloads, without being carried along in shared links.
+
+use web_sys::Storage;
+
+pub fn load_item(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok().flatten()
+}
+
+pub fn save_item(key: &str, value: &str) {
+    let Some(storage) = local_storage() else {
+        log::warn!("local storage is unavailable so {key} cannot be saved");
+        return;
+    };
+    if let Err(e) = storage.set_item(key, value) {
+        log::error!("failed to save {key} due to {e:?}");
+    }
+}
+
+pub fn load_flag(key: &str) -> bool {
+    load_item(key).is_some_and(|value| value == "true")
+}
+
+pub fn save_flag(key: &str, value: bool) {
+    save_item(key, if value { "true" } else { "false" });
+}
+
+fn local_storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
@@ -0,0 +1,115 @@
+// Decoding of a single keyframe into a still image with WebCodecs, configured from the decoder
+// configuration record of the initialization segment, so that a video segment can be recognised at
+// a glance without being played.
+
+use crate::utils::{keyframe::VideoSampleDescription, playback::js_error};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    CanvasRenderingContext2d, EncodedVideoChunk, EncodedVideoChunkInit, EncodedVideoChunkType,
+    HtmlCanvasElement, VideoDecoder, VideoDecoderConfig, VideoDecoderInit, VideoFrame,
+    js_sys::Uint8Array,
+};
+
+/// The largest width that a thumbnail is drawn at (the height keeps the aspect ratio of the frame).
+const MAX_THUMBNAIL_WIDTH: u32 = 320;
+
+/// Decodes the keyframe and draws it as a PNG, which is returned as a data URL.
+pub async fn decode_thumbnail(
+    codec: &str,
+    sample_description: &VideoSampleDescription,
+    keyframe: &[u8],
+) -> Result<String, String> {
+    if !window_has_video_decoder() {
+        return Err(String::from(
+            "This browser does not support WebCodecs, which is needed to decode a thumbnail.",
+        ));
+    }
+    let decoded = Rc::new(RefCell::new(None::<VideoFrame>));
+    let output = Closure::<dyn FnMut(VideoFrame)>::new({
+        let decoded = Rc::clone(&decoded);
+        move |frame: VideoFrame| {
+            // Only the first frame is kept, though only one chunk is decoded anyway.
+            if decoded.borrow().is_some() {
+                frame.close();
+            } else {
+                decoded.replace(Some(frame));
+            }
+        }
+    });
+    // A decoding error also rejects the flush, which is where it is reported from.
+    let error = Closure::<dyn FnMut(JsValue)>::new(|_| ());
+    let decoder = VideoDecoder::new(&VideoDecoderInit::new(
+        error.as_ref().unchecked_ref(),
+        output.as_ref().unchecked_ref(),
+    ))
+    .map_err(js_error)?;
+    let decoding = decode(&decoder, codec, sample_description, keyframe).await;
+    let _ = decoder.close();
+    decoding?;
+    let frame = decoded
+        .take()
+        .ok_or_else(|| String::from("The browser decoded no frame from the keyframe."))?;
+    let drawn = draw(&frame);
+    frame.close();
+    drawn
+}
+
+async fn decode(
+    decoder: &VideoDecoder,
+    codec: &str,
+    sample_description: &VideoSampleDescription,
+    keyframe: &[u8],
+) -> Result<(), String> {
+    let config = VideoDecoderConfig::new(codec);
+    config.set_description_u8_array(&Uint8Array::from(sample_description.description.as_slice()));
+    config.set_coded_width(u32::from(sample_description.width));
+    config.set_coded_height(u32::from(sample_description.height));
+    decoder.configure(&config).map_err(js_error)?;
+    let chunk = EncodedVideoChunk::new(&EncodedVideoChunkInit::new_with_u8_array(
+        &Uint8Array::from(keyframe),
+        0,
+        EncodedVideoChunkType::Key,
+    ))
+    .map_err(js_error)?;
+    decoder.decode(&chunk).map_err(js_error)?;
+    JsFuture::from(decoder.flush())
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("The browser could not decode the keyframe: {}", js_error(e)))
+}
+
+fn draw(frame: &VideoFrame) -> Result<String, String> {
+    let (width, height) = (frame.display_width(), frame.display_height());
+    if width == 0 || height == 0 {
+        return Err(String::from("The decoded frame is empty."));
+    }
+    let scale = f64::from(MAX_THUMBNAIL_WIDTH.min(width)) / f64::from(width);
+    let (width, height) = (
+        (f64::from(width) * scale).round(),
+        (f64::from(height) * scale).round(),
+    );
+    let canvas = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("canvas").ok())
+        .and_then(|element| element.dyn_into::<HtmlCanvasElement>().ok())
+        .ok_or_else(|| String::from("Unable to create a canvas to draw the frame on."))?;
+    canvas.set_width(width as u32);
+    canvas.set_height(height as u32);
+    let context = canvas
+        .get_context("2d")
+        .map_err(js_error)?
+        .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok())
+        .ok_or_else(|| String::from("Unable to draw the frame on a canvas."))?;
+    context
+        .draw_image_with_video_frame_and_dw_and_dh(frame, 0.0, 0.0, width, height)
+        .map_err(js_error)?;
+    canvas.to_data_url().map_err(js_error)
+}
+
+fn window_has_video_decoder() -> bool {
+    web_sys::window().is_some_and(|window| {
+        web_sys::js_sys::Reflect::has(&window, &JsValue::from_str("VideoDecoder")).unwrap_or(false)
+    })
+}
@@ -0,0 +1,367 @@
+use crate::utils::{
+    frame_rate::TrackTiming,
+    mp4_atom_properties::AtomProperties,
+    playlist_lines::{self, LineKind},
+    sample_entries::number_property,
+};
+
+// Comparison of the EXT-X-PROGRAM-DATE-TIME of a segment against the timeline of the media within
+// it, being the base media decode time of its first fragment (tfdt) and, when present, the
+// wall-clock time that the producer associated with the media timeline (prft).
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.4.6
+//
+//    The EXT-X-PROGRAM-DATE-TIME tag associates the first sample of a Media Segment with an
+//    absolute date and/or time.
+
+/// The date and time of a segment, in milliseconds since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentDateTime {
+    pub media_sequence: u64,
+    pub millis: f64,
+    /// Whether the value was calculated from the durations of the preceding segments, rather than
+    /// being declared by an EXT-X-PROGRAM-DATE-TIME tag on the segment itself.
+    pub extrapolated: bool,
+}
+
+/// The date and time of the Media Segment with the given Media Sequence Number, extrapolated from
+/// the closest preceding EXT-X-PROGRAM-DATE-TIME when the segment does not have its own.
+pub fn program_date_time_for_segment(
+    playlist: &str,
+    media_sequence: u64,
+) -> Option<SegmentDateTime> {
+    let mut current_sequence = 0;
+    let mut duration = 0.0;
+    let mut next = None;
+    for line in playlist_lines::parse(playlist) {
+        match line.kind {
+            LineKind::Tag(tag) if tag.name == "EXT-X-MEDIA-SEQUENCE" => {
+                current_sequence = tag.value.and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+            }
+            LineKind::Tag(tag) if tag.name == "EXT-X-PROGRAM-DATE-TIME" => {
+                next = tag
+                    .value
+                    .and_then(parse_date_time)
+                    .map(|millis| (millis, false));
+            }
+            LineKind::Tag(tag) if tag.name == "EXTINF" => {
+                duration = tag
+                    .value
+                    .map(|v| v.split_once(',').map(|(d, _)| d).unwrap_or(v).trim())
+                    .and_then(|d| d.parse::<f64>().ok())
+                    .unwrap_or(0.0);
+            }
+            LineKind::Uri(_) if current_sequence == media_sequence => break,
+            LineKind::Uri(_) => {
+                next = next.map(|(millis, _)| (millis + duration * 1000.0, true));
+                current_sequence += 1;
+            }
+            _ => (),
+        }
+    }
+    // The segment may only exist as partial segments so far, in which case there is no URI line.
+    if current_sequence != media_sequence {
+        return None;
+    }
+    next.map(|(millis, extrapolated)| SegmentDateTime {
+        media_sequence,
+        millis,
+        extrapolated,
+    })
+}
+
+/// Parses a date-time as defined by ISO/IEC 8601:2004 (e.g. `2010-02-19T14:54:23.031+08:00`) into
+/// milliseconds since the Unix epoch.
+pub fn parse_date_time(value: &str) -> Option<f64> {
+    let (date, time) = value.trim().split_once(['T', 't'])?;
+    let mut date = date.splitn(3, '-');
+    let year = date.next()?.parse::<i64>().ok()?;
+    let month = date
+        .next()?
+        .parse::<u32>()
+        .ok()
+        .filter(|m| (1..=12).contains(m))?;
+    let day = date
+        .next()?
+        .parse::<u32>()
+        .ok()
+        .filter(|d| (1..=31).contains(d))?;
+
+    let (time, offset_minutes) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else if let Some(index) = time.rfind(['+', '-']) {
+        let (time, offset) = time.split_at(index);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let offset = offset[1..].replace(':', "");
+        if offset.len() != 4 {
+            return None;
+        }
+        let hours = offset[..2].parse::<i64>().ok()?;
+        let minutes = offset[2..].parse::<i64>().ok()?;
+        (time, sign * (hours * 60 + minutes))
+    } else {
+        (time, 0)
+    };
+    let mut time = time.splitn(3, ':');
+    let hours = time.next()?.parse::<i64>().ok().filter(|h| *h < 24)?;
+    let minutes = time.next()?.parse::<i64>().ok().filter(|m| *m < 60)?;
+    let seconds = time
+        .next()
+        .map(|s| s.parse::<f64>().ok().filter(|s| (0.0..61.0).contains(s)))
+        .unwrap_or(Some(0.0))?;
+
+    let days = days_from_civil(year, month, day);
+    let whole_seconds = days * 86_400 + hours * 3_600 + (minutes - offset_minutes) * 60;
+    Some((whole_seconds as f64 + seconds) * 1000.0)
+}
+
+// The number of days from 1970-01-01 to the given date in the proleptic Gregorian calendar.
+//
+// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// NTP timestamps count seconds from 1900-01-01, with the lower 32 bits holding the fraction.
+const NTP_UNIX_EPOCH_OFFSET_SECONDS: f64 = 2_208_988_800.0;
+
+fn ntp_to_unix_millis(ntp_timestamp: u64) -> f64 {
+    let seconds = (ntp_timestamp >> 32) as f64;
+    let fraction = (ntp_timestamp & 0xffff_ffff) as f64 / 4_294_967_296.0;
+    (seconds - NTP_UNIX_EPOCH_OFFSET_SECONDS + fraction) * 1000.0
+}
+
+/// Where a media segment begins on the timeline of one of its tracks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MediaTime {
+    pub track_id: u32,
+    /// The base media decode time of the first fragment of the track, in milliseconds.
+    pub decode_time_millis: f64,
+    /// The wall-clock time of the decode time in milliseconds since the Unix epoch, when the
+    /// segment carries a `prft` box for the track.
+    pub wall_clock_millis: Option<f64>,
+}
+
+/// Reads where the media segment begins on the timeline of its video track (or of its first track
+/// when there is no video). The timescales are taken from the tracks of the initialization segment.
+pub fn media_time<'a>(
+    atoms: impl IntoIterator<Item = (&'a str, usize, &'a AtomProperties)>,
+    tracks: &[TrackTiming],
+) -> Option<MediaTime> {
+    let mut producer_references = Vec::new();
+    let mut decode_times = Vec::<(u32, u64)>::new();
+    let mut track_id = None;
+    for (kind, _, properties) in atoms {
+        match kind {
+            "prft" => {
+                if let Some(reference_track_id) =
+                    number_property::<u32>(properties, "reference_track_id")
+                    && let Some(ntp_timestamp) = number_property::<u64>(properties, "ntp_timestamp")
+                    && let Some(media_time) = number_property::<u64>(properties, "media_time")
+                {
+                    producer_references.push((reference_track_id, ntp_timestamp, media_time));
+                }
+            }
+            "tfhd" => track_id = number_property::<u32>(properties, "track_id"),
+            "tfdt" => {
+                if let Some(track_id) = track_id
+                    && !decode_times.iter().any(|(id, _)| *id == track_id)
+                    && let Some(decode_time) =
+                        number_property::<u64>(properties, "base_media_decode_time")
+                {
+                    decode_times.push((track_id, decode_time));
+                }
+            }
+            _ => (),
+        }
+    }
+    let timescale = |track_id: u32| {
+        tracks
+            .iter()
+            .find(|track| track.track_id == track_id)
+            .and_then(|track| track.timescale)
+            .filter(|timescale| *timescale > 0)
+            .map(f64::from)
+    };
+    let is_video = |track_id: u32| {
+        tracks
+            .iter()
+            .any(|track| track.track_id == track_id && track.handler.as_deref() == Some("vide"))
+    };
+    let (track_id, decode_time) = decode_times
+        .iter()
+        .find(|(track_id, _)| is_video(*track_id))
+        .or_else(|| decode_times.first())
+        .copied()?;
+    let timescale = timescale(track_id)?;
+    let wall_clock_millis = producer_references
+        .iter()
+        .find(|(reference_track_id, _, _)| *reference_track_id == track_id)
+        .map(|(_, ntp_timestamp, media_time)| {
+            let offset_seconds = (decode_time as f64 - *media_time as f64) / timescale;
+            ntp_to_unix_millis(*ntp_timestamp) + offset_seconds * 1000.0
+        });
+    Some(MediaTime {
+        track_id,
+        decode_time_millis: decode_time as f64 / timescale * 1000.0,
+        wall_clock_millis,
+    })
+}
+
+/// The playlist and media timing of one inspected segment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DriftSample {
+    pub program_date_time: SegmentDateTime,
+    pub media_time: MediaTime,
+}
+
+impl DriftSample {
+    /// How far the producer reference time (prft) places the start of the segment from its
+    /// EXT-X-PROGRAM-DATE-TIME, in milliseconds.
+    pub fn absolute_drift_millis(&self) -> Option<f64> {
+        Some(self.media_time.wall_clock_millis? - self.program_date_time.millis)
+    }
+
+    /// How much further the media timeline has advanced than the EXT-X-PROGRAM-DATE-TIME since the
+    /// `earlier` sample, in milliseconds. Any constant offset between the two timelines (e.g. from
+    /// composition time offsets) cancels out, so only accumulated drift is reported.
+    pub fn drift_since_millis(&self, earlier: &DriftSample) -> Option<f64> {
+        if self.media_time.track_id != earlier.media_time.track_id {
+            return None;
+        }
+        let media_elapsed =
+            self.media_time.decode_time_millis - earlier.media_time.decode_time_millis;
+        let playlist_elapsed = self.program_date_time.millis - earlier.program_date_time.millis;
+        Some(media_elapsed - playlist_elapsed)
+    }
+}
+
+// EXT-X-PROGRAM-DATE-TIME has millisecond precision at best, and the media timeline is quantized to
+// the sample durations, so small differences are expected.
+pub const DRIFT_TOLERANCE_MILLIS: f64 = 50.0;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mp4_atom_properties::{AtomPropertyValue, BasicPropertyValue};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn date_times_are_parsed_to_unix_millis() {
+        assert_eq!(Some(0.0), parse_date_time("1970-01-01T00:00:00Z"));
+        assert_eq!(
+            Some(1_266_591_263_031.0),
+            parse_date_time("2010-02-19T14:54:23.031Z")
+        );
+        assert_eq!(
+            parse_date_time("2010-02-19T06:54:23.031Z"),
+            parse_date_time("2010-02-19T14:54:23.031+08:00")
+        );
+        assert_eq!(
+            parse_date_time("2010-02-19T16:54:23.031Z"),
+            parse_date_time("2010-02-19T14:54:23.031-0200")
+        );
+        assert_eq!(None, parse_date_time("2010-02-19"));
+        assert_eq!(None, parse_date_time("2010-13-19T14:54:23Z"));
+    }
+
+    #[test]
+    fn date_time_is_extrapolated_from_durations() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXT-X-MEDIA-SEQUENCE:100
+#EXT-X-PROGRAM-DATE-TIME:1970-01-01T00:00:10Z
+#EXTINF:6.006,
+a.mp4
+#EXTINF:6.006,
+b.mp4
+#EXT-X-PROGRAM-DATE-TIME:1970-01-01T00:01:00Z
+#EXTINF:6.006,
+c.mp4
+";
+        let date_time = |media_sequence| {
+            program_date_time_for_segment(playlist, media_sequence)
+                .map(|d| (d.millis.round() as u64, d.extrapolated))
+        };
+        assert_eq!(Some((10_000, false)), date_time(100));
+        assert_eq!(Some((16_006, true)), date_time(101));
+        assert_eq!(Some((60_000, false)), date_time(102));
+        assert_eq!(Some((66_006, true)), date_time(103));
+        assert_eq!(None, date_time(104));
+    }
+
+    #[test]
+    fn media_time_uses_tfdt_and_prft() {
+        let number = |key: &'static str, value| {
+            (
+                key.into(),
+                AtomPropertyValue::Basic(BasicPropertyValue::U64(value)),
+            )
+        };
+        let prft = AtomProperties {
+            box_name: "ProducerReferenceTimeBox",
+            properties: vec![
+                number("reference_track_id", 1),
+                // 1970-01-01T00:00:10Z as an NTP timestamp.
+                number("ntp_timestamp", (2_208_988_810u64) << 32),
+                number("media_time", 90_000),
+            ],
+        };
+        let tfhd = AtomProperties {
+            box_name: "TrackFragmentHeaderBox",
+            properties: vec![number("track_id", 1)],
+        };
+        let tfdt = AtomProperties {
+            box_name: "TrackFragmentBaseMediaDecodeTimeBox",
+            properties: vec![number("base_media_decode_time", 180_000)],
+        };
+        let tracks = vec![TrackTiming {
+            track_id: 1,
+            handler: Some(String::from("vide")),
+            timescale: Some(90_000),
+            ..Default::default()
+        }];
+        let segment = vec![
+            ("prft", 0, &prft),
+            ("moof", 0, &tfhd),
+            ("tfhd", 2, &tfhd),
+            ("tfdt", 2, &tfdt),
+        ];
+        let media_time = media_time(segment, &tracks).unwrap();
+        assert_eq!(
+            MediaTime {
+                track_id: 1,
+                decode_time_millis: 2_000.0,
+                wall_clock_millis: Some(11_000.0),
+            },
+            media_time
+        );
+
+        let sample = |millis, decode_time_millis| DriftSample {
+            program_date_time: SegmentDateTime {
+                media_sequence: 0,
+                millis,
+                extrapolated: false,
+            },
+            media_time: MediaTime {
+                decode_time_millis,
+                ..media_time
+            },
+        };
+        assert_eq!(
+            Some(1_000.0),
+            sample(10_000.0, 2_000.0).absolute_drift_millis()
+        );
+        assert_eq!(
+            Some(120.0),
+            sample(16_000.0, 8_120.0).drift_since_millis(&sample(10_000.0, 2_000.0))
+        );
+    }
+}
@@ -0,0 +1,63 @@
+// The playlist URLs that have been viewed recently, most recent first, which are offered as
+// suggestions when entering a URL.
+
+use crate::utils::storage::{load_item, save_item};
+
+const URL_HISTORY_KEY: &str = "url_history";
+const MAX_URL_HISTORY: usize = 20;
+
+pub fn load_url_history() -> Vec<String> {
+    load_item(URL_HISTORY_KEY)
+        .and_then(|history| serde_json::from_str(&history).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_url_history(history: &[String]) {
+    match serde_json::to_string(history) {
+        Ok(history) => save_item(URL_HISTORY_KEY, &history),
+        Err(e) => log::error!("failed to serialize url history due to {e}"),
+    }
+}
+
+/// The history after viewing `url`, which moves it to the front if it was viewed before.
+pub fn with_visited_url(mut history: Vec<String>, url: &str) -> Vec<String> {
+    history.retain(|visited| visited != url);
+    history.insert(0, url.to_string());
+    history.truncate(MAX_URL_HISTORY);
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn visited_urls_move_to_the_front() {
+        let history = vec![
+            String::from("https://a.com/1.m3u8"),
+            String::from("https://a.com/2.m3u8"),
+        ];
+        assert_eq!(
+            vec![
+                String::from("https://a.com/2.m3u8"),
+                String::from("https://a.com/1.m3u8")
+            ],
+            with_visited_url(history, "https://a.com/2.m3u8")
+        );
+    }
+
+    #[test]
+    fn only_the_most_recent_urls_are_kept() {
+        let history = (0..MAX_URL_HISTORY)
+            .map(|i| format!("https://a.com/{i}.m3u8"))
+            .collect();
+        let history = with_visited_url(history, "https://b.com/mvp.m3u8");
+        assert_eq!(MAX_URL_HISTORY, history.len());
+        assert_eq!("https://b.com/mvp.m3u8", history[0]);
+        assert_eq!(
+            format!("https://a.com/{}.m3u8", MAX_URL_HISTORY - 2),
+            history[MAX_URL_HISTORY - 1]
+        );
+    }
+}
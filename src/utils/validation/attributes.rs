@@ -0,0 +1,541 @@
+use super::{Finding, Playlist};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    playlist_lines::{AttributeValue, TagLine},
+};
+use std::collections::HashSet;
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.2
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttributeType {
+    DecimalInteger,
+    DecimalFloatingPoint,
+    SignedDecimalFloatingPoint,
+    DecimalResolution,
+    HexadecimalSequence,
+    QuotedString,
+    EnumeratedString(&'static [&'static str]),
+    // CLOSED-CAPTIONS is either a quoted-string or the enumerated-string NONE.
+    QuotedStringOrNone,
+}
+
+const YES_NO: &[&str] = &["YES", "NO"];
+
+/// The type that the specification gives the attribute of the tag, if it is one that it defines.
+pub fn attribute_type(tag: &str, attribute: &str) -> Option<AttributeType> {
+    use AttributeType::*;
+    match (tag, attribute) {
+        // Client attributes and SCTE35 payloads on EXT-X-DATERANGE are free-form.
+        ("EXT-X-DATERANGE", name) if name.starts_with("X-") => None,
+        ("EXT-X-DATERANGE", "SCTE35-CMD" | "SCTE35-OUT" | "SCTE35-IN") => Some(HexadecimalSequence),
+        ("EXT-X-MEDIA", "TYPE") => Some(EnumeratedString(&[
+            "AUDIO",
+            "VIDEO",
+            "SUBTITLES",
+            "CLOSED-CAPTIONS",
+        ])),
+        ("EXT-X-PRELOAD-HINT", "TYPE") => Some(EnumeratedString(&["PART", "MAP"])),
+        ("EXT-X-KEY" | "EXT-X-SESSION-KEY", "METHOD") => Some(EnumeratedString(&[
+            "NONE",
+            "AES-128",
+            "SAMPLE-AES",
+            "SAMPLE-AES-CTR",
+        ])),
+        ("EXT-X-PRELOAD-HINT", "BYTERANGE-START" | "BYTERANGE-LENGTH") => Some(DecimalInteger),
+        (_, "BANDWIDTH" | "AVERAGE-BANDWIDTH" | "LAST-MSN" | "LAST-PART" | "SKIPPED-SEGMENTS") => {
+            Some(DecimalInteger)
+        }
+        (
+            _,
+            "FRAME-RATE" | "DURATION" | "PLANNED-DURATION" | "PART-TARGET" | "CAN-SKIP-UNTIL"
+            | "HOLD-BACK" | "PART-HOLD-BACK" | "SCORE",
+        ) => Some(DecimalFloatingPoint),
+        (_, "TIME-OFFSET") => Some(SignedDecimalFloatingPoint),
+        (_, "RESOLUTION") => Some(DecimalResolution),
+        (_, "IV") => Some(HexadecimalSequence),
+        (_, "CLOSED-CAPTIONS") => Some(QuotedStringOrNone),
+        (
+            _,
+            "URI"
+            | "KEYFORMAT"
+            | "KEYFORMATVERSIONS"
+            | "BYTERANGE"
+            | "GROUP-ID"
+            | "LANGUAGE"
+            | "ASSOC-LANGUAGE"
+            | "NAME"
+            | "STABLE-RENDITION-ID"
+            | "STABLE-VARIANT-ID"
+            | "INSTREAM-ID"
+            | "CHARACTERISTICS"
+            | "CHANNELS"
+            | "CODECS"
+            | "SUPPLEMENTAL-CODECS"
+            | "AUDIO"
+            | "VIDEO"
+            | "SUBTITLES"
+            | "PATHWAY-ID"
+            | "ID"
+            | "CLASS"
+            | "START-DATE"
+            | "END-DATE"
+            | "CUE"
+            | "DATA-ID"
+            | "VALUE"
+            | "SERVER-URI"
+            | "IMPORT"
+            | "QUERYPARAM"
+            | "REQ-VIDEO-LAYOUT"
+            | "RECENTLY-REMOVED-DATERANGES",
+        ) => Some(QuotedString),
+        (
+            _,
+            "DEFAULT" | "AUTOSELECT" | "FORCED" | "PRECISE" | "INDEPENDENT" | "GAP" | "END-ON-NEXT"
+            | "CAN-BLOCK-RELOAD",
+        ) => Some(EnumeratedString(YES_NO)),
+        (_, "HDCP-LEVEL") => Some(EnumeratedString(&["TYPE-0", "TYPE-1", "NONE"])),
+        (_, "VIDEO-RANGE") => Some(EnumeratedString(&["SDR", "HLG", "PQ"])),
+        _ => None,
+    }
+}
+
+// Attributes that the specification marks as REQUIRED for each attribute-list tag. Tags listed here
+// with no required attributes are still checked for well formed attribute-lists.
+const ATTRIBUTE_LIST_TAGS: &[(&str, &[&str])] = &[
+    ("EXT-X-KEY", &["METHOD"]),
+    ("EXT-X-MAP", &["URI"]),
+    ("EXT-X-PART", &["URI", "DURATION"]),
+    ("EXT-X-DATERANGE", &["ID", "START-DATE"]),
+    ("EXT-X-SKIP", &["SKIPPED-SEGMENTS"]),
+    ("EXT-X-PRELOAD-HINT", &["TYPE", "URI"]),
+    ("EXT-X-RENDITION-REPORT", &["URI"]),
+    ("EXT-X-PART-INF", &["PART-TARGET"]),
+    ("EXT-X-SERVER-CONTROL", &[]),
+    ("EXT-X-START", &["TIME-OFFSET"]),
+    ("EXT-X-DEFINE", &[]),
+    ("EXT-X-MEDIA", &["TYPE", "GROUP-ID", "NAME"]),
+    ("EXT-X-STREAM-INF", &["BANDWIDTH"]),
+    ("EXT-X-I-FRAME-STREAM-INF", &["BANDWIDTH", "URI"]),
+    ("EXT-X-SESSION-DATA", &["DATA-ID"]),
+    ("EXT-X-SESSION-KEY", &["METHOD", "URI"]),
+    ("EXT-X-CONTENT-STEERING", &["SERVER-URI"]),
+];
+
+pub fn attribute_lists(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    for (line, tag) in playlist.tags() {
+        let Some((_, required)) = ATTRIBUTE_LIST_TAGS
+            .iter()
+            .find(|(name, _)| *name == tag.name)
+        else {
+            continue;
+        };
+        let mut seen = HashSet::new();
+        for attribute in tag.attributes() {
+            let (name, value) = match attribute {
+                Ok(attribute) => attribute,
+                Err(entry) => {
+                    findings.push(Finding::error(
+                        line,
+                        "attribute-format",
+                        tr_with(Message::AttributeMalformed, &[("entry", entry)]),
+                    ));
+                    continue;
+                }
+            };
+            if !seen.insert(name) {
+                findings.push(Finding::error(
+                    line,
+                    "attribute-unique",
+                    tr_with(
+                        Message::AttributeRepeated,
+                        &[("name", name), ("tag", tag.name)],
+                    ),
+                ));
+            }
+            if let Some(attribute_type) = attribute_type(tag.name, name)
+                && let Some(problem) = check_value(attribute_type, value)
+            {
+                findings.push(Finding::error(
+                    line,
+                    "attribute-format",
+                    tr_with(
+                        Message::AttributeInvalid,
+                        &[("name", name), ("problem", &problem)],
+                    ),
+                ));
+            }
+        }
+        for name in *required {
+            if !seen.contains(name) {
+                findings.push(Finding::error(
+                    line,
+                    "required-attribute",
+                    tr_with(
+                        Message::AttributeRequired,
+                        &[("tag", tag.name), ("name", name)],
+                    ),
+                ));
+            }
+        }
+        conditional_attributes(line, tag, findings);
+    }
+}
+
+// Requirements that depend on the value of another attribute.
+fn conditional_attributes(line: usize, tag: &TagLine, findings: &mut Vec<Finding>) {
+    match tag.name {
+        // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.4.4
+        //
+        //    URI: [...] This attribute is REQUIRED unless the METHOD is NONE.
+        "EXT-X-KEY" => {
+            let method = tag.attribute_str("METHOD");
+            if method.is_some_and(|m| m != "NONE") && tag.attribute("URI").is_none() {
+                findings.push(Finding::error(
+                    line,
+                    "required-attribute",
+                    tr(Message::KeyUriRequired),
+                ));
+            }
+            if method == Some("NONE") && tag.attributes().len() > 1 {
+                findings.push(Finding::error(
+                    line,
+                    "attribute-format",
+                    tr(Message::KeyMethodNoneWithAttributes),
+                ));
+            }
+        }
+        // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.6.1
+        //
+        //    URI: [...] If the TYPE is CLOSED-CAPTIONS, the URI attribute MUST NOT be present.
+        //
+        //    INSTREAM-ID: [...] This attribute is REQUIRED if the TYPE attribute is
+        //    CLOSED-CAPTIONS [...] For all other TYPE values, the INSTREAM-ID MUST NOT be
+        //    specified.
+        "EXT-X-MEDIA" => {
+            let is_closed_captions = tag.attribute_str("TYPE") == Some("CLOSED-CAPTIONS");
+            if is_closed_captions && tag.attribute("URI").is_some() {
+                findings.push(Finding::error(
+                    line,
+                    "attribute-format",
+                    tr(Message::ClosedCaptionsWithUri),
+                ));
+            }
+            match (is_closed_captions, tag.attribute("INSTREAM-ID").is_some()) {
+                (true, false) => findings.push(Finding::error(
+                    line,
+                    "required-attribute",
+                    tr(Message::InstreamIdRequired),
+                )),
+                (false, true) => findings.push(Finding::error(
+                    line,
+                    "attribute-format",
+                    tr(Message::InstreamIdNotAllowed),
+                )),
+                _ => (),
+            }
+            if tag.attribute_str("AUTOSELECT") == Some("NO")
+                && tag.attribute_str("DEFAULT") == Some("YES")
+            {
+                findings.push(Finding::error(
+                    line,
+                    "attribute-format",
+                    tr(Message::AutoselectRequired),
+                ));
+            }
+        }
+        // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.6.4
+        //
+        //    METHOD: [...] The value MUST NOT be NONE.
+        "EXT-X-SESSION-KEY" if tag.attribute_str("METHOD") == Some("NONE") => {
+            findings.push(Finding::error(
+                line,
+                "attribute-format",
+                tr(Message::SessionKeyMethodNone),
+            ));
+        }
+        // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.6.3
+        //
+        //    Each EXT-X-SESSION-DATA tag MUST contain either a VALUE or URI attribute, but not
+        //    both.
+        "EXT-X-SESSION-DATA"
+            if tag.attribute("VALUE").is_some() == tag.attribute("URI").is_some() =>
+        {
+            findings.push(Finding::error(
+                line,
+                "required-attribute",
+                tr(Message::SessionDataValueOrUri),
+            ));
+        }
+        // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.6.2
+        //
+        //    CODECS: [...] Every EXT-X-STREAM-INF tag SHOULD include a CODECS attribute.
+        "EXT-X-STREAM-INF" | "EXT-X-I-FRAME-STREAM-INF" if tag.attribute("CODECS").is_none() => {
+            findings.push(Finding::warning(
+                line,
+                "codecs-recommended",
+                tr_with(Message::CodecsRecommended, &[("tag", tag.name)]),
+            ));
+        }
+        // https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.2.3
+        "EXT-X-DEFINE" => {
+            let count = ["NAME", "IMPORT", "QUERYPARAM"]
+                .iter()
+                .filter(|name| tag.attribute(name).is_some())
+                .count();
+            if count != 1 {
+                findings.push(Finding::error(
+                    line,
+                    "required-attribute",
+                    tr(Message::DefineSourceRequired),
+                ));
+            }
+            if tag.attribute("NAME").is_some() && tag.attribute("VALUE").is_none() {
+                findings.push(Finding::error(
+                    line,
+                    "required-attribute",
+                    tr(Message::DefineValueRequired),
+                ));
+            }
+        }
+        _ => (),
+    }
+}
+
+// Tags whose value is a single item rather than an attribute-list.
+pub fn tag_values(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    for (line, tag) in playlist.tags() {
+        let attribute_type = match tag.name {
+            "EXT-X-VERSION"
+            | "EXT-X-TARGETDURATION"
+            | "EXT-X-MEDIA-SEQUENCE"
+            | "EXT-X-DISCONTINUITY-SEQUENCE"
+            | "EXT-X-BITRATE" => AttributeType::DecimalInteger,
+            "EXT-X-PLAYLIST-TYPE" => AttributeType::EnumeratedString(&["EVENT", "VOD"]),
+            "EXT-X-BYTERANGE" => {
+                if !tag.value.is_some_and(is_byterange) {
+                    findings.push(Finding::error(
+                        line,
+                        "tag-value",
+                        tr(Message::ByterangeValueInvalid),
+                    ));
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let value = AttributeValue::Unquoted(tag.value.unwrap_or_default().trim());
+        if let Some(problem) = check_value(attribute_type, value) {
+            findings.push(Finding::error(
+                line,
+                "tag-value",
+                tr_with(
+                    Message::TagValueInvalid,
+                    &[("tag", tag.name), ("problem", &problem)],
+                ),
+            ));
+        }
+    }
+}
+
+fn check_value(attribute_type: AttributeType, value: AttributeValue) -> Option<String> {
+    let (quoted, value) = match value {
+        AttributeValue::Quoted(v) => (true, v),
+        AttributeValue::Unquoted(v) => (false, v),
+    };
+    let expect_unquoted = |description: &str, valid: bool| {
+        if quoted {
+            Some(tr_with(Message::ValueQuoted, &[("type", description)]))
+        } else if !valid {
+            Some(tr_with(
+                Message::ValueInvalid,
+                &[("type", description), ("value", value)],
+            ))
+        } else {
+            None
+        }
+    };
+    match attribute_type {
+        AttributeType::DecimalInteger => {
+            expect_unquoted(tr(Message::TypeDecimalInteger), is_decimal_integer(value))
+        }
+        AttributeType::DecimalFloatingPoint => expect_unquoted(
+            tr(Message::TypeDecimalFloatingPoint),
+            is_decimal_float(value),
+        ),
+        AttributeType::SignedDecimalFloatingPoint => expect_unquoted(
+            tr(Message::TypeSignedDecimalFloatingPoint),
+            is_decimal_float(value.strip_prefix('-').unwrap_or(value)),
+        ),
+        AttributeType::DecimalResolution => expect_unquoted(
+            tr(Message::TypeDecimalResolution),
+            value
+                .split_once('x')
+                .is_some_and(|(w, h)| is_decimal_integer(w) && is_decimal_integer(h)),
+        ),
+        AttributeType::HexadecimalSequence => expect_unquoted(
+            tr(Message::TypeHexadecimalSequence),
+            value
+                .strip_prefix("0x")
+                .or_else(|| value.strip_prefix("0X"))
+                .is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit())),
+        ),
+        AttributeType::EnumeratedString(allowed) => expect_unquoted(
+            &tr_with(
+                Message::TypeEnumeratedString,
+                &[("values", &allowed.join(", "))],
+            ),
+            allowed.contains(&value),
+        ),
+        AttributeType::QuotedString => {
+            if quoted {
+                None
+            } else {
+                Some(String::from(tr(Message::ValueNotQuoted)))
+            }
+        }
+        AttributeType::QuotedStringOrNone => {
+            if quoted || value == "NONE" {
+                None
+            } else {
+                Some(String::from(tr(Message::ValueNotQuotedOrNone)))
+            }
+        }
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.2
+//
+//    decimal-integer: an unquoted string of characters from the set [0..9] expressing an integer
+//    in base-10 arithmetic in the range from 0 to 2^64-1 (18446744073709551615).
+fn is_decimal_integer(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) && value.parse::<u64>().is_ok()
+}
+
+//    decimal-floating-point: an unquoted string of characters from the set [0..9] and '.' that
+//    expresses a non-negative floating-point number in decimal positional notation.
+fn is_decimal_float(value: &str) -> bool {
+    !value.is_empty()
+        && value.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && value.chars().filter(|c| *c == '.').count() <= 1
+        && value.chars().any(|c| c.is_ascii_digit())
+}
+
+fn is_byterange(value: &str) -> bool {
+    match value.trim().split_once('@') {
+        Some((length, offset)) => is_decimal_integer(length) && is_decimal_integer(offset),
+        None => is_decimal_integer(value.trim()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn messages(rule: super::super::Rule, playlist: &str) -> Vec<String> {
+        let playlist = Playlist::new(playlist);
+        let mut findings = Vec::new();
+        rule(&playlist, &mut findings);
+        findings.into_iter().map(|f| f.message).collect()
+    }
+
+    #[test]
+    fn missing_required_attributes_are_reported() {
+        assert_eq!(
+            vec![String::from(
+                "#EXT-X-STREAM-INF is missing the required BANDWIDTH attribute."
+            )],
+            messages(
+                attribute_lists,
+                "#EXTM3U\n#EXT-X-STREAM-INF:CODECS=\"avc1.64001f\"\nvideo.m3u8\n"
+            )
+        );
+    }
+
+    #[test]
+    fn attribute_value_formats_are_checked() {
+        assert_eq!(
+            vec![
+                String::from("BANDWIDTH must be a decimal-integer but was 1.5M."),
+                String::from(
+                    "RESOLUTION must be a decimal-resolution (e.g. 1920x1080) but was 1280*720."
+                ),
+                String::from("CODECS must be a quoted-string."),
+            ],
+            messages(
+                attribute_lists,
+                "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1.5M,RESOLUTION=1280*720,CODECS=avc1\n"
+            )
+        );
+    }
+
+    #[test]
+    fn duplicate_and_malformed_attributes_are_reported() {
+        assert_eq!(
+            vec![
+                String::from("Malformed attribute \"AES-128\"; expected NAME=VALUE."),
+                String::from("Attribute METHOD must not appear more than once in #EXT-X-KEY."),
+            ],
+            messages(
+                attribute_lists,
+                "#EXTM3U\n#EXT-X-KEY:METHOD=SAMPLE-AES,URI=\"k\",AES-128,METHOD=SAMPLE-AES\n"
+            )
+        );
+    }
+
+    #[test]
+    fn key_uri_is_required_unless_method_is_none() {
+        assert_eq!(
+            vec![String::from(
+                "#EXT-X-KEY is missing the URI attribute, which is required unless the METHOD is \
+                 NONE."
+            )],
+            messages(attribute_lists, "#EXTM3U\n#EXT-X-KEY:METHOD=AES-128\n")
+        );
+        assert!(messages(attribute_lists, "#EXTM3U\n#EXT-X-KEY:METHOD=NONE\n").is_empty());
+    }
+
+    #[test]
+    fn closed_captions_rules() {
+        assert_eq!(
+            vec![
+                String::from("URI must not be present when TYPE is CLOSED-CAPTIONS."),
+                String::from("INSTREAM-ID is required when TYPE is CLOSED-CAPTIONS."),
+            ],
+            messages(
+                attribute_lists,
+                "#EXTM3U\n#EXT-X-MEDIA:TYPE=CLOSED-CAPTIONS,GROUP-ID=\"cc\",NAME=\"CC\",URI=\"cc.m3u8\"\n"
+            )
+        );
+    }
+
+    #[test]
+    fn daterange_client_attributes_are_not_checked() {
+        assert!(
+            messages(
+                attribute_lists,
+                "#EXTM3U\n#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2024-01-01T00:00:00Z\",X-COM-EXAMPLE=1.2.3\n"
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn single_value_tags_are_checked() {
+        assert_eq!(
+            vec![
+                String::from(
+                    "The value of #EXT-X-TARGETDURATION must be a decimal-integer but was 6.5."
+                ),
+                String::from(
+                    "The value of #EXT-X-PLAYLIST-TYPE must be one of EVENT, VOD but was LIVE."
+                ),
+                String::from("#EXT-X-BYTERANGE must have a value of the form <n>[@<o>]."),
+            ],
+            messages(
+                tag_values,
+                "#EXTM3U\n#EXT-X-TARGETDURATION:6.5\n#EXT-X-PLAYLIST-TYPE:LIVE\n#EXT-X-BYTERANGE:100@\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use super::{Finding, Playlist};
+use crate::utils::{
+    playlist_lines::{AttributeValue, TagLine},
+    scte35::{decode_attribute, splice_insert},
+    timeline_drift::parse_date_time,
+};
+use std::collections::HashMap;
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.5.1
+//
+//    If a Playlist contains two EXT-X-DATERANGE tags with the same ID attribute value, then any
+//    AttributeName that appears in both tags MUST have the same AttributeValue.
+pub fn daterange_ids(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    let mut seen = HashMap::<&str, Vec<(&str, AttributeValue, usize)>>::new();
+    for (line, tag) in dateranges(playlist) {
+        let Some(id) = tag.attribute_str("ID") else {
+            continue;
+        };
+        let known = seen.entry(id).or_default();
+        for (name, value) in tag.attributes().into_iter().filter_map(Result::ok) {
+            match known.iter().find(|(known_name, _, _)| *known_name == name) {
+                Some((_, known_value, _)) if known_value.as_str() == value.as_str() => (),
+                Some((_, known_value, known_line)) => findings.push(Finding::error(
+                    line,
+                    "daterange-id",
+                    format!(
+                        "EXT-X-DATERANGE ID \"{id}\" is reused with {name}={}, but line \
+                         {known_line} has {name}={}. Tags with the same ID must agree on the \
+                         attributes that they share.",
+                        value.as_str(),
+                        known_value.as_str()
+                    ),
+                )),
+                None => known.push((name, value, line)),
+            }
+        }
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.5.1
+//
+//    If a Date Range contains both a DURATION attribute and an END-DATE attribute, the value of the
+//    END-DATE attribute MUST be equal to the value of the START-DATE attribute plus the value of the
+//    DURATION attribute.
+//
+// The attributes of a Date Range may be spread across several tags with the same ID, so the
+// START-DATE and DURATION are carried over to later tags.
+pub fn daterange_durations(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    let mut known = HashMap::<&str, (Option<f64>, Option<f64>)>::new();
+    for (line, tag) in dateranges(playlist) {
+        let mut start_date = tag.attribute_str("START-DATE").and_then(parse_date_time);
+        let mut duration = tag.attribute_str("DURATION").and_then(seconds);
+        if let Some(id) = tag.attribute_str("ID") {
+            let (known_start_date, known_duration) = known.entry(id).or_default();
+            start_date = known_start_date.or(start_date);
+            duration = known_duration.or(duration);
+            (*known_start_date, *known_duration) = (start_date, duration);
+        }
+        let end_date = tag.attribute_str("END-DATE").and_then(parse_date_time);
+        let (Some(start_date), Some(end_date), Some(duration)) = (start_date, end_date, duration)
+        else {
+            continue;
+        };
+        // Date-times are only precise to the millisecond.
+        let elapsed = end_date - start_date;
+        if elapsed.round() != (duration * 1000.0).round() {
+            findings.push(Finding::error(
+                line,
+                "daterange-duration",
+                format!(
+                    "END-DATE is {:.3}s after START-DATE, but DURATION is {duration}s.",
+                    elapsed / 1000.0
+                ),
+            ));
+        }
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.5.1.1
+//
+//    A splice_info_section() containing a splice_insert() with out_of_network_indicator set to 1
+//    [...] SHOULD be carried in an EXT-X-DATERANGE tag by the SCTE35-OUT attribute [...] The
+//    SCTE35-IN attribute [...] SHOULD appear in the same EXT-X-DATERANGE tag as the SCTE35-OUT
+//    attribute, or in a subsequent EXT-X-DATERANGE tag with the same ID.
+pub fn scte35_breaks(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    let mut outs = Vec::new();
+    let mut ins = Vec::new();
+    for (line, tag) in dateranges(playlist) {
+        let Some(id) = tag.attribute_str("ID") else {
+            continue;
+        };
+        if let Some(out) = tag.attribute("SCTE35-OUT") {
+            outs.push((line, id));
+            if let Some(problem) = planned_duration_problem(tag, out.as_str()) {
+                findings.push(Finding::warning(line, "scte35-duration", problem));
+            }
+        }
+        if tag.attribute("SCTE35-IN").is_some() {
+            ins.push((line, id));
+        }
+    }
+    let ended = playlist.first_tag("EXT-X-ENDLIST").is_some();
+    for (index, (line, id)) in outs.iter().enumerate() {
+        if ins.iter().any(|(_, in_id)| in_id == id) {
+            continue;
+        }
+        // A live playlist may not have reached the end of the break yet, unless a later break has
+        // already started.
+        let superseded = outs[index + 1..].iter().any(|(_, later_id)| later_id != id);
+        if ended || superseded {
+            findings.push(Finding::warning(
+                *line,
+                "scte35-pairing",
+                format!("The SCTE35-OUT of \"{id}\" has no SCTE35-IN with the same ID."),
+            ));
+        }
+    }
+    // The SCTE35-OUT of a live playlist may already have been removed from the start of it.
+    if ended {
+        for (line, id) in ins {
+            if !outs.iter().any(|(_, out_id)| *out_id == id) {
+                findings.push(Finding::warning(
+                    line,
+                    "scte35-pairing",
+                    format!("The SCTE35-IN of \"{id}\" has no SCTE35-OUT with the same ID."),
+                ));
+            }
+        }
+    }
+}
+
+fn planned_duration_problem(tag: &TagLine, out: &str) -> Option<String> {
+    let break_duration = decode_attribute(out)
+        .as_deref()
+        .and_then(splice_insert)
+        .and_then(|command| command.break_duration)?
+        .seconds();
+    match tag.attribute_str("PLANNED-DURATION").and_then(seconds) {
+        None => Some(format!(
+            "The SCTE35-OUT splice_insert has a break_duration of {break_duration:.3}s, which \
+             should also be given as the PLANNED-DURATION."
+        )),
+        Some(planned) if (planned * 1000.0).round() != (break_duration * 1000.0).round() => {
+            Some(format!(
+                "PLANNED-DURATION is {planned}s, but the break_duration of the SCTE35-OUT \
+                 splice_insert is {break_duration:.3}s."
+            ))
+        }
+        Some(_) => None,
+    }
+}
+
+fn dateranges<'a, 'b>(
+    playlist: &'b Playlist<'a>,
+) -> impl Iterator<Item = (usize, &'b TagLine<'a>)> {
+    playlist
+        .tags()
+        .filter(|(_, tag)| tag.name == "EXT-X-DATERANGE")
+}
+
+fn seconds(value: &str) -> Option<f64> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|s| s.is_finite() && *s >= 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn messages(rule: fn(&Playlist, &mut Vec<Finding>), playlist: &str) -> Vec<String> {
+        let playlist = Playlist::new(playlist);
+        let mut findings = Vec::new();
+        rule(&playlist, &mut findings);
+        findings.into_iter().map(|f| f.message).collect()
+    }
+
+    // splice_insert out of network with a 30 second break_duration.
+    const OUT: &str =
+        "0xFC302500000000000000FFF01405000000017FEFFE00000000FE002932E00000000000000000";
+
+    #[test]
+    fn reused_ids_must_agree() {
+        let playlist = "#EXTM3U
+#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2024-01-01T00:00:00Z\",PLANNED-DURATION=30
+#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2024-01-01T00:00:00Z\",DURATION=30
+#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2024-01-01T00:01:00Z\"
+";
+        assert_eq!(
+            vec![String::from(
+                "EXT-X-DATERANGE ID \"a\" is reused with START-DATE=2024-01-01T00:01:00Z, but line \
+                 2 has START-DATE=2024-01-01T00:00:00Z. Tags with the same ID must agree on the \
+                 attributes that they share."
+            )],
+            messages(daterange_ids, playlist)
+        );
+    }
+
+    #[test]
+    fn end_date_must_match_duration() {
+        let playlist = "#EXTM3U
+#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2024-01-01T00:00:00Z\",END-DATE=\"2024-01-01T00:00:30.500Z\",DURATION=30.5
+#EXT-X-DATERANGE:ID=\"b\",START-DATE=\"2024-01-01T00:01:00Z\",DURATION=30
+#EXT-X-DATERANGE:ID=\"b\",END-DATE=\"2024-01-01T00:01:20Z\"
+";
+        assert_eq!(
+            Vec::<String>::new(),
+            messages(daterange_durations, &playlist.replace("01:20Z", "01:30Z"))
+        );
+        assert_eq!(
+            vec![String::from(
+                "END-DATE is 20.000s after START-DATE, but DURATION is 30s."
+            )],
+            messages(daterange_durations, playlist)
+        );
+    }
+
+    #[test]
+    fn planned_duration_must_match_break_duration() {
+        let playlist = format!(
+            "#EXTM3U\n#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2024-01-01T00:00:00Z\",\
+             PLANNED-DURATION=30.000,SCTE35-OUT={OUT}\n\
+             #EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2024-01-01T00:00:00Z\",SCTE35-IN=0xFC\n\
+             #EXT-X-ENDLIST\n"
+        );
+        assert_eq!(Vec::<String>::new(), messages(scte35_breaks, &playlist));
+        assert_eq!(
+            vec![String::from(
+                "PLANNED-DURATION is 15s, but the break_duration of the SCTE35-OUT splice_insert \
+                 is 30.000s."
+            )],
+            messages(scte35_breaks, &playlist.replace("=30.000", "=15"))
+        );
+    }
+
+    #[test]
+    fn out_must_be_followed_by_in() {
+        let live = "#EXTM3U
+#EXT-X-DATERANGE:ID=\"a\",START-DATE=\"2024-01-01T00:00:00Z\",SCTE35-OUT=0xFC
+#EXT-X-DATERANGE:ID=\"b\",START-DATE=\"2024-01-01T00:01:00Z\",SCTE35-OUT=0xFC
+";
+        assert_eq!(
+            vec![String::from(
+                "The SCTE35-OUT of \"a\" has no SCTE35-IN with the same ID."
+            )],
+            messages(scte35_breaks, live)
+        );
+        assert_eq!(
+            vec![
+                String::from("The SCTE35-OUT of \"a\" has no SCTE35-IN with the same ID."),
+                String::from("The SCTE35-OUT of \"b\" has no SCTE35-IN with the same ID."),
+            ],
+            messages(scte35_breaks, &format!("{live}#EXT-X-ENDLIST\n"))
+        );
+    }
+}
@@ -0,0 +1,104 @@
+// The findings of a validation as JSON and CSV, so that reports can be archived alongside a ticket
+// or checked in CI. Severities are written as `error` and `warning` whatever the language of the
+// viewer, so that scripts can rely on them.
+
+use super::{Finding, Severity};
+use crate::utils::csv::csv;
+use serde_json::{Value, json};
+
+/// The findings as a pretty printed JSON document, with a count of each severity for gating on.
+pub fn findings_json(findings: &[Finding]) -> String {
+    serde_json::to_string_pretty(&findings_value(findings)).unwrap_or_default()
+}
+
+/// The findings as the JSON value that [`findings_json`] writes out.
+pub fn findings_value(findings: &[Finding]) -> Value {
+    let count = |severity| findings.iter().filter(|f| f.severity == severity).count();
+    json!({
+        "errors": count(Severity::Error),
+        "warnings": count(Severity::Warning),
+        "findings": findings
+            .iter()
+            .map(|finding| json!({
+                "rule": finding.rule,
+                "severity": severity_id(finding.severity),
+                "line": finding.line,
+                "message": finding.message,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// The findings as CSV, one row each, with the line left empty for findings about the playlist as
+/// a whole.
+pub fn findings_csv(findings: &[Finding]) -> String {
+    let header = ["rule", "severity", "line", "message"].map(String::from);
+    let rows = findings.iter().map(|finding| {
+        [
+            finding.rule.to_string(),
+            severity_id(finding.severity).to_string(),
+            finding
+                .line
+                .map(|line| line.to_string())
+                .unwrap_or_default(),
+            finding.message.clone(),
+        ]
+    });
+    csv(std::iter::once(header).chain(rows))
+}
+
+fn severity_id(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn findings() -> Vec<Finding> {
+        vec![
+            Finding::error(None, "playlist-kind", "Neither media nor multivariant."),
+            Finding::warning(3, "attribute-list", "Unknown attribute \"FOO\", ignored."),
+        ]
+    }
+
+    #[test]
+    fn json_has_counts_and_findings() {
+        let json = serde_json::from_str::<Value>(&findings_json(&findings())).unwrap();
+        assert_eq!(
+            json!({
+                "errors": 1,
+                "warnings": 1,
+                "findings": [
+                    {
+                        "rule": "playlist-kind",
+                        "severity": "error",
+                        "line": null,
+                        "message": "Neither media nor multivariant.",
+                    },
+                    {
+                        "rule": "attribute-list",
+                        "severity": "warning",
+                        "line": 3,
+                        "message": "Unknown attribute \"FOO\", ignored.",
+                    },
+                ],
+            }),
+            json
+        );
+    }
+
+    #[test]
+    fn csv_has_a_row_per_finding() {
+        assert_eq!(
+            "rule,severity,line,message\r\n\
+             playlist-kind,error,,Neither media nor multivariant.\r\n\
+             attribute-list,warning,3,\"Unknown attribute \"\"FOO\"\", ignored.\"\r\n",
+            findings_csv(&findings())
+        );
+    }
+}
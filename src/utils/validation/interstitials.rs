@@ -0,0 +1,185 @@
+use super::{Finding, Playlist};
+use crate::utils::{
+    daterange_cue::INTERSTITIAL_CLASS,
+    playlist_lines::{AttributeValue, TagLine},
+};
+
+// Validation of the EXT-X-DATERANGE tags that schedule interstitials, which are identified by a
+// CLASS of "com.apple.hls.interstitial".
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#appendix-D.2
+//
+//    X-ASSET-URI: [...] Each Interstitial EXT-X-DATERANGE tag MUST have either an X-ASSET-URI
+//    attribute or an X-ASSET-LIST attribute.  It MUST NOT have both.
+//
+//    X-RESUME-OFFSET: The value of X-RESUME-OFFSET is a decimal-floating-point of seconds that
+//    specifies where primary playback is to resume following the playback of the interstitial.
+//
+//    X-PLAYOUT-LIMIT: The value of X-PLAYOUT-LIMIT is a decimal-floating-point of seconds that
+//    specifies a limit for the playout time of the entire interstitial.
+//
+//    X-RESTRICT: The value of X-RESTRICT is a quoted-string containing an enumerated-string-list of
+//    Navigation Restriction Identifiers.  The following Navigation Restriction Identifiers are
+//    defined: SKIP [...] JUMP [...]
+pub fn interstitials(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    for (line, tag) in playlist.tags() {
+        if tag.name != "EXT-X-DATERANGE" || tag.attribute_str("CLASS") != Some(INTERSTITIAL_CLASS) {
+            continue;
+        }
+        let id = tag.attribute_str("ID").unwrap_or("(no ID)");
+        for problem in interstitial_problems(tag) {
+            findings.push(Finding::error(
+                line,
+                "interstitial",
+                format!("Interstitial \"{id}\": {problem}"),
+            ));
+        }
+    }
+}
+
+fn interstitial_problems(tag: &TagLine) -> Vec<String> {
+    let mut problems = Vec::new();
+    // ID and START-DATE are required of every EXT-X-DATERANGE, which the attribute-list rule
+    // already reports.
+    match (tag.attribute("X-ASSET-URI"), tag.attribute("X-ASSET-LIST")) {
+        (None, None) => problems.push(String::from(
+            "must have either an X-ASSET-URI or an X-ASSET-LIST attribute.",
+        )),
+        (Some(_), Some(_)) => problems.push(String::from(
+            "must not have both an X-ASSET-URI and an X-ASSET-LIST attribute.",
+        )),
+        (Some(value), None) | (None, Some(value)) => {
+            if !matches!(value, AttributeValue::Quoted(_)) {
+                problems.push(String::from(
+                    "the asset URI (X-ASSET-URI or X-ASSET-LIST) must be a quoted-string.",
+                ));
+            }
+        }
+    }
+    for (name, must_be_positive) in [("X-RESUME-OFFSET", false), ("X-PLAYOUT-LIMIT", true)] {
+        let Some(value) = tag.attribute(name) else {
+            continue;
+        };
+        let seconds = match value {
+            AttributeValue::Unquoted(value) => value.parse::<f64>().ok(),
+            AttributeValue::Quoted(_) => None,
+        };
+        match seconds {
+            Some(seconds) if seconds.is_finite() && seconds >= 0.0 => {
+                if must_be_positive && seconds == 0.0 {
+                    problems.push(format!("{name} must be greater than 0."));
+                }
+            }
+            _ => problems.push(format!(
+                "{name} must be a decimal-floating-point number of seconds but was {}.",
+                value.as_str()
+            )),
+        }
+    }
+    enumerated_list(tag, "X-RESTRICT", &["SKIP", "JUMP"], &mut problems);
+    enumerated_list(tag, "X-SNAP", &["OUT", "IN"], &mut problems);
+    problems
+}
+
+// Checks an attribute whose value is a quoted-string containing an enumerated-string-list.
+fn enumerated_list(tag: &TagLine, name: &str, identifiers: &[&str], problems: &mut Vec<String>) {
+    match tag.attribute(name) {
+        None => (),
+        Some(AttributeValue::Unquoted(_)) => {
+            problems.push(format!("{name} must be a quoted-string."));
+        }
+        Some(AttributeValue::Quoted(value)) => {
+            for identifier in value.split(',').map(str::trim) {
+                if !identifiers.contains(&identifier) {
+                    problems.push(format!(
+                        "{name} contains \"{identifier}\", which is not one of {}.",
+                        identifiers.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn messages(playlist: &str) -> Vec<String> {
+        let playlist = Playlist::new(playlist);
+        let mut findings = Vec::new();
+        interstitials(&playlist, &mut findings);
+        findings.into_iter().map(|f| f.message).collect()
+    }
+
+    #[test]
+    fn valid_interstitial_has_no_findings() {
+        assert_eq!(
+            Vec::<String>::new(),
+            messages(
+                "#EXTM3U\n#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",\
+                 START-DATE=\"2024-01-01T00:00:00Z\",X-ASSET-URI=\"ad.m3u8\",X-RESUME-OFFSET=0,\
+                 X-PLAYOUT-LIMIT=30.5,X-RESTRICT=\"SKIP,JUMP\",X-SNAP=\"OUT\"\n"
+            )
+        );
+    }
+
+    #[test]
+    fn asset_uri_and_list_are_exclusive() {
+        assert_eq!(
+            vec![String::from(
+                "Interstitial \"ad1\": must not have both an X-ASSET-URI and an X-ASSET-LIST \
+                 attribute."
+            )],
+            messages(
+                "#EXTM3U\n#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",\
+                 START-DATE=\"2024-01-01T00:00:00Z\",X-ASSET-URI=\"ad.m3u8\",\
+                 X-ASSET-LIST=\"ads.json\"\n"
+            )
+        );
+        assert_eq!(
+            vec![String::from(
+                "Interstitial \"ad1\": must have either an X-ASSET-URI or an X-ASSET-LIST \
+                 attribute."
+            )],
+            messages(
+                "#EXTM3U\n#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",\
+                 START-DATE=\"2024-01-01T00:00:00Z\"\n"
+            )
+        );
+    }
+
+    #[test]
+    fn offsets_and_restrictions_are_checked() {
+        assert_eq!(
+            vec![
+                String::from(
+                    "Interstitial \"ad1\": X-RESUME-OFFSET must be a decimal-floating-point \
+                     number of seconds but was -5."
+                ),
+                String::from("Interstitial \"ad1\": X-PLAYOUT-LIMIT must be greater than 0."),
+                String::from(
+                    "Interstitial \"ad1\": X-RESTRICT contains \"SEEK\", which is not one of \
+                     SKIP, JUMP."
+                ),
+            ],
+            messages(
+                "#EXTM3U\n#EXT-X-DATERANGE:ID=\"ad1\",CLASS=\"com.apple.hls.interstitial\",\
+                 START-DATE=\"2024-01-01T00:00:00Z\",X-ASSET-LIST=\"ads.json\",\
+                 X-RESUME-OFFSET=-5,X-PLAYOUT-LIMIT=0,X-RESTRICT=\"SKIP,SEEK\"\n"
+            )
+        );
+    }
+
+    #[test]
+    fn other_classes_are_not_checked() {
+        assert_eq!(
+            Vec::<String>::new(),
+            messages(
+                "#EXTM3U\n#EXT-X-DATERANGE:ID=\"a\",CLASS=\"com.example\",\
+                 START-DATE=\"2024-01-01T00:00:00Z\"\n"
+            )
+        );
+    }
+}
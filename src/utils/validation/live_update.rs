@@ -0,0 +1,289 @@
+use super::{Finding, Playlist};
+use crate::utils::playlist_lines::LineKind;
+use std::ops::Range;
+
+// Validation of the change between two snapshots of a live Media Playlist.
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-6.2.1
+//
+//    The server MUST NOT change the Media Playlist file, except to:
+//    o  Append lines to it (Section 6.2.1).
+//    o  Remove Media Segment URIs from the Playlist in the order that they appear, along with any
+//       tags that apply only to those Media Segments (Section 6.2.2).
+//    o  Increment the value of the EXT-X-MEDIA-SEQUENCE or EXT-X-DISCONTINUITY-SEQUENCE tags
+//       (Section 4.4.3).
+//    o  Add an EXT-X-ENDLIST tag to the Playlist (Section 4.4.3.4).
+//
+//    [...] The EXT-X-TARGETDURATION value MUST NOT change.
+//
+// The findings relate to the lines of the `current` playlist.
+pub fn validate_update(previous: &str, current: &str) -> Vec<Finding> {
+    let previous = Snapshot::new(&Playlist::new(previous));
+    let current = Snapshot::new(&Playlist::new(current));
+    let mut findings = Vec::new();
+    if current.media_sequence < previous.media_sequence {
+        findings.push(Finding::error(
+            current.media_sequence_line,
+            "live-media-sequence",
+            format!(
+                "EXT-X-MEDIA-SEQUENCE went from {} to {}, but it must never decrease.",
+                previous.media_sequence, current.media_sequence
+            ),
+        ));
+    }
+    if let (Some(before), Some((line, after))) = (previous.target_duration, current.target_duration)
+        && before.1 != after
+    {
+        findings.push(Finding::error(
+            line,
+            "live-target-duration",
+            format!(
+                "EXT-X-TARGETDURATION changed from {} to {after}, but it must not change.",
+                before.1
+            ),
+        ));
+    }
+    let last_sequence = current.segments.last().map(|s| s.media_sequence);
+    for segment in &previous.segments {
+        if segment.media_sequence < current.media_sequence
+            || current.skipped.contains(&segment.media_sequence)
+        {
+            continue;
+        }
+        let Some(now) = current
+            .segments
+            .iter()
+            .find(|s| s.media_sequence == segment.media_sequence)
+        else {
+            if last_sequence.is_none_or(|last| segment.media_sequence > last) {
+                findings.push(Finding::error(
+                    None,
+                    "live-segment-removed",
+                    format!(
+                        "Segment {} ({}) was removed from the end of the playlist, but segments \
+                         may only be removed from the start.",
+                        segment.media_sequence, segment.uri
+                    ),
+                ));
+            }
+            continue;
+        };
+        if now.uri != segment.uri {
+            findings.push(Finding::error(
+                now.line,
+                "live-segment-changed",
+                format!(
+                    "The URI of segment {} changed from {} to {}.",
+                    segment.media_sequence, segment.uri, now.uri
+                ),
+            ));
+        }
+        if seconds(now.duration) != seconds(segment.duration) {
+            findings.push(Finding::error(
+                now.duration_line.unwrap_or(now.line),
+                "live-segment-changed",
+                format!(
+                    "The EXTINF duration of segment {} changed from {} to {}.",
+                    segment.media_sequence,
+                    segment.duration.unwrap_or("(none)"),
+                    now.duration.unwrap_or("(none)")
+                ),
+            ));
+        }
+    }
+    findings.sort_by_key(|finding| (finding.line, finding.severity));
+    findings
+}
+
+/// Whether the playlist is a Media Playlist that may still be updated, and so is worth rechecking.
+pub fn is_live(playlist: &str) -> bool {
+    let playlist = Playlist::new(playlist);
+    playlist.first_tag("EXT-X-TARGETDURATION").is_some()
+        && playlist.first_tag("EXT-X-ENDLIST").is_none()
+        && playlist
+            .first_tag("EXT-X-PLAYLIST-TYPE")
+            .and_then(|(_, tag)| tag.value)
+            .is_none_or(|value| value.trim() != "VOD")
+}
+
+fn seconds(duration: Option<&str>) -> Option<f64> {
+    duration.and_then(|d| d.parse::<f64>().ok())
+}
+
+struct Snapshot<'a> {
+    media_sequence: u64,
+    media_sequence_line: Option<usize>,
+    target_duration: Option<(usize, &'a str)>,
+    // The Media Sequence Numbers of the segments replaced by an EXT-X-SKIP in a Playlist Delta
+    // Update, which are unchanged by definition.
+    skipped: Range<u64>,
+    segments: Vec<Segment<'a>>,
+}
+
+struct Segment<'a> {
+    media_sequence: u64,
+    line: usize,
+    uri: &'a str,
+    duration_line: Option<usize>,
+    duration: Option<&'a str>,
+}
+
+impl<'a> Snapshot<'a> {
+    fn new(playlist: &Playlist<'a>) -> Self {
+        let mut snapshot = Self {
+            media_sequence: 0,
+            media_sequence_line: None,
+            target_duration: None,
+            skipped: 0..0,
+            segments: Vec::new(),
+        };
+        let mut next_sequence = 0;
+        let mut duration = None;
+        for line in &playlist.lines {
+            match &line.kind {
+                LineKind::Tag(tag) => match tag.name {
+                    "EXT-X-MEDIA-SEQUENCE" => {
+                        next_sequence = tag.value.and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+                        snapshot.media_sequence = next_sequence;
+                        snapshot.media_sequence_line = Some(line.number);
+                    }
+                    "EXT-X-TARGETDURATION" => {
+                        snapshot.target_duration =
+                            tag.value.map(|value| (line.number, value.trim()));
+                    }
+                    "EXTINF" => {
+                        duration = tag.value.map(|value| {
+                            let duration = value.split_once(',').map(|(d, _)| d).unwrap_or(value);
+                            (line.number, duration.trim())
+                        });
+                    }
+                    "EXT-X-SKIP" => {
+                        let skipped = tag
+                            .attribute_str("SKIPPED-SEGMENTS")
+                            .and_then(|v| v.parse::<u64>().ok())
+                            .unwrap_or(0);
+                        snapshot.skipped = next_sequence..next_sequence + skipped;
+                        next_sequence += skipped;
+                    }
+                    _ => (),
+                },
+                LineKind::Uri(uri) => {
+                    let (duration_line, duration) = duration.take().unzip();
+                    snapshot.segments.push(Segment {
+                        media_sequence: next_sequence,
+                        line: line.number,
+                        uri,
+                        duration_line,
+                        duration,
+                    });
+                    next_sequence += 1;
+                }
+                _ => (),
+            }
+        }
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PREVIOUS: &str = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXT-X-MEDIA-SEQUENCE:10
+#EXTINF:6.000,
+10.ts
+#EXTINF:6.000,
+11.ts
+#EXTINF:6.000,
+12.ts
+";
+
+    fn messages(previous: &str, current: &str) -> Vec<String> {
+        validate_update(previous, current)
+            .into_iter()
+            .map(|f| f.message)
+            .collect()
+    }
+
+    #[test]
+    fn sliding_window_is_valid() {
+        let current = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXT-X-MEDIA-SEQUENCE:11
+#EXTINF:6.000,
+11.ts
+#EXTINF:6.000,
+12.ts
+#EXTINF:6.000,
+13.ts
+";
+        assert_eq!(Vec::<String>::new(), messages(PREVIOUS, current));
+        assert_eq!(
+            Vec::<String>::new(),
+            messages(PREVIOUS, &format!("{PREVIOUS}#EXT-X-ENDLIST\n"))
+        );
+    }
+
+    #[test]
+    fn published_segments_must_not_change() {
+        let current = "#EXTM3U
+#EXT-X-TARGETDURATION:4
+#EXT-X-MEDIA-SEQUENCE:11
+#EXTINF:6.000,
+11b.ts
+#EXTINF:5.000,
+12.ts
+";
+        assert_eq!(
+            vec![
+                String::from("EXT-X-TARGETDURATION changed from 6 to 4, but it must not change."),
+                String::from("The URI of segment 11 changed from 11.ts to 11b.ts."),
+                String::from("The EXTINF duration of segment 12 changed from 6.000 to 5.000."),
+            ],
+            messages(PREVIOUS, current)
+        );
+    }
+
+    #[test]
+    fn segments_are_only_removed_from_the_start() {
+        let current = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXT-X-MEDIA-SEQUENCE:9
+#EXTINF:6.000,
+9.ts
+#EXTINF:6.000,
+10.ts
+#EXTINF:6.000,
+11.ts
+";
+        assert_eq!(
+            vec![
+                String::from(
+                    "Segment 12 (12.ts) was removed from the end of the playlist, but segments may \
+                     only be removed from the start."
+                ),
+                String::from("EXT-X-MEDIA-SEQUENCE went from 10 to 9, but it must never decrease."),
+            ],
+            messages(PREVIOUS, current)
+        );
+    }
+
+    #[test]
+    fn skipped_segments_are_unchanged() {
+        let current = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXT-X-MEDIA-SEQUENCE:10
+#EXT-X-SKIP:SKIPPED-SEGMENTS=2
+#EXTINF:6.000,
+12.ts
+#EXTINF:6.000,
+13.ts
+";
+        assert_eq!(Vec::<String>::new(), messages(PREVIOUS, current));
+        assert!(is_live(current));
+        assert!(!is_live(&format!("{current}#EXT-X-ENDLIST\n")));
+    }
+}
@@ -0,0 +1,272 @@
+mod attributes;
+mod dateranges;
+mod export;
+mod interstitials;
+mod live_update;
+mod structure;
+mod version;
+
+use crate::utils::{
+    i18n::{Message, tr},
+    playlist_lines::{self, PlaylistLine},
+};
+pub use attributes::{AttributeType, attribute_type};
+pub use export::{findings_csv, findings_json, findings_value};
+pub use live_update::{is_live, validate_update};
+use std::{collections::HashMap, fmt::Display};
+
+// Validation of a playlist against the rules set out in
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17
+//
+// Each rule is a function that inspects the parsed lines of the playlist and records any findings.
+// Rules deliberately work from the lenient `playlist_lines` model so that problems can still be
+// reported for input that a strict parser would reject outright.
+type Rule = fn(&Playlist, &mut Vec<Finding>);
+
+const RULES: &[Rule] = &[
+    structure::playlist_header,
+    structure::playlist_kind,
+    structure::unique_tags,
+    structure::media_playlist_tags,
+    structure::segment_uris,
+    structure::variant_uris,
+    attributes::attribute_lists,
+    attributes::tag_values,
+    version::version_constraints,
+    interstitials::interstitials,
+    dateranges::daterange_ids,
+    dateranges::daterange_durations,
+    dateranges::scte35_breaks,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    /// The 1-based line number that the finding relates to, or `None` when the finding relates to
+    /// the playlist as a whole (e.g. a missing mandatory tag).
+    pub line: Option<usize>,
+    /// A short identifier for the rule that produced the finding.
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl Finding {
+    fn error(
+        line: impl Into<Option<usize>>,
+        rule: &'static str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity: Severity::Error,
+            line: line.into(),
+            rule,
+            message: message.into(),
+        }
+    }
+
+    fn warning(
+        line: impl Into<Option<usize>>,
+        rule: &'static str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity: Severity::Warning,
+            line: line.into(),
+            rule,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error => write!(f, "{}", tr(Message::SeverityError)),
+            Self::Warning => write!(f, "{}", tr(Message::SeverityWarning)),
+        }
+    }
+}
+
+/// Runs every validation rule over the playlist, returning the findings ordered by line number.
+pub fn validate(playlist: &str) -> Vec<Finding> {
+    let playlist = Playlist::new(playlist);
+    let mut findings = Vec::new();
+    for rule in RULES {
+        rule(&playlist, &mut findings);
+    }
+    findings.sort_by_key(|finding| (finding.line, finding.severity));
+    findings
+}
+
+/// Groups the findings by the line that they relate to, so that they can be shown alongside it.
+/// Findings that relate to the playlist as a whole are left out.
+pub fn findings_by_line(findings: &[Finding]) -> HashMap<usize, Vec<Finding>> {
+    let mut by_line = HashMap::<usize, Vec<Finding>>::new();
+    for finding in findings {
+        if let Some(line) = finding.line {
+            by_line.entry(line).or_default().push(finding.clone());
+        }
+    }
+    by_line
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PlaylistKind {
+    Media,
+    Multivariant,
+    Unknown,
+}
+
+struct Playlist<'a> {
+    lines: Vec<PlaylistLine<'a>>,
+    kind: PlaylistKind,
+}
+
+impl<'a> Playlist<'a> {
+    fn new(playlist: &'a str) -> Self {
+        let lines = playlist_lines::parse(playlist);
+        // The playlist kind is decided by the first tag that is exclusive to one kind of playlist.
+        // Any tags of the other kind are then reported by the `playlist_kind` rule.
+        let kind = lines
+            .iter()
+            .filter_map(|line| line.tag())
+            .find_map(|tag| {
+                if MULTIVARIANT_TAGS.contains(&tag.name) {
+                    Some(PlaylistKind::Multivariant)
+                } else if MEDIA_TAGS.contains(&tag.name) {
+                    Some(PlaylistKind::Media)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(PlaylistKind::Unknown);
+        Self { lines, kind }
+    }
+
+    fn tags(&self) -> impl Iterator<Item = (usize, &playlist_lines::TagLine<'a>)> {
+        self.lines
+            .iter()
+            .filter_map(|line| line.tag().map(|tag| (line.number, tag)))
+    }
+
+    fn first_tag(&self, name: &str) -> Option<(usize, &playlist_lines::TagLine<'a>)> {
+        self.tags().find(|(_, tag)| tag.name == name)
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.4
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.6
+const MULTIVARIANT_TAGS: &[&str] = &[
+    "EXT-X-MEDIA",
+    "EXT-X-STREAM-INF",
+    "EXT-X-I-FRAME-STREAM-INF",
+    "EXT-X-SESSION-DATA",
+    "EXT-X-SESSION-KEY",
+    "EXT-X-CONTENT-STEERING",
+    "EXT-X-IMAGE-STREAM-INF",
+];
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.3
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.4
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.5
+const MEDIA_TAGS: &[&str] = &[
+    "EXT-X-TARGETDURATION",
+    "EXT-X-MEDIA-SEQUENCE",
+    "EXT-X-DISCONTINUITY-SEQUENCE",
+    "EXT-X-ENDLIST",
+    "EXT-X-PLAYLIST-TYPE",
+    "EXT-X-I-FRAMES-ONLY",
+    "EXT-X-PART-INF",
+    "EXT-X-SERVER-CONTROL",
+    "EXTINF",
+    "EXT-X-BYTERANGE",
+    "EXT-X-DISCONTINUITY",
+    "EXT-X-KEY",
+    "EXT-X-MAP",
+    "EXT-X-PROGRAM-DATE-TIME",
+    "EXT-X-GAP",
+    "EXT-X-BITRATE",
+    "EXT-X-PART",
+    "EXT-X-DATERANGE",
+    "EXT-X-SKIP",
+    "EXT-X-PRELOAD-HINT",
+    "EXT-X-RENDITION-REPORT",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn rules(findings: &[Finding]) -> Vec<(Option<usize>, &'static str)> {
+        findings.iter().map(|f| (f.line, f.rule)).collect()
+    }
+
+    #[test]
+    fn valid_media_playlist_has_no_findings() {
+        let playlist = "#EXTM3U
+#EXT-X-VERSION:3
+#EXT-X-TARGETDURATION:6
+#EXT-X-MEDIA-SEQUENCE:10
+#EXTINF:5.97,
+segment10.ts
+#EXT-X-KEY:METHOD=AES-128,URI=\"key.bin\",IV=0x0123456789abcdef0123456789ABCDEF
+#EXTINF:6.006,
+segment11.ts
+#EXT-X-ENDLIST
+";
+        assert_eq!(Vec::<Finding>::new(), validate(playlist));
+    }
+
+    #[test]
+    fn valid_multivariant_playlist_has_no_findings() {
+        let playlist = "#EXTM3U
+#EXT-X-INDEPENDENT-SEGMENTS
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",DEFAULT=YES,URI=\"audio.m3u8\"
+#EXT-X-STREAM-INF:BANDWIDTH=1280000,RESOLUTION=1280x720,FRAME-RATE=29.970,CODECS=\"avc1.64001f,mp4a.40.2\",AUDIO=\"aac\"
+video.m3u8
+";
+        assert_eq!(Vec::<Finding>::new(), validate(playlist));
+    }
+
+    #[test]
+    fn findings_are_ordered_by_line() {
+        let playlist = "#EXT-X-TARGETDURATION:6
+#EXTINF:10,
+segment.ts
+#EXT-X-TARGETDURATION:6
+";
+        assert_eq!(
+            vec![
+                (Some(1), "playlist-header"),
+                (Some(2), "extinf-duration"),
+                (Some(4), "unique-tag"),
+            ],
+            rules(&validate(playlist))
+        );
+    }
+
+    #[test]
+    fn findings_are_grouped_by_line() {
+        let findings = vec![
+            Finding::error(None, "missing-tag", "Missing"),
+            Finding::error(2, "first", "First"),
+            Finding::warning(2, "second", "Second"),
+            Finding::warning(5, "third", "Third"),
+        ];
+        let by_line = findings_by_line(&findings);
+        assert_eq!(2, by_line.len());
+        assert_eq!(vec!["first", "second"], rules_of(&by_line[&2]));
+        assert_eq!(vec!["third"], rules_of(&by_line[&5]));
+    }
+
+    fn rules_of(findings: &[Finding]) -> Vec<&'static str> {
+        findings.iter().map(|f| f.rule).collect()
+    }
+}
@@ -0,0 +1,361 @@
+use super::{Finding, MEDIA_TAGS, MULTIVARIANT_TAGS, Playlist, PlaylistKind};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    playlist_lines::LineKind,
+};
+use std::collections::HashMap;
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.1.1
+//
+//    The EXTM3U tag indicates that the file is an Extended M3U [M3U]
+//    Playlist file.  It MUST be the first line of every Media Playlist and
+//    every Multivariant Playlist.
+pub fn playlist_header(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    let first_line = playlist.lines.first();
+    if first_line
+        .and_then(|line| line.tag())
+        .is_none_or(|tag| tag.name != "EXTM3U")
+    {
+        findings.push(Finding::error(
+            first_line.map(|line| line.number),
+            "playlist-header",
+            tr(Message::PlaylistHeaderMissing),
+        ));
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.1
+//
+//    A Playlist is either a Media Playlist or a Multivariant Playlist.
+//    [...]
+//    A Multivariant Playlist MUST NOT contain any Media Playlist tags, and a Media Playlist MUST
+//    NOT contain any Multivariant Playlist tags.
+pub fn playlist_kind(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    let (foreign_tags, description) = match playlist.kind {
+        PlaylistKind::Media => (MULTIVARIANT_TAGS, Message::PlaylistKindMedia),
+        PlaylistKind::Multivariant => (MEDIA_TAGS, Message::PlaylistKindMultivariant),
+        PlaylistKind::Unknown => return,
+    };
+    for (line, tag) in playlist.tags() {
+        if foreign_tags.contains(&tag.name) {
+            findings.push(Finding::error(
+                line,
+                "playlist-kind",
+                tr_with(
+                    Message::PlaylistKindMixed,
+                    &[("tag", tag.name), ("kind", tr(description))],
+                ),
+            ));
+        }
+    }
+}
+
+// Tags that the specification states MUST NOT appear more than once in a playlist.
+const UNIQUE_TAGS: &[&str] = &[
+    "EXT-X-VERSION",
+    "EXT-X-INDEPENDENT-SEGMENTS",
+    "EXT-X-START",
+    "EXT-X-TARGETDURATION",
+    "EXT-X-MEDIA-SEQUENCE",
+    "EXT-X-DISCONTINUITY-SEQUENCE",
+    "EXT-X-ENDLIST",
+    "EXT-X-PLAYLIST-TYPE",
+    "EXT-X-I-FRAMES-ONLY",
+    "EXT-X-PART-INF",
+    "EXT-X-SERVER-CONTROL",
+    "EXT-X-SKIP",
+    "EXT-X-CONTENT-STEERING",
+];
+
+pub fn unique_tags(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    let mut first_seen: HashMap<&str, usize> = HashMap::new();
+    for (line, tag) in playlist.tags() {
+        if !UNIQUE_TAGS.contains(&tag.name) {
+            continue;
+        }
+        if let Some(first_line) = first_seen.get(tag.name) {
+            findings.push(Finding::error(
+                line,
+                "unique-tag",
+                tr_with(
+                    Message::UniqueTagRepeated,
+                    &[("tag", tag.name), ("line", &first_line.to_string())],
+                ),
+            ));
+        } else {
+            first_seen.insert(tag.name, line);
+        }
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.3.1
+//
+//    The EXT-X-TARGETDURATION tag specifies the Target Duration.  [...]
+//    This tag is REQUIRED.
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.3.2
+//
+//    The EXT-X-MEDIA-SEQUENCE tag MUST appear before the first Media Segment in the Playlist.
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.3.3
+//
+//    The EXT-X-DISCONTINUITY-SEQUENCE tag MUST appear before the first Media Segment in the
+//    Playlist.
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.4.1
+//
+//    The EXTINF duration of each Media Segment in a Playlist file, when rounded to the nearest
+//    integer, MUST be less than or equal to the Target Duration.
+pub fn media_playlist_tags(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    if playlist.kind != PlaylistKind::Media {
+        return;
+    }
+    let target_duration = match playlist.first_tag("EXT-X-TARGETDURATION") {
+        Some((_, tag)) => tag.value.and_then(|v| v.trim().parse::<u64>().ok()),
+        None => {
+            findings.push(Finding::error(
+                None,
+                "target-duration-required",
+                tr(Message::TargetDurationRequired),
+            ));
+            None
+        }
+    };
+    let mut seen_segment = false;
+    for (line, tag) in playlist.tags() {
+        match tag.name {
+            "EXT-X-MEDIA-SEQUENCE" | "EXT-X-DISCONTINUITY-SEQUENCE" if seen_segment => {
+                findings.push(Finding::error(
+                    line,
+                    "tag-placement",
+                    tr_with(Message::TagAfterFirstSegment, &[("tag", tag.name)]),
+                ));
+            }
+            "EXTINF" => {
+                seen_segment = true;
+                let duration = tag
+                    .value
+                    .map(|v| v.split_once(',').map(|(d, _)| d).unwrap_or(v).trim());
+                let Some(duration) = duration.and_then(|d| d.parse::<f64>().ok()) else {
+                    findings.push(Finding::error(
+                        line,
+                        "extinf-duration",
+                        tr(Message::ExtinfDurationMissing),
+                    ));
+                    continue;
+                };
+                if let Some(target_duration) = target_duration
+                    && duration.round() > target_duration as f64
+                {
+                    findings.push(Finding::error(
+                        line,
+                        "extinf-duration",
+                        tr_with(
+                            Message::ExtinfDurationTooLong,
+                            &[
+                                ("duration", &duration.to_string()),
+                                ("target_duration", &target_duration.to_string()),
+                            ],
+                        ),
+                    ));
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.4.1
+//
+//    The EXTINF tag specifies the duration of a Media Segment.  It applies only to the next Media
+//    Segment.  This tag is REQUIRED for each Media Segment.
+pub fn segment_uris(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    if playlist.kind != PlaylistKind::Media {
+        return;
+    }
+    let mut pending_extinf = None;
+    for line in &playlist.lines {
+        match &line.kind {
+            LineKind::Tag(tag) if tag.name == "EXTINF" => {
+                if let Some(pending) = pending_extinf.replace(line.number) {
+                    findings.push(Finding::error(
+                        pending,
+                        "segment-uri",
+                        tr(Message::ExtinfWithoutUri),
+                    ));
+                }
+            }
+            LineKind::Uri(_) if pending_extinf.is_none() => {
+                findings.push(Finding::error(
+                    line.number,
+                    "segment-uri",
+                    tr(Message::SegmentUriWithoutExtinf),
+                ));
+            }
+            LineKind::Uri(_) => pending_extinf = None,
+            _ => (),
+        }
+    }
+    if let Some(pending) = pending_extinf {
+        findings.push(Finding::error(
+            pending,
+            "segment-uri",
+            tr(Message::ExtinfWithoutUri),
+        ));
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.6.2
+//
+//    The EXT-X-STREAM-INF tag specifies a Variant Stream, which is a set of Renditions that can be
+//    combined to play the presentation.  [...]
+//    The URI line that follows the EXT-X-STREAM-INF tag specifies a Media Playlist that carries a
+//    Rendition of the Variant Stream.  The URI line is REQUIRED.
+pub fn variant_uris(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    if playlist.kind != PlaylistKind::Multivariant {
+        return;
+    }
+    let mut pending_stream_inf = None;
+    for line in &playlist.lines {
+        match &line.kind {
+            LineKind::Uri(_) if pending_stream_inf.is_none() => {
+                findings.push(Finding::error(
+                    line.number,
+                    "variant-uri",
+                    tr(Message::VariantUriWithoutStreamInf),
+                ));
+            }
+            LineKind::Uri(_) => pending_stream_inf = None,
+            LineKind::Tag(tag) => {
+                if let Some(pending) = pending_stream_inf.take() {
+                    findings.push(Finding::error(
+                        pending,
+                        "variant-uri",
+                        tr(Message::StreamInfWithoutUri),
+                    ));
+                }
+                if tag.name == "EXT-X-STREAM-INF" {
+                    pending_stream_inf = Some(line.number);
+                }
+            }
+            LineKind::Comment(_) | LineKind::Blank => (),
+        }
+    }
+    if let Some(pending) = pending_stream_inf {
+        findings.push(Finding::error(
+            pending,
+            "variant-uri",
+            tr(Message::StreamInfWithoutUri),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn run(rule: super::super::Rule, playlist: &str) -> Vec<(Option<usize>, &'static str)> {
+        let playlist = Playlist::new(playlist);
+        let mut findings = Vec::new();
+        rule(&playlist, &mut findings);
+        findings.iter().map(|f| (f.line, f.rule)).collect()
+    }
+
+    #[test]
+    fn missing_header_is_reported_on_first_line() {
+        assert_eq!(
+            vec![(Some(1), "playlist-header")],
+            run(playlist_header, "#EXT-X-VERSION:3\n#EXTM3U\n")
+        );
+    }
+
+    #[test]
+    fn mixed_playlist_kinds_are_reported() {
+        let playlist = "#EXTM3U
+#EXT-X-STREAM-INF:BANDWIDTH=1000
+video.m3u8
+#EXT-X-TARGETDURATION:6
+";
+        assert_eq!(
+            vec![(Some(4), "playlist-kind")],
+            run(playlist_kind, playlist)
+        );
+    }
+
+    #[test]
+    fn repeated_unique_tag_is_reported() {
+        let playlist = "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-VERSION:4\n";
+        assert_eq!(vec![(Some(3), "unique-tag")], run(unique_tags, playlist));
+    }
+
+    #[test]
+    fn missing_target_duration_is_reported() {
+        let playlist = "#EXTM3U\n#EXTINF:4,\nsegment.ts\n";
+        assert_eq!(
+            vec![(None, "target-duration-required")],
+            run(media_playlist_tags, playlist)
+        );
+    }
+
+    #[test]
+    fn media_sequence_after_first_segment_is_reported() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXTINF:4,
+segment.ts
+#EXT-X-MEDIA-SEQUENCE:2
+";
+        assert_eq!(
+            vec![(Some(5), "tag-placement")],
+            run(media_playlist_tags, playlist)
+        );
+    }
+
+    #[test]
+    fn extinf_rounding_is_considered_against_target_duration() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXTINF:6.4,
+a.ts
+#EXTINF:6.5,
+b.ts
+";
+        assert_eq!(
+            vec![(Some(5), "extinf-duration")],
+            run(media_playlist_tags, playlist)
+        );
+    }
+
+    #[test]
+    fn extinf_without_uri_and_uri_without_extinf_are_reported() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXTINF:4,
+#EXTINF:4,
+a.ts
+b.ts
+#EXTINF:4,
+";
+        assert_eq!(
+            vec![
+                (Some(3), "segment-uri"),
+                (Some(6), "segment-uri"),
+                (Some(7), "segment-uri"),
+            ],
+            run(segment_uris, playlist)
+        );
+    }
+
+    #[test]
+    fn stream_inf_without_uri_is_reported() {
+        let playlist = "#EXTM3U
+#EXT-X-STREAM-INF:BANDWIDTH=1000
+#EXT-X-STREAM-INF:BANDWIDTH=2000
+
+# comments and blank lines are allowed between the tag and the URI
+video.m3u8
+";
+        assert_eq!(vec![(Some(2), "variant-uri")], run(variant_uris, playlist));
+    }
+}
@@ -0,0 +1,225 @@
+use super::{Finding, Playlist};
+use crate::utils::{
+    i18n::{Message, tr, tr_with},
+    playlist_lines::{LineKind, TagLine},
+};
+
+// A Playlist without an EXT-X-VERSION tag has a compatibility version of 1.
+//
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-8
+//
+//    A Playlist file MUST indicate an EXT-X-VERSION of 2 or higher if it contains:
+//    o  The IV attribute of the EXT-X-KEY tag.
+//
+//    A Playlist file MUST indicate an EXT-X-VERSION of 3 or higher if it contains:
+//    o  Floating-point EXTINF duration values.
+//
+//    ...
+//
+// Features that the protocol gained without a version change (such as Partial Segments) have no
+// bearing on the required version.
+pub fn version_constraints(playlist: &Playlist, findings: &mut Vec<Finding>) {
+    let version_tag = playlist.first_tag("EXT-X-VERSION");
+    let version = version_tag
+        .and_then(|(_, tag)| tag.value)
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(1);
+    let requirements = requirements(playlist);
+    for requirement in &requirements {
+        if requirement.version > version {
+            findings.push(Finding::error(
+                requirement.line,
+                "version",
+                tr_with(
+                    Message::VersionRequired,
+                    &[
+                        ("feature", tr(requirement.feature)),
+                        ("required", &requirement.version.to_string()),
+                        ("version", &version.to_string()),
+                    ],
+                ),
+            ));
+        }
+    }
+    // An implicit version 1 is never unnecessarily high, so only an explicit tag is checked.
+    if let Some((line, _)) = version_tag {
+        let required = requirements.iter().map(|r| r.version).max().unwrap_or(1);
+        if version > required {
+            findings.push(Finding::warning(
+                line,
+                "version-unnecessary",
+                tr_with(
+                    Message::VersionUnnecessary,
+                    &[
+                        ("version", &version.to_string()),
+                        ("required", &required.to_string()),
+                    ],
+                ),
+            ));
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Requirement {
+    line: usize,
+    version: u64,
+    feature: Message,
+}
+
+fn requirements(playlist: &Playlist) -> Vec<Requirement> {
+    let i_frames_only = playlist.first_tag("EXT-X-I-FRAMES-ONLY").is_some();
+    let mut requirements = Vec::new();
+    let mut require = |line, version, feature| {
+        requirements.push(Requirement {
+            line,
+            version,
+            feature,
+        })
+    };
+    for line in &playlist.lines {
+        let tag = match &line.kind {
+            LineKind::Tag(tag) => tag,
+            LineKind::Uri(uri) => {
+                if uri.contains("{$") {
+                    require(line.number, 8, Message::FeatureVariableSubstitution);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if tag.value.is_some_and(|v| v.contains("{$")) {
+            require(line.number, 8, Message::FeatureVariableSubstitution);
+        }
+        match tag.name {
+            "EXT-X-KEY" => {
+                if tag.attribute("IV").is_some() {
+                    require(line.number, 2, Message::FeatureKeyIv);
+                }
+                if tag.attribute("KEYFORMAT").is_some()
+                    || tag.attribute("KEYFORMATVERSIONS").is_some()
+                {
+                    require(line.number, 5, Message::FeatureKeyFormat);
+                }
+            }
+            "EXTINF" if decimal_duration(tag) => {
+                require(line.number, 3, Message::FeatureDecimalDuration);
+            }
+            "EXT-X-BYTERANGE" => require(line.number, 4, Message::FeatureByterange),
+            "EXT-X-I-FRAMES-ONLY" => require(line.number, 4, Message::FeatureIFramesOnly),
+            "EXT-X-MAP" if i_frames_only => require(line.number, 5, Message::FeatureMap),
+            "EXT-X-MAP" => require(line.number, 6, Message::FeatureMapWithoutIFramesOnly),
+            "EXT-X-MEDIA" if tag.attribute_str("INSTREAM-ID").is_some_and(is_service) => {
+                require(line.number, 7, Message::FeatureInstreamIdService);
+            }
+            "EXT-X-DEFINE" if tag.attribute("QUERYPARAM").is_some() => {
+                require(line.number, 11, Message::FeatureDefineQueryparam);
+            }
+            "EXT-X-DEFINE" => require(line.number, 8, Message::FeatureVariableSubstitution),
+            "EXT-X-SKIP" if tag.attribute("RECENTLY-REMOVED-DATERANGES").is_some() => {
+                require(line.number, 10, Message::FeatureSkipDateranges);
+            }
+            "EXT-X-SKIP" => require(line.number, 9, Message::FeatureSkip),
+            _ => (),
+        }
+        if has_req_attribute(tag) {
+            require(line.number, 12, Message::FeatureReqAttribute);
+        }
+    }
+    requirements
+}
+
+fn decimal_duration(tag: &TagLine) -> bool {
+    tag.value
+        .map(|v| v.split_once(',').map(|(d, _)| d).unwrap_or(v).trim())
+        .is_some_and(|d| d.contains('.'))
+}
+
+fn is_service(instream_id: &str) -> bool {
+    instream_id.starts_with("SERVICE")
+}
+
+// Only attribute-list tags can carry REQ- attributes, and other tag values (e.g. EXTINF) never
+// break down into a well formed NAME=VALUE entry that starts with REQ-.
+fn has_req_attribute(tag: &TagLine) -> bool {
+    tag.attributes()
+        .into_iter()
+        .filter_map(Result::ok)
+        .any(|(name, _)| name.starts_with("REQ-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn findings(playlist: &str) -> Vec<(Option<usize>, &'static str)> {
+        let playlist = Playlist::new(playlist);
+        let mut findings = Vec::new();
+        version_constraints(&playlist, &mut findings);
+        findings.into_iter().map(|f| (f.line, f.rule)).collect()
+    }
+
+    #[test]
+    fn decimal_durations_require_version_3() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXTINF:6,
+a.ts
+#EXTINF:5.5,
+b.ts
+";
+        assert_eq!(vec![(Some(5), "version")], findings(playlist));
+        assert_eq!(
+            Vec::<(Option<usize>, &str)>::new(),
+            findings(&playlist.replace("#EXTM3U", "#EXTM3U\n#EXT-X-VERSION:3"))
+        );
+    }
+
+    #[test]
+    fn map_requirement_depends_on_i_frames_only() {
+        let playlist = "#EXTM3U
+#EXT-X-VERSION:5
+#EXT-X-TARGETDURATION:6
+#EXT-X-MAP:URI=\"init.mp4\"
+#EXTINF:6,
+a.mp4
+";
+        assert_eq!(vec![(Some(4), "version")], findings(playlist));
+        assert_eq!(
+            Vec::<(Option<usize>, &str)>::new(),
+            findings(&playlist.replace("#EXT-X-MAP", "#EXT-X-I-FRAMES-ONLY\n#EXT-X-MAP"))
+        );
+    }
+
+    #[test]
+    fn variable_substitution_requires_version_8() {
+        let playlist = "#EXTM3U
+#EXT-X-VERSION:7
+#EXT-X-TARGETDURATION:6
+#EXT-X-DEFINE:QUERYPARAM=\"token\"
+#EXTINF:6,
+a.ts?token={$token}
+";
+        assert_eq!(
+            vec![(Some(4), "version"), (Some(6), "version")],
+            findings(playlist)
+        );
+    }
+
+    #[test]
+    fn unnecessarily_high_version_is_reported() {
+        let playlist = "#EXTM3U
+#EXT-X-VERSION:7
+#EXT-X-TARGETDURATION:6
+#EXTINF:6.0,
+#EXT-X-BYTERANGE:1000@0
+a.ts
+";
+        assert_eq!(vec![(Some(2), "version-unnecessary")], findings(playlist));
+        assert_eq!(
+            Vec::<(Option<usize>, &str)>::new(),
+            findings(&playlist.replace("VERSION:7", "VERSION:4"))
+        );
+    }
+}
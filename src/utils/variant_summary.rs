@@ -0,0 +1,225 @@
+// The Variant Streams of a Multivariant Playlist side by side, as their EXT-X-STREAM-INF tags
+// describe them, so that the ladder can be read at a glance rather than tag by tag.
+
+use crate::utils::{
+    codecs::is_audio_codec,
+    playlist_lines::{AttributeValue, LineKind, parse},
+    resolution::Resolution,
+};
+use std::collections::HashMap;
+
+/// A Variant Stream of a Multivariant Playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantSummary {
+    /// The 1-based number of the line of the EXT-X-STREAM-INF tag.
+    pub line: usize,
+    pub uri: String,
+    pub bandwidth: Option<u64>,
+    pub average_bandwidth: Option<u64>,
+    pub resolution: Option<Resolution>,
+    pub frame_rate: Option<f64>,
+    pub codecs: Option<String>,
+    /// The GROUP-IDs of the renditions that the variant is played with.
+    pub audio: Option<String>,
+    pub subtitles: Option<String>,
+    pub closed_captions: Option<String>,
+    /// How the audio of the variant is delivered, when that can be told from the playlist.
+    pub audio_delivery: Option<AudioDelivery>,
+}
+
+/// Whether the audio of a Variant Stream is in its own segments or in Media Playlists of its own,
+/// which a player has to fetch alongside the video and keep in step with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioDelivery {
+    /// In the segments of the variant: the renditions of its AUDIO group have no URI, or it has no
+    /// AUDIO group and its CODECS include audio.
+    Muxed,
+    /// In a Media Playlist for each of the renditions of its AUDIO group.
+    Demuxed,
+    /// In the segments of the variant for the renditions of its AUDIO group without a URI, and in
+    /// Media Playlists for the others (the alternatives to the audio in the variant).
+    MuxedWithAlternates,
+}
+
+impl AudioDelivery {
+    /// A short label suitable for a badge.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Muxed => "Muxed audio",
+            Self::Demuxed => "Demuxed audio",
+            Self::MuxedWithAlternates => "Muxed + alternate audio",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Muxed => "The audio is in the segments of the variant, alongside the video.",
+            Self::Demuxed => {
+                "The audio is in Media Playlists of its own (the URIs of the AUDIO group), which \
+                 are fetched alongside the variant."
+            }
+            Self::MuxedWithAlternates => {
+                "The audio is in the segments of the variant for the renditions of the AUDIO \
+                 group without a URI, while choosing another rendition fetches its own Media \
+                 Playlist alongside the variant."
+            }
+        }
+    }
+}
+
+// How many renditions of an AUDIO group have a URI, and how many do not.
+#[derive(Default)]
+struct AudioGroup {
+    with_uri: usize,
+    without_uri: usize,
+}
+
+/// The Variant Streams in the order that they are listed in the playlist.
+pub fn variant_summaries(playlist: &str) -> Vec<VariantSummary> {
+    let lines = parse(playlist);
+    let mut audio_groups = HashMap::<&str, AudioGroup>::new();
+    for line in &lines {
+        if let LineKind::Tag(tag) = &line.kind
+            && tag.name == "EXT-X-MEDIA"
+            && tag.attribute_str("TYPE") == Some("AUDIO")
+            && let Some(group_id) = tag.attribute_str("GROUP-ID")
+        {
+            let group = audio_groups.entry(group_id).or_default();
+            if tag.attribute("URI").is_some() {
+                group.with_uri += 1;
+            } else {
+                group.without_uri += 1;
+            }
+        }
+    }
+    let mut variants = Vec::new();
+    let mut stream_inf = None;
+    for line in &lines {
+        match &line.kind {
+            LineKind::Tag(tag) if tag.name == "EXT-X-STREAM-INF" => {
+                stream_inf = Some((line.number, tag.clone()));
+            }
+            LineKind::Uri(uri) => {
+                let Some((line, tag)) = stream_inf.take() else {
+                    continue;
+                };
+                let string = |name| tag.attribute_str(name).map(String::from);
+                let number = |name| tag.attribute_str(name).and_then(|n| n.parse().ok());
+                variants.push(VariantSummary {
+                    line,
+                    uri: uri.to_string(),
+                    bandwidth: number("BANDWIDTH"),
+                    average_bandwidth: number("AVERAGE-BANDWIDTH"),
+                    resolution: tag.attribute_str("RESOLUTION").and_then(Resolution::parse),
+                    frame_rate: tag.attribute_str("FRAME-RATE").and_then(|f| f.parse().ok()),
+                    codecs: string("CODECS"),
+                    audio: string("AUDIO"),
+                    subtitles: string("SUBTITLES"),
+                    // CLOSED-CAPTIONS=NONE is an enumerated string rather than a GROUP-ID.
+                    closed_captions: match tag.attribute("CLOSED-CAPTIONS") {
+                        Some(AttributeValue::Quoted(group)) => Some(group.to_string()),
+                        _ => None,
+                    },
+                    audio_delivery: match tag.attribute_str("AUDIO") {
+                        Some(group_id) => audio_groups.get(group_id).map(group_delivery),
+                        None => tag
+                            .attribute_str("CODECS")
+                            .is_some_and(|codecs| {
+                                codecs.split(',').any(|c| is_audio_codec(c.trim()))
+                            })
+                            .then_some(AudioDelivery::Muxed),
+                    },
+                });
+            }
+            _ => (),
+        }
+    }
+    variants
+}
+
+fn group_delivery(group: &AudioGroup) -> AudioDelivery {
+    match (group.with_uri > 0, group.without_uri > 0) {
+        (false, _) => AudioDelivery::Muxed,
+        (true, false) => AudioDelivery::Demuxed,
+        (true, true) => AudioDelivery::MuxedWithAlternates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn variants_are_summarised_from_their_tags() {
+        let playlist = "#EXTM3U\n\
+             #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",URI=\"en.m3u8\"\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1280000,AVERAGE-BANDWIDTH=1000000,CODECS=\"avc1.4d401f,\
+             mp4a.40.2\",RESOLUTION=640x360,FRAME-RATE=29.970,AUDIO=\"aac\",CLOSED-CAPTIONS=NONE\n\
+             low.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=7680000,CLOSED-CAPTIONS=\"cc\"\n\
+             high.m3u8\n";
+        assert_eq!(
+            vec![
+                VariantSummary {
+                    line: 3,
+                    uri: String::from("low.m3u8"),
+                    bandwidth: Some(1280000),
+                    average_bandwidth: Some(1000000),
+                    resolution: Some(Resolution {
+                        width: 640,
+                        height: 360
+                    }),
+                    frame_rate: Some(29.97),
+                    codecs: Some(String::from("avc1.4d401f,mp4a.40.2")),
+                    audio: Some(String::from("aac")),
+                    subtitles: None,
+                    closed_captions: None,
+                    audio_delivery: Some(AudioDelivery::Demuxed),
+                },
+                VariantSummary {
+                    line: 5,
+                    uri: String::from("high.m3u8"),
+                    bandwidth: Some(7680000),
+                    average_bandwidth: None,
+                    resolution: None,
+                    frame_rate: None,
+                    codecs: None,
+                    audio: None,
+                    subtitles: None,
+                    closed_captions: Some(String::from("cc")),
+                    audio_delivery: None,
+                },
+            ],
+            variant_summaries(playlist)
+        );
+    }
+
+    #[test]
+    fn audio_is_muxed_where_renditions_have_no_uri() {
+        let playlist = "#EXTM3U\n\
+             #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"muxed\",NAME=\"Main\",DEFAULT=YES\n\
+             #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"mixed\",NAME=\"Main\",DEFAULT=YES\n\
+             #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"mixed\",NAME=\"Commentary\",URI=\"c.m3u8\"\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1000000,AUDIO=\"muxed\"\n\
+             a.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1000000,AUDIO=\"mixed\"\n\
+             b.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1000000,CODECS=\"avc1.4d401f, mp4a.40.2\"\n\
+             c.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1000000,AUDIO=\"missing\"\n\
+             d.m3u8\n";
+        assert_eq!(
+            vec![
+                Some(AudioDelivery::Muxed),
+                Some(AudioDelivery::MuxedWithAlternates),
+                Some(AudioDelivery::Muxed),
+                None,
+            ],
+            variant_summaries(playlist)
+                .into_iter()
+                .map(|variant| variant.audio_delivery)
+                .collect::<Vec<_>>()
+        );
+    }
+}
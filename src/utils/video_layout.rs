@@ -0,0 +1,174 @@
+use std::fmt::Display;
+
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-4.4.6.2
+//
+// REQ-VIDEO-LAYOUT
+//
+//    The value is an enumerated-string-list of Video Layout Specifiers.
+//    [...]
+//    Video Channel Specifier: CH-STEREO, CH-MONO
+//    Video Projection Specifier: PROJ-RECT, PROJ-EQUI, PROJ-HEQU, PROJ-PRIM, PROJ-AIV
+//
+// The draft has been a bit inconsistent in whether the specifiers are separated with commas or with
+// slashes (e.g. "CH-STEREO/PROJ-AIV"), so both are accepted here.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VideoLayout {
+    pub channels: Vec<VideoChannel>,
+    pub projections: Vec<VideoProjection>,
+    pub unrecognized: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VideoChannel {
+    Stereo,
+    Mono,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VideoProjection {
+    Rectilinear,
+    Equirectangular,
+    HalfEquirectangular,
+    ParametricImmersive,
+    AppleImmersiveVideo,
+}
+
+impl VideoLayout {
+    pub fn parse(value: &str) -> Self {
+        let mut layout = Self::default();
+        for specifier in value
+            .split(['/', ','])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            match specifier {
+                "CH-STEREO" => layout.channels.push(VideoChannel::Stereo),
+                "CH-MONO" => layout.channels.push(VideoChannel::Mono),
+                "PROJ-RECT" => layout.projections.push(VideoProjection::Rectilinear),
+                "PROJ-EQUI" => layout.projections.push(VideoProjection::Equirectangular),
+                "PROJ-HEQU" => layout
+                    .projections
+                    .push(VideoProjection::HalfEquirectangular),
+                "PROJ-PRIM" => layout
+                    .projections
+                    .push(VideoProjection::ParametricImmersive),
+                "PROJ-AIV" => layout
+                    .projections
+                    .push(VideoProjection::AppleImmersiveVideo),
+                unknown => layout.unrecognized.push(unknown.to_string()),
+            }
+        }
+        layout
+    }
+
+    pub fn is_stereoscopic(&self) -> bool {
+        self.channels.contains(&VideoChannel::Stereo)
+    }
+
+    pub fn is_immersive(&self) -> bool {
+        self.projections
+            .iter()
+            .any(|p| *p != VideoProjection::Rectilinear)
+    }
+
+    /// A short label suitable for a badge, or `None` when the layout is plain 2D video.
+    pub fn badge_label(&self) -> Option<&'static str> {
+        match (self.is_stereoscopic(), self.is_immersive()) {
+            (true, true) => Some("3D Immersive"),
+            (true, false) => Some("3D"),
+            (false, true) => Some("Immersive"),
+            (false, false) => None,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        let mut parts = self
+            .channels
+            .iter()
+            .map(|c| c.to_string())
+            .chain(self.projections.iter().map(|p| p.to_string()))
+            .collect::<Vec<String>>();
+        for unknown in &self.unrecognized {
+            parts.push(format!("Unrecognized specifier ({unknown})"));
+        }
+        parts.join("; ")
+    }
+}
+
+impl Display for VideoChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stereo => write!(f, "Stereoscopic video (CH-STEREO)"),
+            Self::Mono => write!(f, "Monoscopic video (CH-MONO)"),
+        }
+    }
+}
+
+impl Display for VideoProjection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rectilinear => write!(f, "Rectilinear (flat) projection (PROJ-RECT)"),
+            Self::Equirectangular => write!(f, "360° equirectangular projection (PROJ-EQUI)"),
+            Self::HalfEquirectangular => {
+                write!(f, "180° half-equirectangular projection (PROJ-HEQU)")
+            }
+            Self::ParametricImmersive => write!(f, "Parametric immersive projection (PROJ-PRIM)"),
+            Self::AppleImmersiveVideo => write!(f, "Apple Immersive Video (PROJ-AIV)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_single_channel_specifier() {
+        let layout = VideoLayout::parse("CH-STEREO");
+        assert_eq!(vec![VideoChannel::Stereo], layout.channels);
+        assert!(layout.projections.is_empty());
+        assert_eq!(Some("3D"), layout.badge_label());
+    }
+
+    #[test]
+    fn parse_slash_separated_specifiers() {
+        let layout = VideoLayout::parse("CH-STEREO/PROJ-AIV");
+        assert_eq!(vec![VideoChannel::Stereo], layout.channels);
+        assert_eq!(
+            vec![VideoProjection::AppleImmersiveVideo],
+            layout.projections
+        );
+        assert_eq!(Some("3D Immersive"), layout.badge_label());
+    }
+
+    #[test]
+    fn parse_comma_separated_specifiers() {
+        let layout = VideoLayout::parse("CH-STEREO,CH-MONO");
+        assert_eq!(
+            vec![VideoChannel::Stereo, VideoChannel::Mono],
+            layout.channels
+        );
+    }
+
+    #[test]
+    fn plain_video_has_no_badge() {
+        assert_eq!(None, VideoLayout::parse("CH-MONO/PROJ-RECT").badge_label());
+    }
+
+    #[test]
+    fn immersive_mono_has_immersive_badge() {
+        assert_eq!(
+            Some("Immersive"),
+            VideoLayout::parse("PROJ-EQUI").badge_label()
+        );
+    }
+
+    #[test]
+    fn description_includes_unrecognized_specifiers() {
+        assert_eq!(
+            "Monoscopic video (CH-MONO); Unrecognized specifier (PROJ-NEW)",
+            VideoLayout::parse("CH-MONO/PROJ-NEW").description()
+        );
+    }
+}
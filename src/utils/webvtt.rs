@@ -0,0 +1,515 @@
+use crate::utils::{
+    playlist_lines::{self, LineKind},
+    validation::Severity,
+};
+
+// Validation of WebVTT subtitle segments.
+//
+// https://www.w3.org/TR/webvtt1/#file-structure
+// https://datatracker.ietf.org/doc/html/draft-pantos-hls-rfc8216bis-17#section-3.1.4
+//
+//    Each WebVTT Segment MUST contain all subtitle cues that are intended to be displayed during
+//    the period indicated by the segment EXTINF duration.  The start time offset and end time
+//    offset of each cue MUST indicate the total display time for that cue, even if part of the cue
+//    time range falls outside the Segment period.
+//
+//    [...] the X-TIMESTAMP-MAP metadata header SHOULD be used to map WebVTT cue timestamps to MPEG-2
+//    (program) presentation times.
+
+/// The period of the playlist timeline that a segment covers, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentWindow {
+    pub start: f64,
+    pub end: f64,
+}
+
+/// The segment of a subtitle playlist that a WebVTT file was fetched for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubtitleSegment {
+    pub media_sequence: u64,
+    pub window: Option<SegmentWindow>,
+}
+
+/// A cue with its timings mapped to MPEG-2 presentation time (in seconds), so that cues can be
+/// compared across segments whose X-TIMESTAMP-MAP differs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub line: usize,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebVttFinding {
+    pub severity: Severity,
+    /// The 1-based line number of the WebVTT file, if the finding relates to a single line.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WebVttReport {
+    pub cues: Vec<Cue>,
+    pub findings: Vec<WebVttFinding>,
+}
+
+/// The window of the playlist timeline that the segment with the given Media Sequence Number
+/// covers. The playlist timeline only starts at the beginning of the presentation when the playlist
+/// still begins with its first segment, so there is no window otherwise.
+pub fn segment_window(playlist: &str, media_sequence: u64) -> Option<SegmentWindow> {
+    let mut current_sequence = 0;
+    let mut start = 0.0;
+    let mut duration = None;
+    for line in playlist_lines::parse(playlist) {
+        match line.kind {
+            LineKind::Tag(tag) if tag.name == "EXT-X-MEDIA-SEQUENCE" => {
+                let first_sequence = tag.value.and_then(|v| v.trim().parse::<u64>().ok());
+                if first_sequence != Some(0) {
+                    return None;
+                }
+            }
+            LineKind::Tag(tag) if tag.name == "EXT-X-SKIP" => return None,
+            LineKind::Tag(tag) if tag.name == "EXTINF" => {
+                duration = tag
+                    .value
+                    .and_then(|v| v.split(',').next())
+                    .and_then(|d| d.trim().parse::<f64>().ok());
+            }
+            LineKind::Uri(_) => {
+                let duration = duration.take()?;
+                if current_sequence == media_sequence {
+                    return Some(SegmentWindow {
+                        start,
+                        end: start + duration,
+                    });
+                }
+                start += duration;
+                current_sequence += 1;
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Validates a WebVTT file. When known, the cues are checked against the `window` of the segment
+/// and against the cues of the segment before it.
+pub fn validate_webvtt(
+    text: &str,
+    window: Option<SegmentWindow>,
+    previous_segment: &[Cue],
+) -> WebVttReport {
+    let mut report = WebVttReport::default();
+    let lines = text
+        .strip_prefix('\u{feff}')
+        .unwrap_or(text)
+        .lines()
+        .collect::<Vec<_>>();
+    if !lines.first().is_some_and(|line| is_header(line)) {
+        report.error(
+            Some(1),
+            "The file must start with a WEBVTT line (optionally preceded by a byte order mark).",
+        );
+    }
+    let blocks = blocks(&lines);
+    let mut timestamp_map = None;
+    for (number, line) in blocks.first().into_iter().flatten() {
+        if let Some(value) = line.strip_prefix("X-TIMESTAMP-MAP=") {
+            timestamp_map = parse_timestamp_map(value);
+            if timestamp_map.is_none() {
+                report.error(
+                    Some(*number),
+                    format!(
+                        "X-TIMESTAMP-MAP must have the form MPEGTS:<ticks>,LOCAL:<timestamp> but \
+                         was {value}."
+                    ),
+                );
+            }
+        }
+    }
+    // Cue times relative to the LOCAL of the timestamp map are taken to be on the playlist
+    // timeline, whereas the MPEGTS maps them to presentation time.
+    let (local, mpegts) = timestamp_map.unwrap_or_default();
+
+    let mut previous_start = None::<(usize, f64)>;
+    for block in blocks.iter().skip(1) {
+        let Some(index) = block.iter().take(2).position(|(_, l)| l.contains("-->")) else {
+            let first_line = block.first().map(|(_, l)| *l).unwrap_or_default();
+            if !["NOTE", "STYLE", "REGION"]
+                .iter()
+                .any(|keyword| first_line.starts_with(keyword))
+            {
+                report.warning(
+                    block.first().map(|(n, _)| *n),
+                    "The block has no cue timings and will be ignored.",
+                );
+            }
+            continue;
+        };
+        let (line, timings) = block[index];
+        let Some((start, end, settings)) = parse_timings(timings) else {
+            report.error(
+                Some(line),
+                format!(
+                    "Malformed cue timings \"{timings}\"; expected \
+                     [hh:]mm:ss.ttt --> [hh:]mm:ss.ttt [settings]."
+                ),
+            );
+            continue;
+        };
+        if end <= start {
+            report.error(
+                Some(line),
+                "The cue end time must be greater than its start time.",
+            );
+        }
+        if let Some((previous_line, previous)) = previous_start
+            && start < previous
+        {
+            report.error(
+                Some(line),
+                format!(
+                    "The cue starts before the cue on line {previous_line}, but cues must be in \
+                     order of their start time."
+                ),
+            );
+        }
+        previous_start = Some((line, start));
+        for problem in settings.split_whitespace().filter_map(setting_problem) {
+            report.warning(Some(line), problem);
+        }
+        if let Some(window) = window
+            && (end - local <= window.start || start - local >= window.end)
+        {
+            report.warning(
+                Some(line),
+                format!(
+                    "The cue ({:.3}s to {:.3}s on the playlist timeline) falls outside of the \
+                     segment ({:.3}s to {:.3}s).",
+                    start - local,
+                    end - local,
+                    window.start,
+                    window.end
+                ),
+            );
+        }
+        let cue = Cue {
+            line,
+            start: start - local + mpegts,
+            end: end - local + mpegts,
+            text: block[index + 1..]
+                .iter()
+                .map(|(_, l)| *l)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        if let Some(repeated) = previous_segment.iter().find(|p| overlapping_copy(p, &cue)) {
+            report.warning(
+                Some(line),
+                format!(
+                    "The cue repeats the cue on line {} of the previous segment, with the same \
+                     text and overlapping timings, so it relies on the player removing \
+                     duplicates.",
+                    repeated.line
+                ),
+            );
+        }
+        report.cues.push(cue);
+    }
+    report
+        .findings
+        .sort_by_key(|finding| (finding.line, finding.severity));
+    report
+}
+
+impl WebVttReport {
+    fn error(&mut self, line: Option<usize>, message: impl Into<String>) {
+        self.findings.push(WebVttFinding {
+            severity: Severity::Error,
+            line,
+            message: message.into(),
+        });
+    }
+
+    fn warning(&mut self, line: Option<usize>, message: impl Into<String>) {
+        self.findings.push(WebVttFinding {
+            severity: Severity::Warning,
+            line,
+            message: message.into(),
+        });
+    }
+}
+
+fn is_header(line: &str) -> bool {
+    line.strip_prefix("WEBVTT")
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+// Splits the lines into blocks separated by blank lines, keeping the 1-based line numbers.
+fn blocks<'a>(lines: &[&'a str]) -> Vec<Vec<(usize, &'a str)>> {
+    let mut blocks = vec![Vec::new()];
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            if blocks.last().is_some_and(|block| !block.is_empty()) {
+                blocks.push(Vec::new());
+            }
+        } else if let Some(block) = blocks.last_mut() {
+            block.push((index + 1, *line));
+        }
+    }
+    blocks
+}
+
+// Returns the LOCAL timestamp and the MPEGTS time, both in seconds.
+fn parse_timestamp_map(value: &str) -> Option<(f64, f64)> {
+    let mut local = None;
+    let mut mpegts = None;
+    for part in value.split(',') {
+        match part.trim().split_once(':')? {
+            ("LOCAL", timestamp) => local = Some(parse_timestamp(timestamp)?),
+            ("MPEGTS", ticks) => mpegts = Some(ticks.parse::<u64>().ok()? as f64 / 90_000.0),
+            _ => return None,
+        }
+    }
+    Some((local?, mpegts?))
+}
+
+fn parse_timings(line: &str) -> Option<(f64, f64, &str)> {
+    let (start, rest) = line.split_once("-->")?;
+    let rest = rest.trim_start();
+    let (end, settings) = rest.split_once([' ', '\t']).unwrap_or((rest, ""));
+    Some((
+        parse_timestamp(start.trim())?,
+        parse_timestamp(end)?,
+        settings,
+    ))
+}
+
+// https://www.w3.org/TR/webvtt1/#webvtt-timestamp
+//
+//    1. Optionally (required if hours is non-zero): two or more ASCII digits, representing the
+//       hours as a base ten integer, followed by a U+003A COLON character (:)
+//    2. Two ASCII digits, representing the minutes as a base ten integer in the range 0 ≤ minutes
+//       ≤ 59.
+//    3. A U+003A COLON character (:)
+//    4. Two ASCII digits, representing the seconds as a base ten integer in the range 0 ≤ seconds
+//       ≤ 59.
+//    5. A U+002E FULL STOP character (.).
+//    6. Three ASCII digits, representing the thousandths of a second seconds-frac as a base ten
+//       integer.
+fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    let parts = timestamp.split(':').collect::<Vec<_>>();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [minutes, seconds] => ("0", *minutes, *seconds),
+        [hours, minutes, seconds] if hours.len() >= 2 => (*hours, *minutes, *seconds),
+        _ => return None,
+    };
+    let (seconds, fraction) = seconds.split_once('.')?;
+    if minutes.len() != 2 || seconds.len() != 2 || fraction.len() != 3 {
+        return None;
+    }
+    let digits = |s: &str| {
+        s.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| s.parse::<u64>().ok())
+            .flatten()
+    };
+    let hours = digits(hours)?;
+    let minutes = digits(minutes).filter(|m| *m < 60)?;
+    let seconds = digits(seconds).filter(|s| *s < 60)?;
+    let fraction = digits(fraction)?;
+    Some((hours * 3600 + minutes * 60 + seconds) as f64 + fraction as f64 / 1000.0)
+}
+
+// https://www.w3.org/TR/webvtt1/#webvtt-cue-settings
+fn setting_problem(setting: &str) -> Option<String> {
+    let Some((name, value)) = setting.split_once(':') else {
+        return Some(format!(
+            "The cue setting \"{setting}\" must have the form name:value."
+        ));
+    };
+    let valid = match name {
+        "vertical" => matches!(value, "rl" | "lr"),
+        "line" => {
+            let (line, alignment) = value.split_once(',').unwrap_or((value, "start"));
+            (is_percentage(line) || line.parse::<i64>().is_ok())
+                && matches!(alignment, "start" | "center" | "end")
+        }
+        "position" => {
+            let (position, alignment) = value.split_once(',').unwrap_or((value, "center"));
+            is_percentage(position) && matches!(alignment, "line-left" | "center" | "line-right")
+        }
+        "size" => is_percentage(value),
+        "align" => matches!(value, "start" | "center" | "end" | "left" | "right"),
+        "region" => !value.is_empty(),
+        _ => {
+            return Some(format!("\"{name}\" is not a WebVTT cue setting."));
+        }
+    };
+    (!valid).then(|| format!("The cue setting {name} has an invalid value \"{value}\"."))
+}
+
+fn is_percentage(value: &str) -> bool {
+    value
+        .strip_suffix('%')
+        .and_then(|v| v.parse::<f64>().ok())
+        .is_some_and(|v| (0.0..=100.0).contains(&v))
+}
+
+// Timings are compared to the millisecond, the precision of a WebVTT timestamp.
+fn overlapping_copy(a: &Cue, b: &Cue) -> bool {
+    let millis = |seconds: f64| (seconds * 1000.0).round();
+    a.text == b.text && millis(a.start) < millis(b.end) && millis(b.start) < millis(a.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn messages(report: &WebVttReport) -> Vec<(Option<usize>, &str)> {
+        report
+            .findings
+            .iter()
+            .map(|f| (f.line, f.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn valid_file_has_no_findings() {
+        let text = "\u{feff}WEBVTT
+X-TIMESTAMP-MAP=MPEGTS:900000,LOCAL:00:00:00.000
+
+NOTE a comment
+
+1
+00:00:06.000 --> 00:00:08.500 line:90% align:center
+Hello
+
+00:09.000 --> 00:13.000 position:10%,line-left size:50%
+World
+";
+        let window = SegmentWindow {
+            start: 6.0,
+            end: 12.0,
+        };
+        let report = validate_webvtt(text, Some(window), &[]);
+        assert_eq!(Vec::<(Option<usize>, &str)>::new(), messages(&report));
+        assert_eq!(
+            vec![(16.0, 18.5), (19.0, 23.0)],
+            report
+                .cues
+                .iter()
+                .map(|c| (c.start, c.end))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn malformed_cues_are_reported() {
+        let text = "WEBVTT
+
+00:00:05.000 --> 00:00:04.000
+Backwards
+
+00:00:03.000 --> 00:00:04.000 align:middle
+Out of order
+
+00:00:5.000 --> 00:00:06.000
+Bad timestamp
+";
+        let report = validate_webvtt(text, None, &[]);
+        assert_eq!(
+            vec![
+                (
+                    Some(3),
+                    "The cue end time must be greater than its start time."
+                ),
+                (
+                    Some(6),
+                    "The cue starts before the cue on line 3, but cues must be in order of their \
+                     start time."
+                ),
+                (
+                    Some(6),
+                    "The cue setting align has an invalid value \"middle\"."
+                ),
+                (
+                    Some(9),
+                    "Malformed cue timings \"00:00:5.000 --> 00:00:06.000\"; expected \
+                     [hh:]mm:ss.ttt --> [hh:]mm:ss.ttt [settings]."
+                ),
+            ],
+            messages(&report)
+        );
+        assert_eq!(
+            vec![(
+                Some(1),
+                "The file must start with a WEBVTT line (optionally preceded by a byte order mark)."
+            )],
+            messages(&validate_webvtt("00:00.000 --> 00:01.000\nHi\n", None, &[]))
+        );
+    }
+
+    #[test]
+    fn cues_are_checked_against_the_segment() {
+        let previous = validate_webvtt(
+            "WEBVTT\n\n00:00:05.000 --> 00:00:07.000\nAcross the boundary\n",
+            None,
+            &[],
+        );
+        let text = "WEBVTT
+
+00:00:01.000 --> 00:00:02.000
+Too early
+
+00:00:05.000 --> 00:00:07.000
+Across the boundary
+";
+        let window = SegmentWindow {
+            start: 6.0,
+            end: 12.0,
+        };
+        assert_eq!(
+            vec![
+                (
+                    Some(3),
+                    "The cue (1.000s to 2.000s on the playlist timeline) falls outside of the \
+                     segment (6.000s to 12.000s)."
+                ),
+                (
+                    Some(6),
+                    "The cue repeats the cue on line 3 of the previous segment, with the same text \
+                     and overlapping timings, so it relies on the player removing duplicates."
+                ),
+            ],
+            messages(&validate_webvtt(text, Some(window), &previous.cues))
+        );
+    }
+
+    #[test]
+    fn segment_window_sums_durations() {
+        let playlist = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXTINF:6.000,
+0.vtt
+#EXTINF:5.500,
+1.vtt
+";
+        assert_eq!(
+            Some(SegmentWindow {
+                start: 6.0,
+                end: 11.5
+            }),
+            segment_window(playlist, 1)
+        );
+        assert_eq!(
+            None,
+            segment_window(
+                &playlist.replace("#EXTINF:6", "#EXT-X-MEDIA-SEQUENCE:4\n#EXTINF:6"),
+                5
+            )
+        );
+    }
+}